@@ -0,0 +1,446 @@
+//! Flippio headless CLI
+//!
+//! Runs the same pull, query and export code as the desktop app without a window, so
+//! CI jobs and scripts can automate them:
+//!
+//! ```text
+//! flippio-cli pull --device emulator-5554 --package com.example.app --output ./dbs
+//! flippio-cli query --db app.db "SELECT * FROM users"
+//! flippio-cli export --db app.db --table users --format csv --output users.csv
+//! flippio-cli serve                # MCP / JSON-RPC on stdio
+//! flippio-cli serve --port 7453    # JSON-RPC on 127.0.0.1:7453
+//! ```
+
+use flippio::commands::database::{
+    export_rows_to_file, fetch_export_source, is_read_only_query, render_csv, ExportFormat,
+    ExportSource,
+};
+use flippio::commands::database::helpers::copy_database_with_companions;
+use flippio::commands::device::pull_android_database_files;
+use flippio::commands::guardrails::{check_headless_sql, DangerousOperation};
+use flippio::commands::rpc;
+use sqlx::SqlitePool;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage:
+  flippio-cli pull --device <id> --package <name> [--output <dir>]
+  flippio-cli query --db <file> [--format json|csv] [--confirm <operation>,...] <sql>
+  flippio-cli export --db <file> (--table <name> | --query <sql>) [--format <format>] [--output <file>]
+  flippio-cli serve [--port <port>]
+
+Options:
+  --confirm    Allow drop and/or deleteAll statements in this query
+  --verbose    Log progress to stderr
+  --help       Show this message
+
+Only Android devices can be pulled headless; iOS pulls need the desktop app.";
+
+#[derive(Debug, Clone, PartialEq)]
+enum CliCommand {
+    Pull {
+        device: String,
+        package: String,
+        output: Option<PathBuf>,
+    },
+    Query {
+        db: PathBuf,
+        sql: String,
+        format: String,
+        confirmed: Vec<DangerousOperation>,
+    },
+    Export {
+        db: PathBuf,
+        source: CliExportSource,
+        format: String,
+        output: Option<PathBuf>,
+    },
+    // JSON-RPC on stdio, or on localhost when a port is given
+    Serve {
+        port: Option<u16>,
+    },
+    Help,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum CliExportSource {
+    Table(String),
+    Query(String),
+}
+
+#[derive(Debug, Default)]
+struct ParsedArgs {
+    options: Vec<(String, String)>,
+    positional: Vec<String>,
+    verbose: bool,
+    help: bool,
+}
+
+impl ParsedArgs {
+    fn take(&mut self, name: &str) -> Option<String> {
+        let index = self.options.iter().position(|(key, _)| key == name)?;
+        Some(self.options.remove(index).1)
+    }
+
+    fn require(&mut self, name: &str) -> Result<String, String> {
+        self.take(name)
+            .ok_or_else(|| format!("Missing required option --{}", name))
+    }
+
+    fn finish(self) -> Result<(), String> {
+        match self.options.first() {
+            Some((key, _)) => Err(format!("Unknown option --{}", key)),
+            None => Ok(()),
+        }
+    }
+}
+
+fn split_args(args: &[String]) -> Result<ParsedArgs, String> {
+    let mut parsed = ParsedArgs::default();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--verbose" | "-v" => parsed.verbose = true,
+            "--help" | "-h" => parsed.help = true,
+            "--" => parsed.positional.extend(iter.by_ref().cloned()),
+            option if option.starts_with("--") => {
+                let (key, value) = match option[2..].split_once('=') {
+                    Some((key, value)) => (key.to_string(), value.to_string()),
+                    None => {
+                        let value = iter
+                            .next()
+                            .ok_or_else(|| format!("Option {} needs a value", option))?;
+                        (option[2..].to_string(), value.clone())
+                    }
+                };
+                parsed.options.push((key, value));
+            }
+            _ => parsed.positional.push(arg.clone()),
+        }
+    }
+
+    Ok(parsed)
+}
+
+/// Parse the arguments after the program name. Returns the command and the verbose flag.
+fn parse_command(args: &[String]) -> Result<(CliCommand, bool), String> {
+    let Some((subcommand, rest)) = args.split_first() else {
+        return Ok((CliCommand::Help, false));
+    };
+    let mut parsed = split_args(rest)?;
+    let verbose = parsed.verbose;
+    if parsed.help || matches!(subcommand.as_str(), "help" | "--help" | "-h") {
+        return Ok((CliCommand::Help, verbose));
+    }
+
+    let command = match subcommand.as_str() {
+        "pull" => CliCommand::Pull {
+            device: parsed.require("device")?,
+            package: parsed.require("package")?,
+            output: parsed.take("output").map(PathBuf::from),
+        },
+        "query" => {
+            let db = PathBuf::from(parsed.require("db")?);
+            let format = parsed.take("format").unwrap_or_else(|| "json".to_string());
+            let confirmed = match parsed.take("confirm") {
+                Some(list) => list
+                    .split(',')
+                    .map(|operation| DangerousOperation::parse(operation.trim()))
+                    .collect::<Result<Vec<_>, _>>()?,
+                None => Vec::new(),
+            };
+            let sql = match parsed.take("sql") {
+                Some(sql) => sql,
+                None => std::mem::take(&mut parsed.positional).join(" "),
+            };
+            if sql.trim().is_empty() {
+                return Err("Missing SQL to run".to_string());
+            }
+            CliCommand::Query {
+                db,
+                sql,
+                format,
+                confirmed,
+            }
+        }
+        "export" => {
+            let db = PathBuf::from(parsed.require("db")?);
+            let source = match (parsed.take("table"), parsed.take("query")) {
+                (Some(table), None) => CliExportSource::Table(table),
+                (None, Some(query)) => CliExportSource::Query(query),
+                _ => return Err("Pass exactly one of --table or --query".to_string()),
+            };
+            CliCommand::Export {
+                db,
+                source,
+                format: parsed.take("format").unwrap_or_else(|| "csv".to_string()),
+                output: parsed.take("output").map(PathBuf::from),
+            }
+        }
+        "serve" => CliCommand::Serve {
+            port: parsed
+                .take("port")
+                .map(|port| port.parse().map_err(|_| format!("Invalid port '{}'", port)))
+                .transpose()?,
+        },
+        other => return Err(format!("Unknown command '{}'", other)),
+    };
+
+    if !parsed.positional.is_empty() {
+        return Err(format!("Unexpected argument '{}'", parsed.positional[0]));
+    }
+    parsed.finish()?;
+    Ok((command, verbose))
+}
+
+async fn open_pool(db: &Path) -> Result<SqlitePool, String> {
+    if !db.is_file() {
+        return Err(format!("Database file not found: {}", db.display()));
+    }
+    SqlitePool::connect(&format!("sqlite:{}", db.display()))
+        .await
+        .map_err(|e| format!("Failed to open {}: {}", db.display(), e))
+}
+
+async fn run_pull(device: &str, package: &str, output: Option<&Path>) -> Result<(), String> {
+    let files = pull_android_database_files(device, package, None, false).await;
+    if files.is_empty() {
+        return Err(format!("No databases found for {} on {}", package, device));
+    }
+
+    let mut failed = 0;
+    let mut results = Vec::new();
+    for file in files {
+        // A failed pull is listed with its remote path in place of the local one
+        if file.remote_path.as_deref() == Some(file.path.as_str()) {
+            eprintln!("Failed to pull {}", file.path);
+            failed += 1;
+            continue;
+        }
+
+        let local_path = match output {
+            Some(dir) => {
+                std::fs::create_dir_all(dir)
+                    .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+                let target = dir.join(&file.filename);
+                copy_database_with_companions(Path::new(&file.path), &target)?;
+                target.to_string_lossy().to_string()
+            }
+            None => file.path.clone(),
+        };
+        results.push(serde_json::json!({
+            "path": local_path,
+            "remotePath": file.remote_path,
+            "location": file.location,
+        }));
+    }
+
+    println!("{}", serde_json::to_string_pretty(&results).map_err(|e| e.to_string())?);
+    if failed > 0 {
+        return Err(format!("{} database(s) could not be pulled", failed));
+    }
+    Ok(())
+}
+
+async fn run_query(
+    db: &Path,
+    sql: &str,
+    format: &str,
+    confirmed: &[DangerousOperation],
+) -> Result<(), String> {
+    let read_only = is_read_only_query(sql);
+    if !read_only {
+        check_headless_sql(sql, confirmed)?;
+    }
+    let pool = open_pool(db).await?;
+
+    if !read_only {
+        let result = sqlx::query(sql)
+            .execute(&pool)
+            .await
+            .map_err(|e| format!("Error executing query: {}", e))?;
+        println!("{}", serde_json::json!({ "rowsAffected": result.rows_affected() }));
+        return Ok(());
+    }
+
+    let (columns, rows) = fetch_export_source(&pool, &ExportSource::Query(sql.to_string())).await?;
+    match format {
+        "csv" => print!("{}", render_csv(&columns, &rows)),
+        "json" => {
+            let ordered: Vec<serde_json::Value> = rows
+                .iter()
+                .map(|row| {
+                    columns
+                        .iter()
+                        .map(|column| {
+                            (column.clone(), row.get(column).cloned().unwrap_or(serde_json::Value::Null))
+                        })
+                        .collect::<serde_json::Map<_, _>>()
+                        .into()
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&ordered).map_err(|e| e.to_string())?);
+        }
+        other => return Err(format!("Unsupported query output format '{}'", other)),
+    }
+    Ok(())
+}
+
+async fn run_export(
+    db: &Path,
+    source: &CliExportSource,
+    format: &str,
+    output: Option<&Path>,
+) -> Result<(), String> {
+    let format = ExportFormat::parse(format)?;
+    let (source, stem) = match source {
+        CliExportSource::Table(table) => (ExportSource::Table(table.clone()), table.as_str()),
+        CliExportSource::Query(query) => (ExportSource::Query(query.clone()), "query"),
+    };
+    let output = output
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from(format!("{}.{}", stem, format.extension())));
+
+    let pool = open_pool(db).await?;
+    let result = export_rows_to_file(&pool, &source, format, &output.to_string_lossy()).await?;
+    eprintln!("Exported {} rows to {}", result.row_count, result.path);
+    Ok(())
+}
+
+async fn run(command: CliCommand) -> Result<(), String> {
+    match command {
+        CliCommand::Pull {
+            device,
+            package,
+            output,
+        } => run_pull(&device, &package, output.as_deref()).await,
+        CliCommand::Query {
+            db,
+            sql,
+            format,
+            confirmed,
+        } => run_query(&db, &sql, &format, &confirmed).await,
+        CliCommand::Export {
+            db,
+            source,
+            format,
+            output,
+        } => run_export(&db, &source, &format, output.as_deref()).await,
+        CliCommand::Serve { port } => {
+            let result = match port {
+                Some(port) => rpc::serve_tcp(port).await,
+                None => {
+                    let stdin = tokio::io::BufReader::new(tokio::io::stdin());
+                    rpc::serve_lines(stdin, tokio::io::stdout(), &rpc::RpcAccess::from_settings(None)).await
+                }
+            };
+            result.map_err(|e| format!("RPC server failed: {}", e))
+        }
+        CliCommand::Help => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (command, verbose) = match parse_command(&args) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    // Logs go to stderr so stdout stays parseable
+    let level = if verbose { log::LevelFilter::Info } else { log::LevelFilter::Warn };
+    let _ = simplelog::WriteLogger::init(level, simplelog::Config::default(), std::io::stderr());
+
+    match run(command).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(
+            parse_command(&args(&["pull", "--device", "emulator-5554", "--package=com.example"])).unwrap(),
+            (
+                CliCommand::Pull {
+                    device: "emulator-5554".to_string(),
+                    package: "com.example".to_string(),
+                    output: None,
+                },
+                false
+            )
+        );
+
+        assert_eq!(
+            parse_command(&args(&["query", "--db", "app.db", "-v", "SELECT", "*", "FROM users"])).unwrap(),
+            (
+                CliCommand::Query {
+                    db: PathBuf::from("app.db"),
+                    sql: "SELECT * FROM users".to_string(),
+                    format: "json".to_string(),
+                    confirmed: Vec::new(),
+                },
+                true
+            )
+        );
+
+        assert_eq!(
+            parse_command(&args(&["query", "--db", "app.db", "--confirm", "drop,deleteAll", "DROP TABLE t"]))
+                .unwrap()
+                .0,
+            CliCommand::Query {
+                db: PathBuf::from("app.db"),
+                sql: "DROP TABLE t".to_string(),
+                format: "json".to_string(),
+                confirmed: vec![DangerousOperation::Drop, DangerousOperation::DeleteAll],
+            }
+        );
+
+        assert_eq!(
+            parse_command(&args(&["export", "--db", "app.db", "--table", "users", "--format", "json"]))
+                .unwrap()
+                .0,
+            CliCommand::Export {
+                db: PathBuf::from("app.db"),
+                source: CliExportSource::Table("users".to_string()),
+                format: "json".to_string(),
+                output: None,
+            }
+        );
+        assert_eq!(
+            parse_command(&args(&["serve", "--port", "7453"])).unwrap().0,
+            CliCommand::Serve { port: Some(7453) }
+        );
+        assert_eq!(parse_command(&[]).unwrap().0, CliCommand::Help);
+    }
+
+    #[test]
+    fn test_parse_command_errors() {
+        assert!(parse_command(&args(&["pull", "--device", "x"])).is_err());
+        assert!(parse_command(&args(&["query", "--db", "app.db"])).is_err());
+        assert!(parse_command(&args(&["export", "--db", "app.db"])).is_err());
+        assert!(parse_command(&args(&["export", "--db", "app.db", "--table", "a", "--colour", "red"])).is_err());
+        assert!(parse_command(&args(&["serve", "--port", "http"])).is_err());
+        assert!(parse_command(&args(&["query", "--db", "app.db", "--confirm", "truncate", "SELECT 1"])).is_err());
+        assert!(parse_command(&args(&["sync"])).is_err());
+    }
+}
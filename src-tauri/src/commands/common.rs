@@ -1,9 +1,18 @@
 // Common commands module
 // Implements file dialog and other common IPC commands
 
+use crate::commands::device::leveldb::is_leveldb_directory;
+use crate::commands::database::DbResponse;
+use crate::commands::device::types::{DatabaseFile, DatabaseFileType, DeviceResponse};
+use crate::commands::device::errors::DeviceError;
 use serde::{Deserialize, Serialize};
+use std::future::Future;
 use std::path::{Path, PathBuf};
-use tauri::Manager;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+use tauri::{Emitter, Manager};
+use tokio::sync::{Notify, Semaphore};
 use tauri_plugin_dialog::{DialogExt};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -42,6 +51,224 @@ fn is_exportable_log_file(path: &Path) -> bool {
         .unwrap_or(false)
 }
 
+// Deep enough for exported app containers (Library/Application Support/<bundle>/...)
+const DROPPED_DIRECTORY_MAX_DEPTH: usize = 12;
+
+fn is_dropped_database_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| {
+            matches!(
+                ext.to_ascii_lowercase().as_str(),
+                "db" | "sqlite" | "sqlite3" | "db3" | "realm"
+            )
+        })
+        .unwrap_or(false)
+}
+
+fn dropped_database_entry(root: &Path, path: &Path) -> DatabaseFile {
+    let filename = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default()
+        .to_string();
+    let location = path
+        .parent()
+        .and_then(|parent| parent.strip_prefix(root).ok())
+        .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+        .filter(|relative| !relative.is_empty())
+        .unwrap_or_else(|| ".".to_string());
+    let package_name = root
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default()
+        .to_string();
+    let file_type = if path.is_dir() {
+        DatabaseFileType::LevelDb
+    } else {
+        DatabaseFileType::from_path(&filename)
+    };
+
+    DatabaseFile {
+        path: path.to_string_lossy().to_string(),
+        package_name,
+        file_type,
+        filename,
+        location,
+        remote_path: None,
+        device_type: "local".to_string(),
+        integrity: None,
+        pull_strategy: None,
+    }
+}
+
+/// Recursively collect database files (and LevelDB stores) below a dropped directory.
+fn scan_directory_for_databases(root: &Path) -> Result<Vec<DatabaseFile>, String> {
+    if !root.is_dir() {
+        return Err(format!("{} is not a directory", root.display()));
+    }
+
+    let mut found = Vec::new();
+    let mut pending = vec![(root.to_path_buf(), 0usize)];
+
+    while let Some((dir, depth)) = pending.pop() {
+        let read_dir = match std::fs::read_dir(&dir) {
+            Ok(read_dir) => read_dir,
+            Err(e) => {
+                log::warn!("⚠️ Skipping unreadable directory {}: {}", dir.display(), e);
+                continue;
+            }
+        };
+
+        for entry in read_dir.filter_map(Result::ok) {
+            let path = entry.path();
+            // Don't follow symlinks, they can point back up the tree
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+
+            if file_type.is_dir() {
+                if is_leveldb_directory(&path) {
+                    found.push(dropped_database_entry(root, &path));
+                } else if depth < DROPPED_DIRECTORY_MAX_DEPTH {
+                    pending.push((path, depth + 1));
+                }
+            } else if file_type.is_file() && is_dropped_database_file(&path) {
+                found.push(dropped_database_entry(root, &path));
+            }
+        }
+    }
+
+    found.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(found)
+}
+
+// --- Log redaction ---
+// Queries and values can contain user data, so literals are hashed or dropped before they
+// are logged unless the developer "full logging" switch is on.
+
+static FULL_LOGGING: AtomicBool = AtomicBool::new(false);
+
+pub fn is_full_logging_enabled() -> bool {
+    FULL_LOGGING.load(Ordering::Relaxed)
+}
+
+pub fn set_full_logging(enabled: bool) {
+    FULL_LOGGING.store(enabled, Ordering::Relaxed);
+}
+
+fn short_hash(value: &str) -> String {
+    use sha2::{Digest, Sha256};
+    Sha256::digest(value.as_bytes())
+        .iter()
+        .take(4)
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Log-safe form of a user value: its length and a short hash, so equal values stay recognisable.
+pub fn redact_value(value: &str) -> String {
+    if is_full_logging_enabled() {
+        value.to_string()
+    } else {
+        format!("<redacted {} chars #{}>", value.chars().count(), short_hash(value))
+    }
+}
+
+/// Log-safe form of a JSON cell value; nulls and booleans carry no user data and are kept.
+pub fn redact_json_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null | serde_json::Value::Bool(_) => value.to_string(),
+        serde_json::Value::String(text) => redact_value(text),
+        other => redact_value(&other.to_string()),
+    }
+}
+
+/// Home directories usually contain the user name
+pub fn redact_path(path: &str) -> String {
+    if is_full_logging_enabled() {
+        return path.to_string();
+    }
+    let home = std::env::var("HOME").or_else(|_| std::env::var("USERPROFILE"));
+    match home {
+        Ok(home) if !home.is_empty() && path.starts_with(&home) => format!("~{}", &path[home.len()..]),
+        _ => path.to_string(),
+    }
+}
+
+/// Replace string, blob and numeric literals in SQL with `?`, keeping identifiers intact.
+pub fn redact_sql(sql: &str) -> String {
+    if is_full_logging_enabled() {
+        sql.to_string()
+    } else {
+        strip_sql_literals(sql)
+    }
+}
+
+pub(crate) fn strip_sql_literals(sql: &str) -> String {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut out = String::with_capacity(sql.len());
+    let mut i = 0;
+
+    let is_ident_char = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\'' => {
+                // X'..' blob literal: drop the prefix along with the literal
+                if out.ends_with(['x', 'X']) {
+                    let prefix_is_word = out
+                        .chars()
+                        .rev()
+                        .nth(1)
+                        .is_some_and(is_ident_char);
+                    if !prefix_is_word {
+                        out.pop();
+                    }
+                }
+                i += 1;
+                while i < chars.len() {
+                    if chars[i] == '\'' {
+                        if chars.get(i + 1) == Some(&'\'') {
+                            i += 2;
+                            continue;
+                        }
+                        break;
+                    }
+                    i += 1;
+                }
+                i += 1;
+                out.push('?');
+            }
+            '"' | '`' | '[' => {
+                let close = if c == '[' { ']' } else { c };
+                out.push(c);
+                i += 1;
+                while i < chars.len() {
+                    out.push(chars[i]);
+                    i += 1;
+                    if chars[i - 1] == close {
+                        break;
+                    }
+                }
+            }
+            c if c.is_ascii_digit() && !out.chars().last().is_some_and(is_ident_char) => {
+                while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '.') {
+                    i += 1;
+                }
+                out.push('?');
+            }
+            _ => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+
+    out
+}
+
 fn source_label(source: &str) -> &'static str {
     match source {
         "frontend" => "🖥 frontend",
@@ -108,7 +335,7 @@ fn collect_merged_logs(log_dir: &Path) -> Result<String, String> {
     Ok(entries.join("\n"))
 }
 
-async fn prompt_save_path(
+pub(crate) async fn prompt_save_path(
     app_handle: &tauri::AppHandle,
     default_name: &str,
     filters: &[(&str, &[&str])],
@@ -135,7 +362,7 @@ async fn prompt_save_path(
 #[tauri::command]
 pub async fn dialog_select_file(
     app_handle: tauri::AppHandle,
-    _options: Option<serde_json::Value>
+    options: Option<serde_json::Value>
 ) -> Result<DialogResult, String> {
     use tokio::sync::oneshot;
     
@@ -143,8 +370,19 @@ pub async fn dialog_select_file(
     
     let mut dialog = app_handle.dialog().file();
     
-    // Add database file filters
-    dialog = dialog.add_filter("Database Files", &["db", "sqlite", "sqlite3", "db3"]);
+    // Callers may pass their own `filters`; database files are the default
+    let filters: Vec<DialogFilter> = options
+        .and_then(|options| options.get("filters").cloned())
+        .and_then(|filters| serde_json::from_value(filters).ok())
+        .unwrap_or_default();
+    if filters.is_empty() {
+        dialog = dialog.add_filter("Database Files", &["db", "sqlite", "sqlite3", "db3"]);
+    } else {
+        for filter in &filters {
+            let extensions: Vec<&str> = filter.extensions.iter().map(String::as_str).collect();
+            dialog = dialog.add_filter(&filter.name, &extensions);
+        }
+    }
     dialog = dialog.add_filter("All Files", &["*"]);
     
     dialog.pick_file(move |file_path| {
@@ -203,6 +441,19 @@ pub async fn save_dropped_file(
     Ok(file_path.to_string_lossy().to_string())
 }
 
+#[tauri::command]
+pub async fn scan_dropped_directory(dir_path: String) -> Result<Vec<DatabaseFile>, String> {
+    log::info!("📁 Scanning dropped directory for databases: {}", dir_path);
+
+    let root = PathBuf::from(&dir_path);
+    let files = tokio::task::spawn_blocking(move || scan_directory_for_databases(&root))
+        .await
+        .map_err(|e| format!("Directory scan failed: {}", e))??;
+
+    log::info!("✅ Found {} databases in {}", files.len(), dir_path);
+    Ok(files)
+}
+
 #[tauri::command]
 pub async fn dialog_save_file(
     app_handle: tauri::AppHandle,
@@ -316,6 +567,436 @@ pub async fn export_logs(
     Ok(Some(save_path.to_string_lossy().to_string()))
 }
 
+// Long-running operations
+// Pulls, pushes and exports get an id and a state the frontend can list, follow through
+// `operation-updated` events and cancel. Transfers are queued per device: overlapping pulls
+// from one device can corrupt afcclient sessions, while different devices run in parallel.
+// Cancelling is cooperative: a queued operation never starts, a running one stops at the
+// next `check_cancelled`, so its cleanup (relaunching the app, removing remote temp files)
+// still runs. A push that started replacing the device file runs to the end.
+
+pub const OPERATION_EVENT: &str = "operation-updated";
+pub const OPERATION_CANCELLED: &str = "Operation cancelled";
+// Finished operations kept for `operations_list`
+const MAX_FINISHED_OPERATIONS: usize = 50;
+
+pub static OPERATIONS: LazyLock<OperationManager> = LazyLock::new(OperationManager::default);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OperationKind {
+    Pull,
+    Push,
+    Export,
+    Update,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OperationState {
+    Queued,
+    Running,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+impl OperationState {
+    fn is_finished(self) -> bool {
+        matches!(self, Self::Done | Self::Failed | Self::Cancelled)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationInfo {
+    pub id: String,
+    pub kind: OperationKind,
+    pub label: String,
+    // Set for transfers, which run one at a time per device
+    pub device_id: Option<String>,
+    pub state: OperationState,
+    // Fraction between 0 and 1, when the operation reports it
+    pub progress: Option<f64>,
+    pub error: Option<String>,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+}
+
+/// Results an operation can end with: whether they count as a failure, and how to report a
+/// cancellation in their place.
+pub trait OperationOutcome {
+    fn failure(&self) -> Option<String>;
+    fn cancelled(message: String) -> Self;
+}
+
+impl<T> OperationOutcome for DeviceResponse<T> {
+    fn failure(&self) -> Option<String> {
+        (!self.success).then(|| self.error.clone().unwrap_or_else(|| "Unknown error".to_string()))
+    }
+
+    fn cancelled(message: String) -> Self {
+        DeviceResponse {
+            success: false,
+            data: None,
+            error_detail: Some(DeviceError::Unknown),
+            error: Some(message),
+        }
+    }
+}
+
+impl<T> OperationOutcome for DbResponse<T> {
+    fn failure(&self) -> Option<String> {
+        (!self.success).then(|| self.error.clone().unwrap_or_else(|| "Unknown error".to_string()))
+    }
+
+    fn cancelled(message: String) -> Self {
+        DbResponse {
+            success: false,
+            data: None,
+            error: Some(message),
+        }
+    }
+}
+
+impl<T: OperationOutcome> OperationOutcome for Result<T, String> {
+    fn failure(&self) -> Option<String> {
+        match self {
+            Ok(outcome) => outcome.failure(),
+            Err(e) => Some(e.clone()),
+        }
+    }
+
+    fn cancelled(message: String) -> Self {
+        Ok(T::cancelled(message))
+    }
+}
+
+#[derive(Default)]
+struct CancelFlags {
+    requested: bool,
+    // Set once the operation started changing the device, after which it cannot stop
+    committed: bool,
+}
+
+#[derive(Default)]
+struct CancelState {
+    flags: Mutex<CancelFlags>,
+    // Wakes the operation while it waits for its device's queue
+    wake: Notify,
+}
+
+impl CancelState {
+    fn flags(&self) -> std::sync::MutexGuard<'_, CancelFlags> {
+        self.flags.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn is_requested(&self) -> bool {
+        self.flags().requested
+    }
+}
+
+tokio::task_local! {
+    static CURRENT_CANCEL: Arc<CancelState>;
+    // Devices whose transfer queue this task holds
+    static HELD_DEVICE_TURNS: Vec<String>;
+}
+
+fn held_device_turns() -> Vec<String> {
+    HELD_DEVICE_TURNS.try_with(Vec::clone).unwrap_or_default()
+}
+
+/// Run `work` in `device_id`'s transfer queue, for pulls that are not an operation of their
+/// own (scheduled pulls, bookmarks, snapshots before a push). Inside an operation or another
+/// turn for the same device it runs right away rather than waiting on itself.
+pub async fn with_device_turn<R>(device_id: &str, work: impl Future<Output = R>) -> R {
+    let mut held = held_device_turns();
+    if held.iter().any(|held| held == device_id) {
+        return work.await;
+    }
+    let _turn = OPERATIONS.device_queue(device_id).acquire_owned().await.ok();
+    held.push(device_id.to_string());
+    HELD_DEVICE_TURNS.scope(held, work).await
+}
+
+/// Fails once the operation this task runs for has been cancelled. Call it between steps,
+/// where stopping leaves nothing half written; outside an operation it always passes.
+pub fn check_cancelled() -> Result<(), String> {
+    let requested = CURRENT_CANCEL.try_with(|cancel| cancel.is_requested()).unwrap_or(false);
+    if requested {
+        Err(OPERATION_CANCELLED.to_string())
+    } else {
+        Ok(())
+    }
+}
+
+/// Mark the point after which the running operation is no longer cancellable, right before
+/// it starts replacing a file on the device. Fails when it was cancelled before getting here.
+pub fn commit_operation() -> Result<(), String> {
+    CURRENT_CANCEL
+        .try_with(|cancel| {
+            let mut flags = cancel.flags();
+            if flags.requested {
+                return Err(OPERATION_CANCELLED.to_string());
+            }
+            flags.committed = true;
+            Ok(())
+        })
+        .unwrap_or(Ok(()))
+}
+
+/// Resolves once the operation this task runs for is cancelled, for work that is safe to
+/// drop at any point, like a download into memory. Never resolves outside an operation.
+pub async fn cancelled() {
+    let Ok(cancel) = CURRENT_CANCEL.try_with(Arc::clone) else {
+        return std::future::pending().await;
+    };
+    while !cancel.is_requested() {
+        cancel.wake.notified().await;
+    }
+}
+
+struct TrackedOperation {
+    info: OperationInfo,
+    cancel: Arc<CancelState>,
+}
+
+#[derive(Default)]
+pub struct OperationManager {
+    operations: Mutex<Vec<TrackedOperation>>,
+    device_queues: Mutex<HashMap<String, Arc<Semaphore>>>,
+    app_handle: Mutex<Option<tauri::AppHandle>>,
+}
+
+impl OperationManager {
+    /// Register an operation as queued; it runs once `OperationHandle::run` is awaited and,
+    /// for a device transfer, no other transfer to `device_id` is running.
+    pub fn start(&self, kind: OperationKind, label: impl Into<String>, device_id: Option<&str>) -> OperationHandle<'_> {
+        let device_queue = device_id.map(|device_id| (device_id.to_string(), self.device_queue(device_id)));
+        let info = OperationInfo {
+            id: uuid::Uuid::new_v4().to_string(),
+            kind,
+            label: label.into(),
+            device_id: device_id.map(str::to_string),
+            state: OperationState::Queued,
+            progress: None,
+            error: None,
+            started_at: chrono::Utc::now().to_rfc3339(),
+            finished_at: None,
+        };
+        let cancel = Arc::new(CancelState::default());
+        let handle = OperationHandle {
+            manager: self,
+            id: info.id.clone(),
+            kind,
+            started: std::time::Instant::now(),
+            cancel: cancel.clone(),
+            device_queue,
+        };
+        self.operations.lock().unwrap_or_else(|e| e.into_inner()).push(TrackedOperation {
+            info: info.clone(),
+            cancel,
+        });
+        self.emit(&info);
+        handle
+    }
+
+    fn device_queue(&self, device_id: &str) -> Arc<Semaphore> {
+        self.device_queues
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(device_id.to_string())
+            .or_insert_with(|| Arc::new(Semaphore::new(1)))
+            .clone()
+    }
+
+    pub fn list(&self) -> Vec<OperationInfo> {
+        self.operations
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|operation| operation.info.clone())
+            .collect()
+    }
+
+    /// Ask an unfinished operation to stop. Fails when there is none with this id or when it
+    /// is already writing to the device.
+    pub fn cancel(&self, id: &str) -> Result<(), String> {
+        let operations = self.operations.lock().unwrap_or_else(|e| e.into_inner());
+        let operation = operations
+            .iter()
+            .find(|operation| operation.info.id == id && !operation.info.state.is_finished())
+            .ok_or_else(|| format!("No running operation with id {}", id))?;
+        {
+            let mut flags = operation.cancel.flags();
+            if flags.committed {
+                return Err(format!("Operation {} is writing to the device and can no longer be cancelled", id));
+            }
+            flags.requested = true;
+        }
+        // The permit is kept when the operation is not waiting yet
+        operation.cancel.wake.notify_one();
+        Ok(())
+    }
+
+    fn update(&self, id: &str, change: impl FnOnce(&mut OperationInfo)) {
+        let updated = {
+            let mut operations = self.operations.lock().unwrap_or_else(|e| e.into_inner());
+            let Some(operation) = operations.iter_mut().find(|operation| operation.info.id == id) else {
+                return;
+            };
+            change(&mut operation.info);
+            let updated = operation.info.clone();
+
+            if updated.state.is_finished() {
+                let finished = operations.iter().filter(|operation| operation.info.state.is_finished()).count();
+                let mut excess = finished.saturating_sub(MAX_FINISHED_OPERATIONS);
+                operations.retain(|operation| {
+                    let drop_it = excess > 0 && operation.info.state.is_finished();
+                    if drop_it {
+                        excess -= 1;
+                    }
+                    !drop_it
+                });
+            }
+            updated
+        };
+        self.emit(&updated);
+    }
+
+    fn emit(&self, info: &OperationInfo) {
+        if let Some(app_handle) = self.app_handle.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+            let _ = app_handle.emit(OPERATION_EVENT, info);
+        }
+    }
+}
+
+pub struct OperationHandle<'a> {
+    manager: &'a OperationManager,
+    id: String,
+    kind: OperationKind,
+    started: std::time::Instant,
+    cancel: Arc<CancelState>,
+    device_queue: Option<(String, Arc<Semaphore>)>,
+}
+
+impl OperationHandle<'_> {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn set_progress(&self, done: u64, total: u64) {
+        if total > 0 {
+            let fraction = (done as f64 / total as f64).min(1.0);
+            self.manager.update(&self.id, |info| info.progress = Some(fraction));
+        }
+    }
+
+    fn kind_name(&self) -> String {
+        format!("{:?}", self.kind).to_lowercase()
+    }
+
+    // Empty outside a device queue; empty key-value fields are left out of the operation log
+    fn device_id(&self) -> &str {
+        self.device_queue.as_ref().map_or("", |(device_id, _)| device_id.as_str())
+    }
+
+    /// Wait for the device queue, then run `work`. Returns None when cancelled before `work`
+    /// started; only the wait is abandoned, `work` itself is never dropped half way.
+    async fn run_in_turn<R, F: Future<Output = R>>(&self, work: F) -> Option<R> {
+        let mut held = held_device_turns();
+        let _turn = match &self.device_queue {
+            Some((device_id, queue)) if !held.contains(device_id) => {
+                let turn = tokio::select! {
+                    turn = queue.clone().acquire_owned() => turn.ok(),
+                    _ = self.cancel.wake.notified() => return None,
+                };
+                held.push(device_id.clone());
+                turn
+            }
+            _ => None,
+        };
+        if self.cancel.is_requested() {
+            return None;
+        }
+        self.manager.update(&self.id, |info| info.state = OperationState::Running);
+        log::info!(
+            operationId = self.id.as_str(), kind = self.kind_name().as_str(), device = self.device_id();
+            "Operation {} started", self.id
+        );
+        let work = HELD_DEVICE_TURNS.scope(held, work);
+        Some(CURRENT_CANCEL.scope(self.cancel.clone(), work).await)
+    }
+
+    /// Run `work` as this operation, recording how it ended. A cancellation while queued
+    /// returns the cancelled outcome without running `work`; a running one ends at the next
+    /// `check_cancelled` and its failure is recorded as the cancellation.
+    pub async fn run<R, F>(&self, work: F) -> R
+    where
+        R: OperationOutcome,
+        F: Future<Output = R>,
+    {
+        let outcome = self.run_in_turn(work).await;
+        let (state, error, outcome) = match outcome {
+            Some(outcome) => match outcome.failure() {
+                Some(_) if self.cancel.is_requested() => (OperationState::Cancelled, None, outcome),
+                Some(e) => (OperationState::Failed, Some(e), outcome),
+                None => (OperationState::Done, None, outcome),
+            },
+            None => (OperationState::Cancelled, None, R::cancelled(OPERATION_CANCELLED.to_string())),
+        };
+        let state_name = format!("{:?}", state).to_lowercase();
+        let duration_ms = self.started.elapsed().as_millis() as u64;
+        log::info!(
+            operationId = self.id.as_str(), kind = self.kind_name().as_str(), device = self.device_id(),
+            state = state_name.as_str(), durationMs = duration_ms;
+            "Operation {} {} after {}ms", self.id, state_name, duration_ms
+        );
+
+        self.manager.update(&self.id, |info| {
+            info.state = state;
+            info.error = error;
+            if state == OperationState::Done {
+                info.progress = Some(1.0);
+            }
+            info.finished_at = Some(chrono::Utc::now().to_rfc3339());
+        });
+        outcome
+    }
+}
+
+/// Route operation events to the frontend; run once on startup.
+pub fn init_operations(app_handle: &tauri::AppHandle) {
+    *OPERATIONS.app_handle.lock().unwrap_or_else(|e| e.into_inner()) = Some(app_handle.clone());
+}
+
+/// Running, queued and recently finished operations
+#[tauri::command]
+pub async fn operations_list() -> Result<DbResponse<Vec<OperationInfo>>, String> {
+    Ok(DbResponse {
+        success: true,
+        data: Some(OPERATIONS.list()),
+        error: None,
+    })
+}
+
+#[tauri::command]
+pub async fn operation_cancel(id: String) -> Result<DbResponse<bool>, String> {
+    log::info!(operationId = id.as_str(); "Cancelling operation {}", id);
+    match OPERATIONS.cancel(&id) {
+        Ok(()) => Ok(DbResponse {
+            success: true,
+            data: Some(true),
+            error: None,
+        }),
+        Err(e) => Ok(DbResponse {
+            success: false,
+            data: None,
+            error: Some(e),
+        }),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -519,4 +1200,176 @@ mod tests {
             "2026-01-01T10:00:00.000Z [INFO] [⚙ backend] api\n2026-01-01T10:00:01.000Z [INFO] [🖥 frontend] ui"
         );
     }
+
+    #[test]
+    fn test_scan_directory_for_databases_finds_nested_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let container = temp_dir.path().join("com.example.app");
+        let nested = container.join("Library/Application Support");
+        let leveldb = container.join("Library/WebKit/LocalStorage/leveldb");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::create_dir_all(&leveldb).unwrap();
+
+        std::fs::write(container.join("top.db"), b"").unwrap();
+        std::fs::write(nested.join("store.SQLITE"), b"").unwrap();
+        std::fs::write(nested.join("store.sqlite-wal"), b"").unwrap();
+        std::fs::write(nested.join("notes.txt"), b"").unwrap();
+        std::fs::write(leveldb.join("CURRENT"), b"MANIFEST-000001\n").unwrap();
+        std::fs::write(leveldb.join("MANIFEST-000001"), b"").unwrap();
+
+        let files = scan_directory_for_databases(&container).unwrap();
+        let names: Vec<&str> = files.iter().map(|file| file.filename.as_str()).collect();
+
+        assert_eq!(names, vec!["store.SQLITE", "leveldb", "top.db"]);
+        assert_eq!(files[0].location, "Library/Application Support");
+        assert_eq!(files[1].file_type, DatabaseFileType::LevelDb);
+        assert_eq!(files[2].location, ".");
+        assert!(files.iter().all(|file| file.package_name == "com.example.app"));
+        assert!(files.iter().all(|file| file.device_type == "local"));
+    }
+
+    #[test]
+    fn test_scan_directory_for_databases_rejects_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file = temp_dir.path().join("single.db");
+        std::fs::write(&file, b"").unwrap();
+
+        assert!(scan_directory_for_databases(&file).is_err());
+    }
+
+    #[test]
+    fn test_strip_sql_literals() {
+        assert_eq!(
+            strip_sql_literals("UPDATE users SET name = ? WHERE email = 'jane@example.com' AND id = 42"),
+            "UPDATE users SET name = ? WHERE email = ? AND id = ?"
+        );
+        assert_eq!(
+            strip_sql_literals("SELECT \"col 1\", t2.x FROM t2 WHERE note = 'it''s' OR data = X'00FF' OR v > 1.5e3"),
+            "SELECT \"col 1\", t2.x FROM t2 WHERE note = ? OR data = ? OR v > ?"
+        );
+        assert_eq!(strip_sql_literals("SELECT [weird '1'] FROM tax"), "SELECT [weird '1'] FROM tax");
+    }
+
+    #[tokio::test]
+    async fn test_operation_manager_tracks_and_cancels() {
+        let manager = OperationManager::default();
+
+        let export = manager.start(OperationKind::Export, "Export users", None);
+        assert_eq!(manager.list()[0].state, OperationState::Queued);
+        export.set_progress(5, 10);
+        assert_eq!(manager.list()[0].progress, Some(0.5));
+        let response: DbResponse<u32> = export
+            .run(async { DbResponse { success: true, data: Some(3), error: None } })
+            .await;
+        assert_eq!(response.data, Some(3));
+        assert_eq!(manager.list()[0].state, OperationState::Done);
+
+        let push = manager.start(OperationKind::Push, "Push app.db", Some("emulator-5554"));
+        let failed: Result<DeviceResponse<()>, String> = push.run(async { Err("device offline".to_string()) }).await;
+        assert!(failed.is_err());
+        assert_eq!(manager.list()[1].error.as_deref(), Some("device offline"));
+
+        let pull = manager.start(OperationKind::Pull, "Pull databases", Some("emulator-5554"));
+        assert!(manager.cancel(pull.id()).is_ok());
+        let cancelled: Result<DeviceResponse<()>, String> = pull.run(std::future::pending()).await;
+        assert_eq!(cancelled.unwrap().error.as_deref(), Some("Operation cancelled"));
+        assert_eq!(manager.list()[2].state, OperationState::Cancelled);
+        // Finished operations cannot be cancelled again
+        assert!(manager.cancel(pull.id()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_running_operation_stops_at_safe_points() {
+        let manager = OperationManager::default();
+        let (started, wait_started) = tokio::sync::oneshot::channel::<()>();
+        let (resume, wait_resume) = tokio::sync::oneshot::channel::<()>();
+
+        // The pull finishes its current step and cleans up before reporting the cancellation
+        let pull = manager.start(OperationKind::Pull, "Pull databases", Some("device-a"));
+        let cleaned_up = AtomicBool::new(false);
+        let pull_run = pull.run(async {
+            let _ = started.send(());
+            let _ = wait_resume.await;
+            let result = check_cancelled();
+            cleaned_up.store(true, Ordering::SeqCst);
+            result.map(|()| DeviceResponse { success: true, data: Some(()), error: None, error_detail: None })
+        });
+        let cancel = async {
+            let _ = wait_started.await;
+            assert!(manager.cancel(pull.id()).is_ok());
+            let _ = resume.send(());
+        };
+        let (outcome, ()) = tokio::join!(pull_run, cancel);
+        assert_eq!(outcome.unwrap_err(), OPERATION_CANCELLED);
+        assert!(cleaned_up.load(Ordering::SeqCst));
+        assert_eq!(manager.list()[0].state, OperationState::Cancelled);
+
+        // A push that started writing refuses to be cancelled
+        let push = manager.start(OperationKind::Push, "Push app.db", Some("device-a"));
+        let push_run = push.run(async {
+            commit_operation()?;
+            assert!(manager.cancel(push.id()).unwrap_err().contains("can no longer be cancelled"));
+            check_cancelled().map(|()| DeviceResponse { success: true, data: Some(()), error: None, error_detail: None })
+        });
+        assert!(push_run.await.unwrap().success);
+        assert_eq!(manager.list()[1].state, OperationState::Done);
+    }
+
+    #[tokio::test]
+    async fn test_operations_queue_per_device() {
+        let manager = OperationManager::default();
+        let (release_first, first_released) = tokio::sync::oneshot::channel::<()>();
+
+        let first = manager.start(OperationKind::Pull, "Pull from device A", Some("device-a"));
+        let second = manager.start(OperationKind::Push, "Push to device A", Some("device-a"));
+        let other = manager.start(OperationKind::Pull, "Pull from device B", Some("device-b"));
+        let state = |index: usize| manager.list()[index].state;
+
+        let first_run = first.run(async {
+            let _ = first_released.await;
+            DbResponse { success: true, data: Some(()), error: None }
+        });
+        let second_run = second.run(async { DbResponse { success: true, data: Some(()), error: None } });
+        let checks = async {
+            // Device B does not wait for device A
+            other.run(async { DbResponse { success: true, data: Some(()), error: None } }).await;
+            assert_eq!(state(2), OperationState::Done);
+            assert_eq!(state(0), OperationState::Running);
+            assert_eq!(state(1), OperationState::Queued);
+            let _ = release_first.send(());
+        };
+        tokio::join!(first_run, second_run, checks);
+
+        assert_eq!(state(0), OperationState::Done);
+        assert_eq!(state(1), OperationState::Done);
+    }
+
+    #[tokio::test]
+    async fn test_device_turn_is_reentrant() {
+        let (release, released) = tokio::sync::oneshot::channel::<()>();
+        let nested = with_device_turn("device-turn-test", async {
+            // A transfer inside the turn does not wait for the turn it is part of
+            with_device_turn("device-turn-test", async {}).await;
+            let _ = released.await;
+        });
+        let queued = async {
+            tokio::task::yield_now().await;
+            let queue = OPERATIONS.device_queue("device-turn-test");
+            assert_eq!(queue.available_permits(), 0);
+            let _ = release.send(());
+            with_device_turn("device-turn-test", async {}).await;
+            assert_eq!(queue.available_permits(), 1);
+        };
+        tokio::join!(nested, queued);
+    }
+
+    #[test]
+    fn test_redact_value_hides_content() {
+        let redacted = redact_value("555-0100");
+        assert!(!redacted.contains("555"));
+        assert!(redacted.contains("8 chars"));
+        assert_eq!(redacted, redact_value("555-0100"));
+        assert_eq!(redact_json_value(&serde_json::Value::Null), "null");
+        assert_ne!(redact_json_value(&serde_json::json!(42)), "42");
+    }
 }
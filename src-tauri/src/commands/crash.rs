@@ -0,0 +1,458 @@
+//! Opt-in crash reporting
+//!
+//! A panic hook writes an anonymized report to `crash-reports/` in the app data directory for
+//! every panic, including panics inside async tasks that would otherwise only end the task.
+//! Error-level log records are counted per source so a report shows what was failing before
+//! the crash. Reports only leave the machine through `crash_reports_upload`, which requires
+//! the `crashReports.uploadConsent` setting and sends them with the diagnostics documents.
+
+use crate::commands::database::{DbConnectionCache, DbResponse};
+use crate::commands::diagnostics::{diagnostics_documents, recent_operation_log, write_diagnostics_zip};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex, RwLock};
+use tauri::State;
+
+const CRASH_DIR_NAME: &str = "crash-reports";
+// Oldest reports are dropped beyond this
+const MAX_STORED_REPORTS: usize = 50;
+const UPLOAD_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+static CRASH_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+static ERROR_SUMMARY: LazyLock<Mutex<BTreeMap<String, ErrorStats>>> =
+    LazyLock::new(|| Mutex::new(BTreeMap::new()));
+// Device serials and package IDs from this session's device listings, in first-seen order
+static IDENTIFIERS: LazyLock<Mutex<Identifiers>> = LazyLock::new(|| Mutex::new(Identifiers::default()));
+// Anything shorter could match unrelated text
+const MIN_IDENTIFIER_LEN: usize = 4;
+
+#[derive(Default)]
+struct Identifiers {
+    devices: Vec<String>,
+    packages: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorStats {
+    pub count: u64,
+    pub last_message: String,
+    pub last_seen: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+    pub id: String,
+    pub created_at: String,
+    pub message: String,
+    pub location: Option<String>,
+    pub thread: Option<String>,
+    pub backtrace: String,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    // Error-level log records this session, keyed by log target
+    pub error_summary: BTreeMap<String, ErrorStats>,
+}
+
+fn home_dir() -> Option<String> {
+    std::env::var("HOME")
+        .or_else(|_| std::env::var("USERPROFILE"))
+        .ok()
+        .filter(|home| home.len() > 1)
+}
+
+fn remember(known: &mut Vec<String>, ids: impl IntoIterator<Item = String>) {
+    for id in ids {
+        if id.len() >= MIN_IDENTIFIER_LEN && !known.contains(&id) {
+            known.push(id);
+        }
+    }
+}
+
+/// Remember listed device serials so reports and uploads can redact them
+pub fn remember_device_ids(ids: impl IntoIterator<Item = String>) {
+    if let Ok(mut identifiers) = IDENTIFIERS.lock() {
+        remember(&mut identifiers.devices, ids);
+    }
+}
+
+/// Remember listed package IDs so reports and uploads can redact them
+pub fn remember_package_ids(ids: impl IntoIterator<Item = String>) {
+    if let Ok(mut identifiers) = IDENTIFIERS.lock() {
+        remember(&mut identifiers.packages, ids);
+    }
+}
+
+/// Replace remembered serials and package IDs with `<device-N>`/`<package-N>`, numbered in
+/// first-seen order so the same device reads the same across a report.
+fn redact_identifiers(text: &str) -> String {
+    // try_lock: this also runs in the panic hook
+    let Ok(identifiers) = IDENTIFIERS.try_lock() else {
+        return text.to_string();
+    };
+    let label = |prefix: &'static str| move |(index, id): (usize, &String)| (id.clone(), format!("<{}-{}>", prefix, index + 1));
+    let mut replacements: Vec<(String, String)> = identifiers
+        .devices
+        .iter()
+        .enumerate()
+        .map(label("device"))
+        .chain(identifiers.packages.iter().enumerate().map(label("package")))
+        .collect();
+    drop(identifiers);
+    // Longest first, so an ID containing another one is replaced whole
+    replacements.sort_by_key(|(id, _)| std::cmp::Reverse(id.len()));
+    replacements
+        .into_iter()
+        .fold(text.to_string(), |text, (id, placeholder)| text.replace(&id, &placeholder))
+}
+
+/// `anonymize` every string in a JSON document, object keys included
+fn anonymize_json(value: serde_json::Value, home: Option<&str>) -> serde_json::Value {
+    use serde_json::Value;
+    match value {
+        Value::String(text) => Value::String(anonymize(&text, home)),
+        Value::Array(items) => Value::Array(items.into_iter().map(|item| anonymize_json(item, home)).collect()),
+        Value::Object(fields) => Value::Object(
+            fields
+                .into_iter()
+                .map(|(key, value)| (anonymize(&key, home), anonymize_json(value, home)))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+/// Strip what identifies the user: the home directory (and with it the user name), e-mail
+/// addresses, and the serials and package IDs of devices listed this session.
+pub fn anonymize(text: &str, home: Option<&str>) -> String {
+    let text = match home {
+        Some(home) => text.replace(home, "~"),
+        None => text.to_string(),
+    };
+
+    let text: String = text
+        .split_inclusive(char::is_whitespace)
+        .map(|word| {
+            let token = word.trim_end();
+            let is_email = token
+                .split_once('@')
+                .is_some_and(|(user, domain)| !user.is_empty() && domain.contains('.'));
+            if is_email {
+                word.replacen(token, "<email>", 1)
+            } else {
+                word.to_string()
+            }
+        })
+        .collect();
+    redact_identifiers(&text)
+}
+
+/// Count an error-level log record; called from the log plugin's formatter.
+pub fn record_error(target: &str, message: &str) {
+    // try_lock: a panic while this lock is held must not deadlock the panic hook's logging
+    let Ok(mut summary) = ERROR_SUMMARY.try_lock() else {
+        return;
+    };
+    let stats = summary.entry(target.to_string()).or_insert_with(|| ErrorStats {
+        count: 0,
+        last_message: String::new(),
+        last_seen: String::new(),
+    });
+    stats.count += 1;
+    stats.last_message = anonymize(message, home_dir().as_deref());
+    stats.last_seen = chrono::Utc::now().to_rfc3339();
+}
+
+fn error_summary() -> BTreeMap<String, ErrorStats> {
+    ERROR_SUMMARY
+        .try_lock()
+        .map(|summary| summary.clone())
+        .unwrap_or_default()
+}
+
+impl CrashReport {
+    fn from_panic(message: &str, location: Option<String>) -> Self {
+        let home = home_dir();
+        let backtrace = std::backtrace::Backtrace::force_capture().to_string();
+        CrashReport {
+            id: uuid::Uuid::new_v4().to_string(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            message: anonymize(message, home.as_deref()),
+            location: location.map(|location| anonymize(&location, home.as_deref())),
+            thread: std::thread::current().name().map(str::to_string),
+            backtrace: anonymize(&backtrace, home.as_deref()),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            error_summary: error_summary(),
+        }
+    }
+}
+
+fn write_report(dir: &Path, report: &CrashReport) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{}.json", report.id));
+    std::fs::write(&path, serde_json::to_vec_pretty(report).map_err(std::io::Error::other)?)?;
+
+    let reports = load_reports(dir);
+    for stale in reports.iter().skip(MAX_STORED_REPORTS) {
+        let _ = std::fs::remove_file(dir.join(format!("{}.json", stale.id)));
+    }
+    Ok(path)
+}
+
+/// Stored reports, newest first
+fn load_reports(dir: &Path) -> Vec<CrashReport> {
+    let mut reports: Vec<CrashReport> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                .filter_map(|path| std::fs::read(path).ok())
+                .filter_map(|bytes| serde_json::from_slice(&bytes).ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    reports.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    reports
+}
+
+fn crash_dir() -> Option<PathBuf> {
+    CRASH_DIR.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Install the panic hook; reports are written once `init_crash_reports` knows where.
+pub fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Unknown panic payload".to_string());
+        let location = info
+            .location()
+            .map(|location| format!("{}:{}:{}", location.file(), location.line(), location.column()));
+
+        if let Some(dir) = crash_dir() {
+            let report = CrashReport::from_panic(&message, location);
+            match write_report(&dir, &report) {
+                Ok(path) => log::error!("💥 Panic recorded in crash report {}", path.display()),
+                Err(e) => log::error!("💥 Panic, and the crash report could not be written: {}", e),
+            }
+        }
+        previous(info);
+    }));
+}
+
+pub fn init_crash_reports() {
+    match crate::commands::settings::app_data_dir() {
+        Some(data_dir) => {
+            let dir = data_dir.join(CRASH_DIR_NAME);
+            let pending = load_reports(&dir).len();
+            if pending > 0 {
+                log::warn!("⚠️ {} crash reports from earlier sessions are stored locally", pending);
+            }
+            *CRASH_DIR.write().unwrap_or_else(|e| e.into_inner()) = Some(dir);
+        }
+        None => log::warn!("⚠️ Crash reports disabled, no app data directory"),
+    }
+}
+
+/// Crash reports stored on this machine, newest first
+#[tauri::command]
+pub async fn crash_reports_list() -> Result<DbResponse<Vec<CrashReport>>, String> {
+    let reports = crash_dir().map(|dir| load_reports(&dir)).unwrap_or_default();
+    Ok(DbResponse {
+        success: true,
+        data: Some(reports),
+        error: None,
+    })
+}
+
+#[tauri::command]
+pub async fn crash_reports_clear() -> Result<DbResponse<usize>, String> {
+    let Some(dir) = crash_dir() else {
+        return Ok(DbResponse {
+            success: true,
+            data: Some(0),
+            error: None,
+        });
+    };
+
+    let reports = load_reports(&dir);
+    let removed = reports
+        .iter()
+        .filter(|report| std::fs::remove_file(dir.join(format!("{}.json", report.id))).is_ok())
+        .count();
+    log::info!("🧹 Removed {} crash reports", removed);
+    Ok(DbResponse {
+        success: true,
+        data: Some(removed),
+        error: None,
+    })
+}
+
+/// Upload stored reports with the diagnostics documents; needs the user's consent in settings.
+/// Uploaded reports are removed locally.
+#[tauri::command]
+pub async fn crash_reports_upload(
+    app_handle: tauri::AppHandle,
+    db_cache: State<'_, DbConnectionCache>,
+) -> Result<DbResponse<usize>, String> {
+    let result = async {
+        let settings = crate::commands::settings::current_settings().crash_reports;
+        if !settings.upload_consent {
+            return Err("Crash report upload is off; enable it in settings first".to_string());
+        }
+        if settings.upload_url.trim().is_empty() {
+            return Err("No crash report upload URL is configured".to_string());
+        }
+        let Some(dir) = crash_dir() else {
+            return Ok(0);
+        };
+        let reports = load_reports(&dir);
+        if reports.is_empty() {
+            return Ok(0);
+        }
+
+        let home = home_dir();
+        let mut log_entries = recent_operation_log();
+        for entry in &mut log_entries {
+            entry.message = anonymize(&entry.message, home.as_deref());
+            for value in entry.fields.values_mut() {
+                *value = anonymize(value, home.as_deref());
+            }
+        }
+        // Tool paths and database paths in the documents are as identifying as the log
+        let mut documents: Vec<_> = diagnostics_documents(&app_handle, &db_cache)
+            .await?
+            .into_iter()
+            .map(|(name, document)| (name, anonymize_json(document, home.as_deref())))
+            .collect();
+        documents.push((
+            "crash_reports",
+            anonymize_json(serde_json::to_value(&reports).map_err(|e| e.to_string())?, home.as_deref()),
+        ));
+
+        // Raw log files are left out; they aren't anonymized
+        let bundle_path = std::env::temp_dir().join(format!("flippio-crash-{}.zip", uuid::Uuid::new_v4()));
+        let zip_path = bundle_path.clone();
+        let bundle = tokio::task::spawn_blocking(move || {
+            write_diagnostics_zip(&zip_path, &log_entries, &[], &documents)?;
+            std::fs::read(&zip_path).map_err(|e| e.to_string())
+        })
+        .await
+        .map_err(|e| format!("Crash report bundle task failed: {}", e))?;
+        let _ = std::fs::remove_file(&bundle_path);
+        let bundle = bundle?;
+
+        reqwest::Client::new()
+            .post(settings.upload_url.trim())
+            .timeout(UPLOAD_TIMEOUT)
+            .header(reqwest::header::CONTENT_TYPE, "application/zip")
+            .body(bundle)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| format!("Failed to upload crash reports: {}", e))?;
+
+        for report in &reports {
+            let _ = std::fs::remove_file(dir.join(format!("{}.json", report.id)));
+        }
+        Ok(reports.len())
+    }
+    .await;
+
+    match result {
+        Ok(uploaded) => {
+            log::info!("📤 Uploaded {} crash reports", uploaded);
+            Ok(DbResponse {
+                success: true,
+                data: Some(uploaded),
+                error: None,
+            })
+        }
+        Err(e) => {
+            log::error!("❌ {}", e);
+            Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn report(id: &str, created_at: &str) -> CrashReport {
+        CrashReport {
+            id: id.to_string(),
+            created_at: created_at.to_string(),
+            message: "index out of bounds".to_string(),
+            location: Some("src/main.rs:1:1".to_string()),
+            thread: Some("main".to_string()),
+            backtrace: String::new(),
+            app_version: "1.0.0".to_string(),
+            os: "linux".to_string(),
+            arch: "x86_64".to_string(),
+            error_summary: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_anonymize() {
+        assert_eq!(
+            anonymize("Failed to open /home/alex/db.sqlite for alex@example.com", Some("/home/alex")),
+            "Failed to open ~/db.sqlite for <email>"
+        );
+        assert_eq!(anonymize("user@localhost stays", None), "user@localhost stays");
+    }
+
+    #[test]
+    fn test_anonymize_redacts_listed_devices_and_packages() {
+        remember_device_ids(["R5CWCRASHTEST".to_string()]);
+        remember_package_ids(["org.flippio.crashtest".to_string(), "org.flippio.crashtest.debug".to_string()]);
+        let redacted = anonymize("Pull of org.flippio.crashtest.debug from R5CWCRASHTEST failed", None);
+        assert!(!redacted.contains("R5CWCRASHTEST") && !redacted.contains("org.flippio"));
+        assert!(redacted.contains("<device-") && redacted.contains("<package-"));
+
+        let document = serde_json::json!({
+            "/home/alex/app.db": { "path": "/home/alex/app.db", "device": "R5CWCRASHTEST" }
+        });
+        let anonymized = anonymize_json(document, Some("/home/alex")).to_string();
+        assert!(!anonymized.contains("/home/alex") && !anonymized.contains("R5CWCRASHTEST"));
+    }
+
+    #[test]
+    fn test_write_and_load_reports() {
+        let temp_dir = TempDir::new().unwrap();
+        write_report(temp_dir.path(), &report("older", "2024-01-01T00:00:00Z")).unwrap();
+        write_report(temp_dir.path(), &report("newer", "2024-02-01T00:00:00Z")).unwrap();
+
+        let ids: Vec<String> = load_reports(temp_dir.path()).into_iter().map(|r| r.id).collect();
+        assert_eq!(ids, vec!["newer", "older"]);
+    }
+
+    #[test]
+    fn test_write_report_prunes_oldest() {
+        let temp_dir = TempDir::new().unwrap();
+        for i in 0..=MAX_STORED_REPORTS {
+            let created_at = format!("2024-01-01T00:00:{:02}Z", i);
+            write_report(temp_dir.path(), &report(&format!("r{}", i), &created_at)).unwrap();
+        }
+
+        let reports = load_reports(temp_dir.path());
+        assert_eq!(reports.len(), MAX_STORED_REPORTS);
+        assert!(reports.iter().all(|r| r.id != "r0"));
+    }
+}
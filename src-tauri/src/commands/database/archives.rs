@@ -0,0 +1,285 @@
+// Database archives
+// Finds SQLite databases stored in SQLite archives (.sqlar) and zip-based bundles
+// (.zip/.apk/.ipa) and extracts them to the temp directory so they can be opened like any file
+
+use crate::commands::database::types::DbResponse;
+use crate::commands::device::helpers::{ensure_temp_dir, namespaced_temp_path};
+use flate2::read::ZlibDecoder;
+use rusqlite::{Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+// Entries with these extensions (or none) are checked for the SQLite header
+const DATABASE_EXTENSIONS: [&str; 5] = ["db", "sqlite", "sqlite3", "db3", "sqlitedb"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    Sqlar,
+    Zip,
+}
+
+impl ArchiveKind {
+    pub fn from_path(path: &str) -> Option<Self> {
+        let extension = Path::new(path).extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "sqlar" => Some(Self::Sqlar),
+            "zip" | "apk" | "aab" | "ipa" => Some(Self::Zip),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveDatabase {
+    // Path of the database inside the archive
+    pub entry_name: String,
+    // Extracted copy in the temp directory
+    pub path: String,
+    pub size: u64,
+}
+
+fn is_candidate_entry(name: &str) -> bool {
+    match Path::new(name).extension().and_then(|extension| extension.to_str()) {
+        Some(extension) => DATABASE_EXTENSIONS.contains(&extension.to_lowercase().as_str()),
+        None => true,
+    }
+}
+
+/// Extraction folder for one archive, `<temp>/archives/<archive name>_<hash of its path>`.
+fn archive_namespace(archive_path: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    archive_path.hash(&mut hasher);
+    let name = Path::new(archive_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "archive".to_string());
+    format!("{}_{:x}", name, hasher.finish())
+}
+
+fn extraction_path(dest_dir: &Path, archive_path: &str, entry_name: &str) -> Result<PathBuf, String> {
+    namespaced_temp_path(dest_dir, "archives", &archive_namespace(archive_path), entry_name)
+        .map_err(|e| format!("Failed to prepare extraction path for '{}': {}", entry_name, e))
+}
+
+/// Write `reader` to `target` if it starts with the SQLite header. Returns the bytes written.
+fn extract_if_sqlite(mut reader: impl Read, target: &Path) -> Result<Option<u64>, String> {
+    let mut header = [0u8; 16];
+    if reader.read_exact(&mut header).is_err() || &header != SQLITE_HEADER {
+        return Ok(None);
+    }
+
+    let mut file = fs::File::create(target).map_err(|e| format!("Failed to create '{}': {}", target.display(), e))?;
+    file.write_all(&header)
+        .and_then(|_| std::io::copy(&mut reader, &mut file))
+        .map(|copied| Some(copied + header.len() as u64))
+        .map_err(|e| format!("Failed to extract '{}': {}", target.display(), e))
+}
+
+/// Extract the SQLite databases found in a zip-based archive.
+pub fn extract_zip_databases(archive_path: &str, dest_dir: &Path) -> Result<Vec<ArchiveDatabase>, String> {
+    let file = fs::File::open(archive_path).map_err(|e| format!("Failed to open archive: {}", e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("Failed to read archive: {}", e))?;
+
+    let mut databases = Vec::new();
+    for index in 0..archive.len() {
+        let entry = archive
+            .by_index(index)
+            .map_err(|e| format!("Failed to read archive entry: {}", e))?;
+        let entry_name = entry.name().to_string();
+        if entry.is_dir() || !is_candidate_entry(&entry_name) {
+            continue;
+        }
+
+        let target = extraction_path(dest_dir, archive_path, &entry_name)?;
+        if let Some(size) = extract_if_sqlite(entry, &target)? {
+            databases.push(ArchiveDatabase {
+                entry_name,
+                path: target.to_string_lossy().to_string(),
+                size,
+            });
+        }
+    }
+    Ok(databases)
+}
+
+/// Extract the SQLite databases stored in a SQLite archive. Entry data is zlib-compressed
+/// unless its stored length already equals the original size.
+pub fn extract_sqlar_databases(archive_path: &str, dest_dir: &Path) -> Result<Vec<ArchiveDatabase>, String> {
+    let connection = Connection::open_with_flags(archive_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| format!("Failed to open SQLite archive: {}", e))?;
+    let mut statement = connection
+        .prepare("SELECT name, sz, CAST(data AS BLOB) FROM sqlar WHERE data IS NOT NULL ORDER BY name")
+        .map_err(|e| format!("Not a SQLite archive: {}", e))?;
+    let entries = statement
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?, row.get::<_, Vec<u8>>(2)?)))
+        .map_err(|e| format!("Failed to read SQLite archive: {}", e))?;
+
+    let mut databases = Vec::new();
+    for entry in entries {
+        let (entry_name, size, data) = entry.map_err(|e| format!("Failed to read SQLite archive entry: {}", e))?;
+        if !is_candidate_entry(&entry_name) {
+            continue;
+        }
+
+        let target = extraction_path(dest_dir, archive_path, &entry_name)?;
+        let extracted = if size == data.len() as i64 {
+            extract_if_sqlite(data.as_slice(), &target)?
+        } else {
+            extract_if_sqlite(ZlibDecoder::new(data.as_slice()), &target)?
+        };
+        if let Some(size) = extracted {
+            databases.push(ArchiveDatabase {
+                entry_name,
+                path: target.to_string_lossy().to_string(),
+                size,
+            });
+        }
+    }
+    Ok(databases)
+}
+
+pub fn extract_archive_databases(archive_path: &str) -> Result<Vec<ArchiveDatabase>, String> {
+    let kind = ArchiveKind::from_path(archive_path)
+        .ok_or_else(|| format!("'{}' is not a supported archive", archive_path))?;
+    let dest_dir = ensure_temp_dir().map_err(|e| format!("Failed to prepare temp directory: {}", e))?;
+    match kind {
+        ArchiveKind::Sqlar => extract_sqlar_databases(archive_path, &dest_dir),
+        ArchiveKind::Zip => extract_zip_databases(archive_path, &dest_dir),
+    }
+}
+
+/// Path to open for `file_path`: archives holding exactly one database resolve to its
+/// extracted copy. A .sqlar without databases inside is opened as the SQLite file it is.
+pub fn resolve_archive_database(file_path: &str) -> Result<String, String> {
+    let Some(kind) = ArchiveKind::from_path(file_path) else {
+        return Ok(file_path.to_string());
+    };
+
+    let databases = extract_archive_databases(file_path)?;
+    match (databases.as_slice(), kind) {
+        ([database], _) => {
+            log::info!("📦 Opening '{}' from archive {}", database.entry_name, file_path);
+            Ok(database.path.clone())
+        }
+        ([], ArchiveKind::Sqlar) => Ok(file_path.to_string()),
+        ([], ArchiveKind::Zip) => Err(format!("No SQLite databases found in '{}'", file_path)),
+        (databases, _) => Err(format!(
+            "'{}' contains {} databases ({}); extract the archive and choose one",
+            file_path,
+            databases.len(),
+            databases
+                .iter()
+                .map(|database| database.entry_name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// Extract every SQLite database from a .sqlar, .zip, .apk or .ipa file to the temp directory.
+#[tauri::command]
+pub async fn db_extract_archive_databases(archive_path: String) -> Result<DbResponse<Vec<ArchiveDatabase>>, String> {
+    log::info!("📦 Extracting databases from archive: {}", archive_path);
+
+    match extract_archive_databases(&archive_path) {
+        Ok(databases) => {
+            log::info!("✅ Found {} databases in {}", databases.len(), archive_path);
+            Ok(DbResponse {
+                success: true,
+                data: Some(databases),
+                error: None,
+            })
+        }
+        Err(e) => {
+            log::error!("❌ Failed to extract archive databases: {}", e);
+            Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    use zip::write::SimpleFileOptions;
+
+    fn sqlite_bytes(temp_dir: &TempDir, name: &str) -> Vec<u8> {
+        let db_path = temp_dir.path().join(name);
+        let conn = Connection::open(&db_path).unwrap();
+        conn.execute_batch("CREATE TABLE items (id INTEGER PRIMARY KEY); INSERT INTO items VALUES (1);")
+            .unwrap();
+        drop(conn);
+        fs::read(db_path).unwrap()
+    }
+
+    #[test]
+    fn test_archive_kind_from_path() {
+        assert_eq!(ArchiveKind::from_path("/tmp/App.IPA"), Some(ArchiveKind::Zip));
+        assert_eq!(ArchiveKind::from_path("backup.sqlar"), Some(ArchiveKind::Sqlar));
+        assert_eq!(ArchiveKind::from_path("app.db"), None);
+    }
+
+    #[test]
+    fn test_extract_zip_databases() {
+        let temp_dir = TempDir::new().unwrap();
+        let database = sqlite_bytes(&temp_dir, "source.db");
+        let archive_path = temp_dir.path().join("app.apk");
+
+        let mut writer = zip::ZipWriter::new(fs::File::create(&archive_path).unwrap());
+        let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        writer.start_file("assets/databases/seed.db", options).unwrap();
+        writer.write_all(&database).unwrap();
+        writer.start_file("assets/notes.db", options).unwrap();
+        writer.write_all(b"not a database").unwrap();
+        writer.start_file("res/raw/config.json", options).unwrap();
+        writer.write_all(b"{}").unwrap();
+        writer.finish().unwrap();
+
+        let dest_dir = temp_dir.path().join("extracted");
+        let archive_path = archive_path.to_string_lossy().to_string();
+        let databases = extract_zip_databases(&archive_path, &dest_dir).unwrap();
+        assert_eq!(databases.len(), 1);
+        assert_eq!(databases[0].entry_name, "assets/databases/seed.db");
+        assert_eq!(databases[0].size, database.len() as u64);
+        assert!(databases[0].path.ends_with("seed.db"));
+        assert_eq!(fs::read(&databases[0].path).unwrap(), database);
+    }
+
+    #[test]
+    fn test_extract_sqlar_databases() {
+        let temp_dir = TempDir::new().unwrap();
+        let database = sqlite_bytes(&temp_dir, "source.db");
+        let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&database).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let archive_path = temp_dir.path().join("backup.sqlar");
+        let conn = Connection::open(&archive_path).unwrap();
+        conn.execute_batch("CREATE TABLE sqlar (name TEXT PRIMARY KEY, mode INT, mtime INT, sz INT, data BLOB);")
+            .unwrap();
+        conn.execute(
+            "INSERT INTO sqlar VALUES ('data/app.sqlite', 420, 0, ?1, ?2), ('readme.txt', 420, 0, 5, 'hello')",
+            rusqlite::params![database.len() as i64, compressed],
+        )
+        .unwrap();
+        drop(conn);
+
+        let dest_dir = temp_dir.path().join("extracted");
+        let archive_path = archive_path.to_string_lossy().to_string();
+        let databases = extract_sqlar_databases(&archive_path, &dest_dir).unwrap();
+        assert_eq!(databases.len(), 1);
+        assert_eq!(databases[0].entry_name, "data/app.sqlite");
+        assert_eq!(fs::read(&databases[0].path).unwrap(), database);
+    }
+}
@@ -0,0 +1,507 @@
+// Bulk update
+// Applies column assignments to every row matching a structured filter, with a dry-run preview
+
+use crate::commands::database::change_history::{
+    create_change_event, extract_context_from_path, record_change_with_safety, OperationType,
+};
+use crate::commands::database::change_tracking::create_field_changes_optimized;
+use crate::commands::database::commands::bind_json_values;
+use crate::commands::database::connection_access::get_current_pool;
+use crate::commands::database::export::read_table_columns;
+use crate::commands::database::helpers::ensure_database_file_permissions;
+use crate::commands::database::helpers::quote_ident;
+use crate::commands::database::helpers::decode_row_to_json;
+use crate::commands::database::types::*;
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqlitePool;
+use std::collections::HashMap;
+use tauri::State;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FilterOperator {
+    Eq,
+    NotEq,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+    Like,
+    NotLike,
+    In,
+    NotIn,
+    IsNull,
+    IsNotNull,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterCondition {
+    pub column: String,
+    pub operator: FilterOperator,
+    #[serde(default)]
+    pub value: Option<serde_json::Value>,
+    #[serde(default)]
+    pub values: Option<Vec<serde_json::Value>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterCombinator {
+    #[default]
+    And,
+    Or,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RowFilter {
+    pub conditions: Vec<FilterCondition>,
+    #[serde(default)]
+    pub combinator: FilterCombinator,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkUpdateResult {
+    pub matched_rows: u64,
+    pub updated_rows: u64,
+    pub dry_run: bool,
+    pub sql: String,
+}
+
+/// Compile a structured filter into a WHERE clause and its bound parameters.
+/// Column names are checked against the table so user input never reaches the SQL text.
+pub fn build_where_clause(
+    filter: &RowFilter,
+    table_columns: &[String],
+) -> Result<(String, Vec<serde_json::Value>), String> {
+    if filter.conditions.is_empty() {
+        return Err("At least one filter condition is required".to_string());
+    }
+
+    let mut clauses = Vec::with_capacity(filter.conditions.len());
+    let mut params = Vec::new();
+
+    for condition in &filter.conditions {
+        if !table_columns.contains(&condition.column) {
+            return Err(format!("Unknown column '{}'", condition.column));
+        }
+        let column = quote_ident(&condition.column);
+
+        let single_value = || {
+            condition
+                .value
+                .clone()
+                .ok_or_else(|| format!("Condition on '{}' needs a value", condition.column))
+        };
+
+        let clause = match condition.operator {
+            FilterOperator::IsNull => format!("{} IS NULL", column),
+            FilterOperator::IsNotNull => format!("{} IS NOT NULL", column),
+            FilterOperator::In | FilterOperator::NotIn => {
+                let values = condition
+                    .values
+                    .as_ref()
+                    .filter(|values| !values.is_empty())
+                    .ok_or_else(|| format!("Condition on '{}' needs a list of values", condition.column))?;
+                params.extend(values.iter().cloned());
+                let keyword = if condition.operator == FilterOperator::In {
+                    "IN"
+                } else {
+                    "NOT IN"
+                };
+                format!("{} {} ({})", column, keyword, vec!["?"; values.len()].join(", "))
+            }
+            operator => {
+                params.push(single_value()?);
+                let sql_operator = match operator {
+                    FilterOperator::Eq => "=",
+                    FilterOperator::NotEq => "!=",
+                    FilterOperator::Lt => "<",
+                    FilterOperator::Lte => "<=",
+                    FilterOperator::Gt => ">",
+                    FilterOperator::Gte => ">=",
+                    FilterOperator::Like => "LIKE",
+                    _ => "NOT LIKE",
+                };
+                format!("{} {} ?", column, sql_operator)
+            }
+        };
+        clauses.push(clause);
+    }
+
+    let joiner = match filter.combinator {
+        FilterCombinator::And => " AND ",
+        FilterCombinator::Or => " OR ",
+    };
+    Ok((clauses.join(joiner), params))
+}
+
+fn build_set_clause(
+    assignments: &HashMap<String, serde_json::Value>,
+    table_columns: &[String],
+) -> Result<(String, Vec<String>, Vec<serde_json::Value>), String> {
+    if assignments.is_empty() {
+        return Err("At least one column assignment is required".to_string());
+    }
+
+    let mut columns: Vec<String> = assignments.keys().cloned().collect();
+    columns.sort();
+
+    for column in &columns {
+        if !table_columns.contains(column) {
+            return Err(format!("Unknown column '{}'", column));
+        }
+    }
+
+    let set_clause = columns
+        .iter()
+        .map(|column| format!("{} = ?", quote_ident(column)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let values = columns.iter().map(|column| assignments[column].clone()).collect();
+    Ok((set_clause, columns, values))
+}
+
+/// Old value per assigned column, kept only when every matched row shared it.
+fn uniform_old_values(
+    rows: &[HashMap<String, serde_json::Value>],
+    columns: &[String],
+) -> HashMap<String, serde_json::Value> {
+    columns
+        .iter()
+        .filter_map(|column| {
+            let first = rows.first()?.get(column)?;
+            rows.iter()
+                .all(|row| row.get(column) == Some(first))
+                .then(|| (column.clone(), first.clone()))
+        })
+        .collect()
+}
+
+pub(crate) struct BulkUpdateOutcome {
+    pub result: BulkUpdateResult,
+    pub old_values: HashMap<String, serde_json::Value>,
+}
+
+pub(crate) async fn run_bulk_update(
+    pool: &SqlitePool,
+    table_name: &str,
+    assignments: &HashMap<String, serde_json::Value>,
+    filter: &RowFilter,
+    dry_run: bool,
+) -> Result<BulkUpdateOutcome, String> {
+    let table_columns = read_table_columns(pool, table_name).await?;
+    let (where_clause, where_params) = build_where_clause(filter, &table_columns)?;
+    let (set_clause, assigned_columns, set_values) = build_set_clause(assignments, &table_columns)?;
+
+    let table = quote_ident(table_name);
+    let sql = format!("UPDATE {} SET {} WHERE {}", table, set_clause, where_clause);
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+    let select_columns = assigned_columns
+        .iter()
+        .map(|column| quote_ident(column))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let select = format!("SELECT {} FROM {} WHERE {}", select_columns, table, where_clause);
+    let matched = bind_json_values(sqlx::query(&select), &where_params)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| format!("Error evaluating filter: {}", e))?;
+    let matched_rows: Vec<HashMap<String, serde_json::Value>> =
+        matched.iter().map(decode_row_to_json).collect();
+
+    let mut result = BulkUpdateResult {
+        matched_rows: matched_rows.len() as u64,
+        updated_rows: 0,
+        dry_run,
+        sql: sql.clone(),
+    };
+
+    if matched_rows.is_empty() {
+        // Nothing written; dropping the transaction rolls it back
+        return Ok(BulkUpdateOutcome {
+            result,
+            old_values: HashMap::new(),
+        });
+    }
+
+    let params: Vec<serde_json::Value> = set_values.into_iter().chain(where_params).collect();
+    let update = bind_json_values(sqlx::query(&sql), &params)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Error updating rows: {}", e))?;
+
+    if dry_run {
+        // The update really ran so constraint and trigger errors surface, but it is never kept
+        tx.rollback()
+            .await
+            .map_err(|e| format!("Failed to roll back dry run: {}", e))?;
+        result.matched_rows = update.rows_affected();
+        return Ok(BulkUpdateOutcome {
+            result,
+            old_values: HashMap::new(),
+        });
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit bulk update: {}", e))?;
+
+    result.updated_rows = update.rows_affected();
+    Ok(BulkUpdateOutcome {
+        result,
+        old_values: uniform_old_values(&matched_rows, &assigned_columns),
+    })
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn db_bulk_update(
+    state: State<'_, DbPool>,
+    db_cache: State<'_, DbConnectionCache>,
+    change_history: State<'_, super::change_history::ChangeHistoryManager>,
+    table_name: String,
+    assignments: HashMap<String, serde_json::Value>,
+    filter: RowFilter,
+    dry_run: Option<bool>,
+    current_db_path: Option<String>,
+    // Context information for change tracking
+    device_id: Option<String>,
+    device_name: Option<String>,
+    device_type: Option<String>,
+    package_name: Option<String>,
+    app_name: Option<String>,
+) -> Result<DbResponse<BulkUpdateResult>, String> {
+    let dry_run = dry_run.unwrap_or(false);
+
+    let db_path = match current_db_path.clone() {
+        Some(path) => path,
+        None => {
+            log::error!("❌ BULK UPDATE requires a specific database path");
+            return Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some("BULK UPDATE requires a specific database path - no database selected".to_string()),
+            });
+        }
+    };
+    log::info!(
+        "📝 BULK UPDATE{} for table '{}' on database: {}",
+        if dry_run { " (dry run)" } else { "" },
+        table_name,
+        db_path
+    );
+
+    let pool = match get_current_pool(&state, &db_cache, current_db_path).await {
+        Ok(pool) => pool,
+        Err(e) => {
+            log::error!("❌ Failed to get connection for BULK UPDATE: {}", e);
+            return Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(format!("Database connection error: {}", e)),
+            });
+        }
+    };
+
+    if !dry_run {
+        if let Err(permission_error) = ensure_database_file_permissions(&db_path) {
+            log::error!("❌ Failed to ensure database permissions: {}", permission_error);
+            return Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(format!("Database permission error: {}", permission_error)),
+            });
+        }
+    }
+
+    match run_bulk_update(&pool, &table_name, &assignments, &filter, dry_run).await {
+        Ok(outcome) => {
+            let result = outcome.result;
+            log::info!(
+                "✅ BULK UPDATE on '{}': {} rows matched, {} updated",
+                table_name,
+                result.matched_rows,
+                result.updated_rows
+            );
+
+            if result.updated_rows > 0 {
+                let user_context = extract_context_from_path(
+                    &db_path,
+                    device_id,
+                    device_name,
+                    device_type,
+                    package_name,
+                    app_name,
+                );
+                let field_changes = create_field_changes_optimized(
+                    &OperationType::Update,
+                    &outcome.old_values,
+                    &assignments,
+                );
+
+                match create_change_event(
+                    &db_path,
+                    &table_name,
+                    OperationType::BulkUpdate {
+                        count: result.updated_rows as usize,
+                    },
+                    user_context,
+                    field_changes,
+                    None, // No specific row identifier for bulk operations
+                    Some(result.sql.clone()),
+                ) {
+                    Ok(change_event) => {
+                        let _ = record_change_with_safety(&change_history, change_event).await;
+                    }
+                    Err(e) => {
+                        log::warn!("⚠️ Failed to create change event for BULK UPDATE (non-fatal): {}", e);
+                    }
+                }
+            }
+
+            Ok(DbResponse {
+                success: true,
+                data: Some(result),
+                error: None,
+            })
+        }
+        Err(e) => {
+            log::error!("❌ BULK UPDATE failed on database '{}': {}", db_path, e);
+            Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::database::tests::create_test_database;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    async fn test_pool(temp_dir: &TempDir) -> SqlitePool {
+        let db_path = temp_dir.path().join("bulk.db");
+        create_test_database(db_path.to_str().unwrap()).unwrap();
+        SqlitePool::connect(&format!("sqlite:{}", db_path.display()))
+            .await
+            .unwrap()
+    }
+
+    fn condition(column: &str, operator: FilterOperator, value: serde_json::Value) -> FilterCondition {
+        FilterCondition {
+            column: column.to_string(),
+            operator,
+            value: Some(value),
+            values: None,
+        }
+    }
+
+    #[test]
+    fn test_build_where_clause_binds_values() {
+        let columns = vec!["age".to_string(), "name".to_string()];
+        let filter = RowFilter {
+            conditions: vec![
+                condition("age", FilterOperator::Gte, json!(30)),
+                FilterCondition {
+                    column: "name".to_string(),
+                    operator: FilterOperator::In,
+                    value: None,
+                    values: Some(vec![json!("a"), json!("b")]),
+                },
+            ],
+            combinator: FilterCombinator::Or,
+        };
+
+        let (clause, params) = build_where_clause(&filter, &columns).unwrap();
+        assert_eq!(clause, "\"age\" >= ? OR \"name\" IN (?, ?)");
+        assert_eq!(params, vec![json!(30), json!("a"), json!("b")]);
+    }
+
+    #[test]
+    fn test_build_where_clause_rejects_unknown_columns() {
+        let filter = RowFilter {
+            conditions: vec![condition("age; DROP TABLE users", FilterOperator::Eq, json!(1))],
+            combinator: FilterCombinator::And,
+        };
+        assert!(build_where_clause(&filter, &["age".to_string()]).is_err());
+
+        let empty = RowFilter {
+            conditions: vec![],
+            combinator: FilterCombinator::And,
+        };
+        assert!(build_where_clause(&empty, &["age".to_string()]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_bulk_update_dry_run_does_not_write() {
+        let temp_dir = TempDir::new().unwrap();
+        let pool = test_pool(&temp_dir).await;
+        let filter = RowFilter {
+            conditions: vec![condition("active", FilterOperator::Eq, json!(1))],
+            combinator: FilterCombinator::And,
+        };
+        let assignments = HashMap::from([("age".to_string(), json!(99))]);
+
+        let outcome = run_bulk_update(&pool, "users", &assignments, &filter, true)
+            .await
+            .unwrap();
+        assert_eq!(outcome.result.matched_rows, 2);
+        assert_eq!(outcome.result.updated_rows, 0);
+
+        let updated: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users WHERE age = 99")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(updated, 0);
+    }
+
+    #[tokio::test]
+    async fn test_bulk_update_applies_assignments() {
+        let temp_dir = TempDir::new().unwrap();
+        let pool = test_pool(&temp_dir).await;
+        let filter = RowFilter {
+            conditions: vec![condition("age", FilterOperator::Gte, json!(30))],
+            combinator: FilterCombinator::And,
+        };
+        let assignments = HashMap::from([
+            ("age".to_string(), json!(40)),
+            ("name".to_string(), json!("Renamed")),
+        ]);
+
+        let outcome = run_bulk_update(&pool, "users", &assignments, &filter, false)
+            .await
+            .unwrap();
+        assert_eq!(outcome.result.updated_rows, 2);
+        // The matched rows had different ages and names, so no single old value exists
+        assert!(outcome.old_values.is_empty());
+
+        let renamed: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users WHERE name = 'Renamed' AND age = 40")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(renamed, 2);
+
+        // Now both rows share the same values
+        let assignments = HashMap::from([("age".to_string(), json!(41))]);
+        let filter = RowFilter {
+            conditions: vec![condition("name", FilterOperator::Eq, json!("Renamed"))],
+            combinator: FilterCombinator::And,
+        };
+        let outcome = run_bulk_update(&pool, "users", &assignments, &filter, false)
+            .await
+            .unwrap();
+        assert_eq!(outcome.old_values.get("age"), Some(&json!(40)));
+    }
+}
@@ -190,19 +190,19 @@ pub async fn clear_context_changes(
     context_key: String,
     history_manager: State<'_, ChangeHistoryManager>,
 ) -> Result<DbResponse<bool>, String> {
-    println!("🧹 [Backend] clear_context_changes called with key: {}", context_key);
+    log::info!("🧹 [Backend] clear_context_changes called with key: {}", context_key);
     
     // Check how many changes exist before clearing
     let changes_before = history_manager.get_changes(&context_key).await;
-    println!("🧹 [Backend] Changes count before clear: {}", changes_before.len());
+    log::info!("🧹 [Backend] Changes count before clear: {}", changes_before.len());
     
     history_manager.clear_changes(&context_key).await;
     
     // Check after clearing
     let changes_after = history_manager.get_changes(&context_key).await;
-    println!("🧹 [Backend] Changes count after clear: {}", changes_after.len());
+    log::info!("🧹 [Backend] Changes count after clear: {}", changes_after.len());
     
-    println!("🧹 [Backend] clear_context_changes completed successfully");
+    log::info!("🧹 [Backend] clear_context_changes completed successfully");
     
     Ok(DbResponse {
         success: true,
@@ -216,19 +216,52 @@ pub async fn clear_context_changes(
 pub async fn clear_all_change_history(
     history_manager: State<'_, ChangeHistoryManager>,
 ) -> Result<DbResponse<bool>, String> {
-    println!("💥 [Backend] clear_all_change_history called - NUCLEAR OPTION");
+    log::info!("💥 [Backend] clear_all_change_history called - NUCLEAR OPTION");
     
     // Get diagnostics before clearing
     let contexts_before = history_manager.get_active_contexts().await;
-    println!("💥 [Backend] Active contexts before clear: {:?}", contexts_before);
+    log::info!("💥 [Backend] Active contexts before clear: {:?}", contexts_before);
     
     history_manager.clear_all_changes().await;
     
     // Get diagnostics after clearing
     let contexts_after = history_manager.get_active_contexts().await;
-    println!("💥 [Backend] Active contexts after clear: {:?}", contexts_after);
+    log::info!("💥 [Backend] Active contexts after clear: {:?}", contexts_after);
     
-    println!("💥 [Backend] clear_all_change_history completed successfully");
+    log::info!("💥 [Backend] clear_all_change_history completed successfully");
+    
+    Ok(DbResponse {
+        success: true,
+        data: Some(true),
+        error: None,
+    })
+}
+
+// Push this context's history to the calling window whenever any window changes it
+#[command]
+pub async fn subscribe_change_history_context(
+    window: tauri::Window,
+    context_key: String,
+    history_manager: State<'_, ChangeHistoryManager>,
+) -> Result<DbResponse<Vec<ChangeEvent>>, String> {
+    log::info!("🔔 [Backend] Window {} subscribed to context: {}", window.label(), context_key);
+    history_manager.subscribe_context(window.label(), &context_key);
+    
+    // Hand back the current history so the panel starts from the same state as other windows
+    Ok(DbResponse {
+        success: true,
+        data: Some(history_manager.get_changes(&context_key).await),
+        error: None,
+    })
+}
+
+#[command]
+pub async fn unsubscribe_change_history_context(
+    window: tauri::Window,
+    context_key: String,
+    history_manager: State<'_, ChangeHistoryManager>,
+) -> Result<DbResponse<bool>, String> {
+    history_manager.unsubscribe_context(window.label(), &context_key);
     
     Ok(DbResponse {
         success: true,
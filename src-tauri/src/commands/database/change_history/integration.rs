@@ -151,6 +151,7 @@ pub async fn record_change_with_safety(
 ) -> Result<(), String> {
     let manager = change_manager.inner();
     
+    crate::commands::journal::record_unpushed_edit(&change_event);
     match manager.record_change(change_event).await {
         Ok(_) => {
             log::debug!("📝 Change recorded successfully");
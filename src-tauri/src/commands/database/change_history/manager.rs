@@ -2,12 +2,16 @@
 // Memory-bounded change history manager with all safety checks
 // Following IMPLEMENTATION_ROADMAP.md safety-first approach
 
-use std::collections::{HashMap, VecDeque};
-use std::sync::{Arc, atomic::{AtomicUsize, Ordering}};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex, OnceLock, atomic::{AtomicUsize, Ordering}};
 use chrono::{DateTime, Utc};
+use tauri::Emitter;
 use tokio::sync::RwLock;
 
-use crate::commands::database::change_history::types::{ChangeEvent, ContextSummary};
+use crate::commands::database::change_history::types::{
+    ChangeEvent, ChangeHistoryContextSync, ChangeHistoryUpdate, ChangeHistoryUpdateKind, ContextSummary,
+    CHANGE_HISTORY_CONTEXT_SYNC_EVENT, CHANGE_HISTORY_UPDATED_EVENT,
+};
 
 // SAFETY-FIRST: Memory-bounded change manager (Critical Issue #1 fix)
 pub struct ChangeHistoryManager {
@@ -15,6 +19,9 @@ pub struct ChangeHistoryManager {
     max_changes_per_context: usize,  // HARD LIMIT: 100
     max_total_contexts: usize,       // HARD LIMIT: 50  
     memory_usage_mb: Arc<AtomicUsize>, // Track memory usage
+    // Window labels subscribed to each context key, for cross-window sync
+    subscriptions: Arc<Mutex<HashMap<String, HashSet<String>>>>,
+    app_handle: Arc<OnceLock<tauri::AppHandle>>,
 }
 
 impl ChangeHistoryManager {
@@ -24,6 +31,8 @@ impl ChangeHistoryManager {
             max_changes_per_context: 100,
             max_total_contexts: 50,
             memory_usage_mb: Arc::new(AtomicUsize::new(0)),
+            subscriptions: Arc::new(Mutex::new(HashMap::new())),
+            app_handle: Arc::new(OnceLock::new()),
         }
     }
     
@@ -35,6 +44,8 @@ impl ChangeHistoryManager {
             max_changes_per_context,
             max_total_contexts,
             memory_usage_mb: Arc::new(AtomicUsize::new(0)),
+            subscriptions: Arc::new(Mutex::new(HashMap::new())),
+            app_handle: Arc::new(OnceLock::new()),
         }
     }
     
@@ -64,6 +75,7 @@ impl ChangeHistoryManager {
             }
         }
         
+        let context_key = change.context_key.clone();
         context_changes.push_back(change);
         
         // Update memory usage estimate (rough calculation)
@@ -72,7 +84,9 @@ impl ChangeHistoryManager {
             changes_map.len() * self.max_changes_per_context * estimated_size,
             Ordering::Relaxed
         );
+        drop(changes_map);
         
+        self.notify(Some(&context_key), ChangeHistoryUpdateKind::Recorded).await;
         Ok(())
     }
     
@@ -109,7 +123,7 @@ impl ChangeHistoryManager {
     
     // Clear all changes for a specific context
     pub async fn clear_changes(&self, context_key: &str) {
-        println!("🧹 [Manager] clear_changes called for context: {}", context_key);
+        log::info!("🧹 [Manager] clear_changes called for context: {}", context_key);
         let mut changes_map = self.changes.write().await;
         
         let had_changes = changes_map.contains_key(context_key);
@@ -119,29 +133,119 @@ impl ChangeHistoryManager {
             0
         };
         
-        println!("🧹 [Manager] Context exists: {}, Changes count: {}", had_changes, changes_count);
+        log::info!("🧹 [Manager] Context exists: {}, Changes count: {}", had_changes, changes_count);
         
         changes_map.remove(context_key);
         
-        println!("🧹 [Manager] Context cleared. Remaining contexts: {}", changes_map.len());
+        log::info!("🧹 [Manager] Context cleared. Remaining contexts: {}", changes_map.len());
+        drop(changes_map);
+        
+        self.notify(Some(context_key), ChangeHistoryUpdateKind::Cleared).await;
     }
     
     // Clear ALL changes from memory - nuclear option
     pub async fn clear_all_changes(&self) {
-        println!("💥 [Manager] clear_all_changes called - clearing entire change history");
+        log::info!("💥 [Manager] clear_all_changes called - clearing entire change history");
         let mut changes_map = self.changes.write().await;
         
         let total_contexts = changes_map.len();
         let total_changes: usize = changes_map.values().map(|c| c.len()).sum();
         
-        println!("💥 [Manager] Before clear - Contexts: {}, Total changes: {}", total_contexts, total_changes);
+        log::info!("💥 [Manager] Before clear - Contexts: {}, Total changes: {}", total_contexts, total_changes);
         
         changes_map.clear();
         
         // Reset memory usage counter
         self.memory_usage_mb.store(0, Ordering::Relaxed);
         
-        println!("💥 [Manager] After clear - Contexts: {}, Total changes: 0", changes_map.len());
+        log::info!("💥 [Manager] After clear - Contexts: {}, Total changes: 0", changes_map.len());
+        drop(changes_map);
+        
+        self.notify(None, ChangeHistoryUpdateKind::ClearedAll).await;
+    }
+    
+    // Events are only emitted once the running app has been attached
+    pub fn attach_app_handle(&self, app_handle: tauri::AppHandle) {
+        let _ = self.app_handle.set(app_handle);
+    }
+    
+    // Keep `window_label`'s history panel for `context_key` in sync with edits made elsewhere
+    pub fn subscribe_context(&self, window_label: &str, context_key: &str) {
+        let mut subscriptions = self.subscriptions.lock().unwrap_or_else(|e| e.into_inner());
+        subscriptions
+            .entry(context_key.to_string())
+            .or_default()
+            .insert(window_label.to_string());
+    }
+    
+    pub fn unsubscribe_context(&self, window_label: &str, context_key: &str) {
+        let mut subscriptions = self.subscriptions.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(windows) = subscriptions.get_mut(context_key) {
+            windows.remove(window_label);
+            if windows.is_empty() {
+                subscriptions.remove(context_key);
+            }
+        }
+    }
+    
+    // Drop every subscription of a closed window
+    pub fn unsubscribe_window(&self, window_label: &str) {
+        let mut subscriptions = self.subscriptions.lock().unwrap_or_else(|e| e.into_inner());
+        subscriptions.retain(|_, windows| {
+            windows.remove(window_label);
+            !windows.is_empty()
+        });
+    }
+    
+    pub fn subscribers(&self, context_key: &str) -> Vec<String> {
+        let subscriptions = self.subscriptions.lock().unwrap_or_else(|e| e.into_inner());
+        let mut windows: Vec<String> = subscriptions
+            .get(context_key)
+            .map(|windows| windows.iter().cloned().collect())
+            .unwrap_or_default();
+        windows.sort();
+        windows
+    }
+    
+    // Tell every window something changed, and push the fresh history to subscribed windows
+    async fn notify(&self, context_key: Option<&str>, kind: ChangeHistoryUpdateKind) {
+        let Some(app_handle) = self.app_handle.get() else {
+            return;
+        };
+        
+        let update = ChangeHistoryUpdate {
+            context_key: context_key.map(str::to_string),
+            kind,
+        };
+        if let Err(e) = app_handle.emit(CHANGE_HISTORY_UPDATED_EVENT, update) {
+            log::warn!("⚠️ Failed to emit change history update: {}", e);
+        }
+        
+        let targets: Vec<(String, Vec<String>)> = match context_key {
+            Some(context_key) => vec![(context_key.to_string(), self.subscribers(context_key))],
+            None => {
+                let subscriptions = self.subscriptions.lock().unwrap_or_else(|e| e.into_inner());
+                subscriptions
+                    .iter()
+                    .map(|(context_key, windows)| (context_key.clone(), windows.iter().cloned().collect()))
+                    .collect()
+            }
+        };
+        for (context_key, windows) in targets {
+            if windows.is_empty() {
+                continue;
+            }
+            let sync = ChangeHistoryContextSync {
+                changes: self.get_changes(&context_key).await,
+                context_key,
+                kind,
+            };
+            for window in windows {
+                if let Err(e) = app_handle.emit_to(window.as_str(), CHANGE_HISTORY_CONTEXT_SYNC_EVENT, sync.clone()) {
+                    log::warn!("⚠️ Failed to sync change history to window {}: {}", window, e);
+                }
+            }
+        }
     }
     
     // Get all active contexts (for debugging/admin purposes)
@@ -210,6 +314,11 @@ impl ChangeHistoryManager {
         self.memory_usage_mb.load(Ordering::Relaxed)
     }
     
+    // Contexts kept before the least recently used one is evicted
+    pub fn max_total_contexts(&self) -> usize {
+        self.max_total_contexts
+    }
+    
     // Find LRU context for eviction
     fn find_least_recently_used_context(&self, changes_map: &HashMap<String, VecDeque<ChangeEvent>>) -> Option<String> {
         changes_map.iter()
@@ -236,6 +345,8 @@ impl Clone for ChangeHistoryManager {
             max_changes_per_context: self.max_changes_per_context,
             max_total_contexts: self.max_total_contexts,
             memory_usage_mb: Arc::clone(&self.memory_usage_mb),
+            subscriptions: Arc::clone(&self.subscriptions),
+            app_handle: Arc::clone(&self.app_handle),
         }
     }
 }
@@ -300,6 +411,25 @@ mod tests {
         assert!(contexts.len() <= 3, "Total context limit not enforced: got {} contexts", contexts.len());
     }
 
+    #[test]
+    fn test_context_subscriptions() {
+        let manager = ChangeHistoryManager::new();
+        manager.subscribe_context("main", "ctx_a");
+        manager.subscribe_context("history-1", "ctx_a");
+        manager.subscribe_context("history-1", "ctx_b");
+
+        // Clones share subscriptions, as every command sees the same managed state
+        let clone = manager.clone();
+        assert_eq!(clone.subscribers("ctx_a"), vec!["history-1".to_string(), "main".to_string()]);
+
+        manager.unsubscribe_context("main", "ctx_a");
+        assert_eq!(manager.subscribers("ctx_a"), vec!["history-1".to_string()]);
+
+        manager.unsubscribe_window("history-1");
+        assert!(manager.subscribers("ctx_a").is_empty());
+        assert!(manager.subscribers("ctx_b").is_empty());
+    }
+
     #[tokio::test]
     async fn test_changes_retrieval() {
         let manager = ChangeHistoryManager::new();
@@ -15,6 +15,11 @@ pub use types::{
     UserContext,
     ChangeMetadata,
     ContextSummary,
+    ChangeHistoryUpdate,
+    ChangeHistoryUpdateKind,
+    ChangeHistoryContextSync,
+    CHANGE_HISTORY_UPDATED_EVENT,
+    CHANGE_HISTORY_CONTEXT_SYNC_EVENT,
     generate_context_key,
     generate_custom_file_context_key,
     is_custom_file_context_key,
@@ -77,6 +77,34 @@ pub struct ContextSummary {
     pub last_change_time: Option<DateTime<Utc>>,
 }
 
+// Broadcast to every window whenever a context's history changes
+pub const CHANGE_HISTORY_UPDATED_EVENT: &str = "change-history-updated";
+// Sent only to windows subscribed to the context, carrying its full history
+pub const CHANGE_HISTORY_CONTEXT_SYNC_EVENT: &str = "change-history-context-sync";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeHistoryUpdateKind {
+    Recorded,
+    Cleared,
+    ClearedAll,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeHistoryUpdate {
+    pub context_key: Option<String>,          // None when every context was cleared
+    pub kind: ChangeHistoryUpdateKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeHistoryContextSync {
+    pub context_key: String,
+    pub kind: ChangeHistoryUpdateKind,
+    pub changes: Vec<ChangeEvent>,
+}
+
 // SAFE: Context key generation with full collision detection (Issue #5 fix)
 pub fn generate_context_key(device_id: &str, package_name: &str, database_filename: &str) -> String {
     use sha2::{Sha256, Digest};
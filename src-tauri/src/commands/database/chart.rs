@@ -0,0 +1,440 @@
+// Chart data aggregation
+// Runs a read-only query and reduces its rows to aggregated series that can be plotted directly
+
+use crate::commands::database::compare::{is_read_only_query, run_compared_query, ResultRow};
+use crate::commands::database::connection_access::get_current_pool;
+use crate::commands::database::types::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use tauri::State;
+
+const MAX_CHART_BINS: usize = 1000;
+const MAX_CHART_SERIES: usize = 50;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChartAggregation {
+    // "count", "sum", "avg", "min" or "max"
+    pub function: String,
+    // Not needed for "count", which then counts rows
+    pub column: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChartSpec {
+    pub x_column: String,
+    pub y: Vec<ChartAggregation>,
+    pub group_by: Option<String>,
+    // Split a numeric x axis into this many equal-width bins; distinct values are used otherwise
+    pub bins: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChartPoint {
+    pub x: serde_json::Value,
+    // Upper bound of the bin when the x axis is binned
+    pub x_end: Option<f64>,
+    // None when the series has no values at this x
+    pub y: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChartSeries {
+    pub name: String,
+    pub group: Option<String>,
+    pub aggregation: ChartAggregation,
+    pub points: Vec<ChartPoint>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChartData {
+    pub x_column: String,
+    // "numeric", "binned" or "category"
+    pub x_kind: String,
+    pub row_count: usize,
+    pub series: Vec<ChartSeries>,
+}
+
+#[derive(Debug, Default, Clone)]
+struct Accumulator {
+    rows: usize,
+    values: usize,
+    sum: f64,
+    min: Option<f64>,
+    max: Option<f64>,
+}
+
+impl Accumulator {
+    fn add(&mut self, value: Option<f64>) {
+        self.rows += 1;
+        if let Some(value) = value {
+            self.values += 1;
+            self.sum += value;
+            self.min = Some(self.min.map_or(value, |min| min.min(value)));
+            self.max = Some(self.max.map_or(value, |max| max.max(value)));
+        }
+    }
+
+    fn result(&self, function: &str) -> Option<f64> {
+        match function {
+            "count" => Some(self.rows as f64),
+            "sum" => (self.values > 0).then_some(self.sum),
+            "avg" => (self.values > 0).then(|| self.sum / self.values as f64),
+            "min" => self.min,
+            "max" => self.max,
+            _ => None,
+        }
+    }
+}
+
+fn numeric_value(value: &serde_json::Value) -> Option<f64> {
+    match value {
+        serde_json::Value::Number(number) => number.as_f64(),
+        // SQLite happily stores numbers as text
+        serde_json::Value::String(text) => text.trim().parse::<f64>().ok().filter(|value| value.is_finite()),
+        serde_json::Value::Bool(flag) => Some(if *flag { 1.0 } else { 0.0 }),
+        _ => None,
+    }
+}
+
+fn category_label(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(text) => text.clone(),
+        serde_json::Value::Null => "null".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn validate_chart_spec(spec: &ChartSpec) -> Result<(), String> {
+    if spec.x_column.trim().is_empty() {
+        return Err("Chart spec needs an x column".to_string());
+    }
+    if spec.y.is_empty() {
+        return Err("Chart spec needs at least one y aggregation".to_string());
+    }
+    for aggregation in &spec.y {
+        match aggregation.function.as_str() {
+            "count" => {}
+            "sum" | "avg" | "min" | "max" if aggregation.column.is_some() => {}
+            "sum" | "avg" | "min" | "max" => {
+                return Err(format!("Aggregation '{}' needs a column", aggregation.function));
+            }
+            other => return Err(format!("Unsupported aggregation: {}", other)),
+        }
+    }
+    if matches!(spec.bins, Some(0)) || spec.bins.unwrap_or(0) > MAX_CHART_BINS {
+        return Err(format!("bins must be between 1 and {}", MAX_CHART_BINS));
+    }
+    Ok(())
+}
+
+// Position on the x axis: a numeric value or bin index, or a category label
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum XKey {
+    Bin(usize),
+    Number(u64),
+    Category(String),
+}
+
+// Total order for finite floats so they can key a BTreeMap
+fn ordered_bits(value: f64) -> u64 {
+    let bits = value.to_bits();
+    if bits >> 63 == 1 {
+        !bits
+    } else {
+        bits | (1 << 63)
+    }
+}
+
+fn from_ordered_bits(bits: u64) -> f64 {
+    if bits >> 63 == 1 {
+        f64::from_bits(bits & !(1 << 63))
+    } else {
+        f64::from_bits(!bits)
+    }
+}
+
+/// Reduce result rows to one series per (group, aggregation), sharing the same x positions.
+pub fn aggregate_chart_rows(rows: &[ResultRow], spec: &ChartSpec) -> Result<ChartData, String> {
+    validate_chart_spec(spec)?;
+
+    let x_values: Vec<&serde_json::Value> = rows
+        .iter()
+        .map(|row| row.get(&spec.x_column).unwrap_or(&serde_json::Value::Null))
+        .collect();
+    let numeric_x: Option<Vec<Option<f64>>> = x_values
+        .iter()
+        .map(|value| match value {
+            serde_json::Value::Null => Some(None),
+            value => numeric_value(value).map(Some),
+        })
+        .collect();
+
+    let x_kind;
+    let mut bin_range = None;
+    let x_keys: Vec<Option<XKey>> = match (&numeric_x, spec.bins) {
+        (Some(numbers), Some(bins)) => {
+            x_kind = "binned";
+            let present = numbers.iter().flatten();
+            let min = present.clone().copied().fold(f64::INFINITY, f64::min);
+            let max = present.copied().fold(f64::NEG_INFINITY, f64::max);
+            let width = if max > min { (max - min) / bins as f64 } else { 1.0 };
+            bin_range = Some((min, width, bins));
+            numbers
+                .iter()
+                .map(|number| number.map(|value| XKey::Bin((((value - min) / width) as usize).min(bins - 1))))
+                .collect()
+        }
+        (Some(numbers), None) => {
+            x_kind = "numeric";
+            numbers.iter().map(|number| number.map(|value| XKey::Number(ordered_bits(value)))).collect()
+        }
+        (None, _) => {
+            x_kind = "category";
+            x_values.iter().map(|value| Some(XKey::Category(category_label(value)))).collect()
+        }
+    };
+
+    // accumulators[group][aggregation index][x]
+    let mut accumulators: BTreeMap<Option<String>, Vec<BTreeMap<XKey, Accumulator>>> = BTreeMap::new();
+    let mut all_x: BTreeSet<XKey> = BTreeSet::new();
+
+    for (row, x_key) in rows.iter().zip(x_keys) {
+        // Rows without an x value can't be placed on a numeric axis
+        let Some(x_key) = x_key else {
+            continue;
+        };
+        let group = spec
+            .group_by
+            .as_ref()
+            .map(|column| category_label(row.get(column).unwrap_or(&serde_json::Value::Null)));
+
+        if !accumulators.contains_key(&group) && accumulators.len() >= MAX_CHART_SERIES {
+            return Err(format!(
+                "More than {} groups in '{}'; narrow the query or pick another group-by column",
+                MAX_CHART_SERIES,
+                spec.group_by.as_deref().unwrap_or_default()
+            ));
+        }
+        let per_aggregation = accumulators
+            .entry(group)
+            .or_insert_with(|| vec![BTreeMap::new(); spec.y.len()]);
+
+        for (aggregation, per_x) in spec.y.iter().zip(per_aggregation.iter_mut()) {
+            let value = aggregation
+                .column
+                .as_ref()
+                .and_then(|column| row.get(column))
+                .and_then(numeric_value);
+            per_x.entry(x_key.clone()).or_default().add(value);
+        }
+        all_x.insert(x_key);
+    }
+
+    // Empty bins still get a point so the axis covers the whole range
+    if let Some((_, _, bins)) = bin_range {
+        if !all_x.is_empty() {
+            for bin in 0..bins {
+                all_x.insert(XKey::Bin(bin));
+            }
+        }
+    }
+
+    let points_for = |per_x: &BTreeMap<XKey, Accumulator>, function: &str| -> Vec<ChartPoint> {
+        all_x
+            .iter()
+            .map(|key| {
+                let (x, x_end) = match key {
+                    XKey::Bin(bin) => {
+                        let (min, width, _) = bin_range.unwrap_or((0.0, 1.0, 1));
+                        let start = min + *bin as f64 * width;
+                        (serde_json::json!(start), Some(start + width))
+                    }
+                    XKey::Number(bits) => (serde_json::json!(from_ordered_bits(*bits)), None),
+                    XKey::Category(label) => (serde_json::Value::String(label.clone()), None),
+                };
+                let y = per_x.get(key).and_then(|accumulator| accumulator.result(function));
+                ChartPoint { x, x_end, y }
+            })
+            .collect()
+    };
+
+    let mut series = Vec::new();
+    for (group, per_aggregation) in &accumulators {
+        for (aggregation, per_x) in spec.y.iter().zip(per_aggregation) {
+            let base_name = match &aggregation.column {
+                Some(column) => format!("{}({})", aggregation.function, column),
+                None => format!("{}(*)", aggregation.function),
+            };
+            series.push(ChartSeries {
+                name: match group {
+                    Some(group) => format!("{} [{}]", base_name, group),
+                    None => base_name,
+                },
+                group: group.clone(),
+                aggregation: aggregation.clone(),
+                points: points_for(per_x, &aggregation.function),
+            });
+        }
+    }
+
+    Ok(ChartData {
+        x_column: spec.x_column.clone(),
+        x_kind: x_kind.to_string(),
+        row_count: rows.len(),
+        series,
+    })
+}
+
+/// Aggregate the rows of a read-only query into chart series, e.g. average value per day and type
+#[tauri::command]
+pub async fn db_aggregate_for_chart(
+    state: State<'_, DbPool>,
+    db_cache: State<'_, DbConnectionCache>,
+    query: String,
+    spec: ChartSpec,
+    current_db_path: Option<String>,
+) -> Result<DbResponse<ChartData>, String> {
+    log::info!("📈 Aggregating query results for chart on '{}'", spec.x_column);
+
+    let result = async {
+        if !is_read_only_query(&query) {
+            return Err("Only SELECT queries can be charted".to_string());
+        }
+        validate_chart_spec(&spec)?;
+        let db_path = current_db_path.clone().unwrap_or_default();
+        let pool = get_current_pool(&state, &db_cache, current_db_path).await?;
+        let result_set = run_compared_query(&pool, &db_path, &query).await?;
+        aggregate_chart_rows(&result_set.rows, &spec)
+    }
+    .await;
+
+    match result {
+        Ok(data) => {
+            log::info!("✅ Aggregated {} rows into {} series", data.row_count, data.series.len());
+            Ok(DbResponse {
+                success: true,
+                data: Some(data),
+                error: None,
+            })
+        }
+        Err(e) => {
+            log::error!("❌ Failed to aggregate chart data: {}", e);
+            Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn row(pairs: &[(&str, serde_json::Value)]) -> ResultRow {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.clone())).collect()
+    }
+
+    fn aggregation(function: &str, column: Option<&str>) -> ChartAggregation {
+        ChartAggregation {
+            function: function.to_string(),
+            column: column.map(str::to_string),
+        }
+    }
+
+    fn events() -> Vec<ResultRow> {
+        vec![
+            row(&[("day", json!("2024-05-02")), ("kind", json!("open")), ("ms", json!(30))]),
+            row(&[("day", json!("2024-05-01")), ("kind", json!("open")), ("ms", json!(10))]),
+            row(&[("day", json!("2024-05-01")), ("kind", json!("open")), ("ms", json!("20"))]),
+            row(&[("day", json!("2024-05-01")), ("kind", json!("close")), ("ms", json!(null))]),
+        ]
+    }
+
+    #[test]
+    fn test_category_axis_with_group_by() {
+        let spec = ChartSpec {
+            x_column: "day".to_string(),
+            y: vec![aggregation("count", None), aggregation("avg", Some("ms"))],
+            group_by: Some("kind".to_string()),
+            bins: None,
+        };
+
+        let data = aggregate_chart_rows(&events(), &spec).unwrap();
+
+        assert_eq!(data.x_kind, "category");
+        assert_eq!(data.series.len(), 4);
+        let open_avg = data.series.iter().find(|s| s.name == "avg(ms) [open]").unwrap();
+        assert_eq!(open_avg.points[0].x, json!("2024-05-01"));
+        assert_eq!(open_avg.points[0].y, Some(15.0));
+        assert_eq!(open_avg.points[1].y, Some(30.0));
+
+        let close_avg = data.series.iter().find(|s| s.name == "avg(ms) [close]").unwrap();
+        assert_eq!(close_avg.points[0].y, None);
+        assert_eq!(close_avg.points[1].y, None);
+        let close_count = data.series.iter().find(|s| s.name == "count(*) [close]").unwrap();
+        assert_eq!(close_count.points[0].y, Some(1.0));
+    }
+
+    #[test]
+    fn test_numeric_axis_is_binned() {
+        let rows: Vec<ResultRow> = [0, 1, 2, 9, 10].iter().map(|v| row(&[("v", json!(v))])).collect();
+        let spec = ChartSpec {
+            x_column: "v".to_string(),
+            y: vec![aggregation("count", None)],
+            group_by: None,
+            bins: Some(2),
+        };
+
+        let data = aggregate_chart_rows(&rows, &spec).unwrap();
+
+        assert_eq!(data.x_kind, "binned");
+        let points = &data.series[0].points;
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].x, json!(0.0));
+        assert_eq!(points[0].x_end, Some(5.0));
+        assert_eq!(points[0].y, Some(3.0));
+        assert_eq!(points[1].y, Some(2.0));
+    }
+
+    #[test]
+    fn test_numeric_axis_is_sorted_numerically() {
+        let rows: Vec<ResultRow> = [10, -2, 3].iter().map(|v| row(&[("v", json!(v)), ("w", json!(1))])).collect();
+        let spec = ChartSpec {
+            x_column: "v".to_string(),
+            y: vec![aggregation("sum", Some("w"))],
+            group_by: None,
+            bins: None,
+        };
+
+        let data = aggregate_chart_rows(&rows, &spec).unwrap();
+
+        let xs: Vec<_> = data.series[0].points.iter().map(|p| p.x.clone()).collect();
+        assert_eq!(xs, vec![json!(-2.0), json!(3.0), json!(10.0)]);
+    }
+
+    #[test]
+    fn test_invalid_specs_are_rejected() {
+        let mut spec = ChartSpec {
+            x_column: "day".to_string(),
+            y: vec![aggregation("median", Some("ms"))],
+            group_by: None,
+            bins: None,
+        };
+        assert!(aggregate_chart_rows(&[], &spec).is_err());
+
+        spec.y = vec![aggregation("sum", None)];
+        assert!(aggregate_chart_rows(&[], &spec).is_err());
+
+        spec.y = vec![aggregation("count", None)];
+        spec.bins = Some(0);
+        assert!(aggregate_chart_rows(&[], &spec).is_err());
+    }
+}
@@ -1,7 +1,12 @@
 // Database commands - enhanced with connection caching
+use crate::commands::common::{redact_path, redact_sql};
+use crate::commands::guardrails::{check_dangerous_operation, check_dangerous_sql, DangerousOperation};
 use crate::commands::database::types::*;
-use crate::commands::database::connection_access::get_current_pool;
-use crate::commands::database::helpers::ensure_database_file_permissions;
+use crate::commands::database::connection_access::{get_current_pool, get_read_pool};
+use crate::commands::database::helpers::{decode_row_to_json, ensure_database_file_permissions, TABLE_INFO_QUERY};
+use crate::commands::database::connection_manager::DatabaseConnectionManager;
+use crate::commands::database::query_lint::{controls_transaction, lint_query};
+use crate::commands::database::query_stats::{measure_scan_counters, read_query_plan, QueryStats};
 use crate::commands::database::change_history::{
     capture_old_values_for_update, extract_context_from_path,
     record_change_with_safety, create_change_event, OperationType
@@ -10,11 +15,54 @@ use crate::commands::database::change_tracking::{
     create_field_changes_optimized, extract_row_values
 };
 use serde_json;
-use sqlx::{Column, Row, TypeInfo, ValueRef};
+use sqlx::{Column, Execute, Row};
 use std::collections::HashMap;
-use tauri::State;
+use std::time::Instant;
+use tauri::{Emitter, State};
 
-fn bind_json_values<'q>(
+/// Emitted whenever cached connections are dropped, so open views reload instead of using a closed pool
+pub const DB_CACHE_INVALIDATED_EVENT: &str = "db-cache-invalidated";
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheInvalidated {
+    // None when every cached connection was dropped
+    pub path: Option<String>,
+    pub reason: String,
+}
+
+pub fn emit_cache_invalidated(app_handle: &tauri::AppHandle, path: Option<&str>, reason: &str) {
+    let payload = CacheInvalidated {
+        path: path.map(str::to_string),
+        reason: reason.to_string(),
+    };
+    if let Err(e) = app_handle.emit(DB_CACHE_INVALIDATED_EVENT, payload) {
+        log::warn!("⚠️ Failed to emit cache invalidation: {}", e);
+    }
+}
+
+/// Drop the cached connection and query results for `db_path` and tell the frontend.
+/// Returns whether a connection was cached.
+pub async fn invalidate_cached_connection(
+    app_handle: &tauri::AppHandle,
+    db_cache: &DbConnectionCache,
+    db_path: &str,
+    reason: &str,
+) -> bool {
+    let normalized_path = match std::fs::canonicalize(db_path) {
+        Ok(absolute_path) => absolute_path.to_string_lossy().to_string(),
+        Err(_) => db_path.to_string(),
+    };
+
+    DatabaseConnectionManager::clear_result_cache(Some(db_path));
+    DatabaseConnectionManager::drop_read_replicas(Some(db_path));
+    DatabaseConnectionManager::clear_schema_cache(Some(db_path));
+    let removed = db_cache.write().await.remove(&normalized_path).is_some();
+    emit_cache_invalidated(app_handle, Some(&normalized_path), reason);
+    removed
+}
+
+pub(crate) fn bind_json_values<'q>(
     mut query_builder: sqlx::query::Query<'q, sqlx::Sqlite, sqlx::sqlite::SqliteArguments<'q>>,
     values: &[serde_json::Value],
 ) -> sqlx::query::Query<'q, sqlx::Sqlite, sqlx::sqlite::SqliteArguments<'q>> {
@@ -39,6 +87,43 @@ fn bind_json_values<'q>(
     query_builder
 }
 
+/// Execute a write inside a transaction that is always rolled back, reporting what it would change.
+/// Scripts that control transactions themselves are refused, since a COMMIT would escape the rollback.
+pub(crate) async fn dry_run_execute<'q>(
+    pool: &sqlx::SqlitePool,
+    query_builder: sqlx::query::Query<'q, sqlx::Sqlite, sqlx::sqlite::SqliteArguments<'q>>,
+) -> Result<sqlx::sqlite::SqliteQueryResult, String> {
+    if controls_transaction(query_builder.sql()) {
+        return Err("Dry runs can't include BEGIN, COMMIT, END, ROLLBACK, SAVEPOINT or RELEASE".to_string());
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let result = query_builder.execute(&mut *tx).await.map_err(|e| e.to_string());
+    tx.rollback().await.map_err(|e| e.to_string())?;
+    result
+}
+
+fn dry_run_response(operation: &str, result: Result<sqlx::sqlite::SqliteQueryResult, String>) -> DbResponse<u64> {
+    match result {
+        Ok(result) => {
+            log::info!("🧪 DRY RUN {}: {} rows would be affected", operation, result.rows_affected());
+            DbResponse {
+                success: true,
+                data: Some(result.rows_affected()),
+                error: None,
+            }
+        }
+        Err(e) => {
+            log::error!("❌ DRY RUN {} failed: {}", operation, e);
+            DbResponse {
+                success: false,
+                data: None,
+                error: Some(format!("Dry run failed: {}", e)),
+            }
+        }
+    }
+}
+
 #[tauri::command]
 pub async fn db_update_table_row(
     state: State<'_, DbPool>,
@@ -54,6 +139,8 @@ pub async fn db_update_table_row(
     device_type: Option<String>,
     package_name: Option<String>,
     app_name: Option<String>,
+    // Roll the change back and only report how many rows it would affect
+    dry_run: Option<bool>,
 ) -> Result<DbResponse<u64>, String> {
     // Validate that we have a specific database path for write operations
     let db_path = match current_db_path.clone() {
@@ -99,7 +186,7 @@ pub async fn db_update_table_row(
     let set_clause = columns.iter().map(|col| format!("{} = ?", col)).collect::<Vec<_>>().join(", ");
     let query = format!("UPDATE {} SET {} WHERE {}", table_name, set_clause, condition);
     
-    log::info!("🔧 Executing UPDATE query on database '{}': {}", db_path, query);
+    log::info!("🔧 Executing UPDATE query on database '{}': {}", redact_path(&db_path), redact_sql(&query));
     
     // PHASE 2: Capture old values for change tracking (non-fatal if fails)
     let old_values = match capture_old_values_for_update(&pool, &table_name, &condition, &columns).await {
@@ -139,6 +226,10 @@ pub async fn db_update_table_row(
             };
         }
     }
+
+    if dry_run.unwrap_or(false) {
+        return Ok(dry_run_response("UPDATE", dry_run_execute(&pool, query_builder).await));
+    }
     
     match query_builder.execute(&pool).await {
         Ok(result) => {
@@ -314,7 +405,7 @@ pub async fn db_insert_table_row(
     let columns_str = columns.join(", ");
     let query = format!("INSERT INTO {} ({}) VALUES ({})", table_name, columns_str, placeholders);
     
-    log::info!("🔧 Executing INSERT query on database '{}': {}", db_path, query);
+    log::info!("🔧 Executing INSERT query on database '{}': {}", redact_path(&db_path), redact_sql(&query));
     
     let mut query_builder = sqlx::query(&query);
     
@@ -557,8 +648,7 @@ pub async fn db_add_new_row_with_defaults(
         });
     }
     
-    let pragma_query = format!("PRAGMA table_info({})", table_name);
-    let schema_rows = match sqlx::query(&pragma_query).fetch_all(&pool).await {
+    let schema_rows = match sqlx::query(TABLE_INFO_QUERY).bind(&table_name).fetch_all(&pool).await {
         Ok(rows) => rows,
         Err(e) => {
             log::error!("❌ Failed to read schema for INSERT DEFAULT VALUES on '{}': {}", table_name, e);
@@ -616,7 +706,7 @@ pub async fn db_add_new_row_with_defaults(
         )
     };
     
-    log::info!("🔧 Executing INSERT DEFAULT VALUES query on database '{}': {}", db_path, query);
+    log::info!("🔧 Executing INSERT DEFAULT VALUES query on database '{}': {}", redact_path(&db_path), redact_sql(&query));
     
     match bind_json_values(sqlx::query(&query), &insert_values).execute(&pool).await {
         Ok(result) => {
@@ -805,6 +895,8 @@ pub async fn db_delete_table_row(
     device_type: Option<String>,
     package_name: Option<String>,
     app_name: Option<String>,
+    // Roll the change back and only report how many rows it would affect
+    dry_run: Option<bool>,
 ) -> Result<DbResponse<u64>, String> {
     // Validate that we have a specific database path for write operations
     let db_path = match current_db_path.clone() {
@@ -863,7 +955,11 @@ pub async fn db_delete_table_row(
     }
     
     let query = format!("DELETE FROM {} WHERE {}", table_name, condition);
-    log::info!("🔧 Executing DELETE query on database '{}': {}", db_path, query);
+    log::info!("🔧 Executing DELETE query on database '{}': {}", redact_path(&db_path), redact_sql(&query));
+
+    if dry_run.unwrap_or(false) {
+        return Ok(dry_run_response("DELETE", dry_run_execute(&pool, sqlx::query(&query)).await));
+    }
     
     // PHASE 2: Capture old values before deletion for change tracking (non-fatal if fails)
     let old_values = match sqlx::query(&format!("SELECT * FROM {} WHERE {}", table_name, condition))
@@ -984,9 +1080,13 @@ pub async fn db_execute_query(
     _db_path: String,
     _params: Option<Vec<serde_json::Value>>,
     current_db_path: Option<String>,
+    // Non-SELECT statements run in a rolled back transaction and only report their changes
+    dry_run: Option<bool>,
+    // Re-run SELECTs on a read-only connection to count scanned rows
+    profile: Option<bool>,
 ) -> Result<DbResponse<serde_json::Value>, String> {
     // Get the current pool using the helper function
-    let pool = match get_current_pool(&state, &db_cache, current_db_path).await {
+    let pool = match get_current_pool(&state, &db_cache, current_db_path.clone()).await {
         Ok(pool) => pool,
         Err(e) => {
             log::error!("❌ {}", e);
@@ -998,12 +1098,70 @@ pub async fn db_execute_query(
         }
     };
     
-    let is_select = query.trim().to_uppercase().starts_with("SELECT");
+    let analysis = lint_query(&pool, &query).await;
+    if let Some(issue) = analysis.first_error() {
+        log::warn!("⚠️ Rejected malformed query: {}", issue.describe());
+        return Ok(DbResponse {
+            success: false,
+            data: None,
+            error: Some(issue.describe()),
+        });
+    }
+    let warnings = analysis.warnings();
+    for warning in &warnings {
+        log::warn!("⚠️ {}", warning);
+    }
+
+    let is_select = query.trim().to_uppercase().starts_with("SELECT") || analysis.is_single_query();
+    // Pure reads go to the read replica when enabled, leaving the writer free
+    let pool = if is_select && analysis.read_only {
+        get_read_pool(&state, &db_cache, current_db_path.clone()).await.unwrap_or(pool)
+    } else {
+        pool
+    };
+
+    // EXPLAIN QUERY PLAN would run any statements after the first, so only plan single statements
+    let plan = if analysis.statements.len() == 1 {
+        read_query_plan(&pool, &query).await
+    } else {
+        Vec::new()
+    };
+    let started = Instant::now();
+
+    let cacheable = is_select && !profile.unwrap_or(false);
+    if cacheable {
+        if let Some(mut cached) = current_db_path
+            .as_deref()
+            .and_then(|db_path| DatabaseConnectionManager::cached_result(db_path, &query))
+        {
+            if let Some(object) = cached.as_object_mut() {
+                object.insert("cached".to_string(), serde_json::Value::Bool(true));
+            }
+            return Ok(DbResponse {
+                success: true,
+                data: Some(cached),
+                error: None,
+            });
+        }
+    }
     
     if is_select {
         // Handle SELECT queries
         match sqlx::query(&query).fetch_all(&pool).await {
             Ok(rows) => {
+                let mut stats = QueryStats::new(started.elapsed(), rows.len() as u64, plan);
+                if profile.unwrap_or(false) {
+                    if let Some(db_path) = current_db_path.as_deref() {
+                        match measure_scan_counters(db_path, &query) {
+                            Ok((rows_scanned, vm_steps)) => {
+                                stats.rows_scanned = Some(rows_scanned);
+                                stats.vm_steps = Some(vm_steps);
+                            }
+                            Err(e) => log::warn!("⚠️ Query profiling failed: {}", e),
+                        }
+                    }
+                }
+
                 let mut result_rows = Vec::new();
                 let mut columns = Vec::new();
                 
@@ -1018,83 +1176,24 @@ pub async fn db_execute_query(
                     
                     // Process all rows
                     for row in rows {
-                        let mut row_data = HashMap::new();
-                        for (i, column) in row.columns().iter().enumerate() {
-                            let value = match row.try_get_raw(i) {
-                                Ok(raw_value) => {
-                                    if raw_value.is_null() {
-                                        serde_json::Value::Null
-                                    } else {
-                                        match column.type_info().name() {
-                                            "TEXT" => {
-                                                match row.try_get::<String, _>(i) {
-                                                    Ok(val) => serde_json::Value::String(val),
-                                                    Err(_) => serde_json::Value::String("".to_string()),
-                                                }
-                                            },
-                                            "INTEGER" => {
-                                                match row.try_get::<i64, _>(i) {
-                                                    Ok(val) => serde_json::Value::Number(serde_json::Number::from(val)),
-                                                    Err(_) => {
-                                                        // Try as string first, then convert to number if possible
-                                                        match row.try_get::<String, _>(i) {
-                                                            Ok(str_val) => {
-                                                                if let Ok(int_val) = str_val.parse::<i64>() {
-                                                                    serde_json::Value::Number(serde_json::Number::from(int_val))
-                                                                } else {
-                                                                    serde_json::Value::String(str_val)
-                                                                }
-                                                            },
-                                                            Err(_) => serde_json::Value::Null,
-                                                        }
-                                                    }
-                                                }
-                                            },
-                                            "REAL" => {
-                                                match row.try_get::<f64, _>(i) {
-                                                    Ok(val) => serde_json::Value::Number(
-                                                        serde_json::Number::from_f64(val).unwrap_or(serde_json::Number::from(0))
-                                                    ),
-                                                    Err(_) => {
-                                                        // Try as string first, then convert to number if possible
-                                                        match row.try_get::<String, _>(i) {
-                                                            Ok(str_val) => {
-                                                                if let Ok(float_val) = str_val.parse::<f64>() {
-                                                                    serde_json::Value::Number(
-                                                                        serde_json::Number::from_f64(float_val).unwrap_or(serde_json::Number::from(0))
-                                                                    )
-                                                                } else {
-                                                                    serde_json::Value::String(str_val)
-                                                                }
-                                                            },
-                                                            Err(_) => serde_json::Value::Null,
-                                                        }
-                                                    }
-                                                }
-                                            },
-                                            _ => {
-                                                match row.try_get::<String, _>(i) {
-                                                    Ok(val) => serde_json::Value::String(val),
-                                                    Err(_) => serde_json::Value::String("".to_string()),
-                                                }
-                                            },
-                                        }
-                                    }
-                                }
-                                Err(_) => serde_json::Value::Null,
-                            };
-                            row_data.insert(column.name().to_string(), value);
-                        }
-                        result_rows.push(serde_json::json!(row_data));
+                        result_rows.push(serde_json::json!(decode_row_to_json(&row)));
                     }
                 }
                 
+                let data = serde_json::json!({
+                    "rows": result_rows,
+                    "columns": columns,
+                    "stats": stats
+                });
+                if cacheable {
+                    if let Some(db_path) = current_db_path.as_deref() {
+                        DatabaseConnectionManager::store_result(db_path, &query, &data);
+                    }
+                }
+
                 Ok(DbResponse {
                     success: true,
-                    data: Some(serde_json::json!({
-                        "rows": result_rows,
-                        "columns": columns
-                    })),
+                    data: Some(data),
                     error: None,
                 })
             }
@@ -1107,14 +1206,48 @@ pub async fn db_execute_query(
                 })
             }
         }
+    } else if dry_run.unwrap_or(false) {
+        match dry_run_execute(&pool, sqlx::query(&query)).await {
+            Ok(result) => {
+                log::info!("🧪 DRY RUN query: {} rows would be affected", result.rows_affected());
+                Ok(DbResponse {
+                    success: true,
+                    data: Some(serde_json::json!({
+                        "changes": result.rows_affected(),
+                        "lastID": result.last_insert_rowid(),
+                        "dryRun": true,
+                        "warnings": warnings
+                    })),
+                    error: None,
+                })
+            }
+            Err(e) => {
+                log::error!("Error executing query dry run: {}", e);
+                Ok(DbResponse {
+                    success: false,
+                    data: None,
+                    error: Some(format!("Dry run failed: {}", e)),
+                })
+            }
+        }
     } else {
+        if let Err(e) = check_dangerous_sql(&query) {
+            return Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            });
+        }
+
         // Handle non-SELECT queries (INSERT, UPDATE, DELETE, etc.)
         match sqlx::query(&query).execute(&pool).await {
             Ok(result) => Ok(DbResponse {
                 success: true,
                 data: Some(serde_json::json!({
                     "changes": result.rows_affected(),
-                    "lastID": result.last_insert_rowid()
+                    "lastID": result.last_insert_rowid(),
+                    "warnings": warnings,
+                    "stats": QueryStats::new(started.elapsed(), 0, plan)
                 })),
                 error: None,
             }),
@@ -1130,11 +1263,9 @@ pub async fn db_execute_query(
     }
 }
 
-/// Get database connection statistics
-#[tauri::command]
-pub async fn db_get_connection_stats(
-    db_cache: State<'_, DbConnectionCache>,
-) -> Result<DbResponse<HashMap<String, serde_json::Value>>, String> {
+pub(crate) async fn connection_stats_snapshot(
+    db_cache: &DbConnectionCache,
+) -> HashMap<String, serde_json::Value> {
     let cache_guard = db_cache.read().await;
     let mut stats = HashMap::new();
     
@@ -1153,33 +1284,36 @@ pub async fn db_get_connection_stats(
         
     stats.insert("connections".to_string(), serde_json::Value::Array(connection_details));
     
+    stats
+}
+
+/// Get database connection statistics
+#[tauri::command]
+pub async fn db_get_connection_stats(
+    db_cache: State<'_, DbConnectionCache>,
+) -> Result<DbResponse<HashMap<String, serde_json::Value>>, String> {
     Ok(DbResponse {
         success: true,
-        data: Some(stats),
+        data: Some(connection_stats_snapshot(&db_cache).await),
         error: None,
     })
 }
 
 #[tauri::command]
 pub async fn db_clear_cache_for_path(
+    app_handle: tauri::AppHandle,
     db_cache: State<'_, DbConnectionCache>,
     db_path: String,
 ) -> Result<DbResponse<String>, String> {
-    let normalized_path = match std::fs::canonicalize(&db_path) {
-        Ok(absolute_path) => absolute_path.to_string_lossy().to_string(),
-        Err(_) => db_path.clone(),
-    };
-    
-    let mut cache_guard = db_cache.write().await;
-    if cache_guard.remove(&normalized_path).is_some() {
-        log::info!("🧹 Cleared cache for database: {}", normalized_path);
+    if invalidate_cached_connection(&app_handle, &db_cache, &db_path, "cleared").await {
+        log::info!("🧹 Cleared cache for database: {}", db_path);
         Ok(DbResponse {
             success: true,
             data: Some("Cache cleared".to_string()),
             error: None,
         })
     } else {
-        log::info!("ℹ️ No cache entry found for database: {}", normalized_path);
+        log::info!("ℹ️ No cache entry found for database: {}", db_path);
         Ok(DbResponse {
             success: true,
             data: Some("No cache entry found".to_string()),
@@ -1190,11 +1324,18 @@ pub async fn db_clear_cache_for_path(
 
 #[tauri::command]
 pub async fn db_clear_all_cache(
+    app_handle: tauri::AppHandle,
     db_cache: State<'_, DbConnectionCache>,
 ) -> Result<DbResponse<String>, String> {
+    DatabaseConnectionManager::clear_result_cache(None);
+    DatabaseConnectionManager::drop_read_replicas(None);
+    DatabaseConnectionManager::clear_schema_cache(None);
+
     let mut cache_guard = db_cache.write().await;
     let count = cache_guard.len();
     cache_guard.clear();
+    drop(cache_guard);
+    emit_cache_invalidated(&app_handle, None, "cleared");
     log::info!("🧹 Cleared all database cache entries: {} removed", count);
     
     Ok(DbResponse {
@@ -1206,6 +1347,7 @@ pub async fn db_clear_all_cache(
 
 #[tauri::command]
 pub async fn db_switch_database(
+    app_handle: tauri::AppHandle,
     db_cache: State<'_, DbConnectionCache>,
     new_db_path: String,
 ) -> Result<DbResponse<String>, String> {
@@ -1216,9 +1358,11 @@ pub async fn db_switch_database(
     let cache_size_before = cache_guard.len();
     
     // Remove any connections that might conflict with the new database
+    let mut removed_paths = Vec::new();
     cache_guard.retain(|path, cached_conn| {
         if cached_conn.should_be_removed(std::time::Duration::from_secs(0)) {
             log::info!("🧹 Removed stale connection during database switch: {}", path);
+            removed_paths.push(path.clone());
             false
         } else {
             true
@@ -1227,6 +1371,10 @@ pub async fn db_switch_database(
     
     let cache_size_after = cache_guard.len();
     let cleaned_count = cache_size_before - cache_size_after;
+    drop(cache_guard);
+    for path in &removed_paths {
+        emit_cache_invalidated(&app_handle, Some(path), "switched");
+    }
     
     if cleaned_count > 0 {
         log::info!("🧹 Cleaned {} stale connections during database switch", cleaned_count);
@@ -1276,6 +1424,14 @@ pub async fn db_clear_table(
         }
     };
 
+    if let Err(e) = check_dangerous_operation(DangerousOperation::ClearTable) {
+        return Ok(DbResponse {
+            success: false,
+            data: None,
+            error: Some(e),
+        });
+    }
+
     // Get the current pool using the helper function
     let pool = match get_current_pool(&state, &db_cache, current_db_path.clone()).await {
         Ok(pool) => pool,
@@ -1321,7 +1477,7 @@ pub async fn db_clear_table(
     };
     
     let query = format!("DELETE FROM {}", table_name);
-    log::info!("🔧 Executing CLEAR TABLE query on database '{}': {}", db_path, query);
+    log::info!("🔧 Executing CLEAR TABLE query on database '{}': {}", redact_path(&db_path), redact_sql(&query));
     
     match sqlx::query(&query).execute(&pool).await {
         Ok(result) => {
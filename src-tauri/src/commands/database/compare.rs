@@ -0,0 +1,377 @@
+// Cross-database query comparison
+// Runs the same read-only query against two databases and reports row-level differences
+
+use crate::commands::database::connection_access::open_uncached_connection;
+use crate::commands::database::helpers::decode_row_to_json;
+use crate::commands::database::query_lint::analyze_sql;
+use crate::commands::database::types::*;
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqlitePool, Column, Row};
+use std::collections::{BTreeMap, HashMap};
+
+pub type ResultRow = HashMap<String, serde_json::Value>;
+
+// Result set produced by one side of a comparison
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ComparedResultSet {
+    pub db_path: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<ResultRow>,
+}
+
+// A row present on both sides (matched by key) whose values differ
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangedRow {
+    pub key: ResultRow,
+    pub left: ResultRow,
+    pub right: ResultRow,
+    pub changed_columns: Vec<String>,
+}
+
+// Row-level differences between the left and right result sets
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ResultSetDiff {
+    pub only_in_left: Vec<ResultRow>,
+    pub only_in_right: Vec<ResultRow>,
+    pub changed: Vec<ChangedRow>,
+    pub matching_count: usize,
+    pub identical: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryComparison {
+    pub left: ComparedResultSet,
+    pub right: ComparedResultSet,
+    pub diff: ResultSetDiff,
+}
+
+/// Only read-only statements are allowed so a comparison can never modify either database.
+/// Every statement is classified, so `SELECT 1; DELETE ...` and `WITH ... DELETE` are refused,
+/// as is anything the parser cannot read.
+pub fn is_read_only_query(query: &str) -> bool {
+    let analysis = analyze_sql(query);
+    analysis.first_error().is_none() && analysis.read_only
+}
+
+/// Stable signature for a set of values, independent of HashMap iteration order.
+pub(crate) fn values_signature(row: &ResultRow, columns: &[String]) -> String {
+    let ordered: BTreeMap<&str, &serde_json::Value> = columns
+        .iter()
+        .map(|column| {
+            (
+                column.as_str(),
+                row.get(column).unwrap_or(&serde_json::Value::Null),
+            )
+        })
+        .collect();
+    serde_json::to_string(&ordered).unwrap_or_default()
+}
+
+fn row_columns(row: &ResultRow) -> Vec<String> {
+    let mut columns: Vec<String> = row.keys().cloned().collect();
+    columns.sort();
+    columns
+}
+
+/// Compute the row-level diff between two result sets.
+///
+/// When `key_columns` is empty, rows are compared as whole values (multiset semantics).
+/// Otherwise rows are matched by their key and differing columns are reported as changes.
+pub fn diff_result_rows(
+    left: &[ResultRow],
+    right: &[ResultRow],
+    key_columns: &[String],
+) -> ResultSetDiff {
+    let mut diff = ResultSetDiff::default();
+
+    // Index the right side by signature; each entry is a queue of row positions
+    let mut right_index: HashMap<String, Vec<usize>> = HashMap::new();
+    for (idx, row) in right.iter().enumerate().rev() {
+        let signature = if key_columns.is_empty() {
+            values_signature(row, &row_columns(row))
+        } else {
+            values_signature(row, key_columns)
+        };
+        right_index.entry(signature).or_default().push(idx);
+    }
+
+    let mut matched_right = vec![false; right.len()];
+
+    for left_row in left {
+        let signature = if key_columns.is_empty() {
+            values_signature(left_row, &row_columns(left_row))
+        } else {
+            values_signature(left_row, key_columns)
+        };
+
+        let right_idx = right_index.get_mut(&signature).and_then(|queue| queue.pop());
+        let Some(right_idx) = right_idx else {
+            diff.only_in_left.push(left_row.clone());
+            continue;
+        };
+
+        matched_right[right_idx] = true;
+        let right_row = &right[right_idx];
+
+        if key_columns.is_empty() {
+            diff.matching_count += 1;
+            continue;
+        }
+
+        let mut all_columns = row_columns(left_row);
+        for column in right_row.keys() {
+            if !left_row.contains_key(column) {
+                all_columns.push(column.clone());
+            }
+        }
+        all_columns.sort();
+
+        let changed_columns: Vec<String> = all_columns
+            .into_iter()
+            .filter(|column| left_row.get(column) != right_row.get(column))
+            .collect();
+
+        if changed_columns.is_empty() {
+            diff.matching_count += 1;
+        } else {
+            let key = key_columns
+                .iter()
+                .map(|column| {
+                    (
+                        column.clone(),
+                        left_row.get(column).cloned().unwrap_or(serde_json::Value::Null),
+                    )
+                })
+                .collect();
+            diff.changed.push(ChangedRow {
+                key,
+                left: left_row.clone(),
+                right: right_row.clone(),
+                changed_columns,
+            });
+        }
+    }
+
+    diff.only_in_right = right
+        .iter()
+        .zip(matched_right)
+        .filter(|(_, matched)| !matched)
+        .map(|(row, _)| row.clone())
+        .collect();
+
+    diff.identical =
+        diff.only_in_left.is_empty() && diff.only_in_right.is_empty() && diff.changed.is_empty();
+    diff
+}
+
+pub(crate) async fn run_compared_query(
+    pool: &SqlitePool,
+    db_path: &str,
+    query: &str,
+) -> Result<ComparedResultSet, String> {
+    let rows = sqlx::query(query)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Error executing query on '{}': {}", db_path, e))?;
+
+    let columns = rows
+        .first()
+        .map(|row| row.columns().iter().map(|c| c.name().to_string()).collect())
+        .unwrap_or_default();
+
+    Ok(ComparedResultSet {
+        db_path: db_path.to_string(),
+        columns,
+        rows: rows.iter().map(decode_row_to_json).collect(),
+    })
+}
+
+async fn compare_query_on_paths(
+    query: &str,
+    left_db_path: &str,
+    right_db_path: &str,
+    key_columns: &[String],
+) -> Result<QueryComparison, String> {
+    if !is_read_only_query(query) {
+        return Err("Only SELECT queries can be compared across databases".to_string());
+    }
+
+    let left_pool = open_uncached_connection(left_db_path).await?;
+    let right_pool = open_uncached_connection(right_db_path).await?;
+
+    let left_result = run_compared_query(&left_pool, left_db_path, query).await;
+    let right_result = run_compared_query(&right_pool, right_db_path, query).await;
+
+    left_pool.close().await;
+    right_pool.close().await;
+
+    let left = left_result?;
+    let right = right_result?;
+
+    for key_column in key_columns {
+        for side in [&left, &right] {
+            if !side.rows.is_empty() && !side.columns.contains(key_column) {
+                return Err(format!(
+                    "Key column '{}' is not present in the result set of '{}'",
+                    key_column, side.db_path
+                ));
+            }
+        }
+    }
+
+    let diff = diff_result_rows(&left.rows, &right.rows, key_columns);
+    Ok(QueryComparison { left, right, diff })
+}
+
+#[tauri::command]
+pub async fn db_compare_query(
+    query: String,
+    left_db_path: String,
+    right_db_path: String,
+    key_columns: Option<Vec<String>>,
+) -> Result<DbResponse<QueryComparison>, String> {
+    log::info!(
+        "🔍 Comparing query across databases: '{}' vs '{}'",
+        left_db_path,
+        right_db_path
+    );
+
+    let key_columns = key_columns.unwrap_or_default();
+    match compare_query_on_paths(&query, &left_db_path, &right_db_path, &key_columns)
+        .await
+    {
+        Ok(comparison) => {
+            log::info!(
+                "✅ Comparison finished: {} matching, {} only in left, {} only in right, {} changed",
+                comparison.diff.matching_count,
+                comparison.diff.only_in_left.len(),
+                comparison.diff.only_in_right.len(),
+                comparison.diff.changed.len()
+            );
+            Ok(DbResponse {
+                success: true,
+                data: Some(comparison),
+                error: None,
+            })
+        }
+        Err(e) => {
+            log::error!("❌ Query comparison failed: {}", e);
+            Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::database::tests::create_test_database;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn row(pairs: &[(&str, serde_json::Value)]) -> ResultRow {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_diff_without_keys_uses_multiset_semantics() {
+        let left = vec![
+            row(&[("name", json!("a"))]),
+            row(&[("name", json!("a"))]),
+            row(&[("name", json!("b"))]),
+        ];
+        let right = vec![row(&[("name", json!("a"))]), row(&[("name", json!("c"))])];
+
+        let diff = diff_result_rows(&left, &right, &[]);
+
+        assert_eq!(diff.matching_count, 1);
+        assert_eq!(diff.only_in_left.len(), 2);
+        assert_eq!(diff.only_in_right, vec![row(&[("name", json!("c"))])]);
+        assert!(!diff.identical);
+    }
+
+    #[test]
+    fn test_diff_with_keys_reports_changed_columns() {
+        let left = vec![
+            row(&[("id", json!(1)), ("name", json!("John")), ("age", json!(30))]),
+            row(&[("id", json!(2)), ("name", json!("Jane")), ("age", json!(25))]),
+        ];
+        let right = vec![
+            row(&[("id", json!(1)), ("name", json!("John")), ("age", json!(31))]),
+            row(&[("id", json!(3)), ("name", json!("Bob")), ("age", json!(35))]),
+        ];
+
+        let diff = diff_result_rows(&left, &right, &["id".to_string()]);
+
+        assert_eq!(diff.matching_count, 0);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].changed_columns, vec!["age".to_string()]);
+        assert_eq!(diff.changed[0].key.get("id"), Some(&json!(1)));
+        assert_eq!(diff.only_in_left.len(), 1);
+        assert_eq!(diff.only_in_right.len(), 1);
+    }
+
+    #[test]
+    fn test_diff_of_equal_result_sets_is_identical() {
+        let rows = vec![row(&[("id", json!(1)), ("name", json!("x"))])];
+        let diff = diff_result_rows(&rows, &rows, &["id".to_string()]);
+
+        assert!(diff.identical);
+        assert_eq!(diff.matching_count, 1);
+    }
+
+    #[test]
+    fn test_only_read_only_queries_are_allowed() {
+        assert!(is_read_only_query("  select * from users"));
+        assert!(is_read_only_query("WITH t AS (SELECT 1) SELECT * FROM t"));
+        assert!(!is_read_only_query("DELETE FROM users"));
+        assert!(!is_read_only_query("SELECT 1; DELETE FROM users"));
+        assert!(!is_read_only_query("WITH t AS (SELECT 1) DELETE FROM users WHERE id IN (SELECT * FROM t)"));
+        assert!(!is_read_only_query("WITH t AS (SELECT 1) INSERT INTO users SELECT * FROM t"));
+    }
+
+    #[tokio::test]
+    async fn test_compare_query_on_two_databases() {
+        let temp_dir = TempDir::new().unwrap();
+        let left_path = temp_dir.path().join("android.db");
+        let right_path = temp_dir.path().join("ios.db");
+        create_test_database(left_path.to_str().unwrap()).unwrap();
+        create_test_database(right_path.to_str().unwrap()).unwrap();
+
+        let conn = rusqlite::Connection::open(&right_path).unwrap();
+        conn.execute("UPDATE users SET age = 99 WHERE id = 2", []).unwrap();
+        drop(conn);
+
+        let comparison = compare_query_on_paths(
+            "SELECT id, name, age FROM users ORDER BY id",
+            left_path.to_str().unwrap(),
+            right_path.to_str().unwrap(),
+            &["id".to_string()],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(comparison.left.rows.len(), 3);
+        assert_eq!(comparison.right.columns, vec!["id", "name", "age"]);
+        assert_eq!(comparison.diff.matching_count, 2);
+        assert_eq!(comparison.diff.changed.len(), 1);
+        assert_eq!(comparison.diff.changed[0].changed_columns, vec!["age".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_compare_query_rejects_write_statements() {
+        let result = compare_query_on_paths("DELETE FROM users", "a.db", "b.db", &[]).await;
+
+        assert!(result.unwrap_err().contains("Only SELECT queries"));
+    }
+}
@@ -0,0 +1,328 @@
+// Compressed cells
+// Detects gzip/zlib payloads in BLOB columns, inflates them on demand and writes edited
+// content back in the same compression format
+
+use crate::commands::database::connection_access::get_current_pool;
+use crate::commands::database::helpers::quote_ident;
+use crate::commands::database::transformers::cell_bytes;
+use crate::commands::database::types::*;
+use base64::{engine::general_purpose, Engine as _};
+use flate2::read::{GzDecoder, ZlibDecoder};
+use flate2::write::{GzEncoder, ZlibEncoder};
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqlitePool, Row};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use tauri::State;
+
+// Guards against decompression bombs
+const MAX_DECOMPRESSED_BYTES: u64 = 64 * 1024 * 1024;
+
+// Values sampled per column when looking for compressed payloads
+const DETECTION_SAMPLE_SIZE: usize = 20;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum CompressionFormat {
+    Gzip,
+    Zlib,
+}
+
+impl CompressionFormat {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.to_lowercase().as_str() {
+            "gzip" | "gz" => Ok(Self::Gzip),
+            "zlib" | "deflate" => Ok(Self::Zlib),
+            other => Err(format!("Unsupported compression format '{}'; expected gzip or zlib", other)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CompressedColumn {
+    pub column: String,
+    pub format: CompressionFormat,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DecompressedCell {
+    pub format: CompressionFormat,
+    pub compressed_size: u64,
+    pub decompressed_size: u64,
+    // Set when the payload is UTF-8; `json` additionally when it parses as JSON
+    pub text: Option<String>,
+    pub json: Option<serde_json::Value>,
+    // Binary payloads are returned base64-encoded instead
+    pub base64: Option<String>,
+}
+
+/// Recognize gzip (magic 1f 8b) and zlib streams. For zlib the CMF/FLG header must name deflate,
+/// pass its check bits and not require a preset dictionary, which rules out most plain text.
+pub fn detect_compression(bytes: &[u8]) -> Option<CompressionFormat> {
+    match bytes {
+        [0x1f, 0x8b, 0x08, ..] => Some(CompressionFormat::Gzip),
+        [cmf, flg, _, ..]
+            if cmf & 0x0f == 8
+                && cmf >> 4 <= 7
+                && flg & 0x20 == 0
+                && (u16::from(*cmf) * 256 + u16::from(*flg)) % 31 == 0 =>
+        {
+            Some(CompressionFormat::Zlib)
+        }
+        _ => None,
+    }
+}
+
+pub fn decompress(bytes: &[u8], format: CompressionFormat) -> Result<Vec<u8>, String> {
+    let reader: Box<dyn Read> = match format {
+        CompressionFormat::Gzip => Box::new(GzDecoder::new(bytes)),
+        CompressionFormat::Zlib => Box::new(ZlibDecoder::new(bytes)),
+    };
+    let mut output = Vec::new();
+    reader
+        .take(MAX_DECOMPRESSED_BYTES + 1)
+        .read_to_end(&mut output)
+        .map_err(|e| format!("Failed to decompress cell: {}", e))?;
+    if output.len() as u64 > MAX_DECOMPRESSED_BYTES {
+        return Err(format!(
+            "Decompressed cell is larger than {} MB",
+            MAX_DECOMPRESSED_BYTES / (1024 * 1024)
+        ));
+    }
+    Ok(output)
+}
+
+pub fn compress(bytes: &[u8], format: CompressionFormat) -> Result<Vec<u8>, String> {
+    let result = match format {
+        CompressionFormat::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(bytes).and_then(|_| encoder.finish())
+        }
+        CompressionFormat::Zlib => {
+            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(bytes).and_then(|_| encoder.finish())
+        }
+    };
+    result.map_err(|e| format!("Failed to compress cell: {}", e))
+}
+
+pub fn describe_payload(compressed: &[u8], format: CompressionFormat) -> Result<DecompressedCell, String> {
+    let payload = decompress(compressed, format)?;
+    let decompressed_size = payload.len() as u64;
+    let (text, base64) = match String::from_utf8(payload) {
+        Ok(text) => (Some(text), None),
+        Err(e) => (None, Some(general_purpose::STANDARD.encode(e.into_bytes()))),
+    };
+    Ok(DecompressedCell {
+        format,
+        compressed_size: compressed.len() as u64,
+        decompressed_size,
+        json: text.as_deref().and_then(|text| serde_json::from_str(text).ok()),
+        text,
+        base64,
+    })
+}
+
+/// Columns whose sampled values are gzip or zlib streams. Only BLOB-like columns are sampled.
+pub fn detect_compressed_columns(
+    columns: &[ColumnInfo],
+    rows: &[HashMap<String, serde_json::Value>],
+) -> Vec<CompressedColumn> {
+    columns
+        .iter()
+        .filter(|column| {
+            let declared = column.type_name.to_uppercase();
+            declared.is_empty() || declared.contains("BLOB")
+        })
+        .filter_map(|column| {
+            rows.iter()
+                .filter_map(|row| row.get(&column.name))
+                .filter(|value| value.is_string())
+                .take(DETECTION_SAMPLE_SIZE)
+                .find_map(|value| cell_bytes(value, &column.type_name).and_then(|bytes| detect_compression(&bytes)))
+                .map(|format| CompressedColumn {
+                    column: column.name.clone(),
+                    format,
+                })
+        })
+        .collect()
+}
+
+async fn read_cell_bytes(pool: &SqlitePool, table_name: &str, column_name: &str, row_id: i64) -> Result<Vec<u8>, String> {
+    let query = format!(
+        "SELECT {} FROM {} WHERE rowid = ?",
+        quote_ident(column_name),
+        quote_ident(table_name)
+    );
+    let row = sqlx::query(&query)
+        .bind(row_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Error reading cell: {}", e))?
+        .ok_or_else(|| format!("Row {} not found in table '{}'", row_id, table_name))?;
+    row.try_get_unchecked::<Option<Vec<u8>>, _>(0)
+        .map_err(|e| format!("Error reading cell: {}", e))?
+        .ok_or_else(|| "Cell is NULL".to_string())
+}
+
+/// Inflate a gzip/zlib cell and return its content as text, JSON or base64.
+#[tauri::command]
+pub async fn db_decompress_cell(
+    state: State<'_, DbPool>,
+    db_cache: State<'_, DbConnectionCache>,
+    table_name: String,
+    column_name: String,
+    row_id: i64,
+    current_db_path: Option<String>,
+) -> Result<DbResponse<DecompressedCell>, String> {
+    let result = async {
+        let pool = get_current_pool(&state, &db_cache, current_db_path).await?;
+        let bytes = read_cell_bytes(&pool, &table_name, &column_name, row_id).await?;
+        let format = detect_compression(&bytes).ok_or("Cell is not gzip or zlib compressed")?;
+        describe_payload(&bytes, format)
+    }
+    .await;
+
+    match result {
+        Ok(cell) => Ok(DbResponse {
+            success: true,
+            data: Some(cell),
+            error: None,
+        }),
+        Err(e) => {
+            log::error!("❌ Failed to decompress cell {}.{}: {}", table_name, column_name, e);
+            Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            })
+        }
+    }
+}
+
+/// Compress edited text and store it as a BLOB. The format defaults to the one the cell already uses.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn db_recompress_cell(
+    state: State<'_, DbPool>,
+    db_cache: State<'_, DbConnectionCache>,
+    table_name: String,
+    column_name: String,
+    row_id: i64,
+    content: String,
+    format: Option<String>,
+    current_db_path: Option<String>,
+) -> Result<DbResponse<u64>, String> {
+    log::info!("🗜️ Recompressing cell {}.{} (rowid {})", table_name, column_name, row_id);
+
+    let result = async {
+        let pool = get_current_pool(&state, &db_cache, current_db_path).await?;
+        let format = match format.as_deref() {
+            Some(format) => CompressionFormat::parse(format)?,
+            None => {
+                let bytes = read_cell_bytes(&pool, &table_name, &column_name, row_id).await?;
+                detect_compression(&bytes).ok_or("Cell is not compressed; choose gzip or zlib")?
+            }
+        };
+        let compressed = compress(content.as_bytes(), format)?;
+        let query = format!(
+            "UPDATE {} SET {} = ? WHERE rowid = ?",
+            quote_ident(&table_name),
+            quote_ident(&column_name)
+        );
+        sqlx::query(&query)
+            .bind(compressed)
+            .bind(row_id)
+            .execute(&pool)
+            .await
+            .map(|result| result.rows_affected())
+            .map_err(|e| format!("Error writing cell: {}", e))
+    }
+    .await;
+
+    match result {
+        Ok(rows_affected) => Ok(DbResponse {
+            success: true,
+            data: Some(rows_affected),
+            error: None,
+        }),
+        Err(e) => {
+            log::error!("❌ Failed to recompress cell {}.{}: {}", table_name, column_name, e);
+            Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_detect_and_round_trip() {
+        let payload = br#"{"theme":"dark","count":3}"#;
+        for format in [CompressionFormat::Gzip, CompressionFormat::Zlib] {
+            let compressed = compress(payload, format).unwrap();
+            assert_eq!(detect_compression(&compressed), Some(format));
+            let cell = describe_payload(&compressed, format).unwrap();
+            assert_eq!(cell.json, Some(json!({ "theme": "dark", "count": 3 })));
+            assert_eq!(cell.decompressed_size, payload.len() as u64);
+            assert!(cell.base64.is_none());
+        }
+
+        assert_eq!(detect_compression(b"plain text"), None);
+        assert_eq!(detect_compression(&[0x78]), None);
+        assert!(decompress(&[0x1f, 0x8b, 0x08, 0x00], CompressionFormat::Gzip).is_err());
+        assert_eq!(CompressionFormat::parse("GZ"), Ok(CompressionFormat::Gzip));
+        assert!(CompressionFormat::parse("brotli").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_detect_columns_and_read_cell() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("compressed.db");
+        let compressed = compress(b"hello", CompressionFormat::Zlib).unwrap();
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        conn.execute_batch("CREATE TABLE cache (id INTEGER PRIMARY KEY, payload BLOB, note TEXT);")
+            .unwrap();
+        conn.execute(
+            "INSERT INTO cache (payload, note) VALUES (?1, 'x')",
+            rusqlite::params![compressed],
+        )
+        .unwrap();
+        drop(conn);
+
+        let pool = SqlitePool::connect(&format!("sqlite:{}", db_path.display())).await.unwrap();
+        let bytes = read_cell_bytes(&pool, "cache", "payload", 1).await.unwrap();
+        assert_eq!(describe_payload(&bytes, CompressionFormat::Zlib).unwrap().text.as_deref(), Some("hello"));
+        assert!(read_cell_bytes(&pool, "cache", "payload", 2).await.is_err());
+        pool.close().await;
+
+        let column = |name: &str, type_name: &str| ColumnInfo {
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+            notnull: false,
+            pk: false,
+            default_value: serde_json::Value::Null,
+        };
+        let rows = vec![HashMap::from([
+            ("payload".to_string(), json!(general_purpose::STANDARD.encode(&compressed))),
+            ("note".to_string(), json!("x")),
+        ])];
+        assert_eq!(
+            detect_compressed_columns(&[column("payload", "BLOB"), column("note", "TEXT")], &rows),
+            vec![CompressedColumn {
+                column: "payload".to_string(),
+                format: CompressionFormat::Zlib,
+            }]
+        );
+    }
+}
@@ -1,9 +1,23 @@
-use crate::commands::database::helpers::ensure_database_file_permissions;
-use crate::commands::database::types::{DbConnectionCache, DbPool};
+use crate::commands::database::helpers::{
+    ensure_database_file_permissions, ensure_supported_database_format,
+};
+use crate::commands::database::connection_manager::DatabaseConnectionManager;
+use crate::commands::database::types::{CachedConnection, DbConnectionCache, DbPool};
+use crate::commands::settings::current_settings;
 use log::{error, info, warn};
-use sqlx::sqlite::SqlitePool;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
+use std::str::FromStr;
 use tauri::State;
 
+/// Connect options for a database file, with the statement cache sized from settings so the
+/// metadata queries every navigation runs are compiled once per connection.
+pub fn sqlite_connect_options(normalized_path: &str, mode: &str) -> Result<SqliteConnectOptions, String> {
+    let capacity = current_settings().connection.statement_cache_capacity;
+    SqliteConnectOptions::from_str(&format!("sqlite:{}?mode={}", normalized_path, mode))
+        .map(|options| options.statement_cache_capacity(capacity))
+        .map_err(|e| format!("Invalid database path '{}': {}", normalized_path, e))
+}
+
 /// Helper function to validate that a pool is actually usable.
 pub async fn validate_pool_health(pool: &SqlitePool) -> bool {
     if pool.is_closed() {
@@ -23,25 +37,64 @@ pub async fn validate_pool_health(pool: &SqlitePool) -> bool {
     }
 }
 
-/// Get or create a database connection (cache disabled - always creates fresh connections).
+/// Get or create a database connection. With `connection.cacheEnabled` the pool is kept in
+/// `db_cache` and reused until unused for `connectionTtlSeconds`, for at most
+/// `maxConnections` databases; otherwise every call opens a fresh connection.
 pub async fn get_cached_connection(
-    _db_cache: &DbConnectionCache,
+    db_cache: &DbConnectionCache,
     db_path: &str,
 ) -> Result<SqlitePool, String> {
-    let normalized_path = match std::fs::canonicalize(db_path) {
+    let normalized_path = normalize_db_path(db_path);
+    let config = current_settings().connection.to_config();
+
+    if config.cache_disabled {
+        info!("🚫 Cache disabled - creating fresh connection for: {}", normalized_path);
+    } else {
+        let mut cache_guard = db_cache.write().await;
+        if let Some(cached_conn) = cache_guard.get_mut(&normalized_path) {
+            if !cached_conn.should_be_removed(config.connection_ttl) {
+                cached_conn.update_last_used();
+                info!("📦 Reusing cached connection for: {}", normalized_path);
+                return Ok(cached_conn.pool.clone());
+            }
+            cache_guard.remove(&normalized_path);
+        }
+    }
+
+    let pool = connect_database(&normalized_path).await?;
+    if !config.cache_disabled {
+        let mut cache_guard = db_cache.write().await;
+        if cache_guard.len() >= config.max_connections && !cache_guard.contains_key(&normalized_path) {
+            DatabaseConnectionManager::evict_oldest_connection(&mut cache_guard);
+        }
+        cache_guard.insert(normalized_path, CachedConnection::new(pool.clone()));
+    }
+    Ok(pool)
+}
+
+/// Open a pool that is never cached, for one-shot work on files such as snapshots that the
+/// caller closes when done. Closing a pool from `get_cached_connection` would close it for
+/// every other user of the cache.
+pub async fn open_uncached_connection(db_path: &str) -> Result<SqlitePool, String> {
+    connect_database(&normalize_db_path(db_path)).await
+}
+
+fn normalize_db_path(db_path: &str) -> String {
+    match std::fs::canonicalize(db_path) {
         Ok(absolute_path) => absolute_path.to_string_lossy().to_string(),
         Err(_) => db_path.to_string(),
-    };
-
-    info!("🚫 Cache disabled - creating fresh connection for: {}", normalized_path);
+    }
+}
 
-    if !std::path::Path::new(&normalized_path).exists() {
+async fn connect_database(normalized_path: &str) -> Result<SqlitePool, String> {
+    if !std::path::Path::new(normalized_path).exists() {
         return Err(format!("Database file does not exist: {}", normalized_path));
     }
 
-    ensure_database_file_permissions(&normalized_path)?;
+    ensure_supported_database_format(normalized_path)?;
+    ensure_database_file_permissions(normalized_path)?;
 
-    match SqlitePool::connect(&format!("sqlite:{}?mode=rwc", normalized_path)).await {
+    match SqlitePool::connect_with(sqlite_connect_options(normalized_path, "rwc")?).await {
         Ok(pool) => {
             info!("✅ Successfully connected to database: {}", normalized_path);
             Ok(pool)
@@ -53,6 +106,24 @@ pub async fn get_cached_connection(
     }
 }
 
+/// Pool for read-only work on `current_db_path`: its read replica when `connection.read_replicas`
+/// is on, otherwise the same pool writes use. Falls back to the writer if the replica can't open.
+pub async fn get_read_pool(
+    state: &State<'_, DbPool>,
+    db_cache: &State<'_, DbConnectionCache>,
+    current_db_path: Option<String>,
+) -> Result<SqlitePool, String> {
+    if let Some(db_path) = current_db_path.as_deref() {
+        if current_settings().connection.read_replicas {
+            match DatabaseConnectionManager::read_replica(db_path).await {
+                Ok(pool) => return Ok(pool),
+                Err(e) => warn!("⚠️ Read replica unavailable, reading through the writer: {}", e),
+            }
+        }
+    }
+    get_current_pool(state, db_cache, current_db_path).await
+}
+
 // Helper function to get the current active database from cache or state.
 pub async fn get_current_pool(
     state: &State<'_, DbPool>,
@@ -1,14 +1,69 @@
 // Database connection management with per-database caching
 use crate::commands::database::types::*;
+use crate::commands::database::connection_access::sqlite_connect_options;
 use crate::commands::database::helpers::ensure_database_file_permissions;
+use crate::commands::database::schema_cache::{SchemaSnapshot, SCHEMA_CACHE};
+use crate::commands::settings::current_settings;
 use log::{info, warn, error};
+use serde::Serialize;
 use sqlx::sqlite::SqlitePool;
 use std::collections::HashMap;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant, UNIX_EPOCH};
 use tokio::sync::RwLock;
 use tokio::time::sleep;
 
+const MAX_CACHED_RESULTS: usize = 32;
+const READ_REPLICA_MAX_CONNECTIONS: u32 = 4;
+
+// Read-only pools kept apart from the writer connection, keyed by normalized db path
+static READ_REPLICAS: LazyLock<Mutex<HashMap<String, SqlitePool>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// Short-lived SELECT results shared by all commands, keyed by (db path, data version, SQL)
+static RESULT_CACHE: LazyLock<Mutex<HashMap<(String, String, String), CachedResult>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+struct CachedResult {
+    value: serde_json::Value,
+    stored_at: Instant,
+}
+
+/// Version token for a database file, used as the data version part of result cache keys.
+/// Commands open a fresh connection each time and `PRAGMA data_version` is only comparable
+/// on a single connection, so this uses the size and mtime of the file and its WAL instead.
+pub fn data_version_token(db_path: &str) -> Option<String> {
+    let stamp = |path: &str| {
+        std::fs::metadata(path).ok().map(|metadata| {
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|elapsed| elapsed.as_nanos())
+                .unwrap_or_default();
+            format!("{}:{}", metadata.len(), modified)
+        })
+    };
+    let main = stamp(db_path)?;
+    let wal = stamp(&format!("{}-wal", db_path)).unwrap_or_default();
+    Some(format!("{}/{}", main, wal))
+}
+
+// Absolute path of a database, so every cache agrees on its key
+fn normalize_db_path(db_path: &str) -> String {
+    match std::fs::canonicalize(db_path) {
+        Ok(absolute_path) => absolute_path.to_string_lossy().to_string(),
+        Err(_) => db_path.to_string(),
+    }
+}
+
+fn result_cache_ttl() -> Option<Duration> {
+    let connection = current_settings().connection;
+    (connection.result_cache_enabled && connection.result_cache_ttl_seconds > 0)
+        .then(|| Duration::from_secs(connection.result_cache_ttl_seconds))
+}
+
 /// Database connection manager with caching and automatic cleanup
 pub struct DatabaseConnectionManager {
     cache: DbConnectionCache,
@@ -30,6 +85,11 @@ impl DatabaseConnectionManager {
         }
     }
 
+    /// Manage an existing cache, e.g. the one registered as Tauri state
+    pub fn with_cache(cache: DbConnectionCache, config: ConnectionConfig) -> Self {
+        Self { cache, config }
+    }
+
     /// Get the cache for use in Tauri state management
     pub fn get_cache(&self) -> DbConnectionCache {
         self.cache.clone()
@@ -77,7 +137,7 @@ impl DatabaseConnectionManager {
             
             // Check cache size limit
             if cache_guard.len() >= self.config.max_connections {
-                self.cleanup_oldest_connection(&mut cache_guard).await;
+                Self::evict_oldest_connection(&mut cache_guard);
             }
             
             cache_guard.insert(normalized_path.clone(), CachedConnection::new(pool.clone()));
@@ -86,6 +146,58 @@ impl DatabaseConnectionManager {
         Ok(pool)
     }
 
+    /// Get a connection for reads: a read-only replica pool when enabled, otherwise the writer
+    pub async fn get_read_connection(&self, db_path: &str) -> Result<SqlitePool, String> {
+        if !self.config.read_replicas {
+            return self.get_connection(db_path).await;
+        }
+        Self::read_replica(db_path).await
+    }
+
+    /// The read-only pool for a database, opened on first use and shared by every reader
+    pub async fn read_replica(db_path: &str) -> Result<SqlitePool, String> {
+        let normalized_path = normalize_db_path(db_path);
+
+        if let Some(pool) = READ_REPLICAS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&normalized_path)
+            .filter(|pool| !pool.is_closed())
+        {
+            return Ok(pool.clone());
+        }
+
+        if !Path::new(&normalized_path).exists() {
+            return Err(format!("Database file does not exist: {}", normalized_path));
+        }
+
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(READ_REPLICA_MAX_CONNECTIONS)
+            .connect_with(sqlite_connect_options(&normalized_path, "ro")?)
+            .await
+            .map_err(|e| {
+                error!("❌ Failed to open read replica for '{}': {}", normalized_path, e);
+                format!("Could not open read-only connection: {}", e)
+            })?;
+        info!("📖 Opened read replica for: {}", normalized_path);
+
+        let mut replicas = READ_REPLICAS.lock().unwrap_or_else(|e| e.into_inner());
+        // Another reader may have opened one meanwhile; keep the first
+        let pool = replicas.entry(normalized_path).or_insert(pool).clone();
+        Ok(pool)
+    }
+
+    /// Drop the read replica of one database, or of all databases
+    pub fn drop_read_replicas(db_path: Option<&str>) {
+        let mut replicas = READ_REPLICAS.lock().unwrap_or_else(|e| e.into_inner());
+        match db_path {
+            Some(db_path) => {
+                replicas.remove(&normalize_db_path(db_path));
+            }
+            None => replicas.clear(),
+        }
+    }
+
     /// Create a new SQLite connection
     async fn create_new_connection(&self, db_path: &str) -> Result<SqlitePool, String> {
         // Validate file exists
@@ -97,7 +209,7 @@ impl DatabaseConnectionManager {
         ensure_database_file_permissions(db_path)?;
 
         // Create connection with optimized settings
-        match SqlitePool::connect(&format!("sqlite:{}?mode=rwc", db_path)).await {
+        match SqlitePool::connect_with(sqlite_connect_options(db_path, "rwc")?).await {
             Ok(pool) => {
                 info!("✅ Successfully connected to database: {}", db_path);
                 Ok(pool)
@@ -110,7 +222,7 @@ impl DatabaseConnectionManager {
     }
 
     /// Remove oldest unused connection to make space
-    async fn cleanup_oldest_connection(&self, cache: &mut HashMap<String, CachedConnection>) {
+    pub(crate) fn evict_oldest_connection(cache: &mut HashMap<String, CachedConnection>) {
         if let Some((oldest_path, _)) = cache
             .iter()
             .min_by_key(|(_, conn)| conn.last_used)
@@ -208,12 +320,85 @@ impl DatabaseConnectionManager {
         stats
     }
 
+    /// Cached result of an identical query on an unchanged database, when the result cache is on
+    pub fn cached_result(db_path: &str, sql: &str) -> Option<serde_json::Value> {
+        let ttl = result_cache_ttl()?;
+        let key = (db_path.to_string(), data_version_token(db_path)?, sql.to_string());
+        let mut cache = RESULT_CACHE.lock().ok()?;
+        cache.retain(|_, cached| cached.stored_at.elapsed() < ttl);
+        let value = cache.get(&key).map(|cached| cached.value.clone());
+        if value.is_some() {
+            info!("⚡ Serving cached result for: {}", db_path);
+        }
+        value
+    }
+
+    /// Remember a query result for the current data version of the database
+    pub fn store_result<T: Serialize>(db_path: &str, sql: &str, value: &T) {
+        if result_cache_ttl().is_none() {
+            return;
+        }
+        let (Some(data_version), Ok(value)) = (data_version_token(db_path), serde_json::to_value(value)) else {
+            return;
+        };
+        let Ok(mut cache) = RESULT_CACHE.lock() else {
+            return;
+        };
+        if cache.len() >= MAX_CACHED_RESULTS {
+            if let Some(oldest) = cache
+                .iter()
+                .min_by_key(|(_, cached)| cached.stored_at)
+                .map(|(key, _)| key.clone())
+            {
+                cache.remove(&oldest);
+            }
+        }
+        cache.insert(
+            (db_path.to_string(), data_version, sql.to_string()),
+            CachedResult {
+                value,
+                stored_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Drop cached results for one database, or for all databases
+    pub fn clear_result_cache(db_path: Option<&str>) {
+        if let Ok(mut cache) = RESULT_CACHE.lock() {
+            match db_path {
+                Some(db_path) => cache.retain(|(path, _, _), _| path != db_path),
+                None => cache.clear(),
+            }
+        }
+    }
+
+    /// Load column metadata for every table in one query, so the first table opened doesn't
+    /// pay a PRAGMA round trip. Returns the number of tables cached.
+    pub async fn warm_schema(pool: &SqlitePool, db_path: &str) -> Result<usize, String> {
+        let started = Instant::now();
+        let snapshot = Self::schema(pool, db_path).await?;
+        info!("🔥 Warmed schema metadata for {} tables in {:?}: {}", snapshot.tables.len(), started.elapsed(), db_path);
+        Ok(snapshot.tables.len())
+    }
+
+    /// Tables and columns of a database, from the schema cache while its schema is unchanged
+    pub async fn schema(pool: &SqlitePool, db_path: &str) -> Result<Arc<SchemaSnapshot>, String> {
+        SCHEMA_CACHE.load(pool, &normalize_db_path(db_path)).await
+    }
+
+    /// Columns of `table` from the schema cache; None if the table doesn't exist
+    pub async fn table_columns(pool: &SqlitePool, db_path: &str, table: &str) -> Result<Option<Vec<ColumnInfo>>, String> {
+        Ok(Self::schema(pool, db_path).await?.columns.get(table).cloned())
+    }
+
+    /// Forget schema metadata for one database, or for all databases
+    pub fn clear_schema_cache(db_path: Option<&str>) {
+        SCHEMA_CACHE.invalidate(db_path.map(normalize_db_path).as_deref());
+    }
+
     /// Normalize database path for consistent caching
     fn normalize_path(&self, db_path: &str) -> String {
         // Convert to absolute path to avoid cache misses due to relative path differences
-        match std::fs::canonicalize(db_path) {
-            Ok(absolute_path) => absolute_path.to_string_lossy().to_string(),
-            Err(_) => db_path.to_string(), // Fallback to original path if canonicalization fails
-        }
+        normalize_db_path(db_path)
     }
 } 
\ No newline at end of file
@@ -0,0 +1,152 @@
+// Writable database copies
+// Duplicates a database into the temp dir so it can be changed without touching the original
+
+use crate::commands::database::connection_access::get_cached_connection;
+use crate::commands::database::helpers::{
+    copy_database_with_companions, ensure_database_file_permissions,
+    ensure_supported_database_format,
+};
+use crate::commands::database::types::*;
+use crate::commands::device::helpers::get_temp_dir_path;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+const COPIES_DIR_NAME: &str = "copies";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseCopy {
+    pub copy_path: String,
+    pub original_path: String,
+    pub size: u64,
+}
+
+fn copy_file_name(original: &Path) -> String {
+    let stem = original
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("database");
+    let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S_%3f");
+
+    match original.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) => format!("{}_copy_{}.{}", stem, timestamp, ext),
+        None => format!("{}_copy_{}", stem, timestamp),
+    }
+}
+
+/// Copy a database (with WAL/SHM and pull metadata) into `copies_dir` and make it writable.
+pub fn create_database_copy(original_path: &str, copies_dir: &Path) -> Result<DatabaseCopy, String> {
+    let original = Path::new(original_path);
+    if !original.is_file() {
+        return Err(format!("Database file does not exist: {}", original_path));
+    }
+    ensure_supported_database_format(original_path)?;
+
+    std::fs::create_dir_all(copies_dir)
+        .map_err(|e| format!("Failed to create copies directory: {}", e))?;
+    let copy_path: PathBuf = copies_dir.join(copy_file_name(original));
+
+    copy_database_with_companions(original, &copy_path)?;
+
+    // Keep the pull metadata so the copy can be pushed back to the same location
+    let metadata = format!("{}.meta.json", original.display());
+    if Path::new(&metadata).exists() {
+        if let Err(e) = std::fs::copy(&metadata, format!("{}.meta.json", copy_path.display())) {
+            log::warn!("⚠️ Failed to copy database metadata: {}", e);
+        }
+    }
+
+    let copy_path_str = copy_path.to_string_lossy().to_string();
+    // The original may be read-only (e.g. pulled from an iOS device)
+    ensure_database_file_permissions(&copy_path_str)?;
+
+    let size = std::fs::metadata(&copy_path).map(|m| m.len()).unwrap_or(0);
+    Ok(DatabaseCopy {
+        copy_path: copy_path_str,
+        original_path: original_path.to_string(),
+        size,
+    })
+}
+
+#[tauri::command]
+pub async fn db_open_copy(
+    state: State<'_, DbPool>,
+    db_cache: State<'_, DbConnectionCache>,
+    file_path: String,
+) -> Result<DbResponse<DatabaseCopy>, String> {
+    log::info!("📑 Creating writable copy of {}", file_path);
+
+    let copies_dir = get_temp_dir_path().join(COPIES_DIR_NAME);
+    let copy = match create_database_copy(&file_path, &copies_dir) {
+        Ok(copy) => copy,
+        Err(e) => {
+            log::error!("❌ Failed to copy database: {}", e);
+            return Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            });
+        }
+    };
+
+    match get_cached_connection(&db_cache, &copy.copy_path).await {
+        Ok(pool) => {
+            *state.write().await = Some(pool);
+            log::info!("✅ Opened database copy {}", copy.copy_path);
+            Ok(DbResponse {
+                success: true,
+                data: Some(copy),
+                error: None,
+            })
+        }
+        Err(e) => {
+            log::error!("❌ Failed to open database copy: {}", e);
+            Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::database::tests::create_test_database;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_create_database_copy_includes_wal_and_metadata() {
+        let temp_dir = TempDir::new().unwrap();
+        let original = temp_dir.path().join("app.db");
+        create_test_database(original.to_str().unwrap()).unwrap();
+        std::fs::write(temp_dir.path().join("app.db-wal"), b"wal").unwrap();
+        std::fs::write(temp_dir.path().join("app.db.meta.json"), b"{}").unwrap();
+
+        let copies_dir = temp_dir.path().join("copies");
+        let copy = create_database_copy(original.to_str().unwrap(), &copies_dir).unwrap();
+
+        assert_ne!(copy.copy_path, copy.original_path);
+        assert!(copy.copy_path.ends_with(".db"));
+        assert!(Path::new(&format!("{}-wal", copy.copy_path)).exists());
+        assert!(Path::new(&format!("{}.meta.json", copy.copy_path)).exists());
+
+        // Writing to the copy leaves the original untouched
+        let conn = rusqlite::Connection::open(&copy.copy_path).unwrap();
+        conn.execute("DELETE FROM users", []).unwrap();
+        let original_conn = rusqlite::Connection::open(&original).unwrap();
+        let count: i64 = original_conn
+            .query_row("SELECT COUNT(*) FROM users", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    fn test_create_database_copy_requires_existing_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let result = create_database_copy("/does/not/exist.db", temp_dir.path());
+        assert!(result.is_err());
+    }
+}
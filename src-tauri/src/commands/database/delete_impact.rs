@@ -0,0 +1,324 @@
+// Delete impact analysis
+// Follows foreign key references to report which child rows a delete would cascade to or orphan
+
+use crate::commands::database::connection_access::get_current_pool;
+use crate::commands::database::helpers::{quote_ident, TABLE_INFO_QUERY};
+use crate::commands::database::types::*;
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqlitePool, Row};
+use std::collections::BTreeMap;
+use tauri::State;
+
+// Self-referencing and cyclic schemas would otherwise cascade forever
+const MAX_CASCADE_DEPTH: usize = 8;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteImpact {
+    pub table: String,
+    pub parent_table: String,
+    pub columns: Vec<String>,
+    pub parent_columns: Vec<String>,
+    // ON DELETE action as declared in the schema, e.g. "CASCADE" or "NO ACTION"
+    pub on_delete: String,
+    // "cascade", "setNull", "setDefault", "blocked" or "orphan"
+    pub effect: String,
+    pub affected_rows: u64,
+    // 1 for direct children of the deleted rows
+    pub depth: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteImpactReport {
+    pub table: String,
+    pub target_rows: u64,
+    pub foreign_keys_enforced: bool,
+    pub impacts: Vec<DeleteImpact>,
+    pub cascaded_rows: u64,
+    pub orphaned_rows: u64,
+    // A RESTRICT/NO ACTION reference would make the delete fail
+    pub blocked: bool,
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ForeignKeyRef {
+    pub(crate) child_table: String,
+    pub(crate) child_columns: Vec<String>,
+    pub(crate) parent_columns: Vec<String>,
+    pub(crate) on_delete: String,
+}
+
+/// What happens to referencing rows for a given ON DELETE action.
+fn delete_effect(on_delete: &str, foreign_keys_enforced: bool) -> &'static str {
+    if !foreign_keys_enforced {
+        return "orphan";
+    }
+    match on_delete.to_uppercase().as_str() {
+        "CASCADE" => "cascade",
+        "SET NULL" => "setNull",
+        "SET DEFAULT" => "setDefault",
+        _ => "blocked",
+    }
+}
+
+fn column_list(columns: &[String]) -> String {
+    columns.iter().map(|c| quote_ident(c)).collect::<Vec<_>>().join(", ")
+}
+
+/// WHERE clause selecting child rows that reference the parent rows matched by `parent_filter`.
+fn child_filter(reference: &ForeignKeyRef, parent_table: &str, parent_filter: &str) -> String {
+    let child_columns = column_list(&reference.child_columns);
+    let child_columns = if reference.child_columns.len() > 1 {
+        format!("({})", child_columns)
+    } else {
+        child_columns
+    };
+    format!(
+        "{} IN (SELECT {} FROM {} WHERE {})",
+        child_columns,
+        column_list(&reference.parent_columns),
+        quote_ident(parent_table),
+        parent_filter
+    )
+}
+
+async fn primary_key_columns(pool: &SqlitePool, table: &str) -> Result<Vec<String>, String> {
+    let rows = sqlx::query(TABLE_INFO_QUERY)
+        .bind(table)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Error reading columns for '{}': {}", table, e))?;
+
+    let mut keys: Vec<(i64, String)> = rows
+        .iter()
+        .map(|row| (row.get::<i64, _>("pk"), row.get::<String, _>("name")))
+        .filter(|(pk, _)| *pk > 0)
+        .collect();
+    keys.sort();
+    let keys: Vec<String> = keys.into_iter().map(|(_, name)| name).collect();
+    Ok(if keys.is_empty() { vec!["rowid".to_string()] } else { keys })
+}
+
+/// Foreign keys of every table, grouped by the parent table they point at.
+pub(crate) async fn read_references(pool: &SqlitePool) -> Result<BTreeMap<String, Vec<ForeignKeyRef>>, String> {
+    let tables: Vec<String> = sqlx::query_scalar(
+        "SELECT name FROM sqlite_master WHERE type='table' AND name NOT LIKE 'sqlite_%'",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Error reading tables: {}", e))?;
+
+    let mut references: BTreeMap<String, Vec<ForeignKeyRef>> = BTreeMap::new();
+    for table in tables {
+        let rows = sqlx::query(&format!("PRAGMA foreign_key_list({})", quote_ident(&table)))
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("Error reading foreign keys for '{}': {}", table, e))?;
+
+        // Composite keys come back as one row per column sharing the same id
+        let mut by_id: BTreeMap<i64, (String, String, Vec<(String, Option<String>)>)> = BTreeMap::new();
+        for row in rows {
+            let entry = by_id.entry(row.get::<i64, _>("id")).or_insert_with(|| {
+                (row.get::<String, _>("table"), row.get::<String, _>("on_delete"), Vec::new())
+            });
+            entry.2.push((row.get("from"), row.try_get::<Option<String>, _>("to").ok().flatten()));
+        }
+
+        for (parent_table, on_delete, pairs) in by_id.into_values() {
+            let child_columns = pairs.iter().map(|(from, _)| from.clone()).collect();
+            // A missing target column means the parent's primary key
+            let parent_columns = if pairs.iter().all(|(_, to)| to.is_some()) {
+                pairs.into_iter().filter_map(|(_, to)| to).collect()
+            } else {
+                primary_key_columns(pool, &parent_table).await?
+            };
+            references.entry(parent_table).or_default().push(ForeignKeyRef {
+                child_table: table.clone(),
+                child_columns,
+                parent_columns,
+                on_delete,
+            });
+        }
+    }
+    Ok(references)
+}
+
+async fn count_rows(pool: &SqlitePool, table: &str, filter: &str) -> Result<u64, String> {
+    sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) FROM {} WHERE {}", quote_ident(table), filter))
+        .fetch_one(pool)
+        .await
+        .map(|count| count.max(0) as u64)
+        .map_err(|e| format!("Error counting rows in '{}': {}", table, e))
+}
+
+/// Walk the foreign key graph from the rows of `table` matching `condition`.
+pub async fn analyze_delete_impact(
+    pool: &SqlitePool,
+    table: &str,
+    condition: &str,
+) -> Result<DeleteImpactReport, String> {
+    let foreign_keys_enforced: i64 = sqlx::query_scalar("PRAGMA foreign_keys")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Error reading foreign key setting: {}", e))?;
+
+    let mut report = DeleteImpactReport {
+        table: table.to_string(),
+        target_rows: count_rows(pool, table, condition).await?,
+        foreign_keys_enforced: foreign_keys_enforced != 0,
+        ..Default::default()
+    };
+    if report.target_rows == 0 {
+        return Ok(report);
+    }
+
+    let references = read_references(pool).await?;
+    // (table, filter selecting the rows of that table being deleted, depth)
+    let mut pending = vec![(table.to_string(), format!("({})", condition), 0usize)];
+
+    while let Some((parent_table, parent_filter, depth)) = pending.pop() {
+        for reference in references.get(&parent_table).into_iter().flatten() {
+            let filter = child_filter(reference, &parent_table, &parent_filter);
+            let affected_rows = count_rows(pool, &reference.child_table, &filter).await?;
+            if affected_rows == 0 {
+                continue;
+            }
+
+            let effect = delete_effect(&reference.on_delete, report.foreign_keys_enforced);
+            match effect {
+                "cascade" => {
+                    report.cascaded_rows += affected_rows;
+                    if depth + 1 < MAX_CASCADE_DEPTH {
+                        pending.push((reference.child_table.clone(), filter, depth + 1));
+                    } else {
+                        report.truncated = true;
+                    }
+                }
+                "orphan" => report.orphaned_rows += affected_rows,
+                "blocked" => report.blocked = true,
+                _ => {}
+            }
+
+            report.impacts.push(DeleteImpact {
+                table: reference.child_table.clone(),
+                parent_table: parent_table.clone(),
+                columns: reference.child_columns.clone(),
+                parent_columns: reference.parent_columns.clone(),
+                on_delete: reference.on_delete.clone(),
+                effect: effect.to_string(),
+                affected_rows,
+                depth: depth + 1,
+            });
+        }
+    }
+
+    report.impacts.sort_by(|a, b| a.depth.cmp(&b.depth).then_with(|| a.table.cmp(&b.table)));
+    Ok(report)
+}
+
+/// Report which rows in other tables would be cascaded, nulled, blocked or orphaned
+/// if the rows matching `condition` were deleted. Nothing is modified.
+#[tauri::command]
+pub async fn db_analyze_delete_impact(
+    state: State<'_, DbPool>,
+    db_cache: State<'_, DbConnectionCache>,
+    table_name: String,
+    condition: String,
+    current_db_path: Option<String>,
+) -> Result<DbResponse<DeleteImpactReport>, String> {
+    if table_name.trim().is_empty() || condition.trim().is_empty() {
+        return Ok(DbResponse {
+            success: false,
+            data: None,
+            error: Some("Table name and condition are required".to_string()),
+        });
+    }
+    log::info!("🔗 Analyzing delete impact for table '{}'", table_name);
+
+    let result = async {
+        let pool = get_current_pool(&state, &db_cache, current_db_path).await?;
+        analyze_delete_impact(&pool, &table_name, &condition).await
+    }
+    .await;
+
+    match result {
+        Ok(report) => {
+            log::info!(
+                "✅ Delete impact: {} target rows, {} cascaded, {} orphaned{}",
+                report.target_rows,
+                report.cascaded_rows,
+                report.orphaned_rows,
+                if report.blocked { ", blocked by a foreign key" } else { "" }
+            );
+            Ok(DbResponse {
+                success: true,
+                data: Some(report),
+                error: None,
+            })
+        }
+        Err(e) => {
+            log::error!("❌ Delete impact analysis failed: {}", e);
+            Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_delete_effect() {
+        assert_eq!(delete_effect("CASCADE", true), "cascade");
+        assert_eq!(delete_effect("SET NULL", true), "setNull");
+        assert_eq!(delete_effect("NO ACTION", true), "blocked");
+        assert_eq!(delete_effect("CASCADE", false), "orphan");
+    }
+
+    #[tokio::test]
+    async fn test_analyze_delete_impact_follows_cascades() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("impact.db");
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT);
+             CREATE TABLE orders (id INTEGER PRIMARY KEY, user_id INTEGER REFERENCES users ON DELETE CASCADE);
+             CREATE TABLE order_items (id INTEGER PRIMARY KEY, order_id INTEGER REFERENCES orders(id) ON DELETE CASCADE);
+             CREATE TABLE reviews (id INTEGER PRIMARY KEY, user_id INTEGER REFERENCES users(id) ON DELETE SET NULL);
+             INSERT INTO users VALUES (1, 'a'), (2, 'b');
+             INSERT INTO orders VALUES (10, 1), (11, 1), (12, 2);
+             INSERT INTO order_items VALUES (100, 10), (101, 10), (102, 11), (103, 12);
+             INSERT INTO reviews VALUES (1000, 1);",
+        )
+        .unwrap();
+        drop(conn);
+
+        let pool = SqlitePool::connect(&format!("sqlite:{}", db_path.display())).await.unwrap();
+        let report = analyze_delete_impact(&pool, "users", "id = 1").await.unwrap();
+        pool.close().await;
+
+        assert_eq!(report.target_rows, 1);
+        assert!(!report.blocked);
+        assert_eq!(report.cascaded_rows, 5);
+        let impacts: Vec<(&str, &str, u64, usize)> = report
+            .impacts
+            .iter()
+            .map(|i| (i.table.as_str(), i.effect.as_str(), i.affected_rows, i.depth))
+            .collect();
+        assert_eq!(
+            impacts,
+            vec![
+                ("orders", "cascade", 2, 1),
+                ("reviews", "setNull", 1, 1),
+                ("order_items", "cascade", 3, 2),
+            ]
+        );
+    }
+}
@@ -0,0 +1,947 @@
+// Row export
+// Exports selected or filtered table rows to a file in one of the supported formats
+
+use crate::commands::common::{check_cancelled, redact_sql, OperationKind, OPERATIONS};
+use crate::commands::database::bulk_update::{build_where_clause, RowFilter};
+use crate::commands::database::commands::bind_json_values;
+use crate::commands::database::compare::{is_read_only_query, ResultRow};
+use crate::commands::database::connection_access::{get_current_pool, get_read_pool};
+use crate::commands::database::helpers::{decode_row_to_json, quote_ident, TABLE_INFO_QUERY};
+use crate::commands::database::types::*;
+use rust_xlsxwriter::{Format, Workbook};
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqlitePool, Column, Row};
+use std::path::PathBuf;
+use tauri::State;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Xlsx,
+}
+
+impl ExportFormat {
+    pub fn parse(format: &str) -> Result<Self, String> {
+        match format.trim().to_lowercase().as_str() {
+            "csv" => Ok(ExportFormat::Csv),
+            "json" => Ok(ExportFormat::Json),
+            "xlsx" | "excel" => Ok(ExportFormat::Xlsx),
+            other => Err(format!("Unsupported export format '{}'", other)),
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Xlsx => "xlsx",
+        }
+    }
+}
+
+// Which rows of a table to export: explicit key values, a structured filter, or both
+#[derive(Debug, Clone, Default)]
+pub struct RowSelection {
+    pub key_column: Option<String>,
+    pub key_values: Vec<serde_json::Value>,
+    pub filter: Option<RowFilter>,
+}
+
+// What to export: a whole table, the result of a read-only query, or selected table rows
+#[derive(Debug, Clone)]
+pub enum ExportSource {
+    Table(String),
+    Query(String),
+    Selection {
+        table_name: String,
+        selection: RowSelection,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportRowsResult {
+    pub path: String,
+    pub format: ExportFormat,
+    pub row_count: usize,
+}
+
+/// Text form of a cell value, shared by the tabular export formats.
+pub fn export_cell_text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => String::new(),
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn escape_csv_value(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn render_csv(columns: &[String], rows: &[ResultRow]) -> String {
+    let mut lines = Vec::with_capacity(rows.len() + 1);
+    lines.push(
+        columns
+            .iter()
+            .map(|column| escape_csv_value(column))
+            .collect::<Vec<_>>()
+            .join(","),
+    );
+
+    for row in rows {
+        lines.push(
+            columns
+                .iter()
+                .map(|column| {
+                    let value = row.get(column).unwrap_or(&serde_json::Value::Null);
+                    escape_csv_value(&export_cell_text(value))
+                })
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+    }
+
+    lines.join("\n")
+}
+
+/// JSON array of objects, keeping the table's column order.
+pub fn render_json(columns: &[String], rows: &[ResultRow]) -> Result<String, String> {
+    let ordered: Vec<serde_json::Map<String, serde_json::Value>> = rows
+        .iter()
+        .map(|row| {
+            columns
+                .iter()
+                .map(|column| {
+                    (
+                        column.clone(),
+                        row.get(column).cloned().unwrap_or(serde_json::Value::Null),
+                    )
+                })
+                .collect()
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&ordered).map_err(|e| format!("Failed to serialize rows: {}", e))
+}
+
+// Excel limits: 1,048,576 rows per sheet and 32,767 characters per cell
+const XLSX_MAX_DATA_ROWS: usize = 1_048_575;
+const XLSX_MAX_CELL_CHARS: usize = 32_767;
+// Integers beyond 2^53 lose precision as Excel numbers, so they are written as text
+const XLSX_MAX_SAFE_INTEGER: i64 = 9_007_199_254_740_991;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum XlsxColumnKind {
+    Number,
+    Boolean,
+    Text,
+}
+
+fn is_xlsx_number(value: &serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(int_val) => int_val.abs() <= XLSX_MAX_SAFE_INTEGER,
+            None => n.as_f64().is_some(),
+        },
+        _ => false,
+    }
+}
+
+/// A column is typed as numeric or boolean only when every non-null value agrees.
+fn infer_xlsx_column_kind(column: &str, rows: &[ResultRow]) -> XlsxColumnKind {
+    let mut values = rows
+        .iter()
+        .filter_map(|row| row.get(column))
+        .filter(|value| !value.is_null())
+        .peekable();
+
+    if values.peek().is_none() {
+        return XlsxColumnKind::Text;
+    }
+
+    let values: Vec<&serde_json::Value> = values.collect();
+    if values.iter().all(|value| is_xlsx_number(value)) {
+        XlsxColumnKind::Number
+    } else if values.iter().all(|value| value.is_boolean()) {
+        XlsxColumnKind::Boolean
+    } else {
+        XlsxColumnKind::Text
+    }
+}
+
+/// Single-sheet workbook with a bold, frozen header row and typed columns.
+pub fn render_xlsx(columns: &[String], rows: &[ResultRow]) -> Result<Vec<u8>, String> {
+    if rows.len() > XLSX_MAX_DATA_ROWS {
+        return Err(format!(
+            "Too many rows for an Excel sheet ({} > {})",
+            rows.len(),
+            XLSX_MAX_DATA_ROWS
+        ));
+    }
+    if columns.len() > u16::MAX as usize {
+        return Err("Too many columns for an Excel sheet".to_string());
+    }
+
+    let xlsx_error = |e: rust_xlsxwriter::XlsxError| format!("Failed to build xlsx file: {}", e);
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    let header_format = Format::new().set_bold();
+
+    for (col_idx, column) in columns.iter().enumerate() {
+        let col = col_idx as u16;
+        worksheet
+            .write_string_with_format(0, col, column, &header_format)
+            .map_err(xlsx_error)?;
+
+        let kind = infer_xlsx_column_kind(column, rows);
+        for (row_idx, row) in rows.iter().enumerate() {
+            let xlsx_row = row_idx as u32 + 1;
+            let value = row.get(column).unwrap_or(&serde_json::Value::Null);
+
+            match (kind, value) {
+                (_, serde_json::Value::Null) => continue,
+                (XlsxColumnKind::Number, serde_json::Value::Number(n)) => {
+                    worksheet
+                        .write_number(xlsx_row, col, n.as_f64().unwrap_or_default())
+                        .map_err(xlsx_error)?;
+                }
+                (XlsxColumnKind::Boolean, serde_json::Value::Bool(b)) => {
+                    worksheet
+                        .write_boolean(xlsx_row, col, *b)
+                        .map_err(xlsx_error)?;
+                }
+                _ => {
+                    let text: String = export_cell_text(value)
+                        .chars()
+                        .take(XLSX_MAX_CELL_CHARS)
+                        .collect();
+                    worksheet
+                        .write_string(xlsx_row, col, text)
+                        .map_err(xlsx_error)?;
+                }
+            };
+        }
+    }
+
+    worksheet.set_freeze_panes(1, 0).map_err(xlsx_error)?;
+    if !columns.is_empty() {
+        worksheet
+            .autofilter(0, 0, rows.len() as u32, columns.len() as u16 - 1)
+            .map_err(xlsx_error)?;
+    }
+    worksheet.autofit();
+
+    workbook.save_to_buffer().map_err(xlsx_error)
+}
+
+fn escape_markdown_cell(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+pub fn render_markdown(columns: &[String], rows: &[ResultRow]) -> String {
+    let mut lines = Vec::with_capacity(rows.len() + 2);
+    lines.push(format!(
+        "| {} |",
+        columns
+            .iter()
+            .map(|column| escape_markdown_cell(column))
+            .collect::<Vec<_>>()
+            .join(" | ")
+    ));
+    lines.push(format!("|{}|", vec![" --- "; columns.len()].join("|")));
+
+    for row in rows {
+        let cells = columns
+            .iter()
+            .map(|column| {
+                let value = row.get(column).unwrap_or(&serde_json::Value::Null);
+                escape_markdown_cell(&export_cell_text(value))
+            })
+            .collect::<Vec<_>>()
+            .join(" | ");
+        lines.push(format!("| {} |", cells));
+    }
+
+    lines.join("\n")
+}
+
+// Text formats for copying rows to the clipboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClipboardFormat {
+    Markdown,
+    Json,
+    Csv,
+    Insert,
+}
+
+impl ClipboardFormat {
+    pub fn parse(format: &str) -> Result<Self, String> {
+        match format.trim().to_lowercase().as_str() {
+            "markdown" | "md" => Ok(ClipboardFormat::Markdown),
+            "json" => Ok(ClipboardFormat::Json),
+            "csv" => Ok(ClipboardFormat::Csv),
+            "insert" | "sql" => Ok(ClipboardFormat::Insert),
+            other => Err(format!("Unsupported copy format '{}'", other)),
+        }
+    }
+}
+
+/// Render rows as clipboard text; `blob_columns` hold base64 BLOBs, which INSERT statements
+/// turn back into hex literals.
+pub fn render_clipboard_text(
+    format: ClipboardFormat,
+    table_name: Option<&str>,
+    columns: &[String],
+    rows: &[ResultRow],
+    blob_columns: &[String],
+) -> Result<String, String> {
+    match format {
+        ClipboardFormat::Markdown => Ok(render_markdown(columns, rows)),
+        ClipboardFormat::Json => render_json(columns, rows),
+        ClipboardFormat::Csv => Ok(render_csv(columns, rows)),
+        ClipboardFormat::Insert => {
+            let table_name = table_name
+                .filter(|name| !name.trim().is_empty())
+                .ok_or_else(|| "A table name is required to copy rows as INSERT statements".to_string())?;
+            let literals = rows.iter().map(|row| {
+                columns
+                    .iter()
+                    .map(|column| {
+                        let value = row.get(column).unwrap_or(&serde_json::Value::Null);
+                        json_sql_literal(value, blob_columns.contains(column))
+                    })
+                    .collect()
+            });
+            Ok(render_insert_statements(table_name, columns, literals).join("\n"))
+        }
+    }
+}
+
+pub fn render_export(
+    format: ExportFormat,
+    columns: &[String],
+    rows: &[ResultRow],
+) -> Result<Vec<u8>, String> {
+    match format {
+        ExportFormat::Csv => Ok(render_csv(columns, rows).into_bytes()),
+        ExportFormat::Json => render_json(columns, rows).map(String::into_bytes),
+        ExportFormat::Xlsx => render_xlsx(columns, rows),
+    }
+}
+
+pub(crate) async fn read_table_columns(
+    pool: &SqlitePool,
+    table_name: &str,
+) -> Result<Vec<String>, String> {
+    let rows = sqlx::query(TABLE_INFO_QUERY)
+        .bind(table_name)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Error getting table info: {}", e))?;
+
+    if rows.is_empty() {
+        return Err(format!("Table '{}' does not exist", table_name));
+    }
+
+    Ok(rows.iter().map(|row| row.get::<String, _>("name")).collect())
+}
+
+/// Columns of a table declared as BLOB
+pub(crate) async fn read_blob_columns(pool: &SqlitePool, table_name: &str) -> Result<Vec<String>, String> {
+    let rows = sqlx::query(TABLE_INFO_QUERY)
+        .bind(table_name)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Error getting table info: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .filter(|row| row.get::<String, _>("type").to_uppercase().contains("BLOB"))
+        .map(|row| row.get::<String, _>("name"))
+        .collect())
+}
+
+/// Build the SELECT for a row selection and its parameters: the key values, then the
+/// filter's values.
+pub(crate) fn build_selection_query(
+    table_name: &str,
+    columns: &[String],
+    selection: &RowSelection,
+) -> Result<(String, Vec<serde_json::Value>), String> {
+    let filter = selection
+        .filter
+        .as_ref()
+        .filter(|filter| !filter.conditions.is_empty());
+
+    if selection.key_values.is_empty() && filter.is_none() {
+        return Err("Select rows or provide a filter to export".to_string());
+    }
+
+    let mut conditions = Vec::new();
+    let mut params = selection.key_values.clone();
+    if !selection.key_values.is_empty() {
+        let key_column = selection.key_column.as_deref().unwrap_or("rowid");
+        if key_column != "rowid" && !columns.iter().any(|column| column == key_column) {
+            return Err(format!(
+                "Column '{}' does not exist in table '{}'",
+                key_column, table_name
+            ));
+        }
+        let placeholders = vec!["?"; selection.key_values.len()].join(", ");
+        let key_column = if key_column == "rowid" {
+            key_column.to_string()
+        } else {
+            quote_ident(key_column)
+        };
+        conditions.push(format!("{} IN ({})", key_column, placeholders));
+    }
+    if let Some(filter) = filter {
+        let (where_clause, where_params) = build_where_clause(filter, columns)?;
+        conditions.push(format!("({})", where_clause));
+        params.extend(where_params);
+    }
+
+    let column_list = columns
+        .iter()
+        .map(|column| quote_ident(column))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let query = format!(
+        "SELECT {} FROM {} WHERE {}",
+        column_list,
+        quote_ident(table_name),
+        conditions.join(" AND ")
+    );
+    Ok((query, params))
+}
+
+/// Read the selected rows of a table, in column order.
+pub(crate) async fn fetch_selected_rows(
+    pool: &SqlitePool,
+    table_name: &str,
+    selection: &RowSelection,
+) -> Result<(Vec<String>, Vec<ResultRow>), String> {
+    let columns = read_table_columns(pool, table_name).await?;
+    let (query, params) = build_selection_query(table_name, &columns, selection)?;
+    log::info!("🔧 Export query: {}", redact_sql(&query));
+
+    let rows = bind_json_values(sqlx::query(&query), &params)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Error reading rows to export: {}", e))?;
+
+    Ok((columns, rows.iter().map(decode_row_to_json).collect()))
+}
+
+async fn fetch_query_rows(
+    pool: &SqlitePool,
+    query: &str,
+) -> Result<(Vec<String>, Vec<ResultRow>), String> {
+    if !is_read_only_query(query) {
+        return Err("Only SELECT queries can be exported".to_string());
+    }
+
+    let rows = sqlx::query(query)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+    let columns = rows
+        .first()
+        .map(|row| row.columns().iter().map(|c| c.name().to_string()).collect())
+        .unwrap_or_default();
+
+    Ok((columns, rows.iter().map(decode_row_to_json).collect()))
+}
+
+pub async fn fetch_export_source(
+    pool: &SqlitePool,
+    source: &ExportSource,
+) -> Result<(Vec<String>, Vec<ResultRow>), String> {
+    match source {
+        ExportSource::Table(table_name) => {
+            let columns = read_table_columns(pool, table_name).await?;
+            let rows = sqlx::query(&format!("SELECT * FROM {}", quote_ident(table_name)))
+                .fetch_all(pool)
+                .await
+                .map_err(|e| format!("Error reading table '{}': {}", table_name, e))?;
+            Ok((columns, rows.iter().map(decode_row_to_json).collect()))
+        }
+        ExportSource::Query(query) => fetch_query_rows(pool, query).await,
+        ExportSource::Selection {
+            table_name,
+            selection,
+        } => fetch_selected_rows(pool, table_name, selection).await,
+    }
+}
+
+pub async fn export_rows_to_file(
+    pool: &SqlitePool,
+    source: &ExportSource,
+    format: ExportFormat,
+    output_path: &str,
+) -> Result<ExportRowsResult, String> {
+    let (columns, rows) = fetch_export_source(pool, source).await?;
+    let content = render_export(format, &columns, &rows)?;
+    check_cancelled()?;
+
+    std::fs::write(output_path, content)
+        .map_err(|e| format!("Failed to write exported file: {}", e))?;
+
+    Ok(ExportRowsResult {
+        path: output_path.to_string(),
+        format,
+        row_count: rows.len(),
+    })
+}
+
+/// Ask for the destination when the frontend did not pass one.
+pub(crate) async fn resolve_export_path(
+    app_handle: &tauri::AppHandle,
+    output_path: Option<String>,
+    default_name: &str,
+    extension: &str,
+) -> Result<Option<PathBuf>, String> {
+    match output_path {
+        Some(path) if !path.trim().is_empty() => Ok(Some(PathBuf::from(path))),
+        _ => {
+            let filter_name = format!("{} Files", extension.to_uppercase());
+            crate::commands::common::prompt_save_path(
+                app_handle,
+                default_name,
+                &[(filter_name.as_str(), &[extension])],
+            )
+            .await
+        }
+    }
+}
+
+async fn run_export(
+    app_handle: &tauri::AppHandle,
+    pool: Result<SqlitePool, String>,
+    source: ExportSource,
+    format: &str,
+    default_stem: &str,
+    output_path: Option<String>,
+) -> Result<Option<ExportRowsResult>, String> {
+    // An empty format falls back to the user's preferred export format
+    let format = if format.trim().is_empty() {
+        ExportFormat::parse(&crate::commands::settings::current_settings().export.default_format)?
+    } else {
+        ExportFormat::parse(format)?
+    };
+    let pool = pool?;
+
+    let default_name = format!("{}.{}", default_stem, format.extension());
+    let Some(output_path) =
+        resolve_export_path(app_handle, output_path, &default_name, format.extension()).await?
+    else {
+        // Save dialog was cancelled
+        return Ok(None);
+    };
+
+    let result =
+        export_rows_to_file(&pool, &source, format, &output_path.to_string_lossy()).await?;
+    log::info!("✅ Exported {} rows to {}", result.row_count, result.path);
+    Ok(Some(result))
+}
+
+fn export_response(
+    result: Result<Option<ExportRowsResult>, String>,
+) -> DbResponse<Option<ExportRowsResult>> {
+    match result {
+        Ok(result) => DbResponse {
+            success: true,
+            data: Some(result),
+            error: None,
+        },
+        Err(e) => {
+            log::error!("❌ Export failed: {}", e);
+            DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            }
+        }
+    }
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn db_export_rows(
+    app_handle: tauri::AppHandle,
+    state: State<'_, DbPool>,
+    db_cache: State<'_, DbConnectionCache>,
+    table_name: String,
+    format: String,
+    key_column: Option<String>,
+    key_values: Option<Vec<serde_json::Value>>,
+    filter: Option<RowFilter>,
+    output_path: Option<String>,
+    current_db_path: Option<String>,
+) -> Result<DbResponse<Option<ExportRowsResult>>, String> {
+    log::info!("📤 Exporting rows from table '{}' as {}", table_name, format);
+
+    let pool = get_read_pool(&state, &db_cache, current_db_path).await;
+    let default_stem = format!("{}_selection", table_name);
+    let source = ExportSource::Selection {
+        table_name,
+        selection: RowSelection {
+            key_column,
+            key_values: key_values.unwrap_or_default(),
+            filter,
+        },
+    };
+
+    let operation = OPERATIONS.start(OperationKind::Export, format!("Export {}", default_stem), None);
+    Ok(operation
+        .run(async { export_response(run_export(&app_handle, pool, source, &format, &default_stem, output_path).await) })
+        .await)
+}
+
+/// Export a whole table or the result of a read-only query.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn db_export_data(
+    app_handle: tauri::AppHandle,
+    state: State<'_, DbPool>,
+    db_cache: State<'_, DbConnectionCache>,
+    format: String,
+    table_name: Option<String>,
+    query: Option<String>,
+    output_path: Option<String>,
+    current_db_path: Option<String>,
+) -> Result<DbResponse<Option<ExportRowsResult>>, String> {
+    let (source, default_stem) = match (table_name, query) {
+        (_, Some(query)) if !query.trim().is_empty() => {
+            (ExportSource::Query(query), "query_results".to_string())
+        }
+        (Some(table_name), _) => (ExportSource::Table(table_name.clone()), table_name),
+        _ => {
+            return Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some("Either a table name or a query is required".to_string()),
+            })
+        }
+    };
+    match &source {
+        ExportSource::Query(query) => log::info!("📤 Exporting query {} as {}", redact_sql(query), format),
+        _ => log::info!("📤 Exporting {} as {}", default_stem, format),
+    }
+
+    let pool = get_current_pool(&state, &db_cache, current_db_path).await;
+    let operation = OPERATIONS.start(OperationKind::Export, format!("Export {}", default_stem), None);
+    Ok(operation
+        .run(async { export_response(run_export(&app_handle, pool, source, &format, &default_stem, output_path).await) })
+        .await)
+}
+
+/// Render rows the frontend already holds (selection or query results) as clipboard text.
+/// For INSERT statements the table's BLOB columns are looked up in the open database.
+#[tauri::command]
+pub async fn db_copy_rows_as(
+    state: State<'_, DbPool>,
+    db_cache: State<'_, DbConnectionCache>,
+    format: String,
+    columns: Vec<String>,
+    rows: Vec<ResultRow>,
+    table_name: Option<String>,
+    current_db_path: Option<String>,
+) -> Result<DbResponse<String>, String> {
+    log::info!("📋 Copying {} rows as {}", rows.len(), format);
+
+    let format = ClipboardFormat::parse(&format);
+    let mut blob_columns = Vec::new();
+    if let (Ok(ClipboardFormat::Insert), Some(table_name)) = (&format, table_name.as_deref()) {
+        let lookup = match get_current_pool(&state, &db_cache, current_db_path).await {
+            Ok(pool) => read_blob_columns(&pool, table_name).await,
+            Err(e) => Err(e),
+        };
+        match lookup {
+            Ok(columns) => blob_columns = columns,
+            Err(e) => log::warn!("⚠️ Could not look up BLOB columns of {}, copying them as text: {}", table_name, e),
+        }
+    }
+    let result = format.and_then(|format| {
+        render_clipboard_text(format, table_name.as_deref(), &columns, &rows, &blob_columns)
+    });
+
+    match result {
+        Ok(text) => Ok(DbResponse {
+            success: true,
+            data: Some(text),
+            error: None,
+        }),
+        Err(e) => {
+            log::error!("❌ Failed to copy rows: {}", e);
+            Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::database::bulk_update::{FilterCombinator, FilterCondition, FilterOperator};
+    use crate::commands::database::tests::create_test_database;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    async fn test_pool(temp_dir: &TempDir) -> SqlitePool {
+        let db_path = temp_dir.path().join("export.db");
+        create_test_database(db_path.to_str().unwrap()).unwrap();
+        SqlitePool::connect(&format!("sqlite:{}", db_path.display()))
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn test_render_csv_escapes_values() {
+        let columns = vec!["id".to_string(), "note".to_string()];
+        let rows = vec![
+            [("id".to_string(), json!(1)), ("note".to_string(), json!("a, \"b\""))]
+                .into_iter()
+                .collect(),
+            [("id".to_string(), json!(2)), ("note".to_string(), json!(null))]
+                .into_iter()
+                .collect(),
+        ];
+
+        assert_eq!(
+            render_csv(&columns, &rows),
+            "id,note\n1,\"a, \"\"b\"\"\"\n2,"
+        );
+    }
+
+    #[test]
+    fn test_render_markdown_escapes_pipes_and_newlines() {
+        let columns = vec!["id".to_string(), "note".to_string()];
+        let rows = vec![[("id".to_string(), json!(1)), ("note".to_string(), json!("a|b\nc"))]
+            .into_iter()
+            .collect()];
+
+        assert_eq!(
+            render_markdown(&columns, &rows),
+            "| id | note |\n| --- | --- |\n| 1 | a\\|b<br>c |"
+        );
+    }
+
+    #[test]
+    fn test_render_clipboard_insert_statements() {
+        let columns = vec!["id".to_string(), "name".to_string(), "active".to_string()];
+        let rows = vec![[
+            ("id".to_string(), json!(7)),
+            ("name".to_string(), json!("O'Brien")),
+            ("active".to_string(), json!(null)),
+        ]
+        .into_iter()
+        .collect()];
+
+        assert_eq!(
+            render_clipboard_text(ClipboardFormat::Insert, Some("users"), &columns, &rows, &[]).unwrap(),
+            "INSERT INTO \"users\" (\"id\", \"name\", \"active\") VALUES (7, 'O''Brien', NULL);"
+        );
+        assert!(render_clipboard_text(ClipboardFormat::Insert, None, &columns, &rows, &[]).is_err());
+
+        // BLOBs reach the frontend base64-encoded and go back out as hex literals
+        let blob_row = vec![[("id".to_string(), json!(1)), ("data".to_string(), json!("AP8Q"))].into_iter().collect()];
+        let blob_columns = vec!["id".to_string(), "data".to_string()];
+        assert_eq!(
+            render_clipboard_text(ClipboardFormat::Insert, Some("files"), &blob_columns, &blob_row, &["data".to_string()])
+                .unwrap(),
+            "INSERT INTO \"files\" (\"id\", \"data\") VALUES (1, X'00FF10');"
+        );
+        assert_eq!(ClipboardFormat::parse("md").unwrap(), ClipboardFormat::Markdown);
+    }
+
+    #[test]
+    fn test_export_format_parse() {
+        assert_eq!(ExportFormat::parse("CSV").unwrap(), ExportFormat::Csv);
+        assert_eq!(ExportFormat::parse("json").unwrap(), ExportFormat::Json);
+        assert!(ExportFormat::parse("pdf").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_selected_rows_by_key_values() {
+        let temp_dir = TempDir::new().unwrap();
+        let pool = test_pool(&temp_dir).await;
+
+        let selection = RowSelection {
+            key_column: Some("id".to_string()),
+            key_values: vec![json!(1), json!(3)],
+            filter: None,
+        };
+        let (columns, rows) = fetch_selected_rows(&pool, "users", &selection).await.unwrap();
+
+        assert_eq!(columns[0], "id");
+        assert_eq!(rows.len(), 2);
+        assert!(rows.iter().all(|row| row["id"] != json!(2)));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_selected_rows_combines_keys_and_filter() {
+        let temp_dir = TempDir::new().unwrap();
+        let pool = test_pool(&temp_dir).await;
+
+        let selection = RowSelection {
+            key_column: None,
+            key_values: vec![json!(1), json!(2), json!(3)],
+            filter: Some(RowFilter {
+                conditions: vec![FilterCondition {
+                    column: "age".to_string(),
+                    operator: FilterOperator::Gt,
+                    value: Some(json!(26)),
+                    values: None,
+                }],
+                combinator: FilterCombinator::And,
+            }),
+        };
+        let (_, rows) = fetch_selected_rows(&pool, "users", &selection).await.unwrap();
+
+        assert_eq!(rows.len(), 2);
+        assert!(rows.iter().all(|row| row["age"].as_i64().unwrap() > 26));
+    }
+
+    #[test]
+    fn test_build_selection_query_binds_filter_values() {
+        let columns = vec!["id".to_string(), "name".to_string()];
+        let selection = RowSelection {
+            key_column: Some("id".to_string()),
+            key_values: vec![json!(1)],
+            filter: Some(RowFilter {
+                conditions: vec![FilterCondition {
+                    column: "name".to_string(),
+                    operator: FilterOperator::Eq,
+                    value: Some(json!("x'); DELETE FROM users; --")),
+                    values: None,
+                }],
+                combinator: FilterCombinator::And,
+            }),
+        };
+        let (query, params) = build_selection_query("users", &columns, &selection).unwrap();
+        assert_eq!(query, "SELECT \"id\", \"name\" FROM \"users\" WHERE \"id\" IN (?) AND (\"name\" = ?)");
+        assert_eq!(params, vec![json!(1), json!("x'); DELETE FROM users; --")]);
+
+        let unknown_column = RowSelection {
+            filter: Some(RowFilter {
+                conditions: vec![FilterCondition {
+                    column: "1); DELETE FROM users; --".to_string(),
+                    operator: FilterOperator::IsNull,
+                    value: None,
+                    values: None,
+                }],
+                combinator: FilterCombinator::And,
+            }),
+            ..Default::default()
+        };
+        assert!(build_selection_query("users", &columns, &unknown_column).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_selected_rows_requires_selection() {
+        let temp_dir = TempDir::new().unwrap();
+        let pool = test_pool(&temp_dir).await;
+
+        let result = fetch_selected_rows(&pool, "users", &RowSelection::default()).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_export_rows_to_file_writes_json() {
+        let temp_dir = TempDir::new().unwrap();
+        let pool = test_pool(&temp_dir).await;
+        let output = temp_dir.path().join("users.json");
+
+        let source = ExportSource::Selection {
+            table_name: "users".to_string(),
+            selection: RowSelection {
+                key_column: Some("id".to_string()),
+                key_values: vec![json!(2)],
+                filter: None,
+            },
+        };
+        let result = export_rows_to_file(
+            &pool,
+            &source,
+            ExportFormat::Json,
+            output.to_str().unwrap(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result.row_count, 1);
+        let exported: Vec<serde_json::Value> =
+            serde_json::from_str(&std::fs::read_to_string(&output).unwrap()).unwrap();
+        assert_eq!(exported[0]["id"], json!(2));
+    }
+
+    #[test]
+    fn test_infer_xlsx_column_kind() {
+        let rows: Vec<ResultRow> = vec![
+            [
+                ("n".to_string(), json!(1.5)),
+                ("b".to_string(), json!(true)),
+                ("big".to_string(), json!(i64::MAX)),
+                ("mixed".to_string(), json!(1)),
+            ]
+            .into_iter()
+            .collect(),
+            [
+                ("n".to_string(), json!(null)),
+                ("b".to_string(), json!(false)),
+                ("big".to_string(), json!(1)),
+                ("mixed".to_string(), json!("x")),
+            ]
+            .into_iter()
+            .collect(),
+        ];
+
+        assert_eq!(infer_xlsx_column_kind("n", &rows), XlsxColumnKind::Number);
+        assert_eq!(infer_xlsx_column_kind("b", &rows), XlsxColumnKind::Boolean);
+        assert_eq!(infer_xlsx_column_kind("big", &rows), XlsxColumnKind::Text);
+        assert_eq!(infer_xlsx_column_kind("mixed", &rows), XlsxColumnKind::Text);
+        assert_eq!(infer_xlsx_column_kind("missing", &rows), XlsxColumnKind::Text);
+    }
+
+    #[tokio::test]
+    async fn test_export_query_results_as_xlsx() {
+        let temp_dir = TempDir::new().unwrap();
+        let pool = test_pool(&temp_dir).await;
+        let output = temp_dir.path().join("users.xlsx");
+
+        let source = ExportSource::Query("SELECT id, name FROM users".to_string());
+        let result = export_rows_to_file(&pool, &source, ExportFormat::Xlsx, output.to_str().unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(result.row_count, 3);
+        // xlsx files are zip archives
+        let bytes = std::fs::read(&output).unwrap();
+        assert_eq!(&bytes[..2], b"PK");
+    }
+
+    #[tokio::test]
+    async fn test_export_query_rejects_write_statements() {
+        let temp_dir = TempDir::new().unwrap();
+        let pool = test_pool(&temp_dir).await;
+
+        let source = ExportSource::Query("DELETE FROM users".to_string());
+        assert!(fetch_export_source(&pool, &source).await.is_err());
+    }
+}
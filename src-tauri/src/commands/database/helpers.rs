@@ -1,10 +1,40 @@
 // Database helpers - exact copy from original database.rs
 // Database helpers with safe default value generation
 
+use base64::{engine::general_purpose, Engine as _};
 use rusqlite::Connection;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Column, Row, TypeInfo, ValueRef};
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
+/// Every column of every user table in one round trip, in sqlite_master then column order
+pub const ALL_TABLE_COLUMNS_QUERY: &str = "SELECT m.name AS table_name, p.* FROM sqlite_master m \
+     JOIN pragma_table_info(m.name) p \
+     WHERE m.type = 'table' AND m.name NOT LIKE 'sqlite_%' ORDER BY m.rowid, p.cid";
+
+/// Column info for the table bound as the only parameter. Unlike `PRAGMA table_info(<name>)`
+/// the SQL is the same for every table, so each connection compiles it once and reuses it.
+pub const TABLE_INFO_QUERY: &str = "SELECT * FROM pragma_table_info(?)";
+
+/// Quote an identifier for interpolation into SQL
+pub(crate) fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Column metadata from a `pragma_table_info` row
+pub fn column_info_from_row(row: &SqliteRow) -> crate::commands::database::types::ColumnInfo {
+    let type_name = row.get::<String, _>("type");
+    crate::commands::database::types::ColumnInfo {
+        name: row.get::<String, _>("name"),
+        default_value: get_default_value_for_type(&type_name),
+        type_name,
+        notnull: row.get::<i64, _>("notnull") != 0,
+        pk: row.get::<i64, _>("pk") != 0,
+    }
+}
+
 // Helper to get default values for column types
 pub fn get_default_value_for_type(type_name: &str) -> serde_json::Value {
     match type_name.to_uppercase().as_str() {
@@ -76,6 +106,52 @@ pub fn prepare_sqlite_file_for_sync(db_path: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Realm files carry the "T-DB" mnemonic right after the two 8-byte top refs of their header.
+fn has_realm_header(path: &Path) -> bool {
+    use std::io::Read;
+
+    let mut header = [0u8; 24];
+    match fs::File::open(path) {
+        Ok(mut file) => file.read_exact(&mut header).is_ok() && &header[16..20] == b"T-DB",
+        Err(_) => false,
+    }
+}
+
+/// Reject database formats Flippio can detect but not open yet, with an explanation for the user
+pub fn ensure_supported_database_format(db_path: &str) -> Result<(), String> {
+    let path = Path::new(db_path);
+    let is_realm = db_path.to_lowercase().ends_with(".realm") || has_realm_header(path);
+
+    if is_realm {
+        return Err(format!(
+            "'{}' is a Realm database. Flippio can list Realm files but only SQLite databases can be opened for now.",
+            path.file_name().and_then(|n| n.to_str()).unwrap_or(db_path)
+        ));
+    }
+
+    Ok(())
+}
+
+/// Copy a database file together with its -wal/-shm companions.
+/// Committed data may still live in the WAL, so the main file alone can be stale.
+pub fn copy_database_with_companions(source: &Path, target: &Path) -> Result<(), String> {
+    fs::copy(source, target).map_err(|e| format!("Failed to copy database: {}", e))?;
+
+    for suffix in ["-wal", "-shm"] {
+        let companion = format!("{}{}", source.display(), suffix);
+        let companion_target = format!("{}{}", target.display(), suffix);
+        if Path::new(&companion).exists() {
+            fs::copy(&companion, &companion_target)
+                .map_err(|e| format!("Failed to copy {} file: {}", suffix, e))?;
+        } else if Path::new(&companion_target).exists() {
+            // Don't leave a stale companion from an earlier copy next to the new one
+            let _ = fs::remove_file(&companion_target);
+        }
+    }
+
+    Ok(())
+}
+
 pub fn ensure_database_file_permissions(db_path: &str) -> Result<(), String> {
     #[cfg(unix)]
     {
@@ -239,12 +315,193 @@ pub fn ensure_database_file_permissions(db_path: &str) -> Result<(), String> {
     }
 }
 
+// Largest integer JavaScript can represent exactly (Number.MAX_SAFE_INTEGER)
+const MAX_SAFE_JSON_INTEGER: i64 = (1 << 53) - 1;
+
+/// INTEGER values outside the range JavaScript numbers hold exactly are sent as strings,
+/// so 64-bit ids and timestamps in nanoseconds survive the trip to the frontend.
+pub fn integer_to_json(value: i64) -> serde_json::Value {
+    if (-MAX_SAFE_JSON_INTEGER..=MAX_SAFE_JSON_INTEGER).contains(&value) {
+        serde_json::Value::from(value)
+    } else {
+        serde_json::Value::String(value.to_string())
+    }
+}
+
+/// JSON has no NaN or infinity; SQLite can store ±Inf (e.g. `9e999`), so keep it as text.
+pub fn real_to_json(value: f64) -> serde_json::Value {
+    match serde_json::Number::from_f64(value) {
+        Some(number) => serde_json::Value::Number(number),
+        None if value.is_infinite() && value > 0.0 => serde_json::Value::String("Infinity".to_string()),
+        None if value.is_infinite() => serde_json::Value::String("-Infinity".to_string()),
+        None => serde_json::Value::Null,
+    }
+}
+
+/// Decode one cell by its storage class rather than its declared column type.
+///
+/// SQLite columns declared BOOLEAN, DATE, NUMERIC, DECIMAL or anything else can hold values of
+/// any storage class, so the declared type is only a hint. Storage classes map to JSON as:
+/// INTEGER → number (string beyond ±2^53), REAL → number, TEXT → string (invalid UTF-8 is
+/// replaced rather than dropped, and decimal text keeps its exact digits), BLOB → base64 string.
+pub fn decode_sqlite_value(row: &SqliteRow, index: usize) -> serde_json::Value {
+    let raw_value = match row.try_get_raw(index) {
+        Ok(raw_value) => raw_value,
+        Err(_) => return serde_json::Value::Null,
+    };
+    if raw_value.is_null() {
+        return serde_json::Value::Null;
+    }
+
+    let storage_class = raw_value.type_info().name().to_string();
+    match storage_class.as_str() {
+        "INTEGER" => row
+            .try_get_unchecked::<i64, _>(index)
+            .map(integer_to_json)
+            .unwrap_or(serde_json::Value::Null),
+        "REAL" => row
+            .try_get_unchecked::<f64, _>(index)
+            .map(real_to_json)
+            .unwrap_or(serde_json::Value::Null),
+        "BLOB" => row
+            .try_get_unchecked::<Vec<u8>, _>(index)
+            .map(|bytes| serde_json::Value::String(general_purpose::STANDARD.encode(bytes)))
+            .unwrap_or(serde_json::Value::Null),
+        _ => match row.try_get_unchecked::<String, _>(index) {
+            Ok(text) => serde_json::Value::String(text),
+            Err(_) => row
+                .try_get_unchecked::<Vec<u8>, _>(index)
+                .map(|bytes| serde_json::Value::String(String::from_utf8_lossy(&bytes).into_owned()))
+                .unwrap_or(serde_json::Value::Null),
+        },
+    }
+}
+
+/// Decode every column of a row into a JSON object keyed by column name.
+/// This is the one decoding path for query results; when a query returns the same
+/// column name twice, the right-most value wins.
+pub fn decode_row_to_json(row: &SqliteRow) -> HashMap<String, serde_json::Value> {
+    row.columns()
+        .iter()
+        .enumerate()
+        .map(|(i, column)| (column.name().to_string(), decode_sqlite_value(row, i)))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use sqlx::sqlite::SqlitePool;
     use tempfile::TempDir;
     use std::fs::File;
 
+    #[test]
+    fn test_integer_and_real_to_json() {
+        assert_eq!(integer_to_json(42), serde_json::json!(42));
+        assert_eq!(integer_to_json(MAX_SAFE_JSON_INTEGER), serde_json::json!(9007199254740991_i64));
+        assert_eq!(integer_to_json(i64::MAX), serde_json::json!("9223372036854775807"));
+        assert_eq!(integer_to_json(-MAX_SAFE_JSON_INTEGER - 1), serde_json::json!("-9007199254740992"));
+
+        assert_eq!(real_to_json(1.5), serde_json::json!(1.5));
+        assert_eq!(real_to_json(f64::INFINITY), serde_json::json!("Infinity"));
+        assert_eq!(real_to_json(f64::NEG_INFINITY), serde_json::json!("-Infinity"));
+        assert_eq!(real_to_json(f64::NAN), serde_json::Value::Null);
+    }
+
+    #[tokio::test]
+    async fn test_decode_sqlite_value_uses_storage_class() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = TempDir::new()?;
+        let db_path = temp_dir.path().join("quirks.db");
+        Connection::open(&db_path)?.execute_batch(
+            "CREATE TABLE quirks (flag BOOLEAN, day DATE, amount NUMERIC, price TEXT, big INTEGER, anything, data BLOB, ratio REAL);
+             INSERT INTO quirks VALUES (1, '2024-05-01', 12.5, '12345678901234567890.12', 9223372036854775807, 'text', x'00ff', 9e999);
+             INSERT INTO quirks VALUES (0, 20240501, 'n/a', 3, -5, 7, 'blob as text', 2);
+             INSERT INTO quirks VALUES (NULL, NULL, NULL, NULL, NULL, CAST(x'c328' AS TEXT), NULL, NULL);",
+        )?;
+
+        let pool = SqlitePool::connect(&format!("sqlite:{}", db_path.display())).await?;
+        let rows = sqlx::query("SELECT * FROM quirks ORDER BY rowid").fetch_all(&pool).await?;
+        pool.close().await;
+        let decoded: Vec<Vec<serde_json::Value>> = rows
+            .iter()
+            .map(|row| (0..row.columns().len()).map(|i| decode_sqlite_value(row, i)).collect())
+            .collect();
+
+        assert_eq!(
+            decoded[0],
+            vec![
+                serde_json::json!(1),
+                serde_json::json!("2024-05-01"),
+                serde_json::json!(12.5),
+                // Decimal text keeps its exact digits
+                serde_json::json!("12345678901234567890.12"),
+                serde_json::json!("9223372036854775807"),
+                serde_json::json!("text"),
+                serde_json::json!("AP8="),
+                serde_json::json!("Infinity"),
+            ]
+        );
+        assert_eq!(
+            decoded[1],
+            vec![
+                serde_json::json!(0),
+                serde_json::json!(20240501),
+                serde_json::json!("n/a"),
+                serde_json::json!("3"),
+                serde_json::json!(-5),
+                serde_json::json!(7),
+                serde_json::json!("blob as text"),
+                serde_json::json!(2.0),
+            ]
+        );
+        assert_eq!(decoded[2][0], serde_json::Value::Null);
+        // Invalid UTF-8 stored as TEXT is replaced instead of dropped
+        assert_eq!(decoded[2][5], serde_json::json!("\u{fffd}("));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_decode_row_to_json() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = TempDir::new()?;
+        let db_path = temp_dir.path().join("rows.db");
+        Connection::open(&db_path)?.execute_batch(
+            "CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT, data BLOB);
+             INSERT INTO items VALUES (7, 'seven', NULL);",
+        )?;
+
+        let pool = SqlitePool::connect(&format!("sqlite:{}", db_path.display())).await?;
+        let row = sqlx::query("SELECT rowid AS __flippio_rowid, *, upper(name) AS name, 1 + 1 AS two FROM items")
+            .fetch_one(&pool)
+            .await?;
+        pool.close().await;
+
+        let decoded = decode_row_to_json(&row);
+        assert_eq!(decoded.len(), 5);
+        assert_eq!(decoded["__flippio_rowid"], serde_json::json!(7));
+        assert_eq!(decoded["id"], serde_json::json!(7));
+        // Duplicate column names keep the right-most value
+        assert_eq!(decoded["name"], serde_json::json!("SEVEN"));
+        assert_eq!(decoded["data"], serde_json::Value::Null);
+        assert_eq!(decoded["two"], serde_json::json!(2));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_decode_row_to_json_empty_result_columns() -> Result<(), Box<dyn std::error::Error>> {
+        let pool = SqlitePool::connect("sqlite::memory:").await?;
+        let row = sqlx::query("SELECT NULL AS nothing, '' AS empty, x'' AS no_bytes").fetch_one(&pool).await?;
+        pool.close().await;
+
+        let decoded = decode_row_to_json(&row);
+        assert_eq!(decoded["nothing"], serde_json::Value::Null);
+        assert_eq!(decoded["empty"], serde_json::json!(""));
+        assert_eq!(decoded["no_bytes"], serde_json::json!(""));
+
+        Ok(())
+    }
+
     #[test]
     fn test_get_default_value_for_type_integer() {
         let result = get_default_value_for_type("INTEGER");
@@ -382,4 +639,28 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_ensure_supported_database_format_rejects_realm() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = TempDir::new()?;
+
+        let realm_path = temp_dir.path().join("default.realm");
+        File::create(&realm_path)?;
+        let error = ensure_supported_database_format(realm_path.to_str().unwrap()).unwrap_err();
+        assert!(error.contains("Realm database"));
+
+        // Realm files are also recognised by header when the extension is missing
+        let disguised_path = temp_dir.path().join("store.db");
+        let mut header = vec![0u8; 16];
+        header.extend_from_slice(b"T-DB");
+        header.extend_from_slice(&[0u8; 8]);
+        fs::write(&disguised_path, header)?;
+        assert!(ensure_supported_database_format(disguised_path.to_str().unwrap()).is_err());
+
+        let sqlite_path = temp_dir.path().join("main.db");
+        Connection::open(&sqlite_path)?.execute("CREATE TABLE t (id INTEGER)", [])?;
+        assert!(ensure_supported_database_format(sqlite_path.to_str().unwrap()).is_ok());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,289 @@
+// INSERT statement generation
+// Turns chosen rows into parameter-free INSERT statements, e.g. for test fixtures
+
+use crate::commands::database::bulk_update::RowFilter;
+use crate::commands::database::commands::bind_json_values;
+use crate::commands::database::connection_access::get_current_pool;
+use crate::commands::database::export::{build_selection_query, read_table_columns, RowSelection};
+use crate::commands::database::helpers::quote_ident;
+use crate::commands::database::types::*;
+use base64::{engine::general_purpose, Engine as _};
+use sqlx::sqlite::{SqlitePool, SqliteRow};
+use sqlx::{Row, TypeInfo, ValueRef};
+use tauri::State;
+
+fn hex_blob_literal(bytes: &[u8]) -> String {
+    let hex: String = bytes.iter().map(|byte| format!("{:02X}", byte)).collect();
+    format!("X'{}'", hex)
+}
+
+fn text_literal(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+fn real_literal(value: f64) -> String {
+    if value.is_nan() {
+        "NULL".to_string()
+    } else if value.is_infinite() {
+        // SQLite parses out-of-range literals as +/-Inf
+        if value > 0.0 { "1e999" } else { "-1e999" }.to_string()
+    } else {
+        // Debug formatting keeps the decimal point so the value stays REAL
+        format!("{:?}", value)
+    }
+}
+
+/// SQL literal for a column value, based on the value's actual storage class.
+fn sql_literal_from_row(row: &SqliteRow, idx: usize) -> String {
+    let Ok(raw_value) = row.try_get_raw(idx) else {
+        return "NULL".to_string();
+    };
+    if raw_value.is_null() {
+        return "NULL".to_string();
+    }
+
+    let storage_class = raw_value.type_info().name().to_string();
+    match storage_class.as_str() {
+        "INTEGER" => row
+            .try_get::<i64, _>(idx)
+            .map(|value| value.to_string())
+            .unwrap_or_else(|_| "NULL".to_string()),
+        "REAL" => row
+            .try_get::<f64, _>(idx)
+            .map(real_literal)
+            .unwrap_or_else(|_| "NULL".to_string()),
+        "BLOB" => row
+            .try_get::<Vec<u8>, _>(idx)
+            .map(|bytes| hex_blob_literal(&bytes))
+            .unwrap_or_else(|_| "NULL".to_string()),
+        _ => match row.try_get::<String, _>(idx) {
+            Ok(text) => text_literal(&text),
+            // Text that is not valid UTF-8 is kept byte-exact as a cast blob
+            Err(_) => row
+                .try_get::<Vec<u8>, _>(idx)
+                .map(|bytes| format!("CAST({} AS TEXT)", hex_blob_literal(&bytes)))
+                .unwrap_or_else(|_| "NULL".to_string()),
+        },
+    }
+}
+
+/// SQL literal for a cell the frontend holds as JSON. BLOBs arrive base64-encoded, so values of
+/// `blob` columns become hex literals again; anything that doesn't decode stays text.
+pub(crate) fn json_sql_literal(value: &serde_json::Value, blob: bool) -> String {
+    match value {
+        serde_json::Value::Null => "NULL".to_string(),
+        serde_json::Value::Bool(b) => if *b { "1" } else { "0" }.to_string(),
+        serde_json::Value::Number(n) => n.to_string(),
+        serde_json::Value::String(s) if blob => match general_purpose::STANDARD.decode(s) {
+            Ok(bytes) => hex_blob_literal(&bytes),
+            Err(_) => text_literal(s),
+        },
+        serde_json::Value::String(s) => text_literal(s),
+        other => text_literal(&other.to_string()),
+    }
+}
+
+/// One INSERT statement per row of literals in `columns` order. The single renderer behind
+/// `db_generate_inserts` and copying rows as INSERT statements.
+pub(crate) fn render_insert_statements(
+    table_name: &str,
+    columns: &[String],
+    rows: impl IntoIterator<Item = Vec<String>>,
+) -> Vec<String> {
+    let column_list = columns
+        .iter()
+        .map(|column| quote_ident(column))
+        .collect::<Vec<_>>()
+        .join(", ");
+    rows.into_iter()
+        .map(|values| {
+            format!(
+                "INSERT INTO {} ({}) VALUES ({});",
+                quote_ident(table_name),
+                column_list,
+                values.join(", ")
+            )
+        })
+        .collect()
+}
+
+async fn read_create_statement(pool: &SqlitePool, table_name: &str) -> Result<String, String> {
+    let row = sqlx::query("SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?")
+        .bind(table_name)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Error reading table definition: {}", e))?
+        .ok_or_else(|| format!("Table '{}' does not exist", table_name))?;
+
+    let sql: String = row
+        .try_get("sql")
+        .map_err(|e| format!("Error reading table definition: {}", e))?;
+    Ok(format!("{};", sql.trim_end_matches(';')))
+}
+
+pub(crate) async fn generate_insert_statements(
+    pool: &SqlitePool,
+    table_name: &str,
+    selection: &RowSelection,
+    include_create_table: bool,
+) -> Result<String, String> {
+    let columns = read_table_columns(pool, table_name).await?;
+    // Columns are selected in `columns` order, so positions line up
+    let (query, params) = build_selection_query(table_name, &columns, selection)?;
+
+    let rows = bind_json_values(sqlx::query(&query), &params)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Error reading rows: {}", e))?;
+
+    let mut statements = Vec::with_capacity(rows.len() + 1);
+    if include_create_table {
+        statements.push(read_create_statement(pool, table_name).await?);
+    }
+
+    let literals = rows
+        .iter()
+        .map(|row| (0..columns.len()).map(|idx| sql_literal_from_row(row, idx)).collect());
+    statements.extend(render_insert_statements(table_name, &columns, literals));
+
+    Ok(statements.join("\n"))
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn db_generate_inserts(
+    state: State<'_, DbPool>,
+    db_cache: State<'_, DbConnectionCache>,
+    table_name: String,
+    key_column: Option<String>,
+    key_values: Option<Vec<serde_json::Value>>,
+    filter: Option<RowFilter>,
+    include_create_table: Option<bool>,
+    current_db_path: Option<String>,
+) -> Result<DbResponse<String>, String> {
+    log::info!("📝 Generating INSERT statements for table '{}'", table_name);
+
+    let selection = RowSelection {
+        key_column,
+        key_values: key_values.unwrap_or_default(),
+        filter,
+    };
+
+    let result = async {
+        let pool = get_current_pool(&state, &db_cache, current_db_path).await?;
+        generate_insert_statements(
+            &pool,
+            &table_name,
+            &selection,
+            include_create_table.unwrap_or(false),
+        )
+        .await
+    }
+    .await;
+
+    match result {
+        Ok(sql) => Ok(DbResponse {
+            success: true,
+            data: Some(sql),
+            error: None,
+        }),
+        Err(e) => {
+            log::error!("❌ Failed to generate INSERT statements: {}", e);
+            Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::database::bulk_update::{FilterCombinator, FilterCondition, FilterOperator};
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    async fn fixture_pool(temp_dir: &TempDir) -> SqlitePool {
+        let db_path = temp_dir.path().join("fixtures.db");
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT, price REAL, data BLOB);
+             INSERT INTO items VALUES (1, 'it''s', 2.0, x'00FF10');
+             INSERT INTO items VALUES (2, NULL, 0.5, NULL);",
+        )
+        .unwrap();
+        drop(conn);
+
+        SqlitePool::connect(&format!("sqlite:{}", db_path.display()))
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn test_literal_helpers() {
+        assert_eq!(hex_blob_literal(&[0x00, 0xab]), "X'00AB'");
+        assert_eq!(real_literal(2.0), "2.0");
+        assert_eq!(real_literal(f64::NEG_INFINITY), "-1e999");
+        assert_eq!(real_literal(f64::NAN), "NULL");
+        assert_eq!(json_sql_literal(&serde_json::json!("AP8Q"), true), "X'00FF10'");
+        assert_eq!(json_sql_literal(&serde_json::json!("not base64!"), true), "'not base64!'");
+        assert_eq!(json_sql_literal(&serde_json::json!("AP8Q"), false), "'AP8Q'");
+    }
+
+    #[tokio::test]
+    async fn test_generate_insert_statements_quotes_values() {
+        let temp_dir = TempDir::new().unwrap();
+        let pool = fixture_pool(&temp_dir).await;
+
+        let selection = RowSelection {
+            key_column: Some("id".to_string()),
+            key_values: vec![json!(1), json!(2)],
+            filter: None,
+        };
+        let sql = generate_insert_statements(&pool, "items", &selection, false)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            sql,
+            "INSERT INTO \"items\" (\"id\", \"name\", \"price\", \"data\") VALUES (1, 'it''s', 2.0, X'00FF10');\n\
+             INSERT INTO \"items\" (\"id\", \"name\", \"price\", \"data\") VALUES (2, NULL, 0.5, NULL);"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_generated_statements_reproduce_rows() {
+        let temp_dir = TempDir::new().unwrap();
+        let pool = fixture_pool(&temp_dir).await;
+
+        let selection = RowSelection {
+            filter: Some(RowFilter {
+                conditions: vec![FilterCondition {
+                    column: "id".to_string(),
+                    operator: FilterOperator::IsNotNull,
+                    value: None,
+                    values: None,
+                }],
+                combinator: FilterCombinator::And,
+            }),
+            ..Default::default()
+        };
+        let sql = generate_insert_statements(&pool, "items", &selection, true)
+            .await
+            .unwrap();
+
+        let copy = rusqlite::Connection::open_in_memory().unwrap();
+        copy.execute_batch(&sql).unwrap();
+        let (name, price, data): (String, f64, Vec<u8>) = copy
+            .query_row("SELECT name, price, data FROM items WHERE id = 1", [], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })
+            .unwrap();
+
+        assert_eq!(name, "it's");
+        assert_eq!(price, 2.0);
+        assert_eq!(data, vec![0x00, 0xFF, 0x10]);
+    }
+}
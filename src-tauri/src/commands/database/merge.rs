@@ -0,0 +1,678 @@
+// Three-way merge
+// Merges the edits made to a pulled copy (local) with the changes the app made on the device
+// since the pull (remote), using the copy taken at pull time (base) as the common ancestor
+
+use crate::commands::database::compare::{diff_result_rows, run_compared_query, values_signature, ResultRow};
+use crate::commands::database::connection_access::open_uncached_connection;
+use crate::commands::database::helpers::{copy_database_with_companions, quote_ident};
+use crate::commands::database::migration::{read_database_schema, DatabaseSchema, SchemaTable};
+use crate::commands::database::types::*;
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqlitePool;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+// Rows read per table and side; larger tables are reported as conflicts instead of merged
+const MAX_MERGE_ROWS_PER_TABLE: usize = 50_000;
+// Tables without a primary key are matched by rowid, read under this alias
+const ROWID_KEY: &str = "__flippio_rowid";
+const LOCAL_SCHEMA: &str = "merge_local";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MergeSide {
+    Local,
+    Remote,
+}
+
+/// How the user settled a conflict reported by an earlier merge attempt
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictResolution {
+    pub table: String,
+    pub key: ResultRow,
+    pub keep: MergeSide,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictKind {
+    // Both sides changed the same columns of a row to different values
+    BothUpdated,
+    // Both sides inserted a row with the same key but different values
+    BothInserted,
+    LocalUpdatedRemoteDeleted,
+    LocalDeletedRemoteUpdated,
+    // The table can't be merged row by row (schema change, too many rows)
+    Table,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeConflict {
+    pub table: String,
+    pub kind: ConflictKind,
+    // Empty for table conflicts
+    pub key: ResultRow,
+    pub columns: Vec<String>,
+    pub base: Option<ResultRow>,
+    pub local: Option<ResultRow>,
+    pub remote: Option<ResultRow>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ThreeWayMerge {
+    // Copy of the remote database with the local changes applied
+    pub merged_path: String,
+    pub applied_changes: usize,
+    pub conflicts: Vec<MergeConflict>,
+    // The remote database had changed since the pull
+    pub remote_changed: bool,
+}
+
+/// A local change to apply to the merged database; values are copied from the local database
+#[derive(Debug, Clone, PartialEq)]
+enum MergeAction {
+    Upsert { key: ResultRow },
+    UpdateColumns { key: ResultRow, columns: Vec<String> },
+    Delete { key: ResultRow },
+}
+
+#[derive(Debug, Default)]
+struct TableMergePlan {
+    actions: Vec<MergeAction>,
+    conflicts: Vec<MergeConflict>,
+}
+
+// Actions of one table together with what is needed to turn them into SQL
+struct TableActions {
+    table: String,
+    columns: Vec<String>,
+    key_columns: Vec<String>,
+    actions: Vec<MergeAction>,
+}
+
+#[derive(Debug, Clone)]
+enum RowChange {
+    Inserted(ResultRow),
+    Updated { before: ResultRow, after: ResultRow, columns: Vec<String> },
+    Deleted(ResultRow),
+}
+
+impl RowChange {
+    fn before(&self) -> Option<&ResultRow> {
+        match self {
+            Self::Inserted(_) => None,
+            Self::Updated { before, .. } => Some(before),
+            Self::Deleted(before) => Some(before),
+        }
+    }
+
+    fn after(&self) -> Option<&ResultRow> {
+        match self {
+            Self::Inserted(after) | Self::Updated { after, .. } => Some(after),
+            Self::Deleted(_) => None,
+        }
+    }
+}
+
+fn key_of(row: &ResultRow, key_columns: &[String]) -> ResultRow {
+    key_columns
+        .iter()
+        .map(|column| (column.clone(), row.get(column).cloned().unwrap_or(serde_json::Value::Null)))
+        .collect()
+}
+
+/// Changes from `base` to `side`, by key signature.
+fn row_changes(base: &[ResultRow], side: &[ResultRow], key_columns: &[String]) -> HashMap<String, (ResultRow, RowChange)> {
+    let diff = diff_result_rows(base, side, key_columns);
+    let deleted = diff
+        .only_in_left
+        .into_iter()
+        .map(|row| (key_of(&row, key_columns), RowChange::Deleted(row)));
+    let inserted = diff
+        .only_in_right
+        .into_iter()
+        .map(|row| (key_of(&row, key_columns), RowChange::Inserted(row)));
+    let updated = diff.changed.into_iter().map(|row| {
+        let change = RowChange::Updated {
+            before: row.left,
+            after: row.right,
+            columns: row.changed_columns,
+        };
+        (row.key, change)
+    });
+
+    deleted
+        .chain(inserted)
+        .chain(updated)
+        .map(|(key, change)| (values_signature(&key, key_columns), (key, change)))
+        .collect()
+}
+
+/// The action that makes the merged row equal to the local one.
+fn take_local(key: ResultRow, change: &RowChange) -> MergeAction {
+    match change {
+        RowChange::Deleted(_) => MergeAction::Delete { key },
+        _ => MergeAction::Upsert { key },
+    }
+}
+
+/// Merge the rows of one table. Local changes are applied unless the remote side changed the
+/// same row in an incompatible way; those become conflicts unless `resolutions` settles them.
+fn merge_table_rows(
+    table: &str,
+    base: &[ResultRow],
+    local: &[ResultRow],
+    remote: &[ResultRow],
+    key_columns: &[String],
+    resolutions: &[ConflictResolution],
+) -> TableMergePlan {
+    let remote_changes = row_changes(base, remote, key_columns);
+    let mut local_changes: Vec<_> = row_changes(base, local, key_columns).into_iter().collect();
+    local_changes.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut plan = TableMergePlan::default();
+    for (signature, (key, change)) in local_changes {
+        let Some((_, remote_change)) = remote_changes.get(&signature) else {
+            plan.actions.push(match &change {
+                RowChange::Updated { columns, .. } => MergeAction::UpdateColumns {
+                    key,
+                    columns: columns.clone(),
+                },
+                _ => take_local(key, &change),
+            });
+            continue;
+        };
+
+        let (kind, columns) = match (&change, remote_change) {
+            (RowChange::Deleted(_), RowChange::Deleted(_)) => continue,
+            (RowChange::Inserted(local_row), RowChange::Inserted(remote_row)) => {
+                let mut columns: Vec<String> = local_row
+                    .keys()
+                    .filter(|column| local_row.get(*column) != remote_row.get(*column))
+                    .cloned()
+                    .collect();
+                if columns.is_empty() {
+                    continue;
+                }
+                columns.sort();
+                (ConflictKind::BothInserted, columns)
+            }
+            (
+                RowChange::Updated { after: local_row, columns: local_columns, .. },
+                RowChange::Updated { after: remote_row, columns: remote_columns, .. },
+            ) => {
+                let overlapping: Vec<String> = local_columns
+                    .iter()
+                    .filter(|column| remote_columns.contains(column) && local_row.get(*column) != remote_row.get(*column))
+                    .cloned()
+                    .collect();
+                if overlapping.is_empty() {
+                    plan.actions.push(MergeAction::UpdateColumns {
+                        key,
+                        columns: local_columns.clone(),
+                    });
+                    continue;
+                }
+                (ConflictKind::BothUpdated, overlapping)
+            }
+            (RowChange::Updated { columns, .. }, RowChange::Deleted(_)) => {
+                (ConflictKind::LocalUpdatedRemoteDeleted, columns.clone())
+            }
+            (RowChange::Deleted(_), RowChange::Updated { columns, .. }) => {
+                (ConflictKind::LocalDeletedRemoteUpdated, columns.clone())
+            }
+            // Inserts can't meet updates or deletes of the same key, the base row either exists or not
+            _ => (ConflictKind::BothUpdated, Vec::new()),
+        };
+
+        let resolution = resolutions
+            .iter()
+            .find(|resolution| resolution.table == table && values_signature(&resolution.key, key_columns) == signature);
+        match resolution.map(|resolution| resolution.keep) {
+            Some(MergeSide::Local) => plan.actions.push(take_local(key, &change)),
+            Some(MergeSide::Remote) => {}
+            None => plan.conflicts.push(MergeConflict {
+                table: table.to_string(),
+                kind,
+                key,
+                columns,
+                base: change.before().cloned(),
+                local: change.after().cloned(),
+                remote: remote_change.after().cloned(),
+                message: None,
+            }),
+        }
+    }
+    plan
+}
+
+fn table_conflict(table: &str, message: impl Into<String>) -> MergeConflict {
+    MergeConflict {
+        table: table.to_string(),
+        kind: ConflictKind::Table,
+        key: ResultRow::new(),
+        columns: Vec::new(),
+        base: None,
+        local: None,
+        remote: None,
+        message: Some(message.into()),
+    }
+}
+
+fn column_names(table: &SchemaTable) -> Vec<String> {
+    table.columns.iter().map(|column| column.name.clone()).collect()
+}
+
+fn key_columns_for(table: &SchemaTable) -> Vec<String> {
+    let mut pk_columns: Vec<_> = table.columns.iter().filter(|column| column.pk > 0).collect();
+    pk_columns.sort_by_key(|column| column.pk);
+    let key: Vec<String> = pk_columns.into_iter().map(|column| column.name.clone()).collect();
+    if key.is_empty() {
+        vec![ROWID_KEY.to_string()]
+    } else {
+        key
+    }
+}
+
+async fn read_rows(pool: &SqlitePool, db_path: &str, table: &str, key_columns: &[String]) -> Result<Option<Vec<ResultRow>>, String> {
+    let rowid = if key_columns == [ROWID_KEY] { format!("rowid AS {}, ", ROWID_KEY) } else { String::new() };
+    let query = format!("SELECT {}* FROM {} LIMIT {}", rowid, quote_ident(table), MAX_MERGE_ROWS_PER_TABLE + 1);
+    let rows = run_compared_query(pool, db_path, &query).await?.rows;
+    Ok((rows.len() <= MAX_MERGE_ROWS_PER_TABLE).then_some(rows))
+}
+
+struct MergeSources<'a> {
+    base: (&'a SqlitePool, &'a str, &'a DatabaseSchema),
+    local: (&'a SqlitePool, &'a str, &'a DatabaseSchema),
+    remote: (&'a SqlitePool, &'a str, &'a DatabaseSchema),
+}
+
+/// The actions and conflicts of one table, and whether the remote side changed it.
+async fn plan_table(
+    sources: &MergeSources<'_>,
+    table: &str,
+    resolutions: &[ConflictResolution],
+) -> Result<(TableMergePlan, bool), String> {
+    let base = sources.base.2.tables.get(table);
+    let local = sources.local.2.tables.get(table);
+    let remote = sources.remote.2.tables.get(table);
+    let shape = |side: Option<&SchemaTable>| side.map(|table| (column_names(table), key_columns_for(table)));
+
+    if shape(base) != shape(local) || shape(base) != shape(remote) {
+        // Only a table the local side left alone can skip a schema change
+        let local_untouched = match (base, local) {
+            (None, None) => true,
+            (Some(base_table), Some(_)) if shape(base) == shape(local) => {
+                let key_columns = key_columns_for(base_table);
+                let base_rows = read_rows(sources.base.0, sources.base.1, table, &key_columns).await?;
+                let local_rows = read_rows(sources.local.0, sources.local.1, table, &key_columns).await?;
+                matches!((base_rows, local_rows), (Some(b), Some(l)) if diff_result_rows(&b, &l, &key_columns).identical)
+            }
+            _ => false,
+        };
+        if local_untouched {
+            return Ok((TableMergePlan::default(), true));
+        }
+        let mut plan = TableMergePlan::default();
+        let resolved = resolutions.iter().any(|r| r.table == table && r.key.is_empty() && r.keep == MergeSide::Remote);
+        if !resolved {
+            plan.conflicts.push(table_conflict(
+                table,
+                "The table's schema changed; only row changes can be merged",
+            ));
+        }
+        return Ok((plan, true));
+    }
+
+    let Some(base_table) = base else {
+        return Ok((TableMergePlan::default(), false));
+    };
+    let key_columns = key_columns_for(base_table);
+    let base_rows = read_rows(sources.base.0, sources.base.1, table, &key_columns).await?;
+    let local_rows = read_rows(sources.local.0, sources.local.1, table, &key_columns).await?;
+    let remote_rows = read_rows(sources.remote.0, sources.remote.1, table, &key_columns).await?;
+
+    let (Some(base_rows), Some(local_rows), Some(remote_rows)) = (base_rows, local_rows, remote_rows) else {
+        let mut plan = TableMergePlan::default();
+        plan.conflicts.push(table_conflict(
+            table,
+            format!("More than {} rows; too large to merge row by row", MAX_MERGE_ROWS_PER_TABLE),
+        ));
+        return Ok((plan, true));
+    };
+
+    let remote_changed = !diff_result_rows(&base_rows, &remote_rows, &key_columns).identical;
+    let plan = merge_table_rows(table, &base_rows, &local_rows, &remote_rows, &key_columns, resolutions);
+    Ok((plan, remote_changed))
+}
+
+pub(crate) fn json_to_sql(value: &serde_json::Value) -> rusqlite::types::Value {
+    use rusqlite::types::Value;
+    match value {
+        serde_json::Value::Null => Value::Null,
+        serde_json::Value::Bool(flag) => Value::Integer(*flag as i64),
+        serde_json::Value::Number(number) => match number.as_i64() {
+            Some(integer) => Value::Integer(integer),
+            None => Value::Real(number.as_f64().unwrap_or_default()),
+        },
+        // Large integers are decoded as text; column affinity converts them back on comparison
+        serde_json::Value::String(text) => Value::Text(text.clone()),
+        other => Value::Text(other.to_string()),
+    }
+}
+
+fn key_expr(column: &str) -> String {
+    if column == ROWID_KEY {
+        "rowid".to_string()
+    } else {
+        quote_ident(column)
+    }
+}
+
+/// `WHERE` condition matching `key` in `schema`.`table`, and its parameters.
+fn key_condition(schema: &str, table: &str, key_columns: &[String], key: &ResultRow) -> (String, Vec<rusqlite::types::Value>) {
+    let condition = key_columns
+        .iter()
+        .map(|column| format!("{}.{}.{} IS ?", schema, quote_ident(table), key_expr(column)))
+        .collect::<Vec<_>>()
+        .join(" AND ");
+    let params = key_columns
+        .iter()
+        .map(|column| json_to_sql(key.get(column).unwrap_or(&serde_json::Value::Null)))
+        .collect();
+    (condition, params)
+}
+
+fn apply_action(
+    tx: &rusqlite::Transaction,
+    table: &str,
+    columns: &[String],
+    key_columns: &[String],
+    action: &MergeAction,
+) -> Result<(), String> {
+    let target = format!("main.{}", quote_ident(table));
+    let source = format!("{}.{}", LOCAL_SCHEMA, quote_ident(table));
+    let (sql, params) = match action {
+        MergeAction::Upsert { key } => {
+            let (condition, params) = key_condition(LOCAL_SCHEMA, table, key_columns, key);
+            let mut column_list: Vec<String> = columns.iter().map(|column| quote_ident(column)).collect();
+            if key_columns == [ROWID_KEY] {
+                column_list.insert(0, "rowid".to_string());
+            }
+            let column_list = column_list.join(", ");
+            (
+                format!("INSERT OR REPLACE INTO {} ({}) SELECT {} FROM {} WHERE {}", target, column_list, column_list, source, condition),
+                params,
+            )
+        }
+        MergeAction::UpdateColumns { key, columns } => {
+            let (source_condition, key_params) = key_condition(LOCAL_SCHEMA, table, key_columns, key);
+            let (target_condition, target_params) = key_condition("main", table, key_columns, key);
+            let assignments = columns
+                .iter()
+                .map(|column| {
+                    let column = quote_ident(column);
+                    format!("{} = (SELECT {} FROM {} WHERE {})", column, column, source, source_condition)
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            // Each assignment's subquery binds the key again
+            let mut params: Vec<_> = columns.iter().flat_map(|_| key_params.iter().cloned()).collect();
+            params.extend(target_params);
+            (format!("UPDATE {} SET {} WHERE {}", target, assignments, target_condition), params)
+        }
+        MergeAction::Delete { key } => {
+            let (condition, params) = key_condition("main", table, key_columns, key);
+            (format!("DELETE FROM {} WHERE {}", target, condition), params)
+        }
+    };
+
+    tx.execute(&sql, rusqlite::params_from_iter(params))
+        .map(|_| ())
+        .map_err(|e| format!("Failed to apply merged change to '{}': {}", table, e))
+}
+
+/// Apply the planned local changes to `merged_path`, copying values from `local_path`.
+fn apply_merge_plans(
+    merged_path: &Path,
+    local_path: &Path,
+    plans: &[TableActions],
+) -> Result<usize, String> {
+    let mut conn = rusqlite::Connection::open(merged_path).map_err(|e| format!("Failed to open merged database: {}", e))?;
+    conn.execute(
+        &format!("ATTACH DATABASE ?1 AS {}", LOCAL_SCHEMA),
+        [local_path.to_string_lossy().to_string()],
+    )
+    .map_err(|e| format!("Failed to attach local database: {}", e))?;
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let mut applied = 0;
+    for plan in plans {
+        for action in &plan.actions {
+            apply_action(&tx, &plan.table, &plan.columns, &plan.key_columns, action)?;
+            applied += 1;
+        }
+    }
+    tx.commit().map_err(|e| format!("Failed to commit merged changes: {}", e))?;
+    Ok(applied)
+}
+
+/// Merge `local_path` and `remote_path` against their common ancestor `base_path` into
+/// `merged_path`, which starts as a copy of the remote database. Conflicting rows keep their
+/// remote values and are reported, unless `resolutions` settles them.
+pub async fn three_way_merge(
+    base_path: &str,
+    local_path: &str,
+    remote_path: &str,
+    merged_path: &str,
+    resolutions: &[ConflictResolution],
+) -> Result<ThreeWayMerge, String> {
+    for path in [base_path, local_path, remote_path] {
+        if !Path::new(path).is_file() {
+            return Err(format!("Database does not exist: {}", path));
+        }
+    }
+
+    let base_pool = open_uncached_connection(base_path).await?;
+    let local_pool = open_uncached_connection(local_path).await?;
+    let remote_pool = open_uncached_connection(remote_path).await?;
+
+    let planned = async {
+        let base_schema = read_database_schema(&base_pool).await?;
+        let local_schema = read_database_schema(&local_pool).await?;
+        let remote_schema = read_database_schema(&remote_pool).await?;
+        let sources = MergeSources {
+            base: (&base_pool, base_path, &base_schema),
+            local: (&local_pool, local_path, &local_schema),
+            remote: (&remote_pool, remote_path, &remote_schema),
+        };
+
+        let table_names: BTreeSet<&String> = base_schema
+            .tables
+            .keys()
+            .chain(local_schema.tables.keys())
+            .chain(remote_schema.tables.keys())
+            .collect();
+        let mut plans = Vec::new();
+        let mut conflicts = Vec::new();
+        let mut remote_changed = false;
+        for table in table_names {
+            let (plan, table_remote_changed) = plan_table(&sources, table, resolutions).await?;
+            remote_changed |= table_remote_changed;
+            conflicts.extend(plan.conflicts);
+            if let Some(schema_table) = local_schema.tables.get(table).filter(|_| !plan.actions.is_empty()) {
+                plans.push(TableActions {
+                    table: table.clone(),
+                    columns: column_names(schema_table),
+                    key_columns: key_columns_for(schema_table),
+                    actions: plan.actions,
+                });
+            }
+        }
+        Ok::<_, String>((plans, conflicts, remote_changed))
+    }
+    .await;
+
+    base_pool.close().await;
+    local_pool.close().await;
+    remote_pool.close().await;
+
+    let (plans, conflicts, remote_changed) = planned?;
+    copy_database_with_companions(Path::new(remote_path), Path::new(merged_path))?;
+    let applied_changes = apply_merge_plans(Path::new(merged_path), Path::new(local_path), &plans)?;
+
+    Ok(ThreeWayMerge {
+        merged_path: merged_path.to_string(),
+        applied_changes,
+        conflicts,
+        remote_changed,
+    })
+}
+
+/// Three-way merge of local database files; `merged_path` defaults to `<local>.merged`
+#[tauri::command]
+pub async fn db_three_way_merge(
+    base_path: String,
+    local_path: String,
+    remote_path: String,
+    merged_path: Option<String>,
+    resolutions: Option<Vec<ConflictResolution>>,
+) -> Result<DbResponse<ThreeWayMerge>, String> {
+    log::info!("🔀 Merging '{}' and '{}' (base '{}')", local_path, remote_path, base_path);
+    let merged_path = merged_path.unwrap_or_else(|| format!("{}.merged", local_path));
+
+    match three_way_merge(&base_path, &local_path, &remote_path, &merged_path, &resolutions.unwrap_or_default()).await {
+        Ok(merge) => {
+            log::info!("✅ Merged {} changes, {} conflicts", merge.applied_changes, merge.conflicts.len());
+            Ok(DbResponse {
+                success: true,
+                data: Some(merge),
+                error: None,
+            })
+        }
+        Err(e) => {
+            log::error!("❌ Three-way merge failed: {}", e);
+            Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::database::tests::create_test_database;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn row(id: i64, name: &str, age: i64) -> ResultRow {
+        [("id", json!(id)), ("name", json!(name)), ("age", json!(age))]
+            .into_iter()
+            .map(|(column, value)| (column.to_string(), value))
+            .collect()
+    }
+
+    fn id_key(id: i64) -> ResultRow {
+        [("id".to_string(), json!(id))].into_iter().collect()
+    }
+
+    #[test]
+    fn test_merge_table_rows_auto_merges_and_reports_conflicts() {
+        let key = vec!["id".to_string()];
+        let base = vec![row(1, "Ann", 30), row(2, "Bob", 40), row(3, "Cid", 50), row(4, "Dee", 60)];
+        // Local: renames 1, changes 2's age, deletes 3, inserts 5
+        let local = vec![row(1, "Anna", 30), row(2, "Bob", 41), row(4, "Dee", 60), row(5, "Eve", 20)];
+        // Remote: changes 1's age, changes 2's age differently, updates 3
+        let remote = vec![row(1, "Ann", 31), row(2, "Bob", 42), row(3, "Cyd", 50), row(4, "Dee", 60)];
+
+        let plan = merge_table_rows("users", &base, &local, &remote, &key, &[]);
+        assert_eq!(
+            plan.actions,
+            vec![
+                MergeAction::UpdateColumns {
+                    key: id_key(1),
+                    columns: vec!["name".to_string()],
+                },
+                MergeAction::Upsert { key: id_key(5) },
+            ]
+        );
+        let kinds: Vec<(ConflictKind, &serde_json::Value)> =
+            plan.conflicts.iter().map(|conflict| (conflict.kind, &conflict.key["id"])).collect();
+        assert_eq!(
+            kinds,
+            vec![(ConflictKind::BothUpdated, &json!(2)), (ConflictKind::LocalDeletedRemoteUpdated, &json!(3))]
+        );
+        assert_eq!(plan.conflicts[0].columns, vec!["age".to_string()]);
+
+        let resolutions = vec![
+            ConflictResolution {
+                table: "users".to_string(),
+                key: id_key(2),
+                keep: MergeSide::Local,
+            },
+            ConflictResolution {
+                table: "users".to_string(),
+                key: id_key(3),
+                keep: MergeSide::Remote,
+            },
+        ];
+        let resolved = merge_table_rows("users", &base, &local, &remote, &key, &resolutions);
+        assert!(resolved.conflicts.is_empty());
+        assert!(resolved.actions.contains(&MergeAction::Upsert { key: id_key(2) }));
+        assert_eq!(resolved.actions.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_three_way_merge_applies_local_changes_to_remote_copy() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = |name: &str| temp_dir.path().join(name).to_string_lossy().to_string();
+        let (base, local, remote, merged) = (path("base.db"), path("local.db"), path("remote.db"), path("merged.db"));
+        create_test_database(&base).unwrap();
+        std::fs::copy(&base, &local).unwrap();
+        std::fs::copy(&base, &remote).unwrap();
+
+        let conn = rusqlite::Connection::open(&local).unwrap();
+        conn.execute_batch(
+            "UPDATE users SET name = 'Johnny' WHERE id = 1;
+             DELETE FROM posts WHERE id = 2;
+             INSERT INTO users (id, name, email, age) VALUES (10, 'Local', 'local@example.com', 1);",
+        )
+        .unwrap();
+        drop(conn);
+        let conn = rusqlite::Connection::open(&remote).unwrap();
+        conn.execute_batch(
+            "UPDATE users SET age = 31 WHERE id = 1;
+             UPDATE users SET age = 99 WHERE id = 3;
+             INSERT INTO users (id, name, email, age) VALUES (11, 'Remote', 'remote@example.com', 2);",
+        )
+        .unwrap();
+        drop(conn);
+
+        let merge = three_way_merge(&base, &local, &remote, &merged, &[]).await.unwrap();
+        assert!(merge.conflicts.is_empty());
+        assert!(merge.remote_changed);
+        assert_eq!(merge.applied_changes, 3);
+
+        let conn = rusqlite::Connection::open(&merged).unwrap();
+        let (name, age): (String, i64) =
+            conn.query_row("SELECT name, age FROM users WHERE id = 1", [], |r| Ok((r.get(0)?, r.get(1)?))).unwrap();
+        assert_eq!((name.as_str(), age), ("Johnny", 31));
+        let ids: Vec<i64> = conn
+            .prepare("SELECT id FROM users ORDER BY id")
+            .unwrap()
+            .query_map([], |r| r.get(0))
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(ids, vec![1, 2, 3, 10, 11]);
+        let posts: i64 = conn.query_row("SELECT COUNT(*) FROM posts", [], |r| r.get(0)).unwrap();
+        assert_eq!(posts, 1);
+    }
+}
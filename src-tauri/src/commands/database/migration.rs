@@ -0,0 +1,666 @@
+// Schema migration script generation
+// Compares the schema of two databases and emits SQL that transforms the first into the second
+
+use crate::commands::database::connection_access::open_uncached_connection;
+use crate::commands::database::helpers::{quote_ident, TABLE_INFO_QUERY};
+use crate::commands::database::types::*;
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqlitePool, Row};
+use std::collections::BTreeMap;
+
+const REBUILD_TABLE_PREFIX: &str = "_flippio_new_";
+
+// Column definition as reported by PRAGMA table_info
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaColumn {
+    pub name: String,
+    pub type_name: String,
+    pub notnull: bool,
+    pub default_sql: Option<String>,
+    pub pk: i64,
+}
+
+// UNIQUE or PRIMARY KEY constraint, as reported by PRAGMA index_list
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaConstraint {
+    pub columns: Vec<String>,
+    pub primary_key: bool,
+}
+
+// Foreign key as reported by PRAGMA foreign_key_list; `to` is None for the parent's primary key
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaForeignKey {
+    pub table: String,
+    pub from: Vec<String>,
+    pub to: Vec<Option<String>>,
+    pub on_update: String,
+    pub on_delete: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaTable {
+    pub name: String,
+    pub sql: String,
+    pub columns: Vec<SchemaColumn>,
+    pub constraints: Vec<SchemaConstraint>,
+    pub foreign_keys: Vec<SchemaForeignKey>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaIndex {
+    pub name: String,
+    pub table_name: String,
+    pub sql: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseSchema {
+    pub tables: BTreeMap<String, SchemaTable>,
+    pub indexes: BTreeMap<String, SchemaIndex>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationScript {
+    pub statements: Vec<String>,
+    pub warnings: Vec<String>,
+    pub sql: String,
+}
+
+// UNIQUE and PRIMARY KEY constraints are backed by automatic indexes, which sqlite_master lists without SQL
+async fn read_table_constraints(pool: &SqlitePool, table: &str) -> Result<Vec<SchemaConstraint>, String> {
+    let index_rows = sqlx::query("SELECT name, origin FROM pragma_index_list(?) WHERE origin != 'c'")
+        .bind(table)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Error reading constraints for '{}': {}", table, e))?;
+
+    let mut constraints = Vec::new();
+    for row in index_rows {
+        let index_name: String = row.get("name");
+        let columns = sqlx::query("SELECT name FROM pragma_index_info(?) ORDER BY seqno")
+            .bind(&index_name)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("Error reading constraint columns for '{}': {}", table, e))?
+            .iter()
+            .map(|column| column.get::<Option<String>, _>("name").unwrap_or_default())
+            .collect();
+        constraints.push(SchemaConstraint {
+            columns,
+            primary_key: row.get::<String, _>("origin") == "pk",
+        });
+    }
+    // Automatic index names and order depend on the table's history, not its definition
+    constraints.sort();
+    Ok(constraints)
+}
+
+async fn read_foreign_keys(pool: &SqlitePool, table: &str) -> Result<Vec<SchemaForeignKey>, String> {
+    let rows = sqlx::query("SELECT * FROM pragma_foreign_key_list(?) ORDER BY id, seq")
+        .bind(table)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Error reading foreign keys for '{}': {}", table, e))?;
+
+    let mut foreign_keys: Vec<(i64, SchemaForeignKey)> = Vec::new();
+    for row in rows {
+        let id: i64 = row.get("id");
+        if foreign_keys.last().map(|(last_id, _)| *last_id) != Some(id) {
+            foreign_keys.push((
+                id,
+                SchemaForeignKey {
+                    table: row.get("table"),
+                    from: Vec::new(),
+                    to: Vec::new(),
+                    on_update: row.get("on_update"),
+                    on_delete: row.get("on_delete"),
+                },
+            ));
+        }
+        if let Some((_, foreign_key)) = foreign_keys.last_mut() {
+            foreign_key.from.push(row.get("from"));
+            foreign_key.to.push(row.get("to"));
+        }
+    }
+
+    let mut foreign_keys: Vec<SchemaForeignKey> = foreign_keys.into_iter().map(|(_, key)| key).collect();
+    foreign_keys.sort();
+    Ok(foreign_keys)
+}
+
+/// Read user tables, their columns, constraints and explicit indexes from a database.
+pub async fn read_database_schema(pool: &SqlitePool) -> Result<DatabaseSchema, String> {
+    let mut schema = DatabaseSchema::default();
+
+    let table_rows = sqlx::query(
+        "SELECT name, sql FROM sqlite_master WHERE type='table' AND name NOT LIKE 'sqlite_%' AND sql IS NOT NULL",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Error reading tables: {}", e))?;
+
+    for row in table_rows {
+        let name: String = row.get("name");
+        let sql: String = row.get("sql");
+
+        let column_rows = sqlx::query(TABLE_INFO_QUERY)
+            .bind(&name)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("Error reading columns for '{}': {}", name, e))?;
+
+        let columns = column_rows
+            .iter()
+            .map(|column| SchemaColumn {
+                name: column.get::<String, _>("name"),
+                type_name: column.get::<String, _>("type"),
+                notnull: column.get::<i64, _>("notnull") != 0,
+                default_sql: column.get::<Option<String>, _>("dflt_value"),
+                pk: column.get::<i64, _>("pk"),
+            })
+            .collect();
+
+        let constraints = read_table_constraints(pool, &name).await?;
+        let foreign_keys = read_foreign_keys(pool, &name).await?;
+
+        schema.tables.insert(
+            name.clone(),
+            SchemaTable {
+                name,
+                sql,
+                columns,
+                constraints,
+                foreign_keys,
+            },
+        );
+    }
+
+    let index_rows = sqlx::query(
+        "SELECT name, tbl_name, sql FROM sqlite_master WHERE type='index' AND sql IS NOT NULL",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Error reading indexes: {}", e))?;
+
+    for row in index_rows {
+        let name: String = row.get("name");
+        schema.indexes.insert(
+            name.clone(),
+            SchemaIndex {
+                name,
+                table_name: row.get("tbl_name"),
+                sql: row.get("sql"),
+            },
+        );
+    }
+
+    Ok(schema)
+}
+
+/// Rewrite a CREATE TABLE statement so it creates a table with a different name.
+fn rename_create_table_sql(sql: &str, new_name: &str) -> String {
+    match sql.find('(') {
+        Some(body_start) => {
+            format!("CREATE TABLE {} {}", quote_ident(new_name), &sql[body_start..])
+        }
+        None => sql.to_string(),
+    }
+}
+
+/// The column list and constraints of a CREATE TABLE statement with whitespace collapsed, so
+/// tables compare equal regardless of how their name is quoted or the SQL is formatted.
+fn table_definition(sql: &str) -> String {
+    let body = sql.find('(').map_or(sql, |body_start| &sql[body_start..]);
+    body.trim_end_matches(';').split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// A column can be added in place only when SQLite's ALTER TABLE ADD COLUMN restrictions allow it.
+fn can_add_column_in_place(column: &SchemaColumn) -> bool {
+    column.pk == 0 && (!column.notnull || column.default_sql.is_some())
+}
+
+fn add_column_sql(table: &str, column: &SchemaColumn) -> String {
+    let mut definition = format!("{} {}", quote_ident(&column.name), column.type_name);
+    if column.notnull {
+        definition.push_str(" NOT NULL");
+    }
+    if let Some(default_sql) = &column.default_sql {
+        definition.push_str(&format!(" DEFAULT {}", default_sql));
+    }
+    format!("ALTER TABLE {} ADD COLUMN {};", quote_ident(table), definition)
+}
+
+fn rebuild_table_statements(from: &SchemaTable, to: &SchemaTable) -> Vec<String> {
+    let temp_name = format!("{}{}", REBUILD_TABLE_PREFIX, to.name);
+    let common_columns: Vec<String> = to
+        .columns
+        .iter()
+        .filter(|column| from.columns.iter().any(|old| old.name == column.name))
+        .map(|column| quote_ident(&column.name))
+        .collect();
+
+    let mut statements = vec![format!("{};", rename_create_table_sql(&to.sql, &temp_name))];
+    if !common_columns.is_empty() {
+        let column_list = common_columns.join(", ");
+        statements.push(format!(
+            "INSERT INTO {} ({}) SELECT {} FROM {};",
+            quote_ident(&temp_name),
+            column_list,
+            column_list,
+            quote_ident(&from.name)
+        ));
+    }
+    statements.push(format!("DROP TABLE {};", quote_ident(&from.name)));
+    statements.push(format!(
+        "ALTER TABLE {} RENAME TO {};",
+        quote_ident(&temp_name),
+        quote_ident(&to.name)
+    ));
+    statements
+}
+
+/// Generate the SQL needed to migrate schema `from` into schema `to`.
+pub fn generate_migration_script(from: &DatabaseSchema, to: &DatabaseSchema) -> MigrationScript {
+    let mut statements = Vec::new();
+    let mut warnings = Vec::new();
+    let mut rebuilt_tables = Vec::new();
+
+    // Indexes that disappear or change are dropped first so table changes don't trip over them
+    for (name, index) in &from.indexes {
+        if to.indexes.get(name) != Some(index) {
+            statements.push(format!("DROP INDEX IF EXISTS {};", quote_ident(name)));
+        }
+    }
+
+    for (name, table) in &to.tables {
+        let Some(existing) = from.tables.get(name) else {
+            statements.push(format!("{};", table.sql.trim_end_matches(';')));
+            continue;
+        };
+
+        // CHECK, COLLATE and other clauses only show up in the table's SQL
+        let constraints_changed = existing.constraints != table.constraints
+            || existing.foreign_keys != table.foreign_keys
+            || (existing.columns == table.columns
+                && table_definition(&existing.sql) != table_definition(&table.sql));
+        if existing.columns == table.columns && !constraints_changed {
+            continue;
+        }
+
+        let removed: Vec<&SchemaColumn> = existing
+            .columns
+            .iter()
+            .filter(|old| !table.columns.iter().any(|new| new.name == old.name))
+            .collect();
+        let added: Vec<&SchemaColumn> = table
+            .columns
+            .iter()
+            .filter(|new| !existing.columns.iter().any(|old| old.name == new.name))
+            .collect();
+        let modified = table.columns.iter().any(|new| {
+            existing
+                .columns
+                .iter()
+                .any(|old| old.name == new.name && old != new)
+        });
+
+        let needs_rebuild = constraints_changed
+            || modified
+            || removed.iter().any(|column| column.pk != 0)
+            || added.iter().any(|column| !can_add_column_in_place(column));
+
+        if needs_rebuild {
+            let reason = if constraints_changed {
+                "its constraints changed"
+            } else {
+                "its column definitions changed in a way ALTER TABLE cannot express"
+            };
+            warnings.push(format!("Table '{}' is rebuilt because {}", name, reason));
+            rebuilt_tables.push(name.clone());
+            statements.extend(rebuild_table_statements(existing, table));
+            continue;
+        }
+
+        for column in removed {
+            warnings.push(format!(
+                "Column '{}.{}' is dropped; its data will be lost",
+                name, column.name
+            ));
+            statements.push(format!(
+                "ALTER TABLE {} DROP COLUMN {};",
+                quote_ident(name),
+                quote_ident(&column.name)
+            ));
+        }
+        for column in added {
+            statements.push(add_column_sql(name, column));
+        }
+    }
+
+    for name in from.tables.keys() {
+        if !to.tables.contains_key(name) {
+            warnings.push(format!("Table '{}' is dropped; its data will be lost", name));
+            statements.push(format!("DROP TABLE IF EXISTS {};", quote_ident(name)));
+        }
+    }
+
+    // Rebuilt tables lose their indexes, so every index on them is recreated
+    for (name, index) in &to.indexes {
+        if from.indexes.get(name) != Some(index) || rebuilt_tables.contains(&index.table_name) {
+            statements.push(format!("{};", index.sql.trim_end_matches(';')));
+        }
+    }
+
+    let sql = if statements.is_empty() {
+        String::new()
+    } else {
+        let mut script = vec![
+            "PRAGMA foreign_keys=OFF;".to_string(),
+            "BEGIN TRANSACTION;".to_string(),
+        ];
+        script.extend(statements.iter().cloned());
+        script.push("COMMIT;".to_string());
+        script.push("PRAGMA foreign_keys=ON;".to_string());
+        script.join("\n")
+    };
+
+    MigrationScript {
+        statements,
+        warnings,
+        sql,
+    }
+}
+
+async fn generate_migration_for_paths(
+    source_db_path: &str,
+    target_db_path: &str,
+) -> Result<MigrationScript, String> {
+    let source_pool = open_uncached_connection(source_db_path).await?;
+    let source_schema = read_database_schema(&source_pool).await;
+    source_pool.close().await;
+
+    let target_pool = open_uncached_connection(target_db_path).await?;
+    let target_schema = read_database_schema(&target_pool).await;
+    target_pool.close().await;
+
+    Ok(generate_migration_script(&source_schema?, &target_schema?))
+}
+
+#[tauri::command]
+pub async fn db_generate_migration(
+    source_db_path: String,
+    target_db_path: String,
+) -> Result<DbResponse<MigrationScript>, String> {
+    log::info!(
+        "🔧 Generating migration from '{}' to '{}'",
+        source_db_path,
+        target_db_path
+    );
+
+    match generate_migration_for_paths(&source_db_path, &target_db_path).await {
+        Ok(script) => {
+            log::info!(
+                "✅ Generated migration with {} statements and {} warnings",
+                script.statements.len(),
+                script.warnings.len()
+            );
+            Ok(DbResponse {
+                success: true,
+                data: Some(script),
+                error: None,
+            })
+        }
+        Err(e) => {
+            log::error!("❌ Failed to generate migration: {}", e);
+            Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+    use tempfile::TempDir;
+
+    fn column(name: &str, type_name: &str) -> SchemaColumn {
+        SchemaColumn {
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+            notnull: false,
+            default_sql: None,
+            pk: 0,
+        }
+    }
+
+    fn table(name: &str, sql: &str, columns: Vec<SchemaColumn>) -> SchemaTable {
+        SchemaTable {
+            name: name.to_string(),
+            sql: sql.to_string(),
+            columns,
+            constraints: Vec::new(),
+            foreign_keys: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_identical_schemas_produce_empty_migration() {
+        let mut schema = DatabaseSchema::default();
+        schema.tables.insert(
+            "users".to_string(),
+            table("users", "CREATE TABLE users (id INTEGER)", vec![column("id", "INTEGER")]),
+        );
+
+        let script = generate_migration_script(&schema, &schema.clone());
+
+        assert!(script.statements.is_empty());
+        assert!(script.sql.is_empty());
+    }
+
+    #[test]
+    fn test_new_column_uses_alter_table() {
+        let mut from = DatabaseSchema::default();
+        from.tables.insert(
+            "users".to_string(),
+            table("users", "CREATE TABLE users (id INTEGER)", vec![column("id", "INTEGER")]),
+        );
+        let mut to = DatabaseSchema::default();
+        to.tables.insert(
+            "users".to_string(),
+            table(
+                "users",
+                "CREATE TABLE users (id INTEGER, email TEXT)",
+                vec![column("id", "INTEGER"), column("email", "TEXT")],
+            ),
+        );
+
+        let script = generate_migration_script(&from, &to);
+
+        assert_eq!(
+            script.statements,
+            vec!["ALTER TABLE \"users\" ADD COLUMN \"email\" TEXT;".to_string()]
+        );
+        assert!(script.sql.starts_with("PRAGMA foreign_keys=OFF;"));
+    }
+
+    #[test]
+    fn test_type_change_triggers_rebuild() {
+        let mut from = DatabaseSchema::default();
+        from.tables.insert(
+            "items".to_string(),
+            table(
+                "items",
+                "CREATE TABLE items (id INTEGER, price TEXT)",
+                vec![column("id", "INTEGER"), column("price", "TEXT")],
+            ),
+        );
+        let mut to = DatabaseSchema::default();
+        to.tables.insert(
+            "items".to_string(),
+            table(
+                "items",
+                "CREATE TABLE items (id INTEGER, price REAL)",
+                vec![column("id", "INTEGER"), column("price", "REAL")],
+            ),
+        );
+
+        let script = generate_migration_script(&from, &to);
+
+        assert_eq!(script.warnings.len(), 1);
+        assert!(script.statements[0]
+            .starts_with("CREATE TABLE \"_flippio_new_items\" (id INTEGER, price REAL)"));
+        assert!(script
+            .statements
+            .contains(&"ALTER TABLE \"_flippio_new_items\" RENAME TO \"items\";".to_string()));
+    }
+
+    #[test]
+    fn test_constraint_change_triggers_rebuild() {
+        let columns = vec![column("id", "INTEGER"), column("qty", "INTEGER")];
+        let mut from = DatabaseSchema::default();
+        from.tables.insert(
+            "items".to_string(),
+            table("items", "CREATE TABLE items (id INTEGER, qty INTEGER)", columns.clone()),
+        );
+        let mut to = DatabaseSchema::default();
+        to.tables.insert(
+            "items".to_string(),
+            table("items", "CREATE TABLE \"items\" (id INTEGER,\n  qty INTEGER)", columns.clone()),
+        );
+        assert!(generate_migration_script(&from, &to).statements.is_empty());
+
+        to.tables.insert(
+            "items".to_string(),
+            table("items", "CREATE TABLE items (id INTEGER, qty INTEGER CHECK (qty >= 0))", columns),
+        );
+        let script = generate_migration_script(&from, &to);
+
+        assert!(script.warnings[0].contains("constraints changed"));
+        assert!(script.statements[0].contains("CHECK (qty >= 0)"));
+    }
+
+    #[test]
+    fn test_dropped_table_is_reported() {
+        let mut from = DatabaseSchema::default();
+        from.tables.insert(
+            "legacy".to_string(),
+            table("legacy", "CREATE TABLE legacy (id INTEGER)", vec![column("id", "INTEGER")]),
+        );
+
+        let script = generate_migration_script(&from, &DatabaseSchema::default());
+
+        assert_eq!(script.statements, vec!["DROP TABLE IF EXISTS \"legacy\";".to_string()]);
+        assert!(script.warnings[0].contains("legacy"));
+    }
+
+    #[tokio::test]
+    async fn test_generated_migration_applies_cleanly() {
+        let temp_dir = TempDir::new().unwrap();
+        let old_path = temp_dir.path().join("old.db");
+        let new_path = temp_dir.path().join("new.db");
+
+        Connection::open(&old_path)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT, legacy_flag INTEGER);
+                 INSERT INTO users (name, legacy_flag) VALUES ('John', 1);
+                 CREATE TABLE obsolete (id INTEGER);",
+            )
+            .unwrap();
+        Connection::open(&new_path)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL, created_at TEXT);
+                 CREATE INDEX idx_users_name ON users (name);
+                 CREATE TABLE settings (key TEXT PRIMARY KEY, value TEXT);",
+            )
+            .unwrap();
+
+        let script = generate_migration_for_paths(
+            old_path.to_str().unwrap(),
+            new_path.to_str().unwrap(),
+        )
+        .await
+        .unwrap();
+
+        let conn = Connection::open(&old_path).unwrap();
+        conn.execute_batch(&script.sql).unwrap();
+
+        let name: String = conn
+            .query_row("SELECT name FROM users WHERE id = 1", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(name, "John");
+
+        let tables: Vec<String> = conn
+            .prepare("SELECT name FROM sqlite_master WHERE type='table' ORDER BY name")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        assert_eq!(tables, vec!["settings".to_string(), "users".to_string()]);
+
+        let index_count: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE type='index' AND name='idx_users_name'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(index_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_unique_and_foreign_key_changes_are_migrated() {
+        let temp_dir = TempDir::new().unwrap();
+        let old_path = temp_dir.path().join("old.db");
+        let new_path = temp_dir.path().join("new.db");
+
+        Connection::open(&old_path)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE users (id INTEGER PRIMARY KEY);
+                 CREATE TABLE posts (id INTEGER PRIMARY KEY, user_id INTEGER, slug TEXT);
+                 INSERT INTO users (id) VALUES (1);
+                 INSERT INTO posts (user_id, slug) VALUES (1, 'hello');",
+            )
+            .unwrap();
+        Connection::open(&new_path)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE users (id INTEGER PRIMARY KEY);
+                 CREATE TABLE posts (id INTEGER PRIMARY KEY, user_id INTEGER REFERENCES users (id) ON DELETE CASCADE, slug TEXT UNIQUE);",
+            )
+            .unwrap();
+
+        let script = generate_migration_for_paths(
+            old_path.to_str().unwrap(),
+            new_path.to_str().unwrap(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(script.warnings, vec!["Table 'posts' is rebuilt because its constraints changed".to_string()]);
+
+        Connection::open(&old_path).unwrap().execute_batch(&script.sql).unwrap();
+
+        // Migrated and target schemas now agree, so a second pass has nothing to do
+        let script = generate_migration_for_paths(
+            old_path.to_str().unwrap(),
+            new_path.to_str().unwrap(),
+        )
+        .await
+        .unwrap();
+        assert!(script.statements.is_empty());
+    }
+}
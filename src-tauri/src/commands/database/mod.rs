@@ -4,7 +4,30 @@ pub mod helpers;
 pub mod commands;
 mod connection_access;
 mod table_reads;
+pub mod compare;
+pub mod chart;
+pub mod migration;
+pub mod snapshot_diff;
+pub mod merge;
+pub mod replay;
+pub mod export;
+pub mod parquet_export;
+pub mod insert_generation;
+pub mod test_data;
+pub mod pii_scan;
+pub mod delete_impact;
+pub mod triggers;
+pub mod schema_diagram;
+pub mod query_lint;
+pub mod query_stats;
+pub mod timestamps;
+pub mod transformers;
+pub mod compressed_cells;
+pub mod archives;
+pub mod bulk_update;
+pub mod copies;
 pub mod connection_manager;
+pub mod schema_cache;
 pub mod change_history;
 pub mod change_tracking;
 
@@ -15,6 +38,28 @@ pub mod tests;
 pub use types::*;
 pub use commands::*;
 pub use table_reads::*;
+pub use compare::*;
+pub use chart::*;
+pub use migration::*;
+pub use snapshot_diff::*;
+pub use merge::*;
+pub use replay::*;
+pub use export::*;
+pub use parquet_export::*;
+pub use insert_generation::*;
+pub use test_data::*;
+pub use pii_scan::*;
+pub use delete_impact::*;
+pub use triggers::*;
+pub use schema_diagram::*;
+pub use query_lint::*;
+pub use query_stats::*;
+pub use timestamps::*;
+pub use transformers::*;
+pub use compressed_cells::*;
+pub use archives::*;
+pub use bulk_update::*;
+pub use copies::*;
 pub use connection_manager::DatabaseConnectionManager;
 
 // Re-export change history components
@@ -0,0 +1,384 @@
+// Parquet export
+// Streams a table into a Parquet file with column types derived from the stored values
+
+use crate::commands::database::connection_access::get_current_pool;
+use crate::commands::database::export::{read_table_columns, resolve_export_path};
+use crate::commands::database::helpers::quote_ident;
+use crate::commands::database::types::*;
+use arrow_array::builder::{BinaryBuilder, Float64Builder, Int64Builder, StringBuilder};
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_schema::{DataType, Field, Schema};
+use futures::TryStreamExt;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::{SqlitePool, SqliteRow};
+use sqlx::{Row, ValueRef};
+use std::path::Path;
+use std::sync::Arc;
+use tauri::State;
+
+// Rows buffered per record batch; keeps memory flat for large tables
+const PARQUET_BATCH_SIZE: usize = 8192;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParquetColumnType {
+    Int64,
+    Float64,
+    Binary,
+    Utf8,
+}
+
+impl ParquetColumnType {
+    /// Pick the narrowest lossless type for the storage classes found in a column.
+    /// SQLite is dynamically typed, so declared types are not trusted here.
+    fn from_storage_counts(integer: i64, real: i64, text: i64, blob: i64) -> Self {
+        if text > 0 || (blob > 0 && integer + real > 0) {
+            ParquetColumnType::Utf8
+        } else if blob > 0 {
+            ParquetColumnType::Binary
+        } else if real > 0 {
+            ParquetColumnType::Float64
+        } else if integer > 0 {
+            ParquetColumnType::Int64
+        } else {
+            ParquetColumnType::Utf8
+        }
+    }
+
+    fn data_type(&self) -> DataType {
+        match self {
+            ParquetColumnType::Int64 => DataType::Int64,
+            ParquetColumnType::Float64 => DataType::Float64,
+            ParquetColumnType::Binary => DataType::Binary,
+            ParquetColumnType::Utf8 => DataType::Utf8,
+        }
+    }
+
+    fn select_expr(&self, column: &str) -> String {
+        match self {
+            ParquetColumnType::Float64 => format!("CAST({} AS REAL)", quote_ident(column)),
+            ParquetColumnType::Utf8 => format!("CAST({} AS TEXT)", quote_ident(column)),
+            _ => quote_ident(column),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ParquetColumn {
+    pub name: String,
+    pub data_type: ParquetColumnType,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ParquetExportResult {
+    pub path: String,
+    pub row_count: usize,
+    pub columns: Vec<ParquetColumn>,
+}
+
+enum ColumnBuilder {
+    Int64(Int64Builder),
+    Float64(Float64Builder),
+    Binary(BinaryBuilder),
+    Utf8(StringBuilder),
+}
+
+impl ColumnBuilder {
+    fn new(column_type: ParquetColumnType) -> Self {
+        match column_type {
+            ParquetColumnType::Int64 => ColumnBuilder::Int64(Int64Builder::new()),
+            ParquetColumnType::Float64 => ColumnBuilder::Float64(Float64Builder::new()),
+            ParquetColumnType::Binary => ColumnBuilder::Binary(BinaryBuilder::new()),
+            ParquetColumnType::Utf8 => ColumnBuilder::Utf8(StringBuilder::new()),
+        }
+    }
+
+    fn append(&mut self, row: &SqliteRow, idx: usize) {
+        let is_null = row
+            .try_get_raw(idx)
+            .map(|value| value.is_null())
+            .unwrap_or(true);
+
+        match self {
+            ColumnBuilder::Int64(builder) if is_null => builder.append_null(),
+            ColumnBuilder::Float64(builder) if is_null => builder.append_null(),
+            ColumnBuilder::Binary(builder) if is_null => builder.append_null(),
+            ColumnBuilder::Utf8(builder) if is_null => builder.append_null(),
+            ColumnBuilder::Int64(builder) => builder.append_option(row.try_get::<i64, _>(idx).ok()),
+            ColumnBuilder::Float64(builder) => {
+                builder.append_option(row.try_get::<f64, _>(idx).ok())
+            }
+            ColumnBuilder::Binary(builder) => {
+                builder.append_option(row.try_get::<Vec<u8>, _>(idx).ok())
+            }
+            ColumnBuilder::Utf8(builder) => {
+                // Blobs cast to TEXT are not guaranteed to be valid UTF-8
+                let text = row.try_get::<String, _>(idx).ok().or_else(|| {
+                    row.try_get::<Vec<u8>, _>(idx)
+                        .ok()
+                        .map(|bytes| String::from_utf8_lossy(&bytes).to_string())
+                });
+                builder.append_option(text)
+            }
+        }
+    }
+
+    fn finish(&mut self) -> ArrayRef {
+        match self {
+            ColumnBuilder::Int64(builder) => Arc::new(builder.finish()),
+            ColumnBuilder::Float64(builder) => Arc::new(builder.finish()),
+            ColumnBuilder::Binary(builder) => Arc::new(builder.finish()),
+            ColumnBuilder::Utf8(builder) => Arc::new(builder.finish()),
+        }
+    }
+}
+
+/// Determine every column's Parquet type with a single scan of the table.
+async fn read_parquet_columns(
+    pool: &SqlitePool,
+    table_name: &str,
+) -> Result<Vec<ParquetColumn>, String> {
+    let names = read_table_columns(pool, table_name).await?;
+
+    let counters: Vec<String> = names
+        .iter()
+        .flat_map(|name| {
+            let column = quote_ident(name);
+            ["integer", "real", "text", "blob"].map(|class| {
+                format!("COALESCE(SUM(typeof({}) = '{}'), 0)", column, class)
+            })
+        })
+        .collect();
+    let query = format!("SELECT {} FROM {}", counters.join(", "), quote_ident(table_name));
+
+    let row = sqlx::query(&query)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Error inspecting column types: {}", e))?;
+
+    Ok(names
+        .into_iter()
+        .enumerate()
+        .map(|(idx, name)| {
+            let count = |offset: usize| row.try_get::<i64, _>(idx * 4 + offset).unwrap_or(0);
+            ParquetColumn {
+                name,
+                data_type: ParquetColumnType::from_storage_counts(
+                    count(0),
+                    count(1),
+                    count(2),
+                    count(3),
+                ),
+            }
+        })
+        .collect())
+}
+
+pub(crate) async fn write_table_parquet(
+    pool: &SqlitePool,
+    table_name: &str,
+    output_path: &Path,
+) -> Result<ParquetExportResult, String> {
+    let columns = read_parquet_columns(pool, table_name).await?;
+
+    let schema = Arc::new(Schema::new(
+        columns
+            .iter()
+            .map(|column| Field::new(&column.name, column.data_type.data_type(), true))
+            .collect::<Vec<_>>(),
+    ));
+
+    let file = std::fs::File::create(output_path)
+        .map_err(|e| format!("Failed to create Parquet file: {}", e))?;
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut writer = ArrowWriter::try_new(file, schema.clone(), Some(properties))
+        .map_err(|e| format!("Failed to create Parquet writer: {}", e))?;
+
+    let mut builders: Vec<ColumnBuilder> = columns
+        .iter()
+        .map(|column| ColumnBuilder::new(column.data_type))
+        .collect();
+
+    let flush = |builders: &mut Vec<ColumnBuilder>,
+                     writer: &mut ArrowWriter<std::fs::File>|
+     -> Result<(), String> {
+        let arrays: Vec<ArrayRef> = builders.iter_mut().map(ColumnBuilder::finish).collect();
+        let batch = RecordBatch::try_new(schema.clone(), arrays)
+            .map_err(|e| format!("Failed to build record batch: {}", e))?;
+        writer
+            .write(&batch)
+            .map_err(|e| format!("Failed to write Parquet data: {}", e))
+    };
+
+    let select_list: Vec<String> = columns
+        .iter()
+        .map(|column| column.data_type.select_expr(&column.name))
+        .collect();
+    let query = format!(
+        "SELECT {} FROM {}",
+        select_list.join(", "),
+        quote_ident(table_name)
+    );
+
+    let mut stream = sqlx::query(&query).fetch(pool);
+    let mut row_count = 0;
+    let mut buffered = 0;
+
+    while let Some(row) = stream
+        .try_next()
+        .await
+        .map_err(|e| format!("Error reading table '{}': {}", table_name, e))?
+    {
+        for (idx, builder) in builders.iter_mut().enumerate() {
+            builder.append(&row, idx);
+        }
+        row_count += 1;
+        buffered += 1;
+
+        if buffered == PARQUET_BATCH_SIZE {
+            flush(&mut builders, &mut writer)?;
+            buffered = 0;
+        }
+    }
+
+    if buffered > 0 {
+        flush(&mut builders, &mut writer)?;
+    }
+
+    writer
+        .close()
+        .map_err(|e| format!("Failed to finish Parquet file: {}", e))?;
+
+    Ok(ParquetExportResult {
+        path: output_path.to_string_lossy().to_string(),
+        row_count,
+        columns,
+    })
+}
+
+#[tauri::command]
+pub async fn db_export_table_parquet(
+    app_handle: tauri::AppHandle,
+    state: State<'_, DbPool>,
+    db_cache: State<'_, DbConnectionCache>,
+    table_name: String,
+    output_path: Option<String>,
+    current_db_path: Option<String>,
+) -> Result<DbResponse<Option<ParquetExportResult>>, String> {
+    log::info!("📤 Exporting table '{}' as Parquet", table_name);
+
+    let result = async {
+        let pool = get_current_pool(&state, &db_cache, current_db_path).await?;
+        let default_name = format!("{}.parquet", table_name);
+        let Some(output_path) =
+            resolve_export_path(&app_handle, output_path, &default_name, "parquet").await?
+        else {
+            // Save dialog was cancelled
+            return Ok(None);
+        };
+
+        write_table_parquet(&pool, &table_name, &output_path)
+            .await
+            .map(Some)
+    }
+    .await;
+
+    match result {
+        Ok(result) => {
+            if let Some(export) = &result {
+                log::info!("✅ Exported {} rows to {}", export.row_count, export.path);
+            }
+            Ok(DbResponse {
+                success: true,
+                data: Some(result),
+                error: None,
+            })
+        }
+        Err(e) => {
+            log::error!("❌ Parquet export failed: {}", e);
+            Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::{Array, Float64Array, Int64Array, StringArray};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_column_type_from_storage_counts() {
+        use ParquetColumnType::*;
+
+        assert_eq!(ParquetColumnType::from_storage_counts(3, 0, 0, 0), Int64);
+        assert_eq!(ParquetColumnType::from_storage_counts(3, 1, 0, 0), Float64);
+        assert_eq!(ParquetColumnType::from_storage_counts(0, 0, 0, 2), Binary);
+        assert_eq!(ParquetColumnType::from_storage_counts(1, 0, 1, 0), Utf8);
+        assert_eq!(ParquetColumnType::from_storage_counts(1, 0, 0, 1), Utf8);
+        assert_eq!(ParquetColumnType::from_storage_counts(0, 0, 0, 0), Utf8);
+    }
+
+    #[tokio::test]
+    async fn test_write_table_parquet_preserves_types() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("metrics.db");
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE metrics (id INTEGER PRIMARY KEY, score REAL, label TEXT, payload BLOB);
+             INSERT INTO metrics VALUES (1, 1, 'a', x'00ff');
+             INSERT INTO metrics VALUES (2, 2.5, NULL, NULL);
+             INSERT INTO metrics VALUES (3, NULL, 'c', x'01');",
+        )
+        .unwrap();
+        drop(conn);
+
+        let pool = SqlitePool::connect(&format!("sqlite:{}", db_path.display()))
+            .await
+            .unwrap();
+        let output = temp_dir.path().join("metrics.parquet");
+        let result = write_table_parquet(&pool, "metrics", &output).await.unwrap();
+
+        assert_eq!(result.row_count, 3);
+        let types: Vec<ParquetColumnType> = result.columns.iter().map(|c| c.data_type).collect();
+        assert_eq!(
+            types,
+            vec![
+                ParquetColumnType::Int64,
+                ParquetColumnType::Float64,
+                ParquetColumnType::Utf8,
+                ParquetColumnType::Binary
+            ]
+        );
+
+        let file = std::fs::File::open(&output).unwrap();
+        let reader = ParquetRecordBatchReaderBuilder::try_new(file)
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<RecordBatch> = reader.map(Result::unwrap).collect();
+        let batch = &batches[0];
+
+        let ids = batch.column(0).as_any().downcast_ref::<Int64Array>().unwrap();
+        let scores = batch.column(1).as_any().downcast_ref::<Float64Array>().unwrap();
+        let labels = batch.column(2).as_any().downcast_ref::<StringArray>().unwrap();
+
+        assert_eq!(ids.value(2), 3);
+        assert_eq!(scores.value(0), 1.0);
+        assert!(scores.is_null(2));
+        assert!(labels.is_null(1));
+        assert_eq!(labels.value(2), "c");
+    }
+}
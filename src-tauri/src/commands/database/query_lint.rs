@@ -0,0 +1,377 @@
+// Query linting
+// Parses SQL before it runs to classify statements, point at syntax errors and flag risky patterns
+
+use crate::commands::database::connection_access::get_current_pool;
+use crate::commands::database::types::*;
+use serde::{Deserialize, Serialize};
+use sqlparser::ast::{Query, SetExpr, Statement};
+use sqlparser::dialect::SQLiteDialect;
+use sqlparser::keywords::Keyword;
+use sqlparser::parser::{Parser, ParserError};
+use sqlparser::tokenizer::{Token, Tokenizer};
+use sqlx::sqlite::SqlitePool;
+use tauri::State;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum LintSeverity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryLintIssue {
+    pub severity: LintSeverity,
+    pub message: String,
+    // 1-based position reported by the parser, when known
+    pub line: Option<u64>,
+    pub column: Option<u64>,
+    pub statement_index: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryAnalysis {
+    // One of select, insert, update, delete, schema, pragma, transaction, explain or other
+    pub statements: Vec<String>,
+    pub read_only: bool,
+    pub issues: Vec<QueryLintIssue>,
+}
+
+impl QueryAnalysis {
+    pub fn first_error(&self) -> Option<&QueryLintIssue> {
+        self.issues.iter().find(|issue| issue.severity == LintSeverity::Error)
+    }
+
+    pub fn warnings(&self) -> Vec<String> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == LintSeverity::Warning)
+            .map(|issue| issue.message.clone())
+            .collect()
+    }
+
+    /// A single query whose rows should be fetched rather than executed for changes.
+    pub fn is_single_query(&self) -> bool {
+        self.statements.len() == 1 && matches!(self.statements[0].as_str(), "select" | "explain")
+    }
+}
+
+impl QueryLintIssue {
+    pub fn describe(&self) -> String {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => format!("{} (line {}, column {})", self.message, line, column),
+            _ => self.message.clone(),
+        }
+    }
+}
+
+fn is_read_only_body(body: &SetExpr) -> bool {
+    match body {
+        SetExpr::Select(_) | SetExpr::Values(_) | SetExpr::Table(_) => true,
+        SetExpr::Query(query) => is_read_only_select(query),
+        SetExpr::SetOperation { left, right, .. } => is_read_only_body(left) && is_read_only_body(right),
+        _ => false,
+    }
+}
+
+/// `WITH ... DELETE` parses as a query whose body is the DELETE, so a query only reads when
+/// its body and every CTE do.
+fn is_read_only_select(query: &Query) -> bool {
+    let ctes_read_only = query
+        .with
+        .as_ref()
+        .is_none_or(|with| with.cte_tables.iter().all(|cte| is_read_only_select(&cte.query)));
+    ctes_read_only && is_read_only_body(&query.body)
+}
+
+fn statement_kind(statement: &Statement) -> &'static str {
+    match statement {
+        Statement::Query(query) if is_read_only_select(query) => "select",
+        Statement::Query(query) => match query.body.as_ref() {
+            SetExpr::Insert(_) => "insert",
+            SetExpr::Update(_) => "update",
+            _ => "other",
+        },
+        Statement::Insert(_) => "insert",
+        Statement::Update { .. } => "update",
+        Statement::Delete(_) => "delete",
+        Statement::CreateTable(_)
+        | Statement::CreateVirtualTable { .. }
+        | Statement::CreateIndex(_)
+        | Statement::CreateView { .. }
+        | Statement::CreateTrigger { .. }
+        | Statement::AlterTable { .. }
+        | Statement::Drop { .. }
+        | Statement::DropTrigger { .. } => "schema",
+        Statement::Pragma { .. } => "pragma",
+        Statement::StartTransaction { .. }
+        | Statement::Commit { .. }
+        | Statement::Rollback { .. }
+        | Statement::Savepoint { .. }
+        | Statement::ReleaseSavepoint { .. } => "transaction",
+        Statement::Explain { .. } | Statement::ExplainTable { .. } => "explain",
+        _ => "other",
+    }
+}
+
+fn warning(message: String, statement_index: usize) -> QueryLintIssue {
+    QueryLintIssue {
+        severity: LintSeverity::Warning,
+        message,
+        line: None,
+        column: None,
+        statement_index: Some(statement_index),
+    }
+}
+
+fn statement_warning(statement: &Statement, index: usize) -> Option<QueryLintIssue> {
+    match statement {
+        Statement::Update { table, selection: None, .. } => Some(warning(
+            format!("UPDATE without WHERE changes every row of {}", table),
+            index,
+        )),
+        Statement::Delete(delete) if delete.selection.is_none() => Some(warning(
+            "DELETE without WHERE removes every row of the table".to_string(),
+            index,
+        )),
+        Statement::Drop { object_type, names, .. } => Some(warning(
+            format!(
+                "DROP {} {} cannot be undone",
+                object_type,
+                names.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+            ),
+            index,
+        )),
+        _ => None,
+    }
+}
+
+/// Split "message at Line: 1, Column: 8" into the message and its position.
+fn parse_error_position(error: &ParserError) -> (String, Option<u64>, Option<u64>) {
+    let message = match error {
+        ParserError::TokenizerError(message) | ParserError::ParserError(message) => message.clone(),
+        ParserError::RecursionLimitExceeded => return ("Query is nested too deeply".to_string(), None, None),
+    };
+
+    let Some(at) = message.rfind(" at Line: ") else {
+        return (message, None, None);
+    };
+    let mut numbers = message[at + " at Line: ".len()..]
+        .split(", Column: ")
+        .map(|part| part.trim().parse::<u64>().ok());
+    let line = numbers.next().flatten();
+    let column = numbers.next().flatten();
+    (message[..at].to_string(), line, column)
+}
+
+/// Classify and lint a SQL script without touching any database.
+pub fn analyze_sql(sql: &str) -> QueryAnalysis {
+    let statements = match Parser::parse_sql(&SQLiteDialect {}, sql) {
+        Ok(statements) => statements,
+        Err(e) => {
+            let (message, line, column) = parse_error_position(&e);
+            return QueryAnalysis {
+                statements: Vec::new(),
+                read_only: false,
+                issues: vec![QueryLintIssue {
+                    severity: LintSeverity::Error,
+                    message: format!("Syntax error: {}", message),
+                    line,
+                    column,
+                    statement_index: None,
+                }],
+            };
+        }
+    };
+
+    let kinds: Vec<String> = statements.iter().map(|s| statement_kind(s).to_string()).collect();
+    QueryAnalysis {
+        read_only: !kinds.is_empty() && kinds.iter().all(|kind| kind == "select" || kind == "explain"),
+        issues: statements
+            .iter()
+            .enumerate()
+            .filter_map(|(index, statement)| statement_warning(statement, index))
+            .collect(),
+        statements: kinds,
+    }
+}
+
+fn first_keywords(tokens: &[Token]) -> impl Iterator<Item = Keyword> + '_ {
+    tokens
+        .iter()
+        .filter(|token| !matches!(token, Token::Whitespace(_)))
+        .map(|token| match token {
+            Token::Word(word) => word.keyword,
+            _ => Keyword::NoKeyword,
+        })
+}
+
+fn is_create_trigger(tokens: &[Token]) -> bool {
+    let mut keywords = first_keywords(tokens);
+    keywords.next() == Some(Keyword::CREATE)
+        && match keywords.next() {
+            Some(Keyword::TEMP | Keyword::TEMPORARY) => keywords.next() == Some(Keyword::TRIGGER),
+            next => next == Some(Keyword::TRIGGER),
+        }
+}
+
+/// Split a script into statements the way SQLite reads it: at semicolons, except inside a
+/// CREATE TRIGGER body, which only ends at `END;`. Literals and comments are tokens of their
+/// own, so a semicolon inside them never splits. None when the script can't be tokenized.
+fn split_statements(sql: &str) -> Option<Vec<String>> {
+    let tokens = Tokenizer::new(&SQLiteDialect {}, sql).tokenize().ok()?;
+    let mut statements = Vec::new();
+    let mut current: Vec<Token> = Vec::new();
+    let mut finish = |current: &mut Vec<Token>| {
+        let statement: String = current.drain(..).map(|token| token.to_string()).collect();
+        if !statement.trim().is_empty() {
+            statements.push(statement.trim().to_string());
+        }
+    };
+
+    for token in tokens {
+        let in_trigger_body = is_create_trigger(&current)
+            && first_keywords(&current).last() != Some(Keyword::END);
+        if token == Token::SemiColon && !in_trigger_body {
+            finish(&mut current);
+        } else {
+            current.push(token);
+        }
+    }
+    finish(&mut current);
+    Some(statements)
+}
+
+const TRANSACTION_CONTROL: [&str; 6] = ["BEGIN", "COMMIT", "END", "ROLLBACK", "SAVEPOINT", "RELEASE"];
+
+/// Whether a script begins, ends or rolls back a transaction itself. Only the first word of
+/// each statement counts, so a trigger's `BEGIN ... END` body doesn't.
+pub(crate) fn controls_transaction(sql: &str) -> bool {
+    let is_control = |word: &str| TRANSACTION_CONTROL.contains(&word.to_uppercase().as_str());
+    match split_statements(sql) {
+        Some(statements) => statements.iter().any(|statement| {
+            let tokens = Tokenizer::new(&SQLiteDialect {}, statement).tokenize().unwrap_or_default();
+            match tokens.iter().find(|token| !matches!(token, Token::Whitespace(_))) {
+                Some(Token::Word(word)) => word.quote_style.is_none() && is_control(&word.value),
+                _ => false,
+            }
+        }),
+        // Not tokenizable: look at the first word of each statement
+        None => sql
+            .split(';')
+            .any(|statement| statement.split_whitespace().next().is_some_and(is_control)),
+    }
+}
+
+/// Lint a query against the open database. The parser doesn't cover every SQLite extension,
+/// so its syntax errors are only reported as errors when SQLite rejects one of the statements too.
+pub async fn lint_query(pool: &SqlitePool, sql: &str) -> QueryAnalysis {
+    let mut analysis = analyze_sql(sql);
+    if analysis.first_error().is_none() {
+        return analysis;
+    }
+    let Some(statements) = split_statements(sql) else {
+        return analysis;
+    };
+
+    // EXPLAIN compiles each statement without running it
+    for statement in &statements {
+        if sqlx::query(&format!("EXPLAIN {}", statement)).fetch_all(pool).await.is_err() {
+            return analysis;
+        }
+    }
+    for issue in &mut analysis.issues {
+        issue.severity = LintSeverity::Warning;
+        issue.message = format!("SQLite accepts this script, but it could not be analyzed: {}", issue.message);
+    }
+    analysis
+}
+
+/// Classify a query and report syntax errors and dangerous patterns, for editor feedback.
+/// Falls back to parser-only linting when no database is open.
+#[tauri::command]
+pub async fn db_lint_query(
+    state: State<'_, DbPool>,
+    db_cache: State<'_, DbConnectionCache>,
+    query: String,
+    current_db_path: Option<String>,
+) -> Result<DbResponse<QueryAnalysis>, String> {
+    let analysis = match get_current_pool(&state, &db_cache, current_db_path).await {
+        Ok(pool) => lint_query(&pool, &query).await,
+        Err(_) => analyze_sql(&query),
+    };
+    Ok(DbResponse {
+        success: true,
+        data: Some(analysis),
+        error: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analyze_sql_classifies_statements() {
+        let analysis = analyze_sql("SELECT * FROM users; INSERT INTO users (name) VALUES ('a')");
+        assert_eq!(analysis.statements, vec!["select", "insert"]);
+        assert!(!analysis.read_only);
+        assert!(analysis.issues.is_empty());
+
+        let analysis = analyze_sql("WITH recent AS (SELECT 1) SELECT * FROM recent");
+        assert!(analysis.read_only);
+        assert!(analysis.is_single_query());
+
+        let analysis = analyze_sql("WITH stale AS (SELECT id FROM users) INSERT INTO archive SELECT * FROM stale");
+        assert_eq!(analysis.statements, vec!["insert"]);
+        assert!(!analysis.read_only);
+    }
+
+    #[test]
+    fn test_analyze_sql_flags_dangerous_statements() {
+        let analysis = analyze_sql("UPDATE users SET active = 0; DELETE FROM logs; DELETE FROM users WHERE id = 1");
+        assert_eq!(analysis.statements, vec!["update", "delete", "delete"]);
+        assert_eq!(analysis.warnings().len(), 2);
+        assert_eq!(analysis.issues[0].statement_index, Some(0));
+        assert!(analysis.issues[0].message.contains("UPDATE without WHERE"));
+        assert_eq!(analysis.issues[1].statement_index, Some(1));
+        assert!(analysis.first_error().is_none());
+    }
+
+    #[test]
+    fn test_analyze_sql_reports_syntax_error_position() {
+        let analysis = analyze_sql("SELECT *\nFROM users WHERE AND id = 1");
+        let error = analysis.first_error().unwrap();
+        assert!(error.message.starts_with("Syntax error"));
+        assert_eq!(error.line, Some(2));
+        assert!(error.column.is_some());
+        assert!(error.describe().contains("(line 2, column"));
+    }
+
+    #[test]
+    fn test_split_statements() {
+        assert_eq!(split_statements("SELECT 'a;b';").unwrap(), vec!["SELECT 'a;b'"]);
+        assert_eq!(split_statements("SELECT 1; SELECT 2").unwrap(), vec!["SELECT 1", "SELECT 2"]);
+        assert_eq!(split_statements("SELECT 1 -- ;\n;").unwrap().len(), 1);
+
+        let script = "CREATE TEMP TRIGGER audit AFTER DELETE ON users BEGIN \
+                      INSERT INTO log VALUES (old.id); UPDATE stats SET n = n - 1; END; SELECT 1";
+        let statements = split_statements(script).unwrap();
+        assert_eq!(statements.len(), 2);
+        assert!(statements[0].ends_with("END"));
+        assert_eq!(statements[1], "SELECT 1");
+    }
+
+    #[test]
+    fn test_controls_transaction() {
+        assert!(controls_transaction("DELETE FROM users; COMMIT"));
+        assert!(controls_transaction("-- undo\nrollback"));
+        assert!(controls_transaction("SAVEPOINT s1; UPDATE users SET n = 1; RELEASE s1"));
+        assert!(!controls_transaction("UPDATE users SET note = 'COMMIT'"));
+        assert!(!controls_transaction(
+            "CREATE TRIGGER t AFTER DELETE ON users BEGIN DELETE FROM log; END"
+        ));
+    }
+}
@@ -0,0 +1,152 @@
+// Query statistics
+// Timing, query plan and scan counters reported alongside db_execute_query results
+
+use rusqlite::{Connection, OpenFlags, StatementStatus};
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqlitePool, Row};
+use std::time::Duration;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryStats {
+    pub execution_time_ms: f64,
+    pub rows_returned: u64,
+    // True when any step of the plan looks rows up through an index or the rowid
+    pub index_used: bool,
+    // Tables read from start to end without an index
+    pub full_scan_tables: Vec<String>,
+    // EXPLAIN QUERY PLAN details, one entry per step
+    pub plan: Vec<String>,
+    // Full scan steps and VM steps from sqlite3_stmt_status; only measured when profiling
+    pub rows_scanned: Option<u64>,
+    pub vm_steps: Option<u64>,
+}
+
+impl QueryStats {
+    pub fn new(elapsed: Duration, rows_returned: u64, plan: Vec<String>) -> Self {
+        let (index_used, full_scan_tables) = summarize_plan(&plan);
+        Self {
+            execution_time_ms: elapsed.as_secs_f64() * 1000.0,
+            rows_returned,
+            index_used,
+            full_scan_tables,
+            plan,
+            rows_scanned: None,
+            vm_steps: None,
+        }
+    }
+}
+
+/// Index usage and fully scanned tables from EXPLAIN QUERY PLAN details such as
+/// `SCAN users`, `SCAN TABLE users` (older SQLite) or `SEARCH users USING INDEX idx_name (name=?)`.
+pub fn summarize_plan(plan: &[String]) -> (bool, Vec<String>) {
+    let mut index_used = false;
+    let mut full_scan_tables = Vec::new();
+
+    for detail in plan {
+        let uses_index = detail.contains("USING INDEX")
+            || detail.contains("USING COVERING INDEX")
+            || detail.contains("USING INTEGER PRIMARY KEY")
+            || detail.contains("USING ROWID");
+        index_used |= uses_index;
+
+        let mut words = detail.split_whitespace();
+        if words.next() != Some("SCAN") || uses_index {
+            continue;
+        }
+        let table = match words.next() {
+            Some("TABLE") => words.next(),
+            other => other,
+        };
+        if let Some(table) = table.filter(|table| *table != "CONSTANT" && *table != "SUBQUERY") {
+            full_scan_tables.push(table.to_string());
+        }
+    }
+    (index_used, full_scan_tables)
+}
+
+/// EXPLAIN QUERY PLAN for a statement; empty when SQLite can't explain it.
+pub async fn read_query_plan(pool: &SqlitePool, sql: &str) -> Vec<String> {
+    match sqlx::query(&format!("EXPLAIN QUERY PLAN {}", sql)).fetch_all(pool).await {
+        Ok(rows) => rows
+            .iter()
+            .filter_map(|row| row.try_get::<String, _>("detail").ok())
+            .collect(),
+        Err(e) => {
+            log::warn!("⚠️ Could not read query plan: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Run a read-only query to completion on a separate read-only connection and
+/// return (full scan steps, VM steps). This costs a second execution, so it is opt-in.
+pub fn measure_scan_counters(db_path: &str, sql: &str) -> Result<(u64, u64), String> {
+    let connection = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| format!("Failed to open database for profiling: {}", e))?;
+    let mut statement = connection
+        .prepare(sql)
+        .map_err(|e| format!("Failed to prepare query for profiling: {}", e))?;
+
+    {
+        let mut rows = statement.raw_query();
+        while rows
+            .next()
+            .map_err(|e| format!("Failed to profile query: {}", e))?
+            .is_some()
+        {}
+    }
+
+    let full_scan_steps = statement.get_status(StatementStatus::FullscanStep).max(0) as u64;
+    let vm_steps = statement.get_status(StatementStatus::VmStep).max(0) as u64;
+    Ok((full_scan_steps, vm_steps))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_summarize_plan() {
+        let plan = vec![
+            "SCAN users".to_string(),
+            "SEARCH orders USING INDEX idx_orders_user (user_id=?)".to_string(),
+            "SCAN CONSTANT ROW".to_string(),
+        ];
+        assert_eq!(summarize_plan(&plan), (true, vec!["users".to_string()]));
+
+        let legacy = vec!["SCAN TABLE logs".to_string()];
+        assert_eq!(summarize_plan(&legacy), (false, vec!["logs".to_string()]));
+
+        let covering = vec!["SCAN users USING COVERING INDEX idx_users_name".to_string()];
+        assert_eq!(summarize_plan(&covering), (true, vec![]));
+    }
+
+    #[tokio::test]
+    async fn test_plan_and_scan_counters() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("stats.db");
+        let conn = Connection::open(&db_path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT);
+             INSERT INTO items (name) VALUES ('a'), ('b'), ('c'), ('d');",
+        )
+        .unwrap();
+        drop(conn);
+        let db_path = db_path.to_string_lossy().to_string();
+
+        let pool = SqlitePool::connect(&format!("sqlite:{}", db_path)).await.unwrap();
+        let plan = read_query_plan(&pool, "SELECT * FROM items WHERE name = 'c'").await;
+        pool.close().await;
+        let stats = QueryStats::new(Duration::from_millis(2), 1, plan);
+        assert_eq!(stats.full_scan_tables, vec!["items".to_string()]);
+        assert!(!stats.index_used);
+        assert_eq!(stats.execution_time_ms, 2.0);
+
+        let (full_scan_steps, vm_steps) =
+            measure_scan_counters(&db_path, "SELECT * FROM items WHERE name = 'c'").unwrap();
+        assert!(full_scan_steps >= 3);
+        assert!(vm_steps > 0);
+    }
+}
@@ -0,0 +1,294 @@
+// Change replay
+// Re-applies the edits recorded in the change history onto another copy of the database,
+// typically a fresh pull after a push was rejected because the device copy had moved on
+
+use crate::commands::database::change_history::{ChangeEvent, ChangeHistoryManager, OperationType};
+use crate::commands::database::merge::json_to_sql;
+use crate::commands::database::helpers::quote_ident;
+use crate::commands::database::types::*;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
+use tauri::State;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedChange {
+    pub change_id: String,
+    pub table_name: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayResult {
+    pub target_path: String,
+    pub applied_change_ids: Vec<String>,
+    pub skipped: Vec<SkippedChange>,
+}
+
+/// The condition of a recorded `UPDATE ... WHERE <condition>` statement.
+fn recorded_condition(sql: &str) -> Option<&str> {
+    sql.find(" WHERE ")
+        .map(|index| sql[index + " WHERE ".len()..].trim())
+        .filter(|condition| !condition.is_empty())
+}
+
+/// A recorded statement that can run again as-is, i.e. one without bound parameters.
+fn recorded_literal_statement(change: &ChangeEvent) -> Result<String, String> {
+    match change.metadata.sql_statement.as_deref() {
+        Some(sql) if !sql.contains('?') => Ok(sql.to_string()),
+        Some(_) => Err("The recorded statement has parameters that were not recorded".to_string()),
+        None => Err("No statement was recorded for this change".to_string()),
+    }
+}
+
+/// Statement and parameters that repeat `change`, or why it cannot be repeated.
+fn replay_statement(change: &ChangeEvent) -> Result<(String, Vec<rusqlite::types::Value>), String> {
+    let table = quote_ident(&change.table_name);
+    match &change.operation_type {
+        // INSERT ... DEFAULT VALUES records no field values
+        OperationType::Insert if change.changes.is_empty() => Ok((recorded_literal_statement(change)?, Vec::new())),
+        OperationType::Insert => {
+            let columns: Vec<String> = change.changes.iter().map(|field| quote_ident(&field.field_name)).collect();
+            let placeholders = vec!["?"; columns.len()].join(", ");
+            let params = change
+                .changes
+                .iter()
+                .map(|field| field.new_value.as_ref().map(json_to_sql).unwrap_or(rusqlite::types::Value::Null))
+                .collect();
+            Ok((format!("INSERT INTO {} ({}) VALUES ({})", table, columns.join(", "), placeholders), params))
+        }
+        OperationType::Update => {
+            let condition = change
+                .metadata
+                .sql_statement
+                .as_deref()
+                .and_then(recorded_condition)
+                .ok_or("The row condition of this update was not recorded")?;
+            let assignments = change
+                .changes
+                .iter()
+                .map(|field| format!("{} = ?", quote_ident(&field.field_name)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let params = change
+                .changes
+                .iter()
+                .map(|field| field.new_value.as_ref().map(json_to_sql).unwrap_or(rusqlite::types::Value::Null))
+                .collect();
+            Ok((format!("UPDATE {} SET {} WHERE {}", table, assignments, condition), params))
+        }
+        OperationType::Delete | OperationType::Clear | OperationType::BulkDelete { .. } => {
+            Ok((recorded_literal_statement(change)?, Vec::new()))
+        }
+        OperationType::BulkInsert { .. } | OperationType::BulkUpdate { .. } => {
+            Err("Bulk changes do not record the values needed to replay them".to_string())
+        }
+        OperationType::Revert { .. } => Err("Reverts are replayed by leaving out the reverted changes".to_string()),
+    }
+}
+
+/// Changes undone later in the session, which must not be replayed.
+fn reverted_change_ids(changes: &[ChangeEvent]) -> HashSet<&str> {
+    changes
+        .iter()
+        .filter_map(|change| match &change.operation_type {
+            OperationType::Revert {
+                original_change_id,
+                cascade_reverted_ids,
+            } => Some(std::iter::once(original_change_id).chain(cascade_reverted_ids)),
+            _ => None,
+        })
+        .flatten()
+        .map(String::as_str)
+        .collect()
+}
+
+/// Apply `changes` in order to the database at `target_path` in one transaction. Changes
+/// that fail or no longer match a row are reported as skipped; the rest are kept.
+pub fn apply_changes(target_path: &Path, changes: &[ChangeEvent]) -> Result<ReplayResult, String> {
+    let mut conn = rusqlite::Connection::open(target_path).map_err(|e| format!("Failed to open target database: {}", e))?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+    let reverted = reverted_change_ids(changes);
+    let mut applied_change_ids = Vec::new();
+    let mut skipped = Vec::new();
+    // A multi-row DELETE is recorded once per deleted row, all with the same statement
+    let mut last_delete: Option<&str> = None;
+
+    for change in changes {
+        let skip = |reason: String| SkippedChange {
+            change_id: change.id.clone(),
+            table_name: change.table_name.clone(),
+            reason,
+        };
+        if matches!(change.operation_type, OperationType::Revert { .. }) {
+            continue;
+        }
+        if reverted.contains(change.id.as_str()) {
+            skipped.push(skip("Reverted during the session".to_string()));
+            continue;
+        }
+
+        let is_delete = matches!(change.operation_type, OperationType::Delete);
+        if is_delete && last_delete.is_some() && last_delete == change.metadata.sql_statement.as_deref() {
+            applied_change_ids.push(change.id.clone());
+            continue;
+        }
+        last_delete = if is_delete { change.metadata.sql_statement.as_deref() } else { None };
+
+        let (sql, params) = match replay_statement(change) {
+            Ok(statement) => statement,
+            Err(reason) => {
+                skipped.push(skip(reason));
+                continue;
+            }
+        };
+        match tx.execute(&sql, rusqlite::params_from_iter(params)) {
+            Ok(0) if matches!(change.operation_type, OperationType::Update | OperationType::Delete) => {
+                skipped.push(skip("No matching row in the target database".to_string()));
+            }
+            Ok(_) => applied_change_ids.push(change.id.clone()),
+            Err(e) => skipped.push(skip(e.to_string())),
+        }
+    }
+
+    tx.commit().map_err(|e| format!("Failed to commit replayed changes: {}", e))?;
+    Ok(ReplayResult {
+        target_path: target_path.to_string_lossy().to_string(),
+        applied_change_ids,
+        skipped,
+    })
+}
+
+/// Replay the changes recorded for `context_key` onto `target_path`, optionally only those
+/// listed in `change_ids`.
+pub async fn replay_changes_onto(
+    history: &ChangeHistoryManager,
+    context_key: &str,
+    target_path: &str,
+    change_ids: Option<&[String]>,
+) -> Result<ReplayResult, String> {
+    if !Path::new(target_path).is_file() {
+        return Err(format!("Database does not exist: {}", target_path));
+    }
+
+    let changes: Vec<ChangeEvent> = history
+        .get_changes(context_key)
+        .await
+        .into_iter()
+        .filter(|change| change_ids.is_none_or(|ids| ids.contains(&change.id)))
+        .collect();
+    if changes.is_empty() {
+        return Err("No recorded changes to replay".to_string());
+    }
+
+    apply_changes(Path::new(target_path), &changes)
+}
+
+/// Re-apply this session's recorded edits to another copy of the database
+#[tauri::command]
+pub async fn db_replay_changes_onto(
+    history_manager: State<'_, ChangeHistoryManager>,
+    context_key: String,
+    target_path: String,
+    change_ids: Option<Vec<String>>,
+) -> Result<DbResponse<ReplayResult>, String> {
+    log::info!("🔁 Replaying changes of context {} onto '{}'", context_key, target_path);
+
+    match replay_changes_onto(&history_manager, &context_key, &target_path, change_ids.as_deref()).await {
+        Ok(result) => {
+            log::info!(
+                "✅ Replayed {} changes, skipped {}",
+                result.applied_change_ids.len(),
+                result.skipped.len()
+            );
+            Ok(DbResponse {
+                success: true,
+                data: Some(result),
+                error: None,
+            })
+        }
+        Err(e) => {
+            log::error!("❌ Replaying changes failed: {}", e);
+            Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::database::change_history::{create_change_event, extract_context_from_path, FieldChange};
+    use crate::commands::database::tests::create_test_database;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn change(operation: OperationType, table: &str, fields: &[(&str, Option<serde_json::Value>)], sql: &str) -> ChangeEvent {
+        let context = extract_context_from_path(
+            "/tmp/app.db",
+            Some("emulator-5554".to_string()),
+            None,
+            Some("android".to_string()),
+            Some("com.example.app".to_string()),
+            None,
+        );
+        let fields = fields
+            .iter()
+            .map(|(name, value)| FieldChange {
+                field_name: name.to_string(),
+                old_value: None,
+                new_value: value.clone(),
+                data_type: "TEXT".to_string(),
+            })
+            .collect();
+        create_change_event("/tmp/app.db", table, operation, context, fields, None, Some(sql.to_string())).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_replay_changes_onto_fresh_copy() {
+        let temp_dir = TempDir::new().unwrap();
+        let target = temp_dir.path().join("fresh.db");
+        create_test_database(&target.to_string_lossy()).unwrap();
+
+        let history = ChangeHistoryManager::new();
+        let changes = vec![
+            change(OperationType::Update, "users", &[("name", Some(json!("Johnny")))], "UPDATE users SET name = ? WHERE id = 1"),
+            change(
+                OperationType::Insert,
+                "users",
+                &[("name", Some(json!("Local"))), ("email", Some(json!("local@example.com")))],
+                "INSERT INTO users (name, email) VALUES (?, ?)",
+            ),
+            change(OperationType::Delete, "users", &[], "DELETE FROM users WHERE age > 28"),
+            change(OperationType::Delete, "users", &[], "DELETE FROM users WHERE age > 28"),
+            change(OperationType::Update, "users", &[("age", Some(json!(1)))], "UPDATE users SET age = ? WHERE id = 99"),
+            change(OperationType::BulkUpdate { count: 2 }, "users", &[], "UPDATE users SET age = ? WHERE age < ?"),
+        ];
+        let context_key = changes[0].context_key.clone();
+        for change in &changes {
+            history.record_change(change.clone()).await.unwrap();
+        }
+
+        let result = replay_changes_onto(&history, &context_key, &target.to_string_lossy(), None).await.unwrap();
+        assert_eq!(result.applied_change_ids.len(), 4);
+        assert_eq!(result.skipped.len(), 2);
+        assert_eq!(result.skipped[0].change_id, changes[4].id);
+        assert_eq!(result.skipped[0].reason, "No matching row in the target database");
+
+        let conn = rusqlite::Connection::open(&target).unwrap();
+        let names: Vec<String> = conn
+            .prepare("SELECT name FROM users ORDER BY id")
+            .unwrap()
+            .query_map([], |r| r.get(0))
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(names, vec!["Jane Smith", "Local"]);
+    }
+}
@@ -0,0 +1,131 @@
+// Table and column metadata kept in memory per database file
+// Entries are tagged with PRAGMA schema_version, which SQLite bumps on every schema change and
+// stores in the file header, so any connection can tell whether a cached entry is still current.
+
+use crate::commands::database::helpers::{column_info_from_row, ALL_TABLE_COLUMNS_QUERY};
+use crate::commands::database::types::ColumnInfo;
+use sqlx::{sqlite::SqlitePool, Row};
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+
+pub static SCHEMA_CACHE: LazyLock<SchemaCache> = LazyLock::new(SchemaCache::default);
+
+/// Tables and columns of one database at one schema version
+#[derive(Debug, Default)]
+pub struct SchemaSnapshot {
+    pub schema_version: i64,
+    // In sqlite_master order, as db_get_tables has always listed them
+    pub tables: Vec<String>,
+    pub columns: HashMap<String, Vec<ColumnInfo>>,
+}
+
+#[derive(Default)]
+pub struct SchemaCache {
+    entries: Mutex<HashMap<String, Arc<SchemaSnapshot>>>,
+}
+
+impl SchemaCache {
+    /// The schema of `db_path`, read from memory unless its schema version moved on.
+    /// Costs one `PRAGMA schema_version` when cached, one more query when not.
+    pub async fn load(&self, pool: &SqlitePool, db_path: &str) -> Result<Arc<SchemaSnapshot>, String> {
+        let schema_version = read_schema_version(pool).await?;
+        if let Some(snapshot) = self.get(db_path).filter(|snapshot| snapshot.schema_version == schema_version) {
+            return Ok(snapshot);
+        }
+
+        let snapshot = Arc::new(read_snapshot(pool, schema_version).await?);
+        log::info!(
+            "📐 Cached schema v{} of {} ({} tables)",
+            schema_version,
+            db_path,
+            snapshot.tables.len()
+        );
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(db_path.to_string(), snapshot.clone());
+        Ok(snapshot)
+    }
+
+    pub fn get(&self, db_path: &str) -> Option<Arc<SchemaSnapshot>> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(db_path)
+            .cloned()
+    }
+
+    /// Forget one database, or all of them. Needed when the file itself is replaced, since a
+    /// different file can carry the same schema version.
+    pub fn invalidate(&self, db_path: Option<&str>) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        match db_path {
+            Some(db_path) => {
+                entries.remove(db_path);
+            }
+            None => entries.clear(),
+        }
+    }
+}
+
+async fn read_schema_version(pool: &SqlitePool) -> Result<i64, String> {
+    sqlx::query_scalar("PRAGMA schema_version")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to read schema version: {}", e))
+}
+
+async fn read_snapshot(pool: &SqlitePool, schema_version: i64) -> Result<SchemaSnapshot, String> {
+    let rows = sqlx::query(ALL_TABLE_COLUMNS_QUERY)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to read schema metadata: {}", e))?;
+
+    let mut snapshot = SchemaSnapshot {
+        schema_version,
+        ..Default::default()
+    };
+    for row in &rows {
+        let table = row.get::<String, _>("table_name");
+        if !snapshot.columns.contains_key(&table) {
+            snapshot.tables.push(table.clone());
+        }
+        snapshot.columns.entry(table).or_default().push(column_info_from_row(row));
+    }
+    Ok(snapshot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_schema_change_invalidates_entry() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("schema.db");
+        let db_path = db_path.to_str().unwrap();
+        rusqlite::Connection::open(db_path)
+            .unwrap()
+            .execute_batch("CREATE TABLE b (id INTEGER PRIMARY KEY); CREATE TABLE a (id INTEGER, name TEXT);")
+            .unwrap();
+        let pool = SqlitePool::connect(&format!("sqlite:{}", db_path)).await.unwrap();
+
+        let cache = SchemaCache::default();
+        let first = cache.load(&pool, db_path).await.unwrap();
+        assert_eq!(first.tables, vec!["b".to_string(), "a".to_string()]);
+        assert!(Arc::ptr_eq(&first, &cache.load(&pool, db_path).await.unwrap()));
+
+        // Plain writes keep the entry, schema changes replace it
+        sqlx::query("INSERT INTO a VALUES (1, 'x')").execute(&pool).await.unwrap();
+        assert!(Arc::ptr_eq(&first, &cache.load(&pool, db_path).await.unwrap()));
+        sqlx::query("ALTER TABLE a ADD COLUMN extra TEXT").execute(&pool).await.unwrap();
+        let altered = cache.load(&pool, db_path).await.unwrap();
+        assert!(altered.schema_version > first.schema_version);
+        assert_eq!(altered.columns["a"].len(), 3);
+
+        cache.invalidate(Some(db_path));
+        assert!(cache.get(db_path).is_none());
+        pool.close().await;
+    }
+}
@@ -0,0 +1,261 @@
+// Schema diagram export
+// Renders tables and foreign keys as Graphviz DOT or a Mermaid ER diagram for documentation
+
+use crate::commands::database::connection_access::get_current_pool;
+use crate::commands::database::delete_impact::{read_references, ForeignKeyRef};
+use crate::commands::database::migration::{read_database_schema, DatabaseSchema};
+use crate::commands::database::types::*;
+use std::collections::BTreeMap;
+use tauri::State;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagramFormat {
+    Dot,
+    Mermaid,
+}
+
+impl DiagramFormat {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.to_lowercase().as_str() {
+            "dot" | "graphviz" => Ok(Self::Dot),
+            "mermaid" => Ok(Self::Mermaid),
+            other => Err(format!("Unsupported diagram format '{}'; expected dot or mermaid", other)),
+        }
+    }
+}
+
+fn dot_quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// Characters with a meaning inside record labels
+fn dot_record_escape(value: &str) -> String {
+    value
+        .chars()
+        .flat_map(|c| match c {
+            '{' | '}' | '|' | '<' | '>' | '"' | '\\' => vec!['\\', c],
+            _ => vec![c],
+        })
+        .collect()
+}
+
+/// Mermaid identifiers only allow letters, digits, `_` and `-`.
+fn mermaid_ident(value: &str) -> String {
+    let ident: String = value
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '-' { c } else { '_' })
+        .collect();
+    if ident.is_empty() {
+        "_".to_string()
+    } else {
+        ident
+    }
+}
+
+fn mermaid_type(type_name: &str) -> String {
+    if type_name.trim().is_empty() {
+        return "ANY".to_string();
+    }
+    type_name
+        .trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || "_()[]".contains(c) { c } else { '_' })
+        .collect()
+}
+
+fn foreign_key_columns(references: &BTreeMap<String, Vec<ForeignKeyRef>>, table: &str) -> Vec<String> {
+    references
+        .values()
+        .flatten()
+        .filter(|reference| reference.child_table == table)
+        .flat_map(|reference| reference.child_columns.clone())
+        .collect()
+}
+
+pub fn render_dot(schema: &DatabaseSchema, references: &BTreeMap<String, Vec<ForeignKeyRef>>) -> String {
+    let mut lines = vec![
+        "digraph schema {".to_string(),
+        "  rankdir=LR;".to_string(),
+        "  node [shape=record, fontname=\"Helvetica\"];".to_string(),
+    ];
+
+    for table in schema.tables.values() {
+        let foreign_keys = foreign_key_columns(references, &table.name);
+        let fields: Vec<String> = table
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(index, column)| {
+                let mut label = format!("{} {}", column.name, column.type_name).trim_end().to_string();
+                if column.pk > 0 {
+                    label.push_str(" PK");
+                }
+                if foreign_keys.contains(&column.name) {
+                    label.push_str(" FK");
+                }
+                format!("<c{}> {}\\l", index, dot_record_escape(&label))
+            })
+            .collect();
+        lines.push(format!(
+            "  {} [label=\"{{{}|{}}}\"];",
+            dot_quote(&table.name),
+            dot_record_escape(&table.name),
+            fields.join("|")
+        ));
+    }
+
+    let port = |table: &str, column: &str| -> String {
+        let index = schema
+            .tables
+            .get(table)
+            .and_then(|table| table.columns.iter().position(|c| c.name == column));
+        match index {
+            Some(index) => format!("{}:c{}", dot_quote(table), index),
+            None => dot_quote(table),
+        }
+    };
+
+    for (parent, children) in references {
+        for reference in children {
+            let child_column = reference.child_columns.first().map(String::as_str).unwrap_or_default();
+            let parent_column = reference.parent_columns.first().map(String::as_str).unwrap_or_default();
+            lines.push(format!(
+                "  {} -> {} [label={}];",
+                port(&reference.child_table, child_column),
+                port(parent, parent_column),
+                dot_quote(&reference.child_columns.join(", "))
+            ));
+        }
+    }
+
+    lines.push("}".to_string());
+    lines.join("\n") + "\n"
+}
+
+pub fn render_mermaid(schema: &DatabaseSchema, references: &BTreeMap<String, Vec<ForeignKeyRef>>) -> String {
+    let mut lines = vec!["erDiagram".to_string()];
+
+    for table in schema.tables.values() {
+        let foreign_keys = foreign_key_columns(references, &table.name);
+        lines.push(format!("    {} {{", mermaid_ident(&table.name)));
+        for column in &table.columns {
+            let keys: Vec<&str> = [(column.pk > 0, "PK"), (foreign_keys.contains(&column.name), "FK")]
+                .into_iter()
+                .filter_map(|(present, key)| present.then_some(key))
+                .collect();
+            let mut line = format!(
+                "        {} {}",
+                mermaid_type(&column.type_name),
+                mermaid_ident(&column.name)
+            );
+            if !keys.is_empty() {
+                line.push(' ');
+                line.push_str(&keys.join(", "));
+            }
+            lines.push(line);
+        }
+        lines.push("    }".to_string());
+    }
+
+    for (parent, children) in references {
+        for reference in children {
+            lines.push(format!(
+                "    {} ||--o{{ {} : \"{}\"",
+                mermaid_ident(parent),
+                mermaid_ident(&reference.child_table),
+                reference.child_columns.join(", ").replace('"', "'")
+            ));
+        }
+    }
+
+    lines.join("\n") + "\n"
+}
+
+/// Export the data model as a DOT or Mermaid diagram.
+#[tauri::command]
+pub async fn db_export_schema_diagram(
+    state: State<'_, DbPool>,
+    db_cache: State<'_, DbConnectionCache>,
+    format: String,
+    current_db_path: Option<String>,
+) -> Result<DbResponse<String>, String> {
+    log::info!("🗺️ Exporting schema diagram as {}", format);
+
+    let result = async {
+        let format = DiagramFormat::parse(&format)?;
+        let pool = get_current_pool(&state, &db_cache, current_db_path).await?;
+        let schema = read_database_schema(&pool).await?;
+        let references = read_references(&pool).await?;
+        Ok::<_, String>(match format {
+            DiagramFormat::Dot => render_dot(&schema, &references),
+            DiagramFormat::Mermaid => render_mermaid(&schema, &references),
+        })
+    }
+    .await;
+
+    match result {
+        Ok(diagram) => Ok(DbResponse {
+            success: true,
+            data: Some(diagram),
+            error: None,
+        }),
+        Err(e) => {
+            log::error!("❌ Failed to export schema diagram: {}", e);
+            Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePool;
+    use tempfile::TempDir;
+
+    async fn fixture_pool(temp_dir: &TempDir) -> SqlitePool {
+        let db_path = temp_dir.path().join("diagram.db");
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT);
+             CREATE TABLE \"order items\" (id INTEGER PRIMARY KEY, user_id INTEGER REFERENCES users(id), price DECIMAL(10, 2));",
+        )
+        .unwrap();
+        drop(conn);
+
+        SqlitePool::connect(&format!("sqlite:{}", db_path.display()))
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!(DiagramFormat::parse("Mermaid"), Ok(DiagramFormat::Mermaid));
+        assert_eq!(DiagramFormat::parse("graphviz"), Ok(DiagramFormat::Dot));
+        assert!(DiagramFormat::parse("svg").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_render_diagrams() {
+        let temp_dir = TempDir::new().unwrap();
+        let pool = fixture_pool(&temp_dir).await;
+        let schema = read_database_schema(&pool).await.unwrap();
+        let references = read_references(&pool).await.unwrap();
+        pool.close().await;
+
+        let mermaid = render_mermaid(&schema, &references);
+        assert!(mermaid.starts_with("erDiagram\n"));
+        assert!(mermaid.contains("    order_items {\n"));
+        assert!(mermaid.contains("        INTEGER user_id FK\n"));
+        assert!(mermaid.contains("        DECIMAL(10__2) price\n"));
+        assert!(mermaid.contains("    users ||--o{ order_items : \"user_id\"\n"));
+
+        let dot = render_dot(&schema, &references);
+        assert!(dot.starts_with("digraph schema {\n"));
+        assert!(dot.contains("\"users\" [label=\"{users|<c0> id INTEGER PK\\l|<c1> name TEXT\\l}\"];"));
+        assert!(dot.contains("\"order items\":c1 -> \"users\":c0 [label=\"user_id\"];"));
+    }
+}
@@ -0,0 +1,228 @@
+// Snapshot timeline diffing
+// Compares two snapshots of the same device database table by table, reusing the query comparison diff
+
+use crate::commands::database::compare::{diff_result_rows, run_compared_query, ResultRow, ResultSetDiff};
+use crate::commands::database::connection_access::open_uncached_connection;
+use crate::commands::database::helpers::quote_ident;
+use crate::commands::database::migration::{read_database_schema, DatabaseSchema};
+use crate::commands::database::types::*;
+use crate::commands::device::scheduler::snapshot_taken_at;
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqlitePool;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+// Rows read per table and side; larger tables are diffed on their first rows only
+const MAX_DIFF_ROWS_PER_TABLE: usize = 50_000;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TableSnapshotDiff {
+    pub table: String,
+    // "added", "removed", "changed" or "unchanged"
+    pub status: String,
+    // Primary key used to match rows; empty means rows are compared as whole values
+    pub key_columns: Vec<String>,
+    pub diff: ResultSetDiff,
+    pub truncated: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotDiff {
+    pub left_path: String,
+    pub right_path: String,
+    pub left_taken_at: Option<String>,
+    pub right_taken_at: Option<String>,
+    pub tables: Vec<TableSnapshotDiff>,
+    pub identical: bool,
+}
+
+fn taken_at_for(path: &str) -> Option<String> {
+    Path::new(path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(snapshot_taken_at)
+        .map(|taken_at| taken_at.to_rfc3339())
+}
+
+fn primary_key_columns(schema: &DatabaseSchema, table: &str) -> Vec<String> {
+    let Some(table) = schema.tables.get(table) else {
+        return Vec::new();
+    };
+    let mut pk_columns: Vec<_> = table.columns.iter().filter(|column| column.pk > 0).collect();
+    pk_columns.sort_by_key(|column| column.pk);
+    pk_columns.into_iter().map(|column| column.name.clone()).collect()
+}
+
+async fn read_table_rows(
+    pool: &SqlitePool,
+    db_path: &str,
+    table: &str,
+) -> Result<(Vec<ResultRow>, bool), String> {
+    let query = format!("SELECT * FROM {} LIMIT {}", quote_ident(table), MAX_DIFF_ROWS_PER_TABLE + 1);
+    let mut rows = run_compared_query(pool, db_path, &query).await?.rows;
+    let truncated = rows.len() > MAX_DIFF_ROWS_PER_TABLE;
+    rows.truncate(MAX_DIFF_ROWS_PER_TABLE);
+    Ok((rows, truncated))
+}
+
+async fn diff_tables(
+    left_pool: &SqlitePool,
+    right_pool: &SqlitePool,
+    left_path: &str,
+    right_path: &str,
+) -> Result<Vec<TableSnapshotDiff>, String> {
+    let left_schema = read_database_schema(left_pool).await?;
+    let right_schema = read_database_schema(right_pool).await?;
+
+    let table_names: BTreeSet<&String> = left_schema.tables.keys().chain(right_schema.tables.keys()).collect();
+    let mut tables = Vec::new();
+
+    for table in table_names {
+        let in_left = left_schema.tables.contains_key(table);
+        let in_right = right_schema.tables.contains_key(table);
+
+        let (left_rows, left_truncated) = if in_left {
+            read_table_rows(left_pool, left_path, table).await?
+        } else {
+            (Vec::new(), false)
+        };
+        let (right_rows, right_truncated) = if in_right {
+            read_table_rows(right_pool, right_path, table).await?
+        } else {
+            (Vec::new(), false)
+        };
+
+        // Only match by key when both sides agree on it, otherwise fall back to whole-row comparison
+        let left_key = primary_key_columns(&left_schema, table);
+        let key_columns = if in_left && in_right && left_key != primary_key_columns(&right_schema, table) {
+            Vec::new()
+        } else if in_left {
+            left_key
+        } else {
+            primary_key_columns(&right_schema, table)
+        };
+
+        let diff = diff_result_rows(&left_rows, &right_rows, &key_columns);
+        let status = match (in_left, in_right) {
+            (false, _) => "added",
+            (_, false) => "removed",
+            _ if diff.identical => "unchanged",
+            _ => "changed",
+        };
+
+        tables.push(TableSnapshotDiff {
+            table: table.clone(),
+            status: status.to_string(),
+            key_columns,
+            diff,
+            truncated: left_truncated || right_truncated,
+        });
+    }
+
+    Ok(tables)
+}
+
+pub async fn diff_snapshots(
+    left_path: &str,
+    right_path: &str,
+) -> Result<SnapshotDiff, String> {
+    for path in [left_path, right_path] {
+        if !Path::new(path).is_file() {
+            return Err(format!("Snapshot does not exist: {}", path));
+        }
+    }
+
+    let left_pool = open_uncached_connection(left_path).await?;
+    let right_pool = open_uncached_connection(right_path).await?;
+
+    let tables = diff_tables(&left_pool, &right_pool, left_path, right_path).await;
+
+    left_pool.close().await;
+    right_pool.close().await;
+
+    let tables = tables?;
+    Ok(SnapshotDiff {
+        left_path: left_path.to_string(),
+        right_path: right_path.to_string(),
+        left_taken_at: taken_at_for(left_path),
+        right_taken_at: taken_at_for(right_path),
+        identical: tables.iter().all(|table| table.status == "unchanged"),
+        tables,
+    })
+}
+
+/// Diff two timestamped snapshots of the same device database, `a` being the older one
+#[tauri::command]
+pub async fn snapshot_diff(
+    a: String,
+    b: String,
+) -> Result<DbResponse<SnapshotDiff>, String> {
+    log::info!("🕰️ Diffing snapshots '{}' and '{}'", a, b);
+
+    match diff_snapshots(&a, &b).await {
+        Ok(diff) => {
+            let changed = diff.tables.iter().filter(|table| table.status != "unchanged").count();
+            log::info!("✅ Snapshot diff finished: {} of {} tables differ", changed, diff.tables.len());
+            Ok(DbResponse {
+                success: true,
+                data: Some(diff),
+                error: None,
+            })
+        }
+        Err(e) => {
+            log::error!("❌ Snapshot diff failed: {}", e);
+            Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::database::tests::create_test_database;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_diff_snapshots_reports_table_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let older = temp_dir.path().join("20240501T090000Z_app.db");
+        let newer = temp_dir.path().join("20240501T091500Z_app.db");
+        create_test_database(older.to_str().unwrap()).unwrap();
+        create_test_database(newer.to_str().unwrap()).unwrap();
+
+        let conn = rusqlite::Connection::open(&newer).unwrap();
+        conn.execute("UPDATE users SET age = 99 WHERE id = 2", []).unwrap();
+        conn.execute("CREATE TABLE sessions (id INTEGER PRIMARY KEY, token TEXT)", []).unwrap();
+        drop(conn);
+
+        let diff = diff_snapshots(older.to_str().unwrap(), newer.to_str().unwrap())
+            .await
+            .unwrap();
+
+        assert!(!diff.identical);
+        assert_eq!(diff.left_taken_at.as_deref(), Some("2024-05-01T09:00:00+00:00"));
+
+        let users = diff.tables.iter().find(|table| table.table == "users").unwrap();
+        assert_eq!(users.status, "changed");
+        assert_eq!(users.key_columns, vec!["id".to_string()]);
+        assert_eq!(users.diff.changed.len(), 1);
+        assert_eq!(users.diff.changed[0].right.get("age"), Some(&json!(99)));
+
+        let sessions = diff.tables.iter().find(|table| table.table == "sessions").unwrap();
+        assert_eq!(sessions.status, "added");
+    }
+
+    #[tokio::test]
+    async fn test_diff_snapshots_requires_existing_files() {
+        let result = diff_snapshots("missing-a.db", "missing-b.db").await;
+
+        assert!(result.unwrap_err().contains("does not exist"));
+    }
+}
@@ -1,15 +1,84 @@
 use crate::commands::database::connection_access::{
-    get_cached_connection, get_current_pool, validate_pool_health,
+    get_cached_connection, get_current_pool, get_read_pool, validate_pool_health,
 };
-use crate::commands::database::helpers::get_default_value_for_type;
+use crate::commands::database::helpers::{
+    column_info_from_row, decode_row_to_json, ensure_supported_database_format, quote_ident, TABLE_INFO_QUERY,
+};
+use crate::commands::database::archives::resolve_archive_database;
+use crate::commands::database::compressed_cells::detect_compressed_columns;
+use crate::commands::database::connection_manager::DatabaseConnectionManager;
+use crate::commands::database::timestamps::detect_timestamp_columns;
+use crate::commands::database::transformers::{apply_transformers, table_transformers};
 use crate::commands::database::types::*;
-use base64::{engine::general_purpose, Engine as _};
-use sqlx::{sqlite::SqlitePool, Column, Row, TypeInfo, ValueRef};
+use crate::commands::settings::current_settings;
+use futures::TryStreamExt;
+use sqlx::{sqlite::SqlitePool, Row};
 use std::collections::HashMap;
 use tauri::State;
 
 const FLIPPIO_ROWID_COLUMN: &str = "__flippio_rowid";
 
+/// How table data is read, from the connection settings. Streaming decodes rows one at a time
+/// instead of holding every raw row and its decoded copy in memory at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LargeReadMode {
+    pub streaming: bool,
+    pub mmap_size: u64,
+}
+
+impl LargeReadMode {
+    pub fn from_settings() -> Self {
+        let connection = current_settings().connection;
+        Self {
+            streaming: connection.streaming_reads,
+            mmap_size: supported_mmap_size(connection.mmap_size_bytes),
+        }
+    }
+}
+
+/// Memory-mapping multi-GB files exhausts the address space of 32-bit processes, so mmap is
+/// only used on 64-bit targets.
+pub fn supported_mmap_size(requested: u64) -> u64 {
+    if requested > 0 && !cfg!(target_pointer_width = "64") {
+        log::warn!("⚠️ mmap_size ignored on a 32-bit platform; using regular reads");
+        return 0;
+    }
+    requested
+}
+
+/// Run a SELECT and decode its rows, streaming them on one connection when configured.
+pub(crate) async fn fetch_decoded_rows(
+    pool: &SqlitePool,
+    query: &str,
+    mode: LargeReadMode,
+) -> Result<Vec<HashMap<String, serde_json::Value>>, sqlx::Error> {
+    if !mode.streaming && mode.mmap_size == 0 {
+        let rows = sqlx::query(query).fetch_all(pool).await?;
+        return Ok(rows.iter().map(decode_row_to_json).collect());
+    }
+
+    // PRAGMA mmap_size is per connection, so the pragma and the read share one
+    let mut connection = pool.acquire().await?;
+    if mode.mmap_size > 0 {
+        let applied: i64 = sqlx::query_scalar(&format!("PRAGMA mmap_size = {}", mode.mmap_size))
+            .fetch_one(&mut *connection)
+            .await?;
+        log::info!("🗺️ Memory-mapped reads enabled: {} bytes", applied);
+    }
+
+    if !mode.streaming {
+        let rows = sqlx::query(query).fetch_all(&mut *connection).await?;
+        return Ok(rows.iter().map(decode_row_to_json).collect());
+    }
+
+    let mut decoded = Vec::new();
+    let mut stream = sqlx::query(query).fetch(&mut *connection);
+    while let Some(row) = stream.try_next().await? {
+        decoded.push(decode_row_to_json(&row));
+    }
+    Ok(decoded)
+}
+
 #[tauri::command]
 pub async fn db_open(
     state: State<'_, DbPool>,
@@ -18,9 +87,30 @@ pub async fn db_open(
 ) -> Result<DbResponse<String>, String> {
     log::info!("Opening database with caching: {}", file_path);
 
+    // Archives and app bundles are opened through the database extracted from them
+    let file_path = match resolve_archive_database(&file_path) {
+        Ok(path) => path,
+        Err(e) => {
+            log::error!("Failed to open database: {}", e);
+            return Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            });
+        }
+    };
+
     match get_cached_connection(&db_cache, &file_path).await {
         Ok(pool) => {
-            *state.write().await = Some(pool);
+            *state.write().await = Some(pool.clone());
+
+            // Load every table's columns in the background so the first table click is instant
+            let warm_path = file_path.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = DatabaseConnectionManager::warm_schema(&pool, &warm_path).await {
+                    log::warn!("⚠️ Schema warm-up failed for {}: {}", warm_path, e);
+                }
+            });
 
             Ok(DbResponse {
                 success: true,
@@ -45,7 +135,7 @@ pub async fn db_get_tables(
     db_cache: State<'_, DbConnectionCache>,
     current_db_path: Option<String>,
 ) -> Result<DbResponse<Vec<TableInfo>>, String> {
-    let pool = match get_current_pool(&state, &db_cache, current_db_path).await {
+    let pool = match get_current_pool(&state, &db_cache, current_db_path.clone()).await {
         Ok(pool) => pool,
         Err(e) => {
             log::error!("❌ {}", e);
@@ -57,6 +147,19 @@ pub async fn db_get_tables(
         }
     };
 
+    if let Some(db_path) = current_db_path.as_deref() {
+        match DatabaseConnectionManager::schema(&pool, db_path).await {
+            Ok(schema) => {
+                return Ok(DbResponse {
+                    success: true,
+                    data: Some(schema.tables.iter().map(|name| TableInfo { name: name.clone() }).collect()),
+                    error: None,
+                });
+            }
+            Err(e) => log::warn!("⚠️ Schema cache unavailable, listing tables directly: {}", e),
+        }
+    }
+
     match sqlx::query("SELECT name FROM sqlite_master WHERE type='table' AND name NOT LIKE 'sqlite_%'")
         .fetch_all(&pool)
         .await
@@ -87,14 +190,35 @@ pub async fn db_get_tables(
 
 #[tauri::command]
 pub async fn db_get_table_data(
+    app_handle: tauri::AppHandle,
     state: State<'_, DbPool>,
     db_cache: State<'_, DbConnectionCache>,
     table_name: String,
     current_db_path: Option<String>,
+    // Only read these columns (e.g. the visible ones); large TEXT/BLOB cells can be
+    // loaded on demand with db_get_cell_value. Column metadata always covers the whole table.
+    columns: Option<Vec<String>>,
 ) -> Result<DbResponse<TableData>, String> {
     log::info!("📊 Getting table data for: {}", table_name);
 
-    let mut pool = match get_current_pool(&state, &db_cache, current_db_path.clone()).await {
+    let requested_columns = columns.filter(|columns| !columns.is_empty());
+    let cache_key = match &requested_columns {
+        Some(requested) => format!("table_data:{}:{}", table_name, requested.join(",")),
+        None => format!("table_data:{}", table_name),
+    };
+    if let Some(cached) = current_db_path
+        .as_deref()
+        .and_then(|db_path| DatabaseConnectionManager::cached_result(db_path, &cache_key))
+        .and_then(|cached| serde_json::from_value::<TableData>(cached).ok())
+    {
+        return Ok(DbResponse {
+            success: true,
+            data: Some(cached),
+            error: None,
+        });
+    }
+
+    let mut pool = match get_read_pool(&state, &db_cache, current_db_path.clone()).await {
         Ok(pool) => pool,
         Err(e) => {
             log::error!("❌ {}", e);
@@ -108,7 +232,8 @@ pub async fn db_get_table_data(
 
     if !validate_pool_health(&pool).await {
         log::warn!("🔄 Pool failed health check, attempting to get fresh connection");
-        match get_current_pool(&state, &db_cache, current_db_path.clone()).await {
+        DatabaseConnectionManager::drop_read_replicas(current_db_path.as_deref());
+        match get_read_pool(&state, &db_cache, current_db_path.clone()).await {
             Ok(fresh_pool) => {
                 if validate_pool_health(&fresh_pool).await {
                     log::info!("✅ Fresh pool passed health check");
@@ -163,36 +288,54 @@ pub async fn db_get_table_data(
         current_db_path.as_deref().unwrap_or("unknown")
     );
 
-    let column_query = format!("PRAGMA table_info({})", table_name);
-    let column_rows = match sqlx::query(&column_query).fetch_all(&pool).await {
-        Ok(rows) => {
-            log::info!("✅ Retrieved {} columns for table '{}'", rows.len(), table_name);
-            rows
-        }
-        Err(e) => {
-            log::error!("❌ Error getting table info for '{}': {}", table_name, e);
-            return Ok(DbResponse {
-                success: false,
-                data: None,
-                error: Some(format!("Error getting table info: {}", e)),
-            });
+    let cached_columns = match current_db_path.as_deref() {
+        Some(db_path) => DatabaseConnectionManager::table_columns(&pool, db_path, &table_name)
+            .await
+            .unwrap_or_else(|e| {
+                log::warn!("⚠️ Schema cache unavailable, reading columns directly: {}", e);
+                None
+            }),
+        None => None,
+    };
+    let columns: Vec<ColumnInfo> = match cached_columns {
+        Some(columns) => columns,
+        None => match sqlx::query(TABLE_INFO_QUERY).bind(&table_name).fetch_all(&pool).await {
+            Ok(rows) => {
+                log::info!("✅ Retrieved {} columns for table '{}'", rows.len(), table_name);
+                rows.iter().map(column_info_from_row).collect()
+            }
+            Err(e) => {
+                log::error!("❌ Error getting table info for '{}': {}", table_name, e);
+                return Ok(DbResponse {
+                    success: false,
+                    data: None,
+                    error: Some(format!("Error getting table info: {}", e)),
+                });
+            }
+        },
+    };
+
+    let select_list = match &requested_columns {
+        Some(requested) => {
+            if let Some(unknown) = requested
+                .iter()
+                .find(|name| !columns.iter().any(|column| &column.name == *name))
+            {
+                return Ok(DbResponse {
+                    success: false,
+                    data: None,
+                    error: Some(format!("Column '{}' does not exist in table '{}'", unknown, table_name)),
+                });
+            }
+            requested.iter().map(|name| quote_ident(name)).collect::<Vec<_>>().join(", ")
         }
+        None => "*".to_string(),
     };
 
-    let columns: Vec<ColumnInfo> = column_rows
-        .iter()
-        .map(|row| ColumnInfo {
-            name: row.get::<String, _>("name"),
-            type_name: row.get::<String, _>("type"),
-            notnull: row.get::<i64, _>("notnull") != 0,
-            pk: row.get::<i64, _>("pk") != 0,
-            default_value: get_default_value_for_type(&row.get::<String, _>("type")),
-        })
-        .collect();
-
-    let data_query_with_rowid = format!("SELECT rowid AS {}, * FROM {}", FLIPPIO_ROWID_COLUMN, table_name);
-    let data_query_without_rowid = format!("SELECT * FROM {}", table_name);
-    let data_rows = match sqlx::query(&data_query_with_rowid).fetch_all(&pool).await {
+    let data_query_with_rowid = format!("SELECT rowid AS {}, {} FROM {}", FLIPPIO_ROWID_COLUMN, select_list, table_name);
+    let data_query_without_rowid = format!("SELECT {} FROM {}", select_list, table_name);
+    let read_mode = LargeReadMode::from_settings();
+    let rows = match fetch_decoded_rows(&pool, &data_query_with_rowid, read_mode).await {
         Ok(rows) => {
             log::info!("✅ Retrieved {} rows from table '{}' with rowid metadata", rows.len(), table_name);
             rows
@@ -204,7 +347,7 @@ pub async fn db_get_table_data(
                 rowid_error
             );
 
-            match sqlx::query(&data_query_without_rowid).fetch_all(&pool).await {
+            match fetch_decoded_rows(&pool, &data_query_without_rowid, read_mode).await {
                 Ok(rows) => {
                     log::info!("✅ Retrieved {} rows from table '{}'", rows.len(), table_name);
                     rows
@@ -221,72 +364,6 @@ pub async fn db_get_table_data(
         }
     };
 
-    let mut rows = Vec::new();
-    for row in data_rows {
-        let mut row_data = HashMap::new();
-        for (i, column) in row.columns().iter().enumerate() {
-            let value = match row.try_get_raw(i) {
-                Ok(raw_value) => {
-                    if raw_value.is_null() {
-                        serde_json::Value::Null
-                    } else {
-                        match column.type_info().name() {
-                            "TEXT" => match row.try_get::<String, _>(i) {
-                                Ok(val) => serde_json::Value::String(val),
-                                Err(_) => serde_json::Value::String("".to_string()),
-                            },
-                            "INTEGER" => match row.try_get::<i64, _>(i) {
-                                Ok(val) => serde_json::Value::Number(serde_json::Number::from(val)),
-                                Err(_) => match row.try_get::<String, _>(i) {
-                                    Ok(str_val) => {
-                                        if let Ok(int_val) = str_val.parse::<i64>() {
-                                            serde_json::Value::Number(serde_json::Number::from(int_val))
-                                        } else {
-                                            serde_json::Value::String(str_val)
-                                        }
-                                    }
-                                    Err(_) => serde_json::Value::Null,
-                                },
-                            },
-                            "REAL" => match row.try_get::<f64, _>(i) {
-                                Ok(val) => serde_json::Value::Number(
-                                    serde_json::Number::from_f64(val)
-                                        .unwrap_or(serde_json::Number::from(0)),
-                                ),
-                                Err(_) => match row.try_get::<String, _>(i) {
-                                    Ok(str_val) => {
-                                        if let Ok(float_val) = str_val.parse::<f64>() {
-                                            serde_json::Value::Number(
-                                                serde_json::Number::from_f64(float_val)
-                                                    .unwrap_or(serde_json::Number::from(0)),
-                                            )
-                                        } else {
-                                            serde_json::Value::String(str_val)
-                                        }
-                                    }
-                                    Err(_) => serde_json::Value::Null,
-                                },
-                            },
-                            "BLOB" => match row.try_get::<Vec<u8>, _>(i) {
-                                Ok(blob_data) => {
-                                    serde_json::Value::String(general_purpose::STANDARD.encode(blob_data))
-                                }
-                                Err(_) => serde_json::Value::String("".to_string()),
-                            },
-                            _ => match row.try_get::<String, _>(i) {
-                                Ok(val) => serde_json::Value::String(val),
-                                Err(_) => serde_json::Value::String("Unknown type".to_string()),
-                            },
-                        }
-                    }
-                }
-                Err(_) => serde_json::Value::Null,
-            };
-            row_data.insert(column.name().to_string(), value);
-        }
-        rows.push(row_data);
-    }
-
     log::info!(
         "✅ Successfully processed table data for '{}' from database '{}': {} columns, {} rows",
         table_name,
@@ -295,15 +372,88 @@ pub async fn db_get_table_data(
         rows.len()
     );
 
+    let timestamp_columns = detect_timestamp_columns(&columns, &rows);
+    let transformed_columns = match current_db_path.as_deref() {
+        Some(db_path) => apply_transformers(&columns, &rows, &table_transformers(&app_handle, db_path, &table_name)),
+        None => Vec::new(),
+    };
+    let compressed_columns = detect_compressed_columns(&columns, &rows);
+    let table_data = TableData {
+        columns,
+        rows,
+        timestamp_columns,
+        transformed_columns,
+        compressed_columns,
+    };
+    if let Some(db_path) = current_db_path.as_deref() {
+        DatabaseConnectionManager::store_result(db_path, &cache_key, &table_data);
+    }
+
     Ok(DbResponse {
         success: true,
-        data: Some(TableData { columns, rows }),
+        data: Some(table_data),
         error: None,
     })
 }
 
+/// Load a single cell, for columns left out of a lazy db_get_table_data read.
+#[tauri::command]
+pub async fn db_get_cell_value(
+    state: State<'_, DbPool>,
+    db_cache: State<'_, DbConnectionCache>,
+    table_name: String,
+    column_name: String,
+    row_id: i64,
+    current_db_path: Option<String>,
+) -> Result<DbResponse<serde_json::Value>, String> {
+    let result = async {
+        let pool = get_current_pool(&state, &db_cache, current_db_path).await?;
+        let query = format!(
+            "SELECT {} FROM {} WHERE rowid = ?",
+            quote_ident(&column_name),
+            quote_ident(&table_name)
+        );
+        let row = sqlx::query(&query)
+            .bind(row_id)
+            .fetch_optional(&pool)
+            .await
+            .map_err(|e| format!("Error reading cell: {}", e))?
+            .ok_or_else(|| format!("Row {} not found in table '{}'", row_id, table_name))?;
+        Ok::<_, String>(
+            decode_row_to_json(&row)
+                .remove(&column_name)
+                .unwrap_or(serde_json::Value::Null),
+        )
+    }
+    .await;
+
+    match result {
+        Ok(value) => Ok(DbResponse {
+            success: true,
+            data: Some(value),
+            error: None,
+        }),
+        Err(e) => {
+            log::error!("❌ Failed to load cell {}.{}: {}", table_name, column_name, e);
+            Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            })
+        }
+    }
+}
+
 #[tauri::command]
 pub async fn db_get_info(file_path: String) -> Result<DbResponse<DbInfo>, String> {
+    if let Err(e) = ensure_supported_database_format(&file_path) {
+        return Ok(DbResponse {
+            success: false,
+            data: None,
+            error: Some(e),
+        });
+    }
+
     match std::fs::metadata(&file_path) {
         Ok(metadata) => {
             let pool = match SqlitePool::connect(&format!("sqlite:{}", file_path)).await {
@@ -0,0 +1,644 @@
+// Test data generation
+// Fills a table with realistic fake rows, guessing values from column names and types
+
+use crate::commands::database::commands::bind_json_values;
+use crate::commands::database::connection_access::get_current_pool;
+use crate::commands::database::helpers::{ensure_database_file_permissions, quote_ident, TABLE_INFO_QUERY};
+use crate::commands::database::types::*;
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqlitePool, Row};
+use std::collections::HashMap;
+use tauri::State;
+
+const MAX_GENERATED_ROWS: u32 = 100_000;
+// Parent keys sampled per foreign key
+const MAX_PARENT_KEYS: i64 = 1000;
+
+const FIRST_NAMES: &[&str] = &[
+    "Olivia", "Liam", "Emma", "Noah", "Ava", "Mateo", "Sofia", "Lucas", "Mia", "Arjun", "Yuki",
+    "Chen", "Fatima", "Omar", "Anna", "Ivan",
+];
+const LAST_NAMES: &[&str] = &[
+    "Smith", "Garcia", "Kowalski", "Nguyen", "Kim", "Müller", "Rossi", "Silva", "Patel", "Sato",
+    "Johnson", "Brown", "Novak", "Haddad",
+];
+const CITIES: &[&str] = &[
+    "Kyiv", "Lisbon", "Berlin", "Tokyo", "Austin", "Toronto", "Seoul", "Nairobi", "Lima", "Oslo",
+];
+const COUNTRIES: &[&str] = &[
+    "Ukraine", "Portugal", "Germany", "Japan", "United States", "Canada", "South Korea", "Kenya",
+];
+const STATUSES: &[&str] = &["active", "pending", "archived", "disabled"];
+const WORDS: &[&str] = &[
+    "lorem", "ipsum", "dolor", "sit", "amet", "consectetur", "adipiscing", "elit", "sed", "do",
+    "eiusmod", "tempor", "incididunt", "labore", "magna", "aliqua",
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneratedDataResult {
+    pub table_name: String,
+    pub inserted_rows: u32,
+    pub generated_columns: Vec<String>,
+    pub seed: u64,
+}
+
+/// Small deterministic PRNG (SplitMix64) so a seed reproduces the same data set.
+pub struct TestDataRng(u64);
+
+impl TestDataRng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform integer in `min..=max`.
+    pub fn range(&mut self, min: i64, max: i64) -> i64 {
+        let span = (max - min) as u64 + 1;
+        min + (self.next_u64() % span) as i64
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.next_u64() as usize % items.len()]
+    }
+
+    pub fn bytes16(&mut self) -> [u8; 16] {
+        let mut bytes = [0u8; 16];
+        bytes[..8].copy_from_slice(&self.next_u64().to_le_bytes());
+        bytes[8..].copy_from_slice(&self.next_u64().to_le_bytes());
+        bytes
+    }
+}
+
+#[derive(Debug, Clone)]
+struct GeneratorColumn {
+    name: String,
+    type_name: String,
+    // First value for a unique integer column, past the largest one already in the table
+    unique: Option<i64>,
+    parent_keys: Option<Vec<serde_json::Value>>,
+}
+
+fn is_integer_type(type_name: &str) -> bool {
+    type_name.to_uppercase().contains("INT")
+}
+
+fn is_real_type(type_name: &str) -> bool {
+    let upper = type_name.to_uppercase();
+    ["REAL", "FLOA", "DOUB", "DECIMAL", "NUMERIC"]
+        .iter()
+        .any(|t| upper.contains(t))
+}
+
+fn sentence(rng: &mut TestDataRng, words: usize) -> String {
+    let mut text = (0..words)
+        .map(|_| *rng.pick(WORDS))
+        .collect::<Vec<_>>()
+        .join(" ");
+    if let Some(first) = text.get(0..1) {
+        text.replace_range(0..1, &first.to_uppercase());
+    }
+    text
+}
+
+/// Timestamp within roughly the last two years.
+fn recent_timestamp(rng: &mut TestDataRng) -> chrono::DateTime<chrono::Utc> {
+    let seconds_ago = rng.range(0, 2 * 365 * 24 * 3600);
+    chrono::Utc::now() - chrono::Duration::seconds(seconds_ago)
+}
+
+/// Generate a value for one column, guessing intent from its name first and its type second.
+/// `unique` is set for unique columns and holds the value integer columns count up from.
+pub(crate) fn generate_value(
+    rng: &mut TestDataRng,
+    column_name: &str,
+    type_name: &str,
+    row_index: u32,
+    unique: Option<i64>,
+) -> serde_json::Value {
+    use serde_json::json;
+
+    let unique_start = unique;
+    let unique = unique.is_some();
+
+    let name = column_name.to_lowercase();
+    let integer = is_integer_type(type_name);
+    let suffix = if unique {
+        format!("{}", row_index)
+    } else {
+        String::new()
+    };
+
+    if name.contains("email") {
+        let first = rng.pick(FIRST_NAMES).to_lowercase();
+        let last = rng.pick(LAST_NAMES).to_lowercase();
+        return json!(format!(
+            "{}.{}{}{}@example.com",
+            first,
+            last,
+            rng.range(1, 99),
+            suffix
+        ));
+    }
+    if name.contains("first_name") || name == "firstname" {
+        return json!(format!("{}{}", rng.pick(FIRST_NAMES), suffix));
+    }
+    if name.contains("last_name") || name == "lastname" || name == "surname" {
+        return json!(format!("{}{}", rng.pick(LAST_NAMES), suffix));
+    }
+    if name.contains("username") || name.contains("user_name") || name == "login" {
+        let first = rng.pick(FIRST_NAMES).to_lowercase();
+        return json!(format!("{}{}{}", first, rng.range(1, 9999), suffix));
+    }
+    if name == "name" || name.ends_with("_name") || name == "full_name" || name == "fullname" {
+        let full = format!("{} {}", rng.pick(FIRST_NAMES), rng.pick(LAST_NAMES));
+        return json!(if unique { format!("{} {}", full, row_index) } else { full });
+    }
+    if name.contains("phone") || name.contains("mobile") {
+        return json!(format!("+1-555-{:04}", rng.range(0, 9999)));
+    }
+    if name.contains("url") || name.contains("website") || name.contains("link") {
+        return json!(format!("https://example.com/{}/{}", rng.pick(WORDS), row_index));
+    }
+    if name.contains("uuid") || name.contains("guid") {
+        // Built from the seeded generator so the seed reproduces it
+        return json!(uuid::Builder::from_random_bytes(rng.bytes16()).into_uuid().to_string());
+    }
+    if name.contains("city") {
+        return json!(format!("{}{}", rng.pick(CITIES), suffix));
+    }
+    if name.contains("country") {
+        return json!(format!("{}{}", rng.pick(COUNTRIES), suffix));
+    }
+    if name == "status" || name.ends_with("_status") || name == "state" {
+        return json!(rng.pick(STATUSES));
+    }
+    if name.ends_with("_at")
+        || name.contains("date")
+        || name.contains("time")
+        || name.contains("timestamp")
+    {
+        let timestamp = recent_timestamp(rng);
+        return if integer {
+            if name.contains("ms") || name.contains("millis") {
+                json!(timestamp.timestamp_millis())
+            } else {
+                json!(timestamp.timestamp())
+            }
+        } else if name.contains("date") && !name.contains("time") {
+            json!(timestamp.format("%Y-%m-%d").to_string())
+        } else {
+            json!(timestamp.to_rfc3339())
+        };
+    }
+    if name.starts_with("is_") || name.starts_with("has_") || type_name.to_uppercase().contains("BOOL") {
+        return json!(rng.range(0, 1));
+    }
+    if name == "age" || name.ends_with("_age") {
+        return json!(rng.range(18, 80));
+    }
+    if name.contains("price") || name.contains("amount") || name.contains("cost") || name.contains("total") {
+        let cents = rng.range(100, 50_000);
+        return if integer {
+            json!(cents)
+        } else {
+            json!(cents as f64 / 100.0)
+        };
+    }
+    if name.contains("count") || name.contains("quantity") || name == "qty" {
+        return json!(rng.range(0, 100));
+    }
+    if name.contains("description")
+        || name.contains("note")
+        || name.contains("comment")
+        || name.contains("bio")
+        || name.contains("body")
+    {
+        let word_count = rng.range(6, 14) as usize;
+        return json!(format!("{}.", sentence(rng, word_count)));
+    }
+    if name.contains("title") || name.contains("subject") {
+        let word_count = rng.range(2, 5) as usize;
+        let title = sentence(rng, word_count);
+        return json!(if unique { format!("{} {}", title, row_index) } else { title });
+    }
+
+    // Fall back to the declared type
+    if integer {
+        if let Some(start) = unique_start {
+            json!(start + row_index as i64)
+        } else {
+            json!(rng.range(0, 10_000))
+        }
+    } else if is_real_type(type_name) {
+        json!(rng.range(0, 1_000_000) as f64 / 100.0)
+    } else {
+        json!(format!("{} {}{}", rng.pick(WORDS), rng.pick(WORDS), suffix))
+    }
+}
+
+/// Largest integer already in a column, 0 for an empty table
+async fn read_max_integer(pool: &SqlitePool, table_name: &str, column: &str) -> Result<i64, String> {
+    let max: Option<i64> = sqlx::query_scalar(&format!(
+        "SELECT MAX(CAST({} AS INTEGER)) FROM {}",
+        quote_ident(column),
+        quote_ident(table_name)
+    ))
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("Error reading largest value of '{}': {}", column, e))?;
+    Ok(max.unwrap_or(0).max(0))
+}
+
+/// Columns covered by single-column UNIQUE indexes (including UNIQUE constraints).
+async fn read_unique_columns(
+    pool: &SqlitePool,
+    table_name: &str,
+) -> Result<Vec<String>, String> {
+    let indexes = sqlx::query(&format!("PRAGMA index_list({})", quote_ident(table_name)))
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Error reading indexes: {}", e))?;
+
+    let mut unique_columns = Vec::new();
+    for index in indexes {
+        if index.get::<i64, _>("unique") == 0 {
+            continue;
+        }
+        let index_name: String = index.get("name");
+        let columns = sqlx::query(&format!("PRAGMA index_info({})", quote_ident(&index_name)))
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("Error reading index columns: {}", e))?;
+        if columns.len() == 1 {
+            if let Ok(column) = columns[0].try_get::<String, _>("name") {
+                unique_columns.push(column);
+            }
+        }
+    }
+    Ok(unique_columns)
+}
+
+/// Existing parent key values for each foreign key column.
+async fn read_foreign_key_values(
+    pool: &SqlitePool,
+    table_name: &str,
+) -> Result<HashMap<String, Vec<serde_json::Value>>, String> {
+    let foreign_keys = sqlx::query(&format!("PRAGMA foreign_key_list({})", quote_ident(table_name)))
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Error reading foreign keys: {}", e))?;
+
+    let mut values = HashMap::new();
+    for foreign_key in foreign_keys {
+        let from: String = foreign_key.get("from");
+        let parent_table: String = foreign_key.get("table");
+        // A missing target column means the parent's primary key
+        let parent_column: String = foreign_key
+            .try_get::<Option<String>, _>("to")
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| "rowid".to_string());
+        let parent_column = if parent_column == "rowid" {
+            parent_column
+        } else {
+            quote_ident(&parent_column)
+        };
+
+        let rows = sqlx::query(&format!(
+            "SELECT DISTINCT {} AS parent_key FROM {} WHERE {} IS NOT NULL LIMIT {}",
+            parent_column,
+            quote_ident(&parent_table),
+            parent_column,
+            MAX_PARENT_KEYS
+        ))
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Error reading parent table '{}': {}", parent_table, e))?;
+
+        let keys = rows
+            .iter()
+            .filter_map(|row| {
+                row.try_get::<i64, _>("parent_key")
+                    .map(serde_json::Value::from)
+                    .or_else(|_| row.try_get::<String, _>("parent_key").map(serde_json::Value::from))
+                    .ok()
+            })
+            .collect();
+        values.insert(from, keys);
+    }
+    Ok(values)
+}
+
+pub(crate) async fn generate_test_data(
+    pool: &SqlitePool,
+    table_name: &str,
+    row_count: u32,
+    seed: u64,
+) -> Result<GeneratedDataResult, String> {
+    if row_count == 0 || row_count > MAX_GENERATED_ROWS {
+        return Err(format!(
+            "Row count must be between 1 and {}",
+            MAX_GENERATED_ROWS
+        ));
+    }
+
+    let table_info = sqlx::query(TABLE_INFO_QUERY)
+        .bind(table_name)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Error getting table info: {}", e))?;
+    if table_info.is_empty() {
+        return Err(format!("Table '{}' does not exist", table_name));
+    }
+
+    let unique_columns = read_unique_columns(pool, table_name).await?;
+    let mut foreign_keys = read_foreign_key_values(pool, table_name).await?;
+    let pk_count = table_info
+        .iter()
+        .filter(|row| row.get::<i64, _>("pk") > 0)
+        .count();
+
+    let mut columns = Vec::new();
+    let mut blob_columns = Vec::new();
+    for row in &table_info {
+        let name: String = row.get("name");
+        let type_name: String = row.get("type");
+        let notnull = row.get::<i64, _>("notnull") != 0;
+        let is_pk = row.get::<i64, _>("pk") > 0;
+        let has_default = row
+            .try_get::<Option<String>, _>("dflt_value")
+            .ok()
+            .flatten()
+            .is_some();
+
+        // INTEGER PRIMARY KEY aliases rowid and is assigned by SQLite
+        if is_pk && pk_count == 1 && type_name.eq_ignore_ascii_case("INTEGER") {
+            continue;
+        }
+
+        let parent_keys = foreign_keys.remove(&name);
+        if let Some(keys) = &parent_keys {
+            if keys.is_empty() {
+                if notnull && !has_default {
+                    return Err(format!(
+                        "Column '{}' references a table without rows; add parent rows first",
+                        name
+                    ));
+                }
+                // Leave it to NULL / the column default
+                continue;
+            }
+        }
+
+        if type_name.to_uppercase().contains("BLOB") && parent_keys.is_none() {
+            blob_columns.push(name);
+            continue;
+        }
+
+        let unique = if !(is_pk || unique_columns.contains(&name)) {
+            None
+        } else if is_integer_type(&type_name) {
+            Some(read_max_integer(pool, table_name, &name).await? + 1)
+        } else {
+            Some(1)
+        };
+        columns.push(GeneratorColumn {
+            unique,
+            name,
+            type_name,
+            parent_keys,
+        });
+    }
+
+    let generated_columns: Vec<String> = columns
+        .iter()
+        .map(|column| column.name.clone())
+        .chain(blob_columns.iter().cloned())
+        .collect();
+    let column_list: Vec<String> = generated_columns
+        .iter()
+        .map(|column| quote_ident(column))
+        .collect();
+    let value_list = columns
+        .iter()
+        .map(|_| "?".to_string())
+        .chain(blob_columns.iter().map(|_| "randomblob(16)".to_string()))
+        .collect::<Vec<_>>();
+
+    let insert = if column_list.is_empty() {
+        format!("INSERT INTO {} DEFAULT VALUES", quote_ident(table_name))
+    } else {
+        format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            quote_ident(table_name),
+            column_list.join(", "),
+            value_list.join(", ")
+        )
+    };
+
+    let mut rng = TestDataRng::new(seed);
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+    for row_index in 0..row_count {
+        let values: Vec<serde_json::Value> = columns
+            .iter()
+            .map(|column| match &column.parent_keys {
+                Some(keys) => rng.pick(keys).clone(),
+                None => generate_value(
+                    &mut rng,
+                    &column.name,
+                    &column.type_name,
+                    row_index,
+                    column.unique,
+                ),
+            })
+            .collect();
+
+        // Dropping the transaction on error rolls everything back
+        bind_json_values(sqlx::query(&insert), &values)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to insert generated row {}: {}", row_index + 1, e))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit generated rows: {}", e))?;
+
+    Ok(GeneratedDataResult {
+        table_name: table_name.to_string(),
+        inserted_rows: row_count,
+        generated_columns,
+        seed,
+    })
+}
+
+#[tauri::command]
+pub async fn db_generate_test_data(
+    state: State<'_, DbPool>,
+    db_cache: State<'_, DbConnectionCache>,
+    table_name: String,
+    row_count: u32,
+    seed: Option<u64>,
+    current_db_path: Option<String>,
+) -> Result<DbResponse<GeneratedDataResult>, String> {
+    log::info!("🧪 Generating {} test rows for table '{}'", row_count, table_name);
+
+    let result = async {
+        let db_path = current_db_path
+            .clone()
+            .ok_or_else(|| "Generating test data requires a specific database path".to_string())?;
+        ensure_database_file_permissions(&db_path)?;
+
+        let pool = get_current_pool(&state, &db_cache, current_db_path).await?;
+        let seed = seed.unwrap_or_else(|| chrono::Utc::now().timestamp_nanos_opt().unwrap_or(0) as u64);
+        generate_test_data(&pool, &table_name, row_count, seed).await
+    }
+    .await;
+
+    match result {
+        Ok(result) => {
+            log::info!(
+                "✅ Inserted {} generated rows into '{}' (seed {})",
+                result.inserted_rows,
+                result.table_name,
+                result.seed
+            );
+            Ok(DbResponse {
+                success: true,
+                data: Some(result),
+                error: None,
+            })
+        }
+        Err(e) => {
+            log::error!("❌ Test data generation failed: {}", e);
+            Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    async fn test_pool(temp_dir: &TempDir) -> SqlitePool {
+        let db_path = temp_dir.path().join("test_data.db");
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE users (
+                id INTEGER PRIMARY KEY,
+                email TEXT NOT NULL UNIQUE,
+                first_name TEXT,
+                created_at INTEGER,
+                is_active BOOLEAN,
+                avatar BLOB
+             );
+             CREATE TABLE orders (
+                id INTEGER PRIMARY KEY,
+                user_id INTEGER NOT NULL REFERENCES users(id),
+                total REAL,
+                status TEXT
+             );",
+        )
+        .unwrap();
+        drop(conn);
+
+        SqlitePool::connect(&format!("sqlite:{}", db_path.display()))
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn test_rng_is_deterministic() {
+        let mut a = TestDataRng::new(42);
+        let mut b = TestDataRng::new(42);
+        for _ in 0..10 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+        for _ in 0..100 {
+            let value = a.range(5, 7);
+            assert!((5..=7).contains(&value));
+        }
+    }
+
+    #[test]
+    fn test_generate_value_uses_name_heuristics() {
+        let mut rng = TestDataRng::new(1);
+
+        let email = generate_value(&mut rng, "email", "TEXT", 3, Some(1));
+        assert!(email.as_str().unwrap().ends_with("3@example.com"));
+
+        let created = generate_value(&mut rng, "created_at", "INTEGER", 0, None);
+        assert!(created.as_i64().unwrap() > 1_600_000_000);
+
+        let flag = generate_value(&mut rng, "is_enabled", "INTEGER", 0, None);
+        assert!(matches!(flag.as_i64(), Some(0) | Some(1)));
+
+        let price = generate_value(&mut rng, "price", "REAL", 0, None);
+        assert!(price.is_f64());
+
+        assert_eq!(generate_value(&mut rng, "code", "INTEGER", 2, Some(41)), serde_json::json!(43));
+
+        let uuid = |seed| generate_value(&mut TestDataRng::new(seed), "uuid", "TEXT", 0, None);
+        assert_eq!(uuid(7), uuid(7));
+        assert_ne!(uuid(7), uuid(8));
+    }
+
+    #[tokio::test]
+    async fn test_generate_test_data_fills_tables_and_foreign_keys() {
+        let temp_dir = TempDir::new().unwrap();
+        let pool = test_pool(&temp_dir).await;
+
+        let users = generate_test_data(&pool, "users", 25, 7).await.unwrap();
+        assert_eq!(users.inserted_rows, 25);
+        assert!(users.generated_columns.contains(&"avatar".to_string()));
+        assert!(!users.generated_columns.contains(&"id".to_string()));
+
+        generate_test_data(&pool, "orders", 40, 7).await.unwrap();
+
+        let orphans: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM orders WHERE user_id NOT IN (SELECT id FROM users)",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(orphans, 0);
+
+        let distinct_emails: i64 = sqlx::query_scalar("SELECT COUNT(DISTINCT email) FROM users")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(distinct_emails, 25);
+    }
+
+    #[tokio::test]
+    async fn test_generate_test_data_requires_parent_rows() {
+        let temp_dir = TempDir::new().unwrap();
+        let pool = test_pool(&temp_dir).await;
+
+        let result = generate_test_data(&pool, "orders", 5, 1).await;
+        assert!(result.unwrap_err().contains("references a table without rows"));
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM orders")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+}
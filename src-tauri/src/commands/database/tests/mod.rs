@@ -136,6 +136,137 @@ mod tests {
         assert_eq!(count, 4);
     }
 
+    #[test]
+    fn test_data_version_token_changes_after_write() {
+        use crate::commands::database::connection_manager::data_version_token;
+
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("versioned.db");
+        let db_path = db_path.to_str().unwrap();
+        create_test_database(db_path).unwrap();
+
+        let before = data_version_token(db_path).unwrap();
+        assert_eq!(data_version_token(db_path).unwrap(), before);
+
+        let conn = Connection::open(db_path).unwrap();
+        conn.execute("INSERT INTO users (name, email, age, active) VALUES ('Late', 'late@example.com', 40, 1)", [])
+            .unwrap();
+        drop(conn);
+
+        assert_ne!(data_version_token(db_path).unwrap(), before);
+        assert!(data_version_token(temp_dir.path().join("missing.db").to_str().unwrap()).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_streaming_reads_match_buffered_reads() {
+        use crate::commands::database::table_reads::{fetch_decoded_rows, LargeReadMode};
+
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("large.db");
+        create_test_database(db_path.to_str().unwrap()).unwrap();
+        let pool = sqlx::SqlitePool::connect(&format!("sqlite:{}", db_path.display())).await.unwrap();
+
+        let query = "SELECT * FROM users ORDER BY id";
+        let buffered = fetch_decoded_rows(&pool, query, LargeReadMode { streaming: false, mmap_size: 0 })
+            .await
+            .unwrap();
+        let streamed = fetch_decoded_rows(&pool, query, LargeReadMode { streaming: true, mmap_size: 1 << 20 })
+            .await
+            .unwrap();
+        pool.close().await;
+
+        assert_eq!(buffered.len(), 3);
+        assert_eq!(buffered, streamed);
+    }
+
+    #[tokio::test]
+    async fn test_read_replica_sees_writes_and_rejects_them() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("replica.db");
+        let db_path = db_path.to_str().unwrap();
+        create_test_database(db_path).unwrap();
+
+        let manager = DatabaseConnectionManager::with_config(ConnectionConfig {
+            read_replicas: true,
+            ..ConnectionConfig::with_cache_disabled()
+        });
+        let writer = manager.get_connection(db_path).await.unwrap();
+        let reader = manager.get_read_connection(db_path).await.unwrap();
+
+        sqlx::query("UPDATE users SET name = 'Replicated' WHERE id = 1")
+            .execute(&writer)
+            .await
+            .unwrap();
+        let name: String = sqlx::query("SELECT name FROM users WHERE id = 1")
+            .fetch_one(&reader)
+            .await
+            .unwrap()
+            .get("name");
+        assert_eq!(name, "Replicated");
+
+        assert!(sqlx::query("DELETE FROM users").execute(&reader).await.is_err());
+
+        DatabaseConnectionManager::drop_read_replicas(Some(db_path));
+        reader.close().await;
+        writer.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_table_info_query_reused_across_tables() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("metadata.db");
+        let db_path = db_path.to_str().unwrap();
+        create_test_database(db_path).unwrap();
+        Connection::open(db_path)
+            .unwrap()
+            .execute("CREATE TABLE \"odd \"\"name\" (key TEXT PRIMARY KEY)", [])
+            .unwrap();
+
+        let pool = sqlx::SqlitePool::connect_with(
+            crate::commands::database::connection_access::sqlite_connect_options(db_path, "ro").unwrap(),
+        )
+        .await
+        .unwrap();
+        for (table, expected) in [("users", 5), ("posts", 5), ("odd \"name", 1), ("users", 5)] {
+            let rows = sqlx::query(TABLE_INFO_QUERY).bind(table).fetch_all(&pool).await.unwrap();
+            assert_eq!(rows.len(), expected, "columns of {}", table);
+        }
+        let pk: i64 = sqlx::query(TABLE_INFO_QUERY)
+            .bind("odd \"name")
+            .fetch_one(&pool)
+            .await
+            .unwrap()
+            .get("pk");
+        assert_eq!(pk, 1);
+        pool.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_warm_schema_caches_every_table() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("warm.db");
+        let db_path = db_path.to_str().unwrap();
+        create_test_database(db_path).unwrap();
+        let pool = sqlx::SqlitePool::connect(&format!("sqlite:{}", db_path)).await.unwrap();
+
+        assert_eq!(DatabaseConnectionManager::warm_schema(&pool, db_path).await.unwrap(), 2);
+
+        let users = DatabaseConnectionManager::table_columns(&pool, db_path, "users").await.unwrap().unwrap();
+        let names: Vec<&str> = users.iter().map(|column| column.name.as_str()).collect();
+        assert_eq!(names, vec!["id", "name", "email", "age", "active"]);
+        assert!(users[0].pk);
+        assert!(users[1].notnull);
+        let posts = DatabaseConnectionManager::table_columns(&pool, db_path, "posts").await.unwrap();
+        assert_eq!(posts.unwrap().len(), 5);
+        assert!(DatabaseConnectionManager::table_columns(&pool, db_path, "missing").await.unwrap().is_none());
+
+        DatabaseConnectionManager::clear_schema_cache(Some(db_path));
+        assert!(crate::commands::database::schema_cache::SCHEMA_CACHE
+            .get(&std::fs::canonicalize(db_path).unwrap().to_string_lossy())
+            .is_none());
+        pool.close().await;
+    }
+
     #[tokio::test]
     async fn test_connection_manager_missing_path_returns_clean_error() {
         let manager = DatabaseConnectionManager::new();
@@ -0,0 +1,454 @@
+// Timestamp detection
+// Recognizes epoch, Apple reference date and ISO-8601 values so app dates can be shown readably
+
+use crate::commands::database::types::{ColumnInfo, DbResponse};
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, SecondsFormat, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// Seconds between the Unix epoch and 2001-01-01, the reference date of CFAbsoluteTime/NSDate
+pub const APPLE_REFERENCE_EPOCH: i64 = 978_307_200;
+
+// Epoch values are only treated as dates between 2000-01-01 and 2100-01-01. Starting at 2000
+// keeps current Apple reference times (roughly 8e8) from reading as 1990s Unix seconds.
+const MIN_PLAUSIBLE_SECONDS: f64 = 946_684_800.0;
+const MAX_PLAUSIBLE_SECONDS: f64 = 4_102_444_800.0;
+
+// Values sampled per column when deciding whether it holds timestamps
+const DETECTION_SAMPLE_SIZE: usize = 50;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TimestampFormat {
+    UnixSeconds,
+    UnixMilliseconds,
+    // Seconds since 2001-01-01 UTC, as stored by Core Data and NSDate
+    AppleAbsolute,
+    Iso8601,
+}
+
+impl TimestampFormat {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.to_lowercase().replace(['-', '_'], "").as_str() {
+            "unixseconds" | "seconds" | "epoch" => Ok(Self::UnixSeconds),
+            "unixmilliseconds" | "milliseconds" | "ms" => Ok(Self::UnixMilliseconds),
+            "appleabsolute" | "apple" | "cfabsolutetime" | "coredata" => Ok(Self::AppleAbsolute),
+            "iso8601" | "iso" => Ok(Self::Iso8601),
+            _ => Err(format!(
+                "Unsupported timestamp format '{}'; expected unixSeconds, unixMilliseconds, appleAbsolute or iso8601",
+                value
+            )),
+        }
+    }
+}
+
+/// Timezone used to read ISO strings without an offset and to render converted values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimestampZone {
+    Utc,
+    Local,
+    Fixed(FixedOffset),
+    Named(Tz),
+}
+
+impl TimestampZone {
+    /// Accepts "UTC", "local", an offset such as "+02:00" or an IANA name such as "Europe/Kyiv".
+    pub fn parse(value: Option<&str>) -> Result<Self, String> {
+        let value = value.map(str::trim).unwrap_or_default();
+        match value.to_lowercase().as_str() {
+            "" | "utc" | "z" => return Ok(Self::Utc),
+            "local" => return Ok(Self::Local),
+            _ => {}
+        }
+        if value.starts_with(['+', '-']) {
+            if let Ok(offset) = value.parse::<FixedOffset>() {
+                return Ok(Self::Fixed(offset));
+            }
+        }
+        value
+            .parse::<Tz>()
+            .map(Self::Named)
+            .map_err(|_| format!("Unknown timezone '{}'", value))
+    }
+
+    fn localize(&self, naive: &NaiveDateTime) -> Option<DateTime<Utc>> {
+        // Ambiguous wall times during a DST fall-back resolve to the earlier instant
+        match self {
+            Self::Utc => Some(naive.and_utc()),
+            Self::Local => Local.from_local_datetime(naive).earliest().map(|t| t.with_timezone(&Utc)),
+            Self::Fixed(offset) => offset.from_local_datetime(naive).earliest().map(|t| t.with_timezone(&Utc)),
+            Self::Named(tz) => tz.from_local_datetime(naive).earliest().map(|t| t.with_timezone(&Utc)),
+        }
+    }
+
+    fn render(&self, timestamp: &DateTime<Utc>) -> String {
+        match self {
+            Self::Utc => format_iso(timestamp),
+            Self::Local => timestamp.with_timezone(&Local).to_rfc3339_opts(SecondsFormat::AutoSi, false),
+            Self::Fixed(offset) => timestamp.with_timezone(offset).to_rfc3339_opts(SecondsFormat::AutoSi, false),
+            Self::Named(tz) => timestamp.with_timezone(tz).to_rfc3339_opts(SecondsFormat::AutoSi, false),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TimestampConversion {
+    // The value in the requested format, ready to be written to the cell
+    pub value: serde_json::Value,
+    pub format: TimestampFormat,
+    pub iso_utc: String,
+    // The same instant rendered in the chosen timezone, with its offset
+    pub iso_local: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TimestampColumn {
+    pub column: String,
+    pub format: TimestampFormat,
+    // ISO-8601 rendering of the column's value for each row, in row order; None where it doesn't parse
+    pub formatted: Vec<Option<String>>,
+}
+
+/// Column names such as `created_at`, `updatedAt`, `ZCREATIONDATE` or `last_login_time`.
+fn name_suggests_timestamp(name: &str) -> bool {
+    let lower = name.to_lowercase();
+    lower.ends_with("_at")
+        || name.ends_with("At")
+        || ["date", "time", "created", "updated", "modified", "expires", "expiry"]
+            .iter()
+            .any(|hint| lower.contains(hint))
+}
+
+/// Core Data names its columns in upper case with a Z prefix and stores dates as Apple absolute time.
+fn is_core_data_column(name: &str) -> bool {
+    name.len() > 1 && name.starts_with('Z') && !name.chars().any(|c| c.is_ascii_lowercase())
+}
+
+fn plausible_seconds(seconds: f64) -> bool {
+    (MIN_PLAUSIBLE_SECONDS..MAX_PLAUSIBLE_SECONDS).contains(&seconds)
+}
+
+fn from_unix_seconds(seconds: f64) -> Option<DateTime<Utc>> {
+    if !seconds.is_finite() {
+        return None;
+    }
+    let whole = seconds.floor();
+    let nanos = ((seconds - whole) * 1_000_000_000.0).round().min(999_999_999.0) as u32;
+    Utc.timestamp_opt(whole as i64, nanos).single()
+}
+
+/// Parse ISO-8601 / RFC 3339 text, plus the `YYYY-MM-DD HH:MM:SS` form SQLite's date functions produce.
+/// Values without an offset are read in `zone`.
+pub fn parse_iso_in_zone(text: &str, zone: TimestampZone) -> Option<DateTime<Utc>> {
+    let text = text.trim();
+    if text.len() < 10 || !text.as_bytes()[0].is_ascii_digit() {
+        return None;
+    }
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(text) {
+        return Some(parsed.with_timezone(&Utc));
+    }
+    for format in ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M"] {
+        if let Ok(parsed) = NaiveDateTime::parse_from_str(text, format) {
+            return zone.localize(&parsed);
+        }
+    }
+    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .and_then(|date_time| zone.localize(&date_time))
+}
+
+/// ISO text without an offset is taken as UTC, which is what CURRENT_TIMESTAMP stores.
+pub fn parse_iso_timestamp(text: &str) -> Option<DateTime<Utc>> {
+    parse_iso_in_zone(text, TimestampZone::Utc)
+}
+
+/// Interpret a cell as a timestamp in the given format.
+pub fn parse_timestamp(value: &serde_json::Value, format: TimestampFormat) -> Option<DateTime<Utc>> {
+    match format {
+        TimestampFormat::Iso8601 => value.as_str().and_then(parse_iso_timestamp),
+        TimestampFormat::UnixSeconds => value.as_f64().and_then(from_unix_seconds),
+        TimestampFormat::UnixMilliseconds => value.as_f64().and_then(|ms| from_unix_seconds(ms / 1000.0)),
+        TimestampFormat::AppleAbsolute => value
+            .as_f64()
+            .and_then(|seconds| from_unix_seconds(seconds + APPLE_REFERENCE_EPOCH as f64)),
+    }
+}
+
+// Whole numbers stay integers so they can be written back to INTEGER columns unchanged
+fn number_value(number: f64) -> serde_json::Value {
+    if number.fract() == 0.0 && number.abs() < 9_007_199_254_740_992.0 {
+        serde_json::Value::from(number as i64)
+    } else {
+        serde_json::Value::from(number)
+    }
+}
+
+/// Convert a timestamp between formats. Numeric formats also accept numbers typed as text.
+pub fn convert_timestamp(
+    value: &serde_json::Value,
+    from: TimestampFormat,
+    to: TimestampFormat,
+    zone: TimestampZone,
+) -> Result<TimestampConversion, String> {
+    let value = match (value, from) {
+        (serde_json::Value::String(text), TimestampFormat::Iso8601) => serde_json::Value::String(text.clone()),
+        (serde_json::Value::String(text), _) => text
+            .trim()
+            .parse::<f64>()
+            .map(serde_json::Value::from)
+            .map_err(|_| format!("'{}' is not a number", text))?,
+        (value, _) => value.clone(),
+    };
+    let timestamp = match from {
+        TimestampFormat::Iso8601 => value.as_str().and_then(|text| parse_iso_in_zone(text, zone)),
+        _ => parse_timestamp(&value, from),
+    }
+    .ok_or_else(|| format!("{} is not a valid timestamp", value))?;
+
+    let seconds = timestamp.timestamp() as f64 + timestamp.timestamp_subsec_nanos() as f64 / 1_000_000_000.0;
+    let converted = match to {
+        TimestampFormat::UnixSeconds => number_value(seconds),
+        TimestampFormat::UnixMilliseconds => serde_json::Value::from(timestamp.timestamp_millis()),
+        TimestampFormat::AppleAbsolute => number_value(seconds - APPLE_REFERENCE_EPOCH as f64),
+        TimestampFormat::Iso8601 => serde_json::Value::String(zone.render(&timestamp)),
+    };
+
+    Ok(TimestampConversion {
+        value: converted,
+        format: to,
+        iso_utc: format_iso(&timestamp),
+        iso_local: zone.render(&timestamp),
+    })
+}
+
+pub fn format_iso(timestamp: &DateTime<Utc>) -> String {
+    timestamp.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+/// Guess the format of a single value. Numbers are only considered for columns whose name or
+/// declared type hints at a date, since plain counters and IDs often fall in the epoch range.
+fn guess_format(column: &ColumnInfo, value: &serde_json::Value) -> Option<TimestampFormat> {
+    if let Some(text) = value.as_str() {
+        return parse_iso_timestamp(text).map(|_| TimestampFormat::Iso8601);
+    }
+
+    let number = value.as_f64()?;
+    let declared = column.type_name.to_uppercase();
+    let date_like = name_suggests_timestamp(&column.name) || declared.contains("DATE") || declared.contains("TIME");
+    if !date_like {
+        return None;
+    }
+
+    if is_core_data_column(&column.name) && plausible_seconds(number + APPLE_REFERENCE_EPOCH as f64) {
+        Some(TimestampFormat::AppleAbsolute)
+    } else if plausible_seconds(number / 1000.0) {
+        Some(TimestampFormat::UnixMilliseconds)
+    } else if plausible_seconds(number) {
+        Some(TimestampFormat::UnixSeconds)
+    } else if number > 0.0 && plausible_seconds(number + APPLE_REFERENCE_EPOCH as f64) {
+        Some(TimestampFormat::AppleAbsolute)
+    } else {
+        None
+    }
+}
+
+/// Find columns whose sampled values consistently look like timestamps and render every row's
+/// value as ISO-8601. Raw values in `rows` are left untouched.
+pub fn detect_timestamp_columns(
+    columns: &[ColumnInfo],
+    rows: &[HashMap<String, serde_json::Value>],
+) -> Vec<TimestampColumn> {
+    let mut detected = Vec::new();
+
+    for column in columns {
+        let samples: Vec<&serde_json::Value> = rows
+            .iter()
+            .filter_map(|row| row.get(&column.name))
+            .filter(|value| !value.is_null())
+            .take(DETECTION_SAMPLE_SIZE)
+            .collect();
+        if samples.is_empty() {
+            continue;
+        }
+
+        let mut counts: Vec<(TimestampFormat, usize)> = Vec::new();
+        for format in samples.iter().filter_map(|value| guess_format(column, value)) {
+            match counts.iter_mut().find(|(known, _)| *known == format) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((format, 1)),
+            }
+        }
+        let Some((format, count)) = counts.into_iter().max_by_key(|(_, count)| *count) else {
+            continue;
+        };
+        // Allow a few stray values, e.g. placeholders like 0 or ''
+        if count * 10 < samples.len() * 9 {
+            continue;
+        }
+
+        detected.push(TimestampColumn {
+            column: column.name.clone(),
+            format,
+            formatted: rows
+                .iter()
+                .map(|row| {
+                    row.get(&column.name)
+                        .and_then(|value| parse_timestamp(value, format))
+                        .map(|timestamp| format_iso(&timestamp))
+                })
+                .collect(),
+        });
+    }
+    detected
+}
+
+/// Convert a timestamp between epoch seconds/milliseconds, Apple absolute time and ISO-8601,
+/// reading and rendering wall-clock times in the chosen timezone.
+#[tauri::command]
+pub async fn db_convert_timestamp(
+    value: serde_json::Value,
+    from_format: String,
+    to_format: String,
+    timezone: Option<String>,
+) -> Result<DbResponse<TimestampConversion>, String> {
+    let result = TimestampFormat::parse(&from_format).and_then(|from| {
+        let to = TimestampFormat::parse(&to_format)?;
+        let zone = TimestampZone::parse(timezone.as_deref())?;
+        convert_timestamp(&value, from, to, zone)
+    });
+
+    match result {
+        Ok(conversion) => Ok(DbResponse {
+            success: true,
+            data: Some(conversion),
+            error: None,
+        }),
+        Err(e) => {
+            log::warn!("⚠️ Timestamp conversion failed: {}", e);
+            Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn column(name: &str, type_name: &str) -> ColumnInfo {
+        ColumnInfo {
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+            notnull: false,
+            pk: false,
+            default_value: serde_json::Value::Null,
+        }
+    }
+
+    #[test]
+    fn test_parse_timestamp_formats() {
+        let expected = "2023-11-14T22:13:20Z";
+        let parsed = |value, format| parse_timestamp(&value, format).map(|t| format_iso(&t));
+        assert_eq!(parsed(json!(1_700_000_000), TimestampFormat::UnixSeconds).as_deref(), Some(expected));
+        assert_eq!(parsed(json!(1_700_000_000_000i64), TimestampFormat::UnixMilliseconds).as_deref(), Some(expected));
+        assert_eq!(parsed(json!(721_692_800.0), TimestampFormat::AppleAbsolute).as_deref(), Some(expected));
+        assert_eq!(parsed(json!("2023-11-14 22:13:20"), TimestampFormat::Iso8601).as_deref(), Some(expected));
+        assert_eq!(parsed(json!("2023-11-15T00:13:20+02:00"), TimestampFormat::Iso8601).as_deref(), Some(expected));
+        assert_eq!(
+            parsed(json!(1_700_000_000.25), TimestampFormat::UnixSeconds).as_deref(),
+            Some("2023-11-14T22:13:20.250Z")
+        );
+        assert!(parse_iso_timestamp("not a date").is_none());
+    }
+
+    #[test]
+    fn test_convert_timestamp_between_formats() {
+        let zone = TimestampZone::parse(Some("Europe/Kyiv")).unwrap();
+        let conversion = convert_timestamp(
+            &json!("2023-11-15 00:13:20"),
+            TimestampFormat::Iso8601,
+            TimestampFormat::UnixSeconds,
+            zone,
+        )
+        .unwrap();
+        assert_eq!(conversion.value, json!(1_700_000_000));
+        assert_eq!(conversion.iso_utc, "2023-11-14T22:13:20Z");
+        assert_eq!(conversion.iso_local, "2023-11-15T00:13:20+02:00");
+
+        let conversion = convert_timestamp(
+            &json!("1700000000000"),
+            TimestampFormat::UnixMilliseconds,
+            TimestampFormat::AppleAbsolute,
+            TimestampZone::Utc,
+        )
+        .unwrap();
+        assert_eq!(conversion.value, json!(721_692_800));
+
+        let zone = TimestampZone::parse(Some("-05:00")).unwrap();
+        let conversion = convert_timestamp(
+            &json!(1_700_000_000),
+            TimestampFormat::UnixSeconds,
+            TimestampFormat::Iso8601,
+            zone,
+        )
+        .unwrap();
+        assert_eq!(conversion.value, json!("2023-11-14T17:13:20-05:00"));
+
+        assert!(TimestampZone::parse(Some("Mars/Olympus")).is_err());
+        assert!(TimestampFormat::parse("fortnights").is_err());
+        assert!(convert_timestamp(&json!("soon"), TimestampFormat::UnixSeconds, TimestampFormat::Iso8601, zone).is_err());
+    }
+
+    #[test]
+    fn test_detect_timestamp_columns() {
+        let columns = vec![
+            column("id", "INTEGER"),
+            column("created_at", "INTEGER"),
+            column("updatedAt", "INTEGER"),
+            column("ZCREATIONDATE", "TIMESTAMP"),
+            column("note", "TEXT"),
+            column("visits", "INTEGER"),
+        ];
+        let rows = vec![
+            HashMap::from([
+                ("id".to_string(), json!(1_700_000_000)),
+                ("created_at".to_string(), json!(1_700_000_000)),
+                ("updatedAt".to_string(), json!(1_700_000_000_000i64)),
+                ("ZCREATIONDATE".to_string(), json!(721_692_800.0)),
+                ("note".to_string(), json!("2023-11-14")),
+                ("visits".to_string(), json!(3)),
+            ]),
+            HashMap::from([
+                ("id".to_string(), json!(2)),
+                ("created_at".to_string(), serde_json::Value::Null),
+                ("updatedAt".to_string(), json!(1_700_000_001_000i64)),
+                ("ZCREATIONDATE".to_string(), json!(721_692_801.5)),
+                ("note".to_string(), json!("2023-11-15")),
+                ("visits".to_string(), json!(4)),
+            ]),
+        ];
+
+        let detected = detect_timestamp_columns(&columns, &rows);
+        let formats: Vec<(&str, TimestampFormat)> =
+            detected.iter().map(|c| (c.column.as_str(), c.format)).collect();
+        assert_eq!(
+            formats,
+            vec![
+                ("created_at", TimestampFormat::UnixSeconds),
+                ("updatedAt", TimestampFormat::UnixMilliseconds),
+                ("ZCREATIONDATE", TimestampFormat::AppleAbsolute),
+                ("note", TimestampFormat::Iso8601),
+            ]
+        );
+        assert_eq!(detected[0].formatted, vec![Some("2023-11-14T22:13:20Z".to_string()), None]);
+        assert_eq!(detected[2].formatted[1].as_deref(), Some("2023-11-14T22:13:21.500Z"));
+    }
+}
@@ -0,0 +1,568 @@
+// Value transformers
+// Per-column decoders (hex, UUID bytes, protobuf, flag bitsets) whose output is returned
+// next to the raw cell values; assignments are stored per database in the app data directory
+
+use crate::commands::database::connection_manager::DatabaseConnectionManager;
+use crate::commands::database::helpers::integer_to_json;
+use crate::commands::database::types::*;
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+const VALUE_TRANSFORMERS_NAME: &str = "value_transformers.json";
+
+// Protobuf messages nested deeper than this are not decoded
+const MAX_PROTOBUF_DEPTH: usize = 16;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ProtoFieldType {
+    String,
+    Bytes,
+    Int,
+    // Zigzag-encoded sint32/sint64
+    Sint,
+    Bool,
+    Fixed,
+    Double,
+    Float,
+    Message,
+}
+
+/// One field of a user-provided protobuf message descriptor.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtoFieldDescriptor {
+    pub number: u64,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub field_type: ProtoFieldType,
+    // Fields of the nested message when `type` is message
+    #[serde(default)]
+    pub fields: Vec<ProtoFieldDescriptor>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FlagDescriptor {
+    pub bit: u32,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ValueTransformer {
+    Hex,
+    Uuid,
+    // Without field descriptors, fields are keyed by number and their types are guessed
+    Protobuf {
+        #[serde(default)]
+        fields: Vec<ProtoFieldDescriptor>,
+    },
+    Flags {
+        flags: Vec<FlagDescriptor>,
+    },
+}
+
+impl ValueTransformer {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Hex => "hex",
+            Self::Uuid => "uuid",
+            Self::Protobuf { .. } => "protobuf",
+            Self::Flags { .. } => "flags",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TransformedColumn {
+    pub column: String,
+    pub transformer: String,
+    // Transformed value for each row, in row order; None where the cell couldn't be decoded
+    pub values: Vec<Option<serde_json::Value>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnTransformer {
+    pub table_name: String,
+    pub column_name: String,
+    pub transformer: ValueTransformer,
+}
+
+// Database path -> table -> column -> transformer
+type TransformerStore = BTreeMap<String, BTreeMap<String, BTreeMap<String, ValueTransformer>>>;
+
+pub(crate) fn value_transformers_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    std::fs::create_dir_all(&data_dir)
+        .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    Ok(data_dir.join(VALUE_TRANSFORMERS_NAME))
+}
+
+pub fn load_value_transformers(store_path: &Path) -> TransformerStore {
+    std::fs::read_to_string(store_path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_value_transformers(store_path: &Path, store: &TransformerStore) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(store)
+        .map_err(|e| format!("Failed to serialize value transformers: {}", e))?;
+    std::fs::write(store_path, content).map_err(|e| format!("Failed to save value transformers: {}", e))
+}
+
+/// Transformers assigned to the columns of one table.
+pub(crate) fn table_transformers(
+    app_handle: &tauri::AppHandle,
+    db_path: &str,
+    table_name: &str,
+) -> BTreeMap<String, ValueTransformer> {
+    value_transformers_path(app_handle)
+        .map(|store_path| load_value_transformers(&store_path))
+        .ok()
+        .and_then(|mut store| store.remove(db_path))
+        .and_then(|mut tables| tables.remove(table_name))
+        .unwrap_or_default()
+}
+
+/// Raw bytes of a cell. BLOBs arrive base64-encoded from the row decoder, so string values in
+/// columns with BLOB (or no) declared type are base64-decoded; other text is used as UTF-8.
+pub(crate) fn cell_bytes(value: &serde_json::Value, declared_type: &str) -> Option<Vec<u8>> {
+    let text = value.as_str()?;
+    let declared = declared_type.to_uppercase();
+    if declared.is_empty() || declared.contains("BLOB") {
+        if let Ok(bytes) = general_purpose::STANDARD.decode(text) {
+            return Some(bytes);
+        }
+    }
+    Some(text.as_bytes().to_vec())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn transform_hex(value: &serde_json::Value, declared_type: &str) -> Option<serde_json::Value> {
+    if let Some(number) = value.as_i64() {
+        return Some(serde_json::Value::String(format!("0x{:x}", number)));
+    }
+    cell_bytes(value, declared_type).map(|bytes| serde_json::Value::String(to_hex(&bytes)))
+}
+
+/// 16-byte UUIDs (as stored by many ORMs) or 32-digit hex text, rendered in hyphenated form.
+fn transform_uuid(value: &serde_json::Value, declared_type: &str) -> Option<serde_json::Value> {
+    let bytes = cell_bytes(value, declared_type)?;
+    let uuid = match bytes.len() {
+        16 => uuid::Uuid::from_slice(&bytes).ok(),
+        _ => std::str::from_utf8(&bytes).ok().and_then(|text| uuid::Uuid::parse_str(text.trim()).ok()),
+    }?;
+    Some(serde_json::Value::String(uuid.hyphenated().to_string()))
+}
+
+/// Names of the set bits of an integer; bits without a name are reported as `bit N`.
+fn transform_flags(value: &serde_json::Value, flags: &[FlagDescriptor]) -> Option<serde_json::Value> {
+    let number = match value {
+        serde_json::Value::String(text) => text.trim().parse::<i64>().ok()?,
+        other => other.as_i64()?,
+    } as u64;
+
+    let names = (0..64u32)
+        .filter(|bit| number & (1u64 << bit) != 0)
+        .map(|bit| {
+            let name = flags
+                .iter()
+                .find(|flag| flag.bit == bit)
+                .map(|flag| flag.name.clone())
+                .unwrap_or_else(|| format!("bit {}", bit));
+            serde_json::Value::String(name)
+        })
+        .collect();
+    Some(serde_json::Value::Array(names))
+}
+
+fn read_varint(bytes: &[u8], position: &mut usize) -> Result<u64, String> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes.get(*position).ok_or("Truncated varint")?;
+        *position += 1;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err("Varint is too long".to_string())
+}
+
+fn read_slice<'a>(bytes: &'a [u8], position: &mut usize, length: usize) -> Result<&'a [u8], String> {
+    let end = position.checked_add(length).filter(|end| *end <= bytes.len()).ok_or("Truncated field")?;
+    let slice = &bytes[*position..end];
+    *position = end;
+    Ok(slice)
+}
+
+/// Length-delimited payload without a descriptor: printable UTF-8 as text, then a nested
+/// message, then hex.
+fn guess_length_delimited(payload: &[u8], depth: usize) -> serde_json::Value {
+    if let Ok(text) = std::str::from_utf8(payload) {
+        if !text.chars().any(|c| c.is_control() && !c.is_whitespace()) {
+            return serde_json::Value::String(text.to_string());
+        }
+    }
+    match decode_protobuf(payload, &[], depth + 1) {
+        Ok(message) if !message.is_empty() => serde_json::Value::Object(message),
+        _ => serde_json::Value::String(to_hex(payload)),
+    }
+}
+
+/// Decode a protobuf message using the wire format, naming and typing fields from the
+/// descriptor where one is given. Repeated fields become arrays.
+pub fn decode_protobuf(
+    bytes: &[u8],
+    fields: &[ProtoFieldDescriptor],
+    depth: usize,
+) -> Result<serde_json::Map<String, serde_json::Value>, String> {
+    if depth > MAX_PROTOBUF_DEPTH {
+        return Err("Message is nested too deeply".to_string());
+    }
+
+    let mut message = serde_json::Map::new();
+    let mut position = 0;
+    while position < bytes.len() {
+        let key = read_varint(bytes, &mut position)?;
+        let number = key >> 3;
+        if number == 0 {
+            return Err("Invalid field number 0".to_string());
+        }
+        let descriptor = fields.iter().find(|field| field.number == number);
+        let field_type = descriptor.map(|field| field.field_type);
+
+        let value = match key & 0x7 {
+            0 => {
+                let raw = read_varint(bytes, &mut position)?;
+                match field_type {
+                    Some(ProtoFieldType::Bool) => serde_json::Value::Bool(raw != 0),
+                    Some(ProtoFieldType::Sint) => integer_to_json(((raw >> 1) as i64) ^ -((raw & 1) as i64)),
+                    _ => integer_to_json(raw as i64),
+                }
+            }
+            1 => {
+                let raw: [u8; 8] = read_slice(bytes, &mut position, 8)?.try_into().map_err(|_| "Truncated field")?;
+                match field_type {
+                    Some(ProtoFieldType::Double) => serde_json::json!(f64::from_le_bytes(raw)),
+                    _ => integer_to_json(i64::from_le_bytes(raw)),
+                }
+            }
+            2 => {
+                let length = read_varint(bytes, &mut position)? as usize;
+                let payload = read_slice(bytes, &mut position, length)?;
+                match descriptor {
+                    Some(field) if field.field_type == ProtoFieldType::String => {
+                        serde_json::Value::String(String::from_utf8_lossy(payload).into_owned())
+                    }
+                    Some(field) if field.field_type == ProtoFieldType::Message => {
+                        serde_json::Value::Object(decode_protobuf(payload, &field.fields, depth + 1)?)
+                    }
+                    Some(_) => serde_json::Value::String(to_hex(payload)),
+                    None => guess_length_delimited(payload, depth),
+                }
+            }
+            5 => {
+                let raw: [u8; 4] = read_slice(bytes, &mut position, 4)?.try_into().map_err(|_| "Truncated field")?;
+                match field_type {
+                    Some(ProtoFieldType::Float) => serde_json::json!(f32::from_le_bytes(raw)),
+                    _ => serde_json::json!(i32::from_le_bytes(raw)),
+                }
+            }
+            wire_type => return Err(format!("Unsupported wire type {} for field {}", wire_type, number)),
+        };
+
+        let name = descriptor.map(|field| field.name.clone()).unwrap_or_else(|| number.to_string());
+        match message.get_mut(&name) {
+            Some(serde_json::Value::Array(values)) => values.push(value),
+            Some(existing) => *existing = serde_json::Value::Array(vec![existing.take(), value]),
+            None => {
+                message.insert(name, value);
+            }
+        }
+    }
+    Ok(message)
+}
+
+/// Apply one transformer to a cell. Null cells and values the transformer can't decode give None.
+pub fn transform_value(
+    transformer: &ValueTransformer,
+    value: &serde_json::Value,
+    declared_type: &str,
+) -> Option<serde_json::Value> {
+    if value.is_null() {
+        return None;
+    }
+    match transformer {
+        ValueTransformer::Hex => transform_hex(value, declared_type),
+        ValueTransformer::Uuid => transform_uuid(value, declared_type),
+        ValueTransformer::Protobuf { fields } => cell_bytes(value, declared_type)
+            .and_then(|bytes| decode_protobuf(&bytes, fields, 0).ok())
+            .map(serde_json::Value::Object),
+        ValueTransformer::Flags { flags } => transform_flags(value, flags),
+    }
+}
+
+/// Transformed values for every column of the table that has a transformer assigned.
+pub fn apply_transformers(
+    columns: &[ColumnInfo],
+    rows: &[HashMap<String, serde_json::Value>],
+    transformers: &BTreeMap<String, ValueTransformer>,
+) -> Vec<TransformedColumn> {
+    columns
+        .iter()
+        .filter_map(|column| {
+            let transformer = transformers.get(&column.name)?;
+            Some(TransformedColumn {
+                column: column.name.clone(),
+                transformer: transformer.kind().to_string(),
+                values: rows
+                    .iter()
+                    .map(|row| {
+                        row.get(&column.name)
+                            .and_then(|value| transform_value(transformer, value, &column.type_name))
+                    })
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+fn require_db_path(current_db_path: &Option<String>) -> Result<String, String> {
+    current_db_path
+        .clone()
+        .ok_or_else(|| "Value transformers require a specific database path - no database selected".to_string())
+}
+
+/// Transformers assigned for this database, optionally limited to one table.
+#[tauri::command]
+pub async fn db_list_value_transformers(
+    app_handle: tauri::AppHandle,
+    table_name: Option<String>,
+    current_db_path: Option<String>,
+) -> Result<DbResponse<Vec<ColumnTransformer>>, String> {
+    let result = require_db_path(&current_db_path).and_then(|db_path| {
+        let mut store = load_value_transformers(&value_transformers_path(&app_handle)?);
+        Ok(store
+            .remove(&db_path)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(table, _)| match &table_name {
+                Some(name) => name == table,
+                None => true,
+            })
+            .flat_map(|(table, columns)| {
+                columns.into_iter().map(move |(column, transformer)| ColumnTransformer {
+                    table_name: table.clone(),
+                    column_name: column,
+                    transformer,
+                })
+            })
+            .collect::<Vec<_>>())
+    });
+
+    match result {
+        Ok(transformers) => Ok(DbResponse {
+            success: true,
+            data: Some(transformers),
+            error: None,
+        }),
+        Err(e) => {
+            log::error!("❌ Failed to list value transformers: {}", e);
+            Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            })
+        }
+    }
+}
+
+/// Assign a transformer to a column, or remove the assignment when `transformer` is omitted.
+#[tauri::command]
+pub async fn db_set_value_transformer(
+    app_handle: tauri::AppHandle,
+    table_name: String,
+    column_name: String,
+    transformer: Option<ValueTransformer>,
+    current_db_path: Option<String>,
+) -> Result<DbResponse<bool>, String> {
+    log::info!(
+        "🧩 Setting value transformer for '{}.{}': {}",
+        table_name,
+        column_name,
+        transformer.as_ref().map(ValueTransformer::kind).unwrap_or("none")
+    );
+
+    let result = require_db_path(&current_db_path).and_then(|db_path| {
+        let store_path = value_transformers_path(&app_handle)?;
+        let mut store = load_value_transformers(&store_path);
+        let tables = store.entry(db_path.clone()).or_default();
+        let columns = tables.entry(table_name.clone()).or_default();
+        match transformer {
+            Some(transformer) => {
+                columns.insert(column_name.clone(), transformer);
+            }
+            None => {
+                columns.remove(&column_name);
+                if columns.is_empty() {
+                    tables.remove(&table_name);
+                }
+                if tables.is_empty() {
+                    store.remove(&db_path);
+                }
+            }
+        }
+        save_value_transformers(&store_path, &store)?;
+        // Cached table data carries the old transformed values
+        DatabaseConnectionManager::clear_result_cache(Some(&db_path));
+        Ok(true)
+    });
+
+    match result {
+        Ok(updated) => Ok(DbResponse {
+            success: true,
+            data: Some(updated),
+            error: None,
+        }),
+        Err(e) => {
+            log::error!("❌ Failed to set value transformer: {}", e);
+            Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_hex_and_uuid_transformers() {
+        let uuid_bytes = general_purpose::STANDARD.encode([
+            0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0, 0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0,
+        ]);
+        assert_eq!(
+            transform_value(&ValueTransformer::Uuid, &json!(uuid_bytes), "BLOB"),
+            Some(json!("12345678-9abc-def0-1234-56789abcdef0"))
+        );
+        assert_eq!(
+            transform_value(&ValueTransformer::Uuid, &json!("123456789ABCDEF0123456789ABCDEF0"), "TEXT"),
+            Some(json!("12345678-9abc-def0-1234-56789abcdef0"))
+        );
+        assert_eq!(transform_value(&ValueTransformer::Uuid, &json!("nope"), "TEXT"), None);
+
+        assert_eq!(transform_value(&ValueTransformer::Hex, &json!("AQL/"), "BLOB"), Some(json!("0102ff")));
+        assert_eq!(transform_value(&ValueTransformer::Hex, &json!("AQL/"), "TEXT"), Some(json!("41514c2f")));
+        assert_eq!(transform_value(&ValueTransformer::Hex, &json!(255), "INTEGER"), Some(json!("0xff")));
+        assert_eq!(transform_value(&ValueTransformer::Hex, &serde_json::Value::Null, "BLOB"), None);
+    }
+
+    #[test]
+    fn test_flags_transformer() {
+        let transformer = ValueTransformer::Flags {
+            flags: vec![
+                FlagDescriptor { bit: 0, name: "read".to_string() },
+                FlagDescriptor { bit: 2, name: "archived".to_string() },
+            ],
+        };
+        assert_eq!(
+            transform_value(&transformer, &json!(13), "INTEGER"),
+            Some(json!(["read", "archived", "bit 3"]))
+        );
+        assert_eq!(transform_value(&transformer, &json!(0), "INTEGER"), Some(json!([])));
+    }
+
+    #[test]
+    fn test_protobuf_transformer() {
+        // field 1 varint 150, field 2 string "hi", field 3 sint -2, field 4 nested { field 1 varint 1 },
+        // field 5 repeated varint 7 and 8
+        let bytes = [
+            0x08, 0x96, 0x01, 0x12, 0x02, b'h', b'i', 0x18, 0x03, 0x22, 0x02, 0x08, 0x01, 0x28, 0x07, 0x28, 0x08,
+        ];
+        let encoded = general_purpose::STANDARD.encode(bytes);
+
+        let raw = transform_value(&ValueTransformer::Protobuf { fields: vec![] }, &json!(encoded), "BLOB");
+        assert_eq!(
+            raw,
+            Some(json!({ "1": 150, "2": "hi", "3": 3, "4": { "1": 1 }, "5": [7, 8] }))
+        );
+
+        let field = |number, name: &str, field_type| ProtoFieldDescriptor {
+            number,
+            name: name.to_string(),
+            field_type,
+            fields: vec![],
+        };
+        let transformer = ValueTransformer::Protobuf {
+            fields: vec![
+                field(1, "id", ProtoFieldType::Int),
+                field(2, "name", ProtoFieldType::String),
+                field(3, "delta", ProtoFieldType::Sint),
+                ProtoFieldDescriptor {
+                    fields: vec![field(1, "enabled", ProtoFieldType::Bool)],
+                    ..field(4, "options", ProtoFieldType::Message)
+                },
+            ],
+        };
+        assert_eq!(
+            transform_value(&transformer, &json!(encoded), "BLOB"),
+            Some(json!({ "id": 150, "name": "hi", "delta": -2, "options": { "enabled": true }, "5": [7, 8] }))
+        );
+
+        assert!(decode_protobuf(&[0x08], &[], 0).is_err());
+    }
+
+    #[test]
+    fn test_apply_transformers_and_store_round_trip() {
+        let columns = vec![ColumnInfo {
+            name: "flags".to_string(),
+            type_name: "INTEGER".to_string(),
+            notnull: false,
+            pk: false,
+            default_value: serde_json::Value::Null,
+        }];
+        let rows = vec![
+            HashMap::from([("flags".to_string(), json!(1))]),
+            HashMap::from([("flags".to_string(), serde_json::Value::Null)]),
+        ];
+        let transformers = BTreeMap::from([("flags".to_string(), ValueTransformer::Hex)]);
+        let transformed = apply_transformers(&columns, &rows, &transformers);
+        assert_eq!(transformed.len(), 1);
+        assert_eq!(transformed[0].transformer, "hex");
+        assert_eq!(transformed[0].values, vec![Some(json!("0x1")), None]);
+
+        let temp_dir = TempDir::new().unwrap();
+        let store_path = temp_dir.path().join(VALUE_TRANSFORMERS_NAME);
+        assert!(load_value_transformers(&store_path).is_empty());
+        let mut store = TransformerStore::new();
+        store
+            .entry("/tmp/app.db".to_string())
+            .or_default()
+            .insert("items".to_string(), transformers);
+        save_value_transformers(&store_path, &store).unwrap();
+        assert_eq!(load_value_transformers(&store_path), store);
+
+        let parsed: ValueTransformer =
+            serde_json::from_value(json!({ "kind": "flags", "flags": [{ "bit": 1, "name": "pinned" }] })).unwrap();
+        assert_eq!(parsed.kind(), "flags");
+    }
+}
@@ -0,0 +1,375 @@
+// Trigger inspection
+// Lists triggers per table and temporarily drops them around manual data fixes,
+// keeping the original definitions on disk so they can be recreated later
+
+use crate::commands::database::connection_access::get_current_pool;
+use crate::commands::database::helpers::quote_ident;
+use crate::commands::database::types::*;
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqlitePool, Row};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tauri::{Manager, State};
+
+const DISABLED_TRIGGERS_NAME: &str = "disabled_triggers.json";
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerInfo {
+    pub name: String,
+    pub table_name: String,
+    pub sql: String,
+    // False while the trigger is dropped and only kept in the disabled store
+    pub enabled: bool,
+    pub disabled_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerRestoreFailure {
+    pub name: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerRestoreResult {
+    pub restored: Vec<String>,
+    // Triggers that could not be recreated stay in the disabled store
+    pub failed: Vec<TriggerRestoreFailure>,
+}
+
+// Disabled triggers keyed by database path
+type DisabledTriggerStore = BTreeMap<String, Vec<TriggerInfo>>;
+
+fn disabled_triggers_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    std::fs::create_dir_all(&data_dir)
+        .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    Ok(data_dir.join(DISABLED_TRIGGERS_NAME))
+}
+
+pub fn load_disabled_triggers(store_path: &Path) -> DisabledTriggerStore {
+    std::fs::read_to_string(store_path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_disabled_triggers(store_path: &Path, store: &DisabledTriggerStore) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(store)
+        .map_err(|e| format!("Failed to serialize disabled triggers: {}", e))?;
+    std::fs::write(store_path, content).map_err(|e| format!("Failed to save disabled triggers: {}", e))
+}
+
+/// Triggers currently defined in the database, optionally limited to one table.
+pub async fn read_triggers(pool: &SqlitePool, table_name: Option<&str>) -> Result<Vec<TriggerInfo>, String> {
+    let rows = sqlx::query(
+        "SELECT name, tbl_name, sql FROM sqlite_master WHERE type='trigger' AND sql IS NOT NULL
+         AND (?1 IS NULL OR tbl_name = ?1) ORDER BY tbl_name, name",
+    )
+    .bind(table_name)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Error reading triggers: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| TriggerInfo {
+            name: row.get("name"),
+            table_name: row.get("tbl_name"),
+            sql: row.get("sql"),
+            enabled: true,
+            disabled_at: None,
+        })
+        .collect())
+}
+
+fn matches_table(trigger: &TriggerInfo, table_name: &Option<String>) -> bool {
+    match table_name {
+        Some(table) => &trigger.table_name == table,
+        None => true,
+    }
+}
+
+fn mark_disabled(triggers: &[TriggerInfo]) -> Vec<TriggerInfo> {
+    let disabled_at = chrono::Utc::now().to_rfc3339();
+    triggers
+        .iter()
+        .map(|trigger| TriggerInfo {
+            enabled: false,
+            disabled_at: Some(disabled_at.clone()),
+            ..trigger.clone()
+        })
+        .collect()
+}
+
+/// Drop the triggers in one transaction.
+pub async fn drop_triggers(pool: &SqlitePool, triggers: &[TriggerInfo]) -> Result<(), String> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+    for trigger in triggers {
+        sqlx::query(&format!("DROP TRIGGER {}", quote_ident(&trigger.name)))
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to drop trigger '{}': {}", trigger.name, e))?;
+    }
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))
+}
+
+/// Recreate stored triggers one by one so a single broken definition doesn't block the rest.
+pub async fn recreate_triggers(pool: &SqlitePool, triggers: &[TriggerInfo]) -> TriggerRestoreResult {
+    let mut result = TriggerRestoreResult::default();
+    for trigger in triggers {
+        match sqlx::query(&trigger.sql).execute(pool).await {
+            Ok(_) => result.restored.push(trigger.name.clone()),
+            Err(e) => result.failed.push(TriggerRestoreFailure {
+                name: trigger.name.clone(),
+                error: e.to_string(),
+            }),
+        }
+    }
+    result
+}
+
+fn require_db_path(current_db_path: &Option<String>) -> Result<String, String> {
+    current_db_path
+        .clone()
+        .ok_or_else(|| "Trigger changes require a specific database path - no database selected".to_string())
+}
+
+/// List active triggers plus the ones disabled through Flippio for this database.
+#[tauri::command]
+pub async fn db_list_triggers(
+    app_handle: tauri::AppHandle,
+    state: State<'_, DbPool>,
+    db_cache: State<'_, DbConnectionCache>,
+    table_name: Option<String>,
+    current_db_path: Option<String>,
+) -> Result<DbResponse<Vec<TriggerInfo>>, String> {
+    let result = async {
+        let pool = get_current_pool(&state, &db_cache, current_db_path.clone()).await?;
+        let mut triggers = read_triggers(&pool, table_name.as_deref()).await?;
+        if let Some(db_path) = &current_db_path {
+            let store = load_disabled_triggers(&disabled_triggers_path(&app_handle)?);
+            triggers.extend(
+                store
+                    .get(db_path)
+                    .into_iter()
+                    .flatten()
+                    .filter(|trigger| matches_table(trigger, &table_name))
+                    .cloned(),
+            );
+        }
+        Ok::<_, String>(triggers)
+    }
+    .await;
+
+    match result {
+        Ok(triggers) => Ok(DbResponse {
+            success: true,
+            data: Some(triggers),
+            error: None,
+        }),
+        Err(e) => {
+            log::error!("❌ Failed to list triggers: {}", e);
+            Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            })
+        }
+    }
+}
+
+/// Drop the triggers of a table (or of the whole database) for a bulk edit session.
+/// Definitions are written to the disabled store before anything is dropped.
+#[tauri::command]
+pub async fn db_disable_triggers(
+    app_handle: tauri::AppHandle,
+    state: State<'_, DbPool>,
+    db_cache: State<'_, DbConnectionCache>,
+    table_name: Option<String>,
+    current_db_path: Option<String>,
+) -> Result<DbResponse<Vec<TriggerInfo>>, String> {
+    let result = async {
+        let db_path = require_db_path(&current_db_path)?;
+        let pool = get_current_pool(&state, &db_cache, current_db_path).await?;
+        let triggers = read_triggers(&pool, table_name.as_deref()).await?;
+        if triggers.is_empty() {
+            return Ok::<_, String>(Vec::new());
+        }
+
+        let store_path = disabled_triggers_path(&app_handle)?;
+        let mut store = load_disabled_triggers(&store_path);
+        let disabled = mark_disabled(&triggers);
+        let previous = store.get(&db_path).cloned();
+        store.entry(db_path.clone()).or_default().extend(disabled.iter().cloned());
+        save_disabled_triggers(&store_path, &store)?;
+
+        match drop_triggers(&pool, &triggers).await {
+            Ok(()) => Ok(disabled),
+            Err(e) => {
+                // Nothing was dropped, so forget the definitions again
+                match previous {
+                    Some(previous) => store.insert(db_path, previous),
+                    None => store.remove(&db_path),
+                };
+                save_disabled_triggers(&store_path, &store)?;
+                Err(e)
+            }
+        }
+    }
+    .await;
+
+    match result {
+        Ok(disabled) => {
+            log::info!("⏸️ Disabled {} triggers", disabled.len());
+            Ok(DbResponse {
+                success: true,
+                data: Some(disabled),
+                error: None,
+            })
+        }
+        Err(e) => {
+            log::error!("❌ Failed to disable triggers: {}", e);
+            Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            })
+        }
+    }
+}
+
+/// Recreate triggers previously disabled for this database (optionally only for one table).
+#[tauri::command]
+pub async fn db_restore_triggers(
+    app_handle: tauri::AppHandle,
+    state: State<'_, DbPool>,
+    db_cache: State<'_, DbConnectionCache>,
+    table_name: Option<String>,
+    current_db_path: Option<String>,
+) -> Result<DbResponse<TriggerRestoreResult>, String> {
+    let result = async {
+        let db_path = require_db_path(&current_db_path)?;
+        let pool = get_current_pool(&state, &db_cache, current_db_path).await?;
+        let store_path = disabled_triggers_path(&app_handle)?;
+        let mut store = load_disabled_triggers(&store_path);
+
+        let stored = store.remove(&db_path).unwrap_or_default();
+        let (to_restore, kept): (Vec<TriggerInfo>, Vec<TriggerInfo>) = stored
+            .into_iter()
+            .partition(|trigger| matches_table(trigger, &table_name));
+
+        let outcome = recreate_triggers(&pool, &to_restore).await;
+        let remaining: Vec<TriggerInfo> = kept
+            .into_iter()
+            .chain(
+                to_restore
+                    .into_iter()
+                    .filter(|trigger| outcome.failed.iter().any(|failed| failed.name == trigger.name)),
+            )
+            .collect();
+        if !remaining.is_empty() {
+            store.insert(db_path, remaining);
+        }
+        save_disabled_triggers(&store_path, &store)?;
+        Ok::<_, String>(outcome)
+    }
+    .await;
+
+    match result {
+        Ok(outcome) => {
+            log::info!(
+                "▶️ Restored {} triggers ({} failed)",
+                outcome.restored.len(),
+                outcome.failed.len()
+            );
+            Ok(DbResponse {
+                success: outcome.failed.is_empty(),
+                error: (!outcome.failed.is_empty())
+                    .then(|| format!("{} triggers could not be restored", outcome.failed.len())),
+                data: Some(outcome),
+            })
+        }
+        Err(e) => {
+            log::error!("❌ Failed to restore triggers: {}", e);
+            Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_drop_and_recreate_triggers() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("triggers.db");
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT, updated_at TEXT);
+             CREATE TABLE other (id INTEGER PRIMARY KEY);
+             CREATE TRIGGER items_touch AFTER UPDATE ON items
+             BEGIN UPDATE items SET updated_at = 'trigger' WHERE id = NEW.id; END;
+             CREATE TRIGGER other_noop AFTER INSERT ON other BEGIN SELECT 1; END;
+             INSERT INTO items VALUES (1, 'a', NULL);",
+        )
+        .unwrap();
+        drop(conn);
+
+        let pool = SqlitePool::connect(&format!("sqlite:{}", db_path.display())).await.unwrap();
+        let triggers = read_triggers(&pool, Some("items")).await.unwrap();
+        assert_eq!(triggers.len(), 1);
+        assert_eq!(triggers[0].name, "items_touch");
+
+        drop_triggers(&pool, &triggers).await.unwrap();
+        let disabled = mark_disabled(&triggers);
+        assert!(!disabled[0].enabled);
+        sqlx::query("UPDATE items SET name = 'b'").execute(&pool).await.unwrap();
+        let touched: Option<String> = sqlx::query_scalar("SELECT updated_at FROM items").fetch_one(&pool).await.unwrap();
+        assert_eq!(touched, None);
+        assert_eq!(read_triggers(&pool, None).await.unwrap().len(), 1);
+
+        let outcome = recreate_triggers(&pool, &disabled).await;
+        assert_eq!(outcome.restored, vec!["items_touch".to_string()]);
+        assert!(outcome.failed.is_empty());
+        assert_eq!(read_triggers(&pool, None).await.unwrap().len(), 2);
+        pool.close().await;
+    }
+
+    #[test]
+    fn test_disabled_trigger_store_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let store_path = temp_dir.path().join(DISABLED_TRIGGERS_NAME);
+        assert!(load_disabled_triggers(&store_path).is_empty());
+
+        let mut store = DisabledTriggerStore::new();
+        store.insert(
+            "/tmp/app.db".to_string(),
+            vec![TriggerInfo {
+                name: "t".to_string(),
+                table_name: "items".to_string(),
+                sql: "CREATE TRIGGER t AFTER INSERT ON items BEGIN SELECT 1; END".to_string(),
+                enabled: false,
+                disabled_at: Some("2024-01-01T00:00:00Z".to_string()),
+            }],
+        );
+        save_disabled_triggers(&store_path, &store).unwrap();
+        assert_eq!(load_disabled_triggers(&store_path), store);
+    }
+}
@@ -63,7 +63,7 @@ pub struct TableInfo {
     pub name: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ColumnInfo {
     pub name: String,
     #[serde(rename = "type")]
@@ -78,6 +78,15 @@ pub struct ColumnInfo {
 pub struct TableData {
     pub columns: Vec<ColumnInfo>,
     pub rows: Vec<HashMap<String, serde_json::Value>>,
+    // Readable dates for columns that hold epoch, Apple reference or ISO timestamps
+    #[serde(rename = "timestampColumns", default, skip_serializing_if = "Vec::is_empty")]
+    pub timestamp_columns: Vec<crate::commands::database::timestamps::TimestampColumn>,
+    // Decoded values for columns with a value transformer assigned
+    #[serde(rename = "transformedColumns", default, skip_serializing_if = "Vec::is_empty")]
+    pub transformed_columns: Vec<crate::commands::database::transformers::TransformedColumn>,
+    // BLOB columns holding gzip/zlib payloads, readable through db_decompress_cell
+    #[serde(rename = "compressedColumns", default, skip_serializing_if = "Vec::is_empty")]
+    pub compressed_columns: Vec<crate::commands::database::compressed_cells::CompressedColumn>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -94,6 +103,7 @@ pub struct ConnectionConfig {
     pub connection_ttl: Duration,
     pub cleanup_interval: Duration,
     pub cache_disabled: bool,
+    pub read_replicas: bool,
 }
 
 impl Default for ConnectionConfig {
@@ -103,6 +113,7 @@ impl Default for ConnectionConfig {
             connection_ttl: Duration::from_secs(300), // 5 minutes TTL
             cleanup_interval: Duration::from_secs(60), // Cleanup every minute
             cache_disabled: false,         // Cache enabled by default
+            read_replicas: false,          // Reads share the writer pool by default
         }
     }
 }
@@ -1,6 +1,29 @@
 use super::types::*;
+use super::errors::{CommandErrorExt, DeviceError};
 use super::helpers::*;
+use super::command_policy::PolicyOutputExt;
+use super::android_storage::{
+    android_user_from_path, push_with_strategy, read_remote_permissions, restore_remote_permissions, run_as_args,
+    shell_quote, PushStrategy,
+};
+use super::capabilities::{cached_capabilities, forget_disconnected_capabilities};
+use super::pull_strategy::{android_pull_strategies, exec_out_error, pull_with_fallbacks, pulled_with, PullStrategy};
+use super::hooks::{with_push_hooks, PushHookContext};
+use super::verification::{with_push_verification, PushedFile};
+use super::app_restart::with_app_stopped;
+use super::transfer::{ProgressCallback, TransferProgress, PUSH_PROGRESS_EVENT};
+use tauri::{Emitter, Manager};
+use crate::commands::journal::{begin_operation, JournalOperation};
+use crate::commands::common::{check_cancelled, with_device_turn, OperationKind, OPERATIONS};
+use super::favorites::{with_device_favorites, with_package_favorites};
+use super::scanner::{packages_scan_key, DEVICE_LIST_TTL, DEVICE_SCANNER, PACKAGE_LIST_TTL};
+use super::integrity::{
+    check_push_integrity, compute_file_sha256, pull_integrity_for, read_pulled_file_metadata, refresh_metadata_hash,
+    save_base_copy, spawn_pull_integrity_check,
+};
+use crate::commands::guardrails::{check_dangerous_operation, DangerousOperation};
 use crate::commands::database::helpers::prepare_sqlite_file_for_sync;
+use crate::commands::database::{invalidate_cached_connection, DbConnectionCache};
 use log::{info, error};
 use std::path::Path;
 use std::fs;
@@ -8,31 +31,46 @@ use chrono;
 use serde_json;
 use std::future::Future;
 
+// `adb devices` states worth listing; `device` is the only one that accepts commands
+const ANDROID_DEVICE_STATES: [&str; 6] = ["device", "unauthorized", "offline", "recovery", "sideload", "no permissions"];
+
 fn parse_adb_devices_output(devices_output: &str) -> Vec<Device> {
     let mut devices = Vec::new();
 
-    for line in devices_output.lines().skip(1) {
+    // The header and `* daemon started` notices are skipped by the state check below rather
+    // than by position, since the daemon notices can precede the header
+    for line in devices_output.lines() {
         let trimmed_line = line.trim();
-        if trimmed_line.is_empty() {
+        if trimmed_line.is_empty() || trimmed_line.starts_with('*') {
             continue;
         }
 
         let parts: Vec<&str> = trimmed_line.split_whitespace().collect();
         log::info!("Parsing line: '{}', parts: {:?}", trimmed_line, parts);
 
-        if parts.len() >= 2 && parts[1] == "device" {
+        // `no permissions (...)` is the only state spanning several words
+        let (state, qualifiers_start) = match parts.as_slice() {
+            [_, "no", "permissions", ..] => ("no permissions", 3),
+            [_, state, ..] => (*state, 2),
+            _ => continue,
+        };
+
+        if ANDROID_DEVICE_STATES.contains(&state) {
             let device_id = parts[0].to_string();
             let mut model = "Unknown".to_string();
             let mut device_name = device_id.clone();
 
             let is_physical_device = trimmed_line.contains("usb:");
-            let description = if is_physical_device {
-                "Android device".to_string()
-            } else {
-                "Android emulator".to_string()
+            let kind = if is_physical_device { "Android device" } else { "Android emulator" };
+            let description = match state {
+                "device" => kind.to_string(),
+                "unauthorized" => format!("{} - unauthorized: accept the USB debugging prompt on the device", kind),
+                "offline" => format!("{} - offline: reconnect it or restart adb", kind),
+                "no permissions" => format!("{} - no USB permissions: check your udev rules", kind),
+                other => format!("{} - in {} mode", kind, other),
             };
 
-            for part in &parts[2..] {
+            for part in parts.iter().skip(qualifiers_start) {
                 if part.starts_with("model:") {
                     model = part.replace("model:", "");
                 } else if part.starts_with("device:") {
@@ -40,14 +78,20 @@ fn parse_adb_devices_output(devices_output: &str) -> Vec<Device> {
                 }
             }
 
-            log::info!("Found device: id={}, name={}, model={}", device_id, device_name, model);
+            log::info!("Found device: id={}, name={}, model={}, state={}", device_id, device_name, model, state);
 
             devices.push(Device {
                 id: device_id,
                 name: device_name,
+                display_name: sanitize_device_name(&device_name),
                 model,
                 device_type: "android".to_string(),
                 description,
+                api_level: None,
+                abi: None,
+                build_fingerprint: None,
+                state: Some(state.to_string()),
+                favorite: None,
             });
         }
     }
@@ -71,6 +115,7 @@ fn parse_adb_packages_output(packages_output: &str) -> Vec<Package> {
             packages.push(Package {
                 name: display_name,
                 bundle_id: package_name,
+                favorite: None,
             });
         }
     }
@@ -83,63 +128,51 @@ fn adb_find_database_args(
     package_name: &str,
     location: &str,
     admin_required: bool,
+    user_id: Option<u32>,
 ) -> Vec<String> {
     let path = format!("{}{}/", location, package_name);
 
+    let mut args = vec!["-s".to_string(), device_id.to_string(), "shell".to_string()];
     if admin_required {
-        vec![
-            "-s".to_string(),
-            device_id.to_string(),
-            "shell".to_string(),
-            "run-as".to_string(),
-            package_name.to_string(),
-            "find".to_string(),
-            path,
-            "-name".to_string(),
-            "*.db".to_string(),
-            "-o".to_string(),
-            "-name".to_string(),
-            "*.sqlite".to_string(),
-            "-o".to_string(),
-            "-name".to_string(),
-            "*.sqlite3".to_string(),
-        ]
-    } else {
-        vec![
-            "-s".to_string(),
-            device_id.to_string(),
-            "shell".to_string(),
-            "find".to_string(),
-            path,
-            "-name".to_string(),
-            "*.db".to_string(),
-            "-o".to_string(),
-            "-name".to_string(),
-            "*.sqlite".to_string(),
-            "-o".to_string(),
-            "-name".to_string(),
-            "*.sqlite3".to_string(),
-        ]
+        args.extend(run_as_args(package_name, user_id));
+    }
+    args.extend(["find".to_string(), path]);
+    args.extend(
+        ["-name", "*.db", "-o", "-name", "*.sqlite", "-o", "-name", "*.sqlite3", "-o", "-name", "*.realm"]
+            .iter()
+            .map(|arg| arg.to_string()),
+    );
+    args
+}
+
+/// Database search roots. Secondary users and work profiles live under their own user id;
+/// the primary user is also reachable through the `/data/data` and `/sdcard` aliases.
+fn android_database_locations(user_id: Option<u32>) -> Vec<(String, bool)> {
+    match user_id.filter(|user_id| *user_id != 0) {
+        Some(user_id) => vec![
+            (format!("/data/user/{}/", user_id), true),
+            (format!("/storage/emulated/{}/Android/data/", user_id), false),
+        ],
+        None => vec![
+            ("/data/data/".to_string(), true),
+            ("/sdcard/Android/data/".to_string(), false),
+            ("/storage/emulated/0/Android/data/".to_string(), false),
+        ],
     }
 }
 
 async fn discover_android_database_candidates_with<F, Fut>(
     device_id: &str,
     package_name: &str,
+    user_id: Option<u32>,
     mut execute: F,
 ) -> Vec<(String, bool, String)>
 where
     F: FnMut(Vec<String>) -> Fut,
     Fut: Future<Output = Result<std::process::Output, Box<dyn std::error::Error + Send + Sync>>>,
 {
-    let locations = vec![
-        ("/data/data/", true),
-        ("/sdcard/Android/data/", false),
-        ("/storage/emulated/0/Android/data/", false),
-    ];
-
-    for (location, admin_required) in locations {
-        let args = adb_find_database_args(device_id, package_name, location, admin_required);
+    for (location, admin_required) in android_database_locations(user_id) {
+        let args = adb_find_database_args(device_id, package_name, &location, admin_required, user_id);
         let output = execute(args).await;
 
         if let Ok(result) = output {
@@ -149,7 +182,7 @@ where
                     .lines()
                     .map(str::trim)
                     .filter(|line| !line.is_empty())
-                    .map(|line| (line.to_string(), admin_required, location.to_string()))
+                    .map(|line| (line.to_string(), admin_required, location.clone()))
                     .collect();
 
                 if !found_files.is_empty() {
@@ -180,6 +213,7 @@ where
             return DeviceResponse {
                 success: false,
                 data: None,
+                error_detail: Some(e.device_error()),
                 error: Some(format!(
                     "Failed to execute adb command: {}. Make sure Android SDK is installed and ADB is in your PATH.",
                     e
@@ -189,7 +223,7 @@ where
     };
 
     if output.status.success() {
-        let devices_output = String::from_utf8_lossy(&output.stdout);
+        let devices_output = decode_tool_output(&output.stdout);
         log::info!("ADB devices output: {}", devices_output);
         let devices = parse_adb_devices_output(&devices_output);
 
@@ -199,12 +233,14 @@ where
             success: true,
             data: Some(devices),
             error: None,
+            error_detail: None,
         }
     } else {
         let error_output = String::from_utf8_lossy(&output.stderr);
         DeviceResponse {
             success: false,
             data: None,
+            error_detail: Some(output.device_error()),
             error: Some(error_output.to_string()),
         }
     }
@@ -212,13 +248,14 @@ where
 
 async fn adb_get_packages_with<F, Fut>(
     device_id: &str,
+    user_id: Option<u32>,
     execute: F,
 ) -> DeviceResponse<Vec<Package>>
 where
     F: FnOnce(Vec<String>) -> Fut,
     Fut: Future<Output = Result<std::process::Output, Box<dyn std::error::Error + Send + Sync>>>,
 {
-    let args = vec![
+    let mut args = vec![
         "-s".to_string(),
         device_id.to_string(),
         "shell".to_string(),
@@ -227,6 +264,10 @@ where
         "packages".to_string(),
         "-3".to_string(),
     ];
+    if let Some(user_id) = user_id {
+        args.push("--user".to_string());
+        args.push(user_id.to_string());
+    }
 
     let output = match execute(args).await {
         Ok(output) => output,
@@ -234,6 +275,7 @@ where
             return DeviceResponse {
                 success: false,
                 data: None,
+                error_detail: Some(e.device_error()),
                 error: Some(format!(
                     "Failed to execute adb command: {}. Make sure the device is connected and ADB is working.",
                     e
@@ -250,149 +292,323 @@ where
             success: true,
             data: Some(packages),
             error: None,
+            error_detail: None,
         }
     } else {
         let error_output = String::from_utf8_lossy(&output.stderr);
         DeviceResponse {
             success: false,
             data: None,
+            error_detail: Some(output.device_error()),
             error: Some(error_output.to_string()),
         }
     }
 }
 
-// Pull Android database file to local temp directory
-async fn pull_android_db_file(
+/// Inflate a gzip stream received from the device into `local_path`, returning the bytes written.
+fn gunzip_to_file(compressed: &[u8], local_path: &Path) -> Result<u64, String> {
+    if !compressed.starts_with(&[0x1f, 0x8b]) {
+        let preview = String::from_utf8_lossy(&compressed[..compressed.len().min(200)]).trim().to_string();
+        return Err(format!("Device did not return gzip data: {}", preview));
+    }
+    let mut decoder = flate2::read::GzDecoder::new(compressed);
+    let mut file = fs::File::create(local_path).map_err(|e| format!("Failed to create {}: {}", local_path.display(), e))?;
+    std::io::copy(&mut decoder, &mut file).map_err(|e| format!("Failed to decompress pulled file: {}", e))
+}
+
+/// Pull a private database as `run-as <package> gzip -c <path>` over exec-out.
+async fn pull_run_as_compressed(device_id: &str, run_as: &[String], remote_path: &str, local_path: &Path) -> Result<(), String> {
+    let mut args = vec!["-s", device_id, "exec-out"];
+    args.extend(run_as.iter().map(String::as_str));
+    args.extend(["gzip", "-c", remote_path]);
+    let output = execute_adb_command(&args).await.map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    let written = gunzip_to_file(&output.stdout, local_path)?;
+    info!("📦 Received {} compressed bytes for {} bytes of database", output.stdout.len(), written);
+    Ok(())
+}
+
+/// Pull a private database as `run-as <package> cat`, gzipped on the device when `compress` is set.
+async fn pull_run_as(
+    device_id: &str,
+    package_name: &str,
+    remote_path: &str,
+    local_path: &Path,
+    compress: bool,
+) -> Result<(), String> {
+    info!("Using admin access (run-as) mode");
+    let run_as = run_as_args(package_name, android_user_from_path(remote_path));
+
+    // gzip on the device and inflate locally; fall back to a plain copy when that fails,
+    // e.g. on devices whose toybox has no gzip
+    if compress {
+        match pull_run_as_compressed(device_id, &run_as, remote_path, local_path).await {
+            Ok(()) => return Ok(()),
+            Err(e) => {
+                log::warn!("⚠️ Compressed pull failed, falling back to a plain copy: {}", e);
+                let _ = fs::remove_file(local_path);
+            }
+        }
+    }
+
+    // Use shell command with redirection like in Electron
+    // Important: Use exec-out with run-as and redirect to local file
+    let adb_path = get_adb_path();
+
+    // There is no `sh` on Windows, so stream exec-out into the file directly
+    #[cfg(windows)]
+    let output = {
+        let mut args = vec!["-s", device_id, "exec-out"];
+        args.extend(run_as.iter().map(String::as_str));
+        args.extend(["cat", remote_path]);
+        let output = execute_adb_command_to_file(&args, local_path)
+            .await
+            .map_err(|e| e.to_string())?;
+        if !output.status.success() {
+            let _ = fs::remove_file(local_path);
+        }
+        output
+    };
+
+    #[cfg(not(windows))]
+    let output = {
+        let shell_cmd = format!(
+            "{} -s {} exec-out {} cat {} > {}",
+            shell_quote(&adb_path),
+            shell_quote(device_id),
+            run_as.iter().map(String::as_str).map(shell_quote).collect::<Vec<_>>().join(" "),
+            shell_quote(remote_path),
+            shell_quote(&local_path.to_string_lossy())
+        );
+    
+        info!("Executing shell command: {}", shell_cmd);
+    
+        // Use std::process::Command directly like in Electron for better compatibility
+        let mut command = std::process::Command::new("sh");
+        command.arg("-c").arg(&shell_cmd);
+        command.output_with_policy().await.map_err(|e| e.to_string())?
+    };
+
+    info!("Shell command completed");
+    info!("Exit status: {:?}", output.status);
+
+    if !output.stderr.is_empty() {
+        let stderr_str = String::from_utf8_lossy(&output.stderr);
+        info!("Stderr content: {}", stderr_str);
+        // Note: stderr might contain non-error messages from adb
+    }
+
+    // For exec-out with redirection, check if file was created successfully
+    // rather than relying solely on exit status
+    if !local_path.exists() {
+        let error_msg = String::from_utf8_lossy(&output.stderr);
+        error!("Shell command failed - file not created: {}", error_msg);
+        return Err(format!("ADB exec-out failed to create file: {}", error_msg));
+    }
+    Ok(())
+}
+
+/// Pull any file as root: directly when adbd already runs as root, otherwise through `su`,
+/// trying both common `su` argument styles.
+async fn pull_as_root(device_id: &str, remote_path: &str, local_path: &Path) -> Result<(), String> {
+    let path = shell_quote(remote_path);
+    let script = format!(
+        "if [ \"$(id -u)\" = 0 ]; then cat {0}; else su 0 cat {0} 2>/dev/null || su -c \"cat {0}\"; fi",
+        path
+    );
+    info!("Executing as root: cat {}", remote_path);
+    let output = execute_adb_command_to_file(&["-s", device_id, "exec-out", &script], local_path)
+        .await
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(())
+}
+
+/// Plain `adb pull`, for shared storage or anything the shell user can read.
+async fn pull_direct(device_id: &str, remote_path: &str, local_path: &Path) -> Result<(), String> {
+    info!("Using standard pull mode");
+    info!("Executing: adb -s {} pull {} {}", device_id, remote_path, local_path.display());
+
+    let output = execute_adb_command(&["-s", device_id, "pull", remote_path, &local_path.to_string_lossy()])
+        .await
+        .map_err(|e| e.to_string())?;
+
+    info!("ADB pull command completed");
+    info!("Exit status: {:?}", output.status);
+    info!("Stdout: {}", String::from_utf8_lossy(&output.stdout));
+
+    if !output.stderr.is_empty() {
+        let stderr_str = String::from_utf8_lossy(&output.stderr);
+        info!("Stderr content: {}", stderr_str);
+    }
+
+    if !output.status.success() {
+        let error_msg = String::from_utf8_lossy(&output.stderr);
+        error!("ADB pull failed: {}", error_msg);
+        return Err(format!("ADB pull failed: {}", error_msg));
+    }
+    Ok(())
+}
+
+/// Pull `remote_path` into `local_path` with one strategy; a failed attempt leaves no file behind.
+async fn pull_android_file_with(
+    strategy: PullStrategy,
+    device_id: &str,
+    package_name: &str,
+    remote_path: &str,
+    local_path: &Path,
+    compress: bool,
+) -> Result<(), String> {
+    let result = match strategy {
+        PullStrategy::RunAs => pull_run_as(device_id, package_name, remote_path, local_path, compress).await,
+        PullStrategy::Root => pull_as_root(device_id, remote_path, local_path).await,
+        _ => pull_direct(device_id, remote_path, local_path).await,
+    }
+    .and_then(|()| match fs::metadata(local_path) {
+        Ok(metadata) if metadata.len() == 0 => Err("Pulled database file is empty".to_string()),
+        Ok(metadata) => {
+            info!("File successfully created: {:?} ({} bytes)", local_path, metadata.len());
+            // Refusals over exec-out arrive as the file's content
+            match exec_out_error(local_path).filter(|_| strategy != PullStrategy::AdbPull) {
+                Some(message) => Err(message),
+                None => Ok(()),
+            }
+        }
+        Err(e) => Err(format!("File was not created: {}", e)),
+    });
+
+    if result.is_err() {
+        let _ = fs::remove_file(local_path);
+    }
+    result
+}
+
+/// Pull `remote_path` into `local_path` with the first strategy that works
+async fn pull_android_file(
     device_id: &str,
     package_name: &str,
     remote_path: &str,
     admin_access: bool,
+    compress: bool,
+    local_path: &Path,
+) -> Result<PullStrategy, String> {
+    let strategies = android_pull_strategies(admin_access, cached_capabilities(device_id).as_ref());
+    pull_with_fallbacks(&strategies, move |strategy| {
+        pull_android_file_with(strategy, device_id, package_name, remote_path, local_path, compress)
+    })
+    .await
+}
+
+/// Pull `remote_path` into `local_path` without touching the temp copy, its metadata or base copy
+pub(crate) async fn pull_android_db_file_to(
+    device_id: &str,
+    package_name: &str,
+    remote_path: &str,
+    admin_access: bool,
+    local_path: &Path,
+) -> Result<PullStrategy, String> {
+    with_device_turn(
+        device_id,
+        pull_android_file(device_id, package_name, remote_path, admin_access, false, local_path),
+    )
+    .await
+}
+
+// Pull Android database file to local temp directory; `compress` gzips private files on the device
+pub(crate) async fn pull_android_db_file(
+    device_id: &str,
+    package_name: &str,
+    remote_path: &str,
+    admin_access: bool,
+    compress: bool,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    with_device_turn(
+        device_id,
+        pull_android_db_file_in_turn(device_id, package_name, remote_path, admin_access, compress),
+    )
+    .await
+}
+
+async fn pull_android_db_file_in_turn(
+    device_id: &str,
+    package_name: &str,
+    remote_path: &str,
+    admin_access: bool,
+    compress: bool,
 ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
     info!("=== Starting pull_android_db_file ===");
     info!("Device ID: {}", device_id);
     info!("Package: {}", package_name);
     info!("Remote path: {}", remote_path);
     info!("Admin access: {}", admin_access);
+    info!("Compress: {}", compress);
     
     let temp_dir = ensure_temp_dir()?;
     info!("Temp directory: {:?}", temp_dir);
     
-    // Generate unique filename to avoid conflicts when multiple files have the same name
-    let unique_filename = generate_unique_filename(remote_path)?;
-    let local_path = temp_dir.join(&unique_filename);
-    info!("Local path will be: {:?} (unique filename: {})", local_path, unique_filename);
+    // Namespace by device, app and location so files with the same name never collide
+    let local_path = namespaced_temp_path(&temp_dir, device_id, package_name, remote_path)?;
+    info!("Local path will be: {:?}", local_path);
+    let _journal = begin_operation(
+        JournalOperation::Pull,
+        "android",
+        device_id,
+        package_name,
+        &local_path.to_string_lossy(),
+        remote_path,
+    );
     
-    // Execute ADB command based on admin access
-    if admin_access {
-        info!("Using admin access (run-as) mode");
-        
-        // Use shell command with redirection like in Electron
-        // Important: Use exec-out with run-as and redirect to local file
-        let adb_path = get_adb_path();
-        let shell_cmd = format!("{} -s {} exec-out run-as {} cat {} > \"{}\"", 
-                               adb_path, device_id, package_name, remote_path, local_path.display());
-        
-        info!("Executing shell command: {}", shell_cmd);
-        
-        // Use std::process::Command directly like in Electron for better compatibility
-        let output = std::process::Command::new("sh")
-            .arg("-c")
-            .arg(&shell_cmd)
-            .output()?;
-        
-        info!("Shell command completed");
-        info!("Exit status: {:?}", output.status);
-        
-        if !output.stderr.is_empty() {
-            let stderr_str = String::from_utf8_lossy(&output.stderr);
-            info!("Stderr content: {}", stderr_str);
-            // Note: stderr might contain non-error messages from adb
-        }
-        
-        // For exec-out with redirection, check if file was created successfully
-        // rather than relying solely on exit status
-        if !local_path.exists() {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
-            error!("Shell command failed - file not created: {}", error_msg);
-            return Err(format!("ADB exec-out failed to create file: {}", error_msg).into());
-        }
-        
-    } else {
-        info!("Using standard pull mode");
-        
-        // For standard access, use adb pull
-        info!("Executing: adb -s {} pull {} {}", device_id, remote_path, local_path.display());
-        
-        let output = execute_adb_command(&["-s", device_id, "pull", remote_path, &local_path.to_string_lossy()]).await?;
-        
-        info!("ADB pull command completed");
-        info!("Exit status: {:?}", output.status);
-        info!("Stdout: {}", String::from_utf8_lossy(&output.stdout));
-        
-        if !output.stderr.is_empty() {
-            let stderr_str = String::from_utf8_lossy(&output.stderr);
-            info!("Stderr content: {}", stderr_str);
-        }
-        
-        if !output.status.success() {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
-            error!("ADB pull failed: {}", error_msg);
-            return Err(format!("ADB pull failed: {}", error_msg).into());
-        }
-    }
+    let strategy = pull_android_file(device_id, package_name, remote_path, admin_access, compress, &local_path).await?;
+    info!("Pulled with strategy: {}", strategy.label());
     
-    // Verify the file was created and has content
-    match fs::metadata(&local_path) {
-        Ok(metadata) => {
-            info!("File successfully created: {:?}", local_path);
-            info!("File size: {} bytes", metadata.len());
+    // Log whether the file looks like SQLite (first 16 bytes are the header)
+    if let Ok(mut file) = fs::File::open(&local_path) {
+        use std::io::Read;
+        let mut header = [0u8; 16];
+        if file.read_exact(&mut header).is_ok() {
+            let header_str = String::from_utf8_lossy(&header[..15]); // First 15 bytes
+            info!("File header: {:?}", header_str);
             
-            if metadata.len() == 0 {
-                error!("Created file is empty!");
-                return Err("Pulled database file is empty".into());
-            }
-            
-            // Check if it looks like a SQLite file (first 16 bytes should be SQLite header)
-            if metadata.len() >= 16 {
-                match fs::File::open(&local_path) {
-                    Ok(mut file) => {
-                        use std::io::Read;
-                        let mut header = [0u8; 16];
-                        if let Ok(_) = file.read_exact(&mut header) {
-                            let header_str = String::from_utf8_lossy(&header[..15]); // First 15 bytes
-                            info!("File header: {:?}", header_str);
-                            
-                            if header_str.starts_with("SQLite format") {
-                                info!("✅ File appears to be a valid SQLite database");
-                            } else {
-                                info!("⚠️  File does not appear to be a SQLite database");
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        error!("Could not read file header: {}", e);
-                    }
-                }
+            if header_str.starts_with("SQLite format") {
+                info!("✅ File appears to be a valid SQLite database");
+            } else {
+                info!("⚠️  File does not appear to be a SQLite database");
             }
         }
-        Err(e) => {
-            error!("File was not created: {}", e);
-            return Err(format!("File was not created: {}", e).into());
-        }
     }
     
+    // run-as pushes recreate the file, so remember its mode and owner for restoring them
+    let remote_permissions = if strategy == PullStrategy::RunAs {
+        read_remote_permissions(device_id, package_name, remote_path).await
+    } else {
+        None
+    };
+
     // Store metadata
     let metadata = DatabaseFileMetadata {
         device_id: device_id.to_string(),
         package_name: package_name.to_string(),
         remote_path: remote_path.to_string(),
         timestamp: chrono::Utc::now().to_rfc3339(),
+        sha256: compute_file_sha256(&local_path).ok(),
+        remote_permissions,
+        integrity: None,
+        pull_strategy: Some(strategy),
     };
     
     let metadata_path = format!("{}.meta.json", local_path.display());
     let metadata_json = serde_json::to_string_pretty(&metadata)?;
     fs::write(&metadata_path, metadata_json)?;
     info!("Metadata written to: {}", metadata_path);
+    if let Err(e) = save_base_copy(&local_path) {
+        log::warn!("⚠️ Failed to keep a base copy for merges: {}", e);
+    }
+    spawn_pull_integrity_check(&local_path);
     
     info!("=== pull_android_db_file completed successfully ===");
     Ok(local_path.to_string_lossy().to_string())
@@ -404,11 +620,12 @@ async fn push_android_db_file(
     local_path: &str,
     package_name: &str,
     remote_path: &str,
+    user_id: Option<u32>,
+    progress: Option<ProgressCallback<'_>>,
 ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
     let filename = Path::new(local_path).file_name()
         .ok_or("Invalid local path")?
         .to_string_lossy();
-    let tmp_path = format!("/data/local/tmp/{}", filename);
     
     info!("=== Starting push_android_db_file ===");
     info!("Device ID: {}", device_id);
@@ -419,114 +636,162 @@ async fn push_android_db_file(
 
     prepare_sqlite_file_for_sync(local_path)
         .map_err(|e| format!("Failed to prepare SQLite file for sync: {}", e))?;
+    check_push_integrity(local_path)?;
     
-    // Check if remote path is on external storage (sdcard)
-    if remote_path.contains("sdcard") || remote_path.contains("external") {
-        // Direct push to external storage
-        info!("Pushing directly to external storage");
-        
-        let output = execute_adb_command(&["-s", device_id, "push", local_path, remote_path]).await?;
-        
-        if !output.status.success() {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("ADB direct push failed: {}", error_msg).into());
-        }
-    } else {
-        // Push to tmp directory first
-        info!("Pushing to tmp directory first");
-        
-        let output = execute_adb_command(&["-s", device_id, "push", local_path, &tmp_path]).await?;
-        
-        if !output.status.success() {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("ADB push to tmp failed: {}", error_msg).into());
-        }
-        
-        // Copy from tmp to app's data directory using run-as
-        info!("Copying from tmp to app data directory");
-        
-        let output = execute_adb_command(&["-s", device_id, "shell", "run-as", package_name, "cp", &tmp_path, remote_path]).await?;
-        
-        if !output.status.success() {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("ADB copy from tmp failed: {}", error_msg).into());
+    let strategy = push_with_strategy(device_id, local_path, package_name, remote_path, user_id, progress).await?;
+    info!("Pushed using {:?}", strategy);
+
+    if strategy == PushStrategy::RunAsCopy {
+        let permissions = read_pulled_file_metadata(Path::new(local_path)).and_then(|metadata| metadata.remote_permissions);
+        if let Some(permissions) = permissions {
+            if let Err(e) = restore_remote_permissions(device_id, package_name, remote_path, user_id, &permissions).await {
+                log::warn!("⚠️ Pushed, but could not restore mode {} on {}: {}", permissions.mode, remote_path, e);
+            }
         }
-        
-        // Clean up temp file on device
-        let _ = execute_adb_command(&["-s", device_id, "shell", "rm", &tmp_path]).await;
+    }
+    
+    if let Err(e) = refresh_metadata_hash(Path::new(local_path)) {
+        log::warn!("⚠️ Failed to update pull hash after push: {}", e);
     }
     
     info!("=== push_android_db_file completed successfully ===");
     Ok(format!("Database successfully pushed to {}", remote_path))
 }
 
-#[tauri::command]
-pub async fn adb_get_devices(_app_handle: tauri::AppHandle) -> Result<DeviceResponse<Vec<Device>>, String> {
-    log::info!("Getting Android devices");
+// Properties read in one `adb shell` round trip, printed one per line in this order
+const BUILD_PROPS_COMMAND: &str =
+    "getprop ro.build.version.sdk; getprop ro.product.cpu.abi; getprop ro.build.fingerprint";
+
+/// API level, primary ABI and build fingerprint from `BUILD_PROPS_COMMAND` output.
+fn parse_build_props(output: &str) -> (Option<u32>, Option<String>, Option<String>) {
+    let mut lines = output.lines().map(str::trim);
+    let mut next_value = || lines.next().filter(|value| !value.is_empty()).map(str::to_string);
+    let api_level = next_value().and_then(|value| value.parse().ok());
+    let abi = next_value();
+    let build_fingerprint = next_value();
+    (api_level, abi, build_fingerprint)
+}
 
-    Ok(
-        adb_get_devices_with(|args| async move {
-            let arg_refs: Vec<&str> = args.iter().map(String::as_str).collect();
-            execute_adb_command(&arg_refs).await
-        })
-        .await,
-    )
+async fn fill_build_props(device: &mut Device) {
+    match execute_adb_command(&["-s", &device.id, "shell", BUILD_PROPS_COMMAND]).await {
+        Ok(output) if output.status.success() => {
+            let (api_level, abi, build_fingerprint) = parse_build_props(&String::from_utf8_lossy(&output.stdout));
+            device.api_level = api_level;
+            device.abi = abi;
+            device.build_fingerprint = build_fingerprint;
+        }
+        Ok(output) => log::warn!(
+            "⚠️ Could not read build properties for {}: {}",
+            device.id,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ),
+        Err(e) => log::warn!("⚠️ Could not read build properties for {}: {}", device.id, e),
+    }
 }
 
-#[tauri::command]
-pub async fn adb_get_packages(_app_handle: tauri::AppHandle, device_id: String) -> Result<DeviceResponse<Vec<Package>>, String> {
-    log::info!("Getting packages for device: {}", device_id);
+pub async fn list_android_devices() -> DeviceResponse<Vec<Device>> {
+    let mut response = adb_get_devices_with(|args| async move {
+        let arg_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+        execute_adb_command(&arg_refs).await
+    })
+    .await;
+
+    if let Some(devices) = response.data.as_mut() {
+        for device in devices.iter_mut().filter(|device| device.state.as_deref() == Some("device")) {
+            fill_build_props(device).await;
+        }
+        let connected: Vec<&str> = devices
+            .iter()
+            .filter(|device| device.state.as_deref() == Some("device"))
+            .map(|device| device.id.as_str())
+            .collect();
+        forget_disconnected_capabilities("android", &connected);
+    }
+    response
+}
 
-    Ok(
-        adb_get_packages_with(&device_id, |args| async move {
-            let arg_refs: Vec<&str> = args.iter().map(String::as_str).collect();
-            execute_adb_command(&arg_refs).await
+pub async fn list_android_packages(device_id: &str, user_id: Option<u32>) -> DeviceResponse<Vec<Package>> {
+    adb_get_packages_with(device_id, user_id, |args| async move {
+        let arg_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+        execute_adb_command(&arg_refs).await
+    })
+    .await
+}
+
+/// Connected Android devices, pinned ones first; cached briefly unless `force_refresh` is set
+#[tauri::command]
+pub async fn adb_get_devices(
+    app_handle: tauri::AppHandle,
+    force_refresh: Option<bool>,
+) -> Result<DeviceResponse<Vec<Device>>, String> {
+    DEVICE_SCANNER
+        .scan("adb:devices", DEVICE_LIST_TTL, force_refresh.unwrap_or(false), || async {
+            log::info!("Getting Android devices");
+            Ok(list_android_devices().await)
         })
-        .await,
-    )
+        .await
+        .map(|response| with_device_favorites(&app_handle, response))
 }
 
+/// Installed packages, pinned ones first; cached unless `force_refresh` is set
 #[tauri::command]
-pub async fn adb_get_android_database_files(
-    _app_handle: tauri::AppHandle,
+pub async fn adb_get_packages(
+    app_handle: tauri::AppHandle,
     device_id: String,
-    package_name: String,
-) -> Result<DeviceResponse<Vec<DatabaseFile>>, String> {
-    log::info!("Getting Android database files for device: {} package: {}", device_id, package_name);
-    
-    // Preserve active temp DB files so fast table selection does not race with
-    // a background Android rescan deleting the currently selected file.
-    if let Err(e) = clean_temp_dir() {
-        error!("Failed to clean temp directory: {}", e);
-        // Continue anyway, but log the error
-    } else {
-        info!("✅ Successfully cleaned old temp files before Android database pull");
-    }
-    
+    user_id: Option<u32>,
+    force_refresh: Option<bool>,
+) -> Result<DeviceResponse<Vec<Package>>, String> {
+    let key = format!("{}{}", packages_scan_key(&device_id), user_id.unwrap_or(0));
+    DEVICE_SCANNER
+        .scan(&key, PACKAGE_LIST_TTL, force_refresh.unwrap_or(false), || async {
+            log::info!("Getting packages for device: {} (user {:?})", device_id, user_id);
+            Ok(list_android_packages(&device_id, user_id).await)
+        })
+        .await
+        .map(|response| with_package_favorites(&app_handle, response))
+}
+
+/// Find SQLite databases for a package and pull each one into the temp directory.
+/// Files that fail to pull are listed with their remote path so the UI can show them.
+pub async fn pull_android_database_files(
+    device_id: &str,
+    package_name: &str,
+    user_id: Option<u32>,
+    compress: bool,
+) -> Vec<DatabaseFile> {
     let mut database_files = Vec::new();
 
-    let found_files = discover_android_database_candidates_with(&device_id, &package_name, |args| async move {
+    let found_files = discover_android_database_candidates_with(device_id, package_name, user_id, |args| async move {
         let arg_refs: Vec<&str> = args.iter().map(String::as_str).collect();
         execute_adb_command(&arg_refs).await
     })
     .await;
 
     for (file_path, admin_access, location) in found_files {
-        match pull_android_db_file(&device_id, &package_name, &file_path, admin_access).await {
+        // Files pulled so far are complete; the caller reports the cancellation
+        if check_cancelled().is_err() {
+            break;
+        }
+        match pull_android_db_file(device_id, package_name, &file_path, admin_access, compress).await {
             Ok(local_path) => {
                 let filename = std::path::Path::new(&file_path)
                     .file_name()
                     .and_then(|n| n.to_str())
                     .unwrap_or("unknown")
                     .to_string();
+                let integrity = pull_integrity_for(Path::new(&local_path));
+                let pull_strategy = pulled_with(Path::new(&local_path));
 
                 database_files.push(DatabaseFile {
                     path: local_path,
-                    package_name: package_name.clone(),
+                    package_name: package_name.to_string(),
+                    file_type: DatabaseFileType::from_path(&filename),
                     filename,
                     location,
                     remote_path: Some(file_path),
                     device_type: "android".to_string(),
+                    integrity: Some(integrity),
+                    pull_strategy,
                 });
             }
             Err(e) => {
@@ -539,46 +804,286 @@ pub async fn adb_get_android_database_files(
 
                 database_files.push(DatabaseFile {
                     path: file_path.clone(),
-                    package_name: package_name.clone(),
+                    package_name: package_name.to_string(),
+                    file_type: DatabaseFileType::from_path(&filename),
                     filename,
                     location,
                     remote_path: Some(file_path),
                     device_type: "android".to_string(),
+                    integrity: None,
+                    pull_strategy: None,
                 });
             }
         }
     }
+
+    database_files
+}
+
+// Pull a package's databases; `compress` gzips private files on the device to speed up slow links
+#[tauri::command]
+pub async fn adb_get_android_database_files(
+    _app_handle: tauri::AppHandle,
+    device_id: String,
+    package_name: String,
+    user_id: Option<u32>,
+    compress: Option<bool>,
+) -> Result<DeviceResponse<Vec<DatabaseFile>>, String> {
+    let operation = OPERATIONS.start(
+        OperationKind::Pull,
+        format!("Pull {} databases from {}", package_name, device_id),
+        Some(&device_id),
+    );
+    operation
+        .run(get_android_database_files(device_id, package_name, user_id, compress))
+        .await
+}
+
+async fn get_android_database_files(
+    device_id: String,
+    package_name: String,
+    user_id: Option<u32>,
+    compress: Option<bool>,
+) -> Result<DeviceResponse<Vec<DatabaseFile>>, String> {
+    log::info!("Getting Android database files for device: {} package: {} (user {:?})", device_id, package_name, user_id);
+    
+    // Preserve active temp DB files so fast table selection does not race with
+    // a background Android rescan deleting the currently selected file.
+    if let Err(e) = clean_temp_dir() {
+        error!("Failed to clean temp directory: {}", e);
+        // Continue anyway, but log the error
+    } else {
+        info!("✅ Successfully cleaned old temp files before Android database pull");
+    }
+    
+    let mut database_files = pull_android_database_files(&device_id, &package_name, user_id, compress.unwrap_or(false)).await;
+    check_cancelled()?;
+
+    // LevelDB stores are listed by their remote directory and pulled on demand
+    let leveldb_stores = super::leveldb::discover_android_leveldb_stores_with(&device_id, &package_name, |args| async move {
+        let arg_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+        execute_adb_command(&arg_refs).await
+    })
+    .await;
+
+    for (dir_path, _admin_access, location) in leveldb_stores {
+        let filename = std::path::Path::new(&dir_path)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("leveldb")
+            .to_string();
+
+        database_files.push(DatabaseFile {
+            path: dir_path.clone(),
+            package_name: package_name.clone(),
+            filename,
+            location,
+            remote_path: Some(dir_path),
+            device_type: "android".to_string(),
+            file_type: DatabaseFileType::LevelDb,
+            integrity: None,
+            pull_strategy: None,
+        });
+    }
     
     Ok(DeviceResponse {
         success: true,
         data: Some(database_files),
         error: None,
+        error_detail: None,
     })
 }
 
 
 
-// Push database file back to Android device
+// Push database file back to Android device; `verify` compares the device copy's hash afterwards
+// and `stop_app` force-stops the app for the push and relaunches it
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn adb_push_database_file(
+    app_handle: tauri::AppHandle,
     device_id: String,
     local_path: String,
     package_name: String,
     remote_path: String,
-) -> Result<DeviceResponse<String>, String> {
+    user_id: Option<u32>,
+    verify: Option<bool>,
+    stop_app: Option<bool>,
+) -> Result<DeviceResponse<PushedFile>, String> {
     log::info!("Pushing database file {} to Android device: {}", local_path, device_id);
-    
-    match push_android_db_file(&device_id, &local_path, &package_name, &remote_path).await {
-        Ok(message) => Ok(DeviceResponse {
-            success: true,
-            data: Some(message),
-            error: None,
-        }),
-        Err(e) => Ok(DeviceResponse {
+
+    if let Err(e) = check_dangerous_operation(DangerousOperation::PushDatabase) {
+        return Ok(DeviceResponse {
             success: false,
             data: None,
-            error: Some(format!("Failed to push database file: {}", e)),
+            error_detail: Some(e.device_error()),
+            error: Some(e),
+        });
+    }
+
+    let context = PushHookContext::new("android", &device_id, &package_name, &local_path, &remote_path);
+    let operation = OPERATIONS.start(
+        OperationKind::Push,
+        format!("Push {} to {}", remote_path, device_id),
+        Some(&device_id),
+    );
+    let emit_progress = |progress: TransferProgress| {
+        operation.set_progress(progress.bytes_transferred, progress.total_bytes);
+        let _ = app_handle.emit(PUSH_PROGRESS_EVENT, progress);
+    };
+    let push = async {
+        let response = with_push_hooks(context, async {
+            match push_android_db_file(&device_id, &local_path, &package_name, &remote_path, user_id, Some(&emit_progress)).await {
+                Ok(message) => Ok(DeviceResponse {
+                    success: true,
+                    data: Some(message),
+                    error: None,
+                    error_detail: None,
+                }),
+                Err(e) => Ok(DeviceResponse {
+                    success: false,
+                    data: None,
+                    error_detail: Some(e.device_error()),
+                    error: Some(format!("Failed to push database file: {}", e)),
+                })
+            }
         })
+        .await?;
+
+        Ok(with_push_verification(
+            &app_handle,
+            "android",
+            &device_id,
+            &package_name,
+            &remote_path,
+            &local_path,
+            verify.unwrap_or(false),
+            response,
+        )
+        .await)
+    };
+
+    let journal = begin_operation(JournalOperation::Push, "android", &device_id, &package_name, &local_path, &remote_path);
+    let response = operation
+        .run(with_app_stopped(
+            &app_handle,
+            "android",
+            &device_id,
+            &package_name,
+            &remote_path,
+            user_id,
+            stop_app.unwrap_or(false),
+            push,
+        ))
+        .await;
+    if matches!(&response, Ok(pushed) if pushed.success) {
+        journal.complete_push();
+        invalidate_cached_connection(&app_handle, &app_handle.state::<DbConnectionCache>(), &local_path, "pushed").await;
+    }
+    response
+}
+
+/// Arguments for `adb install`. `-r` keeps the app's data when replacing an installed build.
+fn adb_install_args(device_id: &str, apk_path: &str, allow_downgrade: bool, grant_permissions: bool) -> Vec<String> {
+    let mut args = vec!["-s".to_string(), device_id.to_string(), "install".to_string(), "-r".to_string()];
+    if allow_downgrade {
+        args.push("-d".to_string());
+    }
+    if grant_permissions {
+        args.push("-g".to_string());
+    }
+    args.push(apk_path.to_string());
+    args
+}
+
+/// `adb install` prints `Success`, or `Failure [INSTALL_FAILED_...: details]` (on stdout or
+/// stderr depending on the adb version) and may still exit 0.
+fn parse_adb_install_output(success: bool, stdout: &str, stderr: &str) -> Result<(), String> {
+    let combined = format!("{}\n{}", stdout, stderr);
+    if let Some(line) = combined.lines().map(str::trim).find(|line| line.starts_with("Failure")) {
+        let reason = line
+            .split_once('[')
+            .and_then(|(_, rest)| rest.rsplit_once(']'))
+            .map(|(reason, _)| reason.trim())
+            .unwrap_or(line);
+        return Err(match reason.split([':', ' ']).next().unwrap_or_default() {
+            "INSTALL_FAILED_VERSION_DOWNGRADE" => {
+                format!("{} - the device has a newer build; allow downgrade to replace it", reason)
+            }
+            "INSTALL_FAILED_UPDATE_INCOMPATIBLE" => format!(
+                "{} - the installed app is signed with a different key; uninstall it first",
+                reason
+            ),
+            "INSTALL_FAILED_NO_MATCHING_ABIS" => {
+                format!("{} - the APK has no native libraries for this device's CPU", reason)
+            }
+            "INSTALL_FAILED_INSUFFICIENT_STORAGE" => format!("{} - the device is out of storage", reason),
+            _ => reason.to_string(),
+        });
+    }
+
+    if success && combined.lines().any(|line| line.trim() == "Success") {
+        Ok(())
+    } else {
+        Err(format!("adb install failed: {}", combined.trim()))
+    }
+}
+
+// Install an APK on an Android device or emulator
+#[tauri::command]
+pub async fn adb_install_apk(
+    device_id: String,
+    apk_path: String,
+    allow_downgrade: Option<bool>,
+    grant_permissions: Option<bool>,
+) -> Result<DeviceResponse<String>, String> {
+    log::info!("📲 Installing {} on Android device: {}", apk_path, device_id);
+
+    if !Path::new(&apk_path).is_file() {
+        return Ok(DeviceResponse {
+            success: false,
+            data: None,
+            error_detail: Some(DeviceError::Unknown),
+            error: Some(format!("APK not found: {}", apk_path)),
+        });
+    }
+
+    let args = adb_install_args(
+        &device_id,
+        &apk_path,
+        allow_downgrade.unwrap_or(false),
+        grant_permissions.unwrap_or(false),
+    );
+    let arg_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+    let result = match execute_adb_command(&arg_refs).await {
+        Ok(output) => parse_adb_install_output(
+            output.status.success(),
+            &String::from_utf8_lossy(&output.stdout),
+            &String::from_utf8_lossy(&output.stderr),
+        ),
+        Err(e) => Err(format!("Failed to run adb install: {}", e)),
+    };
+
+    match result {
+        Ok(()) => {
+            log::info!("✅ Installed {} on {}", apk_path, device_id);
+            DEVICE_SCANNER.invalidate(&packages_scan_key(&device_id));
+            Ok(DeviceResponse {
+                success: true,
+                data: Some(format!("Installed {}", apk_path)),
+                error: None,
+                error_detail: None,
+            })
+        }
+        Err(e) => {
+            log::error!("❌ APK install failed: {}", e);
+            Ok(DeviceResponse {
+                success: false,
+                data: None,
+                error_detail: Some(e.device_error()),
+                error: Some(e),
+            })
+        }
     }
 }
 
@@ -670,6 +1175,7 @@ pub async fn adb_get_device_info(device_id: String) -> Result<DeviceResponse<std
                 success: true,
                 data: Some(info),
                 error: None,
+                error_detail: None,
             })
         },
         Err(e) => {
@@ -684,6 +1190,7 @@ pub async fn adb_get_device_info(device_id: String) -> Result<DeviceResponse<std
             Ok(DeviceResponse {
                 success: true,
                 data: Some(mock_info),
+                error_detail: Some(e.device_error()),
                 error: Some(format!("Using mock data - real command failed: {}", e)),
             })
         },
@@ -731,6 +1238,10 @@ mod tests {
             package_name: "com.example.app".to_string(),
             remote_path: "/data/data/com.example.app/databases/test.db".to_string(),
             timestamp: "2024-01-01T12:00:00Z".to_string(),
+            sha256: None,
+            remote_permissions: None,
+            integrity: None,
+            pull_strategy: None,
         };
         
         assert_eq!(metadata.device_id, "emulator-5554");
@@ -745,9 +1256,15 @@ mod tests {
             Device {
                 id: "emulator-5554".to_string(),
                 name: "Android Emulator".to_string(),
+                display_name: "Android Emulator".to_string(),
                 model: "Android SDK built for x86".to_string(),
                 device_type: "emulator".to_string(),
                 description: "Emulator device".to_string(),
+                api_level: None,
+                abi: None,
+                build_fingerprint: None,
+                state: None,
+                favorite: None,
             },
         ];
         
@@ -755,6 +1272,7 @@ mod tests {
             success: true,
             data: Some(devices),
             error: None,
+            error_detail: None,
         };
         
         assert!(response.success);
@@ -768,6 +1286,7 @@ mod tests {
         let response: DeviceResponse<Vec<Device>> = DeviceResponse {
             success: false,
             data: None,
+            error_detail: Some(DeviceError::ToolMissing { tool: Some("adb".to_string()) }),
             error: Some("ADB not found".to_string()),
         };
         
@@ -782,6 +1301,7 @@ mod tests {
         let package = Package {
             name: "Example App".to_string(),
             bundle_id: "com.example.app".to_string(),
+            favorite: None,
         };
         
         assert_eq!(package.name, "Example App");
@@ -797,6 +1317,9 @@ mod tests {
             location: "internal".to_string(),
             remote_path: Some("/data/data/com.example.app/databases/test.db".to_string()),
             device_type: "android".to_string(),
+            file_type: DatabaseFileType::Sqlite,
+            integrity: None,
+            pull_strategy: None,
         };
         
         assert_eq!(db_file.filename, "test.db");
@@ -848,9 +1371,15 @@ mod tests {
         let device = Device {
             id: "test123".to_string(),
             name: "Test Device".to_string(),
+            display_name: "Test Device".to_string(),
             model: "Test Model".to_string(),
             device_type: "android".to_string(),
             description: "Test Description".to_string(),
+            api_level: None,
+            abi: None,
+            build_fingerprint: None,
+            state: None,
+            favorite: None,
         };
         
         // Test serialization
@@ -871,6 +1400,7 @@ mod tests {
         let package = Package {
             name: "Test Package".to_string(),
             bundle_id: "com.test.package".to_string(),
+            favorite: None,
         };
         
         // Test serialization
@@ -894,6 +1424,9 @@ mod tests {
             location: "internal".to_string(),
             remote_path: Some("/remote/test.db".to_string()),
             device_type: "android".to_string(),
+            file_type: DatabaseFileType::Sqlite,
+            integrity: None,
+            pull_strategy: None,
         };
         
         // Test serialization
@@ -915,15 +1448,22 @@ mod tests {
         let devices = vec![Device {
             id: "test".to_string(),
             name: "Test".to_string(),
+            display_name: "Test".to_string(),
             model: "Model".to_string(),
             device_type: "android".to_string(),
             description: "Desc".to_string(),
+            api_level: None,
+            abi: None,
+            build_fingerprint: None,
+            state: None,
+            favorite: None,
         }];
         
         let response = DeviceResponse {
             success: true,
             data: Some(devices),
             error: None,
+            error_detail: None,
         };
         
         let json = serde_json::to_string(&response)?;
@@ -938,17 +1478,28 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_adb_devices_output_filters_offline_and_extracts_metadata() {
+    fn test_parse_adb_devices_output_reports_states_and_extracts_metadata() {
         let device_output = "\
 List of devices attached
 emulator-5554 device product:sdk_gphone64_arm64 model:sdk_gphone64_arm64 device:emu64a transport_id:1
 R5CW123ABC device usb:1-1 product:dm3q model:SM_S918B device:dm3q transport_id:2
 offline-device offline transport_id:3
+9A1B2C unauthorized usb:1-2 transport_id:4
+0123456789 no permissions (user in plugdev group; are your udev rules wrong?); see [http://developer.android.com/tools/device.html] usb:1-3 transport_id:5
+weird-device bootloader transport_id:6
 ";
 
         let devices = parse_adb_devices_output(device_output);
 
-        assert_eq!(devices.len(), 2);
+        assert_eq!(devices.len(), 5);
+        assert_eq!(devices[0].state.as_deref(), Some("device"));
+        assert_eq!(devices[2].id, "offline-device");
+        assert_eq!(devices[2].state.as_deref(), Some("offline"));
+        assert_eq!(devices[3].state.as_deref(), Some("unauthorized"));
+        assert!(devices[3].description.starts_with("Android device - unauthorized"));
+        assert_eq!(devices[4].id, "0123456789");
+        assert_eq!(devices[4].state.as_deref(), Some("no permissions"));
+
         assert_eq!(devices[0].id, "emulator-5554");
         assert_eq!(devices[0].name, "emu64a");
         assert_eq!(devices[0].model, "sdk_gphone64_arm64");
@@ -960,6 +1511,21 @@ offline-device offline transport_id:3
         assert_eq!(devices[1].description, "Android device");
     }
 
+    #[test]
+    fn test_parse_adb_devices_output_skips_daemon_notices_and_keeps_unicode() {
+        let output = decode_tool_output(
+            "* daemon not running; starting now at tcp:5037\r\n* daemon started successfully\r\nList of devices attached\r\nR5CW123ABC device usb:1-1 model:Galaxy_📱 device:機種\r\n"
+                .as_bytes(),
+        );
+
+        let devices = parse_adb_devices_output(&output);
+
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].id, "R5CW123ABC");
+        assert_eq!(devices[0].model, "Galaxy_📱");
+        assert_eq!(devices[0].name, "機種");
+    }
+
     #[tokio::test]
     #[cfg(unix)]
     async fn test_adb_get_devices_with_maps_successful_execution() {
@@ -1032,7 +1598,7 @@ package:
 
     #[test]
     fn test_adb_find_database_args_uses_run_as_for_private_storage() {
-        let args = adb_find_database_args("device-1", "com.example.app", "/data/data/", true);
+        let args = adb_find_database_args("device-1", "com.example.app", "/data/data/", true, None);
 
         assert_eq!(
             args,
@@ -1052,13 +1618,31 @@ package:
                 "-o",
                 "-name",
                 "*.sqlite3",
+                "-o",
+                "-name",
+                "*.realm",
             ]
         );
     }
 
+    #[test]
+    fn test_adb_find_database_args_targets_secondary_user() {
+        let locations = android_database_locations(Some(10));
+        assert_eq!(locations[0], ("/data/user/10/".to_string(), true));
+        assert_eq!(locations[1], ("/storage/emulated/10/Android/data/".to_string(), false));
+        assert_eq!(android_database_locations(Some(0)), android_database_locations(None));
+
+        let args = adb_find_database_args("device-1", "com.example.app", &locations[0].0, true, Some(10));
+        assert_eq!(
+            &args[..8],
+            ["-s", "device-1", "shell", "run-as", "--user", "10", "com.example.app", "find"]
+        );
+        assert_eq!(args[8], "/data/user/10/com.example.app/");
+    }
+
     #[test]
     fn test_adb_find_database_args_uses_plain_find_for_shared_storage() {
-        let args = adb_find_database_args("device-1", "com.example.app", "/sdcard/Android/data/", false);
+        let args = adb_find_database_args("device-1", "com.example.app", "/sdcard/Android/data/", false, None);
 
         assert_eq!(
             args,
@@ -1076,6 +1660,9 @@ package:
                 "-o",
                 "-name",
                 "*.sqlite3",
+                "-o",
+                "-name",
+                "*.realm",
             ]
         );
     }
@@ -1089,6 +1676,7 @@ package:
         let found = discover_android_database_candidates_with(
             "device-1",
             "com.example.app",
+            None,
             move |args| {
                 captured_calls.borrow_mut().push(args.clone());
                 async move {
@@ -1125,6 +1713,7 @@ package:
         let found = discover_android_database_candidates_with(
             "device-1",
             "com.example.app",
+            None,
             move |args| {
                 captured_calls.borrow_mut().push(args.clone());
                 async move {
@@ -1148,7 +1737,7 @@ package:
     #[tokio::test]
     #[cfg(unix)]
     async fn test_adb_get_packages_with_maps_successful_execution() {
-        let response = adb_get_packages_with("emulator-5554", |args| async move {
+        let response = adb_get_packages_with("emulator-5554", None, |args| async move {
             assert_eq!(
                 args,
                 vec![
@@ -1179,7 +1768,7 @@ package:
 
     #[tokio::test]
     async fn test_adb_get_packages_with_maps_launch_failure() {
-        let response = adb_get_packages_with("device-1", |_args| async move {
+        let response = adb_get_packages_with("device-1", None, |_args| async move {
             Err::<std::process::Output, _>("adb missing".into())
         })
         .await;
@@ -1198,7 +1787,7 @@ package:
     #[tokio::test]
     #[cfg(unix)]
     async fn test_adb_get_packages_with_maps_non_zero_exit_to_error() {
-        let response = adb_get_packages_with("device-1", |_args| async move {
+        let response = adb_get_packages_with("device-1", None, |_args| async move {
             Ok(fake_output(1, "", "permission denied"))
         })
         .await;
@@ -1236,6 +1825,58 @@ package:
         }
     }
 
+    #[test]
+    fn test_gunzip_to_file() {
+        use std::io::Write;
+        let temp_dir = TempDir::new().unwrap();
+        let local_path = temp_dir.path().join("app.db");
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(b"SQLite format 3\0").unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        assert_eq!(gunzip_to_file(&compressed, &local_path).unwrap(), 16);
+        assert_eq!(fs::read(&local_path).unwrap(), b"SQLite format 3\0");
+        // run-as errors arrive as plain text on stdout
+        let error = gunzip_to_file(b"run-as: package not debuggable: com.example.app", &local_path).unwrap_err();
+        assert!(error.contains("package not debuggable"));
+    }
+
+    #[test]
+    fn test_parse_build_props() {
+        let output = "34\narm64-v8a\ngoogle/sdk_gphone64_arm64/emu64a:14/UE1A.230829.036/10718183:userdebug/dev-keys\n";
+        let (api_level, abi, fingerprint) = parse_build_props(output);
+        assert_eq!(api_level, Some(34));
+        assert_eq!(abi.as_deref(), Some("arm64-v8a"));
+        assert!(fingerprint.unwrap().starts_with("google/sdk_gphone64_arm64"));
+
+        assert_eq!(parse_build_props("\n\n"), (None, None, None));
+        assert_eq!(parse_build_props("abc\nx86_64\n").0, None);
+    }
+
+    #[test]
+    fn test_adb_install_args_and_output() {
+        assert_eq!(
+            adb_install_args("emulator-5554", "/tmp/app.apk", true, false),
+            vec!["-s", "emulator-5554", "install", "-r", "-d", "/tmp/app.apk"]
+        );
+        assert_eq!(adb_install_args("abc", "a.apk", false, true).last().map(String::as_str), Some("a.apk"));
+
+        assert!(parse_adb_install_output(true, "Performing Streamed Install\nSuccess\n", "").is_ok());
+        let error = parse_adb_install_output(
+            true,
+            "",
+            "adb: failed to install a.apk: Failure [INSTALL_FAILED_VERSION_DOWNGRADE: Downgrade detected]",
+        )
+        .unwrap_err();
+        assert!(error.starts_with("INSTALL_FAILED_VERSION_DOWNGRADE: Downgrade detected"));
+        assert!(error.contains("allow downgrade"));
+        assert_eq!(
+            parse_adb_install_output(false, "Failure [INSTALL_PARSE_FAILED_NOT_APK]", "").unwrap_err(),
+            "INSTALL_PARSE_FAILED_NOT_APK"
+        );
+        assert!(parse_adb_install_output(false, "", "adb: device offline").is_err());
+    }
+
     #[test]
     fn test_temp_file_path_generation() {
         let temp_dir = TempDir::new().unwrap();
@@ -1266,9 +1907,15 @@ package:
         let empty_device = Device {
             id: "".to_string(),
             name: "Test".to_string(),
+            display_name: "Test".to_string(),
             model: "Test".to_string(),
             device_type: "android".to_string(),
             description: "Test".to_string(),
+            api_level: None,
+            abi: None,
+            build_fingerprint: None,
+            state: None,
+            favorite: None,
         };
         assert!(empty_device.id.is_empty());
         
@@ -1276,6 +1923,7 @@ package:
         let invalid_package = Package {
             name: "".to_string(),
             bundle_id: "invalid-bundle-id".to_string(),
+            favorite: None,
         };
         assert!(invalid_package.name.is_empty());
         
@@ -1287,6 +1935,9 @@ package:
             location: "unknown".to_string(),
             remote_path: None,
             device_type: "android".to_string(),
+            file_type: DatabaseFileType::Sqlite,
+            integrity: None,
+            pull_strategy: None,
         };
         assert!(invalid_db_file.path.is_empty());
         assert!(invalid_db_file.remote_path.is_none());
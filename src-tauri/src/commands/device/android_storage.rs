@@ -0,0 +1,526 @@
+//! Android storage push strategies
+//!
+//! Where a database lives decides how adb can write it back. App-private files need a
+//! `run-as` copy; on Android 11+ (API 30) scoped storage also hides other apps'
+//! `Android/data` folders from plain `adb push`, and shared media files are best rewritten
+//! through the MediaStore provider (`adb shell content write`) so the index stays consistent.
+//!
+//! Direct and run-as pushes write a temporary file next to the target and `mv` it into
+//! place, so an interrupted transfer never replaces the app's database with a partial file.
+//! The file created that way has run-as' default mode, so the mode and owner captured when
+//! the database was pulled are restored afterwards.
+
+use super::helpers::execute_adb_command;
+use super::transfer::{push_file_chunked, ProgressCallback, CHUNKED_PUSH_THRESHOLD};
+use crate::commands::common::commit_operation;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+
+/// First API level with scoped storage enforced (Android 11)
+pub const SCOPED_STORAGE_API_LEVEL: u32 = 30;
+
+// Device-side staging directory writable by the shell user and readable by run-as
+const STAGING_DIR: &str = "/data/local/tmp";
+
+// Suffix of the temporary file written next to the target before the rename
+const REMOTE_TEMP_SUFFIX: &str = ".flippio-tmp";
+
+const EXTERNAL_STORAGE_ROOTS: [&str; 4] = ["/sdcard/", "/storage/emulated/", "/storage/self/primary/", "/mnt/sdcard/"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AndroidStorageArea {
+    /// `/data/data/<pkg>` or `/data/user/<n>/<pkg>`
+    AppPrivate,
+    /// `<external>/Android/data/<pkg>` or `Android/media/<pkg>`
+    AppExternal,
+    /// Other external storage (Download, Documents, ...), indexed by MediaStore
+    SharedMedia,
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PushStrategy {
+    /// `adb push` straight to the target path
+    DirectPush,
+    /// Stage in /data/local/tmp, then `run-as <pkg> cp` into place
+    RunAsCopy,
+    /// Stage in /data/local/tmp, then `content write` through the MediaStore row
+    MediaStoreWrite,
+}
+
+/// Mode and ownership of a device file, as printed by `stat -c '%a %U %G'`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemotePermissions {
+    // Octal, e.g. "660"
+    pub mode: String,
+    pub owner: String,
+    pub group: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PushErrorKind {
+    PermissionDenied,
+    NotDebuggable,
+    UnknownPackage,
+    MissingDirectory,
+    StorageFull,
+    ReadOnly,
+    DeviceUnavailable,
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PushError {
+    pub kind: PushErrorKind,
+    pub strategy: PushStrategy,
+    pub details: String,
+}
+
+impl fmt::Display for PushError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hint = match self.kind {
+            PushErrorKind::PermissionDenied => {
+                "Permission denied - Android 11+ scoped storage blocks adb from writing this location"
+            }
+            PushErrorKind::NotDebuggable => "The app is not debuggable, so run-as cannot write its private files",
+            PushErrorKind::UnknownPackage => "The package is not installed on the device",
+            PushErrorKind::MissingDirectory => "The target directory does not exist on the device",
+            PushErrorKind::StorageFull => "The device is out of storage",
+            PushErrorKind::ReadOnly => "The target location is read-only",
+            PushErrorKind::DeviceUnavailable => "The device is offline or disconnected",
+            PushErrorKind::Unknown => "Push failed",
+        };
+        write!(f, "{} ({:?}): {}", hint, self.strategy, self.details)
+    }
+}
+
+impl std::error::Error for PushError {}
+
+/// Map adb/shell error output to a `PushErrorKind`.
+pub fn classify_push_error(output: &str) -> PushErrorKind {
+    let output = output.to_lowercase();
+    if output.contains("not debuggable") {
+        PushErrorKind::NotDebuggable
+    } else if output.contains("unknown package") || output.contains("package not found") {
+        PushErrorKind::UnknownPackage
+    } else if output.contains("permission denied") || output.contains("operation not permitted") {
+        PushErrorKind::PermissionDenied
+    } else if output.contains("no space left") {
+        PushErrorKind::StorageFull
+    } else if output.contains("read-only file system") {
+        PushErrorKind::ReadOnly
+    } else if output.contains("no such file or directory") {
+        PushErrorKind::MissingDirectory
+    } else if output.contains("device offline") || output.contains("no devices") || output.contains("not found") {
+        PushErrorKind::DeviceUnavailable
+    } else {
+        PushErrorKind::Unknown
+    }
+}
+
+fn strip_external_root(remote_path: &str) -> Option<&str> {
+    EXTERNAL_STORAGE_ROOTS.iter().find_map(|root| {
+        let rest = remote_path.strip_prefix(root)?;
+        // /storage/emulated/<user>/...
+        if *root == "/storage/emulated/" {
+            rest.split_once('/').map(|(_, rest)| rest)
+        } else {
+            Some(rest)
+        }
+    })
+}
+
+pub fn classify_storage_area(remote_path: &str, package_name: &str) -> AndroidStorageArea {
+    let private_prefix = format!("/data/data/{}/", package_name);
+    let is_user_private = remote_path
+        .strip_prefix("/data/user/")
+        .and_then(|rest| rest.split_once('/'))
+        .is_some_and(|(_, rest)| rest.starts_with(&format!("{}/", package_name)));
+    if remote_path.starts_with(&private_prefix) || is_user_private {
+        return AndroidStorageArea::AppPrivate;
+    }
+
+    match strip_external_root(remote_path) {
+        Some(relative) if relative.starts_with("Android/data/") || relative.starts_with("Android/media/") => {
+            AndroidStorageArea::AppExternal
+        }
+        Some(_) => AndroidStorageArea::SharedMedia,
+        None => AndroidStorageArea::Other,
+    }
+}
+
+/// Pick how to write `area` on a device running `api_level`.
+pub fn choose_push_strategy(area: AndroidStorageArea, api_level: u32) -> PushStrategy {
+    let scoped = api_level >= SCOPED_STORAGE_API_LEVEL;
+    match area {
+        AndroidStorageArea::AppPrivate => PushStrategy::RunAsCopy,
+        AndroidStorageArea::AppExternal if scoped => PushStrategy::RunAsCopy,
+        AndroidStorageArea::SharedMedia if scoped => PushStrategy::MediaStoreWrite,
+        _ => PushStrategy::DirectPush,
+    }
+}
+
+/// `ro.build.version.sdk`, assuming scoped storage when it cannot be read.
+pub async fn android_api_level(device_id: &str) -> u32 {
+    match execute_adb_command(&["-s", device_id, "shell", "getprop", "ro.build.version.sdk"]).await {
+        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .parse()
+            .unwrap_or(SCOPED_STORAGE_API_LEVEL),
+        _ => {
+            warn!("⚠️ Could not read API level for {}, assuming scoped storage", device_id);
+            SCOPED_STORAGE_API_LEVEL
+        }
+    }
+}
+
+/// Android user (profile) a device path belongs to: `/data/user/<n>/` or `/storage/emulated/<n>/`.
+/// `/data/data` and `/sdcard` are the primary user's views.
+pub fn android_user_from_path(remote_path: &str) -> Option<u32> {
+    ["/data/user/", "/storage/emulated/"].iter().find_map(|prefix| {
+        remote_path
+            .strip_prefix(prefix)?
+            .split('/')
+            .next()?
+            .parse()
+            .ok()
+    })
+}
+
+/// `run-as` prefix for `package_name`; secondary users and work profiles need `--user`.
+pub fn run_as_args(package_name: &str, user_id: Option<u32>) -> Vec<String> {
+    let mut args = vec!["run-as".to_string()];
+    if let Some(user_id) = user_id.filter(|user_id| *user_id != 0) {
+        args.push("--user".to_string());
+        args.push(user_id.to_string());
+    }
+    args.push(package_name.to_string());
+    args
+}
+
+/// `adb` arguments running `command` as `package_name` on `device_id`.
+pub fn run_as_command_args(device_id: &str, package_name: &str, user_id: Option<u32>, command: &[&str]) -> Vec<String> {
+    let mut args = vec!["-s".to_string(), device_id.to_string(), "shell".to_string()];
+    args.extend(run_as_args(package_name, user_id));
+    args.extend(command.iter().map(|arg| arg.to_string()));
+    args
+}
+
+/// Temporary name for a push to `remote_path`, in the same directory so `mv` is a rename.
+fn remote_temp_path(remote_path: &str) -> String {
+    format!("{}{}", remote_path, REMOTE_TEMP_SUFFIX)
+}
+
+/// Quote a value for the device shell.
+pub(crate) fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// First `_id` from `content query` output such as `Row: 0 _id=1234`.
+fn parse_media_store_id(output: &str) -> Option<u64> {
+    output
+        .lines()
+        .filter_map(|line| line.split_once("_id="))
+        .find_map(|(_, rest)| rest.split([',', ' ']).next()?.trim().parse().ok())
+}
+
+async fn run_adb(args: &[&str], strategy: PushStrategy) -> Result<String, PushError> {
+    let output = execute_adb_command(args).await.map_err(|e| PushError {
+        kind: PushErrorKind::DeviceUnavailable,
+        strategy,
+        details: e.to_string(),
+    })?;
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    // `adb shell` only propagates exit codes on newer devices, so also check the output
+    let kind = classify_push_error(&format!("{}\n{}", stdout, stderr));
+    if !output.status.success() || kind != PushErrorKind::Unknown {
+        let details = if stderr.trim().is_empty() { stdout.trim().to_string() } else { stderr.trim().to_string() };
+        return Err(PushError { kind, strategy, details });
+    }
+    Ok(stdout)
+}
+
+/// Copy `local_path` to `device_path` as the shell user, in resumable chunks for large files.
+/// A failed chunked transfer leaves its partial file for the next attempt to continue.
+async fn transfer_to_device(
+    device_id: &str,
+    local_path: &str,
+    device_path: &str,
+    strategy: PushStrategy,
+    progress: Option<ProgressCallback<'_>>,
+) -> Result<(), PushError> {
+    let size = std::fs::metadata(local_path).map(|metadata| metadata.len()).unwrap_or(0);
+    if size < CHUNKED_PUSH_THRESHOLD {
+        let result = run_adb(&["-s", device_id, "push", local_path, device_path], strategy).await;
+        if result.is_err() {
+            let _ = execute_adb_command(&["-s", device_id, "shell", "rm", "-f", device_path]).await;
+        }
+        return result.map(|_| ());
+    }
+
+    push_file_chunked(device_id, local_path, device_path, progress)
+        .await
+        .map_err(|details| PushError {
+            kind: classify_push_error(&details),
+            strategy,
+            details,
+        })
+}
+
+/// Past the upload to a temp path, the push replaces the device file and is no longer
+/// cancellable; a cancellation up to here leaves the device file as it was.
+fn commit_push(strategy: PushStrategy) -> Result<(), PushError> {
+    commit_operation().map_err(|details| PushError {
+        kind: PushErrorKind::Unknown,
+        strategy,
+        details,
+    })
+}
+
+/// Push `local_path` to `remote_path` using the strategy for its storage area and API level.
+pub async fn push_with_strategy(
+    device_id: &str,
+    local_path: &str,
+    package_name: &str,
+    remote_path: &str,
+    user_id: Option<u32>,
+    progress: Option<ProgressCallback<'_>>,
+) -> Result<PushStrategy, PushError> {
+    let area = classify_storage_area(remote_path, package_name);
+    let api_level = android_api_level(device_id).await;
+    let strategy = choose_push_strategy(area, api_level);
+    info!("📦 Push strategy for {} (API {}, {:?}): {:?}", remote_path, api_level, area, strategy);
+
+    let temp_path = remote_temp_path(remote_path);
+    if strategy == PushStrategy::DirectPush {
+        transfer_to_device(device_id, local_path, &temp_path, strategy, progress).await?;
+        if let Err(e) = commit_push(strategy) {
+            let _ = execute_adb_command(&["-s", device_id, "shell", "rm", "-f", &temp_path]).await;
+            return Err(e);
+        }
+        let result = run_adb(&["-s", device_id, "shell", "mv", "-f", &temp_path, remote_path], strategy).await;
+        if result.is_err() {
+            let _ = execute_adb_command(&["-s", device_id, "shell", "rm", "-f", &temp_path]).await;
+        }
+        return result.map(|_| strategy);
+    }
+
+    let filename = Path::new(local_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "flippio_push.db".to_string());
+    let staged_path = format!("{}/{}", STAGING_DIR, filename);
+    transfer_to_device(device_id, local_path, &staged_path, strategy, progress).await?;
+
+    let result = match commit_push(strategy) {
+        Err(e) => Err(e),
+        Ok(()) if strategy == PushStrategy::RunAsCopy => {
+            let user_id = user_id.or_else(|| android_user_from_path(remote_path));
+            let run_as = |command: &[&str]| run_as_command_args(device_id, package_name, user_id, command);
+            let copy = run_as(&["cp", &staged_path, &temp_path]);
+            let rename = run_as(&["mv", "-f", &temp_path, remote_path]);
+            let copy_refs: Vec<&str> = copy.iter().map(String::as_str).collect();
+            let rename_refs: Vec<&str> = rename.iter().map(String::as_str).collect();
+
+            let result = match run_adb(&copy_refs, strategy).await {
+                Ok(_) => run_adb(&rename_refs, strategy).await.map(|_| ()),
+                Err(e) => Err(e),
+            };
+            if result.is_err() {
+                let cleanup = run_as(&["rm", "-f", &temp_path]);
+                let cleanup_refs: Vec<&str> = cleanup.iter().map(String::as_str).collect();
+                let _ = execute_adb_command(&cleanup_refs).await;
+            }
+            result
+        }
+        Ok(()) => media_store_write(device_id, &staged_path, remote_path).await,
+    };
+
+    let _ = execute_adb_command(&["-s", device_id, "shell", "rm", "-f", &staged_path]).await;
+    result.map(|_| strategy)
+}
+
+/// Parse `stat -c '%a %U %G'` output such as `660 u0_a123 u0_a123`.
+fn parse_stat_permissions(stdout: &str) -> Option<RemotePermissions> {
+    let mut fields = stdout.lines().next()?.split_whitespace();
+    let mode = fields.next()?;
+    if !(3..=4).contains(&mode.len()) || !mode.chars().all(|c| ('0'..='7').contains(&c)) {
+        return None;
+    }
+    Some(RemotePermissions {
+        mode: mode.to_string(),
+        owner: fields.next()?.to_string(),
+        group: fields.next()?.to_string(),
+    })
+}
+
+/// Mode and owner of an app-private file, read as the app; `None` when they cannot be read.
+pub async fn read_remote_permissions(device_id: &str, package_name: &str, remote_path: &str) -> Option<RemotePermissions> {
+    let format = shell_quote("%a %U %G");
+    let args = run_as_command_args(
+        device_id,
+        package_name,
+        android_user_from_path(remote_path),
+        &["stat", "-c", &format, remote_path],
+    );
+    let arg_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+    match execute_adb_command(&arg_refs).await {
+        Ok(output) if output.status.success() => parse_stat_permissions(&String::from_utf8_lossy(&output.stdout)),
+        Ok(output) => {
+            warn!("⚠️ Could not read permissions of {}: {}", remote_path, String::from_utf8_lossy(&output.stderr).trim());
+            None
+        }
+        Err(e) => {
+            warn!("⚠️ Could not read permissions of {}: {}", remote_path, e);
+            None
+        }
+    }
+}
+
+/// Put back the mode and owner captured at pull time. The app user can only give files to
+/// itself, so a failed `chown` is logged rather than treated as a failure.
+pub async fn restore_remote_permissions(
+    device_id: &str,
+    package_name: &str,
+    remote_path: &str,
+    user_id: Option<u32>,
+    permissions: &RemotePermissions,
+) -> Result<(), PushError> {
+    let strategy = PushStrategy::RunAsCopy;
+    let user_id = user_id.or_else(|| android_user_from_path(remote_path));
+    let chmod = run_as_command_args(device_id, package_name, user_id, &["chmod", &permissions.mode, remote_path]);
+    let chmod_refs: Vec<&str> = chmod.iter().map(String::as_str).collect();
+    run_adb(&chmod_refs, strategy).await?;
+
+    let owner = format!("{}:{}", permissions.owner, permissions.group);
+    let chown = run_as_command_args(device_id, package_name, user_id, &["chown", &owner, remote_path]);
+    let chown_refs: Vec<&str> = chown.iter().map(String::as_str).collect();
+    if let Err(e) = run_adb(&chown_refs, strategy).await {
+        warn!("⚠️ Could not restore owner {} of {}: {}", owner, remote_path, e);
+    }
+    info!("🔐 Restored mode {} on {}", permissions.mode, remote_path);
+    Ok(())
+}
+
+/// Rewrite a shared file through its MediaStore row. Files MediaStore does not know are
+/// copied in place by the shell user and then scanned so they get indexed.
+async fn media_store_write(device_id: &str, staged_path: &str, remote_path: &str) -> Result<(), PushError> {
+    let strategy = PushStrategy::MediaStoreWrite;
+    let where_clause = shell_quote(&format!("_data='{}'", remote_path.replace('\'', "''")));
+    let query = run_adb(
+        &[
+            "-s",
+            device_id,
+            "shell",
+            "content",
+            "query",
+            "--uri",
+            "content://media/external/file",
+            "--projection",
+            "_id",
+            "--where",
+            &where_clause,
+        ],
+        strategy,
+    )
+    .await?;
+
+    match parse_media_store_id(&query) {
+        Some(id) => {
+            let command = format!(
+                "content write --uri content://media/external/file/{} < {}",
+                id,
+                shell_quote(staged_path)
+            );
+            run_adb(&["-s", device_id, "shell", &command], strategy).await?;
+        }
+        None => {
+            info!("{} is not in MediaStore, copying it in place", remote_path);
+            run_adb(&["-s", device_id, "shell", "cp", staged_path, remote_path], strategy).await?;
+            let uri = format!("file://{}", remote_path);
+            let _ = execute_adb_command(&[
+                "-s",
+                device_id,
+                "shell",
+                "am",
+                "broadcast",
+                "-a",
+                "android.intent.action.MEDIA_SCANNER_SCAN_FILE",
+                "-d",
+                &uri,
+            ])
+            .await;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_storage_area_and_strategy() {
+        let package = "com.example.app";
+        let cases = [
+            ("/data/data/com.example.app/databases/app.db", AndroidStorageArea::AppPrivate),
+            ("/data/user/10/com.example.app/databases/app.db", AndroidStorageArea::AppPrivate),
+            ("/sdcard/Android/data/com.example.app/files/app.db", AndroidStorageArea::AppExternal),
+            ("/storage/emulated/0/Android/data/com.example.app/files/app.db", AndroidStorageArea::AppExternal),
+            ("/storage/emulated/0/Download/app.db", AndroidStorageArea::SharedMedia),
+            ("/sdcard/Documents/app.db", AndroidStorageArea::SharedMedia),
+            ("/data/local/tmp/app.db", AndroidStorageArea::Other),
+        ];
+        for (path, area) in cases {
+            assert_eq!(classify_storage_area(path, package), area, "{}", path);
+        }
+
+        assert_eq!(choose_push_strategy(AndroidStorageArea::AppPrivate, 24), PushStrategy::RunAsCopy);
+        assert_eq!(choose_push_strategy(AndroidStorageArea::AppExternal, 29), PushStrategy::DirectPush);
+        assert_eq!(choose_push_strategy(AndroidStorageArea::AppExternal, 33), PushStrategy::RunAsCopy);
+        assert_eq!(choose_push_strategy(AndroidStorageArea::SharedMedia, 29), PushStrategy::DirectPush);
+        assert_eq!(choose_push_strategy(AndroidStorageArea::SharedMedia, 30), PushStrategy::MediaStoreWrite);
+        assert_eq!(choose_push_strategy(AndroidStorageArea::Other, 34), PushStrategy::DirectPush);
+    }
+
+    #[test]
+    fn test_classify_push_error_and_media_store_id() {
+        assert_eq!(
+            classify_push_error("adb: error: failed to copy 'a.db' to '/sdcard/Android/data/x/a.db': remote Permission denied"),
+            PushErrorKind::PermissionDenied
+        );
+        assert_eq!(classify_push_error("run-as: package not debuggable: com.x"), PushErrorKind::NotDebuggable);
+        assert_eq!(classify_push_error("run-as: unknown package: com.x"), PushErrorKind::UnknownPackage);
+        assert_eq!(classify_push_error("cp: /data/data/x/db/a.db: No such file or directory"), PushErrorKind::MissingDirectory);
+        assert_eq!(classify_push_error("adb: error: device offline"), PushErrorKind::DeviceUnavailable);
+        assert_eq!(classify_push_error("1 file pushed"), PushErrorKind::Unknown);
+
+        assert_eq!(parse_media_store_id("Row: 0 _id=1234\n"), Some(1234));
+        assert_eq!(android_user_from_path("/data/user/10/com.x/databases/a.db"), Some(10));
+        assert_eq!(android_user_from_path("/storage/emulated/0/Download/a.db"), Some(0));
+        assert_eq!(android_user_from_path("/data/data/com.x/databases/a.db"), None);
+        assert_eq!(run_as_args("com.x", Some(10)), vec!["run-as", "--user", "10", "com.x"]);
+        assert_eq!(run_as_args("com.x", Some(0)), vec!["run-as", "com.x"]);
+        assert_eq!(parse_media_store_id("No result found.\n"), None);
+        assert_eq!(shell_quote("it's.db"), "'it'\\''s.db'");
+        assert_eq!(
+            parse_stat_permissions("660 u0_a123 u0_a123\n"),
+            Some(RemotePermissions {
+                mode: "660".to_string(),
+                owner: "u0_a123".to_string(),
+                group: "u0_a123".to_string(),
+            })
+        );
+        assert_eq!(parse_stat_permissions("stat: '/data/data/x/a.db': No such file or directory"), None);
+        assert_eq!(
+            remote_temp_path("/data/data/com.x/databases/a.db"),
+            "/data/data/com.x/databases/a.db.flippio-tmp"
+        );
+    }
+}
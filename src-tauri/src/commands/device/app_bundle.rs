@@ -0,0 +1,319 @@
+//! App bundle inspection
+//!
+//! Apps often ship prepopulated databases as assets (Android `assets/`, iOS `Payload/*.app/`).
+//! `inspect_app_bundle` extracts them from an .apk or .ipa and lists them like databases
+//! found on a device, so they can be opened without installing the app.
+
+use super::helpers::ensure_temp_dir;
+use super::types::{DatabaseFile, DatabaseFileType, DeviceResponse};
+use super::errors::CommandErrorExt;
+use crate::commands::database::archives::extract_zip_databases;
+use log::{error, info};
+use std::io::Read;
+use std::path::Path;
+
+const RES_XML_START_ELEMENT: u16 = 0x0102;
+const RES_STRING_POOL: u16 = 0x0001;
+const UTF8_FLAG: u32 = 0x100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BundleKind {
+    Apk,
+    Ipa,
+}
+
+impl BundleKind {
+    fn from_path(path: &str) -> Option<Self> {
+        match Path::new(path).extension()?.to_str()?.to_lowercase().as_str() {
+            "apk" => Some(Self::Apk),
+            "ipa" => Some(Self::Ipa),
+            _ => None,
+        }
+    }
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    bytes.get(offset..offset + 2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    bytes.get(offset..offset + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// A one or two byte length prefix of a UTF-8 pool string, and the position after it.
+fn utf8_pool_length(bytes: &[u8], position: usize) -> Option<(usize, usize)> {
+    let first = *bytes.get(position)? as usize;
+    if first & 0x80 != 0 {
+        Some((((first & 0x7f) << 8) | *bytes.get(position + 1)? as usize, position + 2))
+    } else {
+        Some((first, position + 1))
+    }
+}
+
+/// Decode one entry of a binary XML string pool.
+fn pool_string(bytes: &[u8], pool_start: usize, index: u32) -> Option<String> {
+    let count = read_u32(bytes, pool_start + 8)?;
+    if index >= count {
+        return None;
+    }
+    let flags = read_u32(bytes, pool_start + 16)?;
+    let strings_start = read_u32(bytes, pool_start + 20)? as usize;
+    let header_size = read_u16(bytes, pool_start + 2)? as usize;
+    let offset = read_u32(bytes, pool_start + header_size + index as usize * 4)? as usize;
+    let mut position = pool_start + strings_start + offset;
+
+    if flags & UTF8_FLAG != 0 {
+        // The UTF-16 length comes first and is skipped; the UTF-8 byte length follows
+        let (_, next) = utf8_pool_length(bytes, position)?;
+        let (length, next) = utf8_pool_length(bytes, next)?;
+        return bytes
+            .get(next..next + length)
+            .map(|text| String::from_utf8_lossy(text).into_owned());
+    }
+
+    let mut length = read_u16(bytes, position)? as usize;
+    position += 2;
+    if length & 0x8000 != 0 {
+        length = ((length & 0x7fff) << 16) | read_u16(bytes, position)? as usize;
+        position += 2;
+    }
+    let units: Option<Vec<u16>> = (0..length).map(|i| read_u16(bytes, position + i * 2)).collect();
+    Some(String::from_utf16_lossy(&units?))
+}
+
+/// Read the `package` attribute of the `<manifest>` element from a compiled AndroidManifest.xml.
+pub fn apk_package_name(manifest: &[u8]) -> Option<String> {
+    let mut pool_start = None;
+    let mut position = read_u16(manifest, 2)? as usize;
+
+    while position + 8 <= manifest.len() {
+        let chunk_type = read_u16(manifest, position)?;
+        let header_size = read_u16(manifest, position + 2)? as usize;
+        let chunk_size = read_u32(manifest, position + 4)? as usize;
+        if chunk_size < 8 {
+            return None;
+        }
+
+        match chunk_type {
+            RES_STRING_POOL if pool_start.is_none() => pool_start = Some(position),
+            RES_XML_START_ELEMENT => {
+                let pool = pool_start?;
+                let extension = position + header_size;
+                let name = pool_string(manifest, pool, read_u32(manifest, extension + 4)?)?;
+                if name == "manifest" {
+                    let attribute_start = read_u16(manifest, extension + 8)? as usize;
+                    let attribute_size = read_u16(manifest, extension + 10)? as usize;
+                    let attribute_count = read_u16(manifest, extension + 12)? as usize;
+                    for index in 0..attribute_count {
+                        let attribute = extension + attribute_start + index * attribute_size;
+                        let attribute_name = pool_string(manifest, pool, read_u32(manifest, attribute + 4)?)?;
+                        if attribute_name == "package" {
+                            return pool_string(manifest, pool, read_u32(manifest, attribute + 8)?);
+                        }
+                    }
+                    return None;
+                }
+            }
+            _ => {}
+        }
+        position += chunk_size;
+    }
+    None
+}
+
+/// Bundle identifier from the app's Info.plist inside `Payload/<App>.app/`.
+fn ipa_bundle_identifier(archive: &mut zip::ZipArchive<std::fs::File>) -> Option<String> {
+    let plist_name = archive
+        .file_names()
+        .find(|name| {
+            let parts: Vec<&str> = name.split('/').collect();
+            parts.len() == 3 && parts[0] == "Payload" && parts[1].ends_with(".app") && parts[2] == "Info.plist"
+        })?
+        .to_string();
+    let mut content = Vec::new();
+    archive.by_name(&plist_name).ok()?.read_to_end(&mut content).ok()?;
+    plist::Value::from_reader(std::io::Cursor::new(content))
+        .ok()?
+        .as_dictionary()?
+        .get("CFBundleIdentifier")?
+        .as_string()
+        .map(str::to_string)
+}
+
+fn bundle_identifier(bundle_path: &str, kind: BundleKind) -> Option<String> {
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(bundle_path).ok()?).ok()?;
+    match kind {
+        BundleKind::Ipa => ipa_bundle_identifier(&mut archive),
+        BundleKind::Apk => {
+            let mut manifest = Vec::new();
+            archive.by_name("AndroidManifest.xml").ok()?.read_to_end(&mut manifest).ok()?;
+            apk_package_name(&manifest)
+        }
+    }
+}
+
+/// Extract the databases bundled in an app package and describe them as local database files.
+pub fn inspect_bundle(bundle_path: &str) -> Result<Vec<DatabaseFile>, String> {
+    let kind = BundleKind::from_path(bundle_path)
+        .ok_or_else(|| format!("'{}' is not an .apk or .ipa file", bundle_path))?;
+    let package_name = bundle_identifier(bundle_path, kind).unwrap_or_else(|| {
+        Path::new(bundle_path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    });
+
+    let temp_dir = ensure_temp_dir().map_err(|e| format!("Failed to prepare temp directory: {}", e))?;
+    let databases = extract_zip_databases(bundle_path, &temp_dir)?;
+
+    Ok(databases
+        .into_iter()
+        .map(|database| {
+            let (location, filename) = match database.entry_name.rsplit_once('/') {
+                Some((folder, filename)) => (format!("{}/", folder), filename.to_string()),
+                None => (String::new(), database.entry_name.clone()),
+            };
+            DatabaseFile {
+                path: database.path,
+                package_name: package_name.clone(),
+                file_type: DatabaseFileType::from_path(&filename),
+                filename,
+                location,
+                remote_path: Some(database.entry_name),
+                device_type: "desktop".to_string(),
+                integrity: None,
+                pull_strategy: None,
+            }
+        })
+        .collect())
+}
+
+/// List the prepopulated databases shipped inside an .apk or .ipa, extracted to the temp directory.
+#[tauri::command]
+pub async fn inspect_app_bundle(bundle_path: String) -> Result<DeviceResponse<Vec<DatabaseFile>>, String> {
+    info!("📦 Inspecting app bundle: {}", bundle_path);
+
+    match inspect_bundle(&bundle_path) {
+        Ok(files) => {
+            info!("✅ Found {} bundled databases in {}", files.len(), bundle_path);
+            Ok(DeviceResponse {
+                success: true,
+                data: Some(files),
+                error: None,
+                error_detail: None,
+            })
+        }
+        Err(e) => {
+            error!("❌ Failed to inspect app bundle: {}", e);
+            Ok(DeviceResponse {
+                success: false,
+                data: None,
+                error_detail: Some(e.device_error()),
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+    use zip::write::SimpleFileOptions;
+
+    /// Minimal compiled manifest: a UTF-16 string pool and a `<manifest package="...">` element.
+    fn compiled_manifest(package: &str) -> Vec<u8> {
+        let strings = ["manifest", "package", package];
+        let mut string_data = Vec::new();
+        let mut offsets = Vec::new();
+        for string in strings {
+            offsets.push(string_data.len() as u32);
+            let units: Vec<u16> = string.encode_utf16().collect();
+            string_data.extend((units.len() as u16).to_le_bytes());
+            units.iter().for_each(|unit| string_data.extend(unit.to_le_bytes()));
+            string_data.extend([0, 0]);
+        }
+        while string_data.len() % 4 != 0 {
+            string_data.push(0);
+        }
+
+        let mut pool = Vec::new();
+        let pool_header = 28u32;
+        let strings_start = pool_header + offsets.len() as u32 * 4;
+        let pool_size = strings_start + string_data.len() as u32;
+        pool.extend(RES_STRING_POOL.to_le_bytes());
+        pool.extend((pool_header as u16).to_le_bytes());
+        pool.extend(pool_size.to_le_bytes());
+        pool.extend((strings.len() as u32).to_le_bytes());
+        pool.extend(0u32.to_le_bytes());
+        pool.extend(0u32.to_le_bytes());
+        pool.extend(strings_start.to_le_bytes());
+        pool.extend(0u32.to_le_bytes());
+        offsets.iter().for_each(|offset| pool.extend(offset.to_le_bytes()));
+        pool.extend(string_data);
+
+        let mut element = Vec::new();
+        element.extend(RES_XML_START_ELEMENT.to_le_bytes());
+        element.extend(16u16.to_le_bytes());
+        element.extend((16u32 + 20 + 20).to_le_bytes());
+        element.extend(1u32.to_le_bytes());
+        element.extend(u32::MAX.to_le_bytes());
+        // ns, name, attributeStart, attributeSize, attributeCount, id/class/style
+        element.extend(u32::MAX.to_le_bytes());
+        element.extend(0u32.to_le_bytes());
+        element.extend(20u16.to_le_bytes());
+        element.extend(20u16.to_le_bytes());
+        element.extend(1u16.to_le_bytes());
+        element.extend([0u8; 6]);
+        // ns, name, raw value, typed value
+        element.extend(u32::MAX.to_le_bytes());
+        element.extend(1u32.to_le_bytes());
+        element.extend(2u32.to_le_bytes());
+        element.extend(8u16.to_le_bytes());
+        element.extend([0u8, 3]);
+        element.extend(2u32.to_le_bytes());
+
+        let mut manifest = Vec::new();
+        manifest.extend(3u16.to_le_bytes());
+        manifest.extend(8u16.to_le_bytes());
+        manifest.extend((8 + pool.len() as u32 + element.len() as u32).to_le_bytes());
+        manifest.extend(pool);
+        manifest.extend(element);
+        manifest
+    }
+
+    #[test]
+    fn test_apk_package_name() {
+        assert_eq!(apk_package_name(&compiled_manifest("com.example.notes")).as_deref(), Some("com.example.notes"));
+        assert_eq!(apk_package_name(b"not a manifest"), None);
+    }
+
+    #[test]
+    fn test_inspect_bundle_lists_apk_databases() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("seed.db");
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        conn.execute_batch("CREATE TABLE items (id INTEGER PRIMARY KEY);").unwrap();
+        drop(conn);
+
+        let bundle_path = temp_dir.path().join("notes.apk");
+        let mut writer = zip::ZipWriter::new(std::fs::File::create(&bundle_path).unwrap());
+        let options = SimpleFileOptions::default();
+        writer.start_file("AndroidManifest.xml", options).unwrap();
+        writer.write_all(&compiled_manifest("com.example.notes")).unwrap();
+        writer.start_file("assets/databases/seed.db", options).unwrap();
+        writer.write_all(&std::fs::read(&db_path).unwrap()).unwrap();
+        writer.finish().unwrap();
+
+        let files = inspect_bundle(&bundle_path.to_string_lossy()).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].package_name, "com.example.notes");
+        assert_eq!(files[0].filename, "seed.db");
+        assert_eq!(files[0].location, "assets/databases/");
+        assert_eq!(files[0].device_type, "desktop");
+        assert!(Path::new(&files[0].path).is_file());
+
+        assert!(inspect_bundle("/tmp/app.zip").is_err());
+    }
+}
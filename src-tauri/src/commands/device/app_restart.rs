@@ -0,0 +1,168 @@
+//! Stopping the target app around a push
+//!
+//! A running app keeps database pages in memory and can write them back over a freshly
+//! pushed file. Push commands given `stop_app` force-stop (Android) or terminate (iOS) the
+//! app first and relaunch it once the push is done.
+
+use super::android_storage::android_user_from_path;
+use super::command_policy::PolicyOutputExt;
+use super::errors::CommandErrorExt;
+use super::helpers::{execute_adb_command, get_xcrun_path};
+use super::ios::app_lifecycle::{ios_launch_app, ios_terminate_app};
+use super::types::DeviceResponse;
+use std::future::Future;
+use tauri_plugin_shell::ShellExt;
+
+/// `adb` arguments that force-stop `package_name`, for one user when given.
+fn android_force_stop_args(device_id: &str, package_name: &str, user_id: Option<u32>) -> Vec<String> {
+    let mut args = vec!["-s", device_id, "shell", "am", "force-stop"]
+        .into_iter()
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    if let Some(user_id) = user_id {
+        args.extend(["--user".to_string(), user_id.to_string()]);
+    }
+    args.push(package_name.to_string());
+    args
+}
+
+/// `adb` arguments that start the launcher activity of `package_name`.
+fn android_launch_args(device_id: &str, package_name: &str) -> Vec<String> {
+    ["-s", device_id, "shell", "monkey", "-p", package_name, "-c", "android.intent.category.LAUNCHER", "1"]
+        .into_iter()
+        .map(str::to_string)
+        .collect()
+}
+
+async fn run_adb_lifecycle(args: Vec<String>, action: &str) -> Result<(), String> {
+    let arg_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+    let output = execute_adb_command(&arg_refs)
+        .await
+        .map_err(|e| format!("Failed to {} app: {}", action, e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // monkey exits 0 even when the package has no launcher activity
+    if output.status.success() && !stdout.contains("No activities found") {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(format!("Failed to {} app: {}", action, if stderr.trim().is_empty() { stdout.trim() } else { stderr.trim() }))
+    }
+}
+
+async fn run_simctl(app_handle: &tauri::AppHandle, args: &[&str]) -> Result<(), String> {
+    let output = app_handle
+        .shell()
+        .command(get_xcrun_path())
+        .arg("simctl")
+        .args(args)
+        .output_with_policy()
+        .await
+        .map_err(|e| format!("Failed to execute xcrun simctl: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!("simctl {} failed: {}", args[0], String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+fn lifecycle_result(response: DeviceResponse<String>) -> Result<(), String> {
+    if response.success {
+        Ok(())
+    } else {
+        Err(response.error.unwrap_or_else(|| "Unknown error".to_string()))
+    }
+}
+
+async fn stop_app(
+    app_handle: &tauri::AppHandle,
+    device_type: &str,
+    device_id: &str,
+    package_name: &str,
+    user_id: Option<u32>,
+) -> Result<(), String> {
+    match device_type {
+        "android" => run_adb_lifecycle(android_force_stop_args(device_id, package_name, user_id), "stop").await,
+        "iphone-device" => {
+            lifecycle_result(ios_terminate_app(app_handle.clone(), device_id.to_string(), package_name.to_string()).await?)
+        }
+        // simctl fails when the app is not running, which is as good as stopped
+        _ => {
+            if let Err(e) = run_simctl(app_handle, &["terminate", device_id, package_name]).await {
+                log::info!("simctl terminate before push: {}", e);
+            }
+            Ok(())
+        }
+    }
+}
+
+async fn launch_app(app_handle: &tauri::AppHandle, device_type: &str, device_id: &str, package_name: &str) -> Result<(), String> {
+    match device_type {
+        "android" => run_adb_lifecycle(android_launch_args(device_id, package_name), "launch").await,
+        "iphone-device" => {
+            lifecycle_result(ios_launch_app(app_handle.clone(), device_id.to_string(), package_name.to_string()).await?)
+        }
+        _ => run_simctl(app_handle, &["launch", device_id, package_name]).await,
+    }
+}
+
+/// Run `push` with the app stopped when `stop_app` is set, relaunching it afterwards whether
+/// or not the push succeeded. The push is not attempted if the app cannot be stopped.
+#[allow(clippy::too_many_arguments)]
+pub async fn with_app_stopped<T, F>(
+    app_handle: &tauri::AppHandle,
+    device_type: &str,
+    device_id: &str,
+    package_name: &str,
+    remote_path: &str,
+    user_id: Option<u32>,
+    stop_app_first: bool,
+    push: F,
+) -> Result<DeviceResponse<T>, String>
+where
+    F: Future<Output = Result<DeviceResponse<T>, String>>,
+{
+    if !stop_app_first {
+        return push.await;
+    }
+
+    let user_id = user_id.or_else(|| android_user_from_path(remote_path));
+    log::info!("🛑 Stopping {} on {} before push", package_name, device_id);
+    if let Err(e) = stop_app(app_handle, device_type, device_id, package_name, user_id).await {
+        log::error!("❌ Could not stop {} before push: {}", package_name, e);
+        return Ok(DeviceResponse {
+            success: false,
+            data: None,
+            error_detail: Some(e.device_error()),
+            error: Some(format!("Could not stop {} before push: {}", package_name, e)),
+        });
+    }
+
+    let response = push.await;
+
+    log::info!("🚀 Relaunching {} on {}", package_name, device_id);
+    if let Err(e) = launch_app(app_handle, device_type, device_id, package_name).await {
+        log::warn!("⚠️ Could not relaunch {} after push: {}", package_name, e);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_android_lifecycle_args() {
+        assert_eq!(
+            android_force_stop_args("emulator-5554", "com.example.app", Some(10)),
+            vec!["-s", "emulator-5554", "shell", "am", "force-stop", "--user", "10", "com.example.app"]
+        );
+        assert_eq!(
+            android_force_stop_args("emulator-5554", "com.example.app", None),
+            vec!["-s", "emulator-5554", "shell", "am", "force-stop", "com.example.app"]
+        );
+        assert_eq!(
+            android_launch_args("emulator-5554", "com.example.app")[3..],
+            ["monkey", "-p", "com.example.app", "-c", "android.intent.category.LAUNCHER", "1"]
+        );
+    }
+}
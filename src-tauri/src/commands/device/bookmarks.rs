@@ -0,0 +1,287 @@
+//! Database bookmarks
+//!
+//! Inspecting an app's database means picking the device, the app, the database file and
+//! then opening the pulled copy, over and over. A bookmark remembers device, package and
+//! remote path; `repull_bookmark` pulls a fresh copy and opens it in a single call.
+//! Bookmarks are stored in `bookmarks.json` in the app data directory.
+
+use super::types::DeviceResponse;
+use super::errors::CommandErrorExt;
+use crate::commands::database::{DbConnectionCache, DbPool};
+use crate::commands::integrations::pull_and_open_database;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{Manager, State};
+
+const BOOKMARKS_FILE_NAME: &str = "bookmarks.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseBookmark {
+    pub id: String,
+    pub name: String,
+    // "android", "iphone-device" or "simulator", as in DatabaseFile
+    pub device_type: String,
+    pub device_id: String,
+    pub package_name: String,
+    pub remote_path: String,
+    pub created_at: String,
+    pub last_pulled_at: Option<String>,
+}
+
+/// Result of a re-pull: the opened local copy and the updated bookmark
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepulledBookmark {
+    pub bookmark: DatabaseBookmark,
+    pub local_path: String,
+}
+
+fn bookmarks_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    std::fs::create_dir_all(&data_dir).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    Ok(data_dir.join(BOOKMARKS_FILE_NAME))
+}
+
+pub fn load_bookmarks(store_path: &Path) -> Vec<DatabaseBookmark> {
+    std::fs::read_to_string(store_path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_bookmarks(store_path: &Path, bookmarks: &[DatabaseBookmark]) -> Result<(), String> {
+    let content =
+        serde_json::to_string_pretty(bookmarks).map_err(|e| format!("Failed to serialize bookmarks: {}", e))?;
+    std::fs::write(store_path, content).map_err(|e| format!("Failed to save bookmarks: {}", e))
+}
+
+/// Add a bookmark, or return the existing one for the same device, package and path
+/// (renamed when a new name is given).
+pub fn upsert_bookmark(
+    bookmarks: &mut Vec<DatabaseBookmark>,
+    device_type: &str,
+    device_id: &str,
+    package_name: &str,
+    remote_path: &str,
+    name: Option<String>,
+) -> DatabaseBookmark {
+    let name = name.map(|name| name.trim().to_string()).filter(|name| !name.is_empty());
+    if let Some(existing) = bookmarks.iter_mut().find(|bookmark| {
+        bookmark.device_id == device_id && bookmark.package_name == package_name && bookmark.remote_path == remote_path
+    }) {
+        if let Some(name) = name {
+            existing.name = name;
+        }
+        return existing.clone();
+    }
+
+    let file_name = remote_path.rsplit('/').next().unwrap_or(remote_path);
+    let bookmark = DatabaseBookmark {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: name.unwrap_or_else(|| format!("{} · {}", package_name, file_name)),
+        device_type: device_type.to_string(),
+        device_id: device_id.to_string(),
+        package_name: package_name.to_string(),
+        remote_path: remote_path.to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        last_pulled_at: None,
+    };
+    bookmarks.push(bookmark.clone());
+    bookmark
+}
+
+fn failure<T>(error: String) -> Result<DeviceResponse<T>, String> {
+    log::error!("❌ {}", error);
+    Ok(DeviceResponse {
+        success: false,
+        data: None,
+        error_detail: Some(error.device_error()),
+        error: Some(error),
+    })
+}
+
+/// Remember a device database so it can be re-pulled in one step
+#[tauri::command]
+pub async fn bookmark_database(
+    app_handle: tauri::AppHandle,
+    device_type: String,
+    device_id: String,
+    package_name: String,
+    remote_path: String,
+    name: Option<String>,
+) -> Result<DeviceResponse<DatabaseBookmark>, String> {
+    if !matches!(device_type.as_str(), "android" | "iphone-device" | "simulator") {
+        return failure(format!("Unsupported device type for bookmarks: {}", device_type));
+    }
+
+    let result = bookmarks_path(&app_handle).and_then(|store_path| {
+        let mut bookmarks = load_bookmarks(&store_path);
+        let bookmark = upsert_bookmark(&mut bookmarks, &device_type, &device_id, &package_name, &remote_path, name);
+        save_bookmarks(&store_path, &bookmarks).map(|_| bookmark)
+    });
+
+    match result {
+        Ok(bookmark) => {
+            log::info!("🔖 Bookmarked {} on {}", bookmark.remote_path, bookmark.device_id);
+            Ok(DeviceResponse {
+                success: true,
+                data: Some(bookmark),
+                error: None,
+                error_detail: None,
+            })
+        }
+        Err(e) => failure(e),
+    }
+}
+
+/// Bookmarks, optionally only those of one device and/or package
+#[tauri::command]
+pub async fn bookmarks_list(
+    app_handle: tauri::AppHandle,
+    device_id: Option<String>,
+    package_name: Option<String>,
+) -> Result<DeviceResponse<Vec<DatabaseBookmark>>, String> {
+    let store_path = match bookmarks_path(&app_handle) {
+        Ok(store_path) => store_path,
+        Err(e) => return failure(e),
+    };
+
+    let bookmarks = load_bookmarks(&store_path)
+        .into_iter()
+        .filter(|bookmark| device_id.as_ref().is_none_or(|id| &bookmark.device_id == id))
+        .filter(|bookmark| package_name.as_ref().is_none_or(|package| &bookmark.package_name == package))
+        .collect();
+    Ok(DeviceResponse {
+        success: true,
+        data: Some(bookmarks),
+        error: None,
+        error_detail: None,
+    })
+}
+
+#[tauri::command]
+pub async fn bookmark_delete(app_handle: tauri::AppHandle, bookmark_id: String) -> Result<DeviceResponse<bool>, String> {
+    let result = bookmarks_path(&app_handle).and_then(|store_path| {
+        let mut bookmarks = load_bookmarks(&store_path);
+        let count = bookmarks.len();
+        bookmarks.retain(|bookmark| bookmark.id != bookmark_id);
+        let removed = bookmarks.len() != count;
+        save_bookmarks(&store_path, &bookmarks).map(|_| removed)
+    });
+
+    match result {
+        Ok(removed) => Ok(DeviceResponse {
+            success: true,
+            data: Some(removed),
+            error: None,
+            error_detail: None,
+        }),
+        Err(e) => failure(e),
+    }
+}
+
+/// Pull a fresh copy of a bookmarked database and open it
+#[tauri::command]
+pub async fn repull_bookmark(
+    app_handle: tauri::AppHandle,
+    state: State<'_, DbPool>,
+    db_cache: State<'_, DbConnectionCache>,
+    bookmark_id: String,
+) -> Result<DeviceResponse<RepulledBookmark>, String> {
+    let store_path = match bookmarks_path(&app_handle) {
+        Ok(store_path) => store_path,
+        Err(e) => return failure(e),
+    };
+    let mut bookmarks = load_bookmarks(&store_path);
+    let Some(bookmark) = bookmarks.iter().find(|bookmark| bookmark.id == bookmark_id).cloned() else {
+        return failure(format!("No bookmark with id {}", bookmark_id));
+    };
+
+    log::info!("🔖 Re-pulling {} from {}", bookmark.remote_path, bookmark.device_id);
+    let opened_path = match pull_and_open_database(
+        &app_handle,
+        state,
+        db_cache,
+        &bookmark.device_type,
+        &bookmark.device_id,
+        &bookmark.package_name,
+        &bookmark.remote_path,
+    )
+    .await
+    {
+        Ok(opened) => opened.path,
+        Err(e) => return failure(e),
+    };
+
+    let pulled_at = chrono::Utc::now().to_rfc3339();
+    let mut updated = bookmark;
+    if let Some(stored) = bookmarks.iter_mut().find(|stored| stored.id == bookmark_id) {
+        stored.last_pulled_at = Some(pulled_at.clone());
+        updated = stored.clone();
+    }
+    if let Err(e) = save_bookmarks(&store_path, &bookmarks) {
+        log::warn!("⚠️ Pulled bookmark but could not record the pull time: {}", e);
+    }
+
+    log::info!("✅ Opened {} from bookmark {}", opened_path, updated.name);
+    Ok(DeviceResponse {
+        success: true,
+        data: Some(RepulledBookmark {
+            bookmark: updated,
+            local_path: opened_path,
+        }),
+        error: None,
+        error_detail: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_upsert_bookmark_dedupes_and_names() {
+        let mut bookmarks = Vec::new();
+        let first = upsert_bookmark(
+            &mut bookmarks,
+            "android",
+            "emulator-5554",
+            "com.example.app",
+            "/data/data/com.example.app/databases/app.db",
+            None,
+        );
+        assert_eq!(first.name, "com.example.app · app.db");
+
+        let again = upsert_bookmark(
+            &mut bookmarks,
+            "android",
+            "emulator-5554",
+            "com.example.app",
+            "/data/data/com.example.app/databases/app.db",
+            Some("Main DB".to_string()),
+        );
+        assert_eq!(again.id, first.id);
+        assert_eq!(again.name, "Main DB");
+        assert_eq!(bookmarks.len(), 1);
+
+        upsert_bookmark(&mut bookmarks, "android", "R5CW123ABC", "com.example.app", "/data/data/com.example.app/databases/app.db", None);
+        assert_eq!(bookmarks.len(), 2);
+    }
+
+    #[test]
+    fn test_bookmarks_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let store_path = temp_dir.path().join(BOOKMARKS_FILE_NAME);
+        assert!(load_bookmarks(&store_path).is_empty());
+
+        let mut bookmarks = Vec::new();
+        upsert_bookmark(&mut bookmarks, "simulator", "A1B2", "com.example.app", "/tmp/app.sqlite", None);
+        save_bookmarks(&store_path, &bookmarks).unwrap();
+        assert_eq!(load_bookmarks(&store_path), bookmarks);
+    }
+}
@@ -0,0 +1,272 @@
+//! Device capability matrix
+//!
+//! What Flippio can do with a given device depends on the device (rooted? `run-as` available?)
+//! and on the host (is `afcclient` installed?). `get_device_capabilities` probes this once per
+//! device and caches the result, so the UI can disable impossible actions up front instead of
+//! letting them fail halfway through a pull or push.
+
+use super::environment::locate_executable;
+use super::helpers::{execute_adb_command, get_xcrun_path, IOS_SIMULATORS_SUPPORTED};
+use super::ios::device::is_device_a_simulator;
+use super::ios::tools::get_tool_command_legacy;
+use super::session::ping_ios_device;
+use super::types::DeviceResponse;
+use super::errors::CommandErrorExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+static CAPABILITIES: LazyLock<Mutex<HashMap<String, DeviceCapabilities>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// One shell round trip; each line that is printed marks a capability. `su` is only looked
+// up, never run, since running it can pop a root prompt on the device.
+const ANDROID_PROBE_SCRIPT: &str = "echo uid=$(id -u); \
+    command -v run-as >/dev/null 2>&1 && echo run-as; \
+    command -v su >/dev/null 2>&1 && echo su; \
+    [ \"$(getprop ro.debuggable)\" = 1 ] && echo debuggable; \
+    [ -r /sdcard/ ] && echo sdcard";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceCapabilities {
+    pub device_id: String,
+    // "android", "iphone-device" or "simulator", as in DatabaseFile
+    pub device_type: String,
+    // Private app files via `run-as`; still limited to debuggable builds
+    pub run_as: bool,
+    // adbd runs as root, or `su` is installed on a debuggable build
+    pub root: bool,
+    // Shared storage such as /sdcard on Android
+    pub shared_storage: bool,
+    // App Documents folders over AFC (apps with file sharing enabled)
+    pub afc_documents: bool,
+    // Whole app containers (house_arrest on devices, plain directories on simulators)
+    pub app_containers: bool,
+    // Any path on the device, not just app sandboxes
+    pub full_disk: bool,
+    pub install_apps: bool,
+    pub launch_apps: bool,
+    pub checked_at: String,
+}
+
+impl DeviceCapabilities {
+    pub(crate) fn new(device_id: &str, device_type: &str) -> Self {
+        Self {
+            device_id: device_id.to_string(),
+            device_type: device_type.to_string(),
+            run_as: false,
+            root: false,
+            shared_storage: false,
+            afc_documents: false,
+            app_containers: false,
+            full_disk: false,
+            install_apps: false,
+            launch_apps: false,
+            checked_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+/// Apply the output of `ANDROID_PROBE_SCRIPT`. A `su` on a user build is usually a root
+/// manager that asks on the device before every use, so it doesn't count as root.
+fn apply_android_probe(capabilities: &mut DeviceCapabilities, output: &str) {
+    let (mut root_adbd, mut su, mut debuggable) = (false, false, false);
+    for line in output.lines().map(str::trim) {
+        match line {
+            "uid=0" => root_adbd = true,
+            "su" => su = true,
+            "debuggable" => debuggable = true,
+            "run-as" => capabilities.run_as = true,
+            "sdcard" => capabilities.shared_storage = true,
+            _ => {}
+        }
+    }
+    capabilities.root = root_adbd || (su && debuggable);
+    capabilities.full_disk = capabilities.root;
+    capabilities.app_containers = capabilities.root || capabilities.run_as;
+}
+
+fn tool_available(tool: &str) -> bool {
+    locate_executable(&get_tool_command_legacy(tool)).is_some()
+}
+
+async fn probe_android(device_id: &str) -> Result<DeviceCapabilities, String> {
+    let output = execute_adb_command(&["-s", device_id, "shell", ANDROID_PROBE_SCRIPT])
+        .await
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    let mut capabilities = DeviceCapabilities::new(device_id, "android");
+    apply_android_probe(&mut capabilities, &String::from_utf8_lossy(&output.stdout));
+    capabilities.install_apps = true;
+    capabilities.launch_apps = true;
+    Ok(capabilities)
+}
+
+/// Capabilities of a physical iOS device. The device has to answer lockdown first: host tools
+/// are no use for a device that is gone or doesn't trust this computer.
+async fn probe_ios_device(device_id: &str) -> Result<DeviceCapabilities, String> {
+    ping_ios_device(device_id).await?;
+
+    let mut capabilities = DeviceCapabilities::new(device_id, "iphone-device");
+    let afcclient = tool_available("afcclient");
+    capabilities.afc_documents = afcclient;
+    capabilities.app_containers = afcclient;
+    capabilities.install_apps = tool_available("ideviceinstaller");
+    // devicectl ships with Xcode on macOS; idevicedebug covers the rest
+    capabilities.launch_apps =
+        tool_available("idevicedebug") || (cfg!(target_os = "macos") && locate_executable(&get_xcrun_path()).is_some());
+    Ok(capabilities)
+}
+
+fn probe_simulator(device_id: &str) -> DeviceCapabilities {
+    // Simulator file systems are directories on this machine
+    let mut capabilities = DeviceCapabilities::new(device_id, "simulator");
+    capabilities.shared_storage = true;
+    capabilities.afc_documents = true;
+    capabilities.app_containers = true;
+    capabilities.full_disk = true;
+    capabilities.install_apps = true;
+    capabilities.launch_apps = true;
+    capabilities
+}
+
+async fn probe_capabilities(device_id: &str) -> Result<DeviceCapabilities, String> {
+    let is_android = execute_adb_command(&["-s", device_id, "get-state"])
+        .await
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+    if is_android {
+        return probe_android(device_id).await;
+    }
+    if IOS_SIMULATORS_SUPPORTED && is_device_a_simulator(device_id).await {
+        return Ok(probe_simulator(device_id));
+    }
+    probe_ios_device(device_id).await
+}
+
+/// Capabilities from an earlier probe, without probing again
+pub fn cached_capabilities(device_id: &str) -> Option<DeviceCapabilities> {
+    CAPABILITIES.lock().ok()?.get(device_id).cloned()
+}
+
+/// Forget a device's capabilities so the next use probes it again, e.g. after a reconnect
+pub fn invalidate_capabilities(device_id: &str) {
+    if let Ok(mut capabilities) = CAPABILITIES.lock() {
+        capabilities.remove(device_id);
+    }
+}
+
+/// Forget the capabilities of `device_type` devices missing from a fresh device list. A device
+/// that comes back may have been rebooted into another build or had root added or removed.
+pub fn forget_disconnected_capabilities(device_type: &str, connected: &[&str]) {
+    if let Ok(mut capabilities) = CAPABILITIES.lock() {
+        capabilities.retain(|device_id, capabilities| {
+            capabilities.device_type != device_type || connected.contains(&device_id.as_str())
+        });
+    }
+}
+
+/// What Flippio can do with a device; probed on first use and cached unless `refresh` is set
+#[tauri::command]
+pub async fn get_device_capabilities(
+    device_id: String,
+    refresh: Option<bool>,
+) -> Result<DeviceResponse<DeviceCapabilities>, String> {
+    if !refresh.unwrap_or(false) {
+        let cached = CAPABILITIES
+            .lock()
+            .map_err(|_| "Capability cache poisoned".to_string())?
+            .get(&device_id)
+            .cloned();
+        if let Some(capabilities) = cached {
+            return Ok(DeviceResponse {
+                success: true,
+                data: Some(capabilities),
+                error: None,
+                error_detail: None,
+            });
+        }
+    }
+
+    log::info!("🧭 Probing capabilities of {}", device_id);
+    match probe_capabilities(&device_id).await {
+        Ok(capabilities) => {
+            log::info!("🧭 Capabilities of {}: {:?}", device_id, capabilities);
+            CAPABILITIES
+                .lock()
+                .map_err(|_| "Capability cache poisoned".to_string())?
+                .insert(device_id, capabilities.clone());
+            Ok(DeviceResponse {
+                success: true,
+                data: Some(capabilities),
+                error: None,
+                error_detail: None,
+            })
+        }
+        Err(e) => {
+            log::error!("❌ Failed to probe capabilities of {}: {}", device_id, e);
+            Ok(DeviceResponse {
+                success: false,
+                data: None,
+                error_detail: Some(e.device_error()),
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_android_probe() {
+        let mut user_build = DeviceCapabilities::new("R5CW123ABC", "android");
+        apply_android_probe(&mut user_build, "uid=2000\nrun-as\nsdcard\n");
+        assert!(user_build.run_as && user_build.shared_storage && user_build.app_containers);
+        assert!(!user_build.root && !user_build.full_disk);
+
+        let mut rooted = DeviceCapabilities::new("emulator-5554", "android");
+        apply_android_probe(&mut rooted, "uid=0\n");
+        assert!(rooted.root && rooted.full_disk && rooted.app_containers);
+        assert!(!rooted.run_as);
+
+        let mut userdebug = DeviceCapabilities::new("R5CW123ABC", "android");
+        apply_android_probe(&mut userdebug, "uid=2000\nrun-as\nsu\ndebuggable\nsdcard\n");
+        assert!(userdebug.root);
+
+        // A root manager on a user build would prompt on the device
+        let mut magisk = DeviceCapabilities::new("R5CW123ABC", "android");
+        apply_android_probe(&mut magisk, "uid=2000\nrun-as\nsu\nsdcard\n");
+        assert!(!magisk.root);
+    }
+
+    #[test]
+    fn test_forget_disconnected_capabilities() {
+        for (device_id, device_type) in [("serial-a", "android"), ("serial-b", "android"), ("udid-c", "iphone-device")] {
+            CAPABILITIES
+                .lock()
+                .unwrap()
+                .insert(device_id.to_string(), DeviceCapabilities::new(device_id, device_type));
+        }
+        forget_disconnected_capabilities("android", &["serial-a"]);
+        assert!(cached_capabilities("serial-a").is_some());
+        assert!(cached_capabilities("serial-b").is_none());
+        assert!(cached_capabilities("udid-c").is_some());
+
+        invalidate_capabilities("udid-c");
+        assert!(cached_capabilities("udid-c").is_none());
+    }
+
+    #[test]
+    fn test_capabilities_serialize_camel_case() {
+        let json = serde_json::to_value(probe_simulator("A1B2")).unwrap();
+        assert_eq!(json["deviceType"], "simulator");
+        assert_eq!(json["afcDocuments"], true);
+        assert_eq!(json["fullDisk"], true);
+    }
+}
@@ -0,0 +1,354 @@
+//! Timeout and retry policy for device tool invocations
+//!
+//! adb and libimobiledevice tools can hang indefinitely when a device is half-connected,
+//! locked or mid-reboot. Every invocation runs under a `CommandPolicy`: read-only queries get a
+//! short timeout and a couple of retries, commands that change the device a short timeout and a
+//! single attempt, transfers a long timeout and a single attempt.
+//! A timed-out process is killed and reported as a `CommandError::Timeout` naming the command.
+
+use log::{info, warn};
+use std::ffi::OsStr;
+use std::fmt;
+use std::path::Path;
+use std::process::{Command as StdCommand, Output, Stdio};
+use std::time::Duration;
+
+// Delay between attempts after a timeout
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+// Locale variables pinned to C so tools print the untranslated messages the parsers expect
+const LOCALE_VARS: [&str; 2] = ["LC_ALL", "LANG"];
+
+// Arguments that mark a command as a (possibly large) transfer or install
+const TRANSFER_ARGS: [&str; 9] = ["push", "pull", "install", "exec-out", "get", "put", "-i", "backup", "restore"];
+
+// Words that mark a command as changing the device, which a timed-out attempt may already have done
+const MUTATING_ARGS: [&str; 17] = [
+    "cp", "mv", "rm", "chmod", "chown", "mkdir", "touch", "ln", "tee", "uninstall", "-U", "clear",
+    "force-stop", "kill", "reboot", "remount", "setprop",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandPolicy {
+    pub timeout: Duration,
+    pub max_attempts: u32,
+}
+
+impl CommandPolicy {
+    /// Read-only probes: listing devices and packages, reading properties and files
+    pub const QUICK: Self = Self {
+        timeout: Duration::from_secs(30),
+        max_attempts: 3,
+    };
+
+    /// Copies, moves, removals and permission changes; not retried since the first attempt
+    /// may have applied before it timed out
+    pub const MUTATION: Self = Self {
+        timeout: Duration::from_secs(30),
+        max_attempts: 1,
+    };
+
+    /// File transfers and app installs; not retried since they can take minutes
+    pub const TRANSFER: Self = Self {
+        timeout: Duration::from_secs(600),
+        max_attempts: 1,
+    };
+
+    pub fn for_command(program: &str, args: &[String]) -> Self {
+        let tool = Path::new(program)
+            .file_stem()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        // Commands wrapped in `sh -c "..."` carry the real command line in one argument
+        let words: Vec<&str> = args
+            .iter()
+            .flat_map(|arg| arg.split(|c: char| c.is_whitespace() || ";&|()'\"".contains(c)))
+            .filter(|word| !word.is_empty())
+            .collect();
+        if tool == "idevicebackup2" || words.iter().any(|word| TRANSFER_ARGS.contains(word)) {
+            Self::TRANSFER
+        } else if words.iter().any(|word| MUTATING_ARGS.contains(word)) || args.iter().any(|arg| redirects_to_file(arg)) {
+            Self::MUTATION
+        } else {
+            Self::QUICK
+        }
+    }
+}
+
+// Shell output redirection to a file; `2>/dev/null` and `2>&1` don't write anything
+fn redirects_to_file(arg: &str) -> bool {
+    arg.match_indices('>').any(|(index, _)| {
+        let target = arg[index + 1..].trim_start_matches('>').trim_start();
+        !(target.is_empty() || target.starts_with('&') || target.starts_with("/dev/null"))
+    })
+}
+
+#[derive(Debug)]
+pub enum CommandError {
+    Timeout {
+        command: String,
+        timeout: Duration,
+        attempts: u32,
+    },
+    Spawn {
+        command: String,
+        source: std::io::Error,
+    },
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::Timeout { command, timeout, attempts } => write!(
+                f,
+                "Command timed out after {}s ({} attempt{}): {}. The device may be locked, rebooting or unresponsive.",
+                timeout.as_secs(),
+                attempts,
+                if *attempts == 1 { "" } else { "s" },
+                command
+            ),
+            CommandError::Spawn { command, source } => write!(f, "Failed to run {}: {}", command, source),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CommandError::Spawn { source, .. } => Some(source),
+            CommandError::Timeout { .. } => None,
+        }
+    }
+}
+
+/// Device a command targets: the serial after adb's `-s` or the UDID after a
+/// libimobiledevice tool's `-u`/`--udid`. Empty when the command names none.
+fn target_device(args: &[String]) -> &str {
+    args.windows(2)
+        .find(|pair| matches!(pair[0].as_str(), "-s" | "-u" | "--udid"))
+        .map_or("", |pair| pair[1].as_str())
+}
+
+fn lossy(value: &OsStr) -> String {
+    value.to_string_lossy().to_string()
+}
+
+/// `std::process::Command` is not `Clone`; rebuild it from its getters for another attempt.
+fn rebuild(command: &StdCommand) -> StdCommand {
+    let mut copy = StdCommand::new(command.get_program());
+    copy.args(command.get_args());
+    for (key, value) in command.get_envs() {
+        match value {
+            Some(value) => copy.env(key, value),
+            None => copy.env_remove(key),
+        };
+    }
+    if let Some(dir) = command.get_current_dir() {
+        copy.current_dir(dir);
+    }
+    copy
+}
+
+/// Pin the C locale unless the caller chose one explicitly.
+fn force_c_locale(command: &mut StdCommand) {
+    for var in LOCALE_VARS {
+        if !command.get_envs().any(|(key, _)| key == var) {
+            command.env(var, "C");
+        }
+    }
+}
+
+/// Run `command` under `policy`, retrying only on timeout.
+pub async fn run_with_policy(command: StdCommand, policy: CommandPolicy) -> Result<Output, CommandError> {
+    run_attempts(command, policy, || Ok(Stdio::piped())).await
+}
+
+/// `run_with_policy` with stdout streamed into `path` rather than kept in memory, for
+/// exec-out pulls of whole files. Each attempt starts the file over; the returned output has
+/// no stdout.
+pub async fn run_to_file_with_policy(command: StdCommand, policy: CommandPolicy, path: &Path) -> Result<Output, CommandError> {
+    run_attempts(command, policy, || std::fs::File::create(path).map(Stdio::from)).await
+}
+
+async fn run_attempts(
+    mut command: StdCommand,
+    policy: CommandPolicy,
+    stdout: impl Fn() -> std::io::Result<Stdio>,
+) -> Result<Output, CommandError> {
+    force_c_locale(&mut command);
+    let program = lossy(command.get_program());
+    let args: Vec<String> = command.get_args().map(lossy).collect();
+    let description = format!("{} {}", program, args.join(" ")).trim().to_string();
+    let device = target_device(&args);
+    let started = std::time::Instant::now();
+
+    let max_attempts = policy.max_attempts.max(1);
+    for attempt in 1..=max_attempts {
+        let mut child = tokio::process::Command::from(rebuild(&command));
+        // Dropping the future on timeout kills the process instead of leaking it
+        child.kill_on_drop(true).stdin(Stdio::null()).stderr(Stdio::piped());
+        let output = async {
+            child.stdout(stdout()?);
+            child.spawn()?.wait_with_output().await
+        };
+
+        match tokio::time::timeout(policy.timeout, output).await {
+            Ok(result) => {
+                if let Ok(output) = &result {
+                    info!(
+                        command = description.as_str(), device = device,
+                        exitCode = output.status.code().unwrap_or(-1),
+                        durationMs = started.elapsed().as_millis() as u64;
+                        "Command finished: {}", description
+                    );
+                }
+                return result.map_err(|source| CommandError::Spawn {
+                    command: description,
+                    source,
+                })
+            }
+            Err(_) if attempt < max_attempts => {
+                warn!(
+                    command = description.as_str(), device = device,
+                    timeoutSecs = policy.timeout.as_secs(), attempt = attempt;
+                    "{} timed out after {}s (attempt {}/{}), retrying",
+                    description,
+                    policy.timeout.as_secs(),
+                    attempt,
+                    max_attempts
+                );
+                tokio::time::sleep(RETRY_DELAY).await;
+            }
+            Err(_) => {}
+        }
+    }
+
+    info!(
+        command = description.as_str(), device = device,
+        durationMs = started.elapsed().as_millis() as u64, attempt = max_attempts;
+        "Giving up on {}", description
+    );
+    Err(CommandError::Timeout {
+        command: description,
+        timeout: policy.timeout,
+        attempts: max_attempts,
+    })
+}
+
+/// `output()` replacement that applies the command's default `CommandPolicy`.
+pub trait PolicyOutputExt {
+    fn output_with_policy(self) -> impl std::future::Future<Output = Result<Output, CommandError>> + Send;
+}
+
+impl PolicyOutputExt for StdCommand {
+    fn output_with_policy(self) -> impl std::future::Future<Output = Result<Output, CommandError>> + Send {
+        let program = lossy(self.get_program());
+        let args: Vec<String> = self.get_args().map(lossy).collect();
+        run_with_policy(self, CommandPolicy::for_command(&program, &args))
+    }
+}
+
+impl PolicyOutputExt for tauri_plugin_shell::process::Command {
+    fn output_with_policy(self) -> impl std::future::Future<Output = Result<Output, CommandError>> + Send {
+        StdCommand::from(self).output_with_policy()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_target_device() {
+        assert_eq!(target_device(&args(&["-s", "emulator-5554", "shell", "ls"])), "emulator-5554");
+        assert_eq!(target_device(&args(&["-u", "00008030-001A", "-b", "com.example"])), "00008030-001A");
+        assert_eq!(target_device(&args(&["devices", "-l"])), "");
+    }
+
+    #[test]
+    fn test_force_c_locale_keeps_explicit_locale() {
+        let mut command = StdCommand::new("adb");
+        command.env("LANG", "ja_JP.UTF-8");
+        force_c_locale(&mut command);
+
+        let envs: Vec<(String, Option<String>)> = command
+            .get_envs()
+            .map(|(key, value)| (lossy(key), value.map(lossy)))
+            .collect();
+        assert!(envs.contains(&("LC_ALL".to_string(), Some("C".to_string()))));
+        assert!(envs.contains(&("LANG".to_string(), Some("ja_JP.UTF-8".to_string()))));
+    }
+
+    #[test]
+    fn test_policy_for_command() {
+        assert_eq!(CommandPolicy::for_command("adb", &args(&["devices", "-l"])), CommandPolicy::QUICK);
+        assert_eq!(
+            CommandPolicy::for_command("/opt/homebrew/bin/adb", &args(&["-s", "x", "push", "a.db", "/sdcard/a.db"])),
+            CommandPolicy::TRANSFER
+        );
+        assert_eq!(
+            CommandPolicy::for_command("sh", &args(&["-c", "adb -s x exec-out run-as com.x cat a.db > out.db"])),
+            CommandPolicy::TRANSFER
+        );
+        assert_eq!(
+            CommandPolicy::for_command("ideviceinstaller", &args(&["-u", "udid", "-l"])),
+            CommandPolicy::QUICK
+        );
+        assert_eq!(
+            CommandPolicy::for_command("ideviceinstaller", &args(&["-u", "udid", "-i", "app.ipa"])),
+            CommandPolicy::TRANSFER
+        );
+        assert_eq!(
+            CommandPolicy::for_command("adb", &args(&["-s", "x", "shell", "run-as", "com.x", "cp", "/a", "/b"])),
+            CommandPolicy::MUTATION
+        );
+        assert_eq!(
+            CommandPolicy::for_command("adb", &args(&["-s", "x", "shell", "run-as com.x chmod 660 'a.db'&&echo ok"])),
+            CommandPolicy::MUTATION
+        );
+        assert_eq!(
+            CommandPolicy::for_command("adb", &args(&["shell", "echo 1 >/sdcard/flag"])),
+            CommandPolicy::MUTATION
+        );
+        assert_eq!(
+            CommandPolicy::for_command("adb", &args(&["-s", "x", "shell", "run-as", "com.x", "ls", "databases"])),
+            CommandPolicy::QUICK
+        );
+        assert_eq!(
+            CommandPolicy::for_command("adb", &args(&["shell", "command -v su 2>/dev/null; id 2>&1"])),
+            CommandPolicy::QUICK
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_with_policy_times_out_and_names_command() {
+        let policy = CommandPolicy {
+            timeout: Duration::from_millis(100),
+            max_attempts: 2,
+        };
+        let mut command = StdCommand::new("sleep");
+        command.arg("5");
+        let error = run_with_policy(command, policy).await.unwrap_err();
+        match &error {
+            CommandError::Timeout { command, attempts, .. } => {
+                assert_eq!(command, "sleep 5");
+                assert_eq!(*attempts, 2);
+            }
+            other => panic!("expected timeout, got {:?}", other),
+        }
+        assert!(error.to_string().contains("sleep 5"));
+
+        let mut command = StdCommand::new("echo");
+        command.arg("ok");
+        let output = run_with_policy(command, policy).await.unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "ok");
+
+        let missing = run_with_policy(StdCommand::new("flippio-no-such-tool"), policy).await;
+        assert!(matches!(missing, Err(CommandError::Spawn { .. })));
+    }
+}
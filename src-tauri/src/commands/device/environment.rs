@@ -0,0 +1,324 @@
+//! Environment check
+//!
+//! Probes every external tool Flippio shells out to (adb, xcrun/simctl and the
+//! libimobiledevice tools) so the UI can show a first-run checklist instead of
+//! failing halfway through a pull.
+
+use super::helpers::{get_adb_path, get_xcrun_path, IOS_SIMULATORS_SUPPORTED, SIMULATOR_UNSUPPORTED_ERROR};
+use super::ios::quarantine::{has_quarantine, quarantine_help};
+use super::ios::tool_validation::{format_tool_version, minimum_tool_version, tool_compatibility_warning};
+use super::ios::tools::get_tool_command_legacy;
+use super::types::DeviceResponse;
+use crate::commands::diagnostics::{probe_tool_version, IOS_TOOLS};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolCheck {
+    pub tool: String,
+    // "android", "ios-device" or "ios-simulator"
+    pub platform: String,
+    pub path: Option<String>,
+    pub found: bool,
+    pub executable: bool,
+    pub version: Option<String>,
+    pub minimum_version: Option<String>,
+    // macOS Gatekeeper quarantine attribute on the binary, which can stop it from launching
+    pub quarantined: bool,
+    // Set when the installed release is older than the minimum version
+    pub warning: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvironmentReport {
+    pub os: String,
+    pub tools: Vec<ToolCheck>,
+    pub android_ready: bool,
+    pub ios_device_ready: bool,
+    pub ios_simulator_ready: bool,
+}
+
+/// Features the current OS can offer at all, independent of installed tools
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PlatformCapabilities {
+    pub os: String,
+    pub android_devices: bool,
+    pub android_emulators: bool,
+    pub ios_devices: bool,
+    pub ios_simulators: bool,
+}
+
+pub fn platform_capabilities() -> PlatformCapabilities {
+    PlatformCapabilities {
+        os: std::env::consts::OS.to_string(),
+        android_devices: true,
+        android_emulators: true,
+        ios_devices: true,
+        ios_simulators: IOS_SIMULATORS_SUPPORTED,
+    }
+}
+
+/// Resolve a command to a file: paths are checked as-is, bare names are looked up on PATH.
+pub fn locate_executable(command: &str) -> Option<PathBuf> {
+    let path = Path::new(command);
+    if path.components().count() > 1 {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+
+    let names: Vec<String> = if cfg!(windows) && path.extension().is_none() {
+        vec![format!("{}.exe", command), command.to_string()]
+    } else {
+        vec![command.to_string()]
+    };
+    let search_path = std::env::var_os("PATH")?;
+    std::env::split_paths(&search_path)
+        .flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+        .find(|candidate| candidate.is_file())
+}
+
+pub fn is_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::metadata(path)
+            .map(|metadata| metadata.permissions().mode() & 0o111 != 0)
+            .unwrap_or(false)
+    }
+
+    #[cfg(not(unix))]
+    {
+        path.is_file()
+    }
+}
+
+fn platform_ready(tools: &[ToolCheck], platform: &str) -> bool {
+    let mut required = tools.iter().filter(|check| check.platform == platform).peekable();
+    required.peek().is_some() && required.all(|check| check.found && check.executable)
+}
+
+async fn check_tool(tool: &str, platform: &str, command: String, version_args: &[&str]) -> ToolCheck {
+    let Some(path) = locate_executable(&command) else {
+        return ToolCheck {
+            tool: tool.to_string(),
+            platform: platform.to_string(),
+            path: None,
+            found: false,
+            executable: false,
+            version: None,
+            minimum_version: minimum_tool_version(tool).map(format_tool_version),
+            quarantined: false,
+            warning: None,
+            error: Some(format!("'{}' was not found", command)),
+        };
+    };
+
+    let path_str = path.to_string_lossy().to_string();
+    let quarantined = has_quarantine(&path);
+    if !is_executable(&path) {
+        return ToolCheck {
+            tool: tool.to_string(),
+            platform: platform.to_string(),
+            path: Some(path_str.clone()),
+            found: true,
+            executable: false,
+            version: None,
+            minimum_version: minimum_tool_version(tool).map(format_tool_version),
+            quarantined,
+            warning: None,
+            error: Some(format!("'{}' is not executable", path_str)),
+        };
+    }
+
+    let probe = probe_tool_version(tool, path_str.clone(), version_args).await;
+    ToolCheck {
+        tool: tool.to_string(),
+        platform: platform.to_string(),
+        path: Some(path_str.clone()),
+        found: true,
+        // The file is executable but failed to launch, e.g. a missing dylib
+        executable: probe.error.is_none(),
+        warning: probe
+            .version
+            .as_deref()
+            .and_then(|version| tool_compatibility_warning(tool, version)),
+        version: probe.version,
+        minimum_version: minimum_tool_version(tool).map(format_tool_version),
+        quarantined,
+        // A quarantined tool that fails to launch was most likely blocked by Gatekeeper
+        error: probe.error.map(|error| {
+            if quarantined {
+                format!("{}. {}", error, quarantine_help(&path_str))
+            } else {
+                error
+            }
+        }),
+    }
+}
+
+fn unsupported_tool(tool: &str, platform: &str, reason: &str) -> ToolCheck {
+    ToolCheck {
+        tool: tool.to_string(),
+        platform: platform.to_string(),
+        path: None,
+        found: false,
+        executable: false,
+        version: None,
+        minimum_version: None,
+        quarantined: false,
+        warning: None,
+        error: Some(reason.to_string()),
+    }
+}
+
+/// simctl has no version flag; `xcrun --find simctl` fails when only the command line tools are installed.
+async fn check_simctl() -> ToolCheck {
+    let output = tokio::process::Command::new(get_xcrun_path())
+        .args(["--find", "simctl"])
+        .output()
+        .await;
+
+    match output {
+        Ok(output) if output.status.success() => ToolCheck {
+            tool: "simctl".to_string(),
+            platform: "ios-simulator".to_string(),
+            path: Some(String::from_utf8_lossy(&output.stdout).trim().to_string()),
+            found: true,
+            executable: true,
+            version: None,
+            minimum_version: None,
+            quarantined: false,
+            warning: None,
+            error: None,
+        },
+        Ok(output) => unsupported_tool(
+            "simctl",
+            "ios-simulator",
+            &format!("simctl not found (is Xcode installed?): {}", String::from_utf8_lossy(&output.stderr).trim()),
+        ),
+        Err(e) => unsupported_tool("simctl", "ios-simulator", &e.to_string()),
+    }
+}
+
+pub async fn collect_environment_report() -> EnvironmentReport {
+    let mut tools = vec![check_tool("adb", "android", get_adb_path(), &["version"]).await];
+
+    for tool in IOS_TOOLS {
+        tools.push(check_tool(tool, "ios-device", get_tool_command_legacy(tool), &["--version"]).await);
+    }
+
+    if IOS_SIMULATORS_SUPPORTED {
+        let xcrun = check_tool("xcrun", "ios-simulator", get_xcrun_path(), &["--version"]).await;
+        let simctl = if xcrun.executable {
+            check_simctl().await
+        } else {
+            unsupported_tool("simctl", "ios-simulator", "xcrun is required to run simctl")
+        };
+        tools.push(xcrun);
+        tools.push(simctl);
+    } else {
+        tools.push(unsupported_tool("xcrun", "ios-simulator", SIMULATOR_UNSUPPORTED_ERROR));
+        tools.push(unsupported_tool("simctl", "ios-simulator", SIMULATOR_UNSUPPORTED_ERROR));
+    }
+
+    EnvironmentReport {
+        os: std::env::consts::OS.to_string(),
+        android_ready: platform_ready(&tools, "android"),
+        ios_device_ready: platform_ready(&tools, "ios-device"),
+        ios_simulator_ready: platform_ready(&tools, "ios-simulator"),
+        tools,
+    }
+}
+
+/// Report which device tools are installed, runnable and at which version
+#[tauri::command]
+pub async fn check_environment() -> Result<DeviceResponse<EnvironmentReport>, String> {
+    log::info!("🩺 Checking device tool environment");
+
+    let report = collect_environment_report().await;
+    for check in &report.tools {
+        if !check.executable {
+            log::warn!(
+                "⚠️ {} unavailable: {}",
+                check.tool,
+                check.error.as_deref().unwrap_or("unknown error")
+            );
+        } else if let Some(warning) = &check.warning {
+            log::warn!("⚠️ {}", warning);
+        }
+    }
+
+    Ok(DeviceResponse {
+        success: true,
+        data: Some(report),
+        error: None,
+        error_detail: None,
+    })
+}
+
+/// Report which device features this OS supports, so the UI can hide the rest
+#[tauri::command]
+pub async fn get_platform_capabilities() -> Result<DeviceResponse<PlatformCapabilities>, String> {
+    Ok(DeviceResponse {
+        success: true,
+        data: Some(platform_capabilities()),
+        error: None,
+        error_detail: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn check(tool: &str, platform: &str, ok: bool) -> ToolCheck {
+        ToolCheck {
+            tool: tool.to_string(),
+            platform: platform.to_string(),
+            path: None,
+            found: ok,
+            executable: ok,
+            version: None,
+            minimum_version: None,
+            quarantined: false,
+            warning: None,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_locate_executable() {
+        let temp_dir = TempDir::new().unwrap();
+        let tool = temp_dir.path().join("adb");
+        std::fs::write(&tool, b"#!/bin/sh\n").unwrap();
+
+        assert_eq!(locate_executable(&tool.to_string_lossy()), Some(tool.clone()));
+        assert_eq!(locate_executable(&temp_dir.path().join("missing").to_string_lossy()), None);
+        assert_eq!(locate_executable("flippio-no-such-tool"), None);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert!(!is_executable(&tool));
+            std::fs::set_permissions(&tool, std::fs::Permissions::from_mode(0o755)).unwrap();
+            assert!(is_executable(&tool));
+        }
+    }
+
+    #[test]
+    fn test_platform_ready() {
+        let tools = vec![
+            check("adb", "android", true),
+            check("idevice_id", "ios-device", true),
+            check("afcclient", "ios-device", false),
+        ];
+
+        assert!(platform_ready(&tools, "android"));
+        assert!(!platform_ready(&tools, "ios-device"));
+        assert!(!platform_ready(&tools, "ios-simulator"));
+    }
+}
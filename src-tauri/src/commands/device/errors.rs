@@ -0,0 +1,288 @@
+//! Device error taxonomy
+//!
+//! Device failures reach the UI as free text from adb, libimobiledevice or our own wrappers.
+//! `DeviceError` classifies them so the UI can offer the matching fix ("accept the RSA prompt
+//! on the phone") instead of only echoing the message. Every `DeviceResponse` with an error
+//! carries it as `errorDetail`, derived with `CommandErrorExt` where the error happens, from the
+//! typed error when there is one.
+
+use super::android_storage::{PushError, PushErrorKind};
+use super::command_policy::CommandError;
+use serde::{Deserialize, Serialize};
+use std::process::Output;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum DeviceError {
+    ToolMissing { tool: Option<String> },
+    DeviceOffline,
+    DeviceNotFound,
+    Unauthorized,
+    AppNotDebuggable,
+    AppNotInstalled,
+    PermissionDenied,
+    StorageFull,
+    Timeout { command: Option<String>, seconds: Option<u64> },
+    TransferFailed { bytes: Option<u64> },
+    Unknown,
+}
+
+impl DeviceError {
+    /// What the user can do about it; `None` when there is nothing specific to suggest.
+    pub fn hint(&self) -> Option<&'static str> {
+        Some(match self {
+            DeviceError::ToolMissing { .. } => {
+                "Install Android platform-tools or libimobiledevice, or set the tool path in Settings"
+            }
+            DeviceError::DeviceOffline => "Reconnect the device, or run `adb kill-server` and try again",
+            DeviceError::DeviceNotFound => "The device is no longer connected; refresh the device list",
+            DeviceError::Unauthorized => {
+                "Unlock the device and accept the USB debugging (RSA key) or \"Trust This Computer\" prompt"
+            }
+            DeviceError::AppNotDebuggable => "Install a debuggable build of the app to access its private files",
+            DeviceError::AppNotInstalled => "Install the app on the device first",
+            DeviceError::PermissionDenied => {
+                "This location is not accessible over adb/AFC; use a debuggable build or a different path"
+            }
+            DeviceError::StorageFull => "Free up space on the device and try again",
+            DeviceError::Timeout { .. } => "The device did not respond; unlock it or reconnect the cable",
+            DeviceError::TransferFailed { .. } => "The transfer was interrupted; check the cable and try again",
+            DeviceError::Unknown => return None,
+        })
+    }
+}
+
+/// `DeviceError` plus its hint, as sent to the UI.
+#[derive(Debug, Serialize)]
+pub struct DeviceErrorDetail {
+    #[serde(flatten)]
+    pub error: DeviceError,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<&'static str>,
+}
+
+impl From<DeviceError> for DeviceErrorDetail {
+    fn from(error: DeviceError) -> Self {
+        let hint = error.hint();
+        Self { error, hint }
+    }
+}
+
+/// Number right before `marker`, e.g. `(1024 bytes` → 1024.
+fn number_before(text: &str, marker: &str) -> Option<u64> {
+    let end = text.find(marker)?;
+    text[..end].split(|c: char| !c.is_ascii_digit()).rfind(|part| !part.is_empty())?.parse().ok()
+}
+
+/// Tool name following "failed to run "/"failed to execute " in a spawn error.
+fn tool_after_prefix(message: &str) -> Option<String> {
+    let lower = message.to_lowercase();
+    ["failed to run ", "failed to execute "].iter().find_map(|prefix| {
+        let start = lower.find(prefix)? + prefix.len();
+        let tool = message[start..].split([' ', ':']).next()?.trim();
+        (!tool.is_empty()).then(|| tool.to_string())
+    })
+}
+
+fn classify_message(message: &str) -> DeviceError {
+    let lower = message.to_lowercase();
+    let has = |needles: &[&str]| needles.iter().any(|needle| lower.contains(needle));
+
+    if has(&["timed out"]) {
+        DeviceError::Timeout {
+            command: None,
+            seconds: lower
+                .split("after ")
+                .nth(1)
+                .and_then(|rest| rest.split(|c: char| !c.is_ascii_digit()).next())
+                .and_then(|seconds| seconds.parse().ok()),
+        }
+    } else if has(&["(os error 2)", "command not found", "is not recognized as an internal"]) {
+        DeviceError::ToolMissing {
+            tool: tool_after_prefix(message),
+        }
+    } else if has(&["unauthorized", "rsa key", "trust dialog", "not paired", "pairing dialog", "passwordprotected"]) {
+        DeviceError::Unauthorized
+    } else if has(&["offline"]) {
+        DeviceError::DeviceOffline
+    } else if has(&["no devices/emulators found", "device not found", "no device found", "no such device"])
+        || (lower.contains("device '") && lower.contains("' not found"))
+    {
+        DeviceError::DeviceNotFound
+    } else if has(&["not debuggable"]) {
+        DeviceError::AppNotDebuggable
+    } else if has(&["unknown package", "package not found", "is not installed", "app not installed"]) {
+        DeviceError::AppNotInstalled
+    } else if has(&["permission denied", "operation not permitted", "read-only file system"]) {
+        DeviceError::PermissionDenied
+    } else if has(&["no space left", "out of storage", "insufficient_storage"]) {
+        DeviceError::StorageFull
+    } else if has(&["failed to copy", "push failed", "pull failed", "protocol fault", "remote couldn't", "afc error"]) {
+        DeviceError::TransferFailed {
+            bytes: number_before(&lower, " bytes"),
+        }
+    } else {
+        DeviceError::Unknown
+    }
+}
+
+/// Derive a `DeviceError` from whatever a device operation failed with.
+pub trait CommandErrorExt {
+    fn device_error(&self) -> DeviceError;
+}
+
+impl CommandErrorExt for str {
+    fn device_error(&self) -> DeviceError {
+        classify_message(self)
+    }
+}
+
+impl CommandErrorExt for CommandError {
+    fn device_error(&self) -> DeviceError {
+        match self {
+            CommandError::Timeout { command, timeout, .. } => DeviceError::Timeout {
+                command: Some(command.clone()),
+                seconds: Some(timeout.as_secs()),
+            },
+            CommandError::Spawn { command, source } if source.kind() == std::io::ErrorKind::NotFound => {
+                DeviceError::ToolMissing {
+                    tool: command.split_whitespace().next().map(str::to_string),
+                }
+            }
+            CommandError::Spawn { .. } => classify_message(&self.to_string()),
+        }
+    }
+}
+
+// Boxed errors keep their type, so the typed impls apply before falling back to the message
+impl CommandErrorExt for dyn std::error::Error + Send + Sync {
+    fn device_error(&self) -> DeviceError {
+        if let Some(error) = self.downcast_ref::<CommandError>() {
+            error.device_error()
+        } else if let Some(error) = self.downcast_ref::<PushError>() {
+            error.device_error()
+        } else {
+            classify_message(&self.to_string())
+        }
+    }
+}
+
+impl CommandErrorExt for Output {
+    fn device_error(&self) -> DeviceError {
+        let stderr = String::from_utf8_lossy(&self.stderr);
+        match classify_message(&stderr) {
+            DeviceError::Unknown => classify_message(&String::from_utf8_lossy(&self.stdout)),
+            error => error,
+        }
+    }
+}
+
+impl CommandErrorExt for PushError {
+    fn device_error(&self) -> DeviceError {
+        match self.kind {
+            PushErrorKind::PermissionDenied | PushErrorKind::ReadOnly => DeviceError::PermissionDenied,
+            PushErrorKind::NotDebuggable => DeviceError::AppNotDebuggable,
+            PushErrorKind::UnknownPackage => DeviceError::AppNotInstalled,
+            PushErrorKind::StorageFull => DeviceError::StorageFull,
+            PushErrorKind::DeviceUnavailable => classify_message(&self.details),
+            PushErrorKind::MissingDirectory | PushErrorKind::Unknown => DeviceError::TransferFailed {
+                bytes: number_before(&self.details.to_lowercase(), " bytes"),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::device::types::DeviceResponse;
+    use std::time::Duration;
+
+    #[test]
+    fn test_classify_messages() {
+        assert_eq!("error: device unauthorized.\nThis adb server's $ADB_VENDOR_KEYS is not set".device_error(), DeviceError::Unauthorized);
+        assert_eq!("ERROR: Could not connect to lockdownd: PasswordProtected (-17)".device_error(), DeviceError::Unauthorized);
+        assert_eq!("adb: device offline".device_error(), DeviceError::DeviceOffline);
+        assert_eq!("adb: device 'emulator-5556' not found".device_error(), DeviceError::DeviceNotFound);
+        assert_eq!("run-as: package not debuggable: com.example".device_error(), DeviceError::AppNotDebuggable);
+        assert_eq!(
+            "Failed to run idevice_id -l: No such file or directory (os error 2)".device_error(),
+            DeviceError::ToolMissing {
+                tool: Some("idevice_id".to_string())
+            }
+        );
+        assert_eq!(
+            "adb: error: failed to copy 'a.db' to '/sdcard/a.db': 4096 bytes written before error".device_error(),
+            DeviceError::TransferFailed { bytes: Some(4096) }
+        );
+        assert_eq!(
+            "Command timed out after 30s (3 attempts): adb devices".device_error(),
+            DeviceError::Timeout {
+                command: None,
+                seconds: Some(30)
+            }
+        );
+        assert_eq!("something odd".device_error(), DeviceError::Unknown);
+        assert!(DeviceError::Unknown.hint().is_none());
+        assert!(DeviceError::Unauthorized.hint().unwrap().contains("RSA"));
+    }
+
+    #[test]
+    fn test_command_errors_and_detail_serialization() {
+        let timeout = CommandError::Timeout {
+            command: "adb -s x shell getprop".to_string(),
+            timeout: Duration::from_secs(30),
+            attempts: 3,
+        };
+        assert_eq!(
+            timeout.device_error(),
+            DeviceError::Timeout {
+                command: Some("adb -s x shell getprop".to_string()),
+                seconds: Some(30)
+            }
+        );
+        let missing = CommandError::Spawn {
+            command: "ideviceinfo -u x".to_string(),
+            source: std::io::Error::from(std::io::ErrorKind::NotFound),
+        };
+        assert_eq!(
+            missing.device_error(),
+            DeviceError::ToolMissing {
+                tool: Some("ideviceinfo".to_string())
+            }
+        );
+
+        // A boxed error is classified by its type, not by its message
+        let boxed: Box<dyn std::error::Error + Send + Sync> = Box::new(timeout);
+        assert_eq!(
+            boxed.device_error(),
+            DeviceError::Timeout {
+                command: Some("adb -s x shell getprop".to_string()),
+                seconds: Some(30)
+            }
+        );
+
+        let detail = serde_json::to_value(DeviceErrorDetail::from(DeviceError::TransferFailed { bytes: Some(10) })).unwrap();
+        assert_eq!(detail["kind"], "transferFailed");
+        assert_eq!(detail["bytes"], 10);
+        assert!(detail["hint"].is_string());
+
+        let message = "error: device unauthorized.".to_string();
+        let response: DeviceResponse<()> = DeviceResponse {
+            success: false,
+            data: None,
+            error_detail: Some(message.device_error()),
+            error: Some(message),
+        };
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["errorDetail"]["kind"], "unauthorized");
+        assert_eq!(json["error"], "error: device unauthorized.");
+        let ok: DeviceResponse<u8> = DeviceResponse {
+            success: true,
+            data: Some(1),
+            error: None,
+            error_detail: None,
+        };
+        assert!(serde_json::to_value(&ok).unwrap().get("errorDetail").is_none());
+    }
+}
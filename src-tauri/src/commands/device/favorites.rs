@@ -0,0 +1,300 @@
+//! Pinned devices and apps
+//!
+//! Users pin the devices and packages they work with most, optionally with a nickname.
+//! Pins are stored in `favorites.json` in the app data directory, in the order the user
+//! gave them, and the listing commands annotate and sort their results with them so
+//! frequent targets come first.
+
+use super::types::{Device, DeviceResponse, Package};
+use super::errors::CommandErrorExt;
+use crate::commands::crash::{remember_device_ids, remember_package_ids};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+const FAVORITES_FILE_NAME: &str = "favorites.json";
+
+/// Pin details attached to a listed device or package
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Favorite {
+    pub pinned: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nickname: Option<String>,
+    // Position among pinned items of the same kind, 0 first
+    pub sort_order: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PinnedItem {
+    // Device id, or package bundle id (packages are pinned across devices)
+    pub id: String,
+    pub nickname: Option<String>,
+    pub pinned_at: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Favorites {
+    #[serde(default)]
+    pub devices: Vec<PinnedItem>,
+    #[serde(default)]
+    pub packages: Vec<PinnedItem>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FavoriteKind {
+    Device,
+    Package,
+}
+
+impl Favorites {
+    fn items_mut(&mut self, kind: FavoriteKind) -> &mut Vec<PinnedItem> {
+        match kind {
+            FavoriteKind::Device => &mut self.devices,
+            FavoriteKind::Package => &mut self.packages,
+        }
+    }
+
+    /// Pin (or update the nickname of) an item; new pins go to the end.
+    pub fn pin(&mut self, kind: FavoriteKind, id: &str, nickname: Option<String>) {
+        let nickname = nickname.map(|name| name.trim().to_string()).filter(|name| !name.is_empty());
+        let items = self.items_mut(kind);
+        match items.iter_mut().find(|item| item.id == id) {
+            Some(item) => item.nickname = nickname,
+            None => items.push(PinnedItem {
+                id: id.to_string(),
+                nickname,
+                pinned_at: chrono::Utc::now().to_rfc3339(),
+            }),
+        }
+    }
+
+    pub fn unpin(&mut self, kind: FavoriteKind, id: &str) {
+        self.items_mut(kind).retain(|item| item.id != id);
+    }
+
+    /// Put the listed ids first, in the given order; pins not listed keep their relative order.
+    pub fn reorder(&mut self, kind: FavoriteKind, ids: &[String]) {
+        let items = self.items_mut(kind);
+        items.sort_by_key(|item| ids.iter().position(|id| id == &item.id).unwrap_or(ids.len()));
+    }
+
+    fn favorite(items: &[PinnedItem], id: &str) -> Option<Favorite> {
+        items.iter().position(|item| item.id == id).map(|sort_order| Favorite {
+            pinned: true,
+            nickname: items[sort_order].nickname.clone(),
+            sort_order,
+        })
+    }
+
+    /// Annotate devices with their pins and move pinned devices to the front.
+    pub fn apply_to_devices(&self, devices: &mut [Device]) {
+        for device in devices.iter_mut() {
+            device.favorite = Self::favorite(&self.devices, &device.id);
+        }
+        devices.sort_by_key(|device| device.favorite.as_ref().map_or(usize::MAX, |favorite| favorite.sort_order));
+    }
+
+    /// Annotate packages with their pins and move pinned packages to the front.
+    pub fn apply_to_packages(&self, packages: &mut [Package]) {
+        for package in packages.iter_mut() {
+            package.favorite = Self::favorite(&self.packages, &package.bundle_id);
+        }
+        packages.sort_by_key(|package| package.favorite.as_ref().map_or(usize::MAX, |favorite| favorite.sort_order));
+    }
+}
+
+fn favorites_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    std::fs::create_dir_all(&data_dir).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    Ok(data_dir.join(FAVORITES_FILE_NAME))
+}
+
+pub fn load_favorites(store_path: &Path) -> Favorites {
+    std::fs::read_to_string(store_path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_favorites(store_path: &Path, favorites: &Favorites) -> Result<(), String> {
+    let content =
+        serde_json::to_string_pretty(favorites).map_err(|e| format!("Failed to serialize favorites: {}", e))?;
+    std::fs::write(store_path, content).map_err(|e| format!("Failed to save favorites: {}", e))
+}
+
+/// Pins of the current user; empty when none are stored or the store cannot be read.
+pub fn current_favorites(app_handle: &tauri::AppHandle) -> Favorites {
+    favorites_path(app_handle)
+        .map(|store_path| load_favorites(&store_path))
+        .unwrap_or_default()
+}
+
+/// Apply pins to a successful device listing. Every listing passes through here, so the
+/// serials are also remembered for redaction in crash reports.
+pub fn with_device_favorites(
+    app_handle: &tauri::AppHandle,
+    mut response: DeviceResponse<Vec<Device>>,
+) -> DeviceResponse<Vec<Device>> {
+    if let Some(devices) = response.data.as_mut() {
+        remember_device_ids(devices.iter().map(|device| device.id.clone()));
+        current_favorites(app_handle).apply_to_devices(devices);
+    }
+    response
+}
+
+/// Apply pins to a successful package listing; the package IDs are remembered for redaction
+/// like the serials in `with_device_favorites`.
+pub fn with_package_favorites(
+    app_handle: &tauri::AppHandle,
+    mut response: DeviceResponse<Vec<Package>>,
+) -> DeviceResponse<Vec<Package>> {
+    if let Some(packages) = response.data.as_mut() {
+        remember_package_ids(packages.iter().map(|package| package.bundle_id.clone()));
+        current_favorites(app_handle).apply_to_packages(packages);
+    }
+    response
+}
+
+fn update_favorites(
+    app_handle: &tauri::AppHandle,
+    update: impl FnOnce(&mut Favorites),
+) -> Result<DeviceResponse<Favorites>, String> {
+    let result = favorites_path(app_handle).and_then(|store_path| {
+        let mut favorites = load_favorites(&store_path);
+        update(&mut favorites);
+        save_favorites(&store_path, &favorites).map(|_| favorites)
+    });
+
+    match result {
+        Ok(favorites) => Ok(DeviceResponse {
+            success: true,
+            data: Some(favorites),
+            error: None,
+            error_detail: None,
+        }),
+        Err(e) => {
+            log::error!("❌ Failed to update favorites: {}", e);
+            Ok(DeviceResponse {
+                success: false,
+                data: None,
+                error_detail: Some(e.device_error()),
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn favorites_get(app_handle: tauri::AppHandle) -> Result<DeviceResponse<Favorites>, String> {
+    Ok(DeviceResponse {
+        success: true,
+        data: Some(current_favorites(&app_handle)),
+        error: None,
+        error_detail: None,
+    })
+}
+
+/// Pin a device or package, or rename an existing pin
+#[tauri::command]
+pub async fn favorites_pin(
+    app_handle: tauri::AppHandle,
+    kind: FavoriteKind,
+    id: String,
+    nickname: Option<String>,
+) -> Result<DeviceResponse<Favorites>, String> {
+    log::info!("📌 Pinning {:?} {}", kind, id);
+    update_favorites(&app_handle, |favorites| favorites.pin(kind, &id, nickname))
+}
+
+#[tauri::command]
+pub async fn favorites_unpin(
+    app_handle: tauri::AppHandle,
+    kind: FavoriteKind,
+    id: String,
+) -> Result<DeviceResponse<Favorites>, String> {
+    log::info!("📌 Unpinning {:?} {}", kind, id);
+    update_favorites(&app_handle, |favorites| favorites.unpin(kind, &id))
+}
+
+/// Reorder pins of one kind; `ids` lists them in their new order
+#[tauri::command]
+pub async fn favorites_reorder(
+    app_handle: tauri::AppHandle,
+    kind: FavoriteKind,
+    ids: Vec<String>,
+) -> Result<DeviceResponse<Favorites>, String> {
+    update_favorites(&app_handle, |favorites| favorites.reorder(kind, &ids))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn package(bundle_id: &str) -> Package {
+        Package {
+            name: bundle_id.to_string(),
+            bundle_id: bundle_id.to_string(),
+            favorite: None,
+        }
+    }
+
+    #[test]
+    fn test_pin_unpin_and_reorder() {
+        let mut favorites = Favorites::default();
+        favorites.pin(FavoriteKind::Package, "com.example.a", None);
+        favorites.pin(FavoriteKind::Package, "com.example.b", Some("  Bee ".to_string()));
+        favorites.pin(FavoriteKind::Package, "com.example.a", Some("Ay".to_string()));
+        favorites.pin(FavoriteKind::Device, "emulator-5554", Some(" ".to_string()));
+
+        assert_eq!(favorites.packages.len(), 2);
+        assert_eq!(favorites.packages[0].nickname.as_deref(), Some("Ay"));
+        assert_eq!(favorites.packages[1].nickname.as_deref(), Some("Bee"));
+        assert_eq!(favorites.devices[0].nickname, None);
+
+        favorites.reorder(FavoriteKind::Package, &["com.example.b".to_string()]);
+        assert_eq!(favorites.packages[0].id, "com.example.b");
+
+        favorites.unpin(FavoriteKind::Package, "com.example.b");
+        assert_eq!(favorites.packages.len(), 1);
+    }
+
+    #[test]
+    fn test_apply_to_packages_sorts_pinned_first() {
+        let mut favorites = Favorites::default();
+        favorites.pin(FavoriteKind::Package, "com.example.c", Some("Main app".to_string()));
+        favorites.pin(FavoriteKind::Package, "com.example.a", None);
+
+        let mut packages = vec![package("com.example.a"), package("com.example.b"), package("com.example.c")];
+        favorites.apply_to_packages(&mut packages);
+
+        let order: Vec<&str> = packages.iter().map(|p| p.bundle_id.as_str()).collect();
+        assert_eq!(order, ["com.example.c", "com.example.a", "com.example.b"]);
+        assert_eq!(packages[0].favorite.as_ref().unwrap().nickname.as_deref(), Some("Main app"));
+        assert!(packages[2].favorite.is_none());
+
+        let json = serde_json::to_value(&packages[1]).unwrap();
+        assert_eq!(json["pinned"], true);
+        assert_eq!(json["sortOrder"], 1);
+        assert!(serde_json::to_value(&packages[2]).unwrap().get("pinned").is_none());
+    }
+
+    #[test]
+    fn test_favorites_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let store_path = temp_dir.path().join(FAVORITES_FILE_NAME);
+        assert_eq!(load_favorites(&store_path), Favorites::default());
+
+        let mut favorites = Favorites::default();
+        favorites.pin(FavoriteKind::Device, "R5CW123ABC", Some("Pixel".to_string()));
+        save_favorites(&store_path, &favorites).unwrap();
+        assert_eq!(load_favorites(&store_path), favorites);
+    }
+}
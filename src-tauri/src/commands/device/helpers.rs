@@ -1,64 +1,161 @@
 use std::fs;
+use super::errors::DeviceError;
 use std::path::{Path, PathBuf};
 use log::{info, error};
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
+use super::command_policy::{run_to_file_with_policy, CommandPolicy, PolicyOutputExt};
+
+const PRIVATE_TEMP_DIR_NAME: &str = "pulled-databases";
+// Longest device name shown before it is cut with an ellipsis
+const MAX_DISPLAY_NAME_CHARS: usize = 48;
+// Well below the 255-byte file name limit of common filesystems
+const MAX_PATH_COMPONENT_BYTES: usize = 100;
 
 // Temp directory utilities
 pub fn get_temp_dir_path() -> PathBuf {
-    std::env::temp_dir().join("flippio-db-temp")
+    let private_storage = crate::commands::settings::current_settings().temp_dir.private_storage;
+    temp_dir_root(private_storage, crate::commands::settings::app_data_dir())
 }
 
-/// Generate a unique local filename based on remote path to avoid conflicts
-/// when multiple files have the same name but come from different device locations
-pub fn generate_unique_filename(remote_path: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-    let path = Path::new(remote_path);
-    let filename = path.file_name()
-        .ok_or("Invalid remote path: no filename")?
-        .to_string_lossy();
-    
-    // Get the parent directory for uniqueness
-    let parent_dir = path.parent()
-        .map(|p| p.to_string_lossy())
-        .unwrap_or_default();
-    
-    // Create a short hash of the full path for uniqueness
-    let mut hasher = DefaultHasher::new();
-    remote_path.hash(&mut hasher);
-    let path_hash = hasher.finish();
-    
-    // Extract meaningful parent folder name for readability
-    let parent_suffix = if !parent_dir.is_empty() {
-        // Get the last meaningful directory component
-        let path_parts: Vec<&str> = parent_dir.split('/').filter(|s| !s.is_empty()).collect();
-        if let Some(last_dir) = path_parts.last() {
-            format!("_{}", last_dir)
-        } else {
-            String::new()
+/// Pulls go to the app data directory when private storage is on and the directory is known
+fn temp_dir_root(private_storage: bool, app_data_dir: Option<PathBuf>) -> PathBuf {
+    match app_data_dir.filter(|_| private_storage) {
+        Some(data_dir) => data_dir.join(PRIVATE_TEMP_DIR_NAME),
+        None => std::env::temp_dir().join("flippio-db-temp"),
+    }
+}
+
+/// Move pulled files to the temp root for a new `private_storage` setting, so turning it on
+/// doesn't leave copies behind in the shared temp directory.
+pub fn relocate_temp_root(was_private: bool, private_storage: bool) {
+    let data_dir = crate::commands::settings::app_data_dir();
+    move_temp_root(
+        &temp_dir_root(was_private, data_dir.clone()),
+        &temp_dir_root(private_storage, data_dir),
+    );
+}
+
+/// Rename `old_root` to `new_root`, or remove it when it can't be moved (across filesystems,
+/// or because `new_root` already holds pulls).
+fn move_temp_root(old_root: &Path, new_root: &Path) {
+    if old_root == new_root || !old_root.exists() {
+        return;
+    }
+
+    if !new_root.exists() {
+        if let Some(parent) = new_root.parent() {
+            let _ = fs::create_dir_all(parent);
         }
-    } else {
-        String::new()
-    };
-    
-    // Handle files with and without extensions
-    if let Some(stem) = path.file_stem().map(|s| s.to_string_lossy()) {
-        if let Some(ext) = path.extension().map(|s| s.to_string_lossy()) {
-            Ok(format!("{}{}_{:x}.{}", stem, parent_suffix, path_hash, ext))
-        } else {
-            Ok(format!("{}{}_{:x}", stem, parent_suffix, path_hash))
+        match fs::rename(old_root, new_root) {
+            Ok(()) => {
+                if let Err(e) = create_temp_root(new_root) {
+                    log::warn!("⚠️ Failed to restrict {}: {}", new_root.display(), e);
+                }
+                info!("📦 Moved pulled databases to {}", new_root.display());
+                return;
+            }
+            Err(e) => log::warn!("⚠️ Could not move pulled databases, removing them instead: {}", e),
         }
+    }
+
+    match remove_temp_dir_all(old_root) {
+        Ok(()) => info!("🗑️ Removed pulled databases from {}", old_root.display()),
+        Err(e) => log::warn!("⚠️ Failed to remove {}: {}", old_root.display(), e),
+    }
+}
+
+/// Create the temp root so only the current user can list or read it. On Windows both the
+/// temp and app data directories already live in the user's profile.
+fn create_temp_root(temp_dir: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(temp_dir)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(temp_dir, fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(())
+}
+
+fn sanitize_path_component(component: &str) -> String {
+    let sanitized: String = component
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') { c } else { '_' })
+        .collect();
+    // Never produce "." or ".." so the path cannot escape its namespace
+    if sanitized.is_empty() || sanitized.chars().all(|c| c == '.') {
+        return "_".to_string();
+    }
+    if sanitized.len() <= MAX_PATH_COMPONENT_BYTES {
+        return sanitized;
+    }
+
+    // Long names keep a readable prefix plus a hash of the original so they stay unique
+    let mut hasher = DefaultHasher::new();
+    component.hash(&mut hasher);
+    format!("{}-{:016x}", &sanitized[..MAX_PATH_COMPONENT_BYTES - 17], hasher.finish())
+}
+
+fn is_bidi_control(c: char) -> bool {
+    matches!(c, '\u{200E}' | '\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}')
+}
+
+/// Display-safe form of a device or simulator name for the UI and logs: control and bidi
+/// override characters dropped, path separators replaced, whitespace collapsed and long
+/// names shortened. Emoji and other scripts are kept. Tools are still addressed by the raw name.
+pub fn sanitize_device_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .filter(|c| !c.is_control() && !is_bidi_control(*c))
+        .map(|c| if matches!(c, '/' | '\\') { '-' } else { c })
+        .collect();
+    let collapsed = cleaned.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    if collapsed.is_empty() {
+        "Unnamed device".to_string()
+    } else if collapsed.chars().count() > MAX_DISPLAY_NAME_CHARS {
+        let shortened: String = collapsed.chars().take(MAX_DISPLAY_NAME_CHARS - 1).collect();
+        format!("{}…", shortened.trim_end())
     } else {
-        Ok(format!("{}_{:x}", filename, path_hash))
+        collapsed
     }
 }
 
+/// Local path for a pulled file, namespaced as `<device_id>/<package>/<location>/<file>`
+/// so identically named databases from different devices, apps or folders never collide.
+/// Parent directories are created.
+pub fn namespaced_temp_path(
+    temp_dir: &Path,
+    device_id: &str,
+    package_name: &str,
+    remote_path: &str,
+) -> Result<PathBuf, Box<dyn std::error::Error + Send + Sync>> {
+    let mut components = remote_path
+        .split(['/', '\\'])
+        .filter(|component| !component.is_empty() && *component != ".")
+        .collect::<Vec<_>>();
+    let filename = components.pop().ok_or("Invalid remote path: no filename")?;
+
+    let mut local_path = temp_dir
+        .join(sanitize_path_component(device_id))
+        .join(sanitize_path_component(package_name));
+    for component in components {
+        local_path.push(sanitize_path_component(component));
+    }
+    fs::create_dir_all(&local_path)?;
+
+    local_path.push(sanitize_path_component(filename));
+    Ok(local_path)
+}
+
 pub fn ensure_temp_dir() -> Result<PathBuf, Box<dyn std::error::Error + Send + Sync>> {
     let temp_dir = get_temp_dir_path();
     
-    // Only create temp directory if it doesn't exist
-    if !temp_dir.exists() {
-        fs::create_dir_all(&temp_dir)?;
-    }
+    // Also tightens the mode of a directory created by an older version
+    create_temp_root(&temp_dir)?;
+    
+    // Make room for the next pull
+    super::temp_storage::enforce_temp_quota(&temp_dir, super::temp_storage::temp_quota_bytes());
     
     Ok(temp_dir)
 }
@@ -68,12 +165,13 @@ pub fn clean_temp_dir() -> Result<PathBuf, Box<dyn std::error::Error + Send + Sy
     
     // Ensure temp directory exists
     if !temp_dir.exists() {
-        fs::create_dir_all(&temp_dir)?;
+        create_temp_root(&temp_dir)?;
         return Ok(temp_dir);
     }
     
-    // Clean only old files (older than 1 hour) to preserve active database files
-    clean_old_temp_files(&temp_dir, std::time::Duration::from_secs(3600))?;
+    // Clean only old files to preserve active database files
+    let max_age = crate::commands::settings::current_settings().temp_dir.max_file_age_seconds;
+    clean_old_temp_files(&temp_dir, std::time::Duration::from_secs(max_age))?;
     
     Ok(temp_dir)
 }
@@ -110,13 +208,23 @@ pub fn clean_old_temp_files(temp_dir: &Path, max_age: std::time::Duration) -> Re
         let entry = entry?;
         let path = entry.path();
         
+        // Descend into device/package namespaces, but leave pulled directories (which carry
+        // their own .meta.json sidecar) intact
+        if path.is_dir() && !Path::new(&format!("{}.meta.json", path.display())).exists() {
+            clean_old_temp_files(&path, max_age)?;
+            if fs::read_dir(&path).map(|mut entries| entries.next().is_none()).unwrap_or(false) {
+                let _ = fs::remove_dir(&path);
+            }
+            continue;
+        }
+        
         if path.is_file() {
             // Check file age
             if let Ok(metadata) = entry.metadata() {
                 if let Ok(modified) = metadata.modified() {
                     if let Ok(age) = now.duration_since(modified) {
                         if age > max_age {
-                            if let Err(e) = fs::remove_file(&path) {
+                            if let Err(e) = remove_temp_file(&path) {
                                 log::warn!("⚠️ Failed to remove old temp file {}: {}", path.display(), e);
                             } else {
                                 log::info!("🗑️ Cleaned old temp file: {}", path.display());
@@ -166,6 +274,66 @@ pub async fn force_clean_temp_directory() -> Result<String, String> {
     }
 }
 
+/// Overwrite a file with zeros and flush it to disk, so its contents do not linger in free
+/// blocks once it is unlinked. Journaling or copy-on-write filesystems and SSD wear levelling
+/// may still keep old copies; this only covers the common case.
+fn overwrite_file(path: &Path) -> std::io::Result<()> {
+    use std::io::Write;
+    const WIPE_BLOCK_SIZE: u64 = 1024 * 1024;
+
+    let len = fs::metadata(path)?.len();
+    let mut file = fs::OpenOptions::new().write(true).open(path)?;
+    let zeros = vec![0u8; WIPE_BLOCK_SIZE as usize];
+    let mut remaining = len;
+    while remaining > 0 {
+        let block = remaining.min(WIPE_BLOCK_SIZE);
+        file.write_all(&zeros[..block as usize])?;
+        remaining -= block;
+    }
+    file.sync_all()
+}
+
+/// Overwrite every file below `dir`; returns how many were wiped.
+fn wipe_dir_contents(dir: &Path) -> usize {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    let mut wiped = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        // Do not follow symlinks out of the temp directory
+        let Ok(file_type) = entry.file_type() else { continue };
+        if file_type.is_dir() {
+            wiped += wipe_dir_contents(&path);
+        } else if file_type.is_file() {
+            match overwrite_file(&path) {
+                Ok(()) => wiped += 1,
+                Err(e) => log::warn!("⚠️ Failed to wipe {}: {}", path.display(), e),
+            }
+        }
+    }
+    wiped
+}
+
+/// Remove a pulled file, overwriting it first when secure wipe is on.
+pub(crate) fn remove_temp_file(path: &Path) -> std::io::Result<()> {
+    if crate::commands::settings::current_settings().temp_dir.secure_wipe {
+        if let Err(e) = overwrite_file(path) {
+            log::warn!("⚠️ Failed to wipe {}: {}", path.display(), e);
+        }
+    }
+    fs::remove_file(path)
+}
+
+/// Remove a directory of pulled files, overwriting them first when secure wipe is on.
+pub(crate) fn remove_temp_dir_all(dir: &Path) -> std::io::Result<()> {
+    if crate::commands::settings::current_settings().temp_dir.secure_wipe {
+        let wiped = wipe_dir_contents(dir);
+        log::info!("🧽 Overwrote {} temp files before removing them", wiped);
+    }
+    fs::remove_dir_all(dir)
+}
+
 /// Force clean all temp files (removes ALL files and recreates directory)
 /// Use when you want to ensure completely clean state before pulling new database files
 pub fn force_clean_temp_dir() -> Result<PathBuf, Box<dyn std::error::Error + Send + Sync>> {
@@ -173,19 +341,95 @@ pub fn force_clean_temp_dir() -> Result<PathBuf, Box<dyn std::error::Error + Sen
     
     // Remove existing temp directory if it exists
     if temp_dir.exists() {
-        fs::remove_dir_all(&temp_dir)?;
+        remove_temp_dir_all(&temp_dir)?;
         log::info!("🗑️ Force cleaned entire temp directory to avoid stale data");
     }
     
     // Create fresh temp directory
-    fs::create_dir_all(&temp_dir)?;
+    create_temp_root(&temp_dir)?;
     log::info!("📁 Created fresh temp directory for database operations");
     
     Ok(temp_dir)
 }
 
+/// Pick a configured override for `tool_name`, ignoring blank entries and missing files.
+pub fn resolve_tool_override(
+    tool_paths: &std::collections::BTreeMap<String, String>,
+    tool_name: &str,
+) -> Option<PathBuf> {
+    let configured = tool_paths.get(tool_name)?.trim();
+    if configured.is_empty() {
+        return None;
+    }
+
+    let path = match (configured.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) => Path::new(&home).join(rest),
+        _ => PathBuf::from(configured),
+    };
+    if path.is_file() {
+        Some(path)
+    } else {
+        log::warn!(
+            "⚠️ Configured path for '{}' does not exist: {}, falling back to lookup",
+            tool_name,
+            path.display()
+        );
+        None
+    }
+}
+
+/// Explicit executable path for `tool_name` from the settings, if the user set one
+pub fn tool_path_override(tool_name: &str) -> Option<PathBuf> {
+    resolve_tool_override(&crate::commands::settings::current_settings().tool_paths, tool_name)
+}
+
+// iOS simulators are driven through xcrun simctl, which only exists on macOS
+pub const IOS_SIMULATORS_SUPPORTED: bool = cfg!(target_os = "macos");
+pub const SIMULATOR_UNSUPPORTED_ERROR: &str = "iOS simulators are not supported on this platform";
+
+/// Response returned by simulator commands on platforms without simctl
+pub fn simulator_unsupported_response<T>() -> super::types::DeviceResponse<T> {
+    log::warn!("⚠️ {}", SIMULATOR_UNSUPPORTED_ERROR);
+    super::types::DeviceResponse {
+        success: false,
+        data: None,
+        error_detail: Some(DeviceError::Unknown),
+        error: Some(SIMULATOR_UNSUPPORTED_ERROR.to_string()),
+    }
+}
+
+/// Text of a device tool's stdout/stderr. Bytes that aren't UTF-8 (device names in a legacy
+/// encoding, truncated multibyte sequences) become U+FFFD instead of breaking the listing;
+/// a leading BOM and Windows line endings are dropped so line parsing sees clean values.
+pub fn decode_tool_output(bytes: &[u8]) -> String {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    String::from_utf8_lossy(bytes).replace("\r\n", "\n")
+}
+
+/// Longest prefix of `text` of at most `max_bytes` that doesn't split a character
+pub fn truncate_on_char_boundary(text: &str, max_bytes: usize) -> &str {
+    if text.len() <= max_bytes {
+        return text;
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+pub fn get_xcrun_path() -> String {
+    tool_path_override("xcrun")
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_else(|| "xcrun".to_string())
+}
+
 // Helper function to get ADB executable path
 pub fn get_adb_path() -> String {
+    if let Some(path) = tool_path_override("adb") {
+        return path.to_string_lossy().to_string();
+    }
+
     // Try to find ADB in common locations
     let possible_paths = vec![
         "adb",  // System PATH
@@ -218,18 +462,16 @@ pub fn get_adb_path() -> String {
     "adb".to_string()
 }
 
-// Execute ADB command with proper error handling
+// Execute ADB command with proper error handling; hung commands time out per `CommandPolicy`
 pub async fn execute_adb_command(args: &[&str]) -> Result<std::process::Output, Box<dyn std::error::Error + Send + Sync>> {
     let adb_path = get_adb_path();
     
     info!("Executing ADB command: {} {}", adb_path, args.join(" "));
     
-    let output = tokio::process::Command::new(adb_path)
-        .args(args)
-        .output()
-        .await?;
-    
-    info!("ADB command completed with exit code: {:?}", output.status);
+    let mut command = std::process::Command::new(adb_path);
+    command.args(args);
+    // The policy runner logs the exit code, duration and device of the command
+    let output = command.output_with_policy().await?;
     
     if !output.status.success() {
         let error_msg = String::from_utf8_lossy(&output.stderr);
@@ -241,6 +483,29 @@ pub async fn execute_adb_command(args: &[&str]) -> Result<std::process::Output,
     Ok(output)
 }
 
+/// `execute_adb_command` with stdout streamed into `local_path`, for pulls that may not fit
+/// in memory.
+pub async fn execute_adb_command_to_file(
+    args: &[&str],
+    local_path: &Path,
+) -> Result<std::process::Output, Box<dyn std::error::Error + Send + Sync>> {
+    let adb_path = get_adb_path();
+
+    info!("Executing ADB command: {} {} > {}", adb_path, args.join(" "), local_path.display());
+
+    let arg_strings: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    let policy = CommandPolicy::for_command(&adb_path, &arg_strings);
+    let mut command = std::process::Command::new(adb_path);
+    command.args(args);
+    let output = run_to_file_with_policy(command, policy, local_path).await?;
+
+    if !output.status.success() {
+        error!("ADB command failed: {}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    Ok(output)
+}
+
 pub fn find_android_emulator_path() -> String {
     let possible_paths = vec![
         "emulator",  // System PATH
@@ -275,6 +540,11 @@ pub fn find_android_emulator_path() -> String {
 
 // Helper function to get libimobiledevice tool path
 pub fn get_libimobiledevice_tool_path(tool_name: &str) -> Option<std::path::PathBuf> {
+    if let Some(path) = tool_path_override(tool_name) {
+        log::info!("[libimobiledevice] Using configured '{}': {:?}", tool_name, path);
+        return Some(path);
+    }
+
     if let Ok(exe_path) = std::env::current_exe() {
         log::info!("[libimobiledevice] current_exe: {:?}", exe_path);
 
@@ -332,12 +602,100 @@ mod tests {
         LOCK.get_or_init(|| Mutex::new(()))
     }
 
+    #[test]
+    fn test_sanitize_device_name() {
+        assert_eq!(sanitize_device_name("Anna's iPhone 📱"), "Anna's iPhone 📱");
+        assert_eq!(sanitize_device_name("QA/Build\\Farm\n  #3\u{202E}"), "QA-Build-Farm #3");
+        assert_eq!(sanitize_device_name(" \t"), "Unnamed device");
+
+        let long = sanitize_device_name(&"端末".repeat(40));
+        assert_eq!(long.chars().count(), MAX_DISPLAY_NAME_CHARS);
+        assert!(long.ends_with('…'));
+    }
+
+    #[test]
+    fn test_sanitize_path_component_caps_length() {
+        let long_a = sanitize_path_component(&format!("{}a", "x".repeat(300)));
+        let long_b = sanitize_path_component(&format!("{}b", "x".repeat(300)));
+        assert_eq!(long_a.len(), MAX_PATH_COMPONENT_BYTES);
+        assert_ne!(long_a, long_b);
+        assert_eq!(sanitize_path_component("emulator-5554"), "emulator-5554");
+    }
+
+    #[test]
+    fn test_decode_tool_output() {
+        assert_eq!(decode_tool_output(b"\xEF\xBB\xBFDeviceName: iPhone\r\n"), "DeviceName: iPhone\n");
+        assert_eq!(decode_tool_output("Pixel 的 📱\n".as_bytes()), "Pixel 的 📱\n");
+        assert_eq!(decode_tool_output(b"caf\xE9\n"), "caf\u{FFFD}\n");
+    }
+
+    #[test]
+    fn test_truncate_on_char_boundary() {
+        assert_eq!(truncate_on_char_boundary("短い名前", 4), "短");
+        assert_eq!(truncate_on_char_boundary("📱ok", 2), "");
+        assert_eq!(truncate_on_char_boundary("adb", 10), "adb");
+    }
+
+    #[test]
+    fn test_resolve_tool_override() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let adb = temp_dir.path().join("adb");
+        fs::write(&adb, b"").unwrap();
+
+        let mut tool_paths = std::collections::BTreeMap::new();
+        tool_paths.insert("adb".to_string(), adb.to_string_lossy().to_string());
+        tool_paths.insert("afcclient".to_string(), "  ".to_string());
+        tool_paths.insert("idevice_id".to_string(), temp_dir.path().join("missing").to_string_lossy().to_string());
+
+        assert_eq!(resolve_tool_override(&tool_paths, "adb"), Some(adb));
+        assert_eq!(resolve_tool_override(&tool_paths, "afcclient"), None);
+        assert_eq!(resolve_tool_override(&tool_paths, "idevice_id"), None);
+        assert_eq!(resolve_tool_override(&tool_paths, "xcrun"), None);
+    }
+
     #[test]
     fn test_get_temp_dir_path() {
         let temp_dir = get_temp_dir_path();
         assert!(temp_dir.to_string_lossy().contains("flippio-db-temp"));
     }
 
+    #[test]
+    fn test_private_temp_dir_root() {
+        let data_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir_root(true, Some(data_dir.path().to_path_buf()));
+        assert_eq!(root, data_dir.path().join(PRIVATE_TEMP_DIR_NAME));
+        // Without a known app data directory pulls stay in the OS temp directory
+        assert!(temp_dir_root(true, None).ends_with("flippio-db-temp"));
+        assert!(temp_dir_root(false, Some(data_dir.path().to_path_buf())).ends_with("flippio-db-temp"));
+
+        create_temp_root(&root).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&root).unwrap().permissions().mode() & 0o777, 0o700);
+        }
+    }
+
+    #[test]
+    fn test_move_temp_root() {
+        let data_dir = tempfile::TempDir::new().unwrap();
+        let shared = data_dir.path().join("shared");
+        let private = data_dir.path().join("app").join(PRIVATE_TEMP_DIR_NAME);
+        fs::create_dir_all(shared.join("device")).unwrap();
+        fs::write(shared.join("device").join("app.db"), b"data").unwrap();
+
+        move_temp_root(&shared, &private);
+        assert!(!shared.exists());
+        assert_eq!(fs::read(private.join("device").join("app.db")).unwrap(), b"data");
+
+        // Pulls already at the destination win; the old root is removed
+        fs::create_dir_all(&shared).unwrap();
+        fs::write(shared.join("stale.db"), b"old").unwrap();
+        move_temp_root(&shared, &private);
+        assert!(!shared.exists());
+        assert!(!private.join("stale.db").exists());
+    }
+
     #[test]
     fn test_ensure_temp_dir() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let _guard = temp_dir_test_lock().lock().unwrap();
@@ -415,6 +773,76 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_wipe_dir_contents_overwrites_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let nested = temp_dir.path().join("emulator-5554").join("com.example.app");
+        fs::create_dir_all(&nested).unwrap();
+        let db = nested.join("app.db");
+        fs::write(&db, vec![0xAB; 3 * 1024 * 1024 + 17]).unwrap();
+        fs::write(temp_dir.path().join("app.db.meta.json"), b"{}").unwrap();
+
+        assert_eq!(wipe_dir_contents(temp_dir.path()), 2);
+        let content = fs::read(&db).unwrap();
+        assert_eq!(content.len(), 3 * 1024 * 1024 + 17);
+        assert!(content.iter().all(|byte| *byte == 0));
+    }
+
+    #[test]
+    fn test_namespaced_temp_path() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let temp_dir = tempfile::TempDir::new()?;
+
+        let first = namespaced_temp_path(
+            temp_dir.path(),
+            "emulator-5554",
+            "com.example.app",
+            "/data/data/com.example.app/databases/cache.db",
+        )?;
+        let second = namespaced_temp_path(
+            temp_dir.path(),
+            "00008030-001A",
+            "com.example.app",
+            "/data/data/com.example.app/databases/cache.db",
+        )?;
+
+        assert_ne!(first, second);
+        assert_eq!(
+            first,
+            temp_dir
+                .path()
+                .join("emulator-5554/com.example.app/data/data/com.example.app/databases/cache.db")
+        );
+        assert!(first.parent().unwrap().is_dir());
+
+        // Path traversal in remote paths stays inside the namespace
+        let escaped = namespaced_temp_path(temp_dir.path(), "device:1", "app", "/../../etc/passwd")?;
+        assert!(escaped.starts_with(temp_dir.path().join("device_1").join("app")));
+        assert!(namespaced_temp_path(temp_dir.path(), "device", "app", "/").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_clean_old_temp_files_descends_into_namespaces() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let nested = temp_dir.path().join("device").join("com.example.app");
+        fs::create_dir_all(&nested)?;
+
+        let old_file = nested.join("old.db");
+        fs::write(&old_file, "old")?;
+        fs::File::options()
+            .write(true)
+            .open(&old_file)?
+            .set_modified(std::time::SystemTime::now() - std::time::Duration::from_secs(7200))?;
+
+        clean_old_temp_files(temp_dir.path(), std::time::Duration::from_secs(3600))?;
+
+        assert!(!old_file.exists());
+        assert!(!temp_dir.path().join("device").exists(), "Empty namespaces are removed");
+
+        Ok(())
+    }
+
     #[test]
     fn test_get_adb_path() {
         let adb_path = get_adb_path();
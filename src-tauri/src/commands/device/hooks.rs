@@ -0,0 +1,306 @@
+//! Push hooks
+//!
+//! User-defined shell commands configured in settings that run before a database push
+//! (e.g. force-stop the app) and after a successful one (e.g. relaunch it, run tests).
+//! A failing before-push hook cancels the push. Hooks receive the push details through
+//! `FLIPPIO_*` environment variables, and the output of the last run is kept for the UI.
+
+use super::errors::DeviceError;
+use super::helpers::truncate_on_char_boundary;
+use super::types::DeviceResponse;
+use crate::commands::settings::{current_settings, PushHook, PushHookSettings};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+// Keep the UI payload bounded when a hook is chatty
+const MAX_HOOK_OUTPUT_BYTES: usize = 64 * 1024;
+
+static LAST_HOOK_RUN: LazyLock<Mutex<Vec<HookResult>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PushHookContext {
+    pub device_type: String,
+    pub device_id: String,
+    pub package_name: String,
+    pub local_path: String,
+    pub remote_path: String,
+}
+
+impl PushHookContext {
+    pub fn new(
+        device_type: &str,
+        device_id: &str,
+        package_name: &str,
+        local_path: &str,
+        remote_path: &str,
+    ) -> Self {
+        Self {
+            device_type: device_type.to_string(),
+            device_id: device_id.to_string(),
+            package_name: package_name.to_string(),
+            local_path: local_path.to_string(),
+            remote_path: remote_path.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HookResult {
+    pub name: String,
+    // "beforePush" or "afterPush"
+    pub stage: String,
+    pub command: String,
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    pub duration_ms: u64,
+    pub timed_out: bool,
+}
+
+fn truncate_output(bytes: &[u8]) -> String {
+    let text = String::from_utf8_lossy(bytes);
+    if text.len() <= MAX_HOOK_OUTPUT_BYTES {
+        return text.to_string();
+    }
+    format!("{}\n… output truncated", truncate_on_char_boundary(&text, MAX_HOOK_OUTPUT_BYTES))
+}
+
+fn shell_command(command: &str) -> tokio::process::Command {
+    #[cfg(windows)]
+    {
+        let mut cmd = tokio::process::Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    }
+
+    #[cfg(not(windows))]
+    {
+        let mut cmd = tokio::process::Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    }
+}
+
+pub async fn run_hook(hook: &PushHook, stage: &str, context: &PushHookContext) -> HookResult {
+    log::info!("🪝 Running {} hook '{}': {}", stage, hook.name, hook.command);
+    let started = Instant::now();
+
+    let mut command = shell_command(&hook.command);
+    command
+        .env("FLIPPIO_HOOK_STAGE", stage)
+        .env("FLIPPIO_DEVICE_TYPE", &context.device_type)
+        .env("FLIPPIO_DEVICE_ID", &context.device_id)
+        .env("FLIPPIO_PACKAGE_NAME", &context.package_name)
+        .env("FLIPPIO_LOCAL_PATH", &context.local_path)
+        .env("FLIPPIO_REMOTE_PATH", &context.remote_path)
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true);
+
+    let timeout = Duration::from_secs(hook.timeout_seconds.max(1));
+    let (exit_code, stdout, stderr, timed_out) = match tokio::time::timeout(timeout, command.output()).await {
+        Ok(Ok(output)) => (
+            output.status.code(),
+            truncate_output(&output.stdout),
+            truncate_output(&output.stderr),
+            false,
+        ),
+        Ok(Err(e)) => (None, String::new(), format!("Failed to start hook: {}", e), false),
+        Err(_) => (
+            None,
+            String::new(),
+            format!("Hook timed out after {} seconds", timeout.as_secs()),
+            true,
+        ),
+    };
+
+    let result = HookResult {
+        name: hook.name.clone(),
+        stage: stage.to_string(),
+        command: hook.command.clone(),
+        success: exit_code == Some(0),
+        exit_code,
+        stdout,
+        stderr,
+        duration_ms: started.elapsed().as_millis() as u64,
+        timed_out,
+    };
+    if result.success {
+        log::info!("✅ Hook '{}' finished in {} ms", result.name, result.duration_ms);
+    } else {
+        log::warn!("⚠️ Hook '{}' failed: {}", result.name, result.stderr.trim());
+    }
+    result
+}
+
+/// Run the enabled hooks of one stage in order, stopping at the first failure.
+async fn run_stage(hooks: &[PushHook], stage: &str, context: &PushHookContext) -> Vec<HookResult> {
+    let mut results = Vec::new();
+    for hook in hooks.iter().filter(|hook| hook.enabled) {
+        let result = run_hook(hook, stage, context).await;
+        let failed = !result.success;
+        results.push(result);
+        if failed {
+            break;
+        }
+    }
+    results
+}
+
+fn hook_failure_message(result: &HookResult) -> String {
+    let detail = [result.stderr.trim(), result.stdout.trim()]
+        .into_iter()
+        .find(|text| !text.is_empty())
+        .unwrap_or("no output");
+    format!("Before-push hook '{}' failed, push cancelled: {}", result.name, detail)
+}
+
+/// Wrap a push with the given hooks. `push` is only awaited when every before-push hook succeeds.
+pub async fn with_hooks<F>(
+    hooks: &PushHookSettings,
+    context: PushHookContext,
+    push: F,
+) -> (Result<DeviceResponse<String>, String>, Vec<HookResult>)
+where
+    F: Future<Output = Result<DeviceResponse<String>, String>>,
+{
+    let mut results = run_stage(&hooks.before_push, "beforePush", &context).await;
+    if let Some(failed) = results.iter().find(|result| !result.success) {
+        let response = DeviceResponse {
+            success: false,
+            data: None,
+            error_detail: Some(DeviceError::Unknown),
+            error: Some(hook_failure_message(failed)),
+        };
+        return (Ok(response), results);
+    }
+
+    let response = push.await;
+    if matches!(&response, Ok(response) if response.success) {
+        results.extend(run_stage(&hooks.after_push, "afterPush", &context).await);
+    }
+    (response, results)
+}
+
+/// Run a push command with the hooks from settings and remember the hook output.
+pub async fn with_push_hooks<F>(context: PushHookContext, push: F) -> Result<DeviceResponse<String>, String>
+where
+    F: Future<Output = Result<DeviceResponse<String>, String>>,
+{
+    let hooks = current_settings().hooks;
+    if hooks.before_push.is_empty() && hooks.after_push.is_empty() {
+        return push.await;
+    }
+
+    let (response, results) = with_hooks(&hooks, context, push).await;
+    if let Ok(mut last_run) = LAST_HOOK_RUN.lock() {
+        *last_run = results;
+    }
+    response
+}
+
+/// Output of the hooks that ran during the most recent push
+#[tauri::command]
+pub async fn get_last_push_hook_results() -> Result<DeviceResponse<Vec<HookResult>>, String> {
+    let results = LAST_HOOK_RUN
+        .lock()
+        .map(|results| results.clone())
+        .unwrap_or_default();
+
+    Ok(DeviceResponse {
+        success: true,
+        data: Some(results),
+        error: None,
+        error_detail: None,
+    })
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    fn hook(name: &str, command: &str) -> PushHook {
+        PushHook {
+            name: name.to_string(),
+            command: command.to_string(),
+            ..PushHook::default()
+        }
+    }
+
+    fn context() -> PushHookContext {
+        PushHookContext::new(
+            "android",
+            "emulator-5554",
+            "com.example.app",
+            "/tmp/app.db",
+            "/data/data/com.example.app/databases/app.db",
+        )
+    }
+
+    #[tokio::test]
+    async fn test_run_hook_captures_output_and_environment() {
+        let result = run_hook(&hook("echo", "echo \"$FLIPPIO_PACKAGE_NAME\"; echo oops >&2"), "beforePush", &context()).await;
+
+        assert!(result.success);
+        assert_eq!(result.stdout.trim(), "com.example.app");
+        assert_eq!(result.stderr.trim(), "oops");
+
+        let failed = run_hook(&hook("fail", "exit 3"), "afterPush", &context()).await;
+        assert!(!failed.success);
+        assert_eq!(failed.exit_code, Some(3));
+    }
+
+    #[tokio::test]
+    async fn test_failing_before_hook_cancels_push() {
+        let hooks = PushHookSettings {
+            before_push: vec![hook("stop app", "echo cannot stop >&2; exit 1")],
+            after_push: vec![hook("relaunch", "true")],
+        };
+        let pushed = AtomicBool::new(false);
+
+        let (response, results) = with_hooks(&hooks, context(), async {
+            pushed.store(true, Ordering::SeqCst);
+            Ok(DeviceResponse {
+                success: true,
+                data: Some("pushed".to_string()),
+                error: None,
+                error_detail: None,
+            })
+        })
+        .await;
+
+        let response = response.unwrap();
+        assert!(!response.success);
+        assert!(response.error.unwrap().contains("cannot stop"));
+        assert!(!pushed.load(Ordering::SeqCst));
+        assert_eq!(results.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_after_hooks_run_after_successful_push() {
+        let hooks = PushHookSettings {
+            before_push: vec![hook("stop app", "true")],
+            after_push: vec![hook("relaunch", "echo relaunched")],
+        };
+
+        let (response, results) = with_hooks(&hooks, context(), async {
+            Ok(DeviceResponse {
+                success: true,
+                data: Some("pushed".to_string()),
+                error: None,
+                error_detail: None,
+            })
+        })
+        .await;
+
+        assert!(response.unwrap().success);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[1].stage, "afterPush");
+        assert_eq!(results[1].stdout.trim(), "relaunched");
+    }
+}
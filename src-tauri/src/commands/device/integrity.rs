@@ -0,0 +1,375 @@
+//! Pulled file integrity
+//!
+//! Each pulled database gets a SHA-256 recorded in its `.meta.json` sidecar. Before a
+//! push the local file is checked again: edits made in Flippio are expected to change
+//! the hash, but a truncated or corrupt file is refused so it never replaces the
+//! device copy. An untouched copy of the pulled file is also kept as the base of
+//! three-way merges with the device copy. Right after a pull the same check runs in the
+//! background, so a truncated transfer is flagged before anyone starts editing it.
+
+use super::types::{DatabaseFileMetadata, PullIntegrity, PullIntegrityStatus};
+use crate::commands::database::helpers::copy_database_with_companions;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::sync::OnceLock;
+use tauri::Emitter;
+
+const SQLITE_HEADER_MAGIC: &[u8; 16] = b"SQLite format 3\0";
+const SQLITE_HEADER_LEN: usize = 100;
+const PULL_INTEGRITY_EVENT: &str = "pull-integrity-checked";
+
+static EVENT_HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PullIntegrityChecked {
+    path: String,
+    integrity: PullIntegrity,
+}
+
+/// Result of comparing a local file against the hash recorded when it was pulled
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PushIntegrity {
+    /// The file is byte-identical to what was pulled
+    Unchanged,
+    /// The file differs from the pulled copy but is still a complete database
+    Modified { pulled_sha256: String },
+    /// No hash was recorded for this file (not pulled, or pulled by an older version)
+    NoBaseline,
+}
+
+pub fn compute_file_sha256(path: &Path) -> Result<String, String> {
+    let mut file = fs::File::open(path)
+        .map_err(|e| format!("Failed to open {} for hashing: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|e| format!("Failed to read {} for hashing: {}", path.display(), e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+pub fn metadata_path_for(local_path: &Path) -> String {
+    format!("{}.meta.json", local_path.display())
+}
+
+/// Copy of the file as it was last pulled or pushed, the common ancestor for merges
+pub fn base_copy_path_for(local_path: &Path) -> String {
+    format!("{}.base", local_path.display())
+}
+
+pub fn save_base_copy(local_path: &Path) -> Result<(), String> {
+    copy_database_with_companions(local_path, Path::new(&base_copy_path_for(local_path)))
+}
+
+pub fn read_pulled_file_metadata(local_path: &Path) -> Option<DatabaseFileMetadata> {
+    let contents = fs::read_to_string(metadata_path_for(local_path)).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+/// Detect SQLite files that are shorter than their header says they should be.
+fn check_sqlite_not_truncated(path: &Path) -> Result<(), String> {
+    let mut file = fs::File::open(path)
+        .map_err(|e| format!("Cannot open {}: {}", path.display(), e))?;
+    let file_len = file
+        .metadata()
+        .map_err(|e| format!("Cannot access {}: {}", path.display(), e))?
+        .len();
+    if file_len == 0 {
+        return Err("Local file is empty".to_string());
+    }
+
+    let mut header = [0u8; SQLITE_HEADER_LEN];
+    if file.read_exact(&mut header).is_err() || &header[..16] != SQLITE_HEADER_MAGIC {
+        // Not a SQLite file (e.g. Realm); only the hash comparison applies
+        return Ok(());
+    }
+
+    let page_size = match u16::from_be_bytes([header[16], header[17]]) {
+        1 => 65536u64,
+        size => size as u64,
+    };
+    let page_count = u32::from_be_bytes([header[28], header[29], header[30], header[31]]) as u64;
+    // The in-header page count is only valid when the version-valid-for number matches the change counter
+    let page_count_valid = header[92..96] == header[24..28];
+
+    if page_count_valid && page_count > 0 && file_len < page_size * page_count {
+        return Err(format!(
+            "Local file is truncated: {} bytes on disk, header expects {}",
+            file_len,
+            page_size * page_count
+        ));
+    }
+
+    let connection = rusqlite::Connection::open_with_flags(
+        path,
+        rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY,
+    )
+    .map_err(|e| format!("Cannot open database for integrity check: {}", e))?;
+    let result: String = connection
+        .query_row("PRAGMA quick_check", [], |row| row.get(0))
+        .map_err(|e| format!("Integrity check failed: {}", e))?;
+    if result != "ok" {
+        return Err(format!("Database is corrupt: {}", result));
+    }
+
+    Ok(())
+}
+
+fn has_sqlite_header(path: &Path) -> bool {
+    let mut magic = [0u8; 16];
+    fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut magic))
+        .map(|_| &magic == SQLITE_HEADER_MAGIC)
+        .unwrap_or(false)
+}
+
+/// Truncation and `PRAGMA quick_check` of a freshly pulled file
+pub fn check_pulled_file(local_path: &Path) -> PullIntegrity {
+    let checked_at = Some(chrono::Utc::now().to_rfc3339());
+    if !has_sqlite_header(local_path) {
+        return PullIntegrity {
+            status: PullIntegrityStatus::Skipped,
+            detail: None,
+            checked_at,
+        };
+    }
+    match check_sqlite_not_truncated(local_path) {
+        Ok(()) => PullIntegrity {
+            status: PullIntegrityStatus::Ok,
+            detail: None,
+            checked_at,
+        },
+        Err(e) => PullIntegrity {
+            status: PullIntegrityStatus::Corrupt,
+            detail: Some(e),
+            checked_at,
+        },
+    }
+}
+
+/// What is known about a pulled file's integrity: the recorded result, or pending
+pub fn pull_integrity_for(local_path: &Path) -> PullIntegrity {
+    read_pulled_file_metadata(local_path)
+        .and_then(|metadata| metadata.integrity)
+        .unwrap_or_else(PullIntegrity::pending)
+}
+
+/// Send pull integrity results to the frontend; run once on startup.
+pub fn init_pull_integrity(app_handle: &tauri::AppHandle) {
+    let _ = EVENT_HANDLE.set(app_handle.clone());
+}
+
+/// Check a pulled file off the pull path, record the result in its sidecar and report it
+pub fn spawn_pull_integrity_check(local_path: &Path) {
+    let local_path = local_path.to_path_buf();
+    tauri::async_runtime::spawn_blocking(move || {
+        let integrity = check_pulled_file(&local_path);
+        match integrity.status {
+            PullIntegrityStatus::Corrupt => log::warn!(
+                "⚠️ Pulled file failed the integrity check: {} ({})",
+                local_path.display(),
+                integrity.detail.as_deref().unwrap_or("unknown")
+            ),
+            _ => log::info!("🩺 Pulled file integrity {:?}: {}", integrity.status, local_path.display()),
+        }
+
+        if let Err(e) = record_pull_integrity(&local_path, &integrity) {
+            log::warn!("⚠️ Failed to record integrity result: {}", e);
+        }
+        if let Some(app_handle) = EVENT_HANDLE.get() {
+            let payload = PullIntegrityChecked {
+                path: local_path.to_string_lossy().to_string(),
+                integrity,
+            };
+            if let Err(e) = app_handle.emit(PULL_INTEGRITY_EVENT, payload) {
+                log::warn!("⚠️ Failed to emit integrity result: {}", e);
+            }
+        }
+    });
+}
+
+fn record_pull_integrity(local_path: &Path, integrity: &PullIntegrity) -> Result<(), String> {
+    let Some(mut metadata) = read_pulled_file_metadata(local_path) else {
+        return Ok(());
+    };
+    metadata.integrity = Some(integrity.clone());
+    let json = serde_json::to_string_pretty(&metadata).map_err(|e| e.to_string())?;
+    fs::write(metadata_path_for(local_path), json)
+        .map_err(|e| format!("Failed to update metadata: {}", e))
+}
+
+/// Check a local database before it replaces the device copy.
+pub fn verify_before_push(local_path: &Path) -> Result<PushIntegrity, String> {
+    check_sqlite_not_truncated(local_path)?;
+
+    let Some(pulled_sha256) = read_pulled_file_metadata(local_path).and_then(|m| m.sha256) else {
+        return Ok(PushIntegrity::NoBaseline);
+    };
+
+    if compute_file_sha256(local_path)? == pulled_sha256 {
+        Ok(PushIntegrity::Unchanged)
+    } else {
+        Ok(PushIntegrity::Modified { pulled_sha256 })
+    }
+}
+
+/// Log the outcome of `verify_before_push`, turning failures into the push error message.
+pub fn check_push_integrity(local_path: &str) -> Result<(), String> {
+    match verify_before_push(Path::new(local_path)) {
+        Ok(PushIntegrity::Unchanged) => {
+            log::info!("✅ {} is unchanged since pull", local_path);
+            Ok(())
+        }
+        Ok(PushIntegrity::Modified { pulled_sha256 }) => {
+            log::info!("📝 {} was modified since pull (pulled sha256 {})", local_path, pulled_sha256);
+            Ok(())
+        }
+        Ok(PushIntegrity::NoBaseline) => {
+            log::info!("ℹ️ No pull hash recorded for {}", local_path);
+            Ok(())
+        }
+        Err(e) => {
+            log::warn!("⚠️ Refusing to push {}: {}", local_path, e);
+            Err(format!(
+                "Local file failed the integrity check and was not pushed to keep the device copy intact: {}",
+                e
+            ))
+        }
+    }
+}
+
+/// Record the current hash and a base copy as the new baseline, e.g. after a successful push.
+pub fn refresh_metadata_hash(local_path: &Path) -> Result<(), String> {
+    let Some(mut metadata) = read_pulled_file_metadata(local_path) else {
+        return Ok(());
+    };
+    metadata.sha256 = Some(compute_file_sha256(local_path)?);
+    // What was pushed is what the device and the local copy now have in common
+    save_base_copy(local_path)?;
+
+    let json = serde_json::to_string_pretty(&metadata).map_err(|e| e.to_string())?;
+    fs::write(metadata_path_for(local_path), json)
+        .map_err(|e| format!("Failed to update metadata: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn create_database(path: &Path) {
+        let conn = rusqlite::Connection::open(path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE items (id INTEGER PRIMARY KEY, payload TEXT);
+             WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 200)
+             INSERT INTO items (payload) SELECT hex(randomblob(64)) FROM n;",
+        )
+        .unwrap();
+    }
+
+    fn write_metadata(path: &Path, sha256: Option<String>) {
+        let metadata = DatabaseFileMetadata {
+            device_id: "emulator-5554".to_string(),
+            package_name: "com.example.app".to_string(),
+            remote_path: "/data/data/com.example.app/databases/app.db".to_string(),
+            timestamp: "2024-01-01T12:00:00Z".to_string(),
+            sha256,
+            remote_permissions: None,
+            integrity: None,
+            pull_strategy: None,
+        };
+        fs::write(metadata_path_for(path), serde_json::to_string(&metadata).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_verify_before_push_detects_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("app.db");
+        create_database(&db_path);
+
+        assert_eq!(verify_before_push(&db_path).unwrap(), PushIntegrity::NoBaseline);
+
+        write_metadata(&db_path, Some(compute_file_sha256(&db_path).unwrap()));
+        assert_eq!(verify_before_push(&db_path).unwrap(), PushIntegrity::Unchanged);
+
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        conn.execute("DELETE FROM items WHERE id = 1", []).unwrap();
+        drop(conn);
+        assert!(matches!(
+            verify_before_push(&db_path).unwrap(),
+            PushIntegrity::Modified { .. }
+        ));
+
+        refresh_metadata_hash(&db_path).unwrap();
+        assert_eq!(verify_before_push(&db_path).unwrap(), PushIntegrity::Unchanged);
+        assert_eq!(
+            compute_file_sha256(Path::new(&base_copy_path_for(&db_path))).unwrap(),
+            compute_file_sha256(&db_path).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_verify_before_push_rejects_truncated_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("app.db");
+        create_database(&db_path);
+
+        let len = fs::metadata(&db_path).unwrap().len();
+        let file = fs::OpenOptions::new().write(true).open(&db_path).unwrap();
+        file.set_len(len / 2).unwrap();
+
+        let error = verify_before_push(&db_path).unwrap_err();
+        assert!(error.contains("truncated"));
+        assert!(check_push_integrity(db_path.to_str().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_verify_before_push_rejects_empty_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("empty.db");
+        fs::write(&db_path, b"").unwrap();
+
+        assert!(verify_before_push(&db_path).is_err());
+    }
+
+    #[test]
+    fn test_check_pulled_file_flags_truncated_pull() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("app.db");
+        create_database(&db_path);
+        write_metadata(&db_path, None);
+        assert_eq!(pull_integrity_for(&db_path).status, PullIntegrityStatus::Pending);
+
+        let integrity = check_pulled_file(&db_path);
+        assert_eq!(integrity.status, PullIntegrityStatus::Ok);
+        record_pull_integrity(&db_path, &integrity).unwrap();
+        assert_eq!(pull_integrity_for(&db_path).status, PullIntegrityStatus::Ok);
+
+        let len = fs::metadata(&db_path).unwrap().len();
+        let file = fs::OpenOptions::new().write(true).open(&db_path).unwrap();
+        file.set_len(len / 2).unwrap();
+        let integrity = check_pulled_file(&db_path);
+        assert_eq!(integrity.status, PullIntegrityStatus::Corrupt);
+        assert!(integrity.detail.is_some());
+
+        let prefs_path = temp_dir.path().join("prefs.xml");
+        fs::write(&prefs_path, b"<map />").unwrap();
+        assert_eq!(check_pulled_file(&prefs_path).status, PullIntegrityStatus::Skipped);
+    }
+}
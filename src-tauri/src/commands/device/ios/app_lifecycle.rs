@@ -0,0 +1,254 @@
+//! iOS App Launch and Termination
+//!
+//! Restarts apps on physical iOS devices so they reopen a freshly pushed database.
+//! `xcrun devicectl` (Xcode 15+) is tried first since it is the only option for iOS 17+;
+//! libimobiledevice's `idevicedebug` covers older devices and non-Xcode setups.
+
+use super::super::helpers::{ensure_temp_dir, get_xcrun_path};
+use super::super::types::DeviceResponse;
+use super::super::errors::CommandErrorExt;
+use super::diagnostic::get_ios_error_help;
+use super::tools::get_tool_command_legacy;
+use log::{error, info, warn};
+use std::fs;
+use tauri_plugin_shell::ShellExt;
+use super::super::command_policy::PolicyOutputExt;
+
+/// Installation URL of `bundle_id` from `devicectl device info apps --json-output`.
+fn parse_devicectl_app_url(json: &str, bundle_id: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(json).ok()?;
+    value["result"]["apps"]
+        .as_array()?
+        .iter()
+        .find(|app| app["bundleIdentifier"].as_str() == Some(bundle_id))
+        .and_then(|app| app["url"].as_str())
+        .map(|url| url.trim_end_matches('/').to_string())
+}
+
+/// PIDs of processes whose executable lives inside the app bundle at `app_url`,
+/// from `devicectl device info processes --json-output`.
+fn parse_devicectl_pids(json: &str, app_url: &str) -> Vec<u64> {
+    let bundle_prefix = format!("{}/", app_url.trim_end_matches('/'));
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(json) else {
+        return Vec::new();
+    };
+    value["result"]["runningProcesses"]
+        .as_array()
+        .map(|processes| {
+            processes
+                .iter()
+                .filter(|process| {
+                    process["executable"]
+                        .as_str()
+                        .is_some_and(|executable| executable.starts_with(&bundle_prefix))
+                })
+                .filter_map(|process| process["processIdentifier"].as_u64())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Run `xcrun devicectl <args> --json-output <file>` and return the JSON it wrote.
+async fn run_devicectl(app_handle: &tauri::AppHandle, args: &[&str]) -> Result<String, String> {
+    let temp_dir = ensure_temp_dir().map_err(|e| format!("Failed to prepare temp directory: {}", e))?;
+    let json_path = temp_dir.join(format!("devicectl_{}.json", uuid::Uuid::new_v4()));
+    let json_path_str = json_path.to_string_lossy().to_string();
+
+    let mut full_args = vec!["devicectl"];
+    full_args.extend_from_slice(args);
+    full_args.extend_from_slice(&["--json-output", &json_path_str]);
+
+    let output = app_handle
+        .shell()
+        .command(get_xcrun_path())
+        .args(&full_args)
+        .output_with_policy()
+        .await
+        .map_err(|e| format!("Failed to execute xcrun devicectl: {}", e))?;
+    let json = fs::read_to_string(&json_path).unwrap_or_default();
+    let _ = fs::remove_file(&json_path);
+
+    if output.status.success() {
+        Ok(json)
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(format!("devicectl {} failed: {}", args.first().copied().unwrap_or_default(), stderr.trim()))
+    }
+}
+
+async fn run_idevicedebug(app_handle: &tauri::AppHandle, device_id: &str, args: &[&str]) -> Result<(), String> {
+    let mut full_args = vec!["-u", device_id];
+    full_args.extend_from_slice(args);
+    let output = app_handle
+        .shell()
+        .command(get_tool_command_legacy("idevicedebug"))
+        .args(&full_args)
+        .output_with_policy()
+        .await
+        .map_err(|e| format!("Failed to execute idevicedebug: {}", e))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = if stderr.trim().is_empty() { stdout.trim() } else { stderr.trim() };
+        Err(get_ios_error_help(message))
+    }
+}
+
+async fn devicectl_launch(app_handle: &tauri::AppHandle, device_id: &str, bundle_id: &str) -> Result<(), String> {
+    run_devicectl(
+        app_handle,
+        &["device", "process", "launch", "--device", device_id, "--terminate-existing", bundle_id],
+    )
+    .await
+    .map(|_| ())
+}
+
+/// devicectl terminates by PID, so resolve the app's bundle location and match running executables.
+async fn devicectl_terminate(app_handle: &tauri::AppHandle, device_id: &str, bundle_id: &str) -> Result<usize, String> {
+    let apps_json = run_devicectl(
+        app_handle,
+        &["device", "info", "apps", "--device", device_id, "--bundle-id", bundle_id],
+    )
+    .await?;
+    let app_url = parse_devicectl_app_url(&apps_json, bundle_id)
+        .ok_or_else(|| format!("App '{}' is not installed on the device", bundle_id))?;
+
+    let processes_json = run_devicectl(app_handle, &["device", "info", "processes", "--device", device_id]).await?;
+    let pids = parse_devicectl_pids(&processes_json, &app_url);
+    for pid in &pids {
+        let pid = pid.to_string();
+        run_devicectl(
+            app_handle,
+            &["device", "process", "terminate", "--device", device_id, "--pid", &pid],
+        )
+        .await?;
+    }
+    Ok(pids.len())
+}
+
+fn lifecycle_response(result: Result<String, String>, action: &str) -> Result<DeviceResponse<String>, String> {
+    match result {
+        Ok(message) => {
+            info!("✅ {}", message);
+            Ok(DeviceResponse {
+                success: true,
+                data: Some(message),
+                error: None,
+                error_detail: None,
+            })
+        }
+        Err(e) => {
+            error!("❌ Failed to {} iOS app: {}", action, e);
+            Ok(DeviceResponse {
+                success: false,
+                data: None,
+                error_detail: Some(e.device_error()),
+                error: Some(e),
+            })
+        }
+    }
+}
+
+/// Launch an app on a physical iOS device, restarting it if it is already running
+#[tauri::command]
+pub async fn ios_launch_app(
+    app_handle: tauri::AppHandle,
+    device_id: String,
+    bundle_id: String,
+) -> Result<DeviceResponse<String>, String> {
+    info!("🚀 Launching {} on iOS device {}", bundle_id, device_id);
+
+    let result = async {
+        let devicectl_error = if cfg!(target_os = "macos") {
+            match devicectl_launch(&app_handle, &device_id, &bundle_id).await {
+                Ok(()) => return Ok(format!("Launched {}", bundle_id)),
+                Err(e) => {
+                    warn!("⚠️ devicectl launch failed, falling back to idevicedebug: {}", e);
+                    Some(e)
+                }
+            }
+        } else {
+            None
+        };
+
+        // idevicedebug cannot replace a running instance, so stop it first; failing to do so is not fatal
+        if let Err(e) = run_idevicedebug(&app_handle, &device_id, &["kill", &bundle_id]).await {
+            info!("idevicedebug kill before launch: {}", e);
+        }
+        match run_idevicedebug(&app_handle, &device_id, &["--detach", "run", &bundle_id]).await {
+            Ok(()) => Ok(format!("Launched {}", bundle_id)),
+            Err(e) => Err(match devicectl_error {
+                Some(devicectl_error) => format!("{}; {}", devicectl_error, e),
+                None => e,
+            }),
+        }
+    }
+    .await;
+
+    lifecycle_response(result, "launch")
+}
+
+/// Terminate a running app on a physical iOS device
+#[tauri::command]
+pub async fn ios_terminate_app(
+    app_handle: tauri::AppHandle,
+    device_id: String,
+    bundle_id: String,
+) -> Result<DeviceResponse<String>, String> {
+    info!("🛑 Terminating {} on iOS device {}", bundle_id, device_id);
+
+    let result = async {
+        let devicectl_error = if cfg!(target_os = "macos") {
+            match devicectl_terminate(&app_handle, &device_id, &bundle_id).await {
+                Ok(0) => return Ok(format!("{} was not running", bundle_id)),
+                Ok(count) => return Ok(format!("Terminated {} ({} process(es))", bundle_id, count)),
+                Err(e) => {
+                    warn!("⚠️ devicectl terminate failed, falling back to idevicedebug: {}", e);
+                    Some(e)
+                }
+            }
+        } else {
+            None
+        };
+
+        match run_idevicedebug(&app_handle, &device_id, &["kill", &bundle_id]).await {
+            Ok(()) => Ok(format!("Terminated {}", bundle_id)),
+            Err(e) => Err(match devicectl_error {
+                Some(devicectl_error) => format!("{}; {}", devicectl_error, e),
+                None => e,
+            }),
+        }
+    }
+    .await;
+
+    lifecycle_response(result, "terminate")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_devicectl_app_url_and_pids() {
+        let apps = r#"{"result":{"apps":[
+            {"bundleIdentifier":"com.example.other","url":"file:///private/var/containers/Bundle/Application/B/Other.app/"},
+            {"bundleIdentifier":"com.example.notes","url":"file:///private/var/containers/Bundle/Application/A/Notes.app/"}
+        ]}}"#;
+        let app_url = parse_devicectl_app_url(apps, "com.example.notes").unwrap();
+        assert_eq!(app_url, "file:///private/var/containers/Bundle/Application/A/Notes.app");
+        assert_eq!(parse_devicectl_app_url(apps, "com.example.missing"), None);
+        assert_eq!(parse_devicectl_app_url("not json", "com.example.notes"), None);
+
+        let processes = r#"{"result":{"runningProcesses":[
+            {"executable":"file:///private/var/containers/Bundle/Application/A/Notes.app/Notes","processIdentifier":412},
+            {"executable":"file:///private/var/containers/Bundle/Application/A/Notes.app/PlugIns/Widget.appex/Widget","processIdentifier":413},
+            {"executable":"file:///private/var/containers/Bundle/Application/A/Notes.appendix/Other","processIdentifier":500},
+            {"executable":"file:///usr/libexec/backboardd","processIdentifier":1}
+        ]}}"#;
+        assert_eq!(parse_devicectl_pids(processes, &app_url), vec![412, 413]);
+        assert!(parse_devicectl_pids("{}", &app_url).is_empty());
+    }
+}
@@ -3,14 +3,26 @@
 //! This module handles database file operations for iOS devices including
 //! detection, pulling, and pushing of database files.
 
-use super::super::types::{DeviceResponse, DatabaseFile};
+use super::super::types::{DeviceResponse, DatabaseFile, DatabaseFileType};
 use super::super::helpers::clean_temp_dir;
+use super::super::hooks::{with_push_hooks, PushHookContext};
+use super::super::verification::{with_push_verification, PushedFile};
+use super::super::app_restart::with_app_stopped;
+use super::super::transfer::{TransferProgress, MAX_TRANSFER_ATTEMPTS, PUSH_PROGRESS_EVENT};
+use super::super::integrity::{check_push_integrity, pull_integrity_for, refresh_metadata_hash};
+use super::super::pull_strategy::pulled_with;
+use super::super::errors::{CommandErrorExt, DeviceError};
 use crate::commands::database::helpers::prepare_sqlite_file_for_sync;
+use crate::commands::database::{invalidate_cached_connection, DbConnectionCache};
+use crate::commands::guardrails::{check_dangerous_operation, DangerousOperation};
+use crate::commands::journal::{begin_operation, JournalOperation};
+use crate::commands::common::{check_cancelled, commit_operation, OperationKind, OPERATIONS};
 use super::file_utils::{pull_ios_db_file, IosAppAccessType};
 use super::tools::get_tool_command_legacy;
 use serde::Serialize;
-use tauri::Emitter;
+use tauri::{Emitter, Manager};
 use tauri_plugin_shell::ShellExt;
+use super::super::command_policy::PolicyOutputExt;
 use log::{info, error};
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::{LazyLock, Mutex};
@@ -27,7 +39,10 @@ static IOS_SCAN_GENERATIONS: LazyLock<Mutex<HashMap<String, u64>>> =
     LazyLock::new(|| Mutex::new(HashMap::new()));
 
 fn is_database_file(path: &str) -> bool {
-    path.ends_with(".db") || path.ends_with(".sqlite") || path.ends_with(".sqlite3")
+    path.ends_with(".db")
+        || path.ends_with(".sqlite")
+        || path.ends_with(".sqlite3")
+        || path.ends_with(".realm")
 }
 
 fn normalize_ios_dir_path(path: &str) -> String {
@@ -85,12 +100,13 @@ fn cancel_ios_scan(scan_key: &str) {
 }
 
 fn is_ios_scan_active(scan_key: &str, generation: u64) -> bool {
-    IOS_SCAN_GENERATIONS
+    let current = IOS_SCAN_GENERATIONS
         .lock()
         .expect("iOS scan registry poisoned")
         .get(scan_key)
-        .copied()
-        == Some(generation)
+        .copied();
+    // A cancelled pull operation ends its scan the same way a newer scan does
+    current == Some(generation) && check_cancelled().is_ok()
 }
 
 fn finish_ios_scan(scan_key: &str, generation: u64) {
@@ -110,7 +126,7 @@ struct IosDbScanProgressPayload {
     files: Vec<DatabaseFile>,
 }
 
-async fn list_ios_directory(
+pub(crate) async fn list_ios_directory(
     shell: &tauri_plugin_shell::Shell<tauri::Wry>,
     afcclient_cmd: &str,
     package_name: &str,
@@ -123,7 +139,7 @@ async fn list_ios_directory(
 
     let output = shell.command(afcclient_cmd)
         .args(cmd_args)
-        .output()
+        .output_with_policy()
         .await
         .map_err(|e| format!("Failed to execute afcclient: {}", e))?;
 
@@ -160,7 +176,7 @@ async fn ios_path_is_directory(
 
     let output = shell.command(afcclient_cmd)
         .args(cmd_args)
-        .output()
+        .output_with_policy()
         .await
         .map_err(|e| format!("Failed to execute afcclient: {}", e))?;
 
@@ -382,13 +398,18 @@ async fn collect_ios_database_files(
         ).await {
             Ok(local_path) => {
                 info!("✅ Successfully pulled file to: {}", local_path);
+                let integrity = pull_integrity_for(std::path::Path::new(&local_path));
+                let pull_strategy = pulled_with(std::path::Path::new(&local_path));
                 let db_file = DatabaseFile {
                     path: local_path,
                     package_name: package_name.to_string(),
+                    file_type: DatabaseFileType::from_path(&filename),
                     filename,
                     remote_path: Some(remote_path.clone()),
                     location,
                     device_type: "iphone-device".to_string(),
+                    integrity: Some(integrity),
+                    pull_strategy,
                 };
 
                 info!("Database file object created: {:?}", db_file);
@@ -399,10 +420,13 @@ async fn collect_ios_database_files(
                 let fallback_db_file = DatabaseFile {
                     path: remote_path.clone(),
                     package_name: package_name.to_string(),
+                    file_type: DatabaseFileType::from_path(&filename),
                     filename,
                     remote_path: Some(remote_path.clone()),
                     location,
                     device_type: "iphone-device".to_string(),
+                    integrity: None,
+                    pull_strategy: None,
                 };
 
                 info!("Fallback database file object created: {:?}", fallback_db_file);
@@ -479,13 +503,30 @@ fn interpolate_library_path(template: &str, package_name: &str) -> String {
     template.replace("{bundle_id}", package_name)
 }
 
-/// Get database files from iOS physical device
+/// Get database files from iOS physical device. Unlike Android and simulator scans this lists
+/// no LevelDB stores, since afcclient has no directory pull.
 #[tauri::command]
 pub async fn get_ios_device_database_files(
     app_handle: tauri::AppHandle,
     device_id: String,
     package_name: String,
     scan_request_id: Option<String>,
+) -> Result<DeviceResponse<Vec<DatabaseFile>>, String> {
+    let operation = OPERATIONS.start(
+        OperationKind::Pull,
+        format!("Pull {} databases from {}", package_name, device_id),
+        Some(&device_id),
+    );
+    operation
+        .run(pull_ios_device_database_files(app_handle, device_id, package_name, scan_request_id))
+        .await
+}
+
+async fn pull_ios_device_database_files(
+    app_handle: tauri::AppHandle,
+    device_id: String,
+    package_name: String,
+    scan_request_id: Option<String>,
 ) -> Result<DeviceResponse<Vec<DatabaseFile>>, String> {
     info!("=== GET iOS DEVICE DATABASE FILES STARTED ===");
     info!("Device ID: {}", device_id);
@@ -696,11 +737,13 @@ pub async fn get_ios_device_database_files(
     }
 
     finish_ios_scan(&scan_key, scan_generation);
+    check_cancelled()?;
     
     Ok(DeviceResponse {
         success: true,
         data: Some(database_files),
         error: None,
+        error_detail: None,
     })
 }
 
@@ -733,24 +776,31 @@ pub async fn refresh_ios_device_database_file(
         access_type,
     ).await {
         Ok(local_path) => {
+            let integrity = pull_integrity_for(std::path::Path::new(&local_path));
+            let pull_strategy = pulled_with(std::path::Path::new(&local_path));
             let db_file = DatabaseFile {
                 path: local_path,
                 package_name,
+                file_type: DatabaseFileType::from_path(&filename),
                 filename,
                 remote_path: Some(remote_path),
                 location,
                 device_type: "iphone-device".to_string(),
+                integrity: Some(integrity),
+                pull_strategy,
             };
 
             Ok(DeviceResponse {
                 success: true,
                 data: Some(db_file),
                 error: None,
+                error_detail: None,
             })
         }
         Err(error) => Ok(DeviceResponse {
             success: false,
             data: None,
+            error_detail: Some(error.device_error()),
             error: Some(error.to_string()),
         }),
     }
@@ -766,6 +816,7 @@ pub async fn cancel_ios_device_database_scan(
         success: true,
         data: Some(true),
         error: None,
+        error_detail: None,
     })
 }
 
@@ -777,6 +828,76 @@ pub async fn device_push_ios_database_file(
     local_path: String,
     package_name: String,
     remote_path: String,
+    verify: Option<bool>,
+    stop_app: Option<bool>,
+) -> Result<DeviceResponse<PushedFile>, String> {
+    if let Err(e) = check_dangerous_operation(DangerousOperation::PushDatabase) {
+        return Ok(DeviceResponse {
+            success: false,
+            data: None,
+            error_detail: Some(e.device_error()),
+            error: Some(e),
+        });
+    }
+
+    let context = PushHookContext::new("iphone-device", &device_id, &package_name, &local_path, &remote_path);
+    let push = async {
+        let response = with_push_hooks(
+            context,
+            push_ios_database_file(
+                app_handle.clone(),
+                device_id.clone(),
+                local_path.clone(),
+                package_name.clone(),
+                remote_path.clone(),
+            ),
+        )
+        .await?;
+
+        Ok(with_push_verification(
+            &app_handle,
+            "iphone-device",
+            &device_id,
+            &package_name,
+            &remote_path,
+            &local_path,
+            verify.unwrap_or(false),
+            response,
+        )
+        .await)
+    };
+
+    let journal = begin_operation(JournalOperation::Push, "iphone-device", &device_id, &package_name, &local_path, &remote_path);
+    let operation = OPERATIONS.start(
+        OperationKind::Push,
+        format!("Push {} to {}", remote_path, device_id),
+        Some(&device_id),
+    );
+    let response = operation
+        .run(with_app_stopped(
+            &app_handle,
+            "iphone-device",
+            &device_id,
+            &package_name,
+            &remote_path,
+            None,
+            stop_app.unwrap_or(false),
+            push,
+        ))
+        .await;
+    if matches!(&response, Ok(pushed) if pushed.success) {
+        journal.complete_push();
+        invalidate_cached_connection(&app_handle, &app_handle.state::<DbConnectionCache>(), &local_path, "pushed").await;
+    }
+    response
+}
+
+async fn push_ios_database_file(
+    app_handle: tauri::AppHandle,
+    device_id: String,
+    local_path: String,
+    package_name: String,
+    remote_path: String,
 ) -> Result<DeviceResponse<String>, String> {
     info!("=== PUSH iOS DATABASE FILE STARTED ===");
     info!("Device ID: {}", device_id);
@@ -793,6 +914,7 @@ pub async fn device_push_ios_database_file(
             return Ok(DeviceResponse {
                 success: false,
                 data: None,
+                error_detail: Some(DeviceError::Unknown),
                 error: Some(format!("Cannot access local file: {}", e)),
             });
         }
@@ -813,6 +935,7 @@ pub async fn device_push_ios_database_file(
         return Ok(DeviceResponse {
             success: false,
             data: None,
+            error_detail: Some(DeviceError::Unknown),
             error: Some(format!("Local file {} does not exist", local_path)),
         });
     }
@@ -823,9 +946,19 @@ pub async fn device_push_ios_database_file(
         return Ok(DeviceResponse {
             success: false,
             data: None,
+            error_detail: Some(DeviceError::Unknown),
             error: Some(format!("Failed to prepare SQLite file for sync: {}", e)),
         });
     }
+
+    if let Err(e) = check_push_integrity(&local_path) {
+        return Ok(DeviceResponse {
+            success: false,
+            data: None,
+            error_detail: Some(e.device_error()),
+            error: Some(e),
+        });
+    }
     
     info!("Step 3: Validating local file content");
     // Validate that the local file is not empty and appears to be a SQLite file
@@ -836,6 +969,7 @@ pub async fn device_push_ios_database_file(
                 return Ok(DeviceResponse {
                     success: false,
                     data: None,
+                    error_detail: Some(DeviceError::Unknown),
                     error: Some("Local file is empty".to_string()),
                 });
             }
@@ -854,6 +988,7 @@ pub async fn device_push_ios_database_file(
                             return Ok(DeviceResponse {
                                 success: false,
                                 data: None,
+                                error_detail: Some(DeviceError::Unknown),
                                 error: Some("Local file is not a valid SQLite database".to_string()),
                             });
                         }
@@ -873,11 +1008,22 @@ pub async fn device_push_ios_database_file(
             return Ok(DeviceResponse {
                 success: false,
                 data: None,
+                error_detail: Some(DeviceError::Unknown),
                 error: Some(format!("Cannot access local file: {}", e)),
             });
         }
     }
     
+    // From here on the device copy is removed and replaced, which must not stop half way
+    if let Err(e) = commit_operation() {
+        return Ok(DeviceResponse {
+            success: false,
+            data: None,
+            error_detail: Some(e.device_error()),
+            error: Some(e),
+        });
+    }
+
     info!("Step 4: Checking if file exists on device");
     let shell = app_handle.shell();
     let afcclient_cmd = get_tool_command_legacy("afcclient");
@@ -895,7 +1041,7 @@ pub async fn device_push_ios_database_file(
     
     let check_output = shell.command(&afcclient_cmd)
         .args(check_args)
-        .output()
+        .output_with_policy()
         .await
         .map_err(|e| format!("Failed to execute afcclient check: {}", e))?;
     
@@ -921,7 +1067,7 @@ pub async fn device_push_ios_database_file(
         
         let remove_output = shell.command(&afcclient_cmd)
             .args(remove_args)
-            .output()
+            .output_with_policy()
             .await
             .map_err(|e| format!("Failed to execute afcclient remove: {}", e))?;
         
@@ -939,6 +1085,7 @@ pub async fn device_push_ios_database_file(
             return Ok(DeviceResponse {
                 success: false,
                 data: None,
+                error_detail: Some(remove_output.device_error()),
                 error: Some(format!("Failed to remove existing file: {}", error_msg)),
             });
         }
@@ -956,12 +1103,33 @@ pub async fn device_push_ios_database_file(
         "put", &local_path, &remote_path
     ];
     info!("Push command: {} {}", afcclient_cmd, args.join(" "));
-    
-    let output = shell.command(&afcclient_cmd)
-        .args(args)
-        .output()
-        .await
-        .map_err(|e| format!("Failed to execute afcclient push: {}", e))?;
+
+    // afcclient cannot resume a transfer, so report start and end and retry the whole put
+    let total_bytes = std::fs::metadata(&local_path).map(|metadata| metadata.len()).unwrap_or(0);
+    let emit_progress = |bytes_transferred: u64| {
+        let progress = TransferProgress {
+            remote_path: remote_path.clone(),
+            bytes_transferred,
+            total_bytes,
+            resumed: false,
+        };
+        let _ = app_handle.emit(PUSH_PROGRESS_EVENT, progress);
+    };
+    emit_progress(0);
+
+    let mut attempt = 1;
+    let output = loop {
+        let output = shell.command(&afcclient_cmd)
+            .args(args)
+            .output_with_policy()
+            .await
+            .map_err(|e| format!("Failed to execute afcclient push: {}", e))?;
+        if output.status.success() || attempt >= MAX_TRANSFER_ATTEMPTS {
+            break output;
+        }
+        log::warn!("⚠️ afcclient put failed (attempt {}), retrying: {}", attempt, String::from_utf8_lossy(&output.stderr).trim());
+        attempt += 1;
+    };
     
     info!("afcclient push exit status: {:?}", output.status);
     if !output.stdout.is_empty() {
@@ -977,10 +1145,12 @@ pub async fn device_push_ios_database_file(
         return Ok(DeviceResponse {
             success: false,
             data: None,
+            error_detail: Some(output.device_error()),
             error: Some(format!("iOS push failed: {}", error_msg)),
         });
     }
     
+    emit_progress(total_bytes);
     info!("✅ Push command executed successfully");
     
     info!("Step 6: Verifying file was pushed successfully");
@@ -994,7 +1164,7 @@ pub async fn device_push_ios_database_file(
     
     let verify_output = shell.command(&afcclient_cmd)
         .args(verify_args)
-        .output()
+        .output_with_policy()
         .await
         .map_err(|e| format!("Failed to execute afcclient verify: {}", e))?;
     
@@ -1011,17 +1181,22 @@ pub async fn device_push_ios_database_file(
         return Ok(DeviceResponse {
             success: false,
             data: None,
+            error_detail: Some(DeviceError::TransferFailed { bytes: None }),
             error: Some("File push verification failed".to_string()),
         });
     }
     
     info!("✅ File verified successfully on device");
+    if let Err(e) = refresh_metadata_hash(std::path::Path::new(&local_path)) {
+        log::warn!("⚠️ Failed to update pull hash after push: {}", e);
+    }
     info!("=== PUSH iOS DATABASE FILE COMPLETED ===");
     
     Ok(DeviceResponse {
         success: true,
         data: Some(format!("Successfully pushed {} to {}", local_path, remote_path)),
         error: None,
+        error_detail: None,
     })
 }
 
@@ -1056,4 +1231,12 @@ mod tests {
         assert!(!matches_bundle_folder_name("/Library/Application Support", "com.example.app"));
         assert!(!matches_bundle_folder_name("/Library/app", "com.example.app"));
     }
+
+    #[test]
+    fn test_realm_files_are_listed_with_distinct_type() {
+        assert!(is_database_file("/Documents/default.realm"));
+        assert!(!is_database_file("/Documents/default.realm.lock"));
+        assert_eq!(DatabaseFileType::from_path("default.realm"), DatabaseFileType::Realm);
+        assert_eq!(DatabaseFileType::from_path("main.sqlite"), DatabaseFileType::Sqlite);
+    }
 }
@@ -4,15 +4,31 @@
 //! from connected iOS devices.
 
 use super::super::types::{DeviceResponse, Device};
+use super::super::errors::CommandErrorExt;
+use super::super::helpers::{decode_tool_output, get_xcrun_path, sanitize_device_name, truncate_on_char_boundary, IOS_SIMULATORS_SUPPORTED, SIMULATOR_UNSUPPORTED_ERROR};
 use super::tools::get_tool_command_legacy;
 use super::diagnostic::get_ios_error_help;
+use super::pairing::{classify_pairing_message, PairingStatus};
+use super::super::capabilities::forget_disconnected_capabilities;
 use tauri_plugin_shell::ShellExt;
+use super::super::command_policy::PolicyOutputExt;
+use super::super::favorites::with_device_favorites;
+use super::super::scanner::{DEVICE_LIST_TTL, DEVICE_SCANNER};
 use log::{info, error};
-use std::time::Duration;
 
-/// Get list of connected iOS devices
+/// Get list of connected iOS devices, pinned ones first; cached briefly unless `force_refresh` is set
 #[tauri::command]
-pub async fn device_get_ios_devices(app_handle: tauri::AppHandle) -> Result<DeviceResponse<Vec<Device>>, String> {
+pub async fn device_get_ios_devices(
+    app_handle: tauri::AppHandle,
+    force_refresh: Option<bool>,
+) -> Result<DeviceResponse<Vec<Device>>, String> {
+    DEVICE_SCANNER
+        .scan("ios:devices", DEVICE_LIST_TTL, force_refresh.unwrap_or(false), || scan_ios_devices(app_handle.clone()))
+        .await
+        .map(|response| with_device_favorites(&app_handle, response))
+}
+
+async fn scan_ios_devices(app_handle: tauri::AppHandle) -> Result<DeviceResponse<Vec<Device>>, String> {
     info!("=== GET iOS DEVICES STARTED ===");
     
     let shell = app_handle.shell();
@@ -21,7 +37,7 @@ pub async fn device_get_ios_devices(app_handle: tauri::AppHandle) -> Result<Devi
     // Get list of device IDs (local USB devices only)
     let output = shell.command(&idevice_id_cmd)
         .args(["-l"])
-        .output()
+        .output_with_policy()
         .await
         .map_err(|e| format!("Failed to execute idevice_id -l: {}", e))?;
 
@@ -33,11 +49,12 @@ pub async fn device_get_ios_devices(app_handle: tauri::AppHandle) -> Result<Devi
         return Ok(DeviceResponse {
             success: false,
             data: None,
+            error_detail: Some(output.device_error()),
             error: Some(error_msg.to_string()),
         });
     }
     
-    let device_ids = String::from_utf8_lossy(&output.stdout);
+    let device_ids = decode_tool_output(&output.stdout);
     info!("📱 Raw device IDs from idevice_id -l:");
     for (i, line) in device_ids.lines().enumerate() {
         info!("  Line {}: '{}'", i + 1, line);
@@ -54,32 +71,53 @@ pub async fn device_get_ios_devices(app_handle: tauri::AppHandle) -> Result<Devi
         
         info!("🔍 Processing device ID: '{}'", device_id);
         
-        // Get device name using ideviceinfo
+        // Get device name using ideviceinfo; lockdown refusing us means the device does not trust this computer
         let ideviceinfo_cmd = get_tool_command_legacy("ideviceinfo");
+        let mut pairing_status = None;
         let device_name = match shell.command(&ideviceinfo_cmd)
             .args(["-u", device_id])
-            .output()
+            .output_with_policy()
             .await 
         {
             Ok(info_result) if info_result.status.success() => {
-                let device_info = String::from_utf8_lossy(&info_result.stdout);
+                let device_info = decode_tool_output(&info_result.stdout);
                 
                 // Find DeviceName line and extract name
                 device_info
                     .lines()
-                    .find(|line| line.trim().starts_with("DeviceName: "))
-                    .map(|line| line.replace("DeviceName: ", ""))
+                    .find_map(|line| line.trim().strip_prefix("DeviceName: "))
+                    .map(|name| name.trim().to_string())
                     .unwrap_or_else(|| "iPhone Device".to_string())
             }
+            Ok(info_result) => {
+                let stderr = String::from_utf8_lossy(&info_result.stderr);
+                match classify_pairing_message(&stderr) {
+                    PairingStatus::Paired | PairingStatus::DeviceNotFound | PairingStatus::Unknown => {}
+                    status => {
+                        info!("🔐 Device {} is not trusted yet: {:?}", device_id, status);
+                        pairing_status = Some(status);
+                    }
+                }
+                "iPhone Device".to_string()
+            }
             _ => "iPhone Device".to_string()
         };
         
         let device = Device {
             id: device_id.to_string(),
             name: device_name,
+            display_name: sanitize_device_name(&device_name),
             model: "iPhone".to_string(),
             device_type: "iphone-device".to_string(),
-            description: "iPhone Device".to_string(),
+            description: match pairing_status.and_then(|status| status.instructions()) {
+                Some(instructions) => format!("iPhone Device - not trusted: {}", instructions),
+                None => "iPhone Device".to_string(),
+            },
+            api_level: None,
+            abi: None,
+            build_fingerprint: None,
+            state: pairing_status.map(|_| "untrusted".to_string()),
+            favorite: None,
         };
         
         devices.push(device);
@@ -90,11 +128,14 @@ pub async fn device_get_ios_devices(app_handle: tauri::AppHandle) -> Result<Devi
         info!("  Device {}: ID='{}', Name='{}'", i + 1, device.id, device.name);
     }
     info!("Found {} iOS devices total", devices.len());
+    let connected: Vec<&str> = devices.iter().map(|device| device.id.as_str()).collect();
+    forget_disconnected_capabilities("iphone-device", &connected);
     
     Ok(DeviceResponse {
         success: true,
         data: Some(devices),
         error: None,
+        error_detail: None,
     })
 }
 
@@ -114,16 +155,11 @@ pub async fn device_check_app_existence(
     let ideviceinstaller_cmd = get_tool_command_legacy("ideviceinstaller");
     info!("Using ideviceinstaller command: {}", ideviceinstaller_cmd);
     
-    info!("🕐 Starting ideviceinstaller with 30s timeout...");
-    let output = tokio::time::timeout(
-        Duration::from_secs(30),
-        shell.command(&ideviceinstaller_cmd)
-            .args(["-u", &device_id, "-l"])
-            .output()
-    )
-    .await
-    .map_err(|_| "ideviceinstaller command timed out after 30 seconds".to_string())?
-    .map_err(|e| format!("Failed to execute ideviceinstaller: {}", e))?;
+    let output = shell.command(&ideviceinstaller_cmd)
+        .args(["-u", &device_id, "-l"])
+        .output_with_policy()
+        .await
+        .map_err(|e| format!("Failed to execute ideviceinstaller: {}", e))?;
     
     info!("ideviceinstaller exit status: {:?}", output.status);
     
@@ -137,18 +173,18 @@ pub async fn device_check_app_existence(
         return Ok(DeviceResponse {
             success: false,
             data: None,
+            error_detail: Some(user_friendly_error.device_error()),
             error: Some(user_friendly_error),
         });
     }
     
     info!("Step 2: Parsing installed apps list");
-    let apps_output = String::from_utf8_lossy(&output.stdout);
+    let apps_output = decode_tool_output(&output.stdout);
     
     // Debug: Log the raw output
     info!("📱 Raw ideviceinstaller output for app check:");
-    info!("Output length: {} characters", apps_output.len());
-    info!("First 500 characters: {}", 
-          if apps_output.len() > 500 { &apps_output[..500] } else { &apps_output });
+    info!("Output length: {} bytes", apps_output.len());
+    info!("First 500 bytes: {}", truncate_on_char_boundary(&apps_output, 500));
     
     // Debug: Log each line with line numbers
     info!("📝 Line by line breakdown:");
@@ -181,6 +217,7 @@ pub async fn device_check_app_existence(
         success: true,
         data: Some(app_exists),
         error: None,
+        error_detail: None,
     })
 }
 
@@ -196,6 +233,7 @@ pub async fn ios_get_device_info(app_handle: tauri::AppHandle, device_id: String
                 success: true,
                 data: Some(info),
                 error: None,
+                error_detail: None,
             })
         },
         Err(e) => {
@@ -210,6 +248,7 @@ pub async fn ios_get_device_info(app_handle: tauri::AppHandle, device_id: String
             Ok(DeviceResponse {
                 success: true,
                 data: Some(mock_info),
+                error_detail: Some(e.device_error()),
                 error: Some(format!("Using mock data - real command failed: {}", e)),
             })
         }
@@ -241,13 +280,16 @@ async fn get_ios_device_detailed_info(app_handle: &tauri::AppHandle, device_id:
 // Get simulator device information using xcrun simctl
 async fn get_simulator_device_info(app_handle: &tauri::AppHandle, device_id: &str) -> Result<std::collections::HashMap<String, String>, Box<dyn std::error::Error + Send + Sync>> {
     info!("Getting simulator device info using xcrun simctl for: {}", device_id);
+    if !IOS_SIMULATORS_SUPPORTED {
+        return Err(SIMULATOR_UNSUPPORTED_ERROR.into());
+    }
     
     let shell = app_handle.shell();
     
     // First, get detailed info for this specific simulator
-    let output = shell.command("xcrun")
+    let output = shell.command(get_xcrun_path())
         .args(["simctl", "list", "--json", "devices"])
-        .output()
+        .output_with_policy()
         .await
         .map_err(|e| format!("Failed to execute xcrun simctl: {}", e))?;
     
@@ -333,16 +375,21 @@ async fn get_simulator_device_info(app_handle: &tauri::AppHandle, device_id: &st
 }
 
 // Check if a device ID corresponds to an iOS simulator by querying xcrun simctl
-async fn is_device_a_simulator(device_id: &str) -> bool {
+pub(crate) async fn is_device_a_simulator(device_id: &str) -> bool {
+    if !IOS_SIMULATORS_SUPPORTED {
+        return false;
+    }
+
     // Quick check: if it's clearly not a UUID format, it's probably not a simulator
     if device_id.len() != 36 || !device_id.contains('-') {
         return false;
     }
     
     // Use xcrun simctl to check if this device exists in the simulator list
-    match std::process::Command::new("xcrun")
+    match std::process::Command::new(get_xcrun_path())
         .args(["simctl", "list", "--json", "devices"])
-        .output()
+        .output_with_policy()
+        .await
     {
         Ok(output) => {
             if output.status.success() {
@@ -385,7 +432,7 @@ async fn get_physical_device_info(app_handle: &tauri::AppHandle, device_id: &str
     
     let output = shell.command(&ideviceinfo_cmd)
         .args(["-u", device_id])
-        .output()
+        .output_with_policy()
         .await
         .map_err(|e| format!("Failed to execute ideviceinfo: {}", e))?;
     
@@ -4,6 +4,7 @@
 use super::tools::get_tool_command_legacy;
 use serde::{Deserialize, Serialize};
 use tauri_plugin_shell::ShellExt;
+use super::super::command_policy::PolicyOutputExt;
 use log::{info, warn, error};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,7 +39,7 @@ pub async fn diagnose_ios_device(
     
     match shell.command(&ideviceinfo_cmd)
         .args(["-u", &device_id, "-k", "DeviceName"])
-        .output()
+        .output_with_policy()
         .await
     {
         Ok(output) if output.status.success() => {
@@ -67,7 +68,7 @@ pub async fn diagnose_ios_device(
         
         match shell.command(&ideviceinstaller_cmd)
             .args(["-u", &device_id, "-l"])
-            .output()
+            .output_with_policy()
             .await
         {
             Ok(output) if output.status.success() => {
@@ -109,7 +110,7 @@ pub async fn diagnose_ios_device(
         // Check device lock status
         match shell.command(&ideviceinfo_cmd)
             .args(["-u", &device_id, "-k", "PasswordProtected"])
-            .output()
+            .output_with_policy()
             .await
         {
             Ok(output) if output.status.success() => {
@@ -124,7 +125,7 @@ pub async fn diagnose_ios_device(
         // Check iOS version for Developer Mode requirement
         match shell.command(&ideviceinfo_cmd)
             .args(["-u", &device_id, "-k", "ProductVersion"])
-            .output()
+            .output_with_policy()
             .await
         {
             Ok(output) if output.status.success() => {
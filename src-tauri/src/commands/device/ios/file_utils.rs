@@ -3,24 +3,49 @@
 //! This module provides file transfer utilities and helper functions
 //! for iOS device file operations.
 
-use super::super::helpers::{ensure_temp_dir, generate_unique_filename};
+use super::super::helpers::{ensure_temp_dir, namespaced_temp_path};
+use super::super::integrity::{compute_file_sha256, save_base_copy, spawn_pull_integrity_check};
+use super::super::capabilities::cached_capabilities;
+use super::super::pull_strategy::{ios_pull_strategies, pull_with_fallbacks, PullStrategy};
 use super::super::types::{DatabaseFileMetadata};
 use super::tools::get_tool_command_legacy;
+use crate::commands::common::with_device_turn;
+use crate::commands::journal::{begin_operation, JournalOperation};
 use tauri_plugin_shell::ShellExt;
+use super::super::command_policy::PolicyOutputExt;
 use log::{info, error};
 use std::fs;
+use std::path::Path;
 use chrono;
 use serde_json;
 
 #[derive(Clone, Copy, Debug)]
 pub enum IosAppAccessType {
     Container,
+    // VendDocuments: only the Documents folder, for apps with file sharing enabled
+    Documents,
 }
 
 impl IosAppAccessType {
     pub(crate) fn afcclient_args<'a>(&self, package_name: &'a str) -> [&'a str; 2] {
         match self {
             Self::Container => ["--container", package_name],
+            Self::Documents => ["--documents", package_name],
+        }
+    }
+
+    /// `remote_path` (relative to the app container) as seen through this access type
+    pub(crate) fn afc_path<'a>(&self, remote_path: &'a str) -> &'a str {
+        match self {
+            Self::Container => remote_path,
+            Self::Documents => remote_path.strip_prefix("/Documents").unwrap_or(remote_path),
+        }
+    }
+
+    fn for_strategy(strategy: PullStrategy, primary: IosAppAccessType) -> Self {
+        match strategy {
+            PullStrategy::AfcDocuments => Self::Documents,
+            _ => primary,
         }
     }
 }
@@ -53,6 +78,99 @@ fn afcclient_output_indicates_failure(stdout: &[u8], stderr: &[u8]) -> Option<St
     None
 }
 
+/// Copy `remote_path` to `local_path` with afcclient through `access_type`; a failed attempt
+/// leaves no file behind.
+async fn afcclient_pull(
+    app_handle: &tauri::AppHandle,
+    device_id: &str,
+    package_name: &str,
+    remote_path: &str,
+    local_path: &Path,
+    access_type: IosAppAccessType,
+) -> Result<(), String> {
+    let afcclient_cmd = get_tool_command_legacy("afcclient");
+    info!("Using afcclient command: {}", afcclient_cmd);
+    
+    // Use afcclient to pull file from device
+    let local_path_str = local_path.to_string_lossy();
+    let access_args = access_type.afcclient_args(package_name);
+    let args = [
+        access_args[0], access_args[1],
+        "-u", device_id,
+        "get", access_type.afc_path(remote_path), &local_path_str
+    ];
+    info!("Pull command: {} {}", afcclient_cmd, args.join(" "));
+    
+    let shell = app_handle.shell();
+    
+    let output = shell.command(&afcclient_cmd)
+        .args(args)
+        .output_with_policy()
+        .await
+        .map_err(|e| format!("Failed to execute afcclient: {}", e))?;
+    
+    info!("afcclient exit status: {:?}", output.status);
+    if !output.stdout.is_empty() {
+        info!("afcclient stdout: {}", String::from_utf8_lossy(&output.stdout));
+    }
+    if !output.stderr.is_empty() {
+        info!("afcclient stderr: {}", String::from_utf8_lossy(&output.stderr));
+    }
+    
+    let failure = if !output.status.success() {
+        let error_msg = String::from_utf8_lossy(&output.stderr).to_string();
+        error!("❌ afcclient command failed: {}", error_msg);
+        Some(error_msg)
+    } else if let Some(error_msg) = afcclient_output_indicates_failure(&output.stdout, &output.stderr) {
+        error!("❌ afcclient reported pull failure despite success status: {}", error_msg);
+        Some(error_msg)
+    } else {
+        None
+    };
+
+    match failure {
+        Some(error_msg) => {
+            let _ = fs::remove_file(local_path);
+            Err(format!("iOS pull failed: {}", error_msg))
+        }
+        None => Ok(()),
+    }
+}
+
+/// Pull `remote_path` into `local_path` with the first strategy that works
+async fn pull_ios_file(
+    app_handle: &tauri::AppHandle,
+    device_id: &str,
+    package_name: &str,
+    remote_path: &str,
+    access_type: IosAppAccessType,
+    local_path: &Path,
+) -> Result<PullStrategy, String> {
+    let strategies = ios_pull_strategies(remote_path, cached_capabilities(device_id).as_ref());
+    pull_with_fallbacks(&strategies, move |strategy| {
+        let access_type = IosAppAccessType::for_strategy(strategy, access_type);
+        afcclient_pull(app_handle, device_id, package_name, remote_path, local_path, access_type)
+    })
+    .await
+}
+
+/// Pull a file from a physical device into `local_path` without touching the temp copy,
+/// its metadata or base copy
+pub(crate) async fn pull_ios_db_file_to(
+    app_handle: &tauri::AppHandle,
+    device_id: &str,
+    package_name: &str,
+    remote_path: &str,
+    access_type: IosAppAccessType,
+    local_path: &Path,
+) -> Result<PullStrategy, String> {
+    with_device_turn(
+        device_id,
+        pull_ios_file(app_handle, device_id, package_name, remote_path, access_type, local_path),
+    )
+    .await
+}
+
 /// Pull iOS database file to local temp directory
 pub async fn pull_ios_db_file(
     app_handle: &tauri::AppHandle,
@@ -61,6 +179,23 @@ pub async fn pull_ios_db_file(
     remote_path: &str,
     is_device: bool,
     access_type: IosAppAccessType,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let pull = pull_ios_db_file_in_turn(app_handle, device_id, package_name, remote_path, is_device, access_type);
+    // Overlapping afcclient sessions on one device corrupt each other; simulators copy locally
+    if is_device {
+        with_device_turn(device_id, pull).await
+    } else {
+        pull.await
+    }
+}
+
+async fn pull_ios_db_file_in_turn(
+    app_handle: &tauri::AppHandle,
+    device_id: &str,
+    package_name: &str,
+    remote_path: &str,
+    is_device: bool,
+    access_type: IosAppAccessType,
 ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
     info!("=== PULL iOS DB FILE STARTED ===");
     info!("Device ID: {}", device_id);
@@ -72,17 +207,21 @@ pub async fn pull_ios_db_file(
     let temp_dir = ensure_temp_dir()?;
     info!("✅ Temp directory: {}", temp_dir.display());
     
-    info!("Step 2: Generating unique filename from remote path");
-    // Generate unique filename to avoid conflicts when multiple files have the same name
-    let unique_filename = generate_unique_filename(remote_path)?;
-    info!("✅ Generated unique filename: {}", unique_filename);
-    
-    info!("Step 3: Creating local file path");
-    let local_path = temp_dir.join(&unique_filename);
+    info!("Step 2: Creating namespaced local file path");
+    // Namespace by device, app and location so files with the same name never collide
+    let local_path = namespaced_temp_path(&temp_dir, device_id, package_name, remote_path)?;
     info!("✅ Local path: {}", local_path.display());
+    let _journal = begin_operation(
+        JournalOperation::Pull,
+        if is_device { "iphone-device" } else { "simulator" },
+        device_id,
+        package_name,
+        &local_path.to_string_lossy(),
+        remote_path,
+    );
 
     if local_path.exists() {
-        info!("Step 3a: Removing existing local temp file before pull");
+        info!("Step 2a: Removing existing local temp file before pull");
         fs::remove_file(&local_path)
             .map_err(|e| format!("Failed to remove stale temp file {}: {}", local_path.display(), e))?;
         let metadata_path = format!("{}.meta.json", local_path.display());
@@ -93,51 +232,15 @@ pub async fn pull_ios_db_file(
         }
     }
     
-    if is_device {
+    let strategy = if is_device {
         info!("Step 4: Pulling from physical iOS device using afcclient");
-        let afcclient_cmd = get_tool_command_legacy("afcclient");
-        info!("Using afcclient command: {}", afcclient_cmd);
-        
-        // Use afcclient to pull file from device
-        let local_path_str = local_path.to_string_lossy();
-        let access_args = access_type.afcclient_args(package_name);
-        let args = [
-            access_args[0], access_args[1],
-            "-u", device_id,
-            "get", remote_path, &local_path_str
-        ];
-        info!("Pull command: {} {}", afcclient_cmd, args.join(" "));
-        
-        let shell = app_handle.shell();
-        
-        let output = shell.command(&afcclient_cmd)
-            .args(args)
-            .output()
-            .await
-            .map_err(|e| format!("Failed to execute afcclient: {}", e))?;
-        
-        info!("afcclient exit status: {:?}", output.status);
-        if !output.stdout.is_empty() {
-            info!("afcclient stdout: {}", String::from_utf8_lossy(&output.stdout));
-        }
-        if !output.stderr.is_empty() {
-            info!("afcclient stderr: {}", String::from_utf8_lossy(&output.stderr));
-        }
-        
-        if !output.status.success() {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
-            error!("❌ afcclient command failed: {}", error_msg);
-            return Err(format!("iOS pull failed: {}", error_msg).into());
-        }
-
-        if let Some(error_msg) = afcclient_output_indicates_failure(&output.stdout, &output.stderr) {
-            error!("❌ afcclient reported pull failure despite success status: {}", error_msg);
-            return Err(format!("iOS pull failed: {}", error_msg).into());
-        }
+        let strategy = pull_ios_file(app_handle, device_id, package_name, remote_path, access_type, &local_path).await?;
+        info!("✅ Pulled with strategy: {}", strategy.label());
+        strategy
     } else {
         error!("❌ Simulator file pulling should use different method");
         return Err("Invalid device type for this function".into());
-    }
+    };
     
     info!("✅ Pull command executed successfully");
     
@@ -184,6 +287,10 @@ pub async fn pull_ios_db_file(
         package_name: package_name.to_string(),
         remote_path: remote_path.to_string(),
         timestamp: chrono::Utc::now().to_rfc3339(),
+        sha256: compute_file_sha256(&local_path).ok(),
+        remote_permissions: None,
+        integrity: None,
+        pull_strategy: Some(strategy),
     };
     
     let metadata_path = format!("{}.meta.json", local_path.display());
@@ -203,6 +310,10 @@ pub async fn pull_ios_db_file(
             // Don't fail the entire operation for metadata serialization failure
         }
     }
+    if let Err(e) = save_base_copy(&local_path) {
+        error!("⚠️  Failed to keep a base copy for merges: {}", e);
+    }
+    spawn_pull_integrity_check(&local_path);
     
     let final_path = local_path.to_string_lossy().to_string();
     info!("✅ File pull completed successfully: {}", final_path);
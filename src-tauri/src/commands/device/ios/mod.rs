@@ -3,6 +3,8 @@
 //! This module provides comprehensive iOS device management functionality including:
 //! - Device detection and information
 //! - Package and application management  
+//! - App launch and termination
+//! - Pairing and trust
 //! - Database file operations
 //! - Simulator support
 //! - File transfer utilities
@@ -11,12 +13,15 @@
 pub mod common;
 pub mod device;
 pub mod packages;
+pub mod app_lifecycle;
+pub mod pairing;
 pub mod simulator;
 pub mod database;
 pub mod file_utils;
 pub mod tools;
 pub mod tool_validation;
 pub mod diagnostic;
+pub mod quarantine;
 
 #[cfg(test)]
 pub mod tests;
@@ -24,6 +29,8 @@ pub mod tests;
 // Public exports for command registration
 pub use device::*;
 pub use packages::*;
+pub use app_lifecycle::*;
+pub use pairing::*;
 pub use simulator::*; 
 pub use database::*;
 // Tools commands available but not auto-exported (can be used via direct module path)
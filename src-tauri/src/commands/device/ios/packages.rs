@@ -4,23 +4,47 @@
 //! simulators and physical devices.
 
 use super::super::types::{DeviceResponse, Package};
+use super::super::errors::CommandErrorExt;
+use super::super::helpers::{decode_tool_output, ensure_temp_dir, get_xcrun_path, simulator_unsupported_response, truncate_on_char_boundary, IOS_SIMULATORS_SUPPORTED};
 use super::tools::get_tool_command_legacy;
 use super::diagnostic::get_ios_error_help;
 use tauri_plugin_shell::ShellExt;
+use super::super::command_policy::PolicyOutputExt;
+use super::super::favorites::with_package_favorites;
+use super::super::scanner::{packages_scan_key, DEVICE_SCANNER, PACKAGE_LIST_TTL};
 use log::{info, error};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
 
-/// Get list of iOS packages (for simulators)
+/// Get list of iOS packages (for simulators), pinned ones first; cached unless `force_refresh` is set
 #[tauri::command]
-pub async fn device_get_ios_packages(app_handle: tauri::AppHandle, device_id: String) -> Result<DeviceResponse<Vec<Package>>, String> {
+pub async fn device_get_ios_packages(
+    app_handle: tauri::AppHandle,
+    device_id: String,
+    force_refresh: Option<bool>,
+) -> Result<DeviceResponse<Vec<Package>>, String> {
+    DEVICE_SCANNER
+        .scan(&packages_scan_key(&device_id), PACKAGE_LIST_TTL, force_refresh.unwrap_or(false), || {
+            scan_ios_simulator_packages(app_handle.clone(), device_id.clone())
+        })
+        .await
+        .map(|response| with_package_favorites(&app_handle, response))
+}
+
+async fn scan_ios_simulator_packages(app_handle: tauri::AppHandle, device_id: String) -> Result<DeviceResponse<Vec<Package>>, String> {
     info!("=== GET iOS PACKAGES STARTED (SIMULATOR) ===");
+    if !IOS_SIMULATORS_SUPPORTED {
+        return Ok(simulator_unsupported_response());
+    }
     info!("Device ID (Simulator): {}", device_id);
     
     info!("Step 1: Using xcrun simctl to get installed apps");
     let shell = app_handle.shell();
     
-    let output = shell.command("xcrun")
+    let output = shell.command(get_xcrun_path())
         .args(["simctl", "listapps", &device_id])
-        .output()
+        .output_with_policy()
         .await
         .map_err(|e| format!("Failed to execute xcrun simctl listapps: {}", e))?;
     
@@ -32,17 +56,17 @@ pub async fn device_get_ios_packages(app_handle: tauri::AppHandle, device_id: St
         return Ok(DeviceResponse {
             success: false,
             data: None,
+            error_detail: Some(output.device_error()),
             error: Some(error_msg.to_string()),
         });
     }
     
     info!("Step 2: Parsing simulator apps output");
-    let apps_output = String::from_utf8_lossy(&output.stdout);
+    let apps_output = decode_tool_output(&output.stdout);
     let mut packages = Vec::new();
     
     // Log the raw output for debugging
-    info!("Raw simctl output (first 200 chars): {}", 
-          if apps_output.len() > 200 { &apps_output[..200] } else { &apps_output });
+    info!("Raw simctl output (first 200 bytes): {}", truncate_on_char_boundary(&apps_output, 200));
     
     // Parse plist-like output from simctl listapps
     // Format: "bundle.id" = { key = value; ... };
@@ -73,6 +97,7 @@ pub async fn device_get_ios_packages(app_handle: tauri::AppHandle, device_id: St
                 let package = Package {
                     name: clean_app_name.clone(),
                     bundle_id: clean_bundle_id.clone(),
+                    favorite: None,
                 };
                 
                 info!("Found app: {} ({})", package.name, package.bundle_id);
@@ -146,6 +171,7 @@ pub async fn device_get_ios_packages(app_handle: tauri::AppHandle, device_id: St
             let package = Package {
                 name: clean_app_name.clone(),
                 bundle_id: clean_bundle_id.clone(),
+                favorite: None,
             };
             
             info!("Found app: {} ({})", package.name, package.bundle_id);
@@ -160,12 +186,26 @@ pub async fn device_get_ios_packages(app_handle: tauri::AppHandle, device_id: St
         success: true,
         data: Some(packages),
         error: None,
+        error_detail: None,
     })
 }
 
-/// Get list of iOS packages from physical device
+/// Get list of iOS packages from physical device, pinned ones first; cached unless `force_refresh` is set
 #[tauri::command]
-pub async fn device_get_ios_device_packages(app_handle: tauri::AppHandle, device_id: String) -> Result<DeviceResponse<Vec<Package>>, String> {
+pub async fn device_get_ios_device_packages(
+    app_handle: tauri::AppHandle,
+    device_id: String,
+    force_refresh: Option<bool>,
+) -> Result<DeviceResponse<Vec<Package>>, String> {
+    DEVICE_SCANNER
+        .scan(&packages_scan_key(&device_id), PACKAGE_LIST_TTL, force_refresh.unwrap_or(false), || {
+            scan_ios_device_packages(app_handle.clone(), device_id.clone())
+        })
+        .await
+        .map(|response| with_package_favorites(&app_handle, response))
+}
+
+async fn scan_ios_device_packages(app_handle: tauri::AppHandle, device_id: String) -> Result<DeviceResponse<Vec<Package>>, String> {
     info!("=== GET iOS DEVICE PACKAGES STARTED ===");
     info!("Device ID: {}", device_id);
     
@@ -177,7 +217,7 @@ pub async fn device_get_ios_device_packages(app_handle: tauri::AppHandle, device
     info!("Step 1: Trying XML mode for faster parsing");
     let xml_output = shell.command(&ideviceinstaller_cmd)
         .args(["-u", &device_id, "-l", "-o", "xml"])
-        .output()
+        .output_with_policy()
         .await
         .map_err(|e| format!("Failed to execute ideviceinstaller: {}", e))?;
     
@@ -196,6 +236,7 @@ pub async fn device_get_ios_device_packages(app_handle: tauri::AppHandle, device
                     success: true,
                     data: Some(packages),
                     error: None,
+                    error_detail: None,
                 });
             },
             Ok(_) => {
@@ -214,7 +255,7 @@ pub async fn device_get_ios_device_packages(app_handle: tauri::AppHandle, device
     info!("Step 2: Fallback to regular text parsing mode");
     let output = shell.command(&ideviceinstaller_cmd)
         .args(["-u", &device_id, "-l"])
-        .output()
+        .output_with_policy()
         .await
         .map_err(|e| format!("Failed to execute ideviceinstaller: {}", e))?;
     
@@ -230,6 +271,7 @@ pub async fn device_get_ios_device_packages(app_handle: tauri::AppHandle, device
         return Ok(DeviceResponse {
             success: false,
             data: None,
+            error_detail: Some(user_friendly_error.device_error()),
             error: Some(user_friendly_error),
         });
     }
@@ -249,9 +291,128 @@ pub async fn device_get_ios_device_packages(app_handle: tauri::AppHandle, device
         success: true,
         data: Some(packages),
         error: None,
+        error_detail: None,
     })
 }
 
+/// Extract the `Payload/<Name>.app` bundle of an .ipa into `dest_dir`; `simctl install`
+/// only accepts .app directories.
+fn extract_ipa_app(ipa_path: &str, dest_dir: &Path) -> Result<PathBuf, String> {
+    let file = fs::File::open(ipa_path).map_err(|e| format!("Failed to open IPA: {}", e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("Failed to read IPA: {}", e))?;
+
+    let mut app_dir: Option<PathBuf> = None;
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|e| format!("Failed to read IPA entry: {}", e))?;
+        // enclosed_name rejects absolute paths and `..` components
+        let Some(relative) = entry.enclosed_name() else {
+            continue;
+        };
+        let mut components = relative.components();
+        let is_payload = components.next().is_some_and(|c| c.as_os_str() == "Payload");
+        let Some(app_name) = components.next().map(|c| c.as_os_str().to_owned()) else {
+            continue;
+        };
+        if !is_payload || !Path::new(&app_name).extension().is_some_and(|ext| ext == "app") {
+            continue;
+        }
+
+        app_dir.get_or_insert_with(|| dest_dir.join("Payload").join(&app_name));
+        let target = dest_dir.join(&relative);
+        if entry.is_dir() {
+            fs::create_dir_all(&target).map_err(|e| format!("Failed to create '{}': {}", target.display(), e))?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create '{}': {}", parent.display(), e))?;
+        }
+        let mut output = fs::File::create(&target).map_err(|e| format!("Failed to create '{}': {}", target.display(), e))?;
+        io::copy(&mut entry, &mut output).map_err(|e| format!("Failed to extract '{}': {}", target.display(), e))?;
+    }
+
+    app_dir.ok_or_else(|| format!("No Payload/*.app bundle found in '{}'", ipa_path))
+}
+
+/// Install an .ipa on a physical device (ideviceinstaller) or an .ipa/.app on a simulator (simctl)
+#[tauri::command]
+pub async fn ios_install_ipa(
+    app_handle: tauri::AppHandle,
+    device_id: String,
+    ipa_path: String,
+    device_type: String,
+) -> Result<DeviceResponse<String>, String> {
+    info!("📲 Installing {} on iOS {} {}", ipa_path, device_type, device_id);
+    let is_simulator = device_type == "simulator";
+    if is_simulator && !IOS_SIMULATORS_SUPPORTED {
+        return Ok(simulator_unsupported_response());
+    }
+
+    let result = async {
+        if !Path::new(&ipa_path).exists() {
+            return Err(format!("App not found: {}", ipa_path));
+        }
+        let shell = app_handle.shell();
+
+        let output = if is_simulator {
+            let app_path = if Path::new(&ipa_path).is_dir() {
+                PathBuf::from(&ipa_path)
+            } else {
+                let temp_dir = ensure_temp_dir().map_err(|e| format!("Failed to prepare temp directory: {}", e))?;
+                let dest_dir = temp_dir.join("ipa_install").join(&device_id);
+                if dest_dir.exists() {
+                    fs::remove_dir_all(&dest_dir).map_err(|e| format!("Failed to clean '{}': {}", dest_dir.display(), e))?;
+                }
+                extract_ipa_app(&ipa_path, &dest_dir)?
+            };
+            shell.command(get_xcrun_path())
+                .args(["simctl", "install", &device_id, &app_path.to_string_lossy()])
+                .output_with_policy()
+                .await
+                .map_err(|e| format!("Failed to execute xcrun simctl install: {}", e))?
+        } else {
+            shell.command(get_tool_command_legacy("ideviceinstaller"))
+                .args(["-u", &device_id, "-i", &ipa_path])
+                .output_with_policy()
+                .await
+                .map_err(|e| format!("Failed to execute ideviceinstaller: {}", e))?
+        };
+
+        // ideviceinstaller reports some failures on stdout with a zero exit code
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !output.status.success() || stdout.contains("ERROR") {
+            let message = if stderr.trim().is_empty() { stdout.trim() } else { stderr.trim() };
+            return Err(if is_simulator { message.to_string() } else { get_ios_error_help(message) });
+        }
+        Ok(format!("Installed {}", ipa_path))
+    }
+    .await;
+
+    match result {
+        Ok(message) => {
+            info!("✅ {} on {}", message, device_id);
+            DEVICE_SCANNER.invalidate(&packages_scan_key(&device_id));
+            Ok(DeviceResponse {
+                success: true,
+                data: Some(message),
+                error: None,
+                error_detail: None,
+            })
+        }
+        Err(e) => {
+            error!("❌ iOS install failed: {}", e);
+            Ok(DeviceResponse {
+                success: false,
+                data: None,
+                error_detail: Some(e.device_error()),
+                error: Some(e),
+            })
+        }
+    }
+}
+
 /// Parse iOS apps from XML plist output
 fn parse_ios_apps_xml(xml_content: &str) -> Result<Vec<Package>, String> {
     let mut packages = Vec::new();
@@ -340,6 +501,7 @@ fn parse_ios_apps_xml(xml_content: &str) -> Result<Vec<Package>, String> {
                     let package = Package {
                         name: clean_app_name.clone(),
                         bundle_id: clean_bundle_id.clone(),
+                        favorite: None,
                     };
                     
                     info!("✅ Found app: {} ({})", package.name, package.bundle_id);
@@ -401,6 +563,7 @@ fn parse_ios_apps_text(text_content: &str) -> Result<Vec<Package>, String> {
                     let package = Package {
                         name: clean_app_name.clone(),
                         bundle_id: clean_bundle_id.clone(),
+                        favorite: None,
                     };
                     
                     info!("✅ Found app: {} ({})", package.name, package.bundle_id);
@@ -613,4 +776,31 @@ com.example.weather, "7.1", "Weather Now"
         assert_eq!(parsed.0, "com.example.reader");
         assert_eq!(parsed.1, "Reader (3.4)");
     }
+
+    #[test]
+    fn test_extract_ipa_app_copies_payload_bundle() {
+        use std::io::Write;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let ipa_path = temp_dir.path().join("Reader.ipa");
+        let mut writer = zip::ZipWriter::new(fs::File::create(&ipa_path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        writer.start_file("Payload/Reader.app/Info.plist", options).unwrap();
+        writer.write_all(b"plist").unwrap();
+        writer.start_file("Payload/Reader.app/Frameworks/Core.dylib", options).unwrap();
+        writer.write_all(b"dylib").unwrap();
+        writer.start_file("iTunesMetadata.plist", options).unwrap();
+        writer.write_all(b"metadata").unwrap();
+        writer.finish().unwrap();
+
+        let dest_dir = temp_dir.path().join("out");
+        let app_dir = extract_ipa_app(&ipa_path.to_string_lossy(), &dest_dir).unwrap();
+        assert_eq!(app_dir, dest_dir.join("Payload").join("Reader.app"));
+        assert_eq!(fs::read(app_dir.join("Frameworks/Core.dylib")).unwrap(), b"dylib");
+        assert!(!dest_dir.join("iTunesMetadata.plist").exists());
+
+        let empty_path = temp_dir.path().join("Empty.ipa");
+        zip::ZipWriter::new(fs::File::create(&empty_path).unwrap()).finish().unwrap();
+        assert!(extract_ipa_app(&empty_path.to_string_lossy(), &dest_dir).is_err());
+    }
 }
@@ -0,0 +1,214 @@
+//! iOS Pairing and Trust
+//!
+//! A physical device only answers lockdown requests once it trusts this computer. Until then
+//! `ideviceinfo` and `ideviceinstaller` fail and the device shows up without apps. These
+//! commands wrap `idevicepair pair`/`validate` and tell the user what to do on the device.
+
+use super::super::command_policy::PolicyOutputExt;
+use super::super::types::DeviceResponse;
+use super::super::errors::CommandErrorExt;
+use super::tools::get_tool_command_legacy;
+use log::{error, info, warn};
+use serde::Serialize;
+use tauri_plugin_shell::ShellExt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PairingStatus {
+    Paired,
+    /// The "Trust This Computer?" dialog is showing and has not been answered yet
+    AwaitingTrust,
+    /// The device is locked with a passcode, so the dialog cannot be shown
+    PasscodeLocked,
+    UserDenied,
+    NotPaired,
+    DeviceNotFound,
+    Unknown,
+}
+
+impl PairingStatus {
+    pub fn instructions(&self) -> Option<&'static str> {
+        Some(match self {
+            PairingStatus::Paired => return None,
+            PairingStatus::AwaitingTrust => {
+                "Tap \"Trust\" in the \"Trust This Computer?\" dialog on the device and enter its passcode, then pair again"
+            }
+            PairingStatus::PasscodeLocked => "Unlock the device so it can show the trust dialog, then pair again",
+            PairingStatus::UserDenied => {
+                "The trust dialog was declined. Reset it in Settings > General > Transfer or Reset > Reset > Reset Location & Privacy, then reconnect and pair again"
+            }
+            PairingStatus::NotPaired => "Pair the device and accept the trust dialog on it",
+            PairingStatus::DeviceNotFound => "Connect the device with a USB cable and unlock it",
+            PairingStatus::Unknown => "Unlock the device, reconnect the cable and try pairing again",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PairingResult {
+    pub status: PairingStatus,
+    pub paired: bool,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<&'static str>,
+}
+
+/// Classify lockdown/pairing messages from idevicepair, ideviceinfo and friends.
+pub fn classify_pairing_message(message: &str) -> PairingStatus {
+    let lower = message.to_lowercase();
+    let has = |needles: &[&str]| needles.iter().any(|needle| lower.contains(needle));
+
+    if has(&["success: paired", "success: validated"]) {
+        PairingStatus::Paired
+    } else if has(&["accept the trust dialog", "pairing dialog response pending", "pairing_dialog_response_pending"]) {
+        PairingStatus::AwaitingTrust
+    } else if has(&["passcode is set", "passwordprotected", "password_protected", "enter the passcode"]) {
+        PairingStatus::PasscodeLocked
+    } else if has(&["denied the trust dialog", "user denied pairing", "user_denied_pairing"]) {
+        PairingStatus::UserDenied
+    } else if has(&["not paired", "invalid hostid", "invalid_host_id", "invalidhostid", "pairing record"]) {
+        PairingStatus::NotPaired
+    } else if has(&["no device found", "not found"]) {
+        PairingStatus::DeviceNotFound
+    } else {
+        PairingStatus::Unknown
+    }
+}
+
+fn parse_idevicepair_output(success: bool, stdout: &str, stderr: &str) -> PairingResult {
+    let message = [stdout.trim(), stderr.trim()]
+        .iter()
+        .filter(|part| !part.is_empty())
+        .copied()
+        .collect::<Vec<_>>()
+        .join("\n");
+    let status = match classify_pairing_message(&message) {
+        // Exit status wins over wording we do not recognise
+        PairingStatus::Unknown if success => PairingStatus::Paired,
+        status => status,
+    };
+    PairingResult {
+        status,
+        paired: status == PairingStatus::Paired,
+        message,
+        instructions: status.instructions(),
+    }
+}
+
+async fn run_idevicepair(
+    app_handle: &tauri::AppHandle,
+    device_id: &str,
+    action: &str,
+) -> Result<DeviceResponse<PairingResult>, String> {
+    info!("🔐 idevicepair {} for iOS device {}", action, device_id);
+
+    let output = match app_handle
+        .shell()
+        .command(get_tool_command_legacy("idevicepair"))
+        .args(["-u", device_id, action])
+        .output_with_policy()
+        .await
+    {
+        Ok(output) => output,
+        Err(e) => {
+            error!("❌ Failed to execute idevicepair {}: {}", action, e);
+            return Ok(DeviceResponse {
+                success: false,
+                data: None,
+                error_detail: Some(e.device_error()),
+                error: Some(format!("Failed to execute idevicepair: {}", e)),
+            });
+        }
+    };
+
+    let result = parse_idevicepair_output(
+        output.status.success(),
+        &String::from_utf8_lossy(&output.stdout),
+        &String::from_utf8_lossy(&output.stderr),
+    );
+    if result.paired {
+        info!("✅ Device {} is paired", device_id);
+    } else {
+        warn!("⚠️ Device {} is not paired ({:?}): {}", device_id, result.status, result.message);
+    }
+
+    // Not being paired yet is an expected outcome, so it is reported as data rather than an error
+    Ok(DeviceResponse {
+        success: true,
+        data: Some(result),
+        error: None,
+        error_detail: None,
+    })
+}
+
+/// Pair with a physical iOS device, triggering the trust dialog on it if needed
+#[tauri::command]
+pub async fn ios_pair_device(
+    app_handle: tauri::AppHandle,
+    device_id: String,
+) -> Result<DeviceResponse<PairingResult>, String> {
+    run_idevicepair(&app_handle, &device_id, "pair").await
+}
+
+/// Check whether this computer's pairing with a physical iOS device is still valid
+#[tauri::command]
+pub async fn ios_validate_pairing(
+    app_handle: tauri::AppHandle,
+    device_id: String,
+) -> Result<DeviceResponse<PairingResult>, String> {
+    run_idevicepair(&app_handle, &device_id, "validate").await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_idevicepair_output() {
+        let paired = parse_idevicepair_output(true, "SUCCESS: Paired with device 00008030-001A\n", "");
+        assert!(paired.paired);
+        assert!(paired.instructions.is_none());
+
+        let pending = parse_idevicepair_output(
+            false,
+            "ERROR: Please accept the trust dialog on the screen of device 00008030-001A, then attempt to pair again.",
+            "",
+        );
+        assert_eq!(pending.status, PairingStatus::AwaitingTrust);
+        assert!(pending.instructions.unwrap().contains("Trust"));
+
+        let locked = parse_idevicepair_output(
+            false,
+            "",
+            "ERROR: Could not validate with device 00008030-001A because a passcode is set. Please enter the passcode on the device and retry.",
+        );
+        assert_eq!(locked.status, PairingStatus::PasscodeLocked);
+
+        let denied = parse_idevicepair_output(false, "ERROR: Device 00008030-001A said that the user denied the trust dialog.", "");
+        assert_eq!(denied.status, PairingStatus::UserDenied);
+
+        let not_paired = parse_idevicepair_output(false, "ERROR: Device 00008030-001A is not paired with this host", "");
+        assert_eq!(not_paired.status, PairingStatus::NotPaired);
+        assert!(!not_paired.paired);
+
+        assert_eq!(parse_idevicepair_output(false, "No device found.", "").status, PairingStatus::DeviceNotFound);
+        assert_eq!(parse_idevicepair_output(false, "", "weird").status, PairingStatus::Unknown);
+    }
+
+    #[test]
+    fn test_classify_lockdown_errors() {
+        assert_eq!(
+            classify_pairing_message("ERROR: Could not connect to lockdownd: Pairing dialog response pending (-19)"),
+            PairingStatus::AwaitingTrust
+        );
+        assert_eq!(
+            classify_pairing_message("ERROR: Could not connect to lockdownd: PasswordProtected (-17)"),
+            PairingStatus::PasscodeLocked
+        );
+        assert_eq!(
+            classify_pairing_message("ERROR: Could not connect to lockdownd: Invalid HostID (-21)"),
+            PairingStatus::NotPaired
+        );
+    }
+}
@@ -0,0 +1,164 @@
+// Gatekeeper quarantine handling for the bundled libimobiledevice tools
+// Binaries that keep `com.apple.quarantine` after a download can be refused by Gatekeeper,
+// which shows up as the tool failing to launch without a useful error.
+
+use crate::commands::device::helpers::get_libimobiledevice_tool_path;
+use crate::commands::device::types::DeviceResponse;
+use super::super::errors::CommandErrorExt;
+use crate::commands::diagnostics::IOS_TOOLS;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+
+const QUARANTINE_ATTRIBUTE: &str = "com.apple.quarantine";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QuarantineStatus {
+    pub tool: String,
+    pub path: String,
+    pub quarantined: bool,
+    pub cleared: bool,
+    pub error: Option<String>,
+}
+
+/// Whether `path` carries the quarantine attribute; always false off macOS.
+pub fn has_quarantine(path: &Path) -> bool {
+    if !cfg!(target_os = "macos") {
+        return false;
+    }
+    Command::new("xattr")
+        .arg("-p")
+        .arg(QUARANTINE_ATTRIBUTE)
+        .arg(path)
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+pub fn clear_quarantine(path: &Path) -> Result<(), String> {
+    let output = Command::new("xattr")
+        .arg("-d")
+        .arg(QUARANTINE_ATTRIBUTE)
+        .arg(path)
+        .output()
+        .map_err(|e| format!("Failed to run xattr: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// What to tell the user when the attribute can't be removed from the app
+pub fn quarantine_help(path: &str) -> String {
+    format!(
+        "macOS quarantined '{}'. Run `xattr -d {} \"{}\"` in Terminal, or open Flippio from Finder with right-click > Open once.",
+        path, QUARANTINE_ATTRIBUTE, path
+    )
+}
+
+/// Check the iOS tools Flippio resolves and optionally clear their quarantine attribute
+pub fn scan_tool_quarantine(clear: bool) -> Vec<QuarantineStatus> {
+    IOS_TOOLS
+        .iter()
+        .filter_map(|tool| get_libimobiledevice_tool_path(tool).map(|path| (*tool, path)))
+        .map(|(tool, path)| {
+            let quarantined = has_quarantine(&path);
+            let path_str = path.to_string_lossy().to_string();
+            let (cleared, error) = if clear && quarantined {
+                match clear_quarantine(&path) {
+                    Ok(()) => (true, None),
+                    Err(e) => (false, Some(format!("{} ({})", quarantine_help(&path_str), e))),
+                }
+            } else {
+                (false, None)
+            };
+            QuarantineStatus {
+                tool: tool.to_string(),
+                path: path_str,
+                quarantined: quarantined && !cleared,
+                cleared,
+                error,
+            }
+        })
+        .collect()
+}
+
+/// Clear the attribute from the bundled tools on startup so the first iOS call doesn't fail
+pub fn clear_tool_quarantine_on_startup() {
+    if !cfg!(target_os = "macos") {
+        return;
+    }
+    tauri::async_runtime::spawn_blocking(|| {
+        for status in scan_tool_quarantine(true) {
+            if status.cleared {
+                log::info!("🔓 Cleared quarantine attribute from {}", status.path);
+            } else if let Some(error) = status.error {
+                log::warn!("⚠️ {}", error);
+            }
+        }
+    });
+}
+
+/// Report which iOS tools are quarantined by Gatekeeper
+#[tauri::command]
+pub async fn ios_check_tool_quarantine() -> Result<DeviceResponse<Vec<QuarantineStatus>>, String> {
+    let statuses = tokio::task::spawn_blocking(|| scan_tool_quarantine(false))
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(DeviceResponse {
+        success: true,
+        data: Some(statuses),
+        error: None,
+        error_detail: None,
+    })
+}
+
+/// Remove the quarantine attribute from the iOS tools
+#[tauri::command]
+pub async fn ios_clear_tool_quarantine() -> Result<DeviceResponse<Vec<QuarantineStatus>>, String> {
+    log::info!("🔓 Clearing quarantine from iOS tools");
+    let statuses = tokio::task::spawn_blocking(|| scan_tool_quarantine(true))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let failures: Vec<&str> = statuses
+        .iter()
+        .filter_map(|status| status.error.as_deref())
+        .collect();
+    if failures.is_empty() {
+        Ok(DeviceResponse {
+            success: true,
+            data: Some(statuses),
+            error: None,
+            error_detail: None,
+        })
+    } else {
+        let error = failures.join("\n");
+        log::warn!("⚠️ {}", error);
+        Ok(DeviceResponse {
+            success: false,
+            data: Some(statuses),
+            error_detail: Some(error.device_error()),
+            error: Some(error),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_has_quarantine_missing_file() {
+        assert!(!has_quarantine(Path::new("/nonexistent/flippio/afcclient")));
+    }
+
+    #[test]
+    fn test_quarantine_help_names_path() {
+        let help = quarantine_help("/Applications/Flippio.app/Contents/MacOS/afcclient");
+        assert!(help.contains("xattr -d com.apple.quarantine"));
+        assert!(help.contains("/Applications/Flippio.app/Contents/MacOS/afcclient"));
+    }
+}
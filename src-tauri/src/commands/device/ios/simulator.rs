@@ -3,10 +3,23 @@
 //! This module handles iOS simulator-specific operations including
 //! database file management and app data access.
 
-use super::super::types::{DeviceResponse, DatabaseFile};
-use super::super::helpers::force_clean_temp_dir;
-use tauri::{State};
+use super::super::types::{DeviceResponse, DatabaseFile, DatabaseFileType};
+use super::super::errors::{CommandErrorExt, DeviceError};
+use super::super::helpers::{
+    force_clean_temp_dir, get_xcrun_path, simulator_unsupported_response, IOS_SIMULATORS_SUPPORTED,
+};
+use super::super::hooks::{with_push_hooks, PushHookContext};
+use super::super::verification::{with_push_verification, PushedFile};
+use super::super::app_restart::with_app_stopped;
+use super::super::integrity::{check_push_integrity, refresh_metadata_hash};
+use super::super::leveldb::is_leveldb_directory;
+use crate::commands::database::{invalidate_cached_connection, DbConnectionCache};
+use crate::commands::guardrails::{check_dangerous_operation, DangerousOperation};
+use crate::commands::journal::{begin_operation, JournalOperation};
+use crate::commands::common::{commit_operation, OperationKind, OPERATIONS};
+use tauri::{Manager, State};
 use tauri_plugin_shell::ShellExt;
+use super::super::command_policy::PolicyOutputExt;
 use log::{info, error};
 use std::collections::{HashSet, VecDeque};
 use std::path::{Path, PathBuf};
@@ -17,7 +30,7 @@ const IOS_SIM_SCAN_MAX_DIRECTORIES: usize = 256;
 fn is_database_file(path: &Path) -> bool {
     path.extension()
         .and_then(|ext| ext.to_str())
-        .map(|ext| matches!(ext, "db" | "sqlite" | "sqlite3"))
+        .map(|ext| matches!(ext, "db" | "sqlite" | "sqlite3" | "realm"))
         .unwrap_or(false)
 }
 
@@ -96,6 +109,11 @@ fn scan_simulator_root(root_path: &Path) -> (Vec<PathBuf>, Vec<String>) {
             };
 
             if file_type.is_dir() {
+                if is_leveldb_directory(&entry_path) {
+                    found_files.push(entry_path);
+                    continue;
+                }
+
                 if depth >= IOS_SIM_SCAN_MAX_DEPTH {
                     scan_warnings.push(format!(
                         "Stopped descending into {} after reaching max depth {}",
@@ -170,8 +188,90 @@ fn scan_simulator_library_targets(container_path: &Path, package_name: &str) ->
 
 /// Upload database file to iOS simulator
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn upload_simulator_ios_db_file(
-    _app_handle: tauri::AppHandle,
+    app_handle: tauri::AppHandle,
+    device_id: String,
+    local_file_path: String,
+    package_name: String,
+    remote_location: String,
+    db_pool_state: State<'_, crate::commands::database::DbPool>,
+    verify: Option<bool>,
+    stop_app: Option<bool>,
+) -> Result<DeviceResponse<PushedFile>, String> {
+    if !IOS_SIMULATORS_SUPPORTED {
+        return Ok(simulator_unsupported_response());
+    }
+
+    if let Err(e) = check_dangerous_operation(DangerousOperation::PushDatabase) {
+        return Ok(DeviceResponse {
+            success: false,
+            data: None,
+            error_detail: Some(e.device_error()),
+            error: Some(e),
+        });
+    }
+
+    let context = PushHookContext::new("simulator", &device_id, &package_name, &local_file_path, &remote_location);
+    let push = async {
+        let response = with_push_hooks(
+            context,
+            upload_simulator_db_file(
+                device_id.clone(),
+                local_file_path.clone(),
+                package_name.clone(),
+                remote_location.clone(),
+                db_pool_state,
+            ),
+        )
+        .await?;
+
+        Ok(with_push_verification(
+            &app_handle,
+            "simulator",
+            &device_id,
+            &package_name,
+            &remote_location,
+            &local_file_path,
+            verify.unwrap_or(false),
+            response,
+        )
+        .await)
+    };
+
+    let journal = begin_operation(
+        JournalOperation::Push,
+        "simulator",
+        &device_id,
+        &package_name,
+        &local_file_path,
+        &remote_location,
+    );
+    let operation = OPERATIONS.start(
+        OperationKind::Push,
+        format!("Push {} to {}", remote_location, device_id),
+        Some(&device_id),
+    );
+    let response = operation
+        .run(with_app_stopped(
+            &app_handle,
+            "simulator",
+            &device_id,
+            &package_name,
+            &remote_location,
+            None,
+            stop_app.unwrap_or(false),
+            push,
+        ))
+        .await;
+    if matches!(&response, Ok(pushed) if pushed.success) {
+        journal.complete_push();
+        invalidate_cached_connection(&app_handle, &app_handle.state::<DbConnectionCache>(), &local_file_path, "pushed").await;
+    }
+    response
+}
+
+async fn upload_simulator_db_file(
     device_id: String,
     local_file_path: String,
     package_name: String,
@@ -208,6 +308,7 @@ pub async fn upload_simulator_ios_db_file(
                 success: true,
                 data: Some("File already in correct location".to_string()),
                 error: None,
+                error_detail: None,
             });
         }
     }
@@ -218,19 +319,42 @@ pub async fn upload_simulator_ios_db_file(
         return Ok(DeviceResponse {
             success: false,
             data: None,
+            error_detail: Some(DeviceError::Unknown),
             error: Some(format!("Local file {} does not exist", local_file_path)),
         });
     }
+
+    if let Err(e) = check_push_integrity(&local_file_path) {
+        return Ok(DeviceResponse {
+            success: false,
+            data: None,
+            error_detail: Some(e.device_error()),
+            error: Some(e),
+        });
+    }
     
+    if let Err(e) = commit_operation() {
+        return Ok(DeviceResponse {
+            success: false,
+            data: None,
+            error_detail: Some(e.device_error()),
+            error: Some(e),
+        });
+    }
+
     // Simple file copy
     info!("� Copying {} to {}", local_file_path, remote_location);
     match std::fs::copy(&local_file_path, &remote_location) {
         Ok(bytes_copied) => {
             info!("✅ Successfully copied {} bytes", bytes_copied);
+            if let Err(e) = refresh_metadata_hash(Path::new(&local_file_path)) {
+                log::warn!("⚠️ Failed to update pull hash after push: {}", e);
+            }
             Ok(DeviceResponse {
                 success: true,
                 data: Some(format!("Successfully uploaded {} to simulator at {}", local_file_path, remote_location)),
                 error: None,
+                error_detail: None,
             })
         }
         Err(e) => {
@@ -238,6 +362,7 @@ pub async fn upload_simulator_ios_db_file(
             Ok(DeviceResponse {
                 success: false,
                 data: None,
+                error_detail: Some(DeviceError::Unknown),
                 error: Some(format!("File copy failed: {}", e)),
             })
         }
@@ -252,6 +377,9 @@ pub async fn get_ios_simulator_database_files(
     package_name: String,
 ) -> Result<DeviceResponse<Vec<DatabaseFile>>, String> {
     info!("=== GET iOS SIMULATOR DATABASE FILES STARTED ===");
+    if !IOS_SIMULATORS_SUPPORTED {
+        return Ok(simulator_unsupported_response());
+    }
     info!("Device ID (Simulator): {}", device_id);
     info!("Package name: {}", package_name);
     
@@ -266,9 +394,9 @@ pub async fn get_ios_simulator_database_files(
     let mut database_files = Vec::new();
     
     info!("Step 1: Getting app container path using xcrun simctl");
-    let get_container_output = shell.command("xcrun")
+    let get_container_output = shell.command(get_xcrun_path())
         .args(["simctl", "get_app_container", &device_id, &package_name, "data"])
-        .output()
+        .output_with_policy()
         .await;
     
     match get_container_output {
@@ -313,13 +441,22 @@ pub async fn get_ios_simulator_database_files(
                         .to_string();
                     let file_path_str = file_path.to_string_lossy().to_string();
 
+                    let file_type = if file_path.is_dir() {
+                        DatabaseFileType::LevelDb
+                    } else {
+                        DatabaseFileType::from_path(&filename)
+                    };
+
                     let db_file = DatabaseFile {
                         path: file_path_str.clone(),
                         package_name: package_name.clone(),
+                        file_type,
                         filename,
                         remote_path: Some(file_path_str.clone()),
                         location: location_from_container_path(&container_path, &file_path),
                         device_type: "simulator".to_string(),
+                        integrity: None,
+                        pull_strategy: None,
                     };
 
                     info!("Database file object: {:?}", db_file);
@@ -331,6 +468,7 @@ pub async fn get_ios_simulator_database_files(
                 return Ok(DeviceResponse {
                     success: false,
                     data: None,
+                    error_detail: Some(container_result.device_error()),
                     error: Some(format!("Failed to get app container: {}", stderr)),
                 });
             }
@@ -340,6 +478,7 @@ pub async fn get_ios_simulator_database_files(
             return Ok(DeviceResponse {
                 success: false,
                 data: None,
+                error_detail: Some(e.device_error()),
                 error: Some(format!("Failed to get app container: {}", e)),
             });
         }
@@ -352,5 +491,6 @@ pub async fn get_ios_simulator_database_files(
         success: true,
         data: Some(database_files),
         error: None,
+        error_detail: None,
     })
 }
@@ -2,6 +2,7 @@
 // Comprehensive tests for iOS device detection, tool validation, and file operations
 
 use crate::commands::device::types::*;
+use crate::commands::device::errors::DeviceError;
 use crate::commands::device::ios::diagnostic::*;
 use tempfile::TempDir;
 
@@ -14,9 +15,15 @@ mod tests {
         let device = Device {
             id: "00008030-001234567890000E".to_string(),
             name: "iPhone 14 Pro".to_string(),
+            display_name: "iPhone 14 Pro".to_string(),
             model: "iPhone15,3".to_string(),
             device_type: "iphone".to_string(),
             description: "Real iOS device".to_string(),
+            api_level: None,
+            abi: None,
+            build_fingerprint: None,
+            state: None,
+            favorite: None,
         };
         
         assert_eq!(device.id, "00008030-001234567890000E");
@@ -30,6 +37,7 @@ mod tests {
         let package = Package {
             name: "Settings".to_string(),
             bundle_id: "com.apple.Preferences".to_string(),
+            favorite: None,
         };
         
         assert_eq!(package.name, "Settings");
@@ -46,6 +54,9 @@ mod tests {
             location: "Documents".to_string(),
             remote_path: Some("/var/mobile/Containers/Data/Application/ABC123/Documents/database.sqlite".to_string()),
             device_type: "iphone".to_string(),
+            file_type: DatabaseFileType::Sqlite,
+            integrity: None,
+            pull_strategy: None,
         };
         
         assert_eq!(db_file.filename, "database.sqlite");
@@ -60,6 +71,7 @@ mod tests {
         let simulator = VirtualDevice {
             id: "A1B2C3D4-5678-90AB-CDEF-1234567890AB".to_string(),
             name: "iPhone 14 Pro Simulator".to_string(),
+            display_name: "iPhone 14 Pro Simulator".to_string(),
             model: Some("iPhone14,3".to_string()),
             platform: "iOS".to_string(),
             state: Some("Booted".to_string()),
@@ -78,16 +90,28 @@ mod tests {
             Device {
                 id: "device1".to_string(),
                 name: "iPhone 14".to_string(),
+                display_name: "iPhone 14".to_string(),
                 model: "iPhone15,2".to_string(),
                 device_type: "iphone".to_string(),
                 description: "iOS device".to_string(),
+                api_level: None,
+                abi: None,
+                build_fingerprint: None,
+                state: None,
+                favorite: None,
             },
             Device {
                 id: "device2".to_string(),
                 name: "iPad Pro".to_string(),
+                display_name: "iPad Pro".to_string(),
                 model: "iPad14,5".to_string(),
                 device_type: "ipad".to_string(),
                 description: "iPad device".to_string(),
+                api_level: None,
+                abi: None,
+                build_fingerprint: None,
+                state: None,
+                favorite: None,
             },
         ];
         
@@ -95,6 +119,7 @@ mod tests {
             success: true,
             data: Some(devices),
             error: None,
+            error_detail: None,
         };
         
         assert!(response.success);
@@ -107,6 +132,7 @@ mod tests {
         let response: DeviceResponse<Vec<Device>> = DeviceResponse {
             success: false,
             data: None,
+            error_detail: Some(DeviceError::ToolMissing { tool: None }),
             error: Some("libimobiledevice tools not found".to_string()),
         };
         
@@ -121,9 +147,15 @@ mod tests {
         let device = Device {
             id: "ios_device_123".to_string(),
             name: "Test iPhone".to_string(),
+            display_name: "Test iPhone".to_string(),
             model: "iPhone15,1".to_string(),
             device_type: "iphone".to_string(),
             description: "Test iOS device".to_string(),
+            api_level: None,
+            abi: None,
+            build_fingerprint: None,
+            state: None,
+            favorite: None,
         };
         
         // Test serialization
@@ -145,6 +177,7 @@ mod tests {
         let package = Package {
             name: "Test iOS App".to_string(),
             bundle_id: "com.example.testapp".to_string(),
+            favorite: None,
         };
         
         let json = serde_json::to_string(&package)?;
@@ -166,6 +199,9 @@ mod tests {
             location: "Documents".to_string(),
             remote_path: Some("/var/mobile/test.sqlite".to_string()),
             device_type: "iphone".to_string(),
+            file_type: DatabaseFileType::Sqlite,
+            integrity: None,
+            pull_strategy: None,
         };
         
         let json = serde_json::to_string(&db_file)?;
@@ -185,6 +221,7 @@ mod tests {
         let simulator = VirtualDevice {
             id: "sim123".to_string(),
             name: "iPhone Simulator".to_string(),
+            display_name: "iPhone Simulator".to_string(),
             model: Some("iPhone14,1".to_string()),
             platform: "iOS".to_string(),
             state: Some("Shutdown".to_string()),
@@ -296,9 +333,15 @@ mod tests {
         let empty_device = Device {
             id: "".to_string(),
             name: "iPhone".to_string(),
+            display_name: "iPhone".to_string(),
             model: "iPhone15,1".to_string(),
             device_type: "iphone".to_string(),
             description: "Test".to_string(),
+            api_level: None,
+            abi: None,
+            build_fingerprint: None,
+            state: None,
+            favorite: None,
         };
         assert!(empty_device.id.is_empty());
         
@@ -306,6 +349,7 @@ mod tests {
         let invalid_package = Package {
             name: "Test App".to_string(),
             bundle_id: "invalid_bundle_id".to_string(),
+            favorite: None,
         };
         assert!(!invalid_package.bundle_id.contains("."));
         
@@ -317,6 +361,9 @@ mod tests {
             location: "Documents".to_string(),
             remote_path: None,
             device_type: "iphone".to_string(),
+            file_type: DatabaseFileType::Sqlite,
+            integrity: None,
+            pull_strategy: None,
         };
         assert!(db_file_no_remote.remote_path.is_none());
         
@@ -324,6 +371,7 @@ mod tests {
         let simulator_no_state = VirtualDevice {
             id: "sim123".to_string(),
             name: "Test Simulator".to_string(),
+            display_name: "Test Simulator".to_string(),
             model: None,
             platform: "iOS".to_string(),
             state: None,
@@ -408,23 +456,41 @@ mod tests {
             Device {
                 id: "device1".to_string(),
                 name: "iPhone 14".to_string(),
+                display_name: "iPhone 14".to_string(),
                 model: "iPhone15,2".to_string(),
                 device_type: "iphone".to_string(),
                 description: "Primary iPhone".to_string(),
+                api_level: None,
+                abi: None,
+                build_fingerprint: None,
+                state: None,
+                favorite: None,
             },
             Device {
                 id: "device2".to_string(),
                 name: "iPad Pro".to_string(),
+                display_name: "iPad Pro".to_string(),
                 model: "iPad14,5".to_string(),
                 device_type: "ipad".to_string(),
                 description: "Work iPad".to_string(),
+                api_level: None,
+                abi: None,
+                build_fingerprint: None,
+                state: None,
+                favorite: None,
             },
             Device {
                 id: "simulator1".to_string(),
                 name: "iPhone 14 Simulator".to_string(),
+                display_name: "iPhone 14 Simulator".to_string(),
                 model: "iPhone15,2".to_string(),
                 device_type: "simulator".to_string(),
                 description: "Development simulator".to_string(),
+                api_level: None,
+                abi: None,
+                build_fingerprint: None,
+                state: None,
+                favorite: None,
             },
         ];
         
@@ -432,6 +498,7 @@ mod tests {
             success: true,
             data: Some(devices),
             error: None,
+            error_detail: None,
         };
         
         assert!(response.success);
@@ -46,6 +46,59 @@ pub struct ValidatedTool {
     pub path: PathBuf,
     pub strategy: String,
     pub version: Option<String>,
+    pub minimum_version: Option<String>,
+    pub compatibility_warning: Option<String>,
+}
+
+/// Oldest releases Flippio is known to work with. adb needs `exec-out` for binary-safe
+/// pulls (1.0.32, platform-tools 21); afcclient and `--version` arrived in libimobiledevice 1.3.0.
+const MINIMUM_TOOL_VERSIONS: [(&str, (u32, u32, u32)); 5] = [
+    ("adb", (1, 0, 32)),
+    ("idevice_id", (1, 3, 0)),
+    ("ideviceinfo", (1, 3, 0)),
+    ("afcclient", (1, 3, 0)),
+    ("ideviceinstaller", (1, 1, 1)),
+];
+
+/// Extract the first `major.minor[.patch]` number from tool version output,
+/// e.g. "Android Debug Bridge version 1.0.41" or "idevice_id 1.3.0-83-g5b8c9a8".
+pub fn parse_tool_version(output: &str) -> Option<(u32, u32, u32)> {
+    output
+        .split(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .filter_map(|token| {
+            let mut parts = token.split('.').filter(|part| !part.is_empty());
+            let major = parts.next()?.parse().ok()?;
+            let minor = parts.next()?.parse().ok()?;
+            let patch = parts.next().and_then(|part| part.parse().ok()).unwrap_or(0);
+            Some((major, minor, patch))
+        })
+        .next()
+}
+
+pub fn minimum_tool_version(tool_name: &str) -> Option<(u32, u32, u32)> {
+    MINIMUM_TOOL_VERSIONS
+        .iter()
+        .find(|(tool, _)| *tool == tool_name)
+        .map(|(_, version)| *version)
+}
+
+pub fn format_tool_version((major, minor, patch): (u32, u32, u32)) -> String {
+    format!("{}.{}.{}", major, minor, patch)
+}
+
+/// Warning text when `version_output` reports a release older than the supported minimum.
+/// Unknown tools and unparseable versions produce no warning.
+pub fn tool_compatibility_warning(tool_name: &str, version_output: &str) -> Option<String> {
+    let minimum = minimum_tool_version(tool_name)?;
+    let found = parse_tool_version(version_output)?;
+    (found < minimum).then(|| {
+        format!(
+            "{} {} is older than the minimum supported version {}; please update it",
+            tool_name,
+            format_tool_version(found),
+            format_tool_version(minimum)
+        )
+    })
 }
 
 /// Enhanced iOS tool validator with multiple fallback strategies
@@ -132,11 +185,20 @@ impl IOSToolValidator {
                     }
                 };
                 
+                let compatibility_warning = version
+                    .as_deref()
+                    .and_then(|version| tool_compatibility_warning(tool_name, version));
+                if let Some(warning) = &compatibility_warning {
+                    warn!("    ⚠️ {}", warning);
+                }
+
                 info!("    ✅ Tool validated successfully!");
                 return Ok(ValidatedTool {
                     path: tool_path,
                     strategy: strategy.name.clone(),
                     version,
+                    minimum_version: minimum_tool_version(tool_name).map(format_tool_version),
+                    compatibility_warning,
                 });
             }
         }
@@ -332,10 +394,7 @@ impl IOSToolValidator {
         
         // Different tools have different ways to check version
         let test_args = match tool_name {
-            "idevice_id" => vec!["--help"],
-            "ideviceinfo" => vec!["--help"],
-            "afcclient" => vec!["--help"],
-            "ideviceinstaller" => vec!["--help"],
+            "adb" => vec!["version"],
             _ => vec!["--version", "--help"],
         };
 
@@ -351,9 +410,12 @@ impl IOSToolValidator {
                         let stdout = String::from_utf8_lossy(&output.stdout);
                         let stderr = String::from_utf8_lossy(&output.stderr);
                         
-                        // Look for version patterns
+                        // Look for version patterns; libimobiledevice prints "<tool> 1.3.0"
                         for line in stdout.lines().chain(stderr.lines()) {
-                            if line.contains("version") || line.contains("Version") {
+                            let is_version_line = line.contains("version")
+                                || line.contains("Version")
+                                || (line.trim_start().starts_with(tool_name) && parse_tool_version(line).is_some());
+                            if is_version_line {
                                 return Ok(Some(line.trim().to_string()));
                             }
                         }
@@ -427,3 +489,25 @@ impl Default for IOSToolValidator {
         Self::new()
     }
 } 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tool_version() {
+        assert_eq!(parse_tool_version("Android Debug Bridge version 1.0.41"), Some((1, 0, 41)));
+        assert_eq!(parse_tool_version("idevice_id 1.3.0-83-g5b8c9a8"), Some((1, 3, 0)));
+        assert_eq!(parse_tool_version("ideviceinstaller 1.1"), Some((1, 1, 0)));
+        assert_eq!(parse_tool_version("Usage: afcclient [OPTIONS]"), None);
+    }
+
+    #[test]
+    fn test_tool_compatibility_warning() {
+        assert!(tool_compatibility_warning("adb", "Android Debug Bridge version 1.0.31").is_some());
+        assert!(tool_compatibility_warning("adb", "Android Debug Bridge version 1.0.41").is_none());
+        assert!(tool_compatibility_warning("afcclient", "afcclient 1.2.0").is_some());
+        assert!(tool_compatibility_warning("afcclient", "Unknown version").is_none());
+        assert!(tool_compatibility_warning("iproxy", "iproxy 1.0.0").is_none());
+    }
+}
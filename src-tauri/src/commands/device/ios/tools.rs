@@ -4,7 +4,7 @@
 
 use super::super::helpers::get_libimobiledevice_tool_path;
 use super::tool_validation::{IOSToolValidator, ToolValidationError};
-use log::{info, error};
+use log::{info, warn, error};
 use std::sync::OnceLock;
 
 // Global tool validator instance
@@ -29,6 +29,9 @@ pub fn get_validated_tool_path(tool_name: &str) -> Result<String, ToolValidation
             if let Some(version) = &validated_tool.version {
                 info!("📋 Tool version: {}", version);
             }
+            if let Some(warning) = &validated_tool.compatibility_warning {
+                warn!("⚠️ {}", warning);
+            }
             Ok(validated_tool.path.to_string_lossy().to_string())
         }
         Err(error) => {
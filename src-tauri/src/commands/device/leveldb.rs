@@ -0,0 +1,338 @@
+//! LevelDB data store discovery
+//!
+//! Hybrid apps keep part of their state in LevelDB folders (WebView IndexedDB and
+//! Local Storage, some React Native storage backends). These are listed next to the
+//! SQLite databases as non-SQLite data stores and can be pulled as a whole directory.
+//!
+//! Android devices and iOS simulators are covered. Physical iOS devices are not: their scan
+//! only sees files through afcclient and there is no directory pull, so LevelDB stores on a
+//! device are not listed.
+
+use super::helpers::{ensure_temp_dir, execute_adb_command, namespaced_temp_path};
+use super::android_storage::shell_quote;
+use super::types::{DatabaseFileMetadata, DeviceResponse};
+use super::errors::CommandErrorExt;
+use crate::commands::common::with_device_turn;
+use log::{error, info, warn};
+use std::collections::BTreeSet;
+use std::fs;
+use std::future::Future;
+use std::path::Path;
+
+const LEVELDB_MANIFEST_PREFIX: &str = "MANIFEST-";
+
+/// A local directory is a LevelDB store when it holds a CURRENT file and at least one manifest.
+pub fn is_leveldb_directory(path: &Path) -> bool {
+    if !path.is_dir() || !path.join("CURRENT").is_file() {
+        return false;
+    }
+
+    fs::read_dir(path)
+        .map(|entries| {
+            entries.filter_map(Result::ok).any(|entry| {
+                entry
+                    .file_name()
+                    .to_string_lossy()
+                    .starts_with(LEVELDB_MANIFEST_PREFIX)
+            })
+        })
+        .unwrap_or(false)
+}
+
+fn adb_find_leveldb_args(
+    device_id: &str,
+    package_name: &str,
+    location: &str,
+    admin_required: bool,
+) -> Vec<String> {
+    // adb shell joins the arguments into one device command line
+    let path = shell_quote(&format!("{}{}/", location, package_name));
+    let mut args = vec!["-s".to_string(), device_id.to_string(), "shell".to_string()];
+
+    if admin_required {
+        args.push("run-as".to_string());
+        args.push(package_name.to_string());
+    }
+
+    args.extend([
+        "find".to_string(),
+        path,
+        "-type".to_string(),
+        "f".to_string(),
+        "-name".to_string(),
+        shell_quote(&format!("{}*", LEVELDB_MANIFEST_PREFIX)),
+    ]);
+    args
+}
+
+/// Turn the manifest paths printed by `find` into their (deduplicated) LevelDB directories.
+fn parse_leveldb_directories(find_output: &str) -> Vec<String> {
+    find_output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| line.rsplit_once('/').map(|(dir, _)| dir.to_string()))
+        .filter(|dir| !dir.is_empty())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Find LevelDB directories in every Android app data location.
+pub(crate) async fn discover_android_leveldb_stores_with<F, Fut>(
+    device_id: &str,
+    package_name: &str,
+    mut execute: F,
+) -> Vec<(String, bool, String)>
+where
+    F: FnMut(Vec<String>) -> Fut,
+    Fut: Future<Output = Result<std::process::Output, Box<dyn std::error::Error + Send + Sync>>>,
+{
+    let locations = vec![
+        ("/data/data/", true),
+        ("/sdcard/Android/data/", false),
+        ("/storage/emulated/0/Android/data/", false),
+    ];
+    let mut stores = Vec::new();
+
+    for (location, admin_required) in locations {
+        let args = adb_find_leveldb_args(device_id, package_name, location, admin_required);
+        if let Ok(result) = execute(args).await {
+            if result.status.success() {
+                let output = String::from_utf8_lossy(&result.stdout);
+                for dir in parse_leveldb_directories(&output) {
+                    stores.push((dir, admin_required, location.to_string()));
+                }
+            }
+        }
+    }
+
+    info!("Found {} LevelDB stores for {}", stores.len(), package_name);
+    stores
+}
+
+async fn list_remote_files(
+    device_id: &str,
+    package_name: &str,
+    remote_dir: &str,
+    admin_access: bool,
+) -> Result<Vec<String>, String> {
+    let quoted_dir = shell_quote(remote_dir);
+    let mut args = vec!["-s", device_id, "shell"];
+    if admin_access {
+        args.extend(["run-as", package_name]);
+    }
+    args.extend(["find", quoted_dir.as_str(), "-type", "f"]);
+
+    let output = execute_adb_command(&args)
+        .await
+        .map_err(|e| format!("Failed to list remote directory: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to list remote directory: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+async fn pull_android_directory(
+    device_id: &str,
+    package_name: &str,
+    remote_dir: &str,
+) -> Result<String, String> {
+    let remote_dir = remote_dir.trim_end_matches('/');
+    let admin_access = remote_dir.starts_with("/data/data/");
+
+    let temp_dir =
+        ensure_temp_dir().map_err(|e| format!("Failed to prepare temp directory: {}", e))?;
+    let local_dir = namespaced_temp_path(&temp_dir, device_id, package_name, remote_dir)
+        .map_err(|e| e.to_string())?;
+
+    if local_dir.exists() {
+        fs::remove_dir_all(&local_dir)
+            .map_err(|e| format!("Failed to replace previous copy: {}", e))?;
+    }
+    fs::create_dir_all(&local_dir)
+        .map_err(|e| format!("Failed to create local directory: {}", e))?;
+
+    let remote_files = list_remote_files(device_id, package_name, remote_dir, admin_access).await?;
+    if remote_files.is_empty() {
+        return Err(format!("No files found in {}", remote_dir));
+    }
+
+    for remote_file in &remote_files {
+        let relative = remote_file
+            .strip_prefix(remote_dir)
+            .unwrap_or(remote_file)
+            .trim_start_matches('/');
+        if relative.split('/').any(|part| part == "..") {
+            warn!("⚠️ Skipping suspicious remote path: {}", remote_file);
+            continue;
+        }
+
+        let local_file = local_dir.join(relative);
+        if let Some(parent) = local_file.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create directory: {}", e))?;
+        }
+
+        // exec-out keeps binary content intact, unlike `adb shell`
+        let quoted_file = shell_quote(remote_file);
+        let mut args = vec!["-s", device_id, "exec-out"];
+        if admin_access {
+            args.extend(["run-as", package_name]);
+        }
+        args.extend(["cat", quoted_file.as_str()]);
+
+        let output = execute_adb_command(&args)
+            .await
+            .map_err(|e| format!("Failed to pull {}: {}", remote_file, e))?;
+
+        if !output.status.success() {
+            return Err(format!(
+                "Failed to pull {}: {}",
+                remote_file,
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        fs::write(&local_file, &output.stdout)
+            .map_err(|e| format!("Failed to write {}: {}", local_file.display(), e))?;
+    }
+
+    let metadata = DatabaseFileMetadata {
+        device_id: device_id.to_string(),
+        package_name: package_name.to_string(),
+        remote_path: remote_dir.to_string(),
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        sha256: None,
+        remote_permissions: None,
+        integrity: None,
+        pull_strategy: None,
+    };
+    let metadata_path = format!("{}.meta.json", local_dir.display());
+    let metadata_json = serde_json::to_string_pretty(&metadata).map_err(|e| e.to_string())?;
+    fs::write(&metadata_path, metadata_json)
+        .map_err(|e| format!("Failed to write metadata: {}", e))?;
+
+    info!(
+        "✅ Pulled {} files from {} into {}",
+        remote_files.len(),
+        remote_dir,
+        local_dir.display()
+    );
+    Ok(local_dir.to_string_lossy().to_string())
+}
+
+/// Pull a whole app data directory (e.g. a LevelDB store) for offline inspection
+#[tauri::command]
+pub async fn adb_pull_data_directory(
+    device_id: String,
+    package_name: String,
+    remote_path: String,
+) -> Result<DeviceResponse<String>, String> {
+    info!("📥 Pulling data directory {} from {}", remote_path, device_id);
+
+    match with_device_turn(&device_id, pull_android_directory(&device_id, &package_name, &remote_path)).await {
+        Ok(local_dir) => Ok(DeviceResponse {
+            success: true,
+            data: Some(local_dir),
+            error: None,
+            error_detail: None,
+        }),
+        Err(e) => {
+            error!("❌ Failed to pull data directory: {}", e);
+            Ok(DeviceResponse {
+                success: false,
+                data: None,
+                error_detail: Some(e.device_error()),
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[cfg(unix)]
+    fn fake_output(code: i32, stdout: &str) -> std::process::Output {
+        use std::os::unix::process::ExitStatusExt;
+
+        std::process::Output {
+            status: std::process::ExitStatus::from_raw(code),
+            stdout: stdout.as_bytes().to_vec(),
+            stderr: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_is_leveldb_directory_requires_current_and_manifest() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = temp_dir.path().join("https_app.example_0.indexeddb.leveldb");
+        fs::create_dir_all(&store).unwrap();
+        fs::write(store.join("CURRENT"), "MANIFEST-000001\n").unwrap();
+        assert!(!is_leveldb_directory(&store));
+
+        fs::write(store.join("MANIFEST-000001"), [0u8; 4]).unwrap();
+        assert!(is_leveldb_directory(&store));
+        assert!(!is_leveldb_directory(&store.join("CURRENT")));
+    }
+
+    #[test]
+    fn test_parse_leveldb_directories_dedupes_parents() {
+        let output = "/data/data/com.app/app_webview/Default/Local Storage/leveldb/MANIFEST-000001\n\
+                      /data/data/com.app/app_webview/Default/Local Storage/leveldb/MANIFEST-000004\n\
+                      /data/data/com.app/files/rn/MANIFEST-000002\n";
+
+        assert_eq!(
+            parse_leveldb_directories(output),
+            vec![
+                "/data/data/com.app/app_webview/Default/Local Storage/leveldb".to_string(),
+                "/data/data/com.app/files/rn".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_adb_find_leveldb_args_uses_run_as_for_private_storage() {
+        let args = adb_find_leveldb_args("device-1", "com.app", "/data/data/", true);
+        assert_eq!(
+            args,
+            vec![
+                "-s", "device-1", "shell", "run-as", "com.app", "find", "'/data/data/com.app/'",
+                "-type", "f", "-name", "'MANIFEST-*'",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn test_discover_android_leveldb_stores_scans_all_locations() {
+        let found = discover_android_leveldb_stores_with("device-1", "com.app", |args| async move {
+            if args.contains(&"'/data/data/com.app/'".to_string()) {
+                Ok(fake_output(0, "/data/data/com.app/app_webview/IndexedDB/db.leveldb/MANIFEST-1\n"))
+            } else if args.contains(&"'/sdcard/Android/data/com.app/'".to_string()) {
+                Ok(fake_output(0, "/sdcard/Android/data/com.app/files/cache/MANIFEST-2\n"))
+            } else {
+                Ok(fake_output(1, ""))
+            }
+        })
+        .await;
+
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].0, "/data/data/com.app/app_webview/IndexedDB/db.leveldb");
+        assert!(found[0].1);
+        assert_eq!(found[1].2, "/sdcard/Android/data/");
+    }
+}
@@ -1,11 +1,53 @@
 // Device module - modular implementation of device commands
 pub mod types;
 pub mod helpers;
+pub mod android_storage;
+pub mod app_bundle;
+pub mod app_restart;
+pub mod bookmarks;
+pub mod capabilities;
+pub mod command_policy;
+pub mod errors;
+pub mod favorites;
+pub mod environment;
+pub mod hooks;
+pub mod integrity;
 pub mod adb;
 pub mod ios;
+pub mod leveldb;
+pub mod preferences;
+pub mod pull_strategy;
+pub mod pulled_metadata;
+pub mod scanner;
+pub mod scheduler;
+pub mod session;
+pub mod temp_storage;
+pub mod transfer;
+pub mod verification;
 pub mod virtual_device;
+pub mod windows_dependencies;
 
 // Re-export all public functions and types from sub-modules
 pub use adb::*;
+pub use android_storage::*;
+pub use app_bundle::*;
+pub use app_restart::*;
+pub use bookmarks::*;
+pub use capabilities::*;
+pub use command_policy::*;
+pub use errors::*;
+pub use favorites::*;
+pub use environment::*;
+pub use hooks::*;
 pub use ios::*;
+pub use leveldb::*;
+pub use preferences::*;
+pub use pull_strategy::*;
+pub use pulled_metadata::*;
+pub use scheduler::*;
+pub use session::*;
+pub use temp_storage::*;
+pub use transfer::*;
+pub use verification::*;
 pub use virtual_device::*;
+pub use windows_dependencies::*;
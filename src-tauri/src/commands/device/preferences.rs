@@ -0,0 +1,643 @@
+//! App preferences (key-value storage)
+//!
+//! Pulls and parses the key-value half of typical mobile app storage:
+//! iOS UserDefaults (`Library/Preferences/*.plist`) and Android Jetpack DataStore
+//! (`files/datastore/*.preferences_pb`). Both are returned as flat key-value tables.
+
+use super::android_storage::shell_quote;
+use super::helpers::{
+    ensure_temp_dir, execute_adb_command, get_xcrun_path, namespaced_temp_path, IOS_SIMULATORS_SUPPORTED,
+    SIMULATOR_UNSUPPORTED_ERROR,
+};
+use super::ios::database::list_ios_directory;
+use super::ios::file_utils::{pull_ios_db_file, IosAppAccessType};
+use super::ios::tools::get_tool_command_legacy;
+use super::types::DeviceResponse;
+use super::errors::CommandErrorExt;
+use base64::{engine::general_purpose, Engine as _};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use tauri_plugin_shell::ShellExt;
+use super::command_policy::PolicyOutputExt;
+use crate::commands::common::with_device_turn;
+
+const DATASTORE_EXTENSION: &str = ".preferences_pb";
+const PLIST_EXTENSION: &str = ".plist";
+const IOS_PREFERENCES_DIR: &str = "/Library/Preferences";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreferenceEntry {
+    pub key: String,
+    pub value_type: String,
+    pub value: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreferencesTable {
+    pub local_path: String,
+    pub remote_path: Option<String>,
+    pub format: String,
+    pub entries: Vec<PreferenceEntry>,
+}
+
+fn entry(key: &str, value_type: &str, value: serde_json::Value) -> PreferenceEntry {
+    PreferenceEntry {
+        key: key.to_string(),
+        value_type: value_type.to_string(),
+        value,
+    }
+}
+
+fn float_to_json(value: f64) -> serde_json::Value {
+    serde_json::Number::from_f64(value)
+        .map(serde_json::Value::Number)
+        .unwrap_or(serde_json::Value::Null)
+}
+
+// --- Android DataStore (protobuf) ---
+
+struct ProtoReader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ProtoReader<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    fn is_done(&self) -> bool {
+        self.pos >= self.buf.len()
+    }
+
+    fn read_varint(&mut self) -> Result<u64, String> {
+        let mut result = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = *self.buf.get(self.pos).ok_or("Truncated varint")?;
+            self.pos += 1;
+            result |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+        }
+        Err("Varint is too long".to_string())
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self.pos.checked_add(len).ok_or("Invalid length")?;
+        let bytes = self.buf.get(self.pos..end).ok_or("Truncated field")?;
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn read_length_delimited(&mut self) -> Result<&'a [u8], String> {
+        let len = self.read_varint()? as usize;
+        self.read_bytes(len)
+    }
+
+    /// Returns (field number, wire type)
+    fn read_tag(&mut self) -> Result<(u64, u8), String> {
+        let tag = self.read_varint()?;
+        Ok((tag >> 3, (tag & 0x7) as u8))
+    }
+
+    fn skip(&mut self, wire_type: u8) -> Result<(), String> {
+        match wire_type {
+            0 => self.read_varint().map(|_| ()),
+            1 => self.read_bytes(8).map(|_| ()),
+            2 => self.read_length_delimited().map(|_| ()),
+            5 => self.read_bytes(4).map(|_| ()),
+            other => Err(format!("Unsupported wire type {}", other)),
+        }
+    }
+}
+
+fn utf8(bytes: &[u8]) -> Result<String, String> {
+    String::from_utf8(bytes.to_vec()).map_err(|e| format!("Invalid UTF-8: {}", e))
+}
+
+/// Decode a DataStore `Value` message (a oneof of the supported preference types).
+fn parse_datastore_value(bytes: &[u8]) -> Result<(String, serde_json::Value), String> {
+    let mut reader = ProtoReader::new(bytes);
+    let mut parsed = ("unknown".to_string(), serde_json::Value::Null);
+
+    while !reader.is_done() {
+        let (field, wire_type) = reader.read_tag()?;
+        parsed = match (field, wire_type) {
+            (1, 0) => (
+                "boolean".to_string(),
+                serde_json::Value::Bool(reader.read_varint()? != 0),
+            ),
+            (2, 5) => {
+                let raw = reader.read_bytes(4)?;
+                let value = f32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]);
+                ("float".to_string(), float_to_json(f64::from(value)))
+            }
+            (3, 0) => (
+                "integer".to_string(),
+                serde_json::json!(reader.read_varint()? as i64 as i32),
+            ),
+            (4, 0) => ("long".to_string(), serde_json::json!(reader.read_varint()? as i64)),
+            (5, 2) => (
+                "string".to_string(),
+                serde_json::Value::String(utf8(reader.read_length_delimited()?)?),
+            ),
+            (6, 2) => {
+                let mut set_reader = ProtoReader::new(reader.read_length_delimited()?);
+                let mut strings = Vec::new();
+                while !set_reader.is_done() {
+                    match set_reader.read_tag()? {
+                        (1, 2) => {
+                            let value = utf8(set_reader.read_length_delimited()?)?;
+                            strings.push(serde_json::Value::String(value));
+                        }
+                        (_, other) => set_reader.skip(other)?,
+                    }
+                }
+                ("stringSet".to_string(), serde_json::Value::Array(strings))
+            }
+            (7, 1) => {
+                let raw = reader.read_bytes(8)?;
+                let mut le = [0u8; 8];
+                le.copy_from_slice(raw);
+                ("double".to_string(), float_to_json(f64::from_le_bytes(le)))
+            }
+            (8, 2) => {
+                let bytes = reader.read_length_delimited()?;
+                (
+                    "bytes".to_string(),
+                    serde_json::Value::String(general_purpose::STANDARD.encode(bytes)),
+                )
+            }
+            (_, other) => {
+                reader.skip(other)?;
+                continue;
+            }
+        };
+    }
+
+    Ok(parsed)
+}
+
+/// Parse the `PreferenceMap` message stored in a DataStore `.preferences_pb` file.
+pub fn parse_datastore_preferences(bytes: &[u8]) -> Result<Vec<PreferenceEntry>, String> {
+    let mut reader = ProtoReader::new(bytes);
+    let mut entries = Vec::new();
+
+    while !reader.is_done() {
+        let (field, wire_type) = reader.read_tag()?;
+        if field != 1 || wire_type != 2 {
+            reader.skip(wire_type)?;
+            continue;
+        }
+
+        // Map entries are encoded as { 1: key, 2: value }
+        let mut entry_reader = ProtoReader::new(reader.read_length_delimited()?);
+        let mut key = String::new();
+        let mut value = ("unknown".to_string(), serde_json::Value::Null);
+        while !entry_reader.is_done() {
+            match entry_reader.read_tag()? {
+                (1, 2) => key = utf8(entry_reader.read_length_delimited()?)?,
+                (2, 2) => value = parse_datastore_value(entry_reader.read_length_delimited()?)?,
+                (_, other) => entry_reader.skip(other)?,
+            }
+        }
+
+        entries.push(entry(&key, &value.0, value.1));
+    }
+
+    entries.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(entries)
+}
+
+// --- iOS UserDefaults (plist) ---
+
+fn plist_to_json(value: &plist::Value) -> (&'static str, serde_json::Value) {
+    match value {
+        plist::Value::Boolean(b) => ("boolean", serde_json::Value::Bool(*b)),
+        plist::Value::Integer(i) => (
+            "integer",
+            i.as_signed()
+                .map(|v| serde_json::json!(v))
+                .or_else(|| i.as_unsigned().map(|v| serde_json::json!(v)))
+                .unwrap_or(serde_json::Value::Null),
+        ),
+        plist::Value::Real(f) => ("real", float_to_json(*f)),
+        plist::Value::String(s) => ("string", serde_json::Value::String(s.clone())),
+        plist::Value::Date(d) => ("date", serde_json::Value::String(d.to_xml_format())),
+        plist::Value::Data(bytes) => (
+            "data",
+            serde_json::Value::String(general_purpose::STANDARD.encode(bytes)),
+        ),
+        plist::Value::Array(items) => (
+            "array",
+            serde_json::Value::Array(items.iter().map(|item| plist_to_json(item).1).collect()),
+        ),
+        plist::Value::Dictionary(dict) => (
+            "dictionary",
+            serde_json::Value::Object(
+                dict.iter()
+                    .map(|(k, v)| (k.clone(), plist_to_json(v).1))
+                    .collect(),
+            ),
+        ),
+        plist::Value::Uid(uid) => ("uid", serde_json::json!(uid.get())),
+        _ => ("unknown", serde_json::Value::Null),
+    }
+}
+
+/// Parse a UserDefaults plist (binary or XML) into top-level key-value entries.
+pub fn parse_plist_preferences(path: &Path) -> Result<Vec<PreferenceEntry>, String> {
+    let value =
+        plist::Value::from_file(path).map_err(|e| format!("Failed to read plist: {}", e))?;
+    let dict = value
+        .as_dictionary()
+        .ok_or("Preferences plist does not contain a dictionary at its root")?;
+
+    let mut entries: Vec<PreferenceEntry> = dict
+        .iter()
+        .map(|(key, value)| {
+            let (value_type, json) = plist_to_json(value);
+            entry(key, value_type, json)
+        })
+        .collect();
+    entries.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(entries)
+}
+
+/// Parse a local preferences file, picking the format from its extension.
+pub fn read_preferences_file(
+    local_path: &str,
+    remote_path: Option<String>,
+) -> Result<PreferencesTable, String> {
+    let lowercase = local_path.to_lowercase();
+    let (format, entries) = if lowercase.ends_with(DATASTORE_EXTENSION) {
+        let bytes = fs::read(local_path).map_err(|e| format!("Failed to read file: {}", e))?;
+        ("datastore", parse_datastore_preferences(&bytes)?)
+    } else if lowercase.ends_with(PLIST_EXTENSION) {
+        ("plist", parse_plist_preferences(Path::new(local_path))?)
+    } else {
+        return Err(format!("Unsupported preferences file: {}", local_path));
+    };
+
+    Ok(PreferencesTable {
+        local_path: local_path.to_string(),
+        remote_path,
+        format: format.to_string(),
+        entries,
+    })
+}
+
+// adb shell and exec-out join the arguments into one device command line, so every remote
+// argument is quoted
+fn android_find_datastore_args(device_id: &str, package_name: &str) -> Vec<String> {
+    vec![
+        "-s".to_string(),
+        device_id.to_string(),
+        "shell".to_string(),
+        "run-as".to_string(),
+        shell_quote(package_name),
+        "find".to_string(),
+        shell_quote(&format!("/data/data/{}/files/datastore", package_name)),
+        "-name".to_string(),
+        shell_quote(&format!("*{}", DATASTORE_EXTENSION)),
+    ]
+}
+
+fn android_cat_args(device_id: &str, package_name: &str, remote_path: &str) -> Vec<String> {
+    vec![
+        "-s".to_string(),
+        device_id.to_string(),
+        "exec-out".to_string(),
+        "run-as".to_string(),
+        shell_quote(package_name),
+        "cat".to_string(),
+        shell_quote(remote_path),
+    ]
+}
+
+fn parse_find_output(output: &[u8]) -> Vec<String> {
+    String::from_utf8_lossy(output)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+async fn simulator_container_path(
+    app_handle: &tauri::AppHandle,
+    device_id: &str,
+    package_name: &str,
+) -> Result<String, String> {
+    if !IOS_SIMULATORS_SUPPORTED {
+        return Err(SIMULATOR_UNSUPPORTED_ERROR.to_string());
+    }
+
+    let output = app_handle
+        .shell()
+        .command(get_xcrun_path())
+        .args(["simctl", "get_app_container", device_id, package_name, "data"])
+        .output_with_policy()
+        .await
+        .map_err(|e| format!("Failed to execute get_app_container: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to get app container: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+async fn list_preference_files(
+    app_handle: &tauri::AppHandle,
+    device_id: &str,
+    package_name: &str,
+    device_type: &str,
+) -> Result<Vec<String>, String> {
+    match device_type {
+        "android" | "emulator" => {
+            let args = android_find_datastore_args(device_id, package_name);
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
+            let output = execute_adb_command(&args)
+                .await
+                .map_err(|e| format!("Failed to list DataStore files: {}", e))?;
+
+            // A missing datastore directory simply means the app has no DataStore files
+            Ok(if output.status.success() {
+                parse_find_output(&output.stdout)
+            } else {
+                Vec::new()
+            })
+        }
+        "simulator" => {
+            let container = simulator_container_path(app_handle, device_id, package_name).await?;
+            let preferences_dir = Path::new(&container).join("Library").join("Preferences");
+            let mut files: Vec<String> = match fs::read_dir(&preferences_dir) {
+                Ok(entries) => entries
+                    .filter_map(Result::ok)
+                    .map(|entry| entry.path())
+                    .filter(|path| path.to_string_lossy().ends_with(PLIST_EXTENSION))
+                    .map(|path| path.to_string_lossy().to_string())
+                    .collect(),
+                Err(_) => Vec::new(),
+            };
+            files.sort();
+            Ok(files)
+        }
+        _ => {
+            let afcclient_cmd = get_tool_command_legacy("afcclient");
+            let shell = app_handle.shell();
+            let entries = list_ios_directory(
+                shell,
+                &afcclient_cmd,
+                package_name,
+                device_id,
+                IOS_PREFERENCES_DIR,
+                IosAppAccessType::Container,
+            )
+            .await?;
+            Ok(entries
+                .into_iter()
+                .filter(|path| path.ends_with(PLIST_EXTENSION))
+                .collect())
+        }
+    }
+}
+
+async fn pull_android_preferences_file(
+    device_id: &str,
+    package_name: &str,
+    remote_path: &str,
+) -> Result<String, String> {
+    let temp_dir =
+        ensure_temp_dir().map_err(|e| format!("Failed to prepare temp directory: {}", e))?;
+    let local_path = namespaced_temp_path(&temp_dir, device_id, package_name, remote_path)
+        .map_err(|e| e.to_string())?;
+
+    let args = android_cat_args(device_id, package_name, remote_path);
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let output = execute_adb_command(&args)
+        .await
+        .map_err(|e| format!("Failed to pull {}: {}", remote_path, e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to pull {}: {}",
+            remote_path,
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    fs::write(&local_path, &output.stdout)
+        .map_err(|e| format!("Failed to write local copy: {}", e))?;
+    Ok(local_path.to_string_lossy().to_string())
+}
+
+/// List the preferences files (UserDefaults plists or DataStore files) of an app
+#[tauri::command]
+pub async fn get_app_preference_files(
+    app_handle: tauri::AppHandle,
+    device_id: String,
+    package_name: String,
+    device_type: String,
+) -> Result<DeviceResponse<Vec<String>>, String> {
+    info!(
+        "🔍 Listing preference files for {} on {} ({})",
+        package_name, device_id, device_type
+    );
+
+    match list_preference_files(&app_handle, &device_id, &package_name, &device_type).await {
+        Ok(files) => Ok(DeviceResponse {
+            success: true,
+            data: Some(files),
+            error: None,
+            error_detail: None,
+        }),
+        Err(e) => {
+            error!("❌ Failed to list preference files: {}", e);
+            Ok(DeviceResponse {
+                success: false,
+                data: None,
+                error_detail: Some(e.device_error()),
+                error: Some(e),
+            })
+        }
+    }
+}
+
+/// Pull a preferences file from the device and parse it into a key-value table
+#[tauri::command]
+pub async fn pull_app_preferences(
+    app_handle: tauri::AppHandle,
+    device_id: String,
+    package_name: String,
+    device_type: String,
+    remote_path: String,
+) -> Result<DeviceResponse<PreferencesTable>, String> {
+    info!(
+        "📥 Pulling preferences {} for {} ({})",
+        remote_path, package_name, device_type
+    );
+
+    let local_path = match device_type.as_str() {
+        "android" | "emulator" => {
+            with_device_turn(&device_id, pull_android_preferences_file(&device_id, &package_name, &remote_path)).await
+        }
+        // Simulator containers live on the host, so the file can be read in place
+        "simulator" => Ok(remote_path.clone()),
+        _ => pull_ios_db_file(
+            &app_handle,
+            &device_id,
+            &package_name,
+            &remote_path,
+            true,
+            IosAppAccessType::Container,
+        )
+        .await
+        .map_err(|e| e.to_string()),
+    };
+
+    let result =
+        local_path.and_then(|path| read_preferences_file(&path, Some(remote_path.clone())));
+
+    match result {
+        Ok(table) => {
+            info!(
+                "✅ Parsed {} preference entries from {}",
+                table.entries.len(),
+                remote_path
+            );
+            Ok(DeviceResponse {
+                success: true,
+                data: Some(table),
+                error: None,
+                error_detail: None,
+            })
+        }
+        Err(e) => {
+            error!("❌ Failed to read preferences {}: {}", remote_path, e);
+            Ok(DeviceResponse {
+                success: false,
+                data: None,
+                error_detail: Some(e.device_error()),
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn varint(mut value: u64) -> Vec<u8> {
+        let mut out = Vec::new();
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                out.push(byte);
+                return out;
+            }
+            out.push(byte | 0x80);
+        }
+    }
+
+    fn length_delimited(field: u64, payload: &[u8]) -> Vec<u8> {
+        let mut out = varint((field << 3) | 2);
+        out.extend(varint(payload.len() as u64));
+        out.extend_from_slice(payload);
+        out
+    }
+
+    fn map_entry(key: &str, value: Vec<u8>) -> Vec<u8> {
+        let mut body = length_delimited(1, key.as_bytes());
+        body.extend(length_delimited(2, &value));
+        length_delimited(1, &body)
+    }
+
+    #[test]
+    fn test_parse_datastore_preferences() {
+        let mut file = Vec::new();
+        file.extend(map_entry("onboarding_done", [varint(1 << 3), varint(1)].concat()));
+        file.extend(map_entry("user_name", length_delimited(5, b"jane")));
+        file.extend(map_entry("launch_count", [varint(3 << 3), varint(42)].concat()));
+        file.extend(map_entry("last_sync", [varint(4 << 3), varint(1_700_000_000_000)].concat()));
+        let mut float_value = varint((2 << 3) | 5);
+        float_value.extend_from_slice(&1.5f32.to_le_bytes());
+        file.extend(map_entry("scale", float_value));
+        let string_set = [length_delimited(1, b"a"), length_delimited(1, b"b")].concat();
+        file.extend(map_entry("tags", length_delimited(6, &string_set)));
+
+        let entries = parse_datastore_preferences(&file).unwrap();
+
+        assert_eq!(entries.len(), 6);
+        assert_eq!(entries[0], entry("last_sync", "long", serde_json::json!(1_700_000_000_000i64)));
+        assert_eq!(entries[1], entry("launch_count", "integer", serde_json::json!(42)));
+        assert_eq!(entries[2], entry("onboarding_done", "boolean", serde_json::json!(true)));
+        assert_eq!(entries[3], entry("scale", "float", serde_json::json!(1.5)));
+        assert_eq!(entries[4], entry("tags", "stringSet", serde_json::json!(["a", "b"])));
+        assert_eq!(entries[5], entry("user_name", "string", serde_json::json!("jane")));
+    }
+
+    #[test]
+    fn test_parse_datastore_preferences_rejects_truncated_input() {
+        let file = map_entry("user_name", length_delimited(5, b"jane"));
+        assert!(parse_datastore_preferences(&file[..file.len() - 2]).is_err());
+    }
+
+    #[test]
+    fn test_read_plist_preferences_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("com.example.app.plist");
+        fs::write(
+            &path,
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>hasSeenIntro</key>
+    <true/>
+    <key>launchCount</key>
+    <integer>7</integer>
+    <key>recentSearches</key>
+    <array><string>coffee</string></array>
+</dict>
+</plist>"#,
+        )
+        .unwrap();
+
+        let table = read_preferences_file(path.to_str().unwrap(), None).unwrap();
+
+        assert_eq!(table.format, "plist");
+        assert_eq!(table.entries[0], entry("hasSeenIntro", "boolean", serde_json::json!(true)));
+        assert_eq!(table.entries[1], entry("launchCount", "integer", serde_json::json!(7)));
+        assert_eq!(table.entries[2], entry("recentSearches", "array", serde_json::json!(["coffee"])));
+    }
+
+    #[test]
+    fn test_read_preferences_file_rejects_unknown_format() {
+        assert!(read_preferences_file("/tmp/settings.json", None).is_err());
+    }
+
+    #[test]
+    fn test_android_preferences_args_quote_remote_arguments() {
+        let find = android_find_datastore_args("emulator-5554", "com.example.app");
+        assert_eq!(find[4], "'com.example.app'");
+        assert_eq!(find[6], "'/data/data/com.example.app/files/datastore'");
+        assert_eq!(find[8], "'*.preferences_pb'");
+
+        let cat = android_cat_args("emulator-5554", "com.example.app", "/data/data/x/it's; rm -rf x");
+        assert_eq!(cat[2], "exec-out");
+        assert_eq!(cat[6], "'/data/data/x/it'\\''s; rm -rf x'");
+    }
+}
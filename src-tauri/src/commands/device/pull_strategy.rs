@@ -0,0 +1,185 @@
+//! Pull strategies and their fallback order
+//!
+//! A pull starts with the strategy the file's location calls for. When that one is refused
+//! (`run-as` on a release build, an afcclient permission error) the next strategy the
+//! device's capability matrix allows is tried, and the one that worked is recorded in the
+//! pulled file's sidecar, so the failure the user sees is the last one rather than the first.
+
+use super::android_storage::{classify_push_error, PushErrorKind};
+use super::capabilities::DeviceCapabilities;
+use super::integrity::read_pulled_file_metadata;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::future::Future;
+use std::path::Path;
+
+// exec-out has no separate stderr, so a refused pull leaves the error text in the local file.
+// Anything this short can't be a database, which is at least one page.
+const EXEC_OUT_ERROR_MAX_LEN: u64 = 512;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PullStrategy {
+    /// `run-as <pkg> cat` over exec-out; debuggable builds only
+    RunAs,
+    /// `cat` as root, through a root adbd or `su`
+    Root,
+    /// Plain `adb pull`, for files the shell user can read
+    AdbPull,
+    /// afcclient through the app container (house_arrest)
+    AfcContainer,
+    /// afcclient through the Documents folder of apps with file sharing enabled
+    AfcDocuments,
+}
+
+impl PullStrategy {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PullStrategy::RunAs => "run-as",
+            PullStrategy::Root => "root",
+            PullStrategy::AdbPull => "adb pull",
+            PullStrategy::AfcContainer => "app container",
+            PullStrategy::AfcDocuments => "app documents",
+        }
+    }
+}
+
+/// Strategies for an Android pull, primary first. Root is only tried on a device whose probe
+/// found it, so an unprobed device never gets a `su` prompt; the others are tried unless the
+/// probe ruled them out.
+pub fn android_pull_strategies(admin_access: bool, capabilities: Option<&DeviceCapabilities>) -> Vec<PullStrategy> {
+    let allowed = |strategy: PullStrategy| match (strategy, capabilities) {
+        (PullStrategy::Root, capabilities) => capabilities.is_some_and(|capabilities| capabilities.root),
+        (PullStrategy::RunAs, Some(capabilities)) => capabilities.run_as,
+        _ => true,
+    };
+    // run-as only reaches the app's own files
+    let candidates: &[PullStrategy] = if admin_access {
+        &[PullStrategy::RunAs, PullStrategy::Root, PullStrategy::AdbPull]
+    } else {
+        &[PullStrategy::AdbPull, PullStrategy::Root]
+    };
+
+    let mut strategies = vec![candidates[0]];
+    strategies.extend(candidates[1..].iter().copied().filter(|strategy| allowed(*strategy)));
+    strategies
+}
+
+/// Strategies for a pull from a physical iOS device, primary first. The Documents folder is
+/// only reachable through file sharing, so it is a fallback for files inside it.
+pub fn ios_pull_strategies(remote_path: &str, capabilities: Option<&DeviceCapabilities>) -> Vec<PullStrategy> {
+    let mut strategies = vec![PullStrategy::AfcContainer];
+    if remote_path.starts_with("/Documents/") && capabilities.is_none_or(|capabilities| capabilities.afc_documents) {
+        strategies.push(PullStrategy::AfcDocuments);
+    }
+    strategies
+}
+
+/// The strategy recorded in the sidecar of a pulled file
+pub fn pulled_with(local_path: &Path) -> Option<PullStrategy> {
+    read_pulled_file_metadata(local_path).and_then(|metadata| metadata.pull_strategy)
+}
+
+/// The device's error message if an exec-out pull wrote one into `local_path` instead of the file
+pub fn exec_out_error(local_path: &Path) -> Option<String> {
+    let len = fs::metadata(local_path).ok()?.len();
+    if len > EXEC_OUT_ERROR_MAX_LEN {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&fs::read(local_path).ok()?).trim().to_string();
+    (classify_push_error(&text) != PushErrorKind::Unknown).then_some(text)
+}
+
+/// Run `attempt` with each strategy in turn until one succeeds and return that strategy.
+/// When all of them fail, the error lists what each one reported.
+pub async fn pull_with_fallbacks<F, Fut>(strategies: &[PullStrategy], mut attempt: F) -> Result<PullStrategy, String>
+where
+    F: FnMut(PullStrategy) -> Fut,
+    Fut: Future<Output = Result<(), String>>,
+{
+    let mut failures = Vec::new();
+    for &strategy in strategies {
+        crate::commands::common::check_cancelled()?;
+        match attempt(strategy).await {
+            Ok(()) => {
+                if !failures.is_empty() {
+                    log::info!("🔁 Pulled with {} after: {}", strategy.label(), failures.join("; "));
+                }
+                return Ok(strategy);
+            }
+            Err(e) => {
+                log::warn!("⚠️ Pull with {} failed: {}", strategy.label(), e);
+                failures.push(format!("{}: {}", strategy.label(), e));
+            }
+        }
+    }
+    Err(format!("Every pull strategy failed ({})", failures.join("; ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn capabilities(run_as: bool, root: bool) -> DeviceCapabilities {
+        let mut capabilities = DeviceCapabilities::new("emulator-5554", "android");
+        capabilities.run_as = run_as;
+        capabilities.root = root;
+        capabilities
+    }
+
+    #[test]
+    fn test_android_pull_strategies_follow_capabilities() {
+        assert_eq!(android_pull_strategies(true, None), vec![PullStrategy::RunAs, PullStrategy::AdbPull]);
+        assert_eq!(android_pull_strategies(false, None), vec![PullStrategy::AdbPull]);
+        assert_eq!(
+            android_pull_strategies(true, Some(&capabilities(true, false))),
+            vec![PullStrategy::RunAs, PullStrategy::AdbPull]
+        );
+        // The primary strategy is always tried, even when the probe missed it
+        assert_eq!(
+            android_pull_strategies(true, Some(&capabilities(false, true))),
+            vec![PullStrategy::RunAs, PullStrategy::Root, PullStrategy::AdbPull]
+        );
+        assert_eq!(
+            android_pull_strategies(false, Some(&capabilities(true, false))),
+            vec![PullStrategy::AdbPull]
+        );
+        assert_eq!(ios_pull_strategies("/Library/main.sqlite", None), vec![PullStrategy::AfcContainer]);
+        assert_eq!(
+            ios_pull_strategies("/Documents/user.db", None),
+            vec![PullStrategy::AfcContainer, PullStrategy::AfcDocuments]
+        );
+    }
+
+    #[test]
+    fn test_exec_out_error_detects_refused_pull() {
+        let temp_dir = TempDir::new().unwrap();
+        let refused = temp_dir.path().join("refused.db");
+        fs::write(&refused, b"run-as: package not debuggable: com.example.app\n").unwrap();
+        assert!(exec_out_error(&refused).unwrap().contains("not debuggable"));
+
+        let database = temp_dir.path().join("app.db");
+        fs::write(&database, [b"SQLite format 3\0".as_slice(), &[0u8; 4080]].concat()).unwrap();
+        assert_eq!(exec_out_error(&database), None);
+    }
+
+    #[tokio::test]
+    async fn test_pull_with_fallbacks_reports_each_failure() {
+        let strategies = [PullStrategy::RunAs, PullStrategy::Root, PullStrategy::AdbPull];
+        let used = pull_with_fallbacks(&strategies, |strategy| async move {
+            match strategy {
+                PullStrategy::RunAs => Err("package not debuggable".to_string()),
+                _ => Ok(()),
+            }
+        })
+        .await;
+        assert_eq!(used, Ok(PullStrategy::Root));
+
+        let error = pull_with_fallbacks(&strategies, |_| async { Err("permission denied".to_string()) })
+            .await
+            .unwrap_err();
+        assert!(error.contains("run-as: permission denied"));
+        assert!(error.contains("adb pull: permission denied"));
+    }
+}
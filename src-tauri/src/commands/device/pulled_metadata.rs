@@ -0,0 +1,252 @@
+//! Pulled file metadata
+//!
+//! Exposes the `.meta.json` sidecars written next to pulled databases and compares the
+//! pull time with the remote file's modification time, so the UI can warn when the app
+//! has changed its database since the local copy was taken.
+
+use super::helpers::execute_adb_command;
+use super::integrity::read_pulled_file_metadata;
+use super::ios::tools::get_tool_command_legacy;
+use super::types::{DatabaseFileMetadata, DeviceResponse};
+use super::errors::CommandErrorExt;
+use chrono::{DateTime, TimeZone, Utc};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri_plugin_shell::ShellExt;
+use super::command_policy::PolicyOutputExt;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PulledFileInfo {
+    pub local_path: String,
+    pub device_id: String,
+    pub package_name: String,
+    pub remote_path: String,
+    pub pulled_at: String,
+    pub sha256: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PulledFileStaleness {
+    pub pulled_at: String,
+    pub remote_modified_at: Option<String>,
+    // Seconds since the pull
+    pub age_seconds: i64,
+    // The remote file changed after it was pulled
+    pub is_stale: bool,
+}
+
+fn load_metadata(local_path: &str) -> Result<DatabaseFileMetadata, String> {
+    read_pulled_file_metadata(Path::new(local_path))
+        .ok_or_else(|| format!("No pull metadata found for {}", local_path))
+}
+
+fn parse_pulled_at(metadata: &DatabaseFileMetadata) -> Result<DateTime<Utc>, String> {
+    DateTime::parse_from_rfc3339(&metadata.timestamp)
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .map_err(|e| format!("Invalid pull timestamp '{}': {}", metadata.timestamp, e))
+}
+
+pub fn evaluate_staleness(
+    pulled_at: DateTime<Utc>,
+    remote_modified_at: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> PulledFileStaleness {
+    PulledFileStaleness {
+        pulled_at: pulled_at.to_rfc3339(),
+        remote_modified_at: remote_modified_at.map(|modified| modified.to_rfc3339()),
+        age_seconds: (now - pulled_at).num_seconds().max(0),
+        is_stale: remote_modified_at.is_some_and(|modified| modified > pulled_at),
+    }
+}
+
+/// Parse the epoch seconds printed by `stat -c %Y`.
+fn parse_stat_epoch(stdout: &str) -> Option<DateTime<Utc>> {
+    let seconds = stdout.trim().lines().next()?.trim().parse::<i64>().ok()?;
+    Utc.timestamp_opt(seconds, 0).single()
+}
+
+/// Parse the `st_mtime` line (nanoseconds) printed by `afcclient info`.
+fn parse_afc_mtime(stdout: &str) -> Option<DateTime<Utc>> {
+    stdout.lines().find_map(|line| {
+        let value = line.trim().strip_prefix("st_mtime")?;
+        let nanos = value.trim_start_matches([':', ' ', '\t']).trim().parse::<i64>().ok()?;
+        Some(Utc.timestamp_nanos(nanos))
+    })
+}
+
+async fn android_remote_mtime(metadata: &DatabaseFileMetadata) -> Result<DateTime<Utc>, String> {
+    let remote_path = metadata.remote_path.as_str();
+    let mut args = vec!["-s", metadata.device_id.as_str(), "shell"];
+    // External storage is readable without run-as, mirroring how pushes are done
+    if !(remote_path.contains("sdcard") || remote_path.contains("external")) {
+        args.extend(["run-as", metadata.package_name.as_str()]);
+    }
+    args.extend(["stat", "-c", "%Y", remote_path]);
+
+    let output = execute_adb_command(&args)
+        .await
+        .map_err(|e| format!("Failed to stat remote file: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to stat remote file: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    parse_stat_epoch(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| "Unexpected stat output".to_string())
+}
+
+async fn ios_device_remote_mtime(
+    app_handle: &tauri::AppHandle,
+    metadata: &DatabaseFileMetadata,
+) -> Result<DateTime<Utc>, String> {
+    let afcclient_cmd = get_tool_command_legacy("afcclient");
+    let output = app_handle
+        .shell()
+        .command(&afcclient_cmd)
+        .args([
+            "--container",
+            &metadata.package_name,
+            "-u",
+            &metadata.device_id,
+            "info",
+            &metadata.remote_path,
+        ])
+        .output_with_policy()
+        .await
+        .map_err(|e| format!("Failed to execute afcclient info: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "afcclient info failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    parse_afc_mtime(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| "afcclient info did not report st_mtime".to_string())
+}
+
+async fn remote_mtime(
+    app_handle: &tauri::AppHandle,
+    metadata: &DatabaseFileMetadata,
+    device_type: &str,
+) -> Result<DateTime<Utc>, String> {
+    match device_type {
+        "android" | "emulator" => android_remote_mtime(metadata).await,
+        // Simulator containers live on the host
+        "simulator" => std::fs::metadata(&metadata.remote_path)
+            .and_then(|m| m.modified())
+            .map(DateTime::<Utc>::from)
+            .map_err(|e| format!("Failed to read remote file time: {}", e)),
+        _ => ios_device_remote_mtime(app_handle, metadata).await,
+    }
+}
+
+/// Read the metadata recorded when a database was pulled
+#[tauri::command]
+pub async fn get_pulled_file_metadata(
+    local_path: String,
+) -> Result<DeviceResponse<PulledFileInfo>, String> {
+    match load_metadata(&local_path) {
+        Ok(metadata) => Ok(DeviceResponse {
+            success: true,
+            data: Some(PulledFileInfo {
+                local_path,
+                device_id: metadata.device_id,
+                package_name: metadata.package_name,
+                remote_path: metadata.remote_path,
+                pulled_at: metadata.timestamp,
+                sha256: metadata.sha256,
+            }),
+            error: None,
+            error_detail: None,
+        }),
+        Err(e) => Ok(DeviceResponse {
+            success: false,
+            data: None,
+            error_detail: Some(e.device_error()),
+            error: Some(e),
+        }),
+    }
+}
+
+/// Compare a pulled copy's timestamp with the remote file's modification time
+#[tauri::command]
+pub async fn check_pulled_file_staleness(
+    app_handle: tauri::AppHandle,
+    local_path: String,
+    device_type: String,
+) -> Result<DeviceResponse<PulledFileStaleness>, String> {
+    info!("🕒 Checking staleness of {} ({})", local_path, device_type);
+
+    let result = async {
+        let metadata = load_metadata(&local_path)?;
+        let pulled_at = parse_pulled_at(&metadata)?;
+        let remote_modified_at = remote_mtime(&app_handle, &metadata, &device_type).await?;
+        Ok::<_, String>(evaluate_staleness(pulled_at, Some(remote_modified_at), Utc::now()))
+    }
+    .await;
+
+    match result {
+        Ok(staleness) => {
+            if staleness.is_stale {
+                info!("⚠️ Remote database changed since {} was pulled", local_path);
+            }
+            Ok(DeviceResponse {
+                success: true,
+                data: Some(staleness),
+                error: None,
+                error_detail: None,
+            })
+        }
+        Err(e) => {
+            error!("❌ Failed to check pulled file staleness: {}", e);
+            Ok(DeviceResponse {
+                success: false,
+                data: None,
+                error_detail: Some(e.device_error()),
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate_staleness() {
+        let pulled_at = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 14, 0, 0).unwrap();
+
+        let fresh = evaluate_staleness(pulled_at, Some(pulled_at - chrono::Duration::minutes(5)), now);
+        assert!(!fresh.is_stale);
+        assert_eq!(fresh.age_seconds, 7200);
+
+        let stale = evaluate_staleness(pulled_at, Some(pulled_at + chrono::Duration::minutes(30)), now);
+        assert!(stale.is_stale);
+
+        assert!(!evaluate_staleness(pulled_at, None, now).is_stale);
+    }
+
+    #[test]
+    fn test_parse_remote_mtime_outputs() {
+        assert_eq!(
+            parse_stat_epoch("1704110400\n"),
+            Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).single()
+        );
+        assert_eq!(parse_stat_epoch("stat: No such file"), None);
+
+        let afc_output = "st_size: 4096\nst_blocks: 8\nst_mtime: 1704110400000000000\n";
+        assert_eq!(
+            parse_afc_mtime(afc_output),
+            Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).single()
+        );
+        assert_eq!(parse_afc_mtime("st_size: 4096"), None);
+    }
+}
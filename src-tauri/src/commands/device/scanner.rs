@@ -0,0 +1,170 @@
+//! Cached device discovery
+//!
+//! The UI polls the device and package lists, and each poll used to spawn a fresh round of
+//! adb/simctl/libimobiledevice processes. `DeviceScanner` keeps the last successful result
+//! of each scan for a short TTL and coalesces concurrent requests for the same scan, so
+//! callers waiting on a scan in flight share its result instead of starting their own.
+
+use super::types::DeviceResponse;
+use std::any::Any;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+pub const DEVICE_LIST_TTL: Duration = Duration::from_secs(3);
+pub const PACKAGE_LIST_TTL: Duration = Duration::from_secs(30);
+
+pub static DEVICE_SCANNER: LazyLock<DeviceScanner> = LazyLock::new(DeviceScanner::default);
+
+struct CachedScan {
+    scanned_at: Instant,
+    value: Arc<dyn Any + Send + Sync>,
+}
+
+// The async mutex is held for the duration of a scan, which is what coalesces callers
+type ScanSlot = Arc<tokio::sync::Mutex<Option<CachedScan>>>;
+
+#[derive(Default)]
+pub struct DeviceScanner {
+    slots: Mutex<HashMap<String, ScanSlot>>,
+}
+
+/// Cache key of a device's package list, also used as the prefix to invalidate it.
+pub fn packages_scan_key(device_id: &str) -> String {
+    format!("packages:{}:", device_id)
+}
+
+impl DeviceScanner {
+    fn slot(&self, key: &str) -> ScanSlot {
+        let mut slots = self.slots.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        slots.entry(key.to_string()).or_default().clone()
+    }
+
+    /// Return the cached result of `key` if it is younger than `ttl`, otherwise run `scan`.
+    /// Only successful responses are cached, so a failed scan is retried on the next call.
+    pub async fn scan<T, F, Fut>(
+        &self,
+        key: &str,
+        ttl: Duration,
+        force_refresh: bool,
+        scan: F,
+    ) -> Result<DeviceResponse<T>, String>
+    where
+        T: Clone + Send + Sync + 'static,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<DeviceResponse<T>, String>>,
+    {
+        let requested_at = Instant::now();
+        let slot = self.slot(key);
+        let mut cached = slot.lock().await;
+
+        if let Some(entry) = cached.as_ref() {
+            // A scan that finished while we waited is fresh enough even when forced
+            let fresh = if force_refresh {
+                entry.scanned_at >= requested_at
+            } else {
+                entry.scanned_at.elapsed() < ttl
+            };
+            if fresh {
+                if let Some(value) = entry.value.downcast_ref::<T>() {
+                    log::debug!("📦 Using cached scan {}", key);
+                    return Ok(DeviceResponse {
+                        success: true,
+                        data: Some(value.clone()),
+                        error: None,
+                        error_detail: None,
+                    });
+                }
+            }
+        }
+
+        let response = scan().await?;
+        *cached = response.data.as_ref().filter(|_| response.success).map(|data| CachedScan {
+            scanned_at: Instant::now(),
+            value: Arc::new(data.clone()) as Arc<dyn Any + Send + Sync>,
+        });
+        Ok(response)
+    }
+
+    /// Drop cached scans whose key starts with `prefix`, e.g. after installing an app.
+    pub fn invalidate(&self, prefix: &str) {
+        let mut slots = self.slots.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        // A slot with a scan in flight is left alone; that scan is about to refresh it anyway
+        slots.retain(|key, slot| !key.starts_with(prefix) || slot.try_lock().is_err());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::device::errors::DeviceError;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn ok(value: u32) -> Result<DeviceResponse<u32>, String> {
+        Ok(DeviceResponse {
+            success: true,
+            data: Some(value),
+            error: None,
+            error_detail: None,
+        })
+    }
+
+    async fn counted_scan(scanner: &DeviceScanner, key: &str, force: bool, calls: &AtomicUsize) -> Option<u32> {
+        scanner
+            .scan(key, Duration::from_secs(60), force, || async move {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                ok(calls.fetch_add(1, Ordering::SeqCst) as u32)
+            })
+            .await
+            .unwrap()
+            .data
+    }
+
+    #[tokio::test]
+    async fn test_scan_caches_and_force_refreshes() {
+        let scanner = DeviceScanner::default();
+        let calls = AtomicUsize::new(0);
+
+        assert_eq!(counted_scan(&scanner, "devices", false, &calls).await, Some(0));
+        assert_eq!(counted_scan(&scanner, "devices", false, &calls).await, Some(0));
+        assert_eq!(counted_scan(&scanner, "devices", true, &calls).await, Some(1));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        scanner.invalidate("dev");
+        assert_eq!(counted_scan(&scanner, "devices", false, &calls).await, Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_scan_does_not_cache_failures() {
+        let scanner = DeviceScanner::default();
+        let response = scanner
+            .scan("devices", Duration::from_secs(60), false, || async {
+                Ok::<DeviceResponse<u32>, String>(DeviceResponse {
+                    success: false,
+                    data: None,
+                    error_detail: Some(DeviceError::ToolMissing { tool: Some("adb".to_string()) }),
+                    error: Some("adb not found".to_string()),
+                })
+            })
+            .await;
+        assert!(!response.unwrap().success);
+
+        let calls = AtomicUsize::new(7);
+        assert_eq!(counted_scan(&scanner, "devices", false, &calls).await, Some(7));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_scans_are_coalesced() {
+        let scanner = DeviceScanner::default();
+        let calls = AtomicUsize::new(0);
+
+        let results = tokio::join!(
+            counted_scan(&scanner, "packages:x:", false, &calls),
+            counted_scan(&scanner, "packages:x:", false, &calls),
+            counted_scan(&scanner, "packages:x:", true, &calls),
+        );
+        assert_eq!(results, (Some(0), Some(0), Some(0)));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,441 @@
+//! Scheduled pulls
+//!
+//! Re-pulls a chosen device database every N minutes and keeps each pull as a
+//! timestamped snapshot in the app data directory, so the evolution of an app's
+//! data during a test session can be inspected afterwards. Schedules live for the
+//! session; snapshots stay on disk until their schedule is deleted with them.
+
+use super::adb::{pull_android_db_file, pull_android_db_file_to};
+use super::ios::file_utils::{pull_ios_db_file, pull_ios_db_file_to, IosAppAccessType};
+use super::types::DeviceResponse;
+use super::errors::{CommandErrorExt, DeviceError};
+use crate::commands::database::helpers::copy_database_with_companions;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+const SNAPSHOTS_DIR_NAME: &str = "snapshots";
+const SNAPSHOT_TAKEN_EVENT: &str = "scheduled-pull-snapshot";
+const SNAPSHOT_TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+const DEFAULT_MAX_SNAPSHOTS: usize = 100;
+
+static SCHEDULES: LazyLock<Mutex<HashMap<String, ScheduleEntry>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledPull {
+    pub id: String,
+    // "android", "iphone-device" or "simulator", as in DatabaseFile
+    pub device_type: String,
+    pub device_id: String,
+    pub package_name: String,
+    pub remote_path: String,
+    pub interval_minutes: u64,
+    // Oldest snapshots are removed beyond this count
+    pub max_snapshots: usize,
+    pub created_at: String,
+    pub last_run_at: Option<String>,
+    pub last_error: Option<String>,
+    pub snapshots: Vec<PullSnapshot>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PullSnapshot {
+    pub schedule_id: String,
+    pub path: String,
+    // RFC 3339, parsed back from the file name
+    pub taken_at: String,
+    pub size: u64,
+}
+
+struct ScheduleEntry {
+    schedule: ScheduledPull,
+    task: tauri::async_runtime::JoinHandle<()>,
+}
+
+pub(crate) fn snapshots_root(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .map(|dir| dir.join(SNAPSHOTS_DIR_NAME))
+        .map_err(|e| format!("No app data directory for snapshots: {}", e))
+}
+
+fn remote_file_name(remote_path: &str) -> &str {
+    remote_path
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .filter(|name| !name.is_empty())
+        .unwrap_or("database.db")
+}
+
+pub fn snapshot_file_name(taken_at: chrono::DateTime<chrono::Utc>, remote_path: &str) -> String {
+    format!("{}_{}", taken_at.format(SNAPSHOT_TIMESTAMP_FORMAT), remote_file_name(remote_path))
+}
+
+pub fn snapshot_taken_at(file_name: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    let (timestamp, _) = file_name.split_once('_')?;
+    chrono::NaiveDateTime::parse_from_str(timestamp, SNAPSHOT_TIMESTAMP_FORMAT)
+        .ok()
+        .map(|naive| naive.and_utc())
+}
+
+/// Snapshots in a schedule directory, oldest first. WAL/SHM companions are not listed.
+pub fn list_snapshots(schedule_id: &str, dir: &Path) -> Vec<PullSnapshot> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut snapshots: Vec<PullSnapshot> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.ends_with("-wal") || name.ends_with("-shm") {
+                return None;
+            }
+            let taken_at = snapshot_taken_at(&name)?;
+            Some(PullSnapshot {
+                schedule_id: schedule_id.to_string(),
+                path: entry.path().to_string_lossy().to_string(),
+                taken_at: taken_at.to_rfc3339(),
+                size: entry.metadata().map(|m| m.len()).unwrap_or(0),
+            })
+        })
+        .collect();
+    snapshots.sort_by(|a, b| a.taken_at.cmp(&b.taken_at));
+    snapshots
+}
+
+/// Delete the oldest snapshots (and their companions) until at most `max_snapshots` remain.
+pub fn prune_snapshots(schedule_id: &str, dir: &Path, max_snapshots: usize) -> Vec<PullSnapshot> {
+    let mut snapshots = list_snapshots(schedule_id, dir);
+    let excess = snapshots.len().saturating_sub(max_snapshots);
+
+    for snapshot in snapshots.drain(..excess) {
+        for suffix in ["", "-wal", "-shm"] {
+            let path = format!("{}{}", snapshot.path, suffix);
+            if let Err(e) = std::fs::remove_file(&path) {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    log::warn!("⚠️ Failed to remove old snapshot {}: {}", path, e);
+                }
+            }
+        }
+    }
+    snapshots
+}
+
+fn validate_schedule(device_type: &str, interval_minutes: u64, max_snapshots: usize) -> Result<(), String> {
+    if !matches!(device_type, "android" | "iphone-device" | "simulator") {
+        return Err(format!("Unsupported device type for scheduled pulls: {}", device_type));
+    }
+    if interval_minutes == 0 {
+        return Err("intervalMinutes must be at least 1".to_string());
+    }
+    if max_snapshots == 0 {
+        return Err("maxSnapshots must be at least 1".to_string());
+    }
+    Ok(())
+}
+
+/// Pull a device database into the temp directory and return the local path.
+/// `device_type` is "android", "iphone-device" or "simulator", as in DatabaseFile.
+/// The pull waits for the device's transfer queue like any pull or push operation.
+pub async fn pull_device_database(
+    app_handle: &tauri::AppHandle,
+    device_type: &str,
+    device_id: &str,
+    package_name: &str,
+    remote_path: &str,
+) -> Result<PathBuf, String> {
+    match device_type {
+        "android" => {
+            // Files under /data need run-as; shared storage is pulled directly
+            let admin_access = remote_path.starts_with("/data/");
+            pull_android_db_file(device_id, package_name, remote_path, admin_access, false)
+                .await
+                .map(PathBuf::from)
+                .map_err(|e| e.to_string())
+        }
+        "iphone-device" => pull_ios_db_file(
+            app_handle,
+            device_id,
+            package_name,
+            remote_path,
+            true,
+            IosAppAccessType::Container,
+        )
+        .await
+        .map(PathBuf::from)
+        .map_err(|e| e.to_string()),
+        // Simulator containers are plain directories on this machine
+        _ => Ok(PathBuf::from(remote_path)),
+    }
+}
+
+/// Pull a device database straight into `target`, leaving the temp copy the app works on alone
+pub async fn pull_device_database_to(
+    app_handle: &tauri::AppHandle,
+    device_type: &str,
+    device_id: &str,
+    package_name: &str,
+    remote_path: &str,
+    target: &Path,
+) -> Result<(), String> {
+    match device_type {
+        "android" => {
+            let admin_access = remote_path.starts_with("/data/");
+            pull_android_db_file_to(device_id, package_name, remote_path, admin_access, target)
+                .await
+                .map(|_| ())
+        }
+        "iphone-device" => {
+            pull_ios_db_file_to(app_handle, device_id, package_name, remote_path, IosAppAccessType::Container, target)
+                .await
+                .map(|_| ())
+        }
+        _ => copy_database_with_companions(Path::new(remote_path), target),
+    }
+}
+
+async fn pull_scheduled_database(app_handle: &tauri::AppHandle, schedule: &ScheduledPull) -> Result<PathBuf, String> {
+    pull_device_database(
+        app_handle,
+        &schedule.device_type,
+        &schedule.device_id,
+        &schedule.package_name,
+        &schedule.remote_path,
+    )
+    .await
+}
+
+async fn take_snapshot(app_handle: &tauri::AppHandle, schedule: &ScheduledPull) -> Result<PullSnapshot, String> {
+    let source = pull_scheduled_database(app_handle, schedule).await?;
+    if !source.is_file() {
+        return Err(format!("Pulled database not found at {}", source.display()));
+    }
+
+    let dir = snapshots_root(app_handle)?.join(&schedule.id);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create snapshot directory: {}", e))?;
+
+    let taken_at = chrono::Utc::now();
+    let target = dir.join(snapshot_file_name(taken_at, &schedule.remote_path));
+    copy_database_with_companions(&source, &target)?;
+
+    Ok(PullSnapshot {
+        schedule_id: schedule.id.clone(),
+        path: target.to_string_lossy().to_string(),
+        taken_at: taken_at.to_rfc3339(),
+        size: std::fs::metadata(&target).map(|m| m.len()).unwrap_or(0),
+    })
+}
+
+async fn run_schedule(app_handle: tauri::AppHandle, schedule: ScheduledPull) {
+    let mut interval = tokio::time::interval(Duration::from_secs(schedule.interval_minutes * 60));
+    // A slow pull must not cause a burst of catch-up pulls
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    loop {
+        interval.tick().await;
+        log::info!("⏰ Scheduled pull {} of {}", schedule.id, schedule.remote_path);
+
+        let result = take_snapshot(&app_handle, &schedule).await;
+        let snapshots = match snapshots_root(&app_handle) {
+            Ok(root) => prune_snapshots(&schedule.id, &root.join(&schedule.id), schedule.max_snapshots),
+            Err(_) => Vec::new(),
+        };
+
+        let last_error = match &result {
+            Ok(snapshot) => {
+                log::info!("📸 Snapshot saved to {}", snapshot.path);
+                if let Err(e) = app_handle.emit(SNAPSHOT_TAKEN_EVENT, snapshot) {
+                    log::warn!("⚠️ Failed to emit snapshot event: {}", e);
+                }
+                None
+            }
+            Err(e) => {
+                log::error!("❌ Scheduled pull {} failed: {}", schedule.id, e);
+                Some(e.clone())
+            }
+        };
+
+        let Ok(mut schedules) = SCHEDULES.lock() else {
+            return;
+        };
+        let Some(entry) = schedules.get_mut(&schedule.id) else {
+            return;
+        };
+        entry.schedule.last_run_at = Some(chrono::Utc::now().to_rfc3339());
+        entry.schedule.last_error = last_error;
+        entry.schedule.snapshots = snapshots;
+    }
+}
+
+/// Start re-pulling a device database every `interval_minutes` into timestamped snapshots
+#[tauri::command]
+pub async fn schedule_pull_create(
+    app_handle: tauri::AppHandle,
+    device_type: String,
+    device_id: String,
+    package_name: String,
+    remote_path: String,
+    interval_minutes: u64,
+    max_snapshots: Option<usize>,
+) -> Result<DeviceResponse<ScheduledPull>, String> {
+    let max_snapshots = max_snapshots.unwrap_or(DEFAULT_MAX_SNAPSHOTS);
+    if let Err(e) = validate_schedule(&device_type, interval_minutes, max_snapshots) {
+        return Ok(DeviceResponse {
+            success: false,
+            data: None,
+            error_detail: Some(e.device_error()),
+            error: Some(e),
+        });
+    }
+
+    let schedule = ScheduledPull {
+        id: uuid::Uuid::new_v4().to_string(),
+        device_type,
+        device_id,
+        package_name,
+        remote_path,
+        interval_minutes,
+        max_snapshots,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        last_run_at: None,
+        last_error: None,
+        snapshots: Vec::new(),
+    };
+    log::info!(
+        "⏰ Scheduling pull of {} on {} every {} min",
+        schedule.remote_path,
+        schedule.device_id,
+        schedule.interval_minutes
+    );
+
+    // The first pull runs right away and stops the task if its entry is missing, so the
+    // registry stays locked until the entry is in
+    let mut schedules = SCHEDULES
+        .lock()
+        .map_err(|_| "Schedule registry poisoned".to_string())?;
+    let task = tauri::async_runtime::spawn(run_schedule(app_handle, schedule.clone()));
+    schedules.insert(schedule.id.clone(), ScheduleEntry { schedule: schedule.clone(), task });
+    drop(schedules);
+
+    Ok(DeviceResponse {
+        success: true,
+        data: Some(schedule),
+        error: None,
+        error_detail: None,
+    })
+}
+
+/// Active schedules with their snapshots, oldest schedule first
+#[tauri::command]
+pub async fn schedule_pull_list() -> Result<DeviceResponse<Vec<ScheduledPull>>, String> {
+    let mut schedules: Vec<ScheduledPull> = SCHEDULES
+        .lock()
+        .map_err(|_| "Schedule registry poisoned".to_string())?
+        .values()
+        .map(|entry| entry.schedule.clone())
+        .collect();
+    schedules.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+
+    Ok(DeviceResponse {
+        success: true,
+        data: Some(schedules),
+        error: None,
+        error_detail: None,
+    })
+}
+
+/// Stop a schedule; its snapshots are kept unless `delete_snapshots` is set
+#[tauri::command]
+pub async fn schedule_pull_delete(
+    app_handle: tauri::AppHandle,
+    schedule_id: String,
+    delete_snapshots: Option<bool>,
+) -> Result<DeviceResponse<bool>, String> {
+    let removed = SCHEDULES
+        .lock()
+        .map_err(|_| "Schedule registry poisoned".to_string())?
+        .remove(&schedule_id);
+
+    let Some(entry) = removed else {
+        return Ok(DeviceResponse {
+            success: false,
+            data: None,
+            error_detail: Some(DeviceError::Unknown),
+            error: Some(format!("No scheduled pull with id {}", schedule_id)),
+        });
+    };
+    entry.task.abort();
+    log::info!("🛑 Stopped scheduled pull {}", schedule_id);
+
+    if delete_snapshots.unwrap_or(false) {
+        if let Ok(root) = snapshots_root(&app_handle) {
+            let dir = root.join(&schedule_id);
+            if let Err(e) = std::fs::remove_dir_all(&dir) {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    log::warn!("⚠️ Failed to delete snapshots in {}: {}", dir.display(), e);
+                }
+            }
+        }
+    }
+
+    Ok(DeviceResponse {
+        success: true,
+        data: Some(true),
+        error: None,
+        error_detail: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_snapshot_file_name_round_trips_timestamp() {
+        let taken_at = chrono::Utc.with_ymd_and_hms(2024, 5, 1, 12, 30, 5).unwrap();
+        let name = snapshot_file_name(taken_at, "/data/data/com.example.app/databases/app_cache.db");
+
+        assert_eq!(name, "20240501T123005Z_app_cache.db");
+        assert_eq!(snapshot_taken_at(&name), Some(taken_at));
+        assert_eq!(snapshot_taken_at("app.db"), None);
+    }
+
+    #[test]
+    fn test_list_and_prune_snapshots() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        for (hour, suffix) in [(9, ""), (10, ""), (10, "-wal"), (11, "")] {
+            let taken_at = chrono::Utc.with_ymd_and_hms(2024, 5, 1, hour, 0, 0).unwrap();
+            std::fs::write(dir.join(format!("{}{}", snapshot_file_name(taken_at, "/app.db"), suffix)), b"x").unwrap();
+        }
+        std::fs::write(dir.join("notes.txt"), b"ignored").unwrap();
+
+        let snapshots = list_snapshots("s1", dir);
+        assert_eq!(snapshots.len(), 3);
+        assert!(snapshots[0].path.contains("T090000Z"));
+
+        let kept = prune_snapshots("s1", dir, 1);
+        assert_eq!(kept.len(), 1);
+        assert!(kept[0].path.contains("T110000Z"));
+        assert!(!dir.join("20240501T100000Z_app.db-wal").exists());
+    }
+
+    #[test]
+    fn test_validate_schedule() {
+        assert!(validate_schedule("android", 5, 10).is_ok());
+        assert!(validate_schedule("emulator", 5, 10).is_err());
+        assert!(validate_schedule("simulator", 0, 10).is_err());
+        assert!(validate_schedule("iphone-device", 5, 0).is_err());
+    }
+}
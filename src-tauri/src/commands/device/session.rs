@@ -0,0 +1,306 @@
+//! Device sessions and keep-alive
+//!
+//! Physical iOS devices drop their lockdown connection when they go to sleep, and the next
+//! pull or push then fails. While a device is selected, `DeviceSessionManager` pings it with
+//! `ideviceinfo` on an interval, retries with backoff when the ping fails, and emits a
+//! `device-connection-state` event whenever the connection state changes.
+
+use super::capabilities::invalidate_capabilities;
+use super::command_policy::{run_with_policy, CommandPolicy};
+use super::ios::tools::get_tool_command_legacy;
+use super::types::DeviceResponse;
+use super::errors::{CommandErrorExt, DeviceError};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::process::Command as StdCommand;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{Emitter, State};
+
+const CONNECTION_STATE_EVENT: &str = "device-connection-state";
+const DEFAULT_HEARTBEAT_SECONDS: u64 = 15;
+// Consecutive failed pings before a device is reported as disconnected
+const MAX_RECONNECT_ATTEMPTS: u32 = 4;
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+// A heartbeat must stay cheap: one short attempt, the session loop does its own retrying
+const HEARTBEAT_POLICY: CommandPolicy = CommandPolicy {
+    timeout: Duration::from_secs(10),
+    max_attempts: 1,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConnectionState {
+    Connected,
+    Reconnecting,
+    Disconnected,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceSession {
+    pub device_id: String,
+    pub device_type: String,
+    pub interval_seconds: u64,
+    pub state: ConnectionState,
+    pub consecutive_failures: u32,
+    pub last_seen_at: Option<String>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionStateEvent {
+    pub device_id: String,
+    pub state: ConnectionState,
+    pub previous_state: ConnectionState,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<&'static str>,
+}
+
+struct SessionEntry {
+    session: DeviceSession,
+    task: tauri::async_runtime::JoinHandle<()>,
+}
+
+type Sessions = Arc<Mutex<HashMap<String, SessionEntry>>>;
+
+/// Keep-alive sessions for the selected devices, registered as Tauri state.
+#[derive(Default)]
+pub struct DeviceSessionManager {
+    sessions: Sessions,
+}
+
+impl DeviceSessionManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn start(&self, app_handle: tauri::AppHandle, device_id: String, device_type: String, interval_seconds: u64) -> Result<DeviceSession, String> {
+        let session = DeviceSession {
+            device_id: device_id.clone(),
+            device_type,
+            interval_seconds,
+            state: ConnectionState::Connected,
+            consecutive_failures: 0,
+            last_seen_at: None,
+            last_error: None,
+        };
+
+        let mut sessions = self.sessions.lock().map_err(|_| "Device session registry poisoned".to_string())?;
+        if let Some(previous) = sessions.remove(&device_id) {
+            previous.task.abort();
+        }
+        let task = tauri::async_runtime::spawn(run_session(app_handle, self.sessions.clone(), session.clone()));
+        sessions.insert(device_id, SessionEntry { session: session.clone(), task });
+        Ok(session)
+    }
+
+    fn stop(&self, device_id: &str) -> Result<bool, String> {
+        let removed = self
+            .sessions
+            .lock()
+            .map_err(|_| "Device session registry poisoned".to_string())?
+            .remove(device_id);
+        match removed {
+            Some(entry) => {
+                entry.task.abort();
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn list(&self) -> Result<Vec<DeviceSession>, String> {
+        let sessions = self.sessions.lock().map_err(|_| "Device session registry poisoned".to_string())?;
+        let mut list: Vec<DeviceSession> = sessions.values().map(|entry| entry.session.clone()).collect();
+        list.sort_by(|a, b| a.device_id.cmp(&b.device_id));
+        Ok(list)
+    }
+}
+
+/// State after a ping, given how many pings in a row have failed (including this one).
+fn next_state(consecutive_failures: u32) -> ConnectionState {
+    match consecutive_failures {
+        0 => ConnectionState::Connected,
+        failures if failures < MAX_RECONNECT_ATTEMPTS => ConnectionState::Reconnecting,
+        _ => ConnectionState::Disconnected,
+    }
+}
+
+/// Wait before the next ping: the regular interval while connected, a short doubling
+/// backoff while reconnecting, and no faster than the interval once disconnected.
+fn next_delay(interval: Duration, consecutive_failures: u32) -> Duration {
+    match next_state(consecutive_failures) {
+        ConnectionState::Connected => interval,
+        ConnectionState::Reconnecting => Duration::from_secs(1 << consecutive_failures.min(6)).min(interval),
+        ConnectionState::Disconnected => interval.max(MAX_RETRY_DELAY.min(interval * 2)),
+    }
+}
+
+pub(crate) async fn ping_ios_device(device_id: &str) -> Result<(), String> {
+    let mut command = StdCommand::new(get_tool_command_legacy("ideviceinfo"));
+    command.args(["-u", device_id, "-k", "DeviceName"]);
+    let output = run_with_policy(command, HEARTBEAT_POLICY).await.map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+async fn run_session(app_handle: tauri::AppHandle, sessions: Sessions, session: DeviceSession) {
+    let interval = Duration::from_secs(session.interval_seconds);
+    let mut state = ConnectionState::Connected;
+    let mut failures = 0;
+
+    loop {
+        let result = ping_ios_device(&session.device_id).await;
+        failures = if result.is_ok() { 0 } else { failures + 1 };
+        let previous_state = state;
+        state = next_state(failures);
+        let error = result.err();
+
+        if state != previous_state {
+            match state {
+                ConnectionState::Connected => {
+                    log::info!("🔌 Device {} reconnected", session.device_id);
+                    invalidate_capabilities(&session.device_id);
+                }
+                _ => log::warn!("⚠️ Device {} is {:?}: {}", session.device_id, state, error.as_deref().unwrap_or_default()),
+            }
+            let event = ConnectionStateEvent {
+                device_id: session.device_id.clone(),
+                state,
+                previous_state,
+                hint: error.as_deref().and_then(|e| e.device_error().hint()),
+                error: error.clone(),
+            };
+            if let Err(e) = app_handle.emit(CONNECTION_STATE_EVENT, event) {
+                log::warn!("⚠️ Failed to emit connection state event: {}", e);
+            }
+        }
+
+        {
+            let Ok(mut sessions) = sessions.lock() else {
+                return;
+            };
+            let Some(entry) = sessions.get_mut(&session.device_id) else {
+                return;
+            };
+            entry.session.state = state;
+            entry.session.consecutive_failures = failures;
+            if failures == 0 {
+                entry.session.last_seen_at = Some(chrono::Utc::now().to_rfc3339());
+            }
+            entry.session.last_error = error;
+        }
+
+        tokio::time::sleep(next_delay(interval, failures)).await;
+    }
+}
+
+/// Start keeping a selected physical device awake; replaces any existing session for it
+#[tauri::command]
+pub async fn device_session_start(
+    app_handle: tauri::AppHandle,
+    sessions: State<'_, DeviceSessionManager>,
+    device_id: String,
+    device_type: String,
+    interval_seconds: Option<u64>,
+) -> Result<DeviceResponse<DeviceSession>, String> {
+    // Simulators and Android devices do not sleep their connection away
+    if device_type != "iphone-device" {
+        return Ok(DeviceResponse {
+            success: false,
+            data: None,
+            error_detail: Some(DeviceError::Unknown),
+            error: Some(format!("Keep-alive is only needed for physical iOS devices, not {}", device_type)),
+        });
+    }
+
+    let interval_seconds = interval_seconds.unwrap_or(DEFAULT_HEARTBEAT_SECONDS).max(1);
+    log::info!("💓 Starting keep-alive for {} every {}s", device_id, interval_seconds);
+    match sessions.start(app_handle, device_id, device_type, interval_seconds) {
+        Ok(session) => Ok(DeviceResponse {
+            success: true,
+            data: Some(session),
+            error: None,
+            error_detail: None,
+        }),
+        Err(e) => Ok(DeviceResponse {
+            success: false,
+            data: None,
+            error_detail: Some(e.device_error()),
+            error: Some(e),
+        }),
+    }
+}
+
+/// Stop the keep-alive session of a device; `false` if it had none
+#[tauri::command]
+pub async fn device_session_stop(
+    sessions: State<'_, DeviceSessionManager>,
+    device_id: String,
+) -> Result<DeviceResponse<bool>, String> {
+    log::info!("🛑 Stopping keep-alive for {}", device_id);
+    match sessions.stop(&device_id) {
+        Ok(stopped) => Ok(DeviceResponse {
+            success: true,
+            data: Some(stopped),
+            error: None,
+            error_detail: None,
+        }),
+        Err(e) => Ok(DeviceResponse {
+            success: false,
+            data: None,
+            error_detail: Some(e.device_error()),
+            error: Some(e),
+        }),
+    }
+}
+
+/// Active keep-alive sessions with their current connection state
+#[tauri::command]
+pub async fn device_session_list(
+    sessions: State<'_, DeviceSessionManager>,
+) -> Result<DeviceResponse<Vec<DeviceSession>>, String> {
+    match sessions.list() {
+        Ok(list) => Ok(DeviceResponse {
+            success: true,
+            data: Some(list),
+            error: None,
+            error_detail: None,
+        }),
+        Err(e) => Ok(DeviceResponse {
+            success: false,
+            data: None,
+            error_detail: Some(e.device_error()),
+            error: Some(e),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_state_and_delay() {
+        let interval = Duration::from_secs(15);
+        assert_eq!(next_state(0), ConnectionState::Connected);
+        assert_eq!(next_delay(interval, 0), interval);
+
+        assert_eq!(next_state(1), ConnectionState::Reconnecting);
+        assert_eq!(next_delay(interval, 1), Duration::from_secs(2));
+        assert_eq!(next_delay(interval, 3), Duration::from_secs(8));
+        assert_eq!(next_delay(Duration::from_secs(3), 3), Duration::from_secs(3));
+
+        assert_eq!(next_state(MAX_RECONNECT_ATTEMPTS), ConnectionState::Disconnected);
+        assert_eq!(next_delay(interval, MAX_RECONNECT_ATTEMPTS), Duration::from_secs(30));
+        assert_eq!(next_delay(Duration::from_secs(120), 10), Duration::from_secs(120));
+    }
+}
@@ -0,0 +1,343 @@
+//! Temp directory quota
+//!
+//! Every pull lands in the flippio temp directory. This module keeps it within a
+//! configurable size by evicting the least recently used databases (active files are
+//! kept fresh through `touch_database_file`), and removes sidecar files whose
+//! database is gone.
+
+use super::helpers::{get_temp_dir_path, remove_temp_dir_all, remove_temp_file};
+use super::types::DeviceResponse;
+use super::errors::CommandErrorExt;
+use crate::commands::settings::{app_data_dir, current_settings, update_settings};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+pub const DEFAULT_TEMP_QUOTA_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+// Where the quota lived before it moved into the settings file
+const LEGACY_QUOTA_FILE_NAME: &str = "temp_storage.json";
+// Files touched this recently are never evicted, so a pull in progress or the open database survives
+const EVICTION_GRACE_PERIOD: Duration = Duration::from_secs(5 * 60);
+// Files that only make sense next to a database file
+const COMPANION_SUFFIXES: [&str; 6] = [".meta.json", "-wal", "-shm", "-journal", ".bak", ".backup"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TempDirUsage {
+    pub path: String,
+    pub total_bytes: u64,
+    pub database_count: usize,
+    pub max_bytes: u64,
+}
+
+/// A database in the temp dir together with its sidecar files
+#[derive(Debug, Clone)]
+struct TempEntry {
+    path: PathBuf,
+    companions: Vec<PathBuf>,
+    size: u64,
+    last_used: SystemTime,
+}
+
+pub fn temp_quota_bytes() -> u64 {
+    current_settings().temp_dir.max_bytes
+}
+
+fn companion_base(path: &Path) -> Option<PathBuf> {
+    let name = path.to_str()?;
+    COMPANION_SUFFIXES
+        .iter()
+        .find_map(|suffix| name.strip_suffix(suffix))
+        .map(PathBuf::from)
+}
+
+fn path_size(path: &Path) -> u64 {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if metadata.is_dir() {
+        fs::read_dir(path)
+            .map(|entries| entries.filter_map(Result::ok).map(|e| path_size(&e.path())).sum())
+            .unwrap_or(0)
+    } else {
+        metadata.len()
+    }
+}
+
+fn modified_time(path: &Path) -> SystemTime {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+/// Collect databases and their companions. Directories holding a `.meta.json` sidecar
+/// (pulled LevelDB stores) count as a single database.
+fn collect_temp_entries(dir: &Path, entries: &mut Vec<TempEntry>, orphans: &mut Vec<PathBuf>) {
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut paths: Vec<PathBuf> = read_dir.filter_map(Result::ok).map(|e| e.path()).collect();
+    paths.sort();
+
+    let mut companions = Vec::new();
+    for path in paths {
+        if companion_base(&path).is_some() {
+            companions.push(path);
+        } else if path.is_dir() && !Path::new(&format!("{}.meta.json", path.display())).exists() {
+            collect_temp_entries(&path, entries, orphans);
+        } else {
+            entries.push(TempEntry {
+                size: path_size(&path),
+                last_used: modified_time(&path),
+                path,
+                companions: Vec::new(),
+            });
+        }
+    }
+
+    for companion in companions {
+        let base = companion_base(&companion).unwrap_or_default();
+        match entries.iter_mut().find(|entry| entry.path == base) {
+            Some(entry) => {
+                entry.size += path_size(&companion);
+                entry.companions.push(companion);
+            }
+            None => orphans.push(companion),
+        }
+    }
+}
+
+// Goes through the secure wipe like every other removal from the temp directory
+fn remove_path(path: &Path) -> std::io::Result<()> {
+    if path.is_dir() {
+        remove_temp_dir_all(path)
+    } else {
+        remove_temp_file(path)
+    }
+}
+
+pub fn temp_dir_usage(temp_dir: &Path) -> TempDirUsage {
+    let mut entries = Vec::new();
+    let mut orphans = Vec::new();
+    collect_temp_entries(temp_dir, &mut entries, &mut orphans);
+
+    TempDirUsage {
+        path: temp_dir.to_string_lossy().to_string(),
+        total_bytes: entries.iter().map(|e| e.size).sum::<u64>()
+            + orphans.iter().map(|p| path_size(p)).sum::<u64>(),
+        database_count: entries.len(),
+        max_bytes: temp_quota_bytes(),
+    }
+}
+
+/// Evict least recently used databases until the temp dir fits in `max_bytes`.
+/// Returns the evicted database paths.
+pub fn enforce_temp_quota(temp_dir: &Path, max_bytes: u64) -> Vec<PathBuf> {
+    let mut entries = Vec::new();
+    let mut orphans = Vec::new();
+    collect_temp_entries(temp_dir, &mut entries, &mut orphans);
+
+    let mut total: u64 = entries.iter().map(|e| e.size).sum();
+    if total <= max_bytes {
+        return Vec::new();
+    }
+
+    entries.sort_by_key(|entry| entry.last_used);
+    let now = SystemTime::now();
+    let mut evicted = Vec::new();
+
+    for entry in entries {
+        if total <= max_bytes {
+            break;
+        }
+        let recently_used = now
+            .duration_since(entry.last_used)
+            .map(|age| age < EVICTION_GRACE_PERIOD)
+            .unwrap_or(true);
+        if recently_used {
+            continue;
+        }
+
+        if let Err(e) = remove_path(&entry.path) {
+            log::warn!("⚠️ Failed to evict temp file {}: {}", entry.path.display(), e);
+            continue;
+        }
+        for companion in &entry.companions {
+            let _ = remove_path(companion);
+        }
+        log::info!("🗑️ Evicted temp database {} ({} bytes)", entry.path.display(), entry.size);
+        total = total.saturating_sub(entry.size);
+        evicted.push(entry.path);
+    }
+
+    if total > max_bytes {
+        log::warn!(
+            "⚠️ Temp directory still uses {} bytes (quota {}); remaining files are in use",
+            total,
+            max_bytes
+        );
+    }
+    evicted
+}
+
+/// Remove `.meta.json`, WAL/SHM and backup files whose database no longer exists.
+pub fn clean_orphaned_temp_files(temp_dir: &Path) -> usize {
+    let mut entries = Vec::new();
+    let mut orphans = Vec::new();
+    collect_temp_entries(temp_dir, &mut entries, &mut orphans);
+
+    let mut removed = 0;
+    for orphan in orphans {
+        match remove_path(&orphan) {
+            Ok(()) => removed += 1,
+            Err(e) => log::warn!("⚠️ Failed to remove orphaned file {}: {}", orphan.display(), e),
+        }
+    }
+    if removed > 0 {
+        log::info!("🧹 Removed {} orphaned temp files", removed);
+    }
+    removed
+}
+
+/// Move a quota saved by earlier versions in its own `temp_storage.json` into the settings.
+fn migrate_legacy_quota_file() {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct LegacyQuota {
+        max_bytes: u64,
+    }
+
+    let Some(path) = app_data_dir().map(|dir| dir.join(LEGACY_QUOTA_FILE_NAME)) else {
+        return;
+    };
+    let Ok(content) = fs::read_to_string(&path) else {
+        return;
+    };
+    match serde_json::from_str::<LegacyQuota>(&content) {
+        Ok(legacy) => match update_settings(serde_json::json!({ "tempDir": { "maxBytes": legacy.max_bytes } })) {
+            Ok(_) => log::info!("📦 Moved temp quota of {} bytes into settings", legacy.max_bytes),
+            Err(e) => {
+                log::warn!("⚠️ Keeping {} for now, its quota was not applied: {}", path.display(), e);
+                return;
+            }
+        },
+        Err(e) => log::warn!("⚠️ Dropping unreadable {}: {}", path.display(), e),
+    }
+    let _ = fs::remove_file(&path);
+}
+
+/// Clean up orphaned files and apply the quota; run once on startup.
+pub fn init_temp_storage() {
+    migrate_legacy_quota_file();
+    let temp_dir = get_temp_dir_path();
+    clean_orphaned_temp_files(&temp_dir);
+    enforce_temp_quota(&temp_dir, temp_quota_bytes());
+}
+
+#[tauri::command]
+pub async fn get_temp_dir_usage() -> Result<DeviceResponse<TempDirUsage>, String> {
+    let usage = tokio::task::spawn_blocking(|| temp_dir_usage(&get_temp_dir_path()))
+        .await
+        .map_err(|e| format!("Failed to measure temp directory: {}", e))?;
+
+    Ok(DeviceResponse {
+        success: true,
+        data: Some(usage),
+        error: None,
+        error_detail: None,
+    })
+}
+
+#[tauri::command]
+pub async fn set_temp_dir_quota(max_bytes: u64) -> Result<DeviceResponse<TempDirUsage>, String> {
+    log::info!("📦 Setting temp directory quota to {} bytes", max_bytes);
+
+    if let Err(e) = update_settings(serde_json::json!({ "tempDir": { "maxBytes": max_bytes } })) {
+        log::error!("❌ {}", e);
+        return Ok(DeviceResponse {
+            success: false,
+            data: None,
+            error_detail: Some(e.device_error()),
+            error: Some(e),
+        });
+    }
+
+    let usage = tokio::task::spawn_blocking(move || {
+        let temp_dir = get_temp_dir_path();
+        enforce_temp_quota(&temp_dir, max_bytes);
+        temp_dir_usage(&temp_dir)
+    })
+    .await
+    .map_err(|e| format!("Failed to apply temp quota: {}", e))?;
+
+    Ok(DeviceResponse {
+        success: true,
+        data: Some(usage),
+        error: None,
+        error_detail: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_aged(path: &Path, bytes: usize, age: Duration) {
+        fs::write(path, vec![0u8; bytes]).unwrap();
+        let file = fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(SystemTime::now() - age).unwrap();
+    }
+
+    #[test]
+    fn test_enforce_temp_quota_evicts_least_recently_used() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let hour = Duration::from_secs(3600);
+
+        write_aged(&dir.join("old.db"), 1000, hour * 3);
+        write_aged(&dir.join("old.db.meta.json"), 10, hour * 3);
+        write_aged(&dir.join("older.db"), 1000, hour * 2);
+        write_aged(&dir.join("recent.db"), 1000, hour);
+        write_aged(&dir.join("active.db"), 1000, Duration::from_secs(0));
+
+        let evicted = enforce_temp_quota(dir, 2500);
+
+        assert_eq!(evicted, vec![dir.join("old.db"), dir.join("older.db")]);
+        assert!(!dir.join("old.db.meta.json").exists());
+        assert!(dir.join("recent.db").exists());
+        assert!(dir.join("active.db").exists());
+    }
+
+    #[test]
+    fn test_enforce_temp_quota_keeps_recently_used_files() {
+        let temp_dir = TempDir::new().unwrap();
+        write_aged(&temp_dir.path().join("active.db"), 1000, Duration::from_secs(0));
+
+        assert!(enforce_temp_quota(temp_dir.path(), 10).is_empty());
+        assert!(temp_dir.path().join("active.db").exists());
+    }
+
+    #[test]
+    fn test_clean_orphaned_temp_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let nested = dir.join("device").join("com.example.app");
+        fs::create_dir_all(&nested).unwrap();
+
+        fs::write(dir.join("kept.db"), b"db").unwrap();
+        fs::write(dir.join("kept.db.meta.json"), b"{}").unwrap();
+        fs::write(dir.join("gone.db.meta.json"), b"{}").unwrap();
+        fs::write(nested.join("gone.db-wal"), b"wal").unwrap();
+
+        assert_eq!(clean_orphaned_temp_files(dir), 2);
+        assert!(dir.join("kept.db.meta.json").exists());
+        assert!(!dir.join("gone.db.meta.json").exists());
+        assert!(!nested.join("gone.db-wal").exists());
+
+        let usage = temp_dir_usage(dir);
+        assert_eq!(usage.database_count, 1);
+        assert_eq!(usage.total_bytes, 4);
+    }
+}
@@ -0,0 +1,240 @@
+//! Chunked pushes for large databases
+//!
+//! A single `adb push` of a 500 MB database gives no feedback and starts over after any
+//! hiccup. Files of `CHUNKED_PUSH_THRESHOLD` or more are sent in `PUSH_CHUNK_SIZE` pieces
+//! that are appended on the device, with a `push-progress` event per chunk. The bytes
+//! already on the device are recorded next to the local file (`<local>.push-state.json`),
+//! so a failed push resumes where it stopped when the same file is pushed again.
+//! afcclient cannot append to a remote file, so iOS pushes report only start and end.
+
+use super::helpers::{ensure_temp_dir, execute_adb_command};
+use super::integrity::compute_file_sha256;
+use crate::commands::common::check_cancelled;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+pub const PUSH_PROGRESS_EVENT: &str = "push-progress";
+
+/// Files this large are pushed in chunks
+pub const CHUNKED_PUSH_THRESHOLD: u64 = 64 * 1024 * 1024;
+const PUSH_CHUNK_SIZE: u64 = 8 * 1024 * 1024;
+pub const MAX_TRANSFER_ATTEMPTS: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferProgress {
+    pub remote_path: String,
+    pub bytes_transferred: u64,
+    pub total_bytes: u64,
+    // True when the push continued a previous, failed one
+    pub resumed: bool,
+}
+
+pub type ProgressCallback<'a> = &'a (dyn Fn(TransferProgress) + Send + Sync);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PushState {
+    device_id: String,
+    device_path: String,
+    sha256: String,
+    bytes_done: u64,
+}
+
+fn push_state_path(local_path: &str) -> String {
+    format!("{}.push-state.json", local_path)
+}
+
+fn load_push_state(local_path: &str) -> Option<PushState> {
+    let content = std::fs::read_to_string(push_state_path(local_path)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn save_push_state(local_path: &str, state: &PushState) {
+    if let Ok(content) = serde_json::to_string(state) {
+        if let Err(e) = std::fs::write(push_state_path(local_path), content) {
+            log::warn!("⚠️ Could not record push progress: {}", e);
+        }
+    }
+}
+
+/// Offset to continue from: the recorded progress of an earlier push of the same file
+/// to the same place, if the device still holds exactly that many bytes.
+fn resume_offset(state: Option<&PushState>, device_id: &str, device_path: &str, sha256: &str, remote_size: Option<u64>) -> u64 {
+    match state {
+        Some(state)
+            if state.device_id == device_id
+                && state.device_path == device_path
+                && state.sha256 == sha256
+                && remote_size == Some(state.bytes_done) =>
+        {
+            state.bytes_done
+        }
+        _ => 0,
+    }
+}
+
+/// Shell quoting for `adb shell` command strings.
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+async fn remote_file_size(device_id: &str, device_path: &str) -> Option<u64> {
+    let output = execute_adb_command(&["-s", device_id, "shell", "stat", "-c", "%s", device_path])
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+fn write_chunk(local_path: &str, offset: u64, len: u64, chunk_path: &Path) -> Result<(), String> {
+    let mut file = std::fs::File::open(local_path).map_err(|e| format!("Failed to open {}: {}", local_path, e))?;
+    file.seek(SeekFrom::Start(offset)).map_err(|e| e.to_string())?;
+    let mut buffer = Vec::with_capacity(len as usize);
+    file.take(len).read_to_end(&mut buffer).map_err(|e| e.to_string())?;
+    std::fs::write(chunk_path, buffer).map_err(|e| format!("Failed to write chunk: {}", e))
+}
+
+/// Push one chunk next to `device_path` and append it (or, for the first chunk, start the file).
+async fn push_chunk(device_id: &str, chunk_path: &Path, device_path: &str, offset: u64) -> Result<(), String> {
+    let part_path = format!("{}.part", device_path);
+    let output = execute_adb_command(&["-s", device_id, "push", &chunk_path.to_string_lossy(), &part_path])
+        .await
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    let redirect = if offset == 0 { ">" } else { ">>" };
+    let command = format!(
+        "cat {part} {redirect} {target} && rm -f {part}",
+        part = quote(&part_path),
+        redirect = redirect,
+        target = quote(device_path)
+    );
+    let output = execute_adb_command(&["-s", device_id, "shell", &command])
+        .await
+        .map_err(|e| e.to_string())?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if output.status.success() && stderr.trim().is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Failed to append chunk: {}", stderr.trim()))
+    }
+}
+
+/// Push `local_path` to `device_path` (a location the shell user can write) in chunks,
+/// resuming an earlier push of the same file when possible.
+pub async fn push_file_chunked(
+    device_id: &str,
+    local_path: &str,
+    device_path: &str,
+    progress: Option<ProgressCallback<'_>>,
+) -> Result<(), String> {
+    let total_bytes = std::fs::metadata(local_path)
+        .map_err(|e| format!("Failed to read {}: {}", local_path, e))?
+        .len();
+    let sha256 = compute_file_sha256(Path::new(local_path))?;
+    let remote_size = remote_file_size(device_id, device_path).await;
+    let mut offset = resume_offset(load_push_state(local_path).as_ref(), device_id, device_path, &sha256, remote_size);
+    let resumed = offset > 0;
+    if resumed {
+        log::info!("⏯️ Resuming push of {} at {} of {} bytes", local_path, offset, total_bytes);
+    }
+
+    let report = |bytes_transferred: u64| {
+        if let Some(progress) = progress {
+            progress(TransferProgress {
+                remote_path: device_path.to_string(),
+                bytes_transferred,
+                total_bytes,
+                resumed,
+            });
+        }
+    };
+    report(offset);
+
+    let temp_dir = ensure_temp_dir().map_err(|e| format!("Failed to create temp directory: {}", e))?;
+    let chunk_path = temp_dir.join(format!(".push-chunk-{}", uuid::Uuid::new_v4()));
+    let result = async {
+        while offset < total_bytes {
+            // The partial upload is kept, so pushing again resumes from here
+            check_cancelled()?;
+            let len = PUSH_CHUNK_SIZE.min(total_bytes - offset);
+            write_chunk(local_path, offset, len, &chunk_path)?;
+
+            let mut attempt = 1;
+            while let Err(e) = push_chunk(device_id, &chunk_path, device_path, offset).await {
+                // Retry only when the device copy still ends exactly where this chunk starts
+                let intact = remote_file_size(device_id, device_path).await.unwrap_or(0) == offset;
+                if attempt >= MAX_TRANSFER_ATTEMPTS || !intact {
+                    return Err(format!("Push stopped at {} of {} bytes: {}", offset, total_bytes, e));
+                }
+                log::warn!("⚠️ Chunk at {} failed (attempt {}), retrying: {}", offset, attempt, e);
+                attempt += 1;
+            }
+
+            offset += len;
+            save_push_state(
+                local_path,
+                &PushState {
+                    device_id: device_id.to_string(),
+                    device_path: device_path.to_string(),
+                    sha256: sha256.clone(),
+                    bytes_done: offset,
+                },
+            );
+            report(offset);
+        }
+        Ok(())
+    }
+    .await;
+
+    let _ = std::fs::remove_file(&chunk_path);
+    if result.is_ok() {
+        let _ = std::fs::remove_file(push_state_path(local_path));
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resume_offset_requires_matching_state_and_remote_size() {
+        let state = PushState {
+            device_id: "emulator-5554".to_string(),
+            device_path: "/data/local/tmp/app.db".to_string(),
+            sha256: "abc".to_string(),
+            bytes_done: 16 * 1024 * 1024,
+        };
+        let offset = |sha256: &str, remote_size: Option<u64>| {
+            resume_offset(Some(&state), "emulator-5554", "/data/local/tmp/app.db", sha256, remote_size)
+        };
+
+        assert_eq!(offset("abc", Some(state.bytes_done)), state.bytes_done);
+        // The local file changed since the failed push
+        assert_eq!(offset("def", Some(state.bytes_done)), 0);
+        // The device copy is not what was recorded
+        assert_eq!(offset("abc", Some(4096)), 0);
+        assert_eq!(offset("abc", None), 0);
+        assert_eq!(resume_offset(None, "emulator-5554", "/data/local/tmp/app.db", "abc", Some(1)), 0);
+    }
+
+    #[test]
+    fn test_write_chunk_reads_the_requested_range() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let source = temp_dir.path().join("source.db");
+        let chunk = temp_dir.path().join("chunk");
+        std::fs::write(&source, b"0123456789").unwrap();
+
+        write_chunk(&source.to_string_lossy(), 3, 4, &chunk).unwrap();
+        assert_eq!(std::fs::read(&chunk).unwrap(), b"3456");
+        write_chunk(&source.to_string_lossy(), 8, 4, &chunk).unwrap();
+        assert_eq!(std::fs::read(&chunk).unwrap(), b"89");
+    }
+}
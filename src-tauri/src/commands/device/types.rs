@@ -1,3 +1,7 @@
+use super::errors::{DeviceError, DeviceErrorDetail};
+use super::android_storage::RemotePermissions;
+use super::pull_strategy::PullStrategy;
+use super::favorites::Favorite;
 use serde::{Deserialize, Serialize};
 
 // Metadata for pulled database files
@@ -7,30 +11,140 @@ pub struct DatabaseFileMetadata {
     pub package_name: String,
     pub remote_path: String,
     pub timestamp: String,
+    // SHA-256 of the file as pulled; absent for directories and older sidecars
+    #[serde(default)]
+    pub sha256: Option<String>,
+    // Android run-as pulls only: mode and owner of the device file, restored after pushes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_permissions: Option<RemotePermissions>,
+    // Outcome of the background PRAGMA quick_check run after the pull
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub integrity: Option<PullIntegrity>,
+    // Strategy that pulled the file; may be a fallback when the primary one was refused
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pull_strategy: Option<PullStrategy>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PullIntegrityStatus {
+    // The check is still running
+    Pending,
+    Ok,
+    // Truncated transfer or failed quick_check; `detail` says which
+    Corrupt,
+    // Not a SQLite file, nothing to check
+    Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PullIntegrity {
+    pub status: PullIntegrityStatus,
+    pub detail: Option<String>,
+    pub checked_at: Option<String>,
+}
+
+impl PullIntegrity {
+    pub fn pending() -> Self {
+        PullIntegrity {
+            status: PullIntegrityStatus::Pending,
+            detail: None,
+            checked_at: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
 pub struct DeviceResponse<T> {
     pub success: bool,
     pub data: Option<T>,
     pub error: Option<String>,
+    // Classified where the error happened, from the typed error when there is one
+    #[serde(default, rename = "errorDetail")]
+    pub error_detail: Option<DeviceError>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+// Failed responses also carry `errorDetail`, the classified `DeviceError` with its fix hint
+impl<T: Serialize> Serialize for DeviceResponse<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let field_count = if self.error_detail.is_some() { 4 } else { 3 };
+        let mut state = serializer.serialize_struct("DeviceResponse", field_count)?;
+        state.serialize_field("success", &self.success)?;
+        state.serialize_field("data", &self.data)?;
+        state.serialize_field("error", &self.error)?;
+        if let Some(error) = &self.error_detail {
+            state.serialize_field("errorDetail", &DeviceErrorDetail::from(error.clone()))?;
+        }
+        state.end()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Device {
     pub id: String,
+    // Name as reported by the platform tool
     pub name: String,
+    // `name` made safe for display and logs, see `sanitize_device_name`
+    #[serde(rename = "displayName", default)]
+    pub display_name: String,
     pub model: String,
     #[serde(rename = "deviceType")]
     pub device_type: String,
     pub description: String,
+    // Android only, from `getprop`; None when unknown
+    #[serde(rename = "apiLevel", default, skip_serializing_if = "Option::is_none")]
+    pub api_level: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub abi: Option<String>,
+    #[serde(rename = "buildFingerprint", default, skip_serializing_if = "Option::is_none")]
+    pub build_fingerprint: Option<String>,
+    // Connection state as reported by the platform tool, e.g. adb's `device`/`unauthorized`/`offline`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    // User pin, flattened into `pinned`/`nickname`/`sortOrder`
+    #[serde(flatten, default, skip_serializing_if = "Option::is_none")]
+    pub favorite: Option<Favorite>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Package {
     pub name: String,
     #[serde(rename = "bundleId")]
     pub bundle_id: String,
+    #[serde(flatten, default, skip_serializing_if = "Option::is_none")]
+    pub favorite: Option<Favorite>,
+}
+
+// Storage format of a discovered database file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DatabaseFileType {
+    #[default]
+    Sqlite,
+    Realm,
+    #[serde(rename = "leveldb")]
+    LevelDb,
+}
+
+impl DatabaseFileType {
+    /// Detect the storage format from a file name or path.
+    pub fn from_path(path: &str) -> Self {
+        let lowercase = path.to_lowercase();
+        if lowercase.ends_with(".realm") {
+            DatabaseFileType::Realm
+        } else if lowercase.ends_with(".leveldb") {
+            DatabaseFileType::LevelDb
+        } else {
+            DatabaseFileType::Sqlite
+        }
+    }
+
+    pub fn is_sqlite(&self) -> bool {
+        matches!(self, DatabaseFileType::Sqlite)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,12 +158,22 @@ pub struct DatabaseFile {
     pub remote_path: Option<String>,
     #[serde(rename = "deviceType")]
     pub device_type: String,
+    #[serde(rename = "fileType", default)]
+    pub file_type: DatabaseFileType,
+    // Set for freshly pulled files; the final result also arrives as a `pull-integrity-checked` event
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub integrity: Option<PullIntegrity>,
+    #[serde(rename = "pullStrategy", default, skip_serializing_if = "Option::is_none")]
+    pub pull_strategy: Option<PullStrategy>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VirtualDevice {
     pub id: String,
     pub name: String,
+    // `name` made safe for display and logs, see `sanitize_device_name`
+    #[serde(rename = "displayName", default)]
+    pub display_name: String,
     pub model: Option<String>,
     pub platform: String,
     pub state: Option<String>,
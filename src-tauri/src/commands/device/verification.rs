@@ -0,0 +1,262 @@
+//! Remote file verification
+//!
+//! A push that "succeeded" can still leave a different file on the device: a run-as copy
+//! that stopped early, an app that rewrote the database from memory, a stale staging
+//! file. These helpers fetch the SHA-256 of a remote file (hashed on Android, re-pulled
+//! from iOS devices, read in place for simulators) so callers can compare it with what
+//! they sent. When an iOS re-pull fails the file size is compared instead.
+
+use super::android_storage::{android_user_from_path, classify_storage_area, run_as_args, AndroidStorageArea};
+use super::command_policy::PolicyOutputExt;
+use super::errors::DeviceError;
+use super::helpers::{ensure_temp_dir, execute_adb_command};
+use super::integrity::compute_file_sha256;
+use super::ios::file_utils::IosAppAccessType;
+use super::ios::tools::get_tool_command_legacy;
+use super::types::DeviceResponse;
+use serde::Serialize;
+use std::path::Path;
+use tauri_plugin_shell::ShellExt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VerificationMethod {
+    Sha256,
+    // afcclient reports sizes but cannot hash files on the device
+    Size,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteFileVerification {
+    pub method: VerificationMethod,
+    pub expected: String,
+    pub actual: String,
+    pub verified: bool,
+}
+
+/// Data of a push response: the push command's message and, when verification was
+/// requested, whether the device copy matches the local file (`None` if it could not be checked)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PushedFile {
+    pub message: String,
+    pub verified: Option<bool>,
+    pub verification: Option<RemoteFileVerification>,
+    pub verification_error: Option<String>,
+}
+
+/// The hash printed by `sha256sum`, e.g. `e3b0c4... /data/data/pkg/databases/app.db`.
+fn parse_sha256sum_output(stdout: &str) -> Option<String> {
+    let hash = stdout.split_whitespace().next()?;
+    (hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit())).then(|| hash.to_ascii_lowercase())
+}
+
+/// The `st_size` line printed by `afcclient info`.
+fn parse_afc_size(stdout: &str) -> Option<u64> {
+    stdout.lines().find_map(|line| {
+        let value = line.trim().strip_prefix("st_size")?;
+        value.trim_start_matches([':', ' ', '\t']).trim().parse().ok()
+    })
+}
+
+async fn android_remote_sha256(device_id: &str, package_name: &str, remote_path: &str) -> Result<String, String> {
+    let mut args = vec!["-s".to_string(), device_id.to_string(), "shell".to_string()];
+    // App-private files are only readable as the app, like pushes into them
+    if classify_storage_area(remote_path, package_name) == AndroidStorageArea::AppPrivate {
+        args.extend(run_as_args(package_name, android_user_from_path(remote_path)));
+    }
+    args.extend(["sha256sum".to_string(), remote_path.to_string()]);
+    let arg_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    let output = execute_adb_command(&arg_refs)
+        .await
+        .map_err(|e| format!("Failed to hash remote file: {}", e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    parse_sha256sum_output(&stdout).ok_or_else(|| {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        format!("Failed to hash remote file: {}", if stderr.trim().is_empty() { stdout.trim() } else { stderr.trim() })
+    })
+}
+
+/// Pull the remote file back into the temp directory and hash the copy.
+async fn ios_device_remote_sha256(
+    app_handle: &tauri::AppHandle,
+    device_id: &str,
+    package_name: &str,
+    remote_path: &str,
+) -> Result<String, String> {
+    let temp_dir = ensure_temp_dir().map_err(|e| format!("Failed to create temp directory: {}", e))?;
+    let copy_path = temp_dir.join(format!(".verify-{}", uuid::Uuid::new_v4()));
+    let copy = copy_path.to_string_lossy().to_string();
+    let access_args = IosAppAccessType::Container.afcclient_args(package_name);
+    let output = app_handle
+        .shell()
+        .command(get_tool_command_legacy("afcclient"))
+        .args([access_args[0], access_args[1], "-u", device_id, "get", remote_path, &copy])
+        .output_with_policy()
+        .await
+        .map_err(|e| format!("Failed to execute afcclient get: {}", e));
+
+    let hash = match output {
+        Ok(output) if output.status.success() => compute_file_sha256(&copy_path),
+        Ok(output) => Err(format!("afcclient get failed: {}", String::from_utf8_lossy(&output.stderr).trim())),
+        Err(e) => Err(e),
+    };
+    let _ = std::fs::remove_file(&copy_path);
+    hash
+}
+
+async fn ios_device_remote_size(
+    app_handle: &tauri::AppHandle,
+    device_id: &str,
+    package_name: &str,
+    remote_path: &str,
+) -> Result<u64, String> {
+    let access_args = IosAppAccessType::Container.afcclient_args(package_name);
+    let output = app_handle
+        .shell()
+        .command(get_tool_command_legacy("afcclient"))
+        .args([access_args[0], access_args[1], "-u", device_id, "info", remote_path])
+        .output_with_policy()
+        .await
+        .map_err(|e| format!("Failed to execute afcclient info: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!("afcclient info failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    parse_afc_size(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| "afcclient info did not report st_size".to_string())
+}
+
+/// Compare the remote file with `local_path`, by SHA-256 where the device can hash it.
+/// An error means the remote file could not be inspected, not that it differs.
+pub async fn verify_remote_file(
+    app_handle: &tauri::AppHandle,
+    device_type: &str,
+    device_id: &str,
+    package_name: &str,
+    remote_path: &str,
+    local_path: &str,
+) -> Result<RemoteFileVerification, String> {
+    let local = Path::new(local_path);
+    let (method, expected, actual) = match device_type {
+        "android" => (
+            VerificationMethod::Sha256,
+            compute_file_sha256(local)?,
+            android_remote_sha256(device_id, package_name, remote_path).await?,
+        ),
+        "iphone-device" => match ios_device_remote_sha256(app_handle, device_id, package_name, remote_path).await {
+            Ok(actual) => (VerificationMethod::Sha256, compute_file_sha256(local)?, actual),
+            Err(e) => {
+                log::warn!("⚠️ Could not re-pull {} for hashing, comparing sizes: {}", remote_path, e);
+                (
+                    VerificationMethod::Size,
+                    std::fs::metadata(local)
+                        .map_err(|e| format!("Failed to read {}: {}", local_path, e))?
+                        .len()
+                        .to_string(),
+                    ios_device_remote_size(app_handle, device_id, package_name, remote_path)
+                        .await?
+                        .to_string(),
+                )
+            }
+        },
+        // Simulator containers live on the host
+        "simulator" => (
+            VerificationMethod::Sha256,
+            compute_file_sha256(local)?,
+            compute_file_sha256(Path::new(remote_path))?,
+        ),
+        _ => return Err(format!("Unsupported device type: {}", device_type)),
+    };
+
+    Ok(RemoteFileVerification {
+        method,
+        verified: expected == actual,
+        expected,
+        actual,
+    })
+}
+
+/// Turn a push command's response into one carrying the verification result. Failed pushes
+/// pass through; a mismatch is reported with `verified: false`, not as a failed push.
+#[allow(clippy::too_many_arguments)]
+pub async fn with_push_verification(
+    app_handle: &tauri::AppHandle,
+    device_type: &str,
+    device_id: &str,
+    package_name: &str,
+    remote_path: &str,
+    local_path: &str,
+    verify: bool,
+    response: DeviceResponse<String>,
+) -> DeviceResponse<PushedFile> {
+    let message = match response.data {
+        Some(message) if response.success => message,
+        _ => {
+            return DeviceResponse {
+                success: false,
+                data: None,
+                error: response.error.or_else(|| Some("Push failed".to_string())),
+                error_detail: response.error_detail.or(Some(DeviceError::TransferFailed { bytes: None })),
+            }
+        }
+    };
+
+    let mut pushed = PushedFile {
+        message,
+        verified: None,
+        verification: None,
+        verification_error: None,
+    };
+    if verify {
+        match verify_remote_file(app_handle, device_type, device_id, package_name, remote_path, local_path).await {
+            Ok(verification) => {
+                if verification.verified {
+                    log::info!("✅ Verified {} on {} ({:?})", remote_path, device_id, verification.method);
+                } else {
+                    log::warn!(
+                        "⚠️ Device copy of {} differs from the pushed file ({:?} {} vs {})",
+                        remote_path,
+                        verification.method,
+                        verification.actual,
+                        verification.expected
+                    );
+                }
+                pushed.verified = Some(verification.verified);
+                pushed.verification = Some(verification);
+            }
+            Err(e) => {
+                log::warn!("⚠️ Could not verify push of {}: {}", remote_path, e);
+                pushed.verification_error = Some(e);
+            }
+        }
+    }
+
+    DeviceResponse {
+        success: true,
+        data: Some(pushed),
+        error: None,
+        error_detail: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_remote_fingerprints() {
+        let hash = "E3B0C44298FC1C149AFBF4C8996FB92427AE41E4649B934CA495991B7852B855";
+        assert_eq!(
+            parse_sha256sum_output(&format!("{}  /data/data/com.example.app/databases/app.db\n", hash)),
+            Some(hash.to_ascii_lowercase())
+        );
+        assert_eq!(parse_sha256sum_output("sha256sum: app.db: No such file or directory"), None);
+        assert_eq!(parse_sha256sum_output(""), None);
+
+        assert_eq!(parse_afc_size("st_ifmt: S_IFREG\nst_size: 20480\nst_blocks: 40\n"), Some(20480));
+        assert_eq!(parse_afc_size("st_ifmt: S_IFREG\n"), None);
+    }
+}
@@ -1,6 +1,9 @@
 use super::types::*;
 use super::helpers::*;
 use tauri_plugin_shell::ShellExt;
+use super::command_policy::PolicyOutputExt;
+use super::errors::CommandErrorExt;
+use super::scanner::{DEVICE_LIST_TTL, DEVICE_SCANNER};
 
 #[tauri::command]
 pub async fn get_android_emulators(app_handle: tauri::AppHandle) -> Result<DeviceResponse<Vec<VirtualDevice>>, String> {
@@ -13,7 +16,7 @@ pub async fn get_android_emulators(app_handle: tauri::AppHandle) -> Result<Devic
     // Step 1: List all configured AVDs
     let avd_list_output = shell.command(&emulator_path)
         .args(["-list-avds"])
-        .output()
+        .output_with_policy()
         .await
         .map_err(|e| format!("Failed to list AVDs using '{}': {}", emulator_path, e))?;
 
@@ -30,7 +33,7 @@ pub async fn get_android_emulators(app_handle: tauri::AppHandle) -> Result<Devic
     // Step 2: List running emulator devices via `adb devices`
     let adb_devices_output = shell.command(&adb_path)
         .args(["devices"])
-        .output()
+        .output_with_policy()
         .await;
 
     let running_ports: Vec<String> = if let Ok(output) = adb_devices_output {
@@ -61,7 +64,7 @@ pub async fn get_android_emulators(app_handle: tauri::AppHandle) -> Result<Devic
         
         let avd_name_output = shell.command(&adb_path)
             .args(["-s", port, "emu", "avd", "name"])
-            .output()
+            .output_with_policy()
             .await;
 
         match avd_name_output {
@@ -94,6 +97,7 @@ pub async fn get_android_emulators(app_handle: tauri::AppHandle) -> Result<Devic
         .map(|avd| VirtualDevice {
             id: avd.clone(),
             name: avd.clone(),
+            display_name: sanitize_device_name(&avd),
             platform: "android".to_string(),
             model: Some(avd.clone()),
             state: Some(if running_avds.contains(&avd) {
@@ -108,22 +112,43 @@ pub async fn get_android_emulators(app_handle: tauri::AppHandle) -> Result<Devic
         success: true,
         data: Some(emulators),
         error: None,
+        error_detail: None,
     })
 }
 
+/// Available iOS simulators, cached briefly unless `force_refresh` is set
 #[tauri::command]
-pub async fn get_ios_simulators(app_handle: tauri::AppHandle) -> Result<DeviceResponse<Vec<VirtualDevice>>, String> {
+pub async fn get_ios_simulators(
+    app_handle: tauri::AppHandle,
+    force_refresh: Option<bool>,
+) -> Result<DeviceResponse<Vec<VirtualDevice>>, String> {
+    DEVICE_SCANNER
+        .scan("ios:simulators", DEVICE_LIST_TTL, force_refresh.unwrap_or(false), || scan_ios_simulators(app_handle))
+        .await
+}
+
+async fn scan_ios_simulators(app_handle: tauri::AppHandle) -> Result<DeviceResponse<Vec<VirtualDevice>>, String> {
     log::info!("Getting iOS simulators");
+
+    if !IOS_SIMULATORS_SUPPORTED {
+        // Nothing to list; the UI reads `get_platform_capabilities` to hide simulator features
+        return Ok(DeviceResponse {
+            success: true,
+            data: Some(Vec::new()),
+            error: None,
+            error_detail: None,
+        });
+    }
     
     let shell = app_handle.shell();
-    let output = shell.command("xcrun")
+    let output = shell.command(get_xcrun_path())
         .args(["simctl", "list", "devices", "available", "--json"])
-        .output()
+        .output_with_policy()
         .await
         .map_err(|e| format!("Failed to execute simctl: {}", e))?;
     
     if output.status.success() {
-        let simulators_output = String::from_utf8_lossy(&output.stdout);
+        let simulators_output = decode_tool_output(&output.stdout);
         let mut simulators = Vec::new();
         
         if let Ok(json) = serde_json::from_str::<serde_json::Value>(&simulators_output) {
@@ -139,6 +164,11 @@ pub async fn get_ios_simulators(app_handle: tauri::AppHandle) -> Result<DeviceRe
                                 simulators.push(VirtualDevice {
                                     id: udid.to_string(),
                                     name: format!("{} ({})", name, runtime),
+                                    display_name: format!(
+                                        "{} ({})",
+                                        sanitize_device_name(name),
+                                        simulator_runtime_label(runtime)
+                                    ),
                                     model: Some(name.to_string()),
                                     platform: "ios".to_string(),
                                     state: Some(state.to_string()),
@@ -154,16 +184,27 @@ pub async fn get_ios_simulators(app_handle: tauri::AppHandle) -> Result<DeviceRe
             success: true,
             data: Some(simulators),
             error: None,
+            error_detail: None,
         })
     } else {
         Ok(DeviceResponse {
             success: true,
             data: Some(Vec::new()),
             error: None,
+            error_detail: None,
         })
     }
 }
 
+/// "com.apple.CoreSimulator.SimRuntime.iOS-17-0" → "iOS 17.0"
+fn simulator_runtime_label(runtime: &str) -> String {
+    let label = runtime.rsplit('.').next().unwrap_or(runtime);
+    match label.split_once('-') {
+        Some((os, version)) => format!("{} {}", os, version.replace('-', ".")),
+        None => label.to_string(),
+    }
+}
+
 #[tauri::command]
 pub async fn launch_android_emulator(app_handle: tauri::AppHandle, emulator_id: String) -> Result<DeviceResponse<String>, String> {
     log::info!("Launching Android emulator: {}", emulator_id);
@@ -180,23 +221,32 @@ pub async fn launch_android_emulator(app_handle: tauri::AppHandle, emulator_id:
             success: true,
             data: Some(format!("Emulator {} launched", emulator_id)),
             error: None,
+            error_detail: None,
         }),
-        Err(e) => Ok(DeviceResponse {
-            success: false,
-            data: None,
-            error: Some(format!("Failed to launch emulator: {}", e)),
-        }),
+        Err(e) => {
+            let error = format!("Failed to launch emulator: {}", e);
+            Ok(DeviceResponse {
+                success: false,
+                data: None,
+                error_detail: Some(error.device_error()),
+                error: Some(error),
+            })
+        }
     }
 }
 
 #[tauri::command]
 pub async fn launch_ios_simulator(app_handle: tauri::AppHandle, simulator_id: String) -> Result<DeviceResponse<String>, String> {
     log::info!("Launching iOS simulator: {}", simulator_id);
+
+    if !IOS_SIMULATORS_SUPPORTED {
+        return Ok(simulator_unsupported_response());
+    }
     
     let shell = app_handle.shell();
-    let output = shell.command("xcrun")
+    let output = shell.command(get_xcrun_path())
         .args(["simctl", "boot", &simulator_id])
-        .output()
+        .output_with_policy()
         .await;
     
     match output {
@@ -205,19 +255,21 @@ pub async fn launch_ios_simulator(app_handle: tauri::AppHandle, simulator_id: St
                 // Open Simulator app
                 let _ = shell.command("open")
                     .args(["-a", "Simulator"])
-                    .output()
+                    .output_with_policy()
                     .await;
                 
                 Ok(DeviceResponse {
                     success: true,
                     data: Some(format!("Simulator {} launched", simulator_id)),
                     error: None,
+                    error_detail: None,
                 })
             } else {
                 let stderr = String::from_utf8_lossy(&result.stderr);
                 Ok(DeviceResponse {
                     success: false,
                     data: None,
+                    error_detail: Some(result.device_error()),
                     error: Some(format!("Failed to launch simulator: {}", stderr)),
                 })
             }
@@ -225,6 +277,7 @@ pub async fn launch_ios_simulator(app_handle: tauri::AppHandle, simulator_id: St
         Err(e) => Ok(DeviceResponse {
             success: false,
             data: None,
+            error_detail: Some(e.device_error()),
             error: Some(format!("Failed to execute simctl: {}", e)),
         }),
     }
@@ -0,0 +1,289 @@
+//! Windows dependency installer
+//!
+//! Installs what `check_environment` finds missing on Windows through winget: the Android
+//! platform tools (adb) and Apple Mobile Device Support, the driver iOS devices need to show
+//! up over USB. Every step is reported as a progress event; when one install fails, the
+//! packages this run already installed are uninstalled again so no half-finished setup is
+//! left behind. libimobiledevice has no winget package, so its tools stay a manual step that
+//! the returned environment report still lists.
+
+use super::command_policy::{run_with_policy, CommandPolicy};
+use super::environment::{collect_environment_report, locate_executable, EnvironmentReport};
+use super::errors::DeviceError;
+use super::types::DeviceResponse;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+use tauri::Emitter;
+
+const WINDOWS_DEPENDENCIES_PROGRESS_EVENT: &str = "windows-dependencies-progress";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WindowsDependency {
+    PlatformTools,
+    AppleMobileDeviceSupport,
+}
+
+impl WindowsDependency {
+    fn winget_id(self) -> &'static str {
+        match self {
+            WindowsDependency::PlatformTools => "Google.PlatformTools",
+            WindowsDependency::AppleMobileDeviceSupport => "Apple.AppleMobileDeviceSupport",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            WindowsDependency::PlatformTools => "Android platform tools",
+            WindowsDependency::AppleMobileDeviceSupport => "Apple Mobile Device Support",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum InstallStage {
+    Installing,
+    Installed,
+    Failed,
+    RollingBack,
+    RolledBack,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyInstallProgress {
+    pub dependency: WindowsDependency,
+    pub stage: InstallStage,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyInstallResult {
+    pub installed: Vec<WindowsDependency>,
+    // Installed by this run, then uninstalled again after a later step failed
+    pub rolled_back: Vec<WindowsDependency>,
+    // The environment after installing, so the checklist can refresh
+    pub report: EnvironmentReport,
+}
+
+/// Apple Mobile Device Support installs its service under Common Files\Apple
+fn apple_mobile_device_support_installed() -> bool {
+    ["CommonProgramFiles", "CommonProgramFiles(x86)", "CommonProgramW6432"]
+        .iter()
+        .filter_map(|var| std::env::var_os(var))
+        .any(|dir| PathBuf::from(dir).join("Apple").join("Mobile Device Support").is_dir())
+}
+
+/// Dependencies to install for a report: adb when it can't run, and the Apple driver when it
+/// is not installed. `requested` narrows the list to what the user picked.
+fn missing_dependencies(
+    report: &EnvironmentReport,
+    amds_installed: bool,
+    requested: Option<&[WindowsDependency]>,
+) -> Vec<WindowsDependency> {
+    let adb_ready = report.tools.iter().any(|check| check.tool == "adb" && check.executable);
+    let mut missing = Vec::new();
+    if !adb_ready {
+        missing.push(WindowsDependency::PlatformTools);
+    }
+    if !amds_installed {
+        missing.push(WindowsDependency::AppleMobileDeviceSupport);
+    }
+    missing.retain(|dependency| requested.is_none_or(|requested| requested.contains(dependency)));
+    missing
+}
+
+fn winget_args(action: &'static str, dependency: WindowsDependency) -> Vec<&'static str> {
+    let mut args = vec![
+        action,
+        "--id",
+        dependency.winget_id(),
+        "--exact",
+        "--silent",
+        "--disable-interactivity",
+    ];
+    if action == "install" {
+        args.extend(["--accept-package-agreements", "--accept-source-agreements"]);
+    }
+    args
+}
+
+/// Run winget for one package; installs and uninstalls can take minutes, so both get the
+/// transfer timeout and a single attempt.
+async fn run_winget(winget: &Path, action: &'static str, dependency: WindowsDependency) -> Result<(), String> {
+    let mut command = StdCommand::new(winget);
+    command.args(winget_args(action, dependency));
+    let output = run_with_policy(command, CommandPolicy::TRANSFER)
+        .await
+        .map_err(|e| e.to_string())?;
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let detail = stderr
+        .lines()
+        .chain(stdout.lines())
+        .map(str::trim)
+        .rfind(|line| !line.is_empty())
+        .unwrap_or("no output");
+    Err(format!("winget {} {} failed ({}): {}", action, dependency.winget_id(), output.status, detail))
+}
+
+fn emit_progress(app_handle: &tauri::AppHandle, dependency: WindowsDependency, stage: InstallStage, message: String) {
+    log::info!("📦 {} {:?}: {}", dependency.label(), stage, message);
+    let progress = DependencyInstallProgress {
+        dependency,
+        stage,
+        message,
+    };
+    if let Err(e) = app_handle.emit(WINDOWS_DEPENDENCIES_PROGRESS_EVENT, &progress) {
+        log::warn!("⚠️ Failed to emit dependency install progress: {}", e);
+    }
+}
+
+/// Uninstall what this run installed, newest first. Returns what was removed.
+async fn roll_back(app_handle: &tauri::AppHandle, winget: &Path, installed: &[WindowsDependency]) -> Vec<WindowsDependency> {
+    let mut rolled_back = Vec::new();
+    for &dependency in installed.iter().rev() {
+        emit_progress(app_handle, dependency, InstallStage::RollingBack, format!("Uninstalling {}", dependency.label()));
+        match run_winget(winget, "uninstall", dependency).await {
+            Ok(()) => {
+                emit_progress(app_handle, dependency, InstallStage::RolledBack, format!("Uninstalled {}", dependency.label()));
+                rolled_back.push(dependency);
+            }
+            Err(e) => log::error!("❌ Could not roll back {}: {}", dependency.label(), e),
+        }
+    }
+    rolled_back
+}
+
+/// Install the Windows device dependencies `check_environment` reports missing, or only
+/// `dependencies` when given. Progress goes out as `windows-dependencies-progress` events.
+#[tauri::command]
+pub async fn install_windows_dependencies(
+    app_handle: tauri::AppHandle,
+    dependencies: Option<Vec<WindowsDependency>>,
+) -> Result<DeviceResponse<DependencyInstallResult>, String> {
+    let failure = |error: String, data: Option<DependencyInstallResult>| {
+        log::error!("❌ {}", error);
+        Ok(DeviceResponse {
+            success: false,
+            data,
+            error: Some(error),
+            error_detail: Some(DeviceError::Unknown),
+        })
+    };
+
+    if !cfg!(windows) {
+        return failure("Dependency installation is only available on Windows".to_string(), None);
+    }
+    let Some(winget) = locate_executable("winget") else {
+        return failure(
+            "winget was not found. Install App Installer from the Microsoft Store, then try again".to_string(),
+            None,
+        );
+    };
+
+    let report = collect_environment_report().await;
+    let missing = missing_dependencies(&report, apple_mobile_device_support_installed(), dependencies.as_deref());
+    log::info!("🧰 Installing Windows dependencies: {:?}", missing);
+
+    let mut installed = Vec::new();
+    for dependency in missing {
+        emit_progress(&app_handle, dependency, InstallStage::Installing, format!("Installing {}", dependency.label()));
+        if let Err(e) = run_winget(&winget, "install", dependency).await {
+            emit_progress(&app_handle, dependency, InstallStage::Failed, e.clone());
+            let rolled_back = roll_back(&app_handle, &winget, &installed).await;
+            installed.retain(|dependency| !rolled_back.contains(dependency));
+            let result = DependencyInstallResult {
+                installed,
+                rolled_back,
+                report: collect_environment_report().await,
+            };
+            return failure(e, Some(result));
+        }
+        emit_progress(&app_handle, dependency, InstallStage::Installed, format!("Installed {}", dependency.label()));
+        installed.push(dependency);
+    }
+
+    Ok(DeviceResponse {
+        success: true,
+        data: Some(DependencyInstallResult {
+            installed,
+            rolled_back: Vec::new(),
+            report: collect_environment_report().await,
+        }),
+        error: None,
+        error_detail: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::device::environment::ToolCheck;
+
+    fn report(adb_executable: bool) -> EnvironmentReport {
+        EnvironmentReport {
+            os: "windows".to_string(),
+            tools: vec![ToolCheck {
+                tool: "adb".to_string(),
+                platform: "android".to_string(),
+                path: None,
+                found: adb_executable,
+                executable: adb_executable,
+                version: None,
+                minimum_version: None,
+                quarantined: false,
+                warning: None,
+                error: None,
+            }],
+            android_ready: adb_executable,
+            ios_device_ready: false,
+            ios_simulator_ready: false,
+        }
+    }
+
+    #[test]
+    fn test_missing_dependencies() {
+        assert_eq!(
+            missing_dependencies(&report(false), false, None),
+            vec![WindowsDependency::PlatformTools, WindowsDependency::AppleMobileDeviceSupport]
+        );
+        assert_eq!(
+            missing_dependencies(&report(true), false, None),
+            vec![WindowsDependency::AppleMobileDeviceSupport]
+        );
+        assert!(missing_dependencies(&report(true), true, None).is_empty());
+        assert_eq!(
+            missing_dependencies(&report(false), false, Some(&[WindowsDependency::PlatformTools])),
+            vec![WindowsDependency::PlatformTools]
+        );
+    }
+
+    #[test]
+    fn test_winget_args() {
+        assert_eq!(
+            winget_args("install", WindowsDependency::PlatformTools),
+            vec![
+                "install",
+                "--id",
+                "Google.PlatformTools",
+                "--exact",
+                "--silent",
+                "--disable-interactivity",
+                "--accept-package-agreements",
+                "--accept-source-agreements",
+            ]
+        );
+        assert_eq!(
+            winget_args("uninstall", WindowsDependency::AppleMobileDeviceSupport),
+            vec!["uninstall", "--id", "Apple.AppleMobileDeviceSupport", "--exact", "--silent", "--disable-interactivity"]
+        );
+    }
+}
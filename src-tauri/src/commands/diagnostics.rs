@@ -0,0 +1,646 @@
+// Diagnostics commands module
+// Structured in-memory operation log and a diagnostics bundle for bug reports
+
+use crate::commands::database::commands::connection_stats_snapshot;
+use crate::commands::database::{ChangeHistoryManager, DbConnectionCache, DbResponse};
+use crate::commands::device::environment::collect_environment_report;
+use crate::commands::device::helpers::{get_adb_path, get_temp_dir_path, get_xcrun_path, IOS_SIMULATORS_SUPPORTED};
+use crate::commands::device::ios::tools::get_tool_command_legacy;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tauri::{Manager, State};
+use zip::write::SimpleFileOptions;
+
+const OPERATION_LOG_CAPACITY: usize = 5000;
+const TOOL_VERSION_TIMEOUT: Duration = Duration::from_secs(5);
+// Only log files touched within this window go into a bundle
+const BUNDLE_LOG_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 3600);
+const UPDATER_PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+// Change history warns once this share of its context limit is in use
+const HISTORY_CONTEXT_WARN_RATIO: f64 = 0.9;
+pub const IOS_TOOLS: [&str; 4] = ["idevice_id", "ideviceinfo", "afcclient", "ideviceinstaller"];
+
+static OPERATION_LOG: LazyLock<Mutex<VecDeque<OperationLogEntry>>> =
+    LazyLock::new(|| Mutex::new(VecDeque::with_capacity(OPERATION_LOG_CAPACITY)));
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationLogEntry {
+    pub timestamp: String,
+    pub level: String,
+    // "backend" or "frontend"
+    pub source: String,
+    pub target: String,
+    pub message: String,
+    // Key-value pairs of the record, e.g. operationId, device and durationMs of an operation
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, String>,
+}
+
+/// Narrows the operation log in a bundle to one operation and/or device
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationLogFilter {
+    pub operation_id: Option<String>,
+    pub device: Option<String>,
+}
+
+impl OperationLogFilter {
+    fn matches(&self, entry: &OperationLogEntry) -> bool {
+        let field_is = |key: &str, wanted: &Option<String>| {
+            wanted.as_ref().is_none_or(|wanted| entry.fields.get(key) == Some(wanted))
+        };
+        field_is("operationId", &self.operation_id) && field_is("device", &self.device)
+    }
+}
+
+struct FieldCollector<'a>(&'a mut BTreeMap<String, String>);
+
+impl<'kvs> log::kv::VisitSource<'kvs> for FieldCollector<'_> {
+    fn visit_pair(&mut self, key: log::kv::Key<'kvs>, value: log::kv::Value<'kvs>) -> Result<(), log::kv::Error> {
+        let value = value.to_string();
+        // Optional fields are logged empty when absent
+        if !value.is_empty() {
+            self.0.insert(key.to_string(), value);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolVersion {
+    pub tool: String,
+    pub command: String,
+    pub version: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticsBundle {
+    pub path: String,
+    pub log_entries: usize,
+    pub log_files: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HealthStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthCheck {
+    pub name: String,
+    pub status: HealthStatus,
+    pub message: String,
+    pub details: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReport {
+    // Worst status among the checks
+    pub status: HealthStatus,
+    pub checked_at: String,
+    pub checks: Vec<HealthCheck>,
+}
+
+/// Record a log line and its key-value fields in the operation log; called from the log
+/// plugin's formatter.
+pub fn record_log_entry(timestamp: &str, record: &log::Record, source: &str) {
+    let mut fields = BTreeMap::new();
+    let _ = record.key_values().visit(&mut FieldCollector(&mut fields));
+    let entry = OperationLogEntry {
+        timestamp: timestamp.to_string(),
+        level: record.level().to_string(),
+        source: source.to_string(),
+        target: record.target().to_string(),
+        message: record.args().to_string(),
+        fields,
+    };
+
+    if let Ok(mut log) = OPERATION_LOG.lock() {
+        if log.len() == OPERATION_LOG_CAPACITY {
+            log.pop_front();
+        }
+        log.push_back(entry);
+    }
+}
+
+pub fn recent_operation_log() -> Vec<OperationLogEntry> {
+    OPERATION_LOG
+        .lock()
+        .map(|log| log.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+fn first_output_line(output: &std::process::Output) -> Option<String> {
+    [&output.stdout, &output.stderr]
+        .iter()
+        .filter_map(|bytes| {
+            String::from_utf8_lossy(bytes)
+                .lines()
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+                .map(str::to_string)
+        })
+        .next()
+}
+
+pub(crate) async fn probe_tool_version(tool: &str, command: String, args: &[&str]) -> ToolVersion {
+    let run = tokio::process::Command::new(&command).args(args).output();
+    let (version, error) = match tokio::time::timeout(TOOL_VERSION_TIMEOUT, run).await {
+        Ok(Ok(output)) => (first_output_line(&output), None),
+        Ok(Err(e)) => (None, Some(e.to_string())),
+        Err(_) => (None, Some("Timed out".to_string())),
+    };
+
+    ToolVersion {
+        tool: tool.to_string(),
+        command,
+        version,
+        error,
+    }
+}
+
+pub async fn collect_tool_versions() -> Vec<ToolVersion> {
+    let mut versions = vec![probe_tool_version("adb", get_adb_path(), &["version"]).await];
+    for tool in IOS_TOOLS {
+        versions.push(probe_tool_version(tool, get_tool_command_legacy(tool), &["--version"]).await);
+    }
+    if cfg!(target_os = "macos") {
+        versions.push(probe_tool_version("xcrun", get_xcrun_path(), &["--version"]).await);
+    }
+    versions
+}
+
+fn recent_log_files(log_dir: &Path) -> Vec<PathBuf> {
+    let now = std::time::SystemTime::now();
+    let mut files: Vec<PathBuf> = std::fs::read_dir(log_dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .filter(|entry| {
+                    entry
+                        .metadata()
+                        .and_then(|m| m.modified())
+                        .ok()
+                        .and_then(|modified| now.duration_since(modified).ok())
+                        .is_some_and(|age| age <= BUNDLE_LOG_MAX_AGE)
+                })
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file())
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+/// Write the bundle as a zip archive. Each value in `documents` becomes a JSON file.
+pub fn write_diagnostics_zip(
+    output_path: &Path,
+    log_entries: &[OperationLogEntry],
+    log_files: &[PathBuf],
+    documents: &[(&str, serde_json::Value)],
+) -> Result<(), String> {
+    let file = std::fs::File::create(output_path)
+        .map_err(|e| format!("Failed to create {}: {}", output_path.display(), e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    let mut add_file = |name: &str, bytes: &[u8]| -> Result<(), String> {
+        zip.start_file(name, options)
+            .map_err(|e| format!("Failed to add {} to bundle: {}", name, e))?;
+        zip.write_all(bytes)
+            .map_err(|e| format!("Failed to write {} to bundle: {}", name, e))
+    };
+
+    let mut operations = String::new();
+    for entry in log_entries {
+        operations.push_str(&serde_json::to_string(entry).map_err(|e| e.to_string())?);
+        operations.push('\n');
+    }
+    add_file("operations.jsonl", operations.as_bytes())?;
+
+    for (name, document) in documents {
+        let json = serde_json::to_vec_pretty(document).map_err(|e| e.to_string())?;
+        add_file(&format!("{}.json", name), &json)?;
+    }
+
+    for log_file in log_files {
+        let Some(name) = log_file.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        match std::fs::read(log_file) {
+            Ok(bytes) => add_file(&format!("logs/{}", name), &bytes)?,
+            Err(e) => log::warn!("⚠️ Skipping log file {}: {}", log_file.display(), e),
+        }
+    }
+
+    zip.finish()
+        .map_err(|e| format!("Failed to finish diagnostics bundle: {}", e))?;
+    Ok(())
+}
+
+/// System info, tool versions and connection stats, as JSON documents for a bundle
+pub async fn diagnostics_documents(
+    app_handle: &tauri::AppHandle,
+    db_cache: &DbConnectionCache,
+) -> Result<Vec<(&'static str, serde_json::Value)>, String> {
+    let system = serde_json::json!({
+        "appVersion": app_handle.package_info().version.to_string(),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "generatedAt": chrono::Utc::now().to_rfc3339(),
+    });
+    let tool_versions =
+        serde_json::to_value(collect_tool_versions().await).map_err(|e| e.to_string())?;
+    let connection_stats = serde_json::to_value(connection_stats_snapshot(db_cache).await)
+        .map_err(|e| e.to_string())?;
+
+    Ok(vec![
+        ("system", system),
+        ("tool_versions", tool_versions),
+        ("connection_stats", connection_stats),
+    ])
+}
+
+fn health_check(name: &str, status: HealthStatus, message: impl Into<String>, details: serde_json::Value) -> HealthCheck {
+    HealthCheck {
+        name: name.to_string(),
+        status,
+        message: message.into(),
+        details,
+    }
+}
+
+fn overall_status(checks: &[HealthCheck]) -> HealthStatus {
+    checks.iter().map(|check| check.status).max().unwrap_or(HealthStatus::Ok)
+}
+
+async fn check_device_tools() -> HealthCheck {
+    let report = collect_environment_report().await;
+    let mut unavailable = Vec::new();
+    if !report.android_ready {
+        unavailable.push("Android");
+    }
+    if !report.ios_device_ready {
+        unavailable.push("iOS devices");
+    }
+    if IOS_SIMULATORS_SUPPORTED && !report.ios_simulator_ready {
+        unavailable.push("iOS simulators");
+    }
+    let warnings = report.tools.iter().filter(|check| check.warning.is_some()).count();
+
+    let (status, message) = if !report.android_ready && !report.ios_device_ready && !report.ios_simulator_ready {
+        (HealthStatus::Error, "No device platform has working tools".to_string())
+    } else if !unavailable.is_empty() {
+        (HealthStatus::Warning, format!("Tools missing for {}", unavailable.join(", ")))
+    } else if warnings > 0 {
+        (HealthStatus::Warning, format!("{} tools have compatibility warnings", warnings))
+    } else {
+        (HealthStatus::Ok, "All device tools are available".to_string())
+    };
+    health_check("deviceTools", status, message, serde_json::to_value(&report).unwrap_or_default())
+}
+
+async fn check_connection_cache(db_cache: &DbConnectionCache) -> HealthCheck {
+    let cache = db_cache.read().await;
+    let stale: Vec<&String> = cache
+        .iter()
+        .filter(|(path, connection)| connection.pool.is_closed() || !Path::new(path.as_str()).exists())
+        .map(|(path, _)| path)
+        .collect();
+
+    let details = serde_json::json!({ "cached": cache.len(), "stale": stale });
+    if stale.is_empty() {
+        health_check(
+            "connectionCache",
+            HealthStatus::Ok,
+            format!("{} cached connections", cache.len()),
+            details,
+        )
+    } else {
+        health_check(
+            "connectionCache",
+            HealthStatus::Warning,
+            format!("{} cached connections are closed or point at missing files", stale.len()),
+            details,
+        )
+    }
+}
+
+fn probe_writable(dir: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let probe = dir.join(format!(".flippio-health-{}", uuid::Uuid::new_v4()));
+    std::fs::write(&probe, b"ok").map_err(|e| e.to_string())?;
+    std::fs::remove_file(&probe).map_err(|e| e.to_string())
+}
+
+fn check_temp_dir() -> HealthCheck {
+    let dir = get_temp_dir_path();
+    let details = serde_json::json!({ "path": dir.to_string_lossy() });
+    match probe_writable(&dir) {
+        Ok(()) => health_check("tempDir", HealthStatus::Ok, "Temp directory is writable", details),
+        Err(e) => health_check(
+            "tempDir",
+            HealthStatus::Error,
+            format!("Temp directory is not writable: {}", e),
+            details,
+        ),
+    }
+}
+
+async fn check_updater(app_handle: &tauri::AppHandle) -> HealthCheck {
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    {
+        let probe = tokio::time::timeout(
+            UPDATER_PROBE_TIMEOUT,
+            crate::commands::updater::probe_update_endpoint(app_handle),
+        )
+        .await;
+        match probe {
+            Ok(Ok(version)) => health_check(
+                "updater",
+                HealthStatus::Ok,
+                match &version {
+                    Some(version) => format!("Update endpoint reachable, version {} is available", version),
+                    None => "Update endpoint reachable, no update pending".to_string(),
+                },
+                serde_json::json!({ "pendingVersion": version }),
+            ),
+            Ok(Err(e)) => health_check(
+                "updater",
+                HealthStatus::Warning,
+                format!("Update endpoint unreachable: {}", e),
+                serde_json::Value::Null,
+            ),
+            Err(_) => health_check(
+                "updater",
+                HealthStatus::Warning,
+                "Update endpoint timed out",
+                serde_json::Value::Null,
+            ),
+        }
+    }
+
+    #[cfg(any(target_os = "android", target_os = "ios"))]
+    {
+        let _ = (app_handle, UPDATER_PROBE_TIMEOUT);
+        health_check("updater", HealthStatus::Ok, "Updates are not supported on this platform", serde_json::Value::Null)
+    }
+}
+
+async fn check_change_history(history_manager: &ChangeHistoryManager) -> HealthCheck {
+    let contexts = history_manager.get_active_contexts().await.len();
+    let limit = history_manager.max_total_contexts();
+    let details = serde_json::json!({
+        "activeContexts": contexts,
+        "maxContexts": limit,
+        "memoryUsageMb": history_manager.get_memory_usage_mb(),
+    });
+
+    if contexts as f64 >= limit as f64 * HISTORY_CONTEXT_WARN_RATIO {
+        health_check(
+            "changeHistory",
+            HealthStatus::Warning,
+            format!("{} of {} change histories in use; the oldest will be evicted", contexts, limit),
+            details,
+        )
+    } else {
+        health_check(
+            "changeHistory",
+            HealthStatus::Ok,
+            format!("{} change histories in use", contexts),
+            details,
+        )
+    }
+}
+
+/// Run every subsystem check at once for the diagnostics screen
+#[tauri::command]
+pub async fn app_health_check(
+    app_handle: tauri::AppHandle,
+    db_cache: State<'_, DbConnectionCache>,
+    history_manager: State<'_, ChangeHistoryManager>,
+) -> Result<DbResponse<HealthReport>, String> {
+    log::info!("🩺 Running health check");
+
+    let (tools, connections, updater, history) = tokio::join!(
+        check_device_tools(),
+        check_connection_cache(&db_cache),
+        check_updater(&app_handle),
+        check_change_history(&history_manager),
+    );
+    let checks = vec![tools, connections, check_temp_dir(), updater, history];
+    for check in checks.iter().filter(|check| check.status != HealthStatus::Ok) {
+        log::warn!("⚠️ Health check {}: {}", check.name, check.message);
+    }
+
+    Ok(DbResponse {
+        success: true,
+        data: Some(HealthReport {
+            status: overall_status(&checks),
+            checked_at: chrono::Utc::now().to_rfc3339(),
+            checks,
+        }),
+        error: None,
+    })
+}
+
+/// Zip recent logs, tool versions and connection stats for attaching to bug reports. With a
+/// `filter`, only operation log entries for that operation or device are included.
+#[tauri::command]
+pub async fn export_diagnostics_bundle(
+    app_handle: tauri::AppHandle,
+    db_cache: State<'_, DbConnectionCache>,
+    output_path: Option<String>,
+    filter: Option<OperationLogFilter>,
+) -> Result<DbResponse<Option<DiagnosticsBundle>>, String> {
+    log::info!("🩺 Exporting diagnostics bundle");
+
+    let result = async {
+        let default_name = format!(
+            "flippio-diagnostics-{}.zip",
+            chrono::Utc::now().format("%Y%m%d_%H%M%S")
+        );
+        let output_path = match output_path {
+            Some(path) if !path.trim().is_empty() => PathBuf::from(path),
+            _ => match crate::commands::common::prompt_save_path(
+                &app_handle,
+                &default_name,
+                &[("Zip Archive", &["zip"])],
+            )
+            .await?
+            {
+                Some(path) => path,
+                // Save dialog was cancelled
+                None => return Ok(None),
+            },
+        };
+
+        let documents = diagnostics_documents(&app_handle, &db_cache).await?;
+        let log_files = app_handle
+            .path()
+            .app_log_dir()
+            .map(|dir| recent_log_files(&dir))
+            .unwrap_or_default();
+        let filter = filter.unwrap_or_default();
+        let log_entries: Vec<_> = recent_operation_log()
+            .into_iter()
+            .filter(|entry| filter.matches(entry))
+            .collect();
+
+        let bundle = DiagnosticsBundle {
+            path: output_path.to_string_lossy().to_string(),
+            log_entries: log_entries.len(),
+            log_files: log_files.len(),
+        };
+        tokio::task::spawn_blocking(move || {
+            write_diagnostics_zip(&output_path, &log_entries, &log_files, &documents)
+        })
+        .await
+        .map_err(|e| format!("Diagnostics export task failed: {}", e))??;
+
+        Ok::<_, String>(Some(bundle))
+    }
+    .await;
+
+    match result {
+        Ok(bundle) => {
+            if let Some(bundle) = &bundle {
+                log::info!("✅ Diagnostics bundle written to {}", bundle.path);
+            }
+            Ok(DbResponse {
+                success: true,
+                data: Some(bundle),
+                error: None,
+            })
+        }
+        Err(e) => {
+            log::error!("❌ Failed to export diagnostics bundle: {}", e);
+            Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_operation_log_records_entries() {
+        let record = log::Record::builder()
+            .level(log::Level::Warn)
+            .target("flippio::commands::device")
+            .args(format_args!("adb not found"))
+            .build();
+        record_log_entry("2024-01-01T12:00:00.000Z", &record, "backend");
+
+        let entries = recent_operation_log();
+        assert!(entries.iter().any(|entry| entry.level == "WARN"
+            && entry.target == "flippio::commands::device"
+            && entry.message == "adb not found"));
+    }
+
+    #[test]
+    fn test_operation_log_keeps_fields_for_filtering() {
+        let fields = [("operationId", "op-1"), ("device", "emulator-5554"), ("note", "")];
+        let record = log::Record::builder()
+            .level(log::Level::Info)
+            .target("flippio::commands::common")
+            .args(format_args!("Operation finished"))
+            .key_values(&fields)
+            .build();
+        record_log_entry("2024-01-01T12:00:00.000Z", &record, "backend");
+
+        let entry = recent_operation_log()
+            .into_iter()
+            .find(|entry| entry.fields.get("operationId").map(String::as_str) == Some("op-1"))
+            .unwrap();
+        assert_eq!(entry.fields.get("device").map(String::as_str), Some("emulator-5554"));
+        assert!(!entry.fields.contains_key("note"));
+
+        let by_device = OperationLogFilter {
+            operation_id: None,
+            device: Some("emulator-5554".to_string()),
+        };
+        assert!(by_device.matches(&entry));
+        let other_operation = OperationLogFilter {
+            operation_id: Some("op-2".to_string()),
+            device: None,
+        };
+        assert!(!other_operation.matches(&entry));
+        assert!(OperationLogFilter::default().matches(&entry));
+    }
+
+    #[test]
+    fn test_overall_status_is_worst_check() {
+        let ok = health_check("a", HealthStatus::Ok, "fine", serde_json::Value::Null);
+        let warning = health_check("b", HealthStatus::Warning, "hmm", serde_json::Value::Null);
+        assert_eq!(overall_status(&[]), HealthStatus::Ok);
+        assert_eq!(overall_status(&[ok.clone()]), HealthStatus::Ok);
+        assert_eq!(overall_status(&[ok, warning]), HealthStatus::Warning);
+    }
+
+    #[test]
+    fn test_probe_writable() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join("nested");
+        probe_writable(&dir).unwrap();
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_write_diagnostics_zip() {
+        let temp_dir = TempDir::new().unwrap();
+        let log_file = temp_dir.path().join("backend.log");
+        std::fs::write(&log_file, "line one\n").unwrap();
+        let bundle_path = temp_dir.path().join("bundle.zip");
+
+        let entries = vec![OperationLogEntry {
+            timestamp: "2024-01-01T12:00:00.000Z".to_string(),
+            level: "INFO".to_string(),
+            source: "backend".to_string(),
+            target: "flippio".to_string(),
+            message: "started".to_string(),
+            fields: BTreeMap::new(),
+        }];
+        write_diagnostics_zip(
+            &bundle_path,
+            &entries,
+            &[log_file],
+            &[("system", serde_json::json!({ "os": "linux" }))],
+        )
+        .unwrap();
+
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&bundle_path).unwrap()).unwrap();
+        let mut names: Vec<String> = archive.file_names().map(str::to_string).collect();
+        names.sort();
+        assert_eq!(names, vec!["logs/backend.log", "operations.jsonl", "system.json"]);
+
+        let mut operations = String::new();
+        archive
+            .by_name("operations.jsonl")
+            .unwrap()
+            .read_to_string(&mut operations)
+            .unwrap();
+        assert!(operations.contains("\"message\":\"started\""));
+    }
+}
@@ -0,0 +1,249 @@
+// Guardrails commands module
+// Optional safe mode that blocks destructive operations until they are confirmed for the session
+
+use crate::commands::database::DbResponse;
+use crate::commands::settings::current_settings;
+use serde::{Deserialize, Serialize};
+use sqlparser::dialect::SQLiteDialect;
+use sqlparser::tokenizer::{Token, Tokenizer};
+use std::collections::BTreeSet;
+use std::sync::{LazyLock, Mutex};
+
+// Operations confirmed during this session; cleared on restart
+static UNLOCKED_OPERATIONS: LazyLock<Mutex<BTreeSet<DangerousOperation>>> =
+    LazyLock::new(|| Mutex::new(BTreeSet::new()));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DangerousOperation {
+    // DROP TABLE/INDEX/VIEW/TRIGGER
+    Drop,
+    // DELETE without a WHERE clause
+    DeleteAll,
+    ClearTable,
+    PushDatabase,
+}
+
+impl DangerousOperation {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "drop" => Ok(Self::Drop),
+            "deleteAll" => Ok(Self::DeleteAll),
+            "clearTable" => Ok(Self::ClearTable),
+            "pushDatabase" => Ok(Self::PushDatabase),
+            other => Err(format!(
+                "Unknown operation '{}'; expected drop, deleteAll, clearTable or pushDatabase",
+                other
+            )),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Drop => "drop",
+            Self::DeleteAll => "deleteAll",
+            Self::ClearTable => "clearTable",
+            Self::PushDatabase => "pushDatabase",
+        }
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            Self::Drop => "DROP statements",
+            Self::DeleteAll => "DELETE without WHERE",
+            Self::ClearTable => "clearing a table",
+            Self::PushDatabase => "pushing a database to a device",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SafeModeStatus {
+    pub enabled: bool,
+    pub unlocked: Vec<DangerousOperation>,
+}
+
+// Unquoted words of each statement, uppercased. Comments, literals and quoted identifiers are
+// dropped in the same lexer pass, so a quote inside a comment can't hide what follows it.
+fn statement_words(sql: &str) -> Vec<Vec<String>> {
+    let Ok(tokens) = Tokenizer::new(&SQLiteDialect {}, sql).tokenize() else {
+        // Not lexable: judge the raw text, which errs towards flagging
+        return sql
+            .split(';')
+            .map(|statement| {
+                statement
+                    .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                    .filter(|word| !word.is_empty())
+                    .map(str::to_uppercase)
+                    .collect()
+            })
+            .collect();
+    };
+
+    let mut statements = vec![Vec::new()];
+    for token in tokens {
+        match token {
+            Token::SemiColon => statements.push(Vec::new()),
+            Token::Word(word) if word.quote_style.is_none() => {
+                if let Some(current) = statements.last_mut() {
+                    current.push(word.value.to_uppercase());
+                }
+            }
+            _ => {}
+        }
+    }
+    statements
+}
+
+/// The most dangerous operation in a SQL script, if any. Literals and comments are ignored,
+/// so `DELETE FROM t -- WHERE id = 1` still counts as a delete without WHERE.
+pub fn classify_dangerous_sql(sql: &str) -> Option<DangerousOperation> {
+    let mut found = None;
+
+    for words in statement_words(sql) {
+        let Some(first) = words.first() else {
+            continue;
+        };
+        let has = |keyword: &str| words.iter().any(|word| word == keyword);
+
+        if first == "DROP" {
+            return Some(DangerousOperation::Drop);
+        }
+        if (first == "DELETE" || (first == "WITH" && has("DELETE"))) && !has("WHERE") {
+            found = Some(DangerousOperation::DeleteAll);
+        }
+    }
+    found
+}
+
+fn is_unlocked(operation: DangerousOperation) -> bool {
+    UNLOCKED_OPERATIONS
+        .lock()
+        .map(|unlocked| unlocked.contains(&operation))
+        .unwrap_or(false)
+}
+
+/// Fail with an explanation when safe mode is on and `operation` hasn't been confirmed this session.
+pub fn check_dangerous_operation(operation: DangerousOperation) -> Result<(), String> {
+    if !current_settings().safety.safe_mode || is_unlocked(operation) {
+        return Ok(());
+    }
+    log::warn!("🛡️ Safe mode blocked {}", operation.describe());
+    Err(format!(
+        "Safe mode blocks {}. Confirm the operation to allow it for this session.",
+        operation.describe()
+    ))
+}
+
+/// Check a SQL script against safe mode before running it
+pub fn check_dangerous_sql(sql: &str) -> Result<(), String> {
+    match classify_dangerous_sql(sql) {
+        Some(operation) => check_dangerous_operation(operation),
+        None => Ok(()),
+    }
+}
+
+/// Check a SQL script for a caller with no session to confirm in, like the CLI. Dangerous
+/// statements are refused unless their operation was confirmed up front.
+pub fn check_headless_sql(sql: &str, confirmed: &[DangerousOperation]) -> Result<(), String> {
+    match classify_dangerous_sql(sql) {
+        Some(operation) if !confirmed.contains(&operation) => {
+            log::warn!("🛡️ Blocked {} without confirmation", operation.describe());
+            Err(format!(
+                "Refusing {} without confirmation; pass --confirm {}",
+                operation.describe(),
+                operation.name()
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn safe_mode_status() -> SafeModeStatus {
+    SafeModeStatus {
+        enabled: current_settings().safety.safe_mode,
+        unlocked: UNLOCKED_OPERATIONS
+            .lock()
+            .map(|unlocked| unlocked.iter().copied().collect())
+            .unwrap_or_default(),
+    }
+}
+
+#[tauri::command]
+pub async fn get_safe_mode_status() -> Result<DbResponse<SafeModeStatus>, String> {
+    Ok(DbResponse {
+        success: true,
+        data: Some(safe_mode_status()),
+        error: None,
+    })
+}
+
+/// Allow one kind of dangerous operation for the rest of the session while safe mode is on
+#[tauri::command]
+pub async fn confirm_dangerous_operation(operation: String) -> Result<DbResponse<SafeModeStatus>, String> {
+    let operation = match DangerousOperation::parse(&operation) {
+        Ok(operation) => operation,
+        Err(e) => {
+            return Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            })
+        }
+    };
+
+    UNLOCKED_OPERATIONS
+        .lock()
+        .map_err(|_| "Safe mode state poisoned".to_string())?
+        .insert(operation);
+    log::info!("🔓 Unlocked {} for this session", operation.describe());
+
+    Ok(DbResponse {
+        success: true,
+        data: Some(safe_mode_status()),
+        error: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_dangerous_sql() {
+        assert_eq!(classify_dangerous_sql("DROP TABLE users"), Some(DangerousOperation::Drop));
+        assert_eq!(classify_dangerous_sql("delete from users"), Some(DangerousOperation::DeleteAll));
+        assert_eq!(
+            classify_dangerous_sql("DELETE FROM users -- WHERE id = 1"),
+            Some(DangerousOperation::DeleteAll)
+        );
+        assert_eq!(
+            classify_dangerous_sql("DELETE FROM notes; SELECT 'drop table x'"),
+            Some(DangerousOperation::DeleteAll)
+        );
+        assert_eq!(classify_dangerous_sql("DELETE FROM users WHERE id = 1"), None);
+        assert_eq!(classify_dangerous_sql("UPDATE users SET note = 'DELETE FROM x'"), None);
+        assert_eq!(classify_dangerous_sql("SELECT * FROM dropped_items"), None);
+        // A quote inside a comment must not swallow the next statement
+        assert_eq!(classify_dangerous_sql("-- don't\nDROP TABLE t"), Some(DangerousOperation::Drop));
+        assert_eq!(
+            classify_dangerous_sql("/* it's */ DELETE FROM \"where\""),
+            Some(DangerousOperation::DeleteAll)
+        );
+    }
+
+    #[test]
+    fn test_check_headless_sql() {
+        assert!(check_headless_sql("DROP TABLE users", &[]).is_err());
+        assert!(check_headless_sql("DROP TABLE users", &[DangerousOperation::Drop]).is_ok());
+        assert!(check_headless_sql("DELETE FROM users", &[DangerousOperation::Drop]).is_err());
+        assert!(check_headless_sql("UPDATE users SET active = 0 WHERE id = 1", &[]).is_ok());
+    }
+
+    #[test]
+    fn test_parse_operation() {
+        assert_eq!(DangerousOperation::parse("clearTable"), Ok(DangerousOperation::ClearTable));
+        assert!(DangerousOperation::parse("truncate").is_err());
+    }
+}
@@ -0,0 +1,616 @@
+//! End-to-end workflows
+//!
+//! Commands that chain device and database steps which the frontend would otherwise
+//! sequence itself over several IPC calls, with a partial-failure state after each one.
+
+use crate::commands::database::helpers::{copy_database_with_companions, prepare_sqlite_file_for_sync};
+use crate::commands::database::merge::{three_way_merge, ConflictResolution, ThreeWayMerge};
+use crate::commands::database::{db_open, invalidate_cached_connection, DbConnectionCache, DbPool};
+use crate::commands::device::integrity::{base_copy_path_for, metadata_path_for, save_base_copy};
+use crate::commands::device::scheduler::{
+    pull_device_database, pull_device_database_to, snapshot_file_name, snapshots_root, PullSnapshot,
+};
+use crate::commands::device::types::DeviceResponse;
+use crate::commands::device::errors::{CommandErrorExt, DeviceError};
+use crate::commands::device::verification::RemoteFileVerification;
+use crate::commands::device::{adb_push_database_file, device_push_ios_database_file, upload_simulator_ios_db_file};
+use serde::Serialize;
+use std::path::Path;
+use tauri::{Emitter, State};
+
+// First four bytes of a WAL file, big-endian, for either checksum byte order
+const WAL_MAGIC: [[u8; 4]; 2] = [[0x37, 0x7f, 0x06, 0x82], [0x37, 0x7f, 0x06, 0x83]];
+
+const SAVE_AND_PUSH_PROGRESS_EVENT: &str = "save-and-push-progress";
+// Snapshots of device copies taken before they are overwritten, listed like a schedule's
+pub const PRE_PUSH_SNAPSHOTS: &str = "pre-push";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenedDeviceDatabase {
+    // Path of the open connection; differs from the pulled copy for archives
+    pub path: String,
+    pub local_path: String,
+    pub device_type: String,
+    pub device_id: String,
+    pub package_name: String,
+    pub remote_path: String,
+    // Whether the -wal file was pulled next to the database
+    pub wal_pulled: bool,
+}
+
+fn is_wal_file(path: &Path) -> bool {
+    use std::io::Read;
+
+    let mut magic = [0u8; 4];
+    std::fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut magic))
+        .is_ok_and(|_| WAL_MAGIC.contains(&magic))
+}
+
+/// Pull the -wal companion of `remote_path` next to `local_path`, since committed data may not
+/// be checkpointed into the main file yet. A missing WAL is normal (the app may not use WAL
+/// mode). The -shm index is never pulled: SQLite rebuilds it, and a stale one from an earlier
+/// pull is removed so it cannot disagree with the new WAL.
+async fn pull_wal_companion(
+    app_handle: &tauri::AppHandle,
+    device_type: &str,
+    device_id: &str,
+    package_name: &str,
+    remote_path: &str,
+    local_path: &str,
+) -> bool {
+    let wal_target = format!("{}-wal", local_path);
+    for stale in [wal_target.clone(), format!("{}-shm", local_path)] {
+        if Path::new(&stale).exists() {
+            let _ = std::fs::remove_file(&stale);
+        }
+    }
+
+    let remote_wal = format!("{}-wal", remote_path);
+    let pulled = match pull_device_database(app_handle, device_type, device_id, package_name, &remote_wal).await {
+        Ok(path) => path,
+        Err(e) => {
+            log::info!("No WAL pulled for {}: {}", remote_path, e);
+            return false;
+        }
+    };
+    // The WAL is not a database of its own, drop the sidecars its pull wrote
+    for sidecar in [metadata_path_for(&pulled), base_copy_path_for(&pulled)] {
+        let _ = std::fs::remove_file(sidecar);
+    }
+    // exec-out can leave an error message in place of a missing file
+    if !is_wal_file(&pulled) {
+        let _ = std::fs::remove_file(&pulled);
+        return false;
+    }
+    match std::fs::rename(&pulled, &wal_target) {
+        Ok(()) => true,
+        Err(e) => {
+            log::warn!("⚠️ Failed to place WAL next to {}: {}", local_path, e);
+            false
+        }
+    }
+}
+
+/// Pull a device database with its WAL file, drop cached connections to the old copy
+/// and open it as the current database.
+pub async fn pull_and_open_database(
+    app_handle: &tauri::AppHandle,
+    state: State<'_, DbPool>,
+    db_cache: State<'_, DbConnectionCache>,
+    device_type: &str,
+    device_id: &str,
+    package_name: &str,
+    remote_path: &str,
+) -> Result<OpenedDeviceDatabase, String> {
+    if !matches!(device_type, "android" | "iphone-device" | "simulator") {
+        return Err(format!("Unsupported device type: {}", device_type));
+    }
+
+    log::info!("📥 Pull and open {} from {} ({})", remote_path, device_id, device_type);
+    let local_path = pull_device_database(app_handle, device_type, device_id, package_name, remote_path)
+        .await
+        .map_err(|e| format!("Failed to pull {}: {}", remote_path, e))?
+        .to_string_lossy()
+        .to_string();
+
+    // Simulator databases are opened in place, their WAL is already beside them
+    let wal_pulled = device_type != "simulator"
+        && pull_wal_companion(app_handle, device_type, device_id, package_name, remote_path, &local_path).await;
+    if wal_pulled {
+        // The base copy taken by the pull lacks what is still in the WAL
+        if let Err(e) = save_base_copy(Path::new(&local_path)) {
+            log::warn!("⚠️ Failed to refresh the base copy of {}: {}", local_path, e);
+        }
+    }
+
+    invalidate_cached_connection(app_handle, &db_cache, &local_path, "pulled").await;
+    let opened = db_open(state, db_cache, local_path.clone()).await?;
+    let path = match opened.data {
+        Some(path) if opened.success => path,
+        _ => return Err(opened.error.unwrap_or_else(|| "Failed to open the pulled database".to_string())),
+    };
+
+    log::info!("✅ Opened {} (WAL pulled: {})", path, wal_pulled);
+    Ok(OpenedDeviceDatabase {
+        path,
+        local_path,
+        device_type: device_type.to_string(),
+        device_id: device_id.to_string(),
+        package_name: package_name.to_string(),
+        remote_path: remote_path.to_string(),
+        wal_pulled,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SaveAndPushStep {
+    Checkpoint,
+    Snapshot,
+    Push,
+    Merge,
+    Verify,
+    Reopen,
+    Done,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveAndPushProgress {
+    pub operation_id: String,
+    pub step: SaveAndPushStep,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedAndPushed {
+    pub operation_id: String,
+    pub local_path: String,
+    pub remote_path: String,
+    // The device copy as it was before the push
+    pub snapshot: PullSnapshot,
+    pub push_message: String,
+    // None when the remote file could not be inspected after the push
+    pub verification: Option<RemoteFileVerification>,
+}
+
+fn emit_progress(app_handle: &tauri::AppHandle, operation_id: &str, step: SaveAndPushStep, message: impl Into<String>) {
+    let progress = SaveAndPushProgress {
+        operation_id: operation_id.to_string(),
+        step,
+        message: message.into(),
+    };
+    log::info!(operationId = operation_id, step:? = step; "Save and push {:?}: {}", step, progress.message);
+    if let Err(e) = app_handle.emit(SAVE_AND_PUSH_PROGRESS_EVENT, &progress) {
+        log::warn!("⚠️ Failed to emit save-and-push progress: {}", e);
+    }
+}
+
+/// Close every connection to `path` so nothing writes to it while it is pushed.
+async fn close_connections(state: &DbPool, db_cache: &DbConnectionCache, path: &str) {
+    let normalized_path = canonical_path(Path::new(path));
+    let cached = db_cache.write().await.remove(&normalized_path);
+    if let Some(cached) = cached {
+        cached.pool.close().await;
+    }
+    // The legacy pool holds whichever database was opened last, cached or not; it is only
+    // closed when that was this one, so other open databases keep working
+    let mut current = state.write().await;
+    let opened_here = current
+        .as_ref()
+        .is_some_and(|pool| canonical_path(pool.connect_options().get_filename()) == normalized_path);
+    if opened_here {
+        if let Some(pool) = current.take() {
+            pool.close().await;
+        }
+    } else if current.as_ref().is_some_and(|pool| pool.is_closed()) {
+        current.take();
+    }
+}
+
+fn canonical_path(path: &Path) -> String {
+    std::fs::canonicalize(path)
+        .unwrap_or_else(|_| path.to_path_buf())
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Copy the device's current database into the pre-push snapshots. The pull goes straight
+/// to the snapshot, so the edited copy, its pull metadata and base copy are never touched.
+async fn snapshot_remote_database(
+    app_handle: &tauri::AppHandle,
+    device_type: &str,
+    device_id: &str,
+    package_name: &str,
+    remote_path: &str,
+) -> Result<PullSnapshot, String> {
+    let dir = snapshots_root(app_handle)?.join(PRE_PUSH_SNAPSHOTS);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create snapshot directory: {}", e))?;
+
+    let taken_at = chrono::Utc::now();
+    let target = dir.join(snapshot_file_name(taken_at, remote_path));
+    pull_device_database_to(app_handle, device_type, device_id, package_name, remote_path, &target).await?;
+
+    Ok(PullSnapshot {
+        schedule_id: PRE_PUSH_SNAPSHOTS.to_string(),
+        path: target.to_string_lossy().to_string(),
+        taken_at: taken_at.to_rfc3339(),
+        size: std::fs::metadata(&target).map(|m| m.len()).unwrap_or(0),
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn push_with_snapshot(
+    app_handle: &tauri::AppHandle,
+    state: State<'_, DbPool>,
+    operation_id: &str,
+    device_type: &str,
+    device_id: &str,
+    package_name: &str,
+    local_path: &str,
+    remote_path: &str,
+    user_id: Option<u32>,
+) -> Result<SavedAndPushed, String> {
+    emit_progress(app_handle, operation_id, SaveAndPushStep::Checkpoint, "Checkpointing the WAL into the database");
+    prepare_sqlite_file_for_sync(local_path)?;
+
+    emit_progress(app_handle, operation_id, SaveAndPushStep::Snapshot, "Saving the device copy before overwriting it");
+    let snapshot = snapshot_remote_database(app_handle, device_type, device_id, package_name, remote_path)
+        .await
+        .map_err(|e| format!("Could not snapshot the device copy, nothing was pushed: {}", e))?;
+
+    let (push_message, verification) = push_and_verify(
+        app_handle,
+        state,
+        operation_id,
+        device_type,
+        device_id,
+        package_name,
+        local_path,
+        remote_path,
+        user_id,
+        &snapshot.path,
+    )
+    .await?;
+
+    Ok(SavedAndPushed {
+        operation_id: operation_id.to_string(),
+        local_path: local_path.to_string(),
+        remote_path: remote_path.to_string(),
+        snapshot,
+        push_message,
+        verification,
+    })
+}
+
+/// Push with the platform's push command and compare the device copy with the local file.
+#[allow(clippy::too_many_arguments)]
+async fn push_and_verify(
+    app_handle: &tauri::AppHandle,
+    state: State<'_, DbPool>,
+    operation_id: &str,
+    device_type: &str,
+    device_id: &str,
+    package_name: &str,
+    local_path: &str,
+    remote_path: &str,
+    user_id: Option<u32>,
+    snapshot_path: &str,
+) -> Result<(String, Option<RemoteFileVerification>), String> {
+    emit_progress(app_handle, operation_id, SaveAndPushStep::Push, format!("Pushing to {}", remote_path));
+    let (app, device_id, package_name) = (app_handle.clone(), device_id.to_string(), package_name.to_string());
+    let (local, remote) = (local_path.to_string(), remote_path.to_string());
+    let response = match device_type {
+        "android" => adb_push_database_file(app, device_id, local, package_name, remote, user_id, Some(true), None).await?,
+        "iphone-device" => {
+            device_push_ios_database_file(app, device_id, local, package_name, remote, Some(true), None).await?
+        }
+        _ => upload_simulator_ios_db_file(app, device_id, local, package_name, remote, state, Some(true), None).await?,
+    };
+    let pushed = match response.data {
+        Some(pushed) if response.success => pushed,
+        _ => return Err(response.error.unwrap_or_else(|| "Push failed".to_string())),
+    };
+
+    emit_progress(app_handle, operation_id, SaveAndPushStep::Verify, "Compared the device copy with the local file");
+    match pushed.verification {
+        Some(verification) if !verification.verified => Err(format!(
+            "The device copy differs from the pushed file ({:?} {} vs {}); the previous copy is in {}",
+            verification.method, verification.actual, verification.expected, snapshot_path
+        )),
+        verification => Ok((pushed.message, verification)),
+    }
+}
+
+/// Save the open database back to the device: checkpoint, snapshot the device copy, push,
+/// verify and reopen, reporting each step as a `save-and-push-progress` event
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn save_and_push(
+    app_handle: tauri::AppHandle,
+    state: State<'_, DbPool>,
+    db_cache: State<'_, DbConnectionCache>,
+    device_type: String,
+    device_id: String,
+    package_name: String,
+    local_path: String,
+    remote_path: String,
+    user_id: Option<u32>,
+) -> Result<DeviceResponse<SavedAndPushed>, String> {
+    let operation_id = uuid::Uuid::new_v4().to_string();
+    if !matches!(device_type.as_str(), "android" | "iphone-device" | "simulator") {
+        return Ok(DeviceResponse {
+            success: false,
+            data: None,
+            error_detail: Some(DeviceError::Unknown),
+            error: Some(format!("Unsupported device type: {}", device_type)),
+        });
+    }
+
+    close_connections(&state, &db_cache, &local_path).await;
+    let result = push_with_snapshot(
+        &app_handle,
+        state.clone(),
+        &operation_id,
+        &device_type,
+        &device_id,
+        &package_name,
+        &local_path,
+        &remote_path,
+        user_id,
+    )
+    .await;
+    reopen_after_push(&app_handle, state, db_cache, &operation_id, &local_path, result).await
+}
+
+/// Reopen the local copy whether or not the push went through, and report the outcome.
+async fn reopen_after_push<T>(
+    app_handle: &tauri::AppHandle,
+    state: State<'_, DbPool>,
+    db_cache: State<'_, DbConnectionCache>,
+    operation_id: &str,
+    local_path: &str,
+    result: Result<T, String>,
+) -> Result<DeviceResponse<T>, String> {
+    emit_progress(app_handle, operation_id, SaveAndPushStep::Reopen, format!("Reopening {}", local_path));
+    let reopened = db_open(state, db_cache, local_path.to_string()).await?;
+    let result = result.and_then(|done| match reopened.error {
+        Some(e) if !reopened.success => Err(format!("Pushed, but failed to reopen {}: {}", local_path, e)),
+        _ => Ok(done),
+    });
+
+    match result {
+        Ok(done) => {
+            emit_progress(app_handle, operation_id, SaveAndPushStep::Done, "Done");
+            Ok(DeviceResponse {
+                success: true,
+                data: Some(done),
+                error: None,
+                error_detail: None,
+            })
+        }
+        Err(e) => {
+            log::error!("❌ Push of {} failed: {}", local_path, e);
+            emit_progress(app_handle, operation_id, SaveAndPushStep::Failed, e.clone());
+            Ok(DeviceResponse {
+                success: false,
+                data: None,
+                error_detail: Some(e.device_error()),
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergedAndPushed {
+    pub operation_id: String,
+    pub local_path: String,
+    pub remote_path: String,
+    // The device copy the local edits were merged into
+    pub snapshot: PullSnapshot,
+    pub merge: ThreeWayMerge,
+    // False while conflicts are left; resolve them and merge again
+    pub pushed: bool,
+    pub push_message: Option<String>,
+    pub verification: Option<RemoteFileVerification>,
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn merge_with_device(
+    app_handle: &tauri::AppHandle,
+    state: State<'_, DbPool>,
+    operation_id: &str,
+    device_type: &str,
+    device_id: &str,
+    package_name: &str,
+    local_path: &str,
+    remote_path: &str,
+    user_id: Option<u32>,
+    resolutions: &[ConflictResolution],
+) -> Result<MergedAndPushed, String> {
+    let base_path = base_copy_path_for(Path::new(local_path));
+    if !Path::new(&base_path).is_file() {
+        return Err(format!("No base copy of {} was kept; pull the database again to merge", local_path));
+    }
+
+    emit_progress(app_handle, operation_id, SaveAndPushStep::Checkpoint, "Checkpointing the WAL into the database");
+    prepare_sqlite_file_for_sync(local_path)?;
+
+    emit_progress(app_handle, operation_id, SaveAndPushStep::Snapshot, "Fetching the current device copy");
+    let snapshot = snapshot_remote_database(app_handle, device_type, device_id, package_name, remote_path)
+        .await
+        .map_err(|e| format!("Could not fetch the device copy: {}", e))?;
+
+    emit_progress(app_handle, operation_id, SaveAndPushStep::Merge, "Merging local edits into the device copy");
+    let merged_path = format!("{}.merged", local_path);
+    let merge = three_way_merge(&base_path, local_path, &snapshot.path, &merged_path, resolutions).await?;
+
+    let mut merged = MergedAndPushed {
+        operation_id: operation_id.to_string(),
+        local_path: local_path.to_string(),
+        remote_path: remote_path.to_string(),
+        snapshot,
+        merge,
+        pushed: false,
+        push_message: None,
+        verification: None,
+    };
+    if !merged.merge.conflicts.is_empty() {
+        emit_progress(
+            app_handle,
+            operation_id,
+            SaveAndPushStep::Merge,
+            format!("{} conflicts need a decision; nothing was pushed", merged.merge.conflicts.len()),
+        );
+        return Ok(merged);
+    }
+
+    // The merged database becomes the local copy, so what is open is what was pushed
+    copy_database_with_companions(Path::new(&merged_path), Path::new(local_path))?;
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", merged_path, suffix));
+    }
+
+    let (push_message, verification) = push_and_verify(
+        app_handle,
+        state,
+        operation_id,
+        device_type,
+        device_id,
+        package_name,
+        local_path,
+        remote_path,
+        user_id,
+        &merged.snapshot.path,
+    )
+    .await?;
+    merged.pushed = true;
+    merged.push_message = Some(push_message);
+    merged.verification = verification;
+    Ok(merged)
+}
+
+/// Push with a three-way merge instead of overwriting: local edits since the pull are applied
+/// to the current device copy and pushed, unless they conflict with changes the app made.
+/// Conflicts are returned without pushing; call again with `resolutions` to settle them.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn merge_and_push(
+    app_handle: tauri::AppHandle,
+    state: State<'_, DbPool>,
+    db_cache: State<'_, DbConnectionCache>,
+    device_type: String,
+    device_id: String,
+    package_name: String,
+    local_path: String,
+    remote_path: String,
+    user_id: Option<u32>,
+    resolutions: Option<Vec<ConflictResolution>>,
+) -> Result<DeviceResponse<MergedAndPushed>, String> {
+    let operation_id = uuid::Uuid::new_v4().to_string();
+    if !matches!(device_type.as_str(), "android" | "iphone-device" | "simulator") {
+        return Ok(DeviceResponse {
+            success: false,
+            data: None,
+            error_detail: Some(DeviceError::Unknown),
+            error: Some(format!("Unsupported device type: {}", device_type)),
+        });
+    }
+
+    close_connections(&state, &db_cache, &local_path).await;
+    let result = merge_with_device(
+        &app_handle,
+        state.clone(),
+        &operation_id,
+        &device_type,
+        &device_id,
+        &package_name,
+        &local_path,
+        &remote_path,
+        user_id,
+        &resolutions.unwrap_or_default(),
+    )
+    .await;
+    reopen_after_push(&app_handle, state, db_cache, &operation_id, &local_path, result).await
+}
+
+/// Pull a database from a device or simulator and open it in one call
+#[tauri::command]
+pub async fn pull_and_open_device_database(
+    app_handle: tauri::AppHandle,
+    state: State<'_, DbPool>,
+    db_cache: State<'_, DbConnectionCache>,
+    device_type: String,
+    device_id: String,
+    package_name: String,
+    remote_path: String,
+) -> Result<DeviceResponse<OpenedDeviceDatabase>, String> {
+    match pull_and_open_database(&app_handle, state, db_cache, &device_type, &device_id, &package_name, &remote_path).await {
+        Ok(opened) => Ok(DeviceResponse {
+            success: true,
+            data: Some(opened),
+            error: None,
+            error_detail: None,
+        }),
+        Err(e) => {
+            log::error!("❌ Pull and open failed: {}", e);
+            Ok(DeviceResponse {
+                success: false,
+                data: None,
+                error_detail: Some(e.device_error()),
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::database::tests::create_test_database;
+    use sqlx::sqlite::SqlitePool;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+    use tokio::sync::RwLock;
+
+    #[tokio::test]
+    async fn test_close_connections_closes_uncached_open_database() {
+        let temp_dir = TempDir::new().unwrap();
+        let pushed = temp_dir.path().join("pushed.db");
+        let other = temp_dir.path().join("other.db");
+        create_test_database(pushed.to_str().unwrap()).unwrap();
+        create_test_database(other.to_str().unwrap()).unwrap();
+        // With the cache disabled db_open only keeps the pool in the legacy state
+        let db_cache: DbConnectionCache = Arc::new(RwLock::new(HashMap::new()));
+        let pool = SqlitePool::connect(&format!("sqlite:{}?mode=rwc", pushed.display())).await.unwrap();
+        let state: DbPool = Arc::new(RwLock::new(Some(pool.clone())));
+
+        close_connections(&state, &db_cache, other.to_str().unwrap()).await;
+        assert!(!pool.is_closed());
+        assert!(state.read().await.is_some());
+
+        close_connections(&state, &db_cache, pushed.to_str().unwrap()).await;
+        assert!(pool.is_closed());
+        assert!(state.read().await.is_none());
+    }
+
+    #[test]
+    fn test_is_wal_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let wal = temp_dir.path().join("app.db-wal");
+        std::fs::write(&wal, [0x37, 0x7f, 0x06, 0x82, 0, 0x2d, 0xe2, 0x18]).unwrap();
+        assert!(is_wal_file(&wal));
+
+        let error_text = temp_dir.path().join("missing.db-wal");
+        std::fs::write(&error_text, b"cat: /data/data/x/databases/app.db-wal: No such file or directory").unwrap();
+        assert!(!is_wal_file(&error_text));
+        assert!(!is_wal_file(&temp_dir.path().join("absent")));
+    }
+}
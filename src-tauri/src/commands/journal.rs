@@ -0,0 +1,385 @@
+//! Crash-safe operation journal
+//!
+//! Pulls, pushes and unpushed edits are written to `operation-journal.json` in the app data
+//! directory while they are in progress and removed once they end. Entries still in the file
+//! at the next launch belong to operations cut short by a crash, and are offered for recovery
+//! until the user resolves them.
+
+use crate::commands::database::change_history::ChangeEvent;
+use crate::commands::database::DbResponse;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex, RwLock};
+
+const JOURNAL_FILE_NAME: &str = "operation-journal.json";
+// Files an interrupted operation can leave next to the local database
+const LEFTOVER_SUFFIXES: [&str; 3] = [".backup", ".bak", ".push-state.json"];
+
+static JOURNAL: LazyLock<OperationJournal> = LazyLock::new(OperationJournal::default);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JournalOperation {
+    Pull,
+    Push,
+    // Edits recorded against a local copy that has not been pushed since
+    Edit,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+    pub id: String,
+    pub operation: JournalOperation,
+    pub device_type: String,
+    pub device_id: String,
+    pub package_name: String,
+    pub local_path: String,
+    pub remote_path: Option<String>,
+    pub started_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InterruptedOperation {
+    #[serde(flatten)]
+    pub entry: JournalEntry,
+    pub local_file_exists: bool,
+    pub leftover_files: Vec<String>,
+    pub suggestion: String,
+}
+
+impl InterruptedOperation {
+    fn inspect(entry: JournalEntry) -> Self {
+        let local_file_exists = Path::new(&entry.local_path).exists();
+        let leftover_files = LEFTOVER_SUFFIXES
+            .iter()
+            .map(|suffix| format!("{}{}", entry.local_path, suffix))
+            .filter(|path| Path::new(path).exists())
+            .collect();
+        let suggestion = match (entry.operation, local_file_exists) {
+            (JournalOperation::Pull, _) => "The pull did not finish; pull the database again".to_string(),
+            (JournalOperation::Push, true) => {
+                "The push did not finish, so the device copy may be missing or partial; push the local copy again"
+                    .to_string()
+            }
+            (JournalOperation::Push, false) => {
+                "The push did not finish and the local copy is gone; check the database on the device".to_string()
+            }
+            (JournalOperation::Edit, true) => "Edits were never pushed; push the local copy to keep them".to_string(),
+            (JournalOperation::Edit, false) => "Edits were never pushed and the local copy is gone".to_string(),
+        };
+        Self {
+            entry,
+            local_file_exists,
+            leftover_files,
+            suggestion,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct OperationJournal {
+    state: Arc<JournalState>,
+}
+
+#[derive(Default)]
+struct JournalState {
+    path: RwLock<Option<PathBuf>>,
+    // Operations of this session
+    entries: Mutex<Vec<JournalEntry>>,
+    // Operations the previous session left unfinished
+    interrupted: Mutex<Vec<JournalEntry>>,
+    // Held from taking the snapshot to the rename, so an older snapshot never lands last
+    write_lock: Mutex<()>,
+}
+
+impl JournalState {
+    /// Write this session's and the unresolved earlier entries through a temp file and rename,
+    /// so a crash mid-write never leaves a truncated journal.
+    fn write_file(&self) {
+        let _writing = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(path) = self.path.read().unwrap_or_else(|e| e.into_inner()).clone() else {
+            return;
+        };
+        let mut all = self.interrupted.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        all.extend(self.entries.lock().unwrap_or_else(|e| e.into_inner()).iter().cloned());
+
+        let result = serde_json::to_vec_pretty(&all)
+            .map_err(std::io::Error::other)
+            .and_then(|json| {
+                let temp_path = path.with_extension("json.tmp");
+                let mut file = std::fs::File::create(&temp_path)?;
+                std::io::Write::write_all(&mut file, &json)?;
+                file.sync_all()?;
+                std::fs::rename(&temp_path, &path)
+            });
+        if let Err(e) = result {
+            log::warn!("⚠️ Failed to write operation journal: {}", e);
+        }
+    }
+}
+
+impl OperationJournal {
+    /// Take over the journal at `path`: its entries become this session's interrupted operations.
+    pub fn load(&self, path: PathBuf) {
+        let leftover = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| match serde_json::from_str::<Vec<JournalEntry>>(&content) {
+                Ok(entries) => Some(entries),
+                Err(e) => {
+                    log::warn!("⚠️ Ignoring unreadable operation journal {}: {}", path.display(), e);
+                    None
+                }
+            })
+            .unwrap_or_default();
+
+        *self.state.interrupted.lock().unwrap_or_else(|e| e.into_inner()) = leftover;
+        *self.state.path.write().unwrap_or_else(|e| e.into_inner()) = Some(path);
+        self.persist();
+    }
+
+    /// Save the journal; on a runtime thread the file work (including its fsync) moves to the
+    /// blocking pool so it never stalls an async worker.
+    fn persist(&self) {
+        let state = Arc::clone(&self.state);
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => {
+                runtime.spawn_blocking(move || state.write_file());
+            }
+            Err(_) => state.write_file(),
+        }
+    }
+
+    pub fn begin(&self, entry: JournalEntry) {
+        self.state.entries.lock().unwrap_or_else(|e| e.into_inner()).push(entry);
+        self.persist();
+    }
+
+    pub fn finish(&self, id: &str) {
+        self.state.entries.lock().unwrap_or_else(|e| e.into_inner()).retain(|entry| entry.id != id);
+        self.persist();
+    }
+
+    /// Record unpushed edits to `entry.local_path` unless they already are.
+    pub fn begin_edit(&self, entry: JournalEntry) {
+        let known = self
+            .state
+            .entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .any(|existing| existing.operation == JournalOperation::Edit && existing.local_path == entry.local_path);
+        if !known {
+            self.begin(entry);
+        }
+    }
+
+    /// Forget unpushed edits to `local_path`, from this session and earlier ones.
+    pub fn clear_edits(&self, local_path: &str) {
+        let is_pushed_edit = |entry: &JournalEntry| entry.operation == JournalOperation::Edit && entry.local_path == local_path;
+        self.state.entries.lock().unwrap_or_else(|e| e.into_inner()).retain(|entry| !is_pushed_edit(entry));
+        self.state.interrupted.lock().unwrap_or_else(|e| e.into_inner()).retain(|entry| !is_pushed_edit(entry));
+        self.persist();
+    }
+
+    pub fn interrupted_operations(&self) -> Vec<InterruptedOperation> {
+        self.state
+            .interrupted
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .cloned()
+            .map(InterruptedOperation::inspect)
+            .collect()
+    }
+
+    /// Drop an interrupted operation from the journal, returning it.
+    pub fn resolve(&self, id: &str) -> Option<JournalEntry> {
+        let mut interrupted = self.state.interrupted.lock().unwrap_or_else(|e| e.into_inner());
+        let index = interrupted.iter().position(|entry| entry.id == id)?;
+        let entry = interrupted.remove(index);
+        drop(interrupted);
+        self.persist();
+        Some(entry)
+    }
+}
+
+/// Removes its journal entry when dropped, i.e. when the operation ends in any way but a crash.
+pub struct JournalGuard {
+    id: String,
+    local_path: String,
+}
+
+impl JournalGuard {
+    /// A push of the local copy succeeded, so its edits are on the device.
+    pub fn complete_push(&self) {
+        JOURNAL.clear_edits(&self.local_path);
+    }
+}
+
+impl Drop for JournalGuard {
+    fn drop(&mut self) {
+        JOURNAL.finish(&self.id);
+    }
+}
+
+fn new_entry(
+    operation: JournalOperation,
+    device_type: &str,
+    device_id: &str,
+    package_name: &str,
+    local_path: &str,
+    remote_path: Option<&str>,
+) -> JournalEntry {
+    JournalEntry {
+        id: uuid::Uuid::new_v4().to_string(),
+        operation,
+        device_type: device_type.to_string(),
+        device_id: device_id.to_string(),
+        package_name: package_name.to_string(),
+        local_path: local_path.to_string(),
+        remote_path: remote_path.map(str::to_string),
+        started_at: chrono::Utc::now().to_rfc3339(),
+    }
+}
+
+/// Journal a pull or push for as long as the returned guard lives.
+pub fn begin_operation(
+    operation: JournalOperation,
+    device_type: &str,
+    device_id: &str,
+    package_name: &str,
+    local_path: &str,
+    remote_path: &str,
+) -> JournalGuard {
+    let entry = new_entry(operation, device_type, device_id, package_name, local_path, Some(remote_path));
+    let guard = JournalGuard {
+        id: entry.id.clone(),
+        local_path: entry.local_path.clone(),
+    };
+    JOURNAL.begin(entry);
+    guard
+}
+
+/// Note that `change` edited a local copy, until that copy is pushed.
+pub fn record_unpushed_edit(change: &ChangeEvent) {
+    let context = &change.user_context;
+    // Files opened from disk have no device to push to
+    if context.device_id == "unknown" {
+        return;
+    }
+    JOURNAL.begin_edit(new_entry(
+        JournalOperation::Edit,
+        &context.device_type,
+        &context.device_id,
+        &context.app_package,
+        &change.database_path,
+        None,
+    ));
+}
+
+/// Load the journal from the app data directory; run on startup after `init_settings`.
+pub fn init_journal() {
+    match crate::commands::settings::app_data_dir() {
+        Some(data_dir) => {
+            JOURNAL.load(data_dir.join(JOURNAL_FILE_NAME));
+            let interrupted = JOURNAL.interrupted_operations().len();
+            if interrupted > 0 {
+                log::warn!("⚠️ {} operations were interrupted in the previous session", interrupted);
+            }
+        }
+        None => log::warn!("⚠️ Operation journal disabled, no app data directory"),
+    }
+}
+
+/// Operations the previous session did not finish
+#[tauri::command]
+pub async fn journal_get_interrupted_operations() -> Result<DbResponse<Vec<InterruptedOperation>>, String> {
+    Ok(DbResponse {
+        success: true,
+        data: Some(JOURNAL.interrupted_operations()),
+        error: None,
+    })
+}
+
+/// Mark an interrupted operation as handled; `discard_local_file` also deletes the local copy
+/// and its leftovers, e.g. a partial pull
+#[tauri::command]
+pub async fn journal_resolve_interrupted_operation(
+    id: String,
+    discard_local_file: Option<bool>,
+) -> Result<DbResponse<InterruptedOperation>, String> {
+    let Some(entry) = JOURNAL.resolve(&id) else {
+        return Ok(DbResponse {
+            success: false,
+            data: None,
+            error: Some(format!("No interrupted operation with id {}", id)),
+        });
+    };
+
+    let resolved = InterruptedOperation::inspect(entry);
+    if discard_local_file.unwrap_or(false) {
+        let paths = std::iter::once(&resolved.entry.local_path).chain(&resolved.leftover_files);
+        for path in paths.filter(|path| Path::new(path).is_file()) {
+            if let Err(e) = std::fs::remove_file(path) {
+                log::warn!("⚠️ Failed to remove {}: {}", path, e);
+            }
+        }
+    }
+    log::info!("🩹 Resolved interrupted {:?} of {}", resolved.entry.operation, resolved.entry.local_path);
+
+    Ok(DbResponse {
+        success: true,
+        data: Some(resolved),
+        error: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entry(operation: JournalOperation, local_path: &str) -> JournalEntry {
+        new_entry(operation, "android", "emulator-5554", "com.example.app", local_path, None)
+    }
+
+    #[test]
+    fn test_unfinished_operations_survive_a_restart() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(JOURNAL_FILE_NAME);
+        let local_db = temp_dir.path().join("app.db");
+        std::fs::write(&local_db, b"db").unwrap();
+        std::fs::write(format!("{}.push-state.json", local_db.display()), b"{}").unwrap();
+        let local_db = local_db.to_string_lossy().to_string();
+
+        let journal = OperationJournal::default();
+        journal.load(path.clone());
+        let finished = entry(JournalOperation::Pull, &local_db);
+        let push = entry(JournalOperation::Push, &local_db);
+        journal.begin(finished.clone());
+        journal.begin(push.clone());
+        journal.begin_edit(entry(JournalOperation::Edit, &local_db));
+        journal.begin_edit(entry(JournalOperation::Edit, &local_db));
+        journal.finish(&finished.id);
+
+        // Simulated crash: a new session loads what was left behind
+        let restarted = OperationJournal::default();
+        restarted.load(path.clone());
+        let interrupted = restarted.interrupted_operations();
+        assert_eq!(interrupted.len(), 2);
+        assert_eq!(interrupted[0].entry, push);
+        assert!(interrupted[0].local_file_exists);
+        assert_eq!(interrupted[0].leftover_files.len(), 1);
+        assert_eq!(interrupted[1].entry.operation, JournalOperation::Edit);
+
+        // A successful push clears the edits, resolving clears the rest
+        restarted.clear_edits(&local_db);
+        assert!(restarted.resolve(&push.id).is_some());
+        assert!(restarted.resolve(&push.id).is_none());
+        let reloaded = OperationJournal::default();
+        reloaded.load(path);
+        assert!(reloaded.interrupted_operations().is_empty());
+    }
+}
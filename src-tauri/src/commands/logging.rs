@@ -0,0 +1,178 @@
+// Logging commands module
+// Runtime log level with per-module filters (e.g. "info,flippio::commands::device=debug")
+
+use crate::commands::common::set_full_logging;
+use crate::commands::database::DbResponse;
+use crate::commands::settings::{current_settings, update_settings, LoggingSettings};
+use log::LevelFilter;
+use std::str::FromStr;
+use std::sync::{LazyLock, RwLock};
+
+static LOG_FILTER: LazyLock<RwLock<LogFilter>> = LazyLock::new(|| RwLock::new(LogFilter::default()));
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogFilter {
+    pub default_level: LevelFilter,
+    // (module prefix, level), most specific prefix first
+    pub modules: Vec<(String, LevelFilter)>,
+}
+
+impl Default for LogFilter {
+    fn default() -> Self {
+        Self {
+            default_level: LevelFilter::Trace,
+            modules: Vec::new(),
+        }
+    }
+}
+
+impl LogFilter {
+    /// Parse an env_logger style spec: a default level and/or `module=level` pairs.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut filter = LogFilter::default();
+
+        for directive in spec.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                Some((module, level)) => {
+                    let module = module.trim();
+                    if module.is_empty() {
+                        return Err(format!("Missing module name in '{}'", directive));
+                    }
+                    filter.modules.push((module.to_lowercase(), parse_level(level)?));
+                }
+                None => filter.default_level = parse_level(directive)?,
+            }
+        }
+
+        filter.modules.sort_by(|a, b| b.0.len().cmp(&a.0.len()));
+        Ok(filter)
+    }
+
+    fn level_for(&self, target: &str) -> LevelFilter {
+        // Targets start with the crate name, which is "Flippio" for the binary
+        let target = target.to_lowercase();
+        self.modules
+            .iter()
+            .find(|(module, _)| {
+                target == *module || target.starts_with(&format!("{}::", module))
+            })
+            .map(|(_, level)| *level)
+            .unwrap_or(self.default_level)
+    }
+
+    pub fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= self.level_for(metadata.target())
+    }
+}
+
+fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    LevelFilter::from_str(level.trim()).map_err(|_| format!("Unknown log level '{}'", level.trim()))
+}
+
+/// Filter used by the log plugin for every record.
+pub fn is_log_enabled(metadata: &log::Metadata) -> bool {
+    LOG_FILTER
+        .read()
+        .map(|filter| filter.enabled(metadata))
+        .unwrap_or(true)
+}
+
+fn apply_log_filter(filter: LogFilter) {
+    if let Ok(mut current) = LOG_FILTER.write() {
+        *current = filter;
+    }
+}
+
+/// Apply saved logging settings; called on startup and whenever settings change.
+pub fn apply_logging_settings(settings: &LoggingSettings) {
+    set_full_logging(settings.full_logging);
+    match LogFilter::parse(&settings.filter) {
+        Ok(filter) => apply_log_filter(filter),
+        Err(e) => log::warn!("⚠️ Ignoring saved log filter '{}': {}", settings.filter, e),
+    }
+}
+
+#[tauri::command]
+pub async fn get_log_level() -> Result<DbResponse<String>, String> {
+    Ok(DbResponse {
+        success: true,
+        data: Some(current_settings().logging.filter),
+        error: None,
+    })
+}
+
+/// Change the log level without restarting, e.g. "warn" or "info,flippio::commands::device=debug"
+#[tauri::command]
+pub async fn set_log_level(filter: String) -> Result<DbResponse<String>, String> {
+    match update_settings(serde_json::json!({ "logging": { "filter": filter } })) {
+        Ok(settings) => {
+            log::info!("🔧 Log filter set to '{}'", settings.logging.filter);
+            Ok(DbResponse {
+                success: true,
+                data: Some(settings.logging.filter),
+                error: None,
+            })
+        }
+        Err(e) => Ok(DbResponse {
+            success: false,
+            data: None,
+            error: Some(e),
+        }),
+    }
+}
+
+/// Opt in to logging queries and values verbatim (developer use only)
+#[tauri::command]
+pub async fn set_full_logging_enabled(enabled: bool) -> Result<DbResponse<bool>, String> {
+    if let Err(e) = update_settings(serde_json::json!({ "logging": { "fullLogging": enabled } })) {
+        log::error!("❌ {}", e);
+        return Ok(DbResponse {
+            success: false,
+            data: None,
+            error: Some(e),
+        });
+    }
+
+    log::warn!(
+        "🔧 Full logging {}",
+        if enabled { "enabled: queries and values are logged verbatim" } else { "disabled" }
+    );
+    Ok(DbResponse {
+        success: true,
+        data: Some(enabled),
+        error: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(level: log::Level, target: &str) -> log::Metadata<'_> {
+        log::Metadata::builder().level(level).target(target).build()
+    }
+
+    #[test]
+    fn test_parse_log_filter() {
+        let filter = LogFilter::parse("warn, flippio::commands=info,flippio::commands::device=debug").unwrap();
+
+        assert_eq!(filter.default_level, LevelFilter::Warn);
+        assert_eq!(filter.modules[0], ("flippio::commands::device".to_string(), LevelFilter::Debug));
+
+        assert!(LogFilter::parse("loud").is_err());
+        assert!(LogFilter::parse("=debug").is_err());
+        assert_eq!(LogFilter::parse("").unwrap(), LogFilter::default());
+    }
+
+    #[test]
+    fn test_log_filter_uses_most_specific_module() {
+        let filter = LogFilter::parse("warn,flippio::commands=info,flippio::commands::device=debug").unwrap();
+
+        assert!(filter.enabled(&metadata(log::Level::Debug, "Flippio::commands::device::adb")));
+        assert!(!filter.enabled(&metadata(log::Level::Debug, "Flippio::commands::database")));
+        assert!(filter.enabled(&metadata(log::Level::Info, "Flippio::commands::database")));
+        assert!(!filter.enabled(&metadata(log::Level::Info, "sqlx::query")));
+        // A prefix only matches whole path segments
+        assert!(!filter.enabled(&metadata(log::Level::Info, "flippio::commands_extra")));
+    }
+}
@@ -3,3 +3,13 @@ pub mod device;
 pub mod database;
 pub mod common;
 pub mod updater;
+pub mod workspace;
+pub mod diagnostics;
+pub mod logging;
+pub mod settings;
+pub mod rpc;
+pub mod guardrails;
+pub mod integrations;
+pub mod journal;
+pub mod crash;
+pub mod telemetry;
@@ -0,0 +1,517 @@
+// RPC server module
+// JSON-RPC 2.0 endpoint over stdio or localhost TCP; the stdio transport speaks the
+// Model Context Protocol so AI assistants can list devices, pull databases and run queries
+//
+// Any local process (including a browser page posting to 127.0.0.1) can reach the TCP port,
+// so TCP clients must first authenticate with the per-launch token in `rpc-token`, a
+// connection is dropped on the first line that isn't JSON-RPC, databases are limited to
+// Flippio's temp directory and queries run read-only unless `rpc.allowWrites` is set.
+
+use crate::commands::database::{fetch_export_source, is_read_only_query, ExportSource};
+use crate::commands::device::helpers::get_temp_dir_path;
+use crate::commands::device::{list_android_devices, list_android_packages, pull_android_database_files};
+use crate::commands::guardrails::check_dangerous_sql;
+use crate::commands::settings::{app_data_dir, current_settings};
+use serde_json::{json, Value};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+
+const MCP_PROTOCOL_VERSION: &str = "2024-11-05";
+const RPC_TOKEN_FILE_NAME: &str = "rpc-token";
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_ERROR: i64 = -32000;
+
+/// What a connection may do
+#[derive(Debug, Clone)]
+pub struct RpcAccess {
+    // Token the first message must present; stdio clients started the process themselves
+    pub token: Option<String>,
+    // Databases must live under this directory
+    pub db_root: PathBuf,
+    pub allow_writes: bool,
+}
+
+impl RpcAccess {
+    /// Limits from the current settings
+    pub fn from_settings(token: Option<String>) -> Self {
+        RpcAccess {
+            token,
+            db_root: get_temp_dir_path(),
+            allow_writes: current_settings().rpc.allow_writes,
+        }
+    }
+}
+
+struct ToolSpec {
+    name: &'static str,
+    description: &'static str,
+    // (parameter, description), all required strings
+    params: &'static [(&'static str, &'static str)],
+}
+
+const TOOLS: [ToolSpec; 5] = [
+    ToolSpec {
+        name: "list_devices",
+        description: "List connected Android devices and emulators",
+        params: &[],
+    },
+    ToolSpec {
+        name: "list_packages",
+        description: "List third-party packages installed on an Android device",
+        params: &[("deviceId", "Device serial from list_devices")],
+    },
+    ToolSpec {
+        name: "pull_databases",
+        description: "Pull every SQLite database of a package into Flippio's temp directory",
+        params: &[
+            ("deviceId", "Device serial from list_devices"),
+            ("packageName", "Package name from list_packages"),
+        ],
+    },
+    ToolSpec {
+        name: "list_tables",
+        description: "List the tables of a local SQLite database file",
+        params: &[("dbPath", "Path to a local database, e.g. from pull_databases")],
+    },
+    ToolSpec {
+        name: "run_query",
+        description: "Run a SQL statement against a local SQLite database file",
+        params: &[
+            ("dbPath", "Path to a local database, e.g. from pull_databases"),
+            ("query", "SQL to execute"),
+        ],
+    },
+];
+
+fn tool_definitions() -> Value {
+    let tools: Vec<Value> = TOOLS
+        .iter()
+        .map(|tool| {
+            let properties: serde_json::Map<String, Value> = tool
+                .params
+                .iter()
+                .map(|(name, description)| {
+                    (name.to_string(), json!({ "type": "string", "description": description }))
+                })
+                .collect();
+            let required: Vec<&str> = tool.params.iter().map(|(name, _)| *name).collect();
+            json!({
+                "name": tool.name,
+                "description": tool.description,
+                "inputSchema": {
+                    "type": "object",
+                    "properties": properties,
+                    "required": required,
+                },
+            })
+        })
+        .collect();
+    json!({ "tools": tools })
+}
+
+fn string_param<'a>(params: &'a Value, name: &str) -> Result<&'a str, (i64, String)> {
+    params
+        .get(name)
+        .and_then(Value::as_str)
+        .filter(|value| !value.trim().is_empty())
+        .ok_or_else(|| (INVALID_PARAMS, format!("Missing string parameter '{}'", name)))
+}
+
+/// `db_path` resolved, as long as it is a file inside `db_root`
+fn allowed_db_path(db_path: &str, db_root: &Path) -> Result<PathBuf, String> {
+    let path = Path::new(db_path)
+        .canonicalize()
+        .ok()
+        .filter(|path| path.is_file())
+        .ok_or_else(|| format!("Database file not found: {}", db_path))?;
+    let root = db_root.canonicalize().unwrap_or_else(|_| db_root.to_path_buf());
+    if !path.starts_with(&root) {
+        return Err(format!("Only databases under {} can be opened over RPC", root.display()));
+    }
+    Ok(path)
+}
+
+async fn open_database(db_path: &Path, read_only: bool) -> Result<SqlitePool, String> {
+    let options = SqliteConnectOptions::new().filename(db_path).read_only(read_only);
+    SqlitePool::connect_with(options)
+        .await
+        .map_err(|e| format!("Failed to open {}: {}", db_path.display(), e))
+}
+
+async fn run_query(db_path: &str, query: &str, access: &RpcAccess) -> Result<Value, String> {
+    let db_path = allowed_db_path(db_path, &access.db_root)?;
+    let read_only = is_read_only_query(query);
+    if !read_only {
+        if !access.allow_writes {
+            return Err("RPC is read-only; enable rpc.allowWrites in settings to modify databases".to_string());
+        }
+        check_dangerous_sql(query)?;
+    }
+    // A read-only connection also stops writes the classification above might miss
+    let pool = open_database(&db_path, !access.allow_writes).await?;
+
+    let result = if read_only {
+        let (columns, rows) = fetch_export_source(&pool, &ExportSource::Query(query.to_string())).await?;
+        json!({ "columns": columns, "rows": rows })
+    } else {
+        let result = sqlx::query(query)
+            .execute(&pool)
+            .await
+            .map_err(|e| format!("Error executing query: {}", e))?;
+        json!({ "rowsAffected": result.rows_affected() })
+    };
+    pool.close().await;
+    Ok(result)
+}
+
+async fn list_tables(db_path: &str, access: &RpcAccess) -> Result<Value, String> {
+    run_query(
+        db_path,
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+        access,
+    )
+    .await
+    .map(|result| {
+        let tables: Vec<Value> = result["rows"]
+            .as_array()
+            .map(|rows| rows.iter().map(|row| row["name"].clone()).collect())
+            .unwrap_or_default();
+        json!({ "tables": tables })
+    })
+}
+
+fn device_result<T: serde::Serialize>(
+    response: crate::commands::device::types::DeviceResponse<T>,
+) -> Result<Value, String> {
+    if response.success {
+        serde_json::to_value(response.data).map_err(|e| e.to_string())
+    } else {
+        Err(response.error.unwrap_or_else(|| "Unknown device error".to_string()))
+    }
+}
+
+/// Run one tool. Parameter problems are reported separately from tool failures.
+async fn call_tool(name: &str, params: &Value, access: &RpcAccess) -> Result<Result<Value, String>, (i64, String)> {
+    log::info!("🛰️ RPC call: {}", name);
+
+    let result = match name {
+        "list_devices" => device_result(list_android_devices().await),
+        "list_packages" => device_result(list_android_packages(string_param(params, "deviceId")?, None).await),
+        "pull_databases" => {
+            let device_id = string_param(params, "deviceId")?;
+            let package_name = string_param(params, "packageName")?;
+            serde_json::to_value(pull_android_database_files(device_id, package_name, None, false).await)
+                .map_err(|e| e.to_string())
+        }
+        "list_tables" => list_tables(string_param(params, "dbPath")?, access).await,
+        "run_query" => {
+            let db_path = string_param(params, "dbPath")?;
+            run_query(db_path, string_param(params, "query")?, access).await
+        }
+        other => return Err((METHOD_NOT_FOUND, format!("Unknown method '{}'", other))),
+    };
+    Ok(result)
+}
+
+fn error_response(id: Value, code: i64, message: String) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+fn success_response(id: Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+/// Handle one JSON-RPC request. Returns `None` for notifications, which get no reply.
+pub async fn handle_request(request: Value, access: &RpcAccess) -> Option<Value> {
+    let Some(method) = request.get("method").and_then(Value::as_str) else {
+        return Some(error_response(
+            request.get("id").cloned().unwrap_or(Value::Null),
+            INVALID_REQUEST,
+            "Request has no method".to_string(),
+        ));
+    };
+    // Requests without an id are notifications, e.g. MCP's "notifications/initialized"
+    let id = request.get("id").cloned()?;
+    let params = request.get("params").cloned().unwrap_or_else(|| json!({}));
+
+    let response = match method {
+        "initialize" => success_response(
+            id,
+            json!({
+                "protocolVersion": MCP_PROTOCOL_VERSION,
+                "capabilities": { "tools": {} },
+                "serverInfo": { "name": "flippio", "version": env!("CARGO_PKG_VERSION") },
+            }),
+        ),
+        "ping" => success_response(id, json!({})),
+        "tools/list" => success_response(id, tool_definitions()),
+        "tools/call" => {
+            let name = params.get("name").and_then(Value::as_str).unwrap_or_default();
+            let arguments = params.get("arguments").cloned().unwrap_or_else(|| json!({}));
+            match call_tool(name, &arguments, access).await {
+                Ok(Ok(value)) => success_response(
+                    id,
+                    json!({
+                        "content": [{ "type": "text", "text": value.to_string() }],
+                        "isError": false,
+                    }),
+                ),
+                // MCP reports tool failures as results so the assistant can read them
+                Ok(Err(e)) => success_response(
+                    id,
+                    json!({ "content": [{ "type": "text", "text": e }], "isError": true }),
+                ),
+                Err((code, message)) => error_response(id, code, message),
+            }
+        }
+        // Plain JSON-RPC: tools are callable directly by name
+        tool => match call_tool(tool, &params, access).await {
+            Ok(Ok(value)) => success_response(id, value),
+            Ok(Err(e)) => error_response(id, SERVER_ERROR, e),
+            Err((code, message)) => error_response(id, code, message),
+        },
+    };
+    Some(response)
+}
+
+/// A JSON-RPC 2.0 request object, or why the line isn't one
+fn parse_request(line: &str) -> Result<Value, (i64, String)> {
+    let request: Value = serde_json::from_str(line).map_err(|e| (PARSE_ERROR, e.to_string()))?;
+    let is_request = request.get("jsonrpc").and_then(Value::as_str) == Some("2.0")
+        && request.get("method").and_then(Value::as_str).is_some();
+    if !is_request {
+        return Err((INVALID_REQUEST, "Not a JSON-RPC 2.0 request".to_string()));
+    }
+    Ok(request)
+}
+
+/// Whether `request` is `authenticate` with the expected token
+fn is_authenticated(request: &Value, token: &str) -> bool {
+    request.get("method").and_then(Value::as_str) == Some("authenticate")
+        && request.pointer("/params/token").and_then(Value::as_str) == Some(token)
+}
+
+async fn write_message<W: AsyncWrite + Unpin>(writer: &mut W, message: &Value) -> std::io::Result<()> {
+    writer.write_all(message.to_string().as_bytes()).await?;
+    writer.write_all(b"\n").await?;
+    writer.flush().await
+}
+
+/// Serve newline-delimited JSON-RPC messages until the reader closes.
+/// Over stdin/stdout this is the MCP stdio transport. When `access` has a token, the first
+/// message must be `authenticate` with it. The connection is closed on the first line that
+/// isn't a JSON-RPC request, so other protocols (e.g. a browser's HTTP request) get nowhere.
+pub async fn serve_lines<R, W>(reader: R, mut writer: W, access: &RpcAccess) -> std::io::Result<()>
+where
+    R: AsyncBufRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut lines = reader.lines();
+    let mut authenticated = access.token.is_none();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let request = match parse_request(&line) {
+            Ok(request) => request,
+            Err((code, message)) => {
+                log::warn!("⚠️ Closing RPC connection after an invalid message");
+                return write_message(&mut writer, &error_response(Value::Null, code, message)).await;
+            }
+        };
+
+        if !authenticated {
+            let id = request.get("id").cloned().unwrap_or(Value::Null);
+            if !access.token.as_deref().is_some_and(|token| is_authenticated(&request, token)) {
+                log::warn!("⚠️ Closing unauthenticated RPC connection");
+                let message = "Authenticate first with the token from the rpc-token file".to_string();
+                return write_message(&mut writer, &error_response(id, INVALID_REQUEST, message)).await;
+            }
+            authenticated = true;
+            write_message(&mut writer, &success_response(id, json!({ "authenticated": true }))).await?;
+            continue;
+        }
+
+        if let Some(response) = handle_request(request, access).await {
+            write_message(&mut writer, &response).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Write a fresh token for this launch, readable by the current user only
+fn write_rpc_token() -> std::io::Result<(PathBuf, String)> {
+    let dir = app_data_dir().unwrap_or_else(get_temp_dir_path);
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(RPC_TOKEN_FILE_NAME);
+    let token = uuid::Uuid::new_v4().simple().to_string();
+
+    // Recreate the file so the mode below applies even if an older one was readable
+    if let Err(e) = std::fs::remove_file(&path) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            return Err(e);
+        }
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(&path)?.write_all(token.as_bytes())?;
+    Ok((path, token))
+}
+
+/// Line-delimited JSON-RPC on 127.0.0.1 only; every connection is served independently.
+pub async fn serve_tcp(port: u16) -> std::io::Result<()> {
+    let (token_path, token) = write_rpc_token()?;
+    let access = RpcAccess::from_settings(Some(token));
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", port)).await?;
+    log::info!(
+        "🛰️ Serving JSON-RPC on {} (token in {}, writes {})",
+        listener.local_addr()?,
+        token_path.display(),
+        if access.allow_writes { "allowed" } else { "disabled" }
+    );
+
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        log::info!("🛰️ RPC client connected: {}", peer);
+        let access = access.clone();
+        tokio::spawn(async move {
+            let (read, write) = stream.into_split();
+            if let Err(e) = serve_lines(BufReader::new(read), write, &access).await {
+                log::warn!("⚠️ RPC connection {} closed with error: {}", peer, e);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn access(db_root: &Path, allow_writes: bool) -> RpcAccess {
+        RpcAccess {
+            token: None,
+            db_root: db_root.to_path_buf(),
+            allow_writes,
+        }
+    }
+
+    async fn serve(input: &[Value], access: &RpcAccess) -> Vec<Value> {
+        let input: String = input.iter().map(|r| format!("{}\n", r)).collect();
+        let mut output = Vec::new();
+        serve_lines(BufReader::new(input.as_bytes()), &mut output, access).await.unwrap();
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    async fn create_notes_db(temp_dir: &TempDir) -> String {
+        let db_path = temp_dir.path().join("rpc.db");
+        let db = db_path.to_string_lossy().to_string();
+        let pool = SqlitePool::connect(&format!("sqlite:{}?mode=rwc", db)).await.unwrap();
+        sqlx::query("CREATE TABLE notes (id INTEGER PRIMARY KEY, body TEXT)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool.close().await;
+        db
+    }
+
+    #[tokio::test]
+    async fn test_handle_request_protocol() {
+        let access = access(&std::env::temp_dir(), false);
+        let init = handle_request(json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize" }), &access)
+            .await
+            .unwrap();
+        assert_eq!(init["result"]["serverInfo"]["name"], "flippio");
+
+        let tools = handle_request(json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }), &access)
+            .await
+            .unwrap();
+        assert_eq!(tools["result"]["tools"].as_array().unwrap().len(), TOOLS.len());
+
+        let unknown = handle_request(json!({ "jsonrpc": "2.0", "id": 3, "method": "drop_everything" }), &access)
+            .await
+            .unwrap();
+        assert_eq!(unknown["error"]["code"], METHOD_NOT_FOUND);
+
+        let missing = handle_request(json!({ "jsonrpc": "2.0", "id": 4, "method": "run_query", "params": {} }), &access)
+            .await
+            .unwrap();
+        assert_eq!(missing["error"]["code"], INVALID_PARAMS);
+
+        assert!(handle_request(json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }), &access)
+            .await
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_serve_lines_runs_queries() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = create_notes_db(&temp_dir).await;
+
+        let requests = [
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "run_query",
+                    "params": { "dbPath": db, "query": "INSERT INTO notes (body) VALUES ('hi')" } }),
+            json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/call",
+                    "params": { "name": "list_tables", "arguments": { "dbPath": db } } }),
+            json!({ "jsonrpc": "2.0", "id": 3, "method": "run_query",
+                    "params": { "dbPath": db, "query": "SELECT body FROM notes" } }),
+        ];
+        let responses = serve(&requests, &access(temp_dir.path(), true)).await;
+        assert_eq!(responses[0]["result"]["rowsAffected"], 1);
+        assert_eq!(responses[1]["result"]["isError"], false);
+        assert!(responses[1]["result"]["content"][0]["text"].as_str().unwrap().contains("notes"));
+        assert_eq!(responses[2]["result"]["rows"][0]["body"], "hi");
+    }
+
+    #[tokio::test]
+    async fn test_serve_lines_enforces_access() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = create_notes_db(&temp_dir).await;
+        let mut locked = access(temp_dir.path(), false);
+        locked.token = Some("secret".to_string());
+
+        // No token: the connection is closed after the first message
+        let insert = json!({ "jsonrpc": "2.0", "id": 1, "method": "run_query",
+                             "params": { "dbPath": db, "query": "INSERT INTO notes (body) VALUES ('hi')" } });
+        let responses = serve(&[insert.clone(), insert.clone()], &locked).await;
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0]["error"]["code"], INVALID_REQUEST);
+
+        // Authenticated but read-only, and stacked writes fail on the read-only connection
+        let auth = json!({ "jsonrpc": "2.0", "id": 0, "method": "authenticate", "params": { "token": "secret" } });
+        let stacked = json!({ "jsonrpc": "2.0", "id": 2, "method": "run_query",
+                              "params": { "dbPath": db, "query": "SELECT 1; DELETE FROM notes" } });
+        let responses = serve(&[auth.clone(), insert.clone(), stacked], &locked).await;
+        assert_eq!(responses[0]["result"]["authenticated"], true);
+        assert!(responses[1]["error"]["message"].as_str().unwrap().contains("read-only"));
+        assert!(responses[2]["error"].is_object());
+
+        // Databases outside the root are refused
+        let outside = TempDir::new().unwrap();
+        let elsewhere = access(&outside.path().join("nothing-here"), true);
+        let responses = serve(&[insert], &elsewhere).await;
+        assert!(responses[0]["error"]["message"].as_str().unwrap().contains("Only databases under"));
+
+        // An HTTP request line closes the connection before the body is read
+        let input = format!("POST / HTTP/1.1\r\n\r\n{}\n", auth);
+        let mut output = Vec::new();
+        serve_lines(BufReader::new(input.as_bytes()), &mut output, &locked).await.unwrap();
+        assert_eq!(String::from_utf8(output).unwrap().lines().count(), 1);
+    }
+}
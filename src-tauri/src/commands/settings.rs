@@ -0,0 +1,448 @@
+// Settings commands module
+// Application preferences kept in one JSON document in the app data directory
+
+use crate::commands::database::{ConnectionConfig, DbResponse};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{LazyLock, RwLock};
+use std::time::Duration;
+use tauri::Manager;
+
+const SETTINGS_FILE_NAME: &str = "settings.json";
+
+static SETTINGS_MANAGER: LazyLock<SettingsManager> = LazyLock::new(SettingsManager::default);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ConnectionSettings {
+    // Keep a pool per database open between commands instead of reconnecting every time,
+    // for up to `max_connections` databases until unused for `connection_ttl_seconds`
+    pub cache_enabled: bool,
+    pub max_connections: usize,
+    pub connection_ttl_seconds: u64,
+    // How often expired pools are closed; takes effect on restart
+    pub cleanup_interval_seconds: u64,
+    // Reuse identical SELECT results while the database file is unchanged
+    pub result_cache_enabled: bool,
+    pub result_cache_ttl_seconds: u64,
+    // For multi-GB databases: decode table rows as they stream in, and memory-map up to this
+    // many bytes of the file (0 disables mmap; ignored on 32-bit platforms)
+    pub streaming_reads: bool,
+    pub mmap_size_bytes: u64,
+    // Serve table reads and SELECTs from a separate read-only pool per database,
+    // so long reads don't hold up writes during bulk edits
+    pub read_replicas: bool,
+    // Compiled statements kept per connection; 0 disables statement caching
+    pub statement_cache_capacity: usize,
+}
+
+impl Default for ConnectionSettings {
+    fn default() -> Self {
+        Self {
+            cache_enabled: false,
+            max_connections: 10,
+            connection_ttl_seconds: 300,
+            cleanup_interval_seconds: 60,
+            result_cache_enabled: false,
+            result_cache_ttl_seconds: 10,
+            streaming_reads: false,
+            mmap_size_bytes: 0,
+            read_replicas: false,
+            statement_cache_capacity: 256,
+        }
+    }
+}
+
+impl ConnectionSettings {
+    pub fn to_config(&self) -> ConnectionConfig {
+        ConnectionConfig {
+            max_connections: self.max_connections,
+            connection_ttl: Duration::from_secs(self.connection_ttl_seconds),
+            cleanup_interval: Duration::from_secs(self.cleanup_interval_seconds),
+            cache_disabled: !self.cache_enabled,
+            read_replicas: self.read_replicas,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TempDirSettings {
+    // Evict least recently used pulls above this size
+    pub max_bytes: u64,
+    // Pulled files untouched for longer than this are cleaned before a pull
+    pub max_file_age_seconds: u64,
+    // Keep pulls in the app data directory instead of the shared OS temp directory
+    pub private_storage: bool,
+    // Overwrite pulled files with zeros before cleanup, eviction or a force clean unlinks them
+    pub secure_wipe: bool,
+}
+
+impl Default for TempDirSettings {
+    fn default() -> Self {
+        Self {
+            max_bytes: crate::commands::device::temp_storage::DEFAULT_TEMP_QUOTA_BYTES,
+            max_file_age_seconds: 3600,
+            private_storage: false,
+            secure_wipe: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExportSettings {
+    pub default_format: String,
+}
+
+impl Default for ExportSettings {
+    fn default() -> Self {
+        Self {
+            default_format: "csv".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UpdateSettings {
+    // "stable" or "beta"; beta also offers prerelease builds
+    pub channel: String,
+}
+
+impl Default for UpdateSettings {
+    fn default() -> Self {
+        Self {
+            channel: "stable".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CrashReportSettings {
+    // Reports are always written locally; uploading needs explicit consent
+    pub upload_consent: bool,
+    pub upload_url: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TelemetrySettings {
+    // Count IPC commands by name; nothing is recorded while off
+    pub enabled: bool,
+    pub upload_url: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LoggingSettings {
+    // env_logger style filter, e.g. "info,flippio::commands::device=debug"
+    pub filter: String,
+    // Developer switch: log queries and values without redaction
+    pub full_logging: bool,
+}
+
+impl Default for LoggingSettings {
+    fn default() -> Self {
+        Self {
+            // Matches the log plugin's default of passing everything through
+            filter: "trace".to_string(),
+            full_logging: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SafetySettings {
+    // Block DROP, DELETE without WHERE, clearing tables and pushes until confirmed per session
+    pub safe_mode: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PushHook {
+    pub name: String,
+    // Run through `sh -c` (`cmd /C` on Windows) with FLIPPIO_* variables describing the push
+    pub command: String,
+    pub enabled: bool,
+    pub timeout_seconds: u64,
+}
+
+impl Default for PushHook {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            command: String::new(),
+            enabled: true,
+            timeout_seconds: 60,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PushHookSettings {
+    pub before_push: Vec<PushHook>,
+    pub after_push: Vec<PushHook>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RpcSettings {
+    // Serve JSON-RPC on 127.0.0.1 while the app runs; takes effect on restart
+    pub enabled: bool,
+    pub port: u16,
+    // Let RPC clients modify databases; queries run on read-only connections otherwise
+    pub allow_writes: bool,
+}
+
+impl Default for RpcSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 7453,
+            allow_writes: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppSettings {
+    pub connection: ConnectionSettings,
+    pub temp_dir: TempDirSettings,
+    pub export: ExportSettings,
+    // Explicit executable paths keyed by tool name (adb, xcrun, afcclient, ...)
+    pub tool_paths: BTreeMap<String, String>,
+    pub logging: LoggingSettings,
+    pub rpc: RpcSettings,
+    pub hooks: PushHookSettings,
+    pub safety: SafetySettings,
+    pub updates: UpdateSettings,
+    pub crash_reports: CrashReportSettings,
+    pub telemetry: TelemetrySettings,
+}
+
+impl AppSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.connection.max_connections == 0 {
+            return Err("connection.maxConnections must be at least 1".to_string());
+        }
+        if self.rpc.port == 0 {
+            return Err("rpc.port must be between 1 and 65535".to_string());
+        }
+        if self.temp_dir.max_bytes == 0 {
+            return Err("tempDir.maxBytes must be greater than 0".to_string());
+        }
+        let hooks = self.hooks.before_push.iter().chain(&self.hooks.after_push);
+        if let Some(hook) = hooks.into_iter().find(|hook| hook.command.trim().is_empty()) {
+            return Err(format!("Hook '{}' has no command", hook.name));
+        }
+        crate::commands::database::ExportFormat::parse(&self.export.default_format)?;
+        crate::commands::logging::LogFilter::parse(&self.logging.filter)?;
+        crate::commands::updater::UpdateChannel::parse(&self.updates.channel)?;
+        validate_upload_url("crashReports.uploadUrl", &self.crash_reports.upload_url)?;
+        validate_upload_url("telemetry.uploadUrl", &self.telemetry.upload_url)?;
+        Ok(())
+    }
+}
+
+fn validate_upload_url(field: &str, url: &str) -> Result<(), String> {
+    let url = url.trim();
+    if url.is_empty() || url.starts_with("https://") {
+        Ok(())
+    } else {
+        Err(format!("{} must be an https:// URL", field))
+    }
+}
+
+/// Recursively merge `patch` into `target`; objects merge key by key, everything else replaces.
+fn merge_json(target: &mut serde_json::Value, patch: serde_json::Value) {
+    match (target, patch) {
+        (serde_json::Value::Object(target), serde_json::Value::Object(patch)) => {
+            for (key, value) in patch {
+                merge_json(target.entry(key).or_insert(serde_json::Value::Null), value);
+            }
+        }
+        (target, patch) => *target = patch,
+    }
+}
+
+#[derive(Default)]
+pub struct SettingsManager {
+    path: RwLock<Option<PathBuf>>,
+    settings: RwLock<AppSettings>,
+}
+
+impl SettingsManager {
+    /// Load settings from `path`, falling back to defaults for missing or invalid files.
+    pub fn load(&self, path: PathBuf) {
+        let loaded = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| match serde_json::from_str::<AppSettings>(&content) {
+                Ok(settings) => Some(settings),
+                Err(e) => {
+                    log::warn!("⚠️ Ignoring unreadable settings file {}: {}", path.display(), e);
+                    None
+                }
+            })
+            .unwrap_or_default();
+
+        *self.settings.write().unwrap_or_else(|e| e.into_inner()) = loaded;
+        *self.path.write().unwrap_or_else(|e| e.into_inner()) = Some(path);
+    }
+
+    /// Directory holding the settings file, i.e. the app data directory once loaded
+    pub fn data_dir(&self) -> Option<PathBuf> {
+        let path = self.path.read().unwrap_or_else(|e| e.into_inner());
+        path.as_ref().and_then(|path| path.parent()).map(PathBuf::from)
+    }
+
+    pub fn get(&self) -> AppSettings {
+        self.settings.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Apply a partial update, validate and persist it. Returns the new settings.
+    pub fn update(&self, patch: serde_json::Value) -> Result<AppSettings, String> {
+        let mut settings = self.settings.write().unwrap_or_else(|e| e.into_inner());
+
+        let mut merged = serde_json::to_value(&*settings).map_err(|e| e.to_string())?;
+        merge_json(&mut merged, patch);
+        let updated: AppSettings =
+            serde_json::from_value(merged).map_err(|e| format!("Invalid settings: {}", e))?;
+        updated.validate()?;
+
+        if let Some(path) = self.path.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+            let json = serde_json::to_string_pretty(&updated).map_err(|e| e.to_string())?;
+            std::fs::write(path, json).map_err(|e| format!("Failed to save settings: {}", e))?;
+        }
+
+        *settings = updated.clone();
+        Ok(updated)
+    }
+}
+
+/// Current settings (defaults until `init_settings` has run)
+pub fn current_settings() -> AppSettings {
+    SETTINGS_MANAGER.get()
+}
+
+/// App data directory (`None` until `init_settings` has run)
+pub fn app_data_dir() -> Option<PathBuf> {
+    SETTINGS_MANAGER.data_dir()
+}
+
+/// Update settings and push the changes to the subsystems that cache them.
+pub fn update_settings(patch: serde_json::Value) -> Result<AppSettings, String> {
+    let previous = current_settings();
+    let settings = SETTINGS_MANAGER.update(patch)?;
+    crate::commands::logging::apply_logging_settings(&settings.logging);
+    crate::commands::telemetry::apply_telemetry_settings(&settings.telemetry);
+    if previous.temp_dir.private_storage != settings.temp_dir.private_storage {
+        crate::commands::device::helpers::relocate_temp_root(
+            previous.temp_dir.private_storage,
+            settings.temp_dir.private_storage,
+        );
+    }
+    Ok(settings)
+}
+
+/// Load settings from the app data directory; run once on startup before other subsystems.
+pub fn init_settings(app_handle: &tauri::AppHandle) {
+    match app_handle.path().app_data_dir() {
+        Ok(data_dir) => {
+            if let Err(e) = std::fs::create_dir_all(&data_dir) {
+                log::warn!("⚠️ Failed to create app data directory: {}", e);
+            }
+            SETTINGS_MANAGER.load(data_dir.join(SETTINGS_FILE_NAME));
+        }
+        Err(e) => log::warn!("⚠️ Using default settings, no app data directory: {}", e),
+    }
+    crate::commands::logging::apply_logging_settings(&current_settings().logging);
+    crate::commands::telemetry::apply_telemetry_settings(&current_settings().telemetry);
+}
+
+#[tauri::command]
+pub async fn settings_get() -> Result<DbResponse<AppSettings>, String> {
+    Ok(DbResponse {
+        success: true,
+        data: Some(current_settings()),
+        error: None,
+    })
+}
+
+/// Update any subset of settings, e.g. `{ "export": { "defaultFormat": "json" } }`
+#[tauri::command]
+pub async fn settings_set(settings: serde_json::Value) -> Result<DbResponse<AppSettings>, String> {
+    match update_settings(settings) {
+        Ok(settings) => {
+            log::info!("🔧 Settings updated");
+            Ok(DbResponse {
+                success: true,
+                data: Some(settings),
+                error: None,
+            })
+        }
+        Err(e) => {
+            log::error!("❌ Failed to update settings: {}", e);
+            Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_settings_manager_merges_and_persists() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(SETTINGS_FILE_NAME);
+        let manager = SettingsManager::default();
+        manager.load(path.clone());
+        assert_eq!(manager.get(), AppSettings::default());
+
+        let updated = manager
+            .update(json!({
+                "export": { "defaultFormat": "json" },
+                "toolPaths": { "adb": "/opt/android/platform-tools/adb" }
+            }))
+            .unwrap();
+        assert_eq!(updated.export.default_format, "json");
+        assert_eq!(updated.temp_dir, TempDirSettings::default());
+
+        let reloaded = SettingsManager::default();
+        reloaded.load(path);
+        assert_eq!(reloaded.get(), updated);
+    }
+
+    #[test]
+    fn test_settings_manager_rejects_invalid_values() {
+        let manager = SettingsManager::default();
+
+        assert!(manager.update(json!({ "export": { "defaultFormat": "pdf" } })).is_err());
+        assert!(manager.update(json!({ "tempDir": { "maxBytes": "lots" } })).is_err());
+        assert!(manager.update(json!({ "logging": { "filter": "chatty" } })).is_err());
+        assert!(manager.update(json!({ "updates": { "channel": "nightly" } })).is_err());
+        assert!(manager.update(json!({ "crashReports": { "uploadUrl": "http://example.com" } })).is_err());
+        assert_eq!(manager.get(), AppSettings::default());
+    }
+
+    #[test]
+    fn test_partial_settings_file_uses_defaults() {
+        let settings: AppSettings = serde_json::from_str(r#"{ "rpc": { "port": 8000 } }"#).unwrap();
+
+        assert_eq!(settings.rpc.port, 8000);
+        assert!(!settings.rpc.allow_writes);
+        assert_eq!(settings.connection, ConnectionSettings::default());
+    }
+}
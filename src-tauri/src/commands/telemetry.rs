@@ -0,0 +1,240 @@
+//! Anonymous usage telemetry, strictly opt-in
+//!
+//! With `telemetry.enabled` on, every IPC command invocation bumps a counter for the command
+//! name; arguments and results are never looked at. Counts are kept in `telemetry.json` in the
+//! app data directory, can be viewed and purged at any time, and are only sent anywhere by
+//! `telemetry_upload`.
+
+use crate::commands::database::DbResponse;
+use crate::commands::settings::TelemetrySettings;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex, RwLock};
+
+const TELEMETRY_FILE_NAME: &str = "telemetry.json";
+// Counts are written to disk every this many invocations, and on exit
+const PERSIST_EVERY: u64 = 50;
+const UPLOAD_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static UNSAVED: AtomicU64 = AtomicU64::new(0);
+static USAGE: LazyLock<UsageStore> = LazyLock::new(UsageStore::default);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageReport {
+    // When counting started, or the last upload/purge
+    pub since: String,
+    pub app_version: String,
+    pub os: String,
+    pub commands: BTreeMap<String, u64>,
+}
+
+impl UsageReport {
+    fn empty() -> Self {
+        UsageReport {
+            since: chrono::Utc::now().to_rfc3339(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            commands: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct UsageStore {
+    path: RwLock<Option<PathBuf>>,
+    report: Mutex<Option<UsageReport>>,
+}
+
+impl UsageStore {
+    fn load(&self, path: PathBuf) {
+        let report = std::fs::read(&path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<UsageReport>(&bytes).ok());
+        *self.report.lock().unwrap_or_else(|e| e.into_inner()) = report;
+        *self.path.write().unwrap_or_else(|e| e.into_inner()) = Some(path);
+    }
+
+    fn record(&self, command: &str) {
+        let mut report = self.report.lock().unwrap_or_else(|e| e.into_inner());
+        let report = report.get_or_insert_with(UsageReport::empty);
+        *report.commands.entry(command.to_string()).or_insert(0) += 1;
+    }
+
+    fn snapshot(&self) -> UsageReport {
+        self.report
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+            .unwrap_or_else(UsageReport::empty)
+    }
+
+    fn reset(&self) {
+        *self.report.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        if let Some(path) = self.path.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+            if let Err(e) = std::fs::remove_file(path) {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    log::warn!("⚠️ Failed to remove usage data: {}", e);
+                }
+            }
+        }
+    }
+
+    fn persist(&self) {
+        let Some(path) = self.path.read().unwrap_or_else(|e| e.into_inner()).clone() else {
+            return;
+        };
+        let Some(report) = self.report.lock().unwrap_or_else(|e| e.into_inner()).clone() else {
+            return;
+        };
+        let result = serde_json::to_vec_pretty(&report)
+            .map_err(std::io::Error::other)
+            .and_then(|json| std::fs::write(&path, json));
+        if let Err(e) = result {
+            log::warn!("⚠️ Failed to write usage data: {}", e);
+        }
+    }
+}
+
+/// Apply the telemetry switch; called on startup and whenever settings change.
+pub fn apply_telemetry_settings(settings: &TelemetrySettings) {
+    ENABLED.store(settings.enabled, Ordering::Relaxed);
+}
+
+pub fn init_telemetry() {
+    if let Some(data_dir) = crate::commands::settings::app_data_dir() {
+        USAGE.load(data_dir.join(TELEMETRY_FILE_NAME));
+    }
+}
+
+/// Count one invocation of `command` if the user opted in.
+pub fn record_command(command: &str) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    USAGE.record(command);
+    if UNSAVED.fetch_add(1, Ordering::Relaxed) + 1 >= PERSIST_EVERY {
+        UNSAVED.store(0, Ordering::Relaxed);
+        USAGE.persist();
+    }
+}
+
+/// Write pending counts; called when the app exits.
+pub fn flush_telemetry() {
+    USAGE.persist();
+}
+
+/// Wrap the IPC handler so each invoked command is counted before it runs.
+pub fn with_usage_counts<R: tauri::Runtime>(
+    handler: impl Fn(tauri::ipc::Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(tauri::ipc::Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke: tauri::ipc::Invoke<R>| {
+        record_command(invoke.message.command());
+        handler(invoke)
+    }
+}
+
+/// The usage counts collected so far, exactly as they would be uploaded
+#[tauri::command]
+pub async fn telemetry_get_usage() -> Result<DbResponse<UsageReport>, String> {
+    Ok(DbResponse {
+        success: true,
+        data: Some(USAGE.snapshot()),
+        error: None,
+    })
+}
+
+#[tauri::command]
+pub async fn telemetry_purge() -> Result<DbResponse<bool>, String> {
+    USAGE.reset();
+    log::info!("🧹 Usage data purged");
+    Ok(DbResponse {
+        success: true,
+        data: Some(true),
+        error: None,
+    })
+}
+
+/// Send the counts to the configured endpoint, then start counting afresh
+#[tauri::command]
+pub async fn telemetry_upload() -> Result<DbResponse<usize>, String> {
+    let result = async {
+        let settings = crate::commands::settings::current_settings().telemetry;
+        if !settings.enabled {
+            return Err("Usage telemetry is off; enable it in settings first".to_string());
+        }
+        if settings.upload_url.trim().is_empty() {
+            return Err("No telemetry upload URL is configured".to_string());
+        }
+
+        let report = USAGE.snapshot();
+        if report.commands.is_empty() {
+            return Ok(0);
+        }
+        let body = serde_json::to_vec(&report).map_err(|e| e.to_string())?;
+        reqwest::Client::new()
+            .post(settings.upload_url.trim())
+            .timeout(UPLOAD_TIMEOUT)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| format!("Failed to upload usage data: {}", e))?;
+
+        USAGE.reset();
+        Ok(report.commands.len())
+    }
+    .await;
+
+    match result {
+        Ok(commands) => {
+            log::info!("📤 Uploaded usage counts for {} commands", commands);
+            Ok(DbResponse {
+                success: true,
+                data: Some(commands),
+                error: None,
+            })
+        }
+        Err(e) => {
+            log::error!("❌ {}", e);
+            Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_usage_store_counts_and_persists() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(TELEMETRY_FILE_NAME);
+
+        let store = UsageStore::default();
+        store.load(path.clone());
+        store.record("adb_get_devices");
+        store.record("adb_get_devices");
+        store.record("db_get_tables");
+        store.persist();
+
+        let reloaded = UsageStore::default();
+        reloaded.load(path.clone());
+        let report = reloaded.snapshot();
+        assert_eq!(report.commands.get("adb_get_devices"), Some(&2));
+        assert_eq!(report.commands.get("db_get_tables"), Some(&1));
+
+        reloaded.reset();
+        assert!(reloaded.snapshot().commands.is_empty());
+        assert!(!path.exists());
+    }
+}
@@ -3,9 +3,17 @@
 
 use serde::{Deserialize, Serialize};
 
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+use std::sync::Mutex;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+use tauri::Emitter;
 #[cfg(not(any(target_os = "android", target_os = "ios")))]
 use tauri_plugin_updater::UpdaterExt;
 
+use crate::commands::common::OperationOutcome;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+use crate::commands::common::{cancelled, OperationHandle, OperationKind, OPERATIONS, OPERATION_CANCELLED};
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpdateInfo {
     pub available: bool,
@@ -21,6 +29,246 @@ pub struct UpdateResponse {
     pub error: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateChangelog {
+    pub current_version: String,
+    pub version: String,
+    pub date: Option<String>,
+    // Markdown release notes from the update manifest
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChangelogResponse {
+    pub success: bool,
+    pub data: Option<UpdateChangelog>,
+    pub error: Option<String>,
+}
+
+impl OperationOutcome for UpdateResponse {
+    fn failure(&self) -> Option<String> {
+        (!self.success).then(|| self.error.clone().unwrap_or_else(|| "Unknown error".to_string()))
+    }
+
+    fn cancelled(message: String) -> Self {
+        update_failure(message)
+    }
+}
+
+fn update_failure(error: String) -> UpdateResponse {
+    UpdateResponse {
+        success: false,
+        data: None,
+        error: Some(error),
+    }
+}
+
+pub const UPDATE_PROGRESS_EVENT: &str = "update-download-progress";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateDownloadProgress {
+    pub downloaded: u64,
+    // Missing when the server doesn't send a content length
+    pub total: Option<u64>,
+}
+
+// Operation id of the download in flight, for `cancel_update_download`
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+static ACTIVE_DOWNLOAD: Mutex<Option<String>> = Mutex::new(None);
+
+// Update downloaded with `install_on_restart`, installed when the app exits
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+static STAGED_UPDATE: Mutex<Option<(tauri_plugin_updater::Update, Vec<u8>)>> = Mutex::new(None);
+
+// The stable channel uses the endpoint in tauri.conf.json; prereleases publish their manifest
+// to the rolling `beta` release
+const BETA_UPDATE_ENDPOINT: &str = "https://github.com/groot007/flippio/releases/download/beta/latest.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateChannel {
+    Stable,
+    Beta,
+}
+
+impl UpdateChannel {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "stable" => Ok(Self::Stable),
+            "beta" => Ok(Self::Beta),
+            other => Err(format!("Unknown update channel '{}'; expected stable or beta", other)),
+        }
+    }
+
+    fn current() -> Self {
+        Self::parse(&crate::commands::settings::current_settings().updates.channel).unwrap_or(Self::Stable)
+    }
+}
+
+/// Whether a release from the channel's manifest should be offered. Normally only newer
+/// versions are; after switching from beta back to stable, the latest stable build is offered
+/// even when the installed prerelease is newer, so testers can back out.
+fn should_offer_release(channel: UpdateChannel, current_is_prerelease: bool, is_newer: bool, is_same_version: bool) -> bool {
+    is_newer || (channel == UpdateChannel::Stable && current_is_prerelease && !is_same_version)
+}
+
+/// Updater for the channel chosen in settings
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+fn channel_updater(app_handle: &tauri::AppHandle) -> Result<tauri_plugin_updater::Updater, String> {
+    let channel = UpdateChannel::current();
+    log::info!("Using the {:?} update channel", channel);
+
+    let mut builder = app_handle.updater_builder().version_comparator(move |current, release| {
+        should_offer_release(
+            channel,
+            !current.pre.is_empty(),
+            release.version > current,
+            release.version == current,
+        )
+    });
+    if channel == UpdateChannel::Beta {
+        let endpoint = tauri::Url::parse(BETA_UPDATE_ENDPOINT).map_err(|e| e.to_string())?;
+        builder = builder.endpoints(vec![endpoint]).map_err(|e| e.to_string())?;
+    }
+    builder.build().map_err(|e| e.to_string())
+}
+
+/// Release notes as shown in the UI; manifests built without notes carry an empty body
+fn normalize_release_notes(body: Option<&str>) -> Option<String> {
+    body.map(|notes| notes.trim().replace("\r\n", "\n"))
+        .filter(|notes| !notes.is_empty())
+}
+
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UpdateBundleKind {
+    MacApp,
+    Msi,
+    Nsis,
+    AppImage,
+}
+
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+impl UpdateBundleKind {
+    fn from_path(path: &str) -> Option<Self> {
+        let lower = path.to_ascii_lowercase();
+        if lower.ends_with(".app.tar.gz") {
+            Some(Self::MacApp)
+        } else if lower.ends_with(".msi") {
+            Some(Self::Msi)
+        } else if lower.ends_with(".exe") {
+            Some(Self::Nsis)
+        } else if lower.ends_with(".appimage") {
+            Some(Self::AppImage)
+        } else {
+            None
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::MacApp => ".app.tar.gz",
+            Self::Msi => ".msi",
+            Self::Nsis => ".exe",
+            Self::AppImage => ".AppImage",
+        }
+    }
+
+    fn matches_current_platform(self) -> bool {
+        match self {
+            Self::MacApp => cfg!(target_os = "macos"),
+            Self::Msi | Self::Nsis => cfg!(target_os = "windows"),
+            Self::AppImage => cfg!(target_os = "linux"),
+        }
+    }
+}
+
+/// Checks a bundle against the base64-encoded minisign signature the release build writes
+/// next to it, using the same public key as the online updater
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+fn verify_bundle_signature(bundle: &[u8], signature: &str, public_key: &str) -> Result<(), String> {
+    use base64::Engine;
+
+    let decode = |value: &str, what: &str| {
+        base64::engine::general_purpose::STANDARD
+            .decode(value.trim())
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .ok_or_else(|| format!("The {} is not valid base64", what))
+    };
+
+    let public_key = minisign_verify::PublicKey::decode(&decode(public_key, "updater public key")?)
+        .map_err(|e| format!("Invalid updater public key: {}", e))?;
+    let signature = minisign_verify::Signature::decode(&decode(signature, "signature file")?)
+        .map_err(|e| format!("Invalid signature file: {}", e))?;
+    public_key
+        .verify(bundle, &signature, true)
+        .map_err(|e| format!("Signature verification failed: {}", e))
+}
+
+/// Version of a bundle, read from the file name in its signature's trusted comment
+/// (`timestamp:<secs>\tfile:Flippio_1.2.3_x64-setup.exe`). The comment is covered by the
+/// signature, unlike the bundle's own file name. None when the name carries no version.
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+fn signed_bundle_version(signature: &str) -> Option<semver::Version> {
+    use base64::Engine;
+
+    let decoded = base64::engine::general_purpose::STANDARD.decode(signature.trim()).ok()?;
+    let signature = minisign_verify::Signature::decode(&String::from_utf8(decoded).ok()?).ok()?;
+    let file_name = signature
+        .trusted_comment()
+        .split('\t')
+        .find_map(|field| field.strip_prefix("file:"))?;
+    file_name
+        .split('_')
+        .find_map(|part| semver::Version::parse(part).ok())
+}
+
+/// Why installing `bundle_version` over `current` needs the user's confirmation, if it does
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+fn downgrade_warning(bundle_version: Option<&semver::Version>, current: &semver::Version) -> Option<String> {
+    match bundle_version {
+        Some(version) if version > current => None,
+        Some(version) if version == current => Some(format!("Flippio {} is already installed", version)),
+        Some(version) => Some(format!(
+            "This bundle holds Flippio {}, older than the installed {}",
+            version, current
+        )),
+        None => Some("The bundle's version can't be determined from its signature".to_string()),
+    }
+}
+
+/// Copy of the verified bytes that only the current user can read, so what gets installed is
+/// exactly what was verified even if the picked file changes in the meantime. The file is
+/// removed when the returned path is dropped.
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+fn stage_verified_bundle(kind: UpdateBundleKind, bundle: &[u8]) -> Result<tempfile::TempPath, String> {
+    use std::io::Write;
+
+    let mut file = tempfile::Builder::new()
+        .prefix("flippio-update-")
+        .suffix(kind.extension())
+        .tempfile()
+        .map_err(|e| format!("Failed to stage update bundle: {}", e))?;
+    file.write_all(bundle)
+        .and_then(|()| file.as_file().sync_all())
+        .map_err(|e| format!("Failed to stage update bundle: {}", e))?;
+    Ok(file.into_temp_path())
+}
+
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+fn updater_public_key(app_handle: &tauri::AppHandle) -> Result<String, String> {
+    app_handle
+        .config()
+        .plugins
+        .0
+        .get("updater")
+        .and_then(|updater| updater.get("pubkey"))
+        .and_then(|pubkey| pubkey.as_str())
+        .map(str::to_string)
+        .ok_or_else(|| "No updater public key is configured".to_string())
+}
+
 fn is_missing_update_artifact_error(error: &str) -> bool {
     let lower = error.to_lowercase();
     (lower.contains("404") || lower.contains("not found"))
@@ -29,13 +277,24 @@ fn is_missing_update_artifact_error(error: &str) -> bool {
             || lower.contains("release artifacts"))
 }
 
+/// Fetch the update manifest once, for the health check. Returns the pending version, if any.
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+pub(crate) async fn probe_update_endpoint(app_handle: &tauri::AppHandle) -> Result<Option<String>, String> {
+    let updater = channel_updater(app_handle)?;
+    match updater.check().await {
+        Ok(update) => Ok(update.map(|update| update.version)),
+        Err(e) if is_missing_update_artifact_error(&e.to_string()) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
 #[tauri::command]
 pub async fn check_for_updates(app_handle: tauri::AppHandle) -> Result<UpdateResponse, String> {
     #[cfg(not(any(target_os = "android", target_os = "ios")))]
     {
         log::info!("Checking for updates...");
         
-        match app_handle.updater() {
+        match channel_updater(&app_handle) {
             Ok(updater) => {
                 match updater.check().await {
                     Ok(Some(update)) => {
@@ -120,71 +379,499 @@ pub async fn check_for_updates(app_handle: tauri::AppHandle) -> Result<UpdateRes
 }
 
 #[tauri::command]
-pub async fn download_and_install_update(app_handle: tauri::AppHandle) -> Result<UpdateResponse, String> {
+pub async fn download_and_install_update(
+    app_handle: tauri::AppHandle,
+    install_on_restart: Option<bool>,
+) -> Result<UpdateResponse, String> {
     #[cfg(not(any(target_os = "android", target_os = "ios")))]
     {
-        log::info!("Starting update download and installation...");
-        
-        match app_handle.updater() {
-            Ok(updater) => {
-                match updater.check().await {
-                    Ok(Some(update)) => {
-                        log::info!("Downloading update version {}", update.version);
-                        
-                        match update.download_and_install(|chunk_length, content_length| {
-                            log::debug!("Downloaded {} of {:?} bytes", chunk_length, content_length);
-                        }, || {
-                            log::info!("Download finished, installing...");
-                        }).await {
-                            Ok(_) => {
-                                log::info!("Update installed successfully, restarting...");
-                                app_handle.restart();
-                            }
-                            Err(e) => {
-                                log::error!("Failed to download/install update: {}", e);
-                                Ok(UpdateResponse {
-                                    success: false,
-                                    data: None,
-                                    error: Some(format!("Failed to download/install update: {}", e)),
-                                })
-                            }
-                        }
-                    }
-                    Ok(None) => {
-                        Ok(UpdateResponse {
-                            success: false,
-                            data: None,
-                            error: Some("No update available".to_string()),
-                        })
-                    }
-                    Err(e) => {
-                        log::error!("Failed to check for updates: {}", e);
-                        Ok(UpdateResponse {
-                            success: false,
-                            data: None,
-                            error: Some(format!("Failed to check for updates: {}", e)),
-                        })
-                    }
-                }
-            }
+        log::info!("Starting update download...");
+
+        let operation = OPERATIONS.start(OperationKind::Update, "Download update", None);
+        *ACTIVE_DOWNLOAD.lock().unwrap_or_else(|e| e.into_inner()) = Some(operation.id().to_string());
+        let response = operation
+            .run(download_update(&app_handle, &operation, install_on_restart.unwrap_or(false)))
+            .await;
+        *ACTIVE_DOWNLOAD.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        response
+    }
+    
+    #[cfg(any(target_os = "android", target_os = "ios"))]
+    {
+        let _ = (app_handle, install_on_restart);
+        // Mobile platforms don't support auto-updates
+        Ok(UpdateResponse {
+            success: false,
+            data: None,
+            error: Some("Auto-updates not supported on mobile platforms".to_string()),
+        })
+    }
+}
+
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+async fn download_update(
+    app_handle: &tauri::AppHandle,
+    operation: &OperationHandle<'_>,
+    install_on_restart: bool,
+) -> Result<UpdateResponse, String> {
+    let updater = match channel_updater(app_handle) {
+        Ok(updater) => updater,
+        Err(e) => {
+            log::error!("Failed to get updater: {}", e);
+            return Ok(update_failure(format!("Updater not available: {}", e)));
+        }
+    };
+    let update = match updater.check().await {
+        Ok(Some(update)) => update,
+        Ok(None) => return Ok(update_failure("No update available".to_string())),
+        Err(e) => {
+            log::error!("Failed to check for updates: {}", e);
+            return Ok(update_failure(format!("Failed to check for updates: {}", e)));
+        }
+    };
+
+    log::info!("Downloading update version {}", update.version);
+    let mut downloaded: u64 = 0;
+    let download = update.download(
+        |chunk_length, content_length| {
+            downloaded += chunk_length as u64;
+            operation.set_progress(downloaded, content_length.unwrap_or(0));
+            let _ = app_handle.emit(
+                UPDATE_PROGRESS_EVENT,
+                UpdateDownloadProgress {
+                    downloaded,
+                    total: content_length,
+                },
+            );
+        },
+        || {
+            log::info!("Update download finished");
+        },
+    );
+    // Nothing is written until the download completes, so it can stop at any point
+    let result = tokio::select! {
+        result = download => result,
+        _ = cancelled() => return Ok(update_failure(OPERATION_CANCELLED.to_string())),
+    };
+    let bytes = match result {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            log::error!("Failed to download update: {}", e);
+            return Ok(update_failure(format!("Failed to download update: {}", e)));
+        }
+    };
+
+    let info = UpdateInfo {
+        available: true,
+        version: Some(update.version.clone()),
+        notes: update.body.clone(),
+        date: update.date.map(|d| d.to_string()),
+    };
+
+    if install_on_restart {
+        log::info!("📦 Update {} staged, it will be installed when Flippio restarts", update.version);
+        *STAGED_UPDATE.lock().unwrap_or_else(|e| e.into_inner()) = Some((update, bytes));
+        return Ok(UpdateResponse {
+            success: true,
+            data: Some(info),
+            error: None,
+        });
+    }
+
+    match update.install(&bytes) {
+        Ok(_) => {
+            log::info!("Update installed successfully, restarting...");
+            app_handle.restart();
+        }
+        Err(e) => {
+            log::error!("Failed to install update: {}", e);
+            Ok(update_failure(format!("Failed to install update: {}", e)))
+        }
+    }
+}
+
+/// Fetches the release notes of the pending update, so users can see what's new before installing
+#[tauri::command]
+pub async fn get_update_changelog(app_handle: tauri::AppHandle) -> Result<ChangelogResponse, String> {
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    {
+        log::info!("Fetching changelog for the pending update...");
+
+        let updater = match channel_updater(&app_handle) {
+            Ok(updater) => updater,
             Err(e) => {
                 log::error!("Failed to get updater: {}", e);
-                Ok(UpdateResponse {
+                return Ok(ChangelogResponse {
                     success: false,
                     data: None,
                     error: Some(format!("Updater not available: {}", e)),
+                });
+            }
+        };
+
+        match updater.check().await {
+            Ok(Some(update)) => Ok(ChangelogResponse {
+                success: true,
+                data: Some(UpdateChangelog {
+                    current_version: update.current_version.clone(),
+                    version: update.version.clone(),
+                    date: update.date.map(|d| d.to_string()),
+                    notes: normalize_release_notes(update.body.as_deref()),
+                }),
+                error: None,
+            }),
+            Ok(None) => Ok(ChangelogResponse {
+                success: false,
+                data: None,
+                error: Some("No update available".to_string()),
+            }),
+            Err(e) => {
+                log::error!("Failed to fetch update changelog: {}", e);
+                Ok(ChangelogResponse {
+                    success: false,
+                    data: None,
+                    error: Some(format!("Failed to fetch update changelog: {}", e)),
                 })
             }
         }
     }
-    
+
     #[cfg(any(target_os = "android", target_os = "ios"))]
     {
-        // Mobile platforms don't support auto-updates
-        Ok(UpdateResponse {
+        let _ = app_handle;
+        Ok(ChangelogResponse {
             success: false,
             data: None,
             error: Some("Auto-updates not supported on mobile platforms".to_string()),
         })
     }
 }
+
+/// Installs an update bundle picked from disk (via `dialog_select_file`), for machines that
+/// can't reach the update endpoint. The bundle must carry a valid signature, read from
+/// `signature_path` or the `.sig` file next to it. Bundles that aren't newer than the running
+/// app are refused unless `allow_downgrade` confirms them.
+#[tauri::command]
+pub async fn install_update_from_file(
+    app_handle: tauri::AppHandle,
+    bundle_path: String,
+    signature_path: Option<String>,
+    allow_downgrade: Option<bool>,
+) -> Result<UpdateResponse, String> {
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    {
+        log::info!("📦 Installing update from local bundle: {}", bundle_path);
+
+        let Some(kind) = UpdateBundleKind::from_path(&bundle_path) else {
+            return Ok(update_failure(
+                "Unsupported update bundle; expected a .app.tar.gz, .msi, .exe or .AppImage file".to_string(),
+            ));
+        };
+        if !kind.matches_current_platform() {
+            return Ok(update_failure(format!("This update bundle is not meant for {}", std::env::consts::OS)));
+        }
+
+        let bundle = match tokio::fs::read(&bundle_path).await {
+            Ok(bundle) => bundle,
+            Err(e) => return Ok(update_failure(format!("Failed to read update bundle: {}", e))),
+        };
+        let signature_path = signature_path.unwrap_or_else(|| format!("{}.sig", bundle_path));
+        let signature = match tokio::fs::read_to_string(&signature_path).await {
+            Ok(signature) => signature,
+            Err(e) => {
+                return Ok(update_failure(format!(
+                    "Failed to read signature file {}: {}",
+                    signature_path, e
+                )))
+            }
+        };
+
+        let verified = updater_public_key(&app_handle)
+            .and_then(|public_key| verify_bundle_signature(&bundle, &signature, &public_key));
+        if let Err(e) = verified {
+            log::error!("❌ Rejected update bundle {}: {}", bundle_path, e);
+            return Ok(update_failure(e));
+        }
+        log::info!("✅ Update bundle signature verified");
+
+        let bundle_version = signed_bundle_version(&signature);
+        if let Some(warning) = downgrade_warning(bundle_version.as_ref(), &app_handle.package_info().version) {
+            if !allow_downgrade.unwrap_or(false) {
+                log::warn!("⚠️ Not installing {}: {}", bundle_path, warning);
+                return Ok(update_failure(format!("{}. Confirm to install it anyway.", warning)));
+            }
+            log::warn!("⚠️ Installing {} as confirmed: {}", bundle_path, warning);
+        }
+
+        let staged = match stage_verified_bundle(kind, &bundle) {
+            Ok(staged) => staged,
+            Err(e) => return Ok(update_failure(e)),
+        };
+
+        match install_bundle(kind, &staged, &bundle) {
+            Ok(()) => {
+                log::info!("Update installed from local bundle, restarting...");
+                app_handle.restart();
+            }
+            Err(e) => {
+                log::error!("Failed to install update bundle: {}", e);
+                Ok(update_failure(format!("Failed to install update: {}", e)))
+            }
+        }
+    }
+
+    #[cfg(any(target_os = "android", target_os = "ios"))]
+    {
+        let _ = (app_handle, bundle_path, signature_path, allow_downgrade);
+        Ok(update_failure("Auto-updates not supported on mobile platforms".to_string()))
+    }
+}
+
+/// Puts a verified bundle, staged at `bundle_path`, in place of the running app. Windows
+/// installers are launched instead and take over from the exiting app.
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+fn install_bundle(kind: UpdateBundleKind, bundle_path: &std::path::Path, bundle: &[u8]) -> Result<(), String> {
+    let current_exe = std::env::current_exe().map_err(|e| e.to_string())?;
+
+    match kind {
+        UpdateBundleKind::MacApp => {
+            // Flippio.app/Contents/MacOS/flippio
+            let app_dir = current_exe
+                .ancestors()
+                .nth(3)
+                .filter(|dir| dir.extension().is_some_and(|ext| ext == "app"))
+                .ok_or("Flippio is not running from an app bundle")?;
+            let parent = app_dir.parent().ok_or("App bundle has no parent directory")?;
+            let extract_dir = parent.join(".flippio-update");
+            let _ = std::fs::remove_dir_all(&extract_dir);
+            std::fs::create_dir_all(&extract_dir).map_err(|e| e.to_string())?;
+
+            let status = std::process::Command::new("tar")
+                .arg("-xzf")
+                .arg(bundle_path)
+                .arg("-C")
+                .arg(&extract_dir)
+                .status()
+                .map_err(|e| format!("Failed to extract bundle: {}", e))?;
+            if !status.success() {
+                let _ = std::fs::remove_dir_all(&extract_dir);
+                return Err("Failed to extract bundle".to_string());
+            }
+
+            let new_app = std::fs::read_dir(&extract_dir)
+                .map_err(|e| e.to_string())?
+                .flatten()
+                .map(|entry| entry.path())
+                .find(|path| path.extension().is_some_and(|ext| ext == "app"))
+                .ok_or("The bundle does not contain an app")?;
+
+            // Keep the current app until the new one is in place, so a failed swap can be undone
+            let backup = parent.join(".flippio-previous.app");
+            let _ = std::fs::remove_dir_all(&backup);
+            std::fs::rename(app_dir, &backup).map_err(|e| e.to_string())?;
+            if let Err(e) = std::fs::rename(&new_app, app_dir) {
+                let _ = std::fs::rename(&backup, app_dir);
+                let _ = std::fs::remove_dir_all(&extract_dir);
+                return Err(e.to_string());
+            }
+            let _ = std::fs::remove_dir_all(&backup);
+            let _ = std::fs::remove_dir_all(&extract_dir);
+            Ok(())
+        }
+        UpdateBundleKind::Msi | UpdateBundleKind::Nsis => {
+            let mut command = if kind == UpdateBundleKind::Msi {
+                let mut command = std::process::Command::new("msiexec");
+                command.arg("/i").arg(bundle_path).arg("/passive");
+                command
+            } else {
+                let mut command = std::process::Command::new(bundle_path);
+                command.arg("/P").arg("/R");
+                command
+            };
+            command.spawn().map_err(|e| format!("Failed to launch installer: {}", e))?;
+            // The installer replaces the files in use, so get out of its way
+            std::process::exit(0);
+        }
+        UpdateBundleKind::AppImage => {
+            let target = std::env::var_os("APPIMAGE")
+                .map(std::path::PathBuf::from)
+                .ok_or("Flippio is not running from an AppImage")?;
+            let staging = target.with_extension("update");
+            std::fs::write(&staging, bundle).map_err(|e| e.to_string())?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                std::fs::set_permissions(&staging, std::fs::Permissions::from_mode(0o755))
+                    .map_err(|e| e.to_string())?;
+            }
+            std::fs::rename(&staging, &target).map_err(|e| {
+                let _ = std::fs::remove_file(&staging);
+                e.to_string()
+            })
+        }
+    }
+}
+
+/// Cancels the update download started by `download_and_install_update`
+#[tauri::command]
+pub async fn cancel_update_download() -> Result<UpdateResponse, String> {
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    {
+        let active = ACTIVE_DOWNLOAD.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        match active {
+            Some(id) if OPERATIONS.cancel(&id).is_ok() => {
+                log::info!("🛑 Update download cancelled");
+                Ok(UpdateResponse {
+                    success: true,
+                    data: None,
+                    error: None,
+                })
+            }
+            _ => Ok(update_failure("No update download in progress".to_string())),
+        }
+    }
+
+    #[cfg(any(target_os = "android", target_os = "ios"))]
+    {
+        Ok(update_failure("Auto-updates not supported on mobile platforms".to_string()))
+    }
+}
+
+/// Installs an update staged with `install_on_restart` right away and restarts the app
+#[tauri::command]
+pub async fn install_staged_update(app_handle: tauri::AppHandle) -> Result<UpdateResponse, String> {
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    {
+        let staged = STAGED_UPDATE.lock().unwrap_or_else(|e| e.into_inner()).take();
+        let Some((update, bytes)) = staged else {
+            return Ok(update_failure("No downloaded update waiting to be installed".to_string()));
+        };
+
+        match update.install(&bytes) {
+            Ok(_) => {
+                log::info!("Staged update {} installed, restarting...", update.version);
+                app_handle.restart();
+            }
+            Err(e) => {
+                log::error!("Failed to install staged update: {}", e);
+                Ok(update_failure(format!("Failed to install update: {}", e)))
+            }
+        }
+    }
+
+    #[cfg(any(target_os = "android", target_os = "ios"))]
+    {
+        let _ = app_handle;
+        Ok(update_failure("Auto-updates not supported on mobile platforms".to_string()))
+    }
+}
+
+/// Installs a staged update while the app exits, so it is picked up on the next launch
+pub fn install_staged_update_on_exit() {
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    {
+        let staged = STAGED_UPDATE.lock().unwrap_or_else(|e| e.into_inner()).take();
+        if let Some((update, bytes)) = staged {
+            log::info!("📦 Installing staged update {} on exit", update.version);
+            if let Err(e) = update.install(&bytes) {
+                log::error!("Failed to install staged update: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_channel_parse() {
+        assert_eq!(UpdateChannel::parse("stable"), Ok(UpdateChannel::Stable));
+        assert_eq!(UpdateChannel::parse(" Beta "), Ok(UpdateChannel::Beta));
+        assert!(UpdateChannel::parse("nightly").is_err());
+    }
+
+    #[test]
+    fn test_should_offer_release() {
+        // Newer builds are offered on either channel
+        assert!(should_offer_release(UpdateChannel::Beta, false, true, false));
+        assert!(!should_offer_release(UpdateChannel::Beta, true, false, false));
+        // Back on stable, an installed prerelease is replaced by the latest stable build
+        assert!(should_offer_release(UpdateChannel::Stable, true, false, false));
+        assert!(!should_offer_release(UpdateChannel::Stable, false, false, false));
+        assert!(!should_offer_release(UpdateChannel::Stable, true, false, true));
+    }
+
+    #[test]
+    fn test_normalize_release_notes() {
+        assert_eq!(
+            normalize_release_notes(Some("  ## Fixes\r\n- Faster pulls\n")),
+            Some("## Fixes\n- Faster pulls".to_string())
+        );
+        assert_eq!(normalize_release_notes(Some("  \n")), None);
+        assert_eq!(normalize_release_notes(None), None);
+    }
+
+    #[test]
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    fn test_update_bundle_kind_from_path() {
+        assert_eq!(UpdateBundleKind::from_path("/tmp/Flippio.app.tar.gz"), Some(UpdateBundleKind::MacApp));
+        assert_eq!(UpdateBundleKind::from_path("C:\\Flippio_1.2.0_x64_en-US.msi"), Some(UpdateBundleKind::Msi));
+        assert_eq!(UpdateBundleKind::from_path("Flippio_1.2.0_x64-setup.exe"), Some(UpdateBundleKind::Nsis));
+        assert_eq!(UpdateBundleKind::from_path("flippio_1.2.0_amd64.AppImage"), Some(UpdateBundleKind::AppImage));
+        assert_eq!(UpdateBundleKind::from_path("Flippio.app.tar.gz.sig"), None);
+    }
+
+    #[test]
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    fn test_bundle_version_and_downgrade_warning() {
+        use base64::Engine;
+
+        let b64 = |bytes: &[u8]| base64::engine::general_purpose::STANDARD.encode(bytes);
+        let signature = |file: &str| {
+            let raw = [b"ED".as_slice(), &[0u8; 8], &[0u8; 64]].concat();
+            b64(format!(
+                "untrusted comment: signature from tauri secret key\n{}\ntrusted comment: timestamp:1700000000\tfile:{}\n{}\n",
+                b64(&raw),
+                file,
+                b64(&[0u8; 64])
+            )
+            .as_bytes())
+        };
+        let version = |value: &str| semver::Version::parse(value).unwrap();
+
+        assert_eq!(signed_bundle_version(&signature("Flippio_1.4.0_x64-setup.exe")), Some(version("1.4.0")));
+        assert_eq!(signed_bundle_version(&signature("Flippio.app.tar.gz")), None);
+
+        let current = version("1.3.0");
+        assert_eq!(downgrade_warning(Some(&version("1.4.0")), &current), None);
+        assert!(downgrade_warning(Some(&version("1.3.0")), &current).unwrap().contains("already installed"));
+        assert!(downgrade_warning(Some(&version("1.2.9")), &current).unwrap().contains("older"));
+        assert!(downgrade_warning(None, &current).is_some());
+    }
+
+    #[test]
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    fn test_verify_bundle_signature_rejects_invalid_signature() {
+        use base64::Engine;
+
+        let public_key = "dW50cnVzdGVkIGNvbW1lbnQ6IG1pbmlzaWduIHB1YmxpYyBrZXk6IEY3QjdGRTMxQkI4MDdCQzkKUldUSmU0QzdNZjYzOTdYVEFTVEt5K0FGZXlxOEI3N1kzNVhoK2lkeHhPMjFOQUtXcVE1b3lVVFkK";
+        let garbage = base64::engine::general_purpose::STANDARD.encode("not a signature");
+
+        assert!(verify_bundle_signature(b"bundle", &garbage, public_key).is_err());
+        assert!(verify_bundle_signature(b"bundle", "%%%", public_key).is_err());
+    }
+
+    #[test]
+    fn test_update_response_outcome() {
+        let cancelled = UpdateResponse::cancelled("Operation cancelled".to_string());
+        assert_eq!(cancelled.failure(), Some("Operation cancelled".to_string()));
+
+        let staged = UpdateResponse {
+            success: true,
+            data: None,
+            error: None,
+        };
+        assert_eq!(staged.failure(), None);
+    }
+}
@@ -0,0 +1,484 @@
+// Local file workspace
+// Opening SQLite files straight from disk (no device): recents, file watching and
+// read-only snapshots of files that are locked by another application
+
+use crate::commands::database::change_history::generate_custom_file_context_key;
+use crate::commands::database::helpers::copy_database_with_companions;
+use crate::commands::database::DbResponse;
+use rusqlite::{Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, SystemTime};
+use tauri::{Emitter, Manager};
+
+const RECENT_FILES_NAME: &str = "recent_files.json";
+const MAX_RECENT_FILES: usize = 20;
+const SNAPSHOT_DIR_NAME: &str = "flippio_workspace_snapshots";
+const LOCAL_FILE_CHANGED_EVENT: &str = "local-file-changed";
+const WATCH_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+static WATCH_GENERATIONS: LazyLock<Mutex<HashMap<String, u64>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentFile {
+    pub path: String,
+    pub filename: String,
+    pub last_opened: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalWorkspaceFile {
+    /// Path to open: the original file, or a snapshot copy when it is read-only
+    pub path: String,
+    pub original_path: String,
+    pub filename: String,
+    pub read_only: bool,
+    pub locked: bool,
+    pub size: u64,
+    /// Change history context, keyed on the original file so snapshots share history
+    pub context_key: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LocalFileChangedPayload {
+    path: String,
+    size: u64,
+    modified: Option<String>,
+    deleted: bool,
+}
+
+fn filename_of(path: &Path) -> String {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("database")
+        .to_string()
+}
+
+// --- Recent files ---
+
+fn recent_files_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    std::fs::create_dir_all(&data_dir)
+        .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    Ok(data_dir.join(RECENT_FILES_NAME))
+}
+
+pub fn load_recent_files(store_path: &Path) -> Vec<RecentFile> {
+    std::fs::read_to_string(store_path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_recent_files(store_path: &Path, recents: &[RecentFile]) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(recents)
+        .map_err(|e| format!("Failed to serialize recent files: {}", e))?;
+    std::fs::write(store_path, content).map_err(|e| format!("Failed to save recent files: {}", e))
+}
+
+/// Move (or insert) a file to the top of the recents list, keeping it bounded.
+pub fn push_recent_file(recents: &mut Vec<RecentFile>, path: &str) {
+    recents.retain(|recent| recent.path != path);
+    recents.insert(
+        0,
+        RecentFile {
+            path: path.to_string(),
+            filename: filename_of(Path::new(path)),
+            last_opened: chrono::Utc::now().to_rfc3339(),
+        },
+    );
+    recents.truncate(MAX_RECENT_FILES);
+}
+
+// --- Locked / read-only files ---
+
+/// A file is considered locked when another process holds a write lock on it.
+pub fn is_database_locked(path: &Path) -> bool {
+    let connection = match Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_WRITE) {
+        Ok(connection) => connection,
+        Err(_) => return true,
+    };
+
+    // Fail fast instead of waiting for the other writer to finish
+    if connection.busy_timeout(Duration::ZERO).is_err() {
+        return true;
+    }
+
+    match connection.execute_batch("BEGIN IMMEDIATE; ROLLBACK;") {
+        Ok(()) => false,
+        Err(e) => {
+            log::warn!("⚠️ Database {} appears to be locked: {}", path.display(), e);
+            true
+        }
+    }
+}
+
+fn is_read_only_on_disk(path: &Path) -> bool {
+    std::fs::metadata(path)
+        .map(|metadata| metadata.permissions().readonly())
+        .unwrap_or(true)
+}
+
+/// Copy a database (and its WAL/SHM companions) into the snapshot directory.
+pub fn create_read_only_snapshot(source: &Path, snapshot_dir: &Path) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(snapshot_dir)
+        .map_err(|e| format!("Failed to create snapshot directory: {}", e))?;
+
+    let context_hash = generate_custom_file_context_key(&source.to_string_lossy());
+    let safe_hash: String = context_hash
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .take(12)
+        .collect();
+    let snapshot_path = snapshot_dir.join(format!("{}_{}", safe_hash, filename_of(source)));
+
+    copy_database_with_companions(source, &snapshot_path)?;
+    Ok(snapshot_path)
+}
+
+pub fn prepare_local_workspace_file(
+    file_path: &str,
+    force_read_only: bool,
+    snapshot_dir: &Path,
+) -> Result<LocalWorkspaceFile, String> {
+    let original = Path::new(file_path);
+    let metadata =
+        std::fs::metadata(original).map_err(|e| format!("Cannot access {}: {}", file_path, e))?;
+    if !metadata.is_file() {
+        return Err(format!("{} is not a file", file_path));
+    }
+
+    let locked = is_database_locked(original);
+    let read_only = force_read_only || locked || is_read_only_on_disk(original);
+
+    let path = if read_only {
+        let snapshot = create_read_only_snapshot(original, snapshot_dir)?;
+        log::info!("📸 Opened read-only snapshot {} for {}", snapshot.display(), file_path);
+        snapshot.to_string_lossy().to_string()
+    } else {
+        file_path.to_string()
+    };
+
+    Ok(LocalWorkspaceFile {
+        path,
+        original_path: file_path.to_string(),
+        filename: filename_of(original),
+        read_only,
+        locked,
+        size: metadata.len(),
+        context_key: generate_custom_file_context_key(file_path),
+    })
+}
+
+// --- File watching ---
+
+fn file_signature(path: &Path) -> Option<(u64, Option<SystemTime>)> {
+    std::fs::metadata(path)
+        .ok()
+        .map(|metadata| (metadata.len(), metadata.modified().ok()))
+}
+
+fn begin_watch(path: &str) -> u64 {
+    let mut watches = WATCH_GENERATIONS.lock().expect("Watch registry poisoned");
+    let next_generation = watches.get(path).copied().unwrap_or(0) + 1;
+    watches.insert(path.to_string(), next_generation);
+    next_generation
+}
+
+fn is_watch_active(path: &str, generation: u64) -> bool {
+    WATCH_GENERATIONS
+        .lock()
+        .expect("Watch registry poisoned")
+        .get(path)
+        .copied()
+        == Some(generation)
+}
+
+fn stop_watch(path: &str) -> bool {
+    WATCH_GENERATIONS
+        .lock()
+        .expect("Watch registry poisoned")
+        .remove(path)
+        .is_some()
+}
+
+// --- Commands ---
+
+/// Open a local SQLite file as a workspace, snapshotting it when it can't be written safely
+#[tauri::command]
+pub async fn workspace_open_local_file(
+    app_handle: tauri::AppHandle,
+    file_path: String,
+    read_only: Option<bool>,
+) -> Result<DbResponse<LocalWorkspaceFile>, String> {
+    log::info!("📂 Opening local workspace file: {}", file_path);
+
+    let snapshot_dir = match app_handle.path().temp_dir() {
+        Ok(dir) => dir.join(SNAPSHOT_DIR_NAME),
+        Err(e) => {
+            return Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(format!("Failed to get temp directory: {}", e)),
+            })
+        }
+    };
+
+    match prepare_local_workspace_file(&file_path, read_only.unwrap_or(false), &snapshot_dir) {
+        Ok(workspace_file) => {
+            if let Ok(store_path) = recent_files_path(&app_handle) {
+                let mut recents = load_recent_files(&store_path);
+                push_recent_file(&mut recents, &file_path);
+                if let Err(e) = save_recent_files(&store_path, &recents) {
+                    log::warn!("⚠️ {}", e);
+                }
+            }
+
+            Ok(DbResponse {
+                success: true,
+                data: Some(workspace_file),
+                error: None,
+            })
+        }
+        Err(e) => {
+            log::error!("❌ Failed to open local workspace file: {}", e);
+            Ok(DbResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn workspace_get_recent_files(
+    app_handle: tauri::AppHandle,
+) -> Result<DbResponse<Vec<RecentFile>>, String> {
+    match recent_files_path(&app_handle) {
+        Ok(store_path) => {
+            // Drop entries whose file no longer exists
+            let recents: Vec<RecentFile> = load_recent_files(&store_path)
+                .into_iter()
+                .filter(|recent| Path::new(&recent.path).exists())
+                .collect();
+            Ok(DbResponse {
+                success: true,
+                data: Some(recents),
+                error: None,
+            })
+        }
+        Err(e) => Ok(DbResponse {
+            success: false,
+            data: None,
+            error: Some(e),
+        }),
+    }
+}
+
+#[tauri::command]
+pub async fn workspace_remove_recent_file(
+    app_handle: tauri::AppHandle,
+    file_path: Option<String>,
+) -> Result<DbResponse<Vec<RecentFile>>, String> {
+    let result = recent_files_path(&app_handle).and_then(|store_path| {
+        let mut recents = load_recent_files(&store_path);
+        match &file_path {
+            Some(path) => recents.retain(|recent| &recent.path != path),
+            None => recents.clear(),
+        }
+        save_recent_files(&store_path, &recents).map(|_| recents)
+    });
+
+    match result {
+        Ok(recents) => Ok(DbResponse {
+            success: true,
+            data: Some(recents),
+            error: None,
+        }),
+        Err(e) => Ok(DbResponse {
+            success: false,
+            data: None,
+            error: Some(e),
+        }),
+    }
+}
+
+/// Poll a local file and emit `local-file-changed` whenever it is modified externally
+#[tauri::command]
+pub async fn workspace_watch_file(
+    app_handle: tauri::AppHandle,
+    file_path: String,
+) -> Result<DbResponse<bool>, String> {
+    if !Path::new(&file_path).exists() {
+        return Ok(DbResponse {
+            success: false,
+            data: None,
+            error: Some(format!("File does not exist: {}", file_path)),
+        });
+    }
+
+    let generation = begin_watch(&file_path);
+    log::info!("👀 Watching local file: {}", file_path);
+
+    tokio::spawn(async move {
+        let path = PathBuf::from(&file_path);
+        let mut last_signature = file_signature(&path);
+
+        while is_watch_active(&file_path, generation) {
+            tokio::time::sleep(WATCH_POLL_INTERVAL).await;
+            if !is_watch_active(&file_path, generation) {
+                break;
+            }
+
+            let signature = file_signature(&path);
+            if signature == last_signature {
+                continue;
+            }
+
+            let payload = LocalFileChangedPayload {
+                path: file_path.clone(),
+                size: signature.map(|(size, _)| size).unwrap_or(0),
+                modified: signature
+                    .and_then(|(_, modified)| modified)
+                    .map(|modified| chrono::DateTime::<chrono::Utc>::from(modified).to_rfc3339()),
+                deleted: signature.is_none(),
+            };
+            if let Err(e) = app_handle.emit(LOCAL_FILE_CHANGED_EVENT, payload) {
+                log::warn!("⚠️ Failed to emit file change event: {}", e);
+            }
+            last_signature = signature;
+        }
+
+        log::info!("🛑 Stopped watching local file: {}", file_path);
+    });
+
+    Ok(DbResponse {
+        success: true,
+        data: Some(true),
+        error: None,
+    })
+}
+
+#[tauri::command]
+pub async fn workspace_unwatch_file(file_path: String) -> Result<DbResponse<bool>, String> {
+    Ok(DbResponse {
+        success: true,
+        data: Some(stop_watch(&file_path)),
+        error: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn create_database(path: &Path) {
+        let connection = Connection::open(path).unwrap();
+        connection
+            .execute_batch(
+                "CREATE TABLE items (id INTEGER PRIMARY KEY); INSERT INTO items DEFAULT VALUES;",
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_push_recent_file_dedupes_and_bounds() {
+        let mut recents = Vec::new();
+        for i in 0..(MAX_RECENT_FILES + 5) {
+            push_recent_file(&mut recents, &format!("/tmp/db_{}.sqlite", i));
+        }
+        push_recent_file(&mut recents, "/tmp/db_10.sqlite");
+
+        assert_eq!(recents.len(), MAX_RECENT_FILES);
+        assert_eq!(recents[0].path, "/tmp/db_10.sqlite");
+        assert_eq!(recents[0].filename, "db_10.sqlite");
+        assert_eq!(recents.iter().filter(|r| r.path == "/tmp/db_10.sqlite").count(), 1);
+    }
+
+    #[test]
+    fn test_recent_files_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let store_path = temp_dir.path().join(RECENT_FILES_NAME);
+        assert!(load_recent_files(&store_path).is_empty());
+
+        let mut recents = Vec::new();
+        push_recent_file(&mut recents, "/tmp/a.db");
+        save_recent_files(&store_path, &recents).unwrap();
+
+        assert_eq!(load_recent_files(&store_path), recents);
+    }
+
+    #[test]
+    fn test_locked_database_is_opened_as_snapshot() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("locked.db");
+        create_database(&db_path);
+
+        let holder = Connection::open(&db_path).unwrap();
+        holder.execute_batch("BEGIN IMMEDIATE;").unwrap();
+
+        let workspace_file = prepare_local_workspace_file(
+            db_path.to_str().unwrap(),
+            false,
+            &temp_dir.path().join(SNAPSHOT_DIR_NAME),
+        )
+        .unwrap();
+
+        assert!(workspace_file.locked);
+        assert!(workspace_file.read_only);
+        assert_ne!(workspace_file.path, workspace_file.original_path);
+        assert_eq!(
+            workspace_file.context_key,
+            generate_custom_file_context_key(db_path.to_str().unwrap())
+        );
+
+        let snapshot = Connection::open(&workspace_file.path).unwrap();
+        let count: i64 = snapshot
+            .query_row("SELECT COUNT(*) FROM items", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+
+        holder.execute_batch("ROLLBACK;").unwrap();
+    }
+
+    #[test]
+    fn test_unlocked_database_opens_in_place() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("free.db");
+        create_database(&db_path);
+
+        let workspace_file = prepare_local_workspace_file(
+            db_path.to_str().unwrap(),
+            false,
+            &temp_dir.path().join(SNAPSHOT_DIR_NAME),
+        )
+        .unwrap();
+
+        assert!(!workspace_file.locked);
+        assert!(!workspace_file.read_only);
+        assert_eq!(workspace_file.path, db_path.to_str().unwrap());
+    }
+
+    #[test]
+    fn test_watch_registry_generations() {
+        let path = "/tmp/flippio-watch-test.db";
+        let first = begin_watch(path);
+        let second = begin_watch(path);
+
+        assert!(!is_watch_active(path, first));
+        assert!(is_watch_active(path, second));
+        assert!(stop_watch(path));
+        assert!(!is_watch_active(path, second));
+    }
+}
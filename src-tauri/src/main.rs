@@ -4,31 +4,43 @@
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use chrono::SecondsFormat;
+use tauri::Manager;
 use tauri_plugin_log::{RotationStrategy, Target, TargetKind, TimezoneStrategy, WEBVIEW_TARGET};
 
 mod commands;
-use commands::database::{DbPool, DatabaseConnectionManager, ChangeHistoryManager, ConnectionConfig};
+use commands::database::{DbPool, DbConnectionCache, DatabaseConnectionManager, ChangeHistoryManager};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    commands::crash::install_panic_hook();
     let is_embedded_wdio = std::env::var("TAURI_WEBDRIVER_PORT").is_ok();
     // Initialize database connection management
     let db_pool: DbPool = Arc::new(RwLock::new(None)); // Legacy pool for compatibility
-    let connection_manager = DatabaseConnectionManager::with_config(ConnectionConfig::with_cache_disabled());
-    let db_cache = connection_manager.get_cache();
+    // Pools are only kept here when `connection.cacheEnabled` is set
+    let db_cache: DbConnectionCache = Default::default();
     
     // Initialize change history manager (Phase 1)
     let change_history_manager = ChangeHistoryManager::new();
     
     let mut log_plugin = tauri_plugin_log::Builder::new()
         .clear_targets()
+        .filter(commands::logging::is_log_enabled)
         .format(|out, message, record| {
             let timestamp = chrono::Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true);
-            let source = if record.target().starts_with(WEBVIEW_TARGET) {
+            let is_frontend = record.target().starts_with(WEBVIEW_TARGET);
+            let source = if is_frontend {
                 "🖥 frontend"
             } else {
                 "⚙ backend"
             };
+            commands::diagnostics::record_log_entry(
+                &timestamp,
+                record,
+                if is_frontend { "frontend" } else { "backend" },
+            );
+            if record.level() == log::Level::Error {
+                commands::crash::record_error(record.target(), &message.to_string());
+            }
             out.finish(format_args!(
                 "{} [{}] [{}] {}",
                 timestamp,
@@ -62,14 +74,45 @@ pub fn run() {
         .manage(db_pool)
         .manage(db_cache)
         .manage(change_history_manager)
-        .setup(|_app| {
+        .manage(commands::device::DeviceSessionManager::new())
+        .setup(|app| {
+            commands::settings::init_settings(app.handle());
+            commands::journal::init_journal();
+            commands::crash::init_crash_reports();
+            commands::telemetry::init_telemetry();
+            commands::device::ios::quarantine::clear_tool_quarantine_on_startup();
+            commands::common::init_operations(app.handle());
+            commands::device::integrity::init_pull_integrity(app.handle());
+            app.state::<ChangeHistoryManager>().attach_app_handle(app.handle().clone());
+            let settings = commands::settings::current_settings();
+
+            if settings.rpc.enabled {
+                let port = settings.rpc.port;
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = commands::rpc::serve_tcp(port).await {
+                        log::error!("❌ JSON-RPC server on port {} stopped: {}", port, e);
+                    }
+                });
+            }
+
+            // Apply the temp quota and drop orphaned sidecar files left by earlier sessions
+            tauri::async_runtime::spawn_blocking(commands::device::temp_storage::init_temp_storage);
+
             // Start background cleanup task after Tauri runtime is initialized
-            let connection_manager = DatabaseConnectionManager::with_config(ConnectionConfig::with_cache_disabled());
+            let connection_manager = DatabaseConnectionManager::with_cache(
+                app.state::<DbConnectionCache>().inner().clone(),
+                settings.connection.to_config(),
+            );
             tauri::async_runtime::spawn(async move {
                 connection_manager.start_cleanup_task().await;
             });
             Ok(())
         })
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::Destroyed = event {
+                window.state::<ChangeHistoryManager>().unsubscribe_window(window.label());
+            }
+        })
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_wdio::init())
@@ -83,13 +126,18 @@ pub fn run() {
     }
 
     builder
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(commands::telemetry::with_usage_counts(tauri::generate_handler![
             // Device commands (ADB)
             commands::device::adb_get_devices,
             commands::device::adb_get_packages,
             commands::device::adb_get_android_database_files,
             commands::device::adb_push_database_file,
             commands::device::adb_get_device_info,
+            commands::device::adb_pull_data_directory,
+            commands::device::get_app_preference_files,
+            commands::device::pull_app_preferences,
+            commands::device::get_pulled_file_metadata,
+            commands::device::check_pulled_file_staleness,
             // Device commands (iOS)
             commands::device::device_get_ios_devices,
             commands::device::device_get_ios_packages,
@@ -112,6 +160,7 @@ pub fn run() {
             commands::database::db_open,
             commands::database::db_get_tables,
             commands::database::db_get_table_data,
+            commands::database::db_get_cell_value,
             commands::database::db_get_info,
             commands::database::db_update_table_row,
             commands::database::db_insert_table_row,
@@ -123,6 +172,33 @@ pub fn run() {
             commands::database::db_clear_cache_for_path,
             commands::database::db_clear_all_cache,
             commands::database::db_switch_database,
+            commands::database::db_compare_query,
+            commands::database::db_generate_migration,
+            commands::database::snapshot_diff,
+            commands::database::db_three_way_merge,
+            commands::database::db_replay_changes_onto,
+            commands::database::db_aggregate_for_chart,
+            commands::database::db_scan_pii,
+            commands::database::db_analyze_delete_impact,
+            commands::database::db_list_triggers,
+            commands::database::db_disable_triggers,
+            commands::database::db_restore_triggers,
+            commands::database::db_export_schema_diagram,
+            commands::database::db_lint_query,
+            commands::database::db_convert_timestamp,
+            commands::database::db_list_value_transformers,
+            commands::database::db_set_value_transformer,
+            commands::database::db_decompress_cell,
+            commands::database::db_recompress_cell,
+            commands::database::db_extract_archive_databases,
+            commands::database::db_export_rows,
+            commands::database::db_export_data,
+            commands::database::db_export_table_parquet,
+            commands::database::db_copy_rows_as,
+            commands::database::db_generate_inserts,
+            commands::database::db_generate_test_data,
+            commands::database::db_bulk_update,
+            commands::database::db_open_copy,
             // Change History commands (Phase 1)
             commands::database::change_history::commands::record_database_change_safe,
             commands::database::change_history::commands::get_database_change_history,
@@ -132,25 +208,98 @@ pub fn run() {
             commands::database::change_history::commands::clear_context_changes,
             commands::database::change_history::commands::clear_all_change_history,
             commands::database::change_history::commands::get_change_history_diagnostics,
+            commands::database::change_history::commands::subscribe_change_history_context,
+            commands::database::change_history::commands::unsubscribe_change_history_context,
             commands::database::change_history::commands::generate_custom_file_context_key_command,
             // Common commands (file dialogs)
             commands::common::dialog_select_file,
             commands::common::dialog_save_file,
             commands::common::export_text_file,
             commands::common::save_dropped_file,
+            commands::common::scan_dropped_directory,
             commands::common::export_logs,
+            commands::common::operations_list,
+            commands::common::operation_cancel,
+            // Local file workspace commands
+            commands::workspace::workspace_open_local_file,
+            commands::workspace::workspace_get_recent_files,
+            commands::workspace::workspace_remove_recent_file,
+            commands::workspace::workspace_watch_file,
+            commands::workspace::workspace_unwatch_file,
             // Device helper commands
             commands::device::helpers::touch_database_file,
             commands::device::helpers::force_clean_temp_directory,
+            commands::device::get_temp_dir_usage,
+            commands::device::set_temp_dir_quota,
+            commands::device::check_environment,
+            commands::device::install_windows_dependencies,
+            commands::device::get_platform_capabilities,
+            commands::device::get_device_capabilities,
+            commands::device::favorites_get,
+            commands::device::favorites_pin,
+            commands::device::favorites_unpin,
+            commands::device::favorites_reorder,
+            commands::device::bookmark_database,
+            commands::device::bookmarks_list,
+            commands::device::bookmark_delete,
+            commands::device::repull_bookmark,
+            commands::integrations::pull_and_open_device_database,
+            commands::integrations::save_and_push,
+            commands::integrations::merge_and_push,
+            commands::device::get_last_push_hook_results,
+            commands::device::schedule_pull_create,
+            commands::device::schedule_pull_list,
+            commands::device::schedule_pull_delete,
+            commands::device::device_session_start,
+            commands::device::device_session_stop,
+            commands::device::device_session_list,
+            commands::device::inspect_app_bundle,
+            commands::device::adb_install_apk,
+            commands::device::ios_install_ipa,
+            commands::device::ios_launch_app,
+            commands::device::ios_terminate_app,
+            commands::device::ios_pair_device,
+            commands::device::ios_validate_pairing,
+            // Diagnostics commands
+            commands::diagnostics::export_diagnostics_bundle,
+            commands::diagnostics::app_health_check,
+            commands::crash::crash_reports_list,
+            commands::crash::crash_reports_clear,
+            commands::crash::crash_reports_upload,
+            commands::telemetry::telemetry_get_usage,
+            commands::telemetry::telemetry_purge,
+            commands::telemetry::telemetry_upload,
+            commands::logging::get_log_level,
+            commands::logging::set_log_level,
+            commands::logging::set_full_logging_enabled,
+            // Settings commands
+            commands::settings::settings_get,
+            commands::settings::settings_set,
+            commands::journal::journal_get_interrupted_operations,
+            commands::journal::journal_resolve_interrupted_operation,
+            commands::guardrails::get_safe_mode_status,
+            commands::guardrails::confirm_dangerous_operation,
             // Updater commands
             commands::updater::check_for_updates,
             commands::updater::download_and_install_update,
+            commands::updater::get_update_changelog,
+            commands::updater::install_update_from_file,
+            commands::updater::cancel_update_download,
+            commands::updater::install_staged_update,
             // iOS diagnostic commands
             commands::device::ios::diagnostic::diagnose_ios_device,
-            commands::device::ios::diagnostic::check_ios_device_status
-        ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+            commands::device::ios::diagnostic::check_ios_device_status,
+            commands::device::ios::quarantine::ios_check_tool_quarantine,
+            commands::device::ios::quarantine::ios_clear_tool_quarantine
+        ]))
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|_app_handle, event| {
+            if let tauri::RunEvent::Exit = event {
+                commands::telemetry::flush_telemetry();
+                commands::updater::install_staged_update_on_exit();
+            }
+        });
 }
 
 fn main() {
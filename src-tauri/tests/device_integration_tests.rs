@@ -65,6 +65,9 @@ mod ios_integration_tests {
                 location: "Documents".to_string(),
                 remote_path: Some("/var/mobile/Containers/Data/Application/ABC123/Documents/database.sqlite".to_string()),
                 device_type: "iphone".to_string(),
+                file_type: DatabaseFileType::Sqlite,
+                integrity: None,
+                pull_strategy: None,
             },
             DatabaseFile {
                 path: "/var/mobile/Containers/Data/Application/DEF456/Library/cache.db".to_string(),
@@ -73,6 +76,9 @@ mod ios_integration_tests {
                 location: "Library".to_string(),
                 remote_path: Some("/var/mobile/Containers/Data/Application/DEF456/Library/cache.db".to_string()),
                 device_type: "iphone".to_string(),
+                file_type: DatabaseFileType::Sqlite,
+                integrity: None,
+                pull_strategy: None,
             },
         ];
 
@@ -148,6 +154,9 @@ mod android_integration_tests {
                 location: "internal".to_string(),
                 remote_path: Some("/data/data/com.example.app/databases/main.db".to_string()),
                 device_type: "android".to_string(),
+                file_type: DatabaseFileType::Sqlite,
+                integrity: None,
+                pull_strategy: None,
             },
             DatabaseFile {
                 path: "/storage/emulated/0/Android/data/com.app/files/cache.sqlite".to_string(),
@@ -156,6 +165,9 @@ mod android_integration_tests {
                 location: "external".to_string(),
                 remote_path: Some("/storage/emulated/0/Android/data/com.app/files/cache.sqlite".to_string()),
                 device_type: "android".to_string(),
+                file_type: DatabaseFileType::Sqlite,
+                integrity: None,
+                pull_strategy: None,
             },
         ];
 
@@ -72,6 +72,7 @@ vi.mock('@renderer/hooks/useChangeHistory', () => ({
     data: { changes: [] },
     isLoading: false,
   }),
+  useChangeHistorySync: vi.fn(),
 }))
 
 vi.mock('ag-grid-react', () => ({
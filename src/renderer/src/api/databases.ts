@@ -15,7 +15,8 @@ export interface ExportFileOptions {
 export interface DatabaseApi {
   exportFile: (options: ExportFileOptions) => Promise<string | null>
   getTables: (dbPath?: string) => Promise<any>
-  getTableInfo: (tableName: string, dbPath?: string) => Promise<any>
+  getTableInfo: (tableName: string, dbPath?: string, columns?: string[]) => Promise<any>
+  getCellValue: (tableName: string, columnName: string, rowId: number, dbPath?: string) => Promise<any>
   openDatabase: (filePath: string) => Promise<any>
   openFile: () => Promise<OpenFileResult>
   switchDatabase: (filePath: string) => Promise<any>
@@ -69,8 +70,8 @@ export function createDatabaseApi({
     openDatabase: async (filePath: string) => {
       validateInput(filePath, 'filePath', { required: true, type: 'string', maxLength: 500 })
 
-      if (!filePath.match(/\.(db|sqlite|sqlite3)$/i)) {
-        throw new Error('Invalid database file extension. Expected .db, .sqlite, or .sqlite3')
+      if (!filePath.match(/\.(db|sqlite|sqlite3|sqlar|zip|apk|aab|ipa)$/i)) {
+        throw new Error('Invalid database file extension. Expected .db, .sqlite, .sqlite3 or an archive (.sqlar, .zip, .apk, .ipa)')
       }
 
       try {
@@ -88,7 +89,7 @@ export function createDatabaseApi({
       }
     },
 
-    getTableInfo: async (tableName: string, dbPath?: string) => {
+    getTableInfo: async (tableName: string, dbPath?: string, columns?: string[]) => {
       validateInput(tableName, 'tableName', { required: true, type: 'string', maxLength: 100 })
       if (dbPath) {
         validateInput(dbPath, 'dbPath', { type: 'string', maxLength: 500 })
@@ -102,15 +103,19 @@ export function createDatabaseApi({
         const response = await invokeRaw<any>('db_get_table_data', {
           tableName,
           currentDbPath: dbPath,
+          columns,
         })
 
-        const validatedResponse = validateDeviceResponse<{ columns: any[], rows: any[] }>(response)
+        const validatedResponse = validateDeviceResponse<{ columns: any[], rows: any[], timestampColumns?: any[], transformedColumns?: any[], compressedColumns?: any[] }>(response)
 
         if (validatedResponse.success && validatedResponse.data) {
           return {
             success: true,
             columns: validatedResponse.data.columns,
             rows: validatedResponse.data.rows,
+            timestampColumns: validatedResponse.data.timestampColumns ?? [],
+            transformedColumns: validatedResponse.data.transformedColumns ?? [],
+            compressedColumns: validatedResponse.data.compressedColumns ?? [],
           }
         }
 
@@ -121,6 +126,9 @@ export function createDatabaseApi({
       }
     },
 
+    getCellValue: (tableName: string, columnName: string, rowId: number, dbPath?: string) =>
+      invokeCommandWithResponse('db:getCellValue', 'value', tableName, columnName, rowId, dbPath),
+
     switchDatabase: (filePath: string) =>
       invokeCommandWithResponse('db:switchDatabase', 'result', filePath),
 
@@ -7,6 +7,13 @@ export interface DeviceSummary {
   platform?: string
   deviceType?: string
   state?: string
+  apiLevel?: number
+  abi?: string
+  buildFingerprint?: string
+  // Set for devices the user pinned; sortOrder is the position among pinned devices
+  pinned?: boolean
+  nickname?: string
+  sortOrder?: number
 }
 
 export interface GetDevicesResult {
@@ -24,13 +31,14 @@ export interface CancelIOSDeviceDatabaseScanResult {
 export interface DeviceApi {
   cancelIOSDeviceDatabaseScan: (scanKey: string) => Promise<CancelIOSDeviceDatabaseScanResult>
   checkAppExistence: (deviceId: string, applicationId: string) => Promise<any>
-  getAndroidDatabaseFiles: (deviceId: string, applicationId: string) => Promise<any>
-  getAndroidPackages: (deviceId: string) => Promise<any>
-  getDevices: () => Promise<GetDevicesResult>
-  getIOsDevicePackages: (deviceId: string) => Promise<any>
+  getAndroidDatabaseFiles: (deviceId: string, applicationId: string, userId?: number, compress?: boolean) => Promise<any>
+  getAndroidPackages: (deviceId: string, userId?: number, forceRefresh?: boolean) => Promise<any>
+  // Device and package lists are cached briefly by the backend; forceRefresh bypasses the cache
+  getDevices: (forceRefresh?: boolean) => Promise<GetDevicesResult>
+  getIOsDevicePackages: (deviceId: string, forceRefresh?: boolean) => Promise<any>
   getIOSDeviceDatabaseFiles: (deviceId: string, applicationId: string, scanRequestId?: string) => Promise<any>
   refreshIOSDeviceDatabaseFile: (deviceId: string, applicationId: string, remotePath: string) => Promise<any>
-  getIOSPackages: (deviceId: string) => Promise<any>
+  getIOSPackages: (deviceId: string, forceRefresh?: boolean) => Promise<any>
   getIOSSimulatorDatabaseFiles: (deviceId: string, applicationId: string) => Promise<any>
 }
 
@@ -40,10 +48,10 @@ interface DeviceApiDependencies {
 
 export function createDeviceApi({ invokeCommandWithResponse }: DeviceApiDependencies): DeviceApi {
   return {
-    getDevices: async () => {
+    getDevices: async (forceRefresh?: boolean) => {
       try {
-        const androidResp = await invokeCommandWithResponse('adb:getDevices', 'devices')
-        const iosResp = await invokeCommandWithResponse('device:getIOsDevices', 'devices')
+        const androidResp = await invokeCommandWithResponse('adb:getDevices', 'devices', forceRefresh)
+        const iosResp = await invokeCommandWithResponse('device:getIOsDevices', 'devices', forceRefresh)
 
         let iosSimulatorsResp: { success: boolean, simulators: DeviceSummary[] } = { success: false, simulators: [] }
         const abortController = new AbortController()
@@ -51,7 +59,7 @@ export function createDeviceApi({ invokeCommandWithResponse }: DeviceApiDependen
 
         try {
           const result = await Promise.race([
-            invokeCommandWithResponse('getIOSSimulators', 'simulators'),
+            invokeCommandWithResponse('getIOSSimulators', 'simulators', forceRefresh),
             new Promise<{ success: boolean, simulators: DeviceSummary[] }>((_, reject) => {
               abortController.signal.addEventListener('abort', () => {
                 reject(new Error('iOS Simulators fetch timed out'))
@@ -73,7 +81,7 @@ export function createDeviceApi({ invokeCommandWithResponse }: DeviceApiDependen
           androidResp.devices.forEach((device: DeviceSummary) => {
             allDevices.push({
               ...device,
-              label: `${device.name || device.id}`,
+              label: `${device.nickname || device.name || device.id}`,
               description: device.description || 'Android',
             })
           })
@@ -83,8 +91,8 @@ export function createDeviceApi({ invokeCommandWithResponse }: DeviceApiDependen
           iosResp.devices.forEach((device: DeviceSummary) => {
             allDevices.push({
               ...device,
-              label: `${device.name || device.id}`,
-              description: 'iPhone Device',
+              label: `${device.nickname || device.name || device.id}`,
+              description: device.description || 'iPhone Device',
             })
           })
         }
@@ -105,6 +113,10 @@ export function createDeviceApi({ invokeCommandWithResponse }: DeviceApiDependen
             })
         }
 
+        // Each platform comes back with pinned devices first; keep that order across platforms
+        const pinOrder = (device: DeviceSummary) => device.pinned ? device.sortOrder ?? 0 : Number.POSITIVE_INFINITY
+        allDevices.sort((a, b) => pinOrder(a) - pinOrder(b))
+
         return { success: true, devices: allDevices }
       }
       catch (error) {
@@ -113,19 +125,19 @@ export function createDeviceApi({ invokeCommandWithResponse }: DeviceApiDependen
       }
     },
 
-    getIOSPackages: (deviceId: string) => {
+    getIOSPackages: (deviceId: string, forceRefresh?: boolean) => {
       console.log('getIOSPackages called with deviceId:', deviceId)
-      return invokeCommandWithResponse('device:getIosPackages', 'packages', deviceId)
+      return invokeCommandWithResponse('device:getIosPackages', 'packages', deviceId, forceRefresh)
     },
 
-    getAndroidPackages: (deviceId: string) =>
-      invokeCommandWithResponse('adb:getPackages', 'packages', deviceId),
+    getAndroidPackages: (deviceId: string, userId?: number, forceRefresh?: boolean) =>
+      invokeCommandWithResponse('adb:getPackages', 'packages', deviceId, userId, forceRefresh),
 
-    getIOsDevicePackages: (deviceId: string) =>
-      invokeCommandWithResponse('device:getIosDevicePackages', 'packages', deviceId),
+    getIOsDevicePackages: (deviceId: string, forceRefresh?: boolean) =>
+      invokeCommandWithResponse('device:getIosDevicePackages', 'packages', deviceId, forceRefresh),
 
-    getAndroidDatabaseFiles: (deviceId: string, applicationId: string) =>
-      invokeCommandWithResponse('adb:getAndroidDatabaseFiles', 'files', deviceId, applicationId),
+    getAndroidDatabaseFiles: (deviceId: string, applicationId: string, userId?: number, compress?: boolean) =>
+      invokeCommandWithResponse('adb:getAndroidDatabaseFiles', 'files', deviceId, applicationId, userId, compress),
 
     checkAppExistence: (deviceId: string, applicationId: string) =>
       invokeCommandWithResponse('device:checkAppExistence', 'exists', deviceId, applicationId),
@@ -1,5 +1,5 @@
 import { Badge, Button, Text } from '@chakra-ui/react'
-import { useChangeHistory } from '@renderer/hooks/useChangeHistory'
+import { useChangeHistory, useChangeHistorySync } from '@renderer/hooks/useChangeHistory'
 import { formatDistanceToNow } from 'date-fns'
 import { useState } from 'react'
 import { LuClock } from 'react-icons/lu'
@@ -19,6 +19,7 @@ export function ChangeHistoryIndicator({
 }: ChangeHistoryIndicatorProps) {
   const [isOpen, setIsOpen] = useState(false)
   const { data: changes = [], isLoading, error } = useChangeHistory(10, 0)
+  useChangeHistorySync()
   
   const changeCount = changes.length
   // Only show badge if there are actual changes AND no error
@@ -8,6 +8,9 @@ import {
   selectDevice,
 } from '@renderer/features/layout/selectionSession'
 import { useAppHeaderSelectionEffects } from '@renderer/features/layout/useAppHeaderSelectionEffects'
+import { useCacheInvalidation } from '@renderer/features/layout/useCacheInvalidation'
+import { useDeviceKeepAlive } from '@renderer/features/layout/useDeviceKeepAlive'
+import { usePullIntegrityAlerts } from '@renderer/features/layout/usePullIntegrityAlerts'
 import { useSelectionSessionActions } from '@renderer/features/layout/useSelectionSessionActions'
 import { useSelectionSessionState } from '@renderer/features/layout/useSelectionSessionState'
 import { fetchApplicationsForDevice, useApplications } from '@renderer/hooks/useApplications'
@@ -154,6 +157,10 @@ function AppHeader() {
     selectionActions,
   })
 
+  useDeviceKeepAlive(selectedDevice)
+  useCacheInvalidation()
+  usePullIntegrityAlerts()
+
   const handleRefreshDevices = useCallback(async () => {
     try {
       console.info('CriticalPath: refreshing devices from header', {
@@ -274,6 +281,7 @@ function AppHeader() {
         showInfoIcon: true,
         onInfoClick: handleDeviceInfoClick,
         ...device,
+        isDisabled: !!device.state && device.state !== 'device',
       }
     }), [devicesList, handleDeviceInfoClick])
 
@@ -290,7 +298,7 @@ function AppHeader() {
       const description = app.bundleId === app.name ? '' : app.bundleId
       const isRecentlyUsed = recentBundleIds.has(app.bundleId)
       return {
-        label: app.name,
+        label: app.nickname || app.name,
         value: app.bundleId,
         description,
         isRecentlyUsed,
@@ -305,7 +313,7 @@ function AppHeader() {
         if (foundApp) {
           const description = foundApp.bundleId === foundApp.name ? '' : foundApp.bundleId
           return {
-            label: foundApp.name,
+            label: foundApp.nickname || foundApp.name,
             value: foundApp.bundleId,
             description,
             isRecentlyUsed: true,
@@ -46,6 +46,8 @@ beforeAll(() => {
     getIOSDeviceDatabaseFiles: vi.fn(),
     cancelIOSDeviceDatabaseScan: vi.fn(),
     getIOSSimulatorDatabaseFiles: vi.fn(),
+    deviceSessionStart: vi.fn().mockResolvedValue({ success: true }),
+    deviceSessionStop: vi.fn().mockResolvedValue({ success: true }),
   } as any
 })
 
@@ -88,6 +90,10 @@ vi.mock('@tauri-apps/api/core', () => ({
   invoke: vi.fn().mockResolvedValue('/path/to/tool'),
 }))
 
+vi.mock('@tauri-apps/api/event', () => ({
+  listen: vi.fn().mockResolvedValue(() => {}),
+}))
+
 vi.mock('@renderer/store', () => ({
   useCurrentDeviceSelection: (selector) => {
     const state = {
@@ -0,0 +1,50 @@
+import { listenAppEvent } from '@renderer/utils/tauriEventBridge'
+import { useQueryClient } from '@tanstack/react-query'
+import { useEffect } from 'react'
+
+const DB_CACHE_INVALIDATED_EVENT = 'db-cache-invalidated'
+
+interface CacheInvalidatedEvent {
+  // null when every cached connection was dropped
+  path: string | null
+  reason: string
+}
+
+// Paths sit at a different position in each database query key
+const DATABASE_QUERY_PATH_INDEX: Record<string, number> = {
+  databaseTables: 1,
+  tableData: 2,
+}
+
+// Refetches table lists and rows whose connection the backend dropped, so views never read a closed pool
+export function useCacheInvalidation() {
+  const queryClient = useQueryClient()
+
+  useEffect(() => {
+    let isMounted = true
+
+    const unlistenPromise = listenAppEvent<CacheInvalidatedEvent>(DB_CACHE_INVALIDATED_EVENT, (event) => {
+      if (!isMounted) {
+        return
+      }
+
+      const { path, reason } = event.payload
+      console.info('Database cache invalidated', { path, reason })
+      void queryClient.invalidateQueries({
+        predicate: (query) => {
+          const [kind] = query.queryKey
+          const pathIndex = typeof kind === 'string' ? DATABASE_QUERY_PATH_INDEX[kind] : undefined
+          if (pathIndex === undefined) {
+            return false
+          }
+          return path === null || query.queryKey[pathIndex] === path
+        },
+      })
+    })
+
+    return () => {
+      isMounted = false
+      void unlistenPromise.then(unlisten => unlisten())
+    }
+  }, [queryClient])
+}
@@ -0,0 +1,60 @@
+import type { DeviceInfo } from '@renderer/types'
+import { toaster } from '@renderer/ui/toaster'
+import { listenAppEvent } from '@renderer/utils/tauriEventBridge'
+import { useEffect } from 'react'
+
+const CONNECTION_STATE_EVENT = 'device-connection-state'
+
+interface ConnectionStateEvent {
+  deviceId: string
+  state: 'connected' | 'reconnecting' | 'disconnected'
+  previousState: 'connected' | 'reconnecting' | 'disconnected'
+  error?: string
+  hint?: string
+}
+
+// Keeps the selected physical iPhone's connection alive and reports when it drops or comes back
+export function useDeviceKeepAlive(selectedDevice: DeviceInfo | null) {
+  const deviceId = selectedDevice?.deviceType === 'iphone-device' ? selectedDevice.id : null
+
+  useEffect(() => {
+    if (!deviceId) {
+      return
+    }
+
+    let isMounted = true
+    void window.api.deviceSessionStart(deviceId, 'iphone-device')
+
+    const unlistenPromise = listenAppEvent<ConnectionStateEvent>(CONNECTION_STATE_EVENT, (event) => {
+      const { payload } = event
+      if (!isMounted || payload.deviceId !== deviceId) {
+        return
+      }
+
+      if (payload.state === 'disconnected') {
+        toaster.create({
+          title: 'iPhone connection lost',
+          description: payload.hint || payload.error || 'The device stopped responding.',
+          type: 'error',
+          duration: 8000,
+          meta: {
+            closable: true,
+          },
+        })
+      }
+      else if (payload.state === 'connected' && payload.previousState === 'disconnected') {
+        toaster.create({
+          title: 'iPhone reconnected',
+          type: 'success',
+          duration: 3000,
+        })
+      }
+    })
+
+    return () => {
+      isMounted = false
+      void unlistenPromise.then(unlisten => unlisten())
+      void window.api.deviceSessionStop(deviceId)
+    }
+  }, [deviceId])
+}
@@ -0,0 +1,49 @@
+import type { DatabaseFile, PullIntegrity } from '@renderer/types/devices'
+import { toaster } from '@renderer/ui/toaster'
+import { listenAppEvent } from '@renderer/utils/tauriEventBridge'
+import { useQueryClient } from '@tanstack/react-query'
+import { useEffect } from 'react'
+
+const PULL_INTEGRITY_EVENT = 'pull-integrity-checked'
+
+interface PullIntegrityEvent {
+  path: string
+  integrity: PullIntegrity
+}
+
+// Attaches background quick_check results to the listed files and warns about corrupt pulls
+export function usePullIntegrityAlerts() {
+  const queryClient = useQueryClient()
+
+  useEffect(() => {
+    let isMounted = true
+
+    const unlistenPromise = listenAppEvent<PullIntegrityEvent>(PULL_INTEGRITY_EVENT, (event) => {
+      if (!isMounted) {
+        return
+      }
+
+      const { path, integrity } = event.payload
+      queryClient.setQueriesData<DatabaseFile[]>({ queryKey: ['databaseFiles'] }, files =>
+        files?.map(file => (file.path === path ? { ...file, integrity } : file)))
+
+      if (integrity.status === 'corrupt') {
+        const filename = path.split(/[\\/]/).pop() || path
+        toaster.create({
+          title: `${filename} looks corrupt`,
+          description: `${integrity.detail || 'The integrity check failed.'} Pull it again before editing.`,
+          type: 'error',
+          duration: 10000,
+          meta: {
+            closable: true,
+          },
+        })
+      }
+    })
+
+    return () => {
+      isMounted = false
+      void unlistenPromise.then(unlisten => unlisten())
+    }
+  }, [queryClient])
+}
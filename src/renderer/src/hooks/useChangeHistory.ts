@@ -2,7 +2,18 @@ import type { ChangeEvent, ContextSummary } from '@renderer/types/changeHistory'
 import { useCurrentDatabaseSelection } from '@renderer/store/useCurrentDatabaseSelection'
 import { useCurrentDeviceSelection } from '@renderer/store/useCurrentDeviceSelection'
 import { generateContextKey } from '@renderer/utils/contextKey'
+import { listenAppEvent } from '@renderer/utils/tauriEventBridge'
 import { useQuery, useQueryClient } from '@tanstack/react-query'
+import { useEffect } from 'react'
+
+const CHANGE_HISTORY_UPDATED_EVENT = 'change-history-updated'
+const CHANGE_HISTORY_CONTEXT_SYNC_EVENT = 'change-history-context-sync'
+
+interface ChangeHistoryContextSync {
+  contextKey: string
+  kind: 'recorded' | 'cleared' | 'clearedAll'
+  changes: ChangeEvent[]
+}
 
 /**
  * Hook for fetching change history for the current database context
@@ -155,3 +166,62 @@ export function useChangeHistoryRefresh() {
     },
   }
 }
+
+/**
+ * Hook keeping this window's change history in sync with edits made in other windows.
+ * Subscribes to the current context so the backend pushes its full history on every change.
+ */
+export function useChangeHistorySync() {
+  const queryClient = useQueryClient()
+  const { selectedDevice, selectedApplication } = useCurrentDeviceSelection()
+  const { selectedDatabaseFile } = useCurrentDatabaseSelection()
+
+  const deviceId = selectedDevice?.id
+  const packageName = selectedApplication?.bundleId || selectedDatabaseFile?.packageName
+  const databasePath = selectedDatabaseFile?.path
+
+  useEffect(() => {
+    let isMounted = true
+
+    const unlistenUpdatedPromise = listenAppEvent(CHANGE_HISTORY_UPDATED_EVENT, () => {
+      if (!isMounted) {
+        return
+      }
+      void queryClient.invalidateQueries({ queryKey: ['contextSummaries'] })
+      void queryClient.invalidateQueries({ queryKey: ['changeHistoryDiagnostics'] })
+    })
+
+    if (!databasePath) {
+      return () => {
+        isMounted = false
+        void unlistenUpdatedPromise.then(unlisten => unlisten())
+      }
+    }
+
+    const contextKeyPromise = generateContextKey(deviceId, packageName, databasePath)
+    void contextKeyPromise.then(contextKey => window.api.subscribeChangeHistory(contextKey))
+
+    const unlistenSyncPromise = listenAppEvent<ChangeHistoryContextSync>(CHANGE_HISTORY_CONTEXT_SYNC_EVENT, async (event) => {
+      const contextKey = await contextKeyPromise
+      if (!isMounted || event.payload.contextKey !== contextKey) {
+        return
+      }
+
+      // Each cached page of this context gets its slice of the pushed history
+      const queries = queryClient.getQueryCache().findAll({
+        queryKey: ['changeHistory', deviceId, packageName, databasePath],
+      })
+      for (const query of queries) {
+        const [, , , , limit = 50, offset = 0] = query.queryKey as [string, unknown, unknown, unknown, number?, number?]
+        queryClient.setQueryData(query.queryKey, event.payload.changes.slice(offset, offset + limit))
+      }
+    })
+
+    return () => {
+      isMounted = false
+      void unlistenUpdatedPromise.then(unlisten => unlisten())
+      void unlistenSyncPromise.then(unlisten => unlisten())
+      void contextKeyPromise.then(contextKey => window.api.unsubscribeChangeHistory(contextKey))
+    }
+  }, [queryClient, deviceId, packageName, databasePath])
+}
@@ -23,8 +23,9 @@ export function useDatabaseTables(
         throw new Error('Device not selected for device database file')
       }
 
-      const dbPath = selectedDatabaseFile.path
-      await window.api.openDatabase(dbPath)
+      // Archives open through the database extracted from them, at a different path
+      const openResult = await window.api.openDatabase(selectedDatabaseFile.path)
+      const dbPath = openResult?.path || selectedDatabaseFile.path
 
       // Pass the database path to getTables to ensure it uses the correct connection
       const response = await window.api.getTables(dbPath)
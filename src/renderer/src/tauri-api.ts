@@ -3,6 +3,7 @@
 
 import { createDatabaseApi } from '@renderer/api/databases'
 import { createDeviceApi } from '@renderer/api/devices'
+import type { ConflictResolution, RowFilter } from '@renderer/types/database'
 import {
   getUnhandledCommandSentinel,
   installE2EController,
@@ -30,10 +31,25 @@ initializeEventSystem()
 installE2EController()
 
 // Types to match the Electron API
+export type DeviceErrorKind = 'toolMissing' | 'deviceOffline' | 'deviceNotFound' | 'unauthorized'
+  | 'appNotDebuggable' | 'appNotInstalled' | 'permissionDenied' | 'storageFull' | 'timeout'
+  | 'transferFailed' | 'unknown'
+
+// Classified device failure with a suggested fix, sent alongside `error`
+export interface DeviceErrorDetail {
+  kind: DeviceErrorKind
+  hint?: string
+  tool?: string | null
+  command?: string | null
+  seconds?: number | null
+  bytes?: number | null
+}
+
 interface DeviceResponse<T> {
   success: boolean
   data?: T
   error?: string
+  errorDetail?: DeviceErrorDetail
 }
 
 // Validation utilities for API responses and inputs
@@ -172,6 +188,65 @@ const COMMAND_MAP = {
   'adb:getAndroidDatabaseFiles': 'adb_get_android_database_files',
   'adb:pushDatabaseFile': 'adb_push_database_file',
   'adb:getDeviceInfo': 'adb_get_device_info',
+  'adb:pullDataDirectory': 'adb_pull_data_directory',
+  'device:getAppPreferenceFiles': 'get_app_preference_files',
+  'device:pullAppPreferences': 'pull_app_preferences',
+  'device:getPulledFileMetadata': 'get_pulled_file_metadata',
+  'device:checkPulledFileStaleness': 'check_pulled_file_staleness',
+  'device:getTempDirUsage': 'get_temp_dir_usage',
+  'device:setTempDirQuota': 'set_temp_dir_quota',
+  'device:checkEnvironment': 'check_environment',
+  'device:installWindowsDependencies': 'install_windows_dependencies',
+  'device:checkToolQuarantine': 'ios_check_tool_quarantine',
+  'device:clearToolQuarantine': 'ios_clear_tool_quarantine',
+  'device:getPlatformCapabilities': 'get_platform_capabilities',
+  'device:getDeviceCapabilities': 'get_device_capabilities',
+  'device:favoritesGet': 'favorites_get',
+  'device:favoritesPin': 'favorites_pin',
+  'device:favoritesUnpin': 'favorites_unpin',
+  'device:favoritesReorder': 'favorites_reorder',
+  'device:bookmarkDatabase': 'bookmark_database',
+  'device:bookmarksList': 'bookmarks_list',
+  'device:bookmarkDelete': 'bookmark_delete',
+  'device:repullBookmark': 'repull_bookmark',
+  'device:pullAndOpenDeviceDatabase': 'pull_and_open_device_database',
+  'device:saveAndPush': 'save_and_push',
+  'device:mergeAndPush': 'merge_and_push',
+  'device:getLastPushHookResults': 'get_last_push_hook_results',
+  'device:schedulePullCreate': 'schedule_pull_create',
+  'device:schedulePullList': 'schedule_pull_list',
+  'device:schedulePullDelete': 'schedule_pull_delete',
+  'device:deviceSessionStart': 'device_session_start',
+  'device:deviceSessionStop': 'device_session_stop',
+  'device:deviceSessionList': 'device_session_list',
+  'device:inspectAppBundle': 'inspect_app_bundle',
+  'adb:installApk': 'adb_install_apk',
+  'device:installIpa': 'ios_install_ipa',
+  'ios:iosLaunchApp': 'ios_launch_app',
+  'ios:iosTerminateApp': 'ios_terminate_app',
+  'ios:iosPairDevice': 'ios_pair_device',
+  'ios:iosValidatePairing': 'ios_validate_pairing',
+
+  // Diagnostics
+  'diagnostics:exportBundle': 'export_diagnostics_bundle',
+  'diagnostics:healthCheck': 'app_health_check',
+  'crash:listReports': 'crash_reports_list',
+  'crash:clearReports': 'crash_reports_clear',
+  'crash:uploadReports': 'crash_reports_upload',
+  'telemetry:getUsage': 'telemetry_get_usage',
+  'telemetry:purge': 'telemetry_purge',
+  'telemetry:upload': 'telemetry_upload',
+  'diagnostics:getLogLevel': 'get_log_level',
+  'diagnostics:setLogLevel': 'set_log_level',
+  'diagnostics:setFullLogging': 'set_full_logging_enabled',
+
+  // Settings
+  'settings:get': 'settings_get',
+  'settings:set': 'settings_set',
+  'journal:getInterruptedOperations': 'journal_get_interrupted_operations',
+  'journal:resolveInterruptedOperation': 'journal_resolve_interrupted_operation',
+  'safeMode:getStatus': 'get_safe_mode_status',
+  'safeMode:confirmDangerousOperation': 'confirm_dangerous_operation',
   'device:getIOsDevices': 'device_get_ios_devices',
   'device:getIosPackages': 'device_get_ios_packages',
   'device:getIosDevicePackages': 'device_get_ios_device_packages',
@@ -194,6 +269,7 @@ const COMMAND_MAP = {
   'db:open': 'db_open',
   'db:getTables': 'db_get_tables',
   'db:getTableData': 'db_get_table_data',
+  'db:getCellValue': 'db_get_cell_value',
   'db:getInfo': 'db_get_info',
   'db:updateTableRow': 'db_update_table_row',
   'db:insertTableRow': 'db_insert_table_row',
@@ -202,6 +278,33 @@ const COMMAND_MAP = {
   'db:clearTable': 'db_clear_table',
   'db:executeQuery': 'db_execute_query',
   'db:switchDatabase': 'db_switch_database',
+  'db:compareQuery': 'db_compare_query',
+  'db:generateMigration': 'db_generate_migration',
+  'db:snapshotDiff': 'snapshot_diff',
+  'db:threeWayMerge': 'db_three_way_merge',
+  'db:replayChangesOnto': 'db_replay_changes_onto',
+  'db:aggregateForChart': 'db_aggregate_for_chart',
+  'db:scanPii': 'db_scan_pii',
+  'db:analyzeDeleteImpact': 'db_analyze_delete_impact',
+  'db:listTriggers': 'db_list_triggers',
+  'db:disableTriggers': 'db_disable_triggers',
+  'db:restoreTriggers': 'db_restore_triggers',
+  'db:exportSchemaDiagram': 'db_export_schema_diagram',
+  'db:lintQuery': 'db_lint_query',
+  'db:convertTimestamp': 'db_convert_timestamp',
+  'db:listValueTransformers': 'db_list_value_transformers',
+  'db:setValueTransformer': 'db_set_value_transformer',
+  'db:decompressCell': 'db_decompress_cell',
+  'db:recompressCell': 'db_recompress_cell',
+  'db:extractArchiveDatabases': 'db_extract_archive_databases',
+  'db:exportRows': 'db_export_rows',
+  'db:exportData': 'db_export_data',
+  'db:exportTableParquet': 'db_export_table_parquet',
+  'db:copyRowsAs': 'db_copy_rows_as',
+  'db:generateInserts': 'db_generate_inserts',
+  'db:generateTestData': 'db_generate_test_data',
+  'db:bulkUpdate': 'db_bulk_update',
+  'db:openCopy': 'db_open_copy',
 
   // Change history commands
   'db:getChangeHistory': 'get_database_change_history',
@@ -209,6 +312,8 @@ const COMMAND_MAP = {
   'db:getChangeHistoryDiagnostics': 'get_change_history_diagnostics',
   'db:clearContextChanges': 'clear_context_changes',
   'db:clearAllChangeHistory': 'clear_all_change_history',
+  'db:subscribeChangeHistory': 'subscribe_change_history_context',
+  'db:unsubscribeChangeHistory': 'unsubscribe_change_history_context',
   'db:generateCustomFileContextKey': 'generate_custom_file_context_key_command',
 
   // Common commands
@@ -216,6 +321,16 @@ const COMMAND_MAP = {
   'dialog:saveFile': 'dialog_save_file',
   'dialog:saveTextFile': 'export_text_file',
   'common:exportLogs': 'export_logs',
+  'operations:list': 'operations_list',
+  'operations:cancel': 'operation_cancel',
+  'common:scanDroppedDirectory': 'scan_dropped_directory',
+
+  // Local file workspace commands
+  'workspace:openLocalFile': 'workspace_open_local_file',
+  'workspace:getRecentFiles': 'workspace_get_recent_files',
+  'workspace:removeRecentFile': 'workspace_remove_recent_file',
+  'workspace:watchFile': 'workspace_watch_file',
+  'workspace:unwatchFile': 'workspace_unwatch_file',
 }
 
 async function invokeTauriCommand<T>(tauriCommand: string, parameters?: Record<string, unknown>): Promise<T> {
@@ -296,7 +411,11 @@ async function invokeCommandWithResponse<T>(electronCommand: string, dataFieldNa
       }
       else {
         console.error(`🔍 [invokeCommandWithResponse] Command failed:`, validatedResponse.error)
-        return { success: false, error: validatedResponse.error || 'Unknown error occurred' }
+        return {
+          success: false,
+          error: validatedResponse.error || 'Unknown error occurred',
+          ...(validatedResponse.errorDetail && { errorDetail: validatedResponse.errorDetail }),
+        }
       }
     }
     catch (error) {
@@ -321,38 +440,122 @@ async function invokeCommandWithResponse<T>(electronCommand: string, dataFieldNa
 function getParameterNames(command: string): string[] {
   const paramMap: Record<string, string[]> = {
     // Device commands
-    adb_get_devices: [], // No parameters
-    adb_get_packages: ['deviceId'],
-    adb_get_android_database_files: ['deviceId', 'packageName'],
-    adb_push_database_file: ['deviceId', 'localPath', 'packageName', 'remotePath'],
+    adb_get_devices: ['forceRefresh'],
+    adb_get_packages: ['deviceId', 'userId', 'forceRefresh'],
+    adb_get_android_database_files: ['deviceId', 'packageName', 'userId', 'compress'],
+    adb_push_database_file: ['deviceId', 'localPath', 'packageName', 'remotePath', 'userId', 'verify', 'stopApp'],
     adb_get_device_info: ['deviceId'],
-    device_push_ios_database_file: ['deviceId', 'localPath', 'packageName', 'remotePath'],
-    device_get_ios_packages: ['deviceId'],
-    device_get_ios_device_packages: ['deviceId'],
+    adb_pull_data_directory: ['deviceId', 'packageName', 'remotePath'],
+    get_app_preference_files: ['deviceId', 'packageName', 'deviceType'],
+    pull_app_preferences: ['deviceId', 'packageName', 'deviceType', 'remotePath'],
+    get_pulled_file_metadata: ['localPath'],
+    check_pulled_file_staleness: ['localPath', 'deviceType'],
+    get_temp_dir_usage: [],
+    set_temp_dir_quota: ['maxBytes'],
+    check_environment: [],
+    install_windows_dependencies: ['dependencies'],
+    ios_check_tool_quarantine: [],
+    ios_clear_tool_quarantine: [],
+    get_platform_capabilities: [],
+    get_device_capabilities: ['deviceId', 'refresh'],
+    favorites_get: [],
+    favorites_pin: ['kind', 'id', 'nickname'],
+    favorites_unpin: ['kind', 'id'],
+    favorites_reorder: ['kind', 'ids'],
+    bookmark_database: ['deviceType', 'deviceId', 'packageName', 'remotePath', 'name'],
+    bookmarks_list: ['deviceId', 'packageName'],
+    bookmark_delete: ['bookmarkId'],
+    repull_bookmark: ['bookmarkId'],
+    pull_and_open_device_database: ['deviceType', 'deviceId', 'packageName', 'remotePath'],
+    save_and_push: ['deviceType', 'deviceId', 'packageName', 'localPath', 'remotePath', 'userId'],
+    merge_and_push: ['deviceType', 'deviceId', 'packageName', 'localPath', 'remotePath', 'userId', 'resolutions'],
+    get_last_push_hook_results: [],
+    schedule_pull_create: ['deviceType', 'deviceId', 'packageName', 'remotePath', 'intervalMinutes', 'maxSnapshots'],
+    schedule_pull_list: [],
+    schedule_pull_delete: ['scheduleId', 'deleteSnapshots'],
+    device_session_start: ['deviceId', 'deviceType', 'intervalSeconds'],
+    device_session_stop: ['deviceId'],
+    device_session_list: [],
+    inspect_app_bundle: ['bundlePath'],
+    adb_install_apk: ['deviceId', 'apkPath', 'allowDowngrade', 'grantPermissions'],
+    ios_install_ipa: ['deviceId', 'ipaPath', 'deviceType'],
+    ios_launch_app: ['deviceId', 'bundleId'],
+    ios_terminate_app: ['deviceId', 'bundleId'],
+    ios_pair_device: ['deviceId'],
+    ios_validate_pairing: ['deviceId'],
+    export_diagnostics_bundle: ['outputPath', 'filter'],
+    app_health_check: [],
+    crash_reports_list: [],
+    crash_reports_clear: [],
+    crash_reports_upload: [],
+    telemetry_get_usage: [],
+    telemetry_purge: [],
+    telemetry_upload: [],
+    get_log_level: [],
+    set_log_level: ['filter'],
+    set_full_logging_enabled: ['enabled'],
+    settings_get: [],
+    settings_set: ['settings'],
+    journal_get_interrupted_operations: [],
+    journal_resolve_interrupted_operation: ['id', 'discardLocalFile'],
+    get_safe_mode_status: [],
+    confirm_dangerous_operation: ['operation'],
+    device_push_ios_database_file: ['deviceId', 'localPath', 'packageName', 'remotePath', 'verify', 'stopApp'],
+    device_get_ios_devices: ['forceRefresh'],
+    device_get_ios_packages: ['deviceId', 'forceRefresh'],
+    device_get_ios_device_packages: ['deviceId', 'forceRefresh'],
     get_ios_device_database_files: ['deviceId', 'packageName', 'scanRequestId'],
     refresh_ios_device_database_file: ['deviceId', 'packageName', 'remotePath'],
     cancel_ios_device_database_scan: ['scanKey'],
     get_ios_simulator_database_files: ['deviceId', 'packageName'],
     device_check_app_existence: ['deviceId', 'packageName'],
     ios_get_device_info: ['deviceId'],
-    upload_simulator_ios_db_file: ['deviceId', 'localFilePath', 'packageName', 'remoteLocation'],
+    upload_simulator_ios_db_file: ['deviceId', 'localFilePath', 'packageName', 'remoteLocation', 'verify', 'stopApp'],
     launch_android_emulator: ['emulatorId'],
     launch_ios_simulator: ['simulatorId'],
     get_android_emulators: [],
-    get_ios_simulators: [],
+    get_ios_simulators: ['forceRefresh'],
 
     // Database commands
     db_open: ['filePath'],
     db_get_tables: ['currentDbPath'],
-    db_get_table_data: ['tableName', 'currentDbPath'],
+    db_get_table_data: ['tableName', 'currentDbPath', 'columns'],
+    db_get_cell_value: ['tableName', 'columnName', 'rowId', 'currentDbPath'],
     db_get_info: ['filePath'],
-    db_update_table_row: ['tableName', 'row', 'condition', 'currentDbPath', 'deviceId', 'deviceName', 'deviceType', 'packageName', 'appName'],
+    db_update_table_row: ['tableName', 'row', 'condition', 'currentDbPath', 'deviceId', 'deviceName', 'deviceType', 'packageName', 'appName', 'dryRun'],
     db_insert_table_row: ['tableName', 'row', 'currentDbPath', 'deviceId', 'deviceName', 'deviceType', 'packageName', 'appName'],
     db_add_new_row_with_defaults: ['tableName', 'currentDbPath', 'deviceId', 'deviceName', 'deviceType', 'packageName', 'appName'],
-    db_delete_table_row: ['tableName', 'condition', 'currentDbPath', 'deviceId', 'deviceName', 'deviceType', 'packageName', 'appName'],
+    db_delete_table_row: ['tableName', 'condition', 'currentDbPath', 'deviceId', 'deviceName', 'deviceType', 'packageName', 'appName', 'dryRun'],
     db_clear_table: ['tableName', 'currentDbPath', 'deviceId', 'deviceName', 'deviceType', 'packageName', 'appName'],
-    db_execute_query: ['query', 'dbPath', 'params'],
+    db_execute_query: ['query', 'dbPath', 'params', 'dryRun', 'profile'],
     db_switch_database: ['newDbPath'],
+    db_compare_query: ['query', 'leftDbPath', 'rightDbPath', 'keyColumns'],
+    db_generate_migration: ['sourceDbPath', 'targetDbPath'],
+    snapshot_diff: ['a', 'b'],
+    db_three_way_merge: ['basePath', 'localPath', 'remotePath', 'mergedPath', 'resolutions'],
+    db_replay_changes_onto: ['contextKey', 'targetPath', 'changeIds'],
+    db_aggregate_for_chart: ['query', 'spec', 'currentDbPath'],
+    db_scan_pii: ['sampleSize', 'currentDbPath'],
+    db_analyze_delete_impact: ['tableName', 'condition', 'currentDbPath'],
+    db_list_triggers: ['tableName', 'currentDbPath'],
+    db_disable_triggers: ['tableName', 'currentDbPath'],
+    db_restore_triggers: ['tableName', 'currentDbPath'],
+    db_export_schema_diagram: ['format', 'currentDbPath'],
+    db_lint_query: ['query', 'currentDbPath'],
+    db_convert_timestamp: ['value', 'fromFormat', 'toFormat', 'timezone'],
+    db_list_value_transformers: ['tableName', 'currentDbPath'],
+    db_set_value_transformer: ['tableName', 'columnName', 'transformer', 'currentDbPath'],
+    db_decompress_cell: ['tableName', 'columnName', 'rowId', 'currentDbPath'],
+    db_recompress_cell: ['tableName', 'columnName', 'rowId', 'content', 'format', 'currentDbPath'],
+    db_extract_archive_databases: ['archivePath'],
+    db_export_rows: ['tableName', 'format', 'keyColumn', 'keyValues', 'filter', 'outputPath', 'currentDbPath'],
+    db_export_data: ['format', 'tableName', 'query', 'outputPath', 'currentDbPath'],
+    db_export_table_parquet: ['tableName', 'outputPath', 'currentDbPath'],
+    db_copy_rows_as: ['format', 'columns', 'rows', 'tableName', 'currentDbPath'],
+    db_generate_inserts: ['tableName', 'keyColumn', 'keyValues', 'filter', 'includeCreateTable', 'currentDbPath'],
+    db_generate_test_data: ['tableName', 'rowCount', 'seed', 'currentDbPath'],
+    db_bulk_update: ['tableName', 'assignments', 'filter', 'dryRun', 'currentDbPath', 'deviceId', 'deviceName', 'deviceType', 'packageName', 'appName'],
+    db_open_copy: ['filePath'],
 
     // Change history commands
     get_database_change_history: ['contextKey', 'tableName'],
@@ -360,6 +563,8 @@ function getParameterNames(command: string): string[] {
     get_change_history_diagnostics: [],
     clear_context_changes: ['contextKey'],
     clear_all_change_history: [],
+    subscribe_change_history_context: ['contextKey'],
+    unsubscribe_change_history_context: ['contextKey'],
     generate_custom_file_context_key_command: ['databasePath'],
 
     // Common commands
@@ -369,6 +574,16 @@ function getParameterNames(command: string): string[] {
     dialog_open_file: ['options'],
     dialog_save_file: ['options'],
     export_logs: [],
+    operations_list: [],
+    operation_cancel: ['id'],
+    scan_dropped_directory: ['dirPath'],
+
+    // Local file workspace commands
+    workspace_open_local_file: ['filePath', 'readOnly'],
+    workspace_get_recent_files: [],
+    workspace_remove_recent_file: ['filePath'],
+    workspace_watch_file: ['filePath'],
+    workspace_unwatch_file: ['filePath'],
   }
   return paramMap[command] || []
 }
@@ -382,7 +597,7 @@ export const api = {
   getIOSDeviceDatabaseFilesNew: (deviceId: string, applicationId: string) =>
     invokeCommandWithResponse('device:getIOSDeviceDatabaseFilesNew', 'files', deviceId, applicationId),
 
-  pushDatabaseFile: async (deviceId: string, localPath: string, packageName: string, remotePath: string, deviceType?: string) => {
+  pushDatabaseFile: async (deviceId: string, localPath: string, packageName: string, remotePath: string, deviceType?: string, userId?: number, verify?: boolean, stopApp?: boolean) => {
     // Validate required parameters
     validateInput(deviceId, 'deviceId', { required: true, type: 'string', maxLength: 100 })
     validateInput(localPath, 'localPath', { required: true, type: 'string', maxLength: 500 })
@@ -423,13 +638,13 @@ export const api = {
 
     // Use appropriate command based on device type
     if (deviceType === 'android') {
-      return invokeCommandWithResponse('adb:pushDatabaseFile', 'result', deviceId, localPath, packageName, remotePath)
+      return invokeCommandWithResponse('adb:pushDatabaseFile', 'result', deviceId, localPath, packageName, remotePath, userId, verify, stopApp)
     }
     else if (deviceType.includes('simulator')) {
-      return invokeCommandWithResponse('simulator:uploadSimulatorIOSDbFile', 'result', deviceId, localPath, packageName, remotePath)
+      return invokeCommandWithResponse('simulator:uploadSimulatorIOSDbFile', 'result', deviceId, localPath, packageName, remotePath, verify, stopApp)
     }
     else {
-      return invokeCommandWithResponse('device:pushIOSDbFile', 'result', deviceId, localPath, packageName, remotePath)
+      return invokeCommandWithResponse('device:pushIOSDbFile', 'result', deviceId, localPath, packageName, remotePath, verify, stopApp)
     }
   },
 
@@ -450,11 +665,12 @@ export const api = {
     deviceType?: string,
     packageName?: string,
     appName?: string,
+    dryRun?: boolean,
   ) =>
-    invokeCommandWithResponse('db:updateTableRow', 'result', tableName, row, condition, dbPath, deviceId, deviceName, deviceType, packageName, appName),
+    invokeCommandWithResponse('db:updateTableRow', 'result', tableName, row, condition, dbPath, deviceId, deviceName, deviceType, packageName, appName, dryRun),
 
-  executeQuery: (query: string, dbPath: string) =>
-    invokeCommandWithResponse('db:executeQuery', 'result', query, dbPath),
+  executeQuery: (query: string, dbPath: string, dryRun?: boolean, profile?: boolean) =>
+    invokeCommandWithResponse('db:executeQuery', 'result', query, dbPath, undefined, dryRun, profile),
 
   insertTableRow: (
     tableName: string,
@@ -488,8 +704,9 @@ export const api = {
     deviceType?: string,
     packageName?: string,
     appName?: string,
+    dryRun?: boolean,
   ) =>
-    invokeCommandWithResponse('db:deleteTableRow', 'result', tableName, condition, dbPath, deviceId, deviceName, deviceType, packageName, appName),
+    invokeCommandWithResponse('db:deleteTableRow', 'result', tableName, condition, dbPath, deviceId, deviceName, deviceType, packageName, appName, dryRun),
 
   clearTable: (
     tableName: string,
@@ -502,6 +719,157 @@ export const api = {
   ) =>
     invokeCommandWithResponse('db:clearTable', 'result', tableName, dbPath, deviceId, deviceName, deviceType, packageName, appName),
 
+  compareQuery: (query: string, leftDbPath: string, rightDbPath: string, keyColumns?: string[]) =>
+    invokeCommandWithResponse('db:compareQuery', 'comparison', query, leftDbPath, rightDbPath, keyColumns),
+
+  generateMigration: (sourceDbPath: string, targetDbPath: string) =>
+    invokeCommandWithResponse('db:generateMigration', 'migration', sourceDbPath, targetDbPath),
+
+  snapshotDiff: (a: string, b: string) =>
+    invokeCommandWithResponse('db:snapshotDiff', 'diff', a, b),
+
+  threeWayMerge: (basePath: string, localPath: string, remotePath: string, mergedPath?: string, resolutions?: ConflictResolution[]) =>
+    invokeCommandWithResponse('db:threeWayMerge', 'merge', basePath, localPath, remotePath, mergedPath, resolutions),
+
+  replayChangesOnto: (contextKey: string, targetPath: string, changeIds?: string[]) =>
+    invokeCommandWithResponse('db:replayChangesOnto', 'replay', contextKey, targetPath, changeIds),
+
+  aggregateForChart: (
+    query: string,
+    spec: {
+      xColumn: string
+      y: Array<{ function: 'count' | 'sum' | 'avg' | 'min' | 'max', column?: string }>
+      groupBy?: string
+      bins?: number
+    },
+    currentDbPath?: string,
+  ) =>
+    invokeCommandWithResponse('db:aggregateForChart', 'chart', query, spec, currentDbPath),
+
+  scanPii: (sampleSize?: number, currentDbPath?: string) =>
+    invokeCommandWithResponse('db:scanPii', 'report', sampleSize, currentDbPath),
+
+  analyzeDeleteImpact: (tableName: string, condition: string, currentDbPath?: string) =>
+    invokeCommandWithResponse('db:analyzeDeleteImpact', 'report', tableName, condition, currentDbPath),
+
+  listTriggers: (tableName?: string, currentDbPath?: string) =>
+    invokeCommandWithResponse('db:listTriggers', 'triggers', tableName, currentDbPath),
+
+  disableTriggers: (tableName?: string, currentDbPath?: string) =>
+    invokeCommandWithResponse('db:disableTriggers', 'triggers', tableName, currentDbPath),
+
+  restoreTriggers: (tableName?: string, currentDbPath?: string) =>
+    invokeCommandWithResponse('db:restoreTriggers', 'result', tableName, currentDbPath),
+
+  exportSchemaDiagram: (format: 'dot' | 'mermaid', currentDbPath?: string) =>
+    invokeCommandWithResponse('db:exportSchemaDiagram', 'diagram', format, currentDbPath),
+
+  lintQuery: (query: string, currentDbPath?: string) =>
+    invokeCommandWithResponse('db:lintQuery', 'analysis', query, currentDbPath),
+
+  convertTimestamp: (value: number | string, fromFormat: string, toFormat: string, timezone?: string) =>
+    invokeCommandWithResponse('db:convertTimestamp', 'conversion', value, fromFormat, toFormat, timezone),
+
+  listValueTransformers: (tableName?: string, currentDbPath?: string) =>
+    invokeCommandWithResponse('db:listValueTransformers', 'transformers', tableName, currentDbPath),
+
+  setValueTransformer: (tableName: string, columnName: string, transformer: { kind: 'hex' | 'uuid' | 'protobuf' | 'flags', [key: string]: unknown } | null, currentDbPath?: string) =>
+    invokeCommandWithResponse('db:setValueTransformer', 'result', tableName, columnName, transformer, currentDbPath),
+
+  decompressCell: (tableName: string, columnName: string, rowId: number, currentDbPath?: string) =>
+    invokeCommandWithResponse('db:decompressCell', 'cell', tableName, columnName, rowId, currentDbPath),
+
+  recompressCell: (tableName: string, columnName: string, rowId: number, content: string, format?: 'gzip' | 'zlib', currentDbPath?: string) =>
+    invokeCommandWithResponse('db:recompressCell', 'rowsAffected', tableName, columnName, rowId, content, format, currentDbPath),
+
+  extractArchiveDatabases: (archivePath: string) =>
+    invokeCommandWithResponse('db:extractArchiveDatabases', 'databases', archivePath),
+
+  exportRows: (
+    tableName: string,
+    format: 'csv' | 'json' | 'xlsx',
+    selection: { keyColumn?: string, keyValues?: unknown[], filter?: RowFilter },
+    outputPath?: string,
+    currentDbPath?: string,
+  ) =>
+    invokeCommandWithResponse(
+      'db:exportRows',
+      'result',
+      tableName,
+      format,
+      selection.keyColumn,
+      selection.keyValues,
+      selection.filter,
+      outputPath,
+      currentDbPath,
+    ),
+
+  exportData: (
+    format: 'csv' | 'json' | 'xlsx',
+    source: { tableName?: string, query?: string },
+    outputPath?: string,
+    currentDbPath?: string,
+  ) =>
+    invokeCommandWithResponse('db:exportData', 'result', format, source.tableName, source.query, outputPath, currentDbPath),
+
+  exportTableParquet: (tableName: string, outputPath?: string, currentDbPath?: string) =>
+    invokeCommandWithResponse('db:exportTableParquet', 'result', tableName, outputPath, currentDbPath),
+
+  copyRowsAs: (
+    format: 'markdown' | 'json' | 'csv' | 'insert',
+    columns: string[],
+    rows: Record<string, unknown>[],
+    tableName?: string,
+    currentDbPath?: string,
+  ) =>
+    invokeCommandWithResponse('db:copyRowsAs', 'text', format, columns, rows, tableName, currentDbPath),
+
+  generateInserts: (
+    tableName: string,
+    selection: { keyColumn?: string, keyValues?: unknown[], filter?: RowFilter },
+    includeCreateTable?: boolean,
+    currentDbPath?: string,
+  ) =>
+    invokeCommandWithResponse(
+      'db:generateInserts',
+      'sql',
+      tableName,
+      selection.keyColumn,
+      selection.keyValues,
+      selection.filter,
+      includeCreateTable,
+      currentDbPath,
+    ),
+
+  generateTestData: (tableName: string, rowCount: number, seed?: number, currentDbPath?: string) =>
+    invokeCommandWithResponse('db:generateTestData', 'result', tableName, rowCount, seed, currentDbPath),
+
+  bulkUpdate: (
+    tableName: string,
+    assignments: Record<string, unknown>,
+    filter: RowFilter,
+    dryRun?: boolean,
+    currentDbPath?: string,
+    deviceContext?: { deviceId?: string, deviceName?: string, deviceType?: string, packageName?: string, appName?: string },
+  ) =>
+    invokeCommandWithResponse(
+      'db:bulkUpdate',
+      'result',
+      tableName,
+      assignments,
+      filter,
+      dryRun,
+      currentDbPath,
+      deviceContext?.deviceId,
+      deviceContext?.deviceName,
+      deviceContext?.deviceType,
+      deviceContext?.packageName,
+      deviceContext?.appName,
+    ),
+
+  openCopy: (filePath: string) =>
+    invokeCommandWithResponse('db:openCopy', 'copy', filePath),
+
   // Change history methods
   getChangeHistory: async (contextKey: string, tableName?: string) => {
     console.log('🔍 [API] getChangeHistory called with:', { contextKey, tableName })
@@ -530,6 +898,12 @@ export const api = {
   clearAllChangeHistory: () =>
     invokeCommandWithResponse('db:clearAllChangeHistory', 'result'),
 
+  subscribeChangeHistory: (contextKey: string) =>
+    invokeCommandWithResponse('db:subscribeChangeHistory', 'data', contextKey),
+
+  unsubscribeChangeHistory: (contextKey: string) =>
+    invokeCommandWithResponse('db:unsubscribeChangeHistory', 'result', contextKey),
+
   generateCustomFileContextKey: (databasePath: string) =>
     invokeCommandWithResponse('db:generateCustomFileContextKey', 'data', databasePath),
 
@@ -586,6 +960,29 @@ export const api = {
     }
   },
 
+  // Long-running operations
+  listOperations: () =>
+    invokeCommandWithResponse('operations:list', 'operations'),
+
+  cancelOperation: (id: string) =>
+    invokeCommandWithResponse('operations:cancel', 'cancelled', id),
+
+  // Local file workspace methods
+  openLocalFile: (filePath: string, readOnly?: boolean) =>
+    invokeCommandWithResponse('workspace:openLocalFile', 'file', filePath, readOnly),
+
+  getRecentFiles: () =>
+    invokeCommandWithResponse('workspace:getRecentFiles', 'files'),
+
+  removeRecentFile: (filePath?: string) =>
+    invokeCommandWithResponse('workspace:removeRecentFile', 'files', filePath),
+
+  watchLocalFile: (filePath: string) =>
+    invokeCommandWithResponse('workspace:watchFile', 'result', filePath),
+
+  unwatchLocalFile: (filePath: string) =>
+    invokeCommandWithResponse('workspace:unwatchFile', 'result', filePath),
+
   // Virtual device methods
   getAndroidEmulators: () => invokeCommandWithResponse('getAndroidEmulators', 'emulators'),
 
@@ -597,6 +994,59 @@ export const api = {
   launchIOSSimulator: (simulatorId: string) =>
     invokeCommandWithResponse('launchIOSSimulator', 'result', simulatorId),
 
+  // Diagnostics methods
+  exportDiagnosticsBundle: (outputPath?: string, filter?: { operationId?: string, device?: string }) =>
+    invokeCommandWithResponse('diagnostics:exportBundle', 'bundle', outputPath, filter),
+
+  runHealthCheck: () =>
+    invokeCommandWithResponse('diagnostics:healthCheck', 'report'),
+
+  listCrashReports: () =>
+    invokeCommandWithResponse('crash:listReports', 'reports'),
+
+  clearCrashReports: () =>
+    invokeCommandWithResponse('crash:clearReports', 'removed'),
+
+  uploadCrashReports: () =>
+    invokeCommandWithResponse('crash:uploadReports', 'uploaded'),
+
+  getTelemetryUsage: () =>
+    invokeCommandWithResponse('telemetry:getUsage', 'usage'),
+
+  purgeTelemetry: () =>
+    invokeCommandWithResponse('telemetry:purge', 'purged'),
+
+  uploadTelemetry: () =>
+    invokeCommandWithResponse('telemetry:upload', 'uploaded'),
+
+  getLogLevel: () =>
+    invokeCommandWithResponse('diagnostics:getLogLevel', 'filter'),
+
+  setLogLevel: (filter: string) =>
+    invokeCommandWithResponse('diagnostics:setLogLevel', 'filter', filter),
+
+  setFullLogging: (enabled: boolean) =>
+    invokeCommandWithResponse('diagnostics:setFullLogging', 'enabled', enabled),
+
+  // Settings
+  getSettings: () =>
+    invokeCommandWithResponse('settings:get', 'settings'),
+
+  setSettings: (settings: Record<string, unknown>) =>
+    invokeCommandWithResponse('settings:set', 'settings', settings),
+
+  getInterruptedOperations: () =>
+    invokeCommandWithResponse('journal:getInterruptedOperations', 'operations'),
+
+  resolveInterruptedOperation: (id: string, discardLocalFile?: boolean) =>
+    invokeCommandWithResponse('journal:resolveInterruptedOperation', 'operation', id, discardLocalFile),
+
+  getSafeModeStatus: () =>
+    invokeCommandWithResponse('safeMode:getStatus', 'status'),
+
+  confirmDangerousOperation: (operation: 'drop' | 'deleteAll' | 'clearTable' | 'pushDatabase') =>
+    invokeCommandWithResponse('safeMode:confirmDangerousOperation', 'status', operation),
+
   // Auto-updater methods
   checkForUpdates: async () => {
     try {
@@ -616,11 +1066,12 @@ export const api = {
     }
   },
 
-  downloadAndInstallUpdate: async () => {
+  downloadAndInstallUpdate: async (installOnRestart?: boolean) => {
     try {
-      const response = await invokeTauriCommand<any>('download_and_install_update')
+      const response = await invokeTauriCommand<any>('download_and_install_update', { installOnRestart })
       return {
         success: response.success,
+        version: response.data?.version,
         error: response.error,
       }
     }
@@ -630,6 +1081,81 @@ export const api = {
     }
   },
 
+  getUpdateChangelog: async () => {
+    try {
+      const response = await invokeTauriCommand<any>('get_update_changelog')
+      return {
+        success: response.success,
+        data: response.data,
+        error: response.error,
+      }
+    }
+    catch (error) {
+      console.error('Error fetching update changelog:', error)
+      return { success: false, error: (error as Error).message }
+    }
+  },
+
+  selectUpdateBundle: async () => {
+    try {
+      const response = await invokeTauriCommand<any>('dialog_select_file', {
+        options: {
+          filters: [{ name: 'Update Bundles', extensions: ['gz', 'msi', 'exe', 'AppImage'] }],
+        },
+      })
+      return {
+        canceled: response?.canceled || false,
+        filePath: response?.file_path as string | undefined,
+      }
+    }
+    catch (error) {
+      console.error('Error selecting update bundle:', error)
+      return { canceled: true }
+    }
+  },
+
+  installUpdateFromFile: async (bundlePath: string, signaturePath?: string, allowDowngrade?: boolean) => {
+    try {
+      const response = await invokeTauriCommand<any>('install_update_from_file', { bundlePath, signaturePath, allowDowngrade })
+      return {
+        success: response.success,
+        error: response.error,
+      }
+    }
+    catch (error) {
+      console.error('Error installing update from file:', error)
+      return { success: false, error: (error as Error).message }
+    }
+  },
+
+  cancelUpdateDownload: async () => {
+    try {
+      const response = await invokeTauriCommand<any>('cancel_update_download')
+      return {
+        success: response.success,
+        error: response.error,
+      }
+    }
+    catch (error) {
+      console.error('Error cancelling update download:', error)
+      return { success: false, error: (error as Error).message }
+    }
+  },
+
+  installStagedUpdate: async () => {
+    try {
+      const response = await invokeTauriCommand<any>('install_staged_update')
+      return {
+        success: response.success,
+        error: response.error,
+      }
+    }
+    catch (error) {
+      console.error('Error installing update:', error)
+      return { success: false, error: (error as Error).message }
+    }
+  },
+
   // Device info methods
   adbGetDeviceInfo: async (deviceId: string) => {
     try {
@@ -646,6 +1172,127 @@ export const api = {
     }
   },
 
+  adbPullDataDirectory: (deviceId: string, packageName: string, remotePath: string) =>
+    invokeCommandWithResponse('adb:pullDataDirectory', 'path', deviceId, packageName, remotePath),
+
+  getAppPreferenceFiles: (deviceId: string, packageName: string, deviceType: string) =>
+    invokeCommandWithResponse('device:getAppPreferenceFiles', 'files', deviceId, packageName, deviceType),
+
+  pullAppPreferences: (deviceId: string, packageName: string, deviceType: string, remotePath: string) =>
+    invokeCommandWithResponse('device:pullAppPreferences', 'preferences', deviceId, packageName, deviceType, remotePath),
+
+  getPulledFileMetadata: (localPath: string) =>
+    invokeCommandWithResponse('device:getPulledFileMetadata', 'metadata', localPath),
+
+  checkPulledFileStaleness: (localPath: string, deviceType: string) =>
+    invokeCommandWithResponse('device:checkPulledFileStaleness', 'staleness', localPath, deviceType),
+
+  getTempDirUsage: () =>
+    invokeCommandWithResponse('device:getTempDirUsage', 'usage'),
+
+  setTempDirQuota: (maxBytes: number) =>
+    invokeCommandWithResponse('device:setTempDirQuota', 'usage', maxBytes),
+
+  checkEnvironment: () =>
+    invokeCommandWithResponse('device:checkEnvironment', 'report'),
+
+  installWindowsDependencies: (dependencies?: Array<'platformTools' | 'appleMobileDeviceSupport'>) =>
+    invokeCommandWithResponse('device:installWindowsDependencies', 'result', dependencies),
+
+  checkToolQuarantine: () =>
+    invokeCommandWithResponse('device:checkToolQuarantine', 'tools'),
+
+  clearToolQuarantine: () =>
+    invokeCommandWithResponse('device:clearToolQuarantine', 'tools'),
+
+  getPlatformCapabilities: () =>
+    invokeCommandWithResponse('device:getPlatformCapabilities', 'capabilities'),
+
+  getDeviceCapabilities: (deviceId: string, refresh?: boolean) =>
+    invokeCommandWithResponse('device:getDeviceCapabilities', 'capabilities', deviceId, refresh),
+
+  favoritesGet: () =>
+    invokeCommandWithResponse('device:favoritesGet', 'favorites'),
+
+  bookmarkDatabase: (deviceType: string, deviceId: string, packageName: string, remotePath: string, name?: string) =>
+    invokeCommandWithResponse('device:bookmarkDatabase', 'bookmark', deviceType, deviceId, packageName, remotePath, name),
+
+  bookmarksList: (deviceId?: string, packageName?: string) =>
+    invokeCommandWithResponse('device:bookmarksList', 'bookmarks', deviceId, packageName),
+
+  bookmarkDelete: (bookmarkId: string) =>
+    invokeCommandWithResponse('device:bookmarkDelete', 'deleted', bookmarkId),
+
+  repullBookmark: (bookmarkId: string) =>
+    invokeCommandWithResponse('device:repullBookmark', 'result', bookmarkId),
+
+  pullAndOpenDeviceDatabase: (deviceType: string, deviceId: string, packageName: string, remotePath: string) =>
+    invokeCommandWithResponse('device:pullAndOpenDeviceDatabase', 'result', deviceType, deviceId, packageName, remotePath),
+
+  saveAndPush: (deviceType: string, deviceId: string, packageName: string, localPath: string, remotePath: string, userId?: number) =>
+    invokeCommandWithResponse('device:saveAndPush', 'result', deviceType, deviceId, packageName, localPath, remotePath, userId),
+
+  mergeAndPush: (deviceType: string, deviceId: string, packageName: string, localPath: string, remotePath: string, userId?: number, resolutions?: ConflictResolution[]) =>
+    invokeCommandWithResponse('device:mergeAndPush', 'result', deviceType, deviceId, packageName, localPath, remotePath, userId, resolutions),
+
+  favoritesPin: (kind: 'device' | 'package', id: string, nickname?: string) =>
+    invokeCommandWithResponse('device:favoritesPin', 'favorites', kind, id, nickname),
+
+  favoritesUnpin: (kind: 'device' | 'package', id: string) =>
+    invokeCommandWithResponse('device:favoritesUnpin', 'favorites', kind, id),
+
+  favoritesReorder: (kind: 'device' | 'package', ids: string[]) =>
+    invokeCommandWithResponse('device:favoritesReorder', 'favorites', kind, ids),
+
+  getLastPushHookResults: () =>
+    invokeCommandWithResponse('device:getLastPushHookResults', 'results'),
+
+  schedulePullCreate: (
+    deviceType: string,
+    deviceId: string,
+    packageName: string,
+    remotePath: string,
+    intervalMinutes: number,
+    maxSnapshots?: number,
+  ) =>
+    invokeCommandWithResponse('device:schedulePullCreate', 'schedule', deviceType, deviceId, packageName, remotePath, intervalMinutes, maxSnapshots),
+
+  schedulePullList: () =>
+    invokeCommandWithResponse('device:schedulePullList', 'schedules'),
+
+  schedulePullDelete: (scheduleId: string, deleteSnapshots?: boolean) =>
+    invokeCommandWithResponse('device:schedulePullDelete', 'deleted', scheduleId, deleteSnapshots),
+
+  inspectAppBundle: (bundlePath: string) =>
+    invokeCommandWithResponse('device:inspectAppBundle', 'files', bundlePath),
+
+  installApk: (deviceId: string, apkPath: string, allowDowngrade?: boolean, grantPermissions?: boolean) =>
+    invokeCommandWithResponse('adb:installApk', 'result', deviceId, apkPath, allowDowngrade, grantPermissions),
+
+  installIpa: (deviceId: string, ipaPath: string, deviceType: string) =>
+    invokeCommandWithResponse('device:installIpa', 'result', deviceId, ipaPath, deviceType),
+
+  iosLaunchApp: (deviceId: string, bundleId: string) =>
+    invokeCommandWithResponse('ios:iosLaunchApp', 'result', deviceId, bundleId),
+
+  iosTerminateApp: (deviceId: string, bundleId: string) =>
+    invokeCommandWithResponse('ios:iosTerminateApp', 'result', deviceId, bundleId),
+
+  deviceSessionStart: (deviceId: string, deviceType: string, intervalSeconds?: number) =>
+    invokeCommandWithResponse('device:deviceSessionStart', 'session', deviceId, deviceType, intervalSeconds),
+
+  deviceSessionStop: (deviceId: string) =>
+    invokeCommandWithResponse('device:deviceSessionStop', 'result', deviceId),
+
+  deviceSessionList: () =>
+    invokeCommandWithResponse('device:deviceSessionList', 'sessions'),
+
+  iosPairDevice: (deviceId: string) =>
+    invokeCommandWithResponse('ios:iosPairDevice', 'result', deviceId),
+
+  iosValidatePairing: (deviceId: string) =>
+    invokeCommandWithResponse('ios:iosValidatePairing', 'result', deviceId),
+
   iosGetDeviceInfo: async (deviceId: string) => {
     try {
       const response = await invokeCommandWithResponse('ios:getDeviceInfo', 'data', deviceId)
@@ -661,6 +1308,18 @@ export const api = {
     }
   },
 
+  // Scan a dropped folder (e.g. an exported app container) for database files
+  scanDroppedDirectory: async (dirPath: string) => {
+    try {
+      const files = await invokeTauriCommand<any[]>('scan_dropped_directory', { dirPath })
+      return { success: true, files }
+    }
+    catch (error) {
+      console.error('Error scanning dropped directory:', error)
+      return { success: false, error: (error as Error).message }
+    }
+  },
+
   // Add webUtils placeholder for compatibility
   webUtils: {
     getPathForFile: async (file: File) => {
@@ -146,6 +146,8 @@ beforeAll(() => {
     launchIOSSimulator: vi.fn(),
     checkForUpdates: vi.fn(),
     downloadAndInstallUpdate: vi.fn(),
+    deviceSessionStart: vi.fn().mockResolvedValue({ success: true }),
+    deviceSessionStop: vi.fn().mockResolvedValue({ success: true }),
   }
 })
 
@@ -0,0 +1,31 @@
+export type FilterOperator =
+  | 'eq'
+  | 'notEq'
+  | 'lt'
+  | 'lte'
+  | 'gt'
+  | 'gte'
+  | 'like'
+  | 'notLike'
+  | 'in'
+  | 'notIn'
+  | 'isNull'
+  | 'isNotNull'
+
+export interface FilterCondition {
+  column: string
+  operator: FilterOperator
+  value?: unknown
+  values?: unknown[]
+}
+
+export interface RowFilter {
+  conditions: FilterCondition[]
+  combinator?: 'and' | 'or'
+}
+
+export interface ConflictResolution {
+  table: string
+  key: Record<string, unknown>
+  keep: 'local' | 'remote'
+}
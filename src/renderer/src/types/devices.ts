@@ -1,19 +1,59 @@
 type DeviceType = 'android' | 'iphone' | 'desktop' | 'iphone-device' | 'emulator' | 'simulator'
+export type DatabaseFileType = 'sqlite' | 'realm' | 'leveldb'
 export interface DeviceInfo {
   deviceType: DeviceType
   id: string
   model: string
   name: string
+  // Backend-sanitized name for display; `name` is the raw value from the device tools
+  displayName?: string
   label?: string
   description?: string
   platform?: string
+  apiLevel?: number
+  abi?: string
+  buildFingerprint?: string
+  // adb connection state; anything other than 'device' cannot be used yet
+  state?: string
+  pinned?: boolean
+  nickname?: string
+  sortOrder?: number
+}
+
+// What the backend can do with a device, from get_device_capabilities
+export interface DeviceCapabilities {
+  deviceId: string
+  deviceType: string
+  runAs: boolean
+  root: boolean
+  sharedStorage: boolean
+  afcDocuments: boolean
+  appContainers: boolean
+  fullDisk: boolean
+  installApps: boolean
+  launchApps: boolean
+  checkedAt: string
 }
 
 export interface ApplicationSelection {
   bundleId: string
   name: string
+  // Set for packages the user pinned, which the backend lists first
+  pinned?: boolean
+  nickname?: string
+  sortOrder?: number
 }
 
+// Result of the background quick_check the backend runs after each pull
+export interface PullIntegrity {
+  status: 'pending' | 'ok' | 'corrupt' | 'skipped'
+  detail?: string
+  checkedAt?: string
+}
+
+// How the backend got the file off the device; later ones are fallbacks for refused pulls
+export type PullStrategy = 'runAs' | 'root' | 'adbPull' | 'afcContainer' | 'afcDocuments'
+
 export interface DatabaseFile {
   path: string
   filename: string
@@ -21,6 +61,9 @@ export interface DatabaseFile {
   location: string
   remotePath?: string
   deviceType?: DeviceType
+  fileType?: DatabaseFileType
+  integrity?: PullIntegrity
+  pullStrategy?: PullStrategy
 }
 
 export interface DatabaseTable {
@@ -1,5 +1,6 @@
 import type { DatabaseApi, ExportFileOptions, OpenFileResult } from '@renderer/api/databases'
 import type { CancelIOSDeviceDatabaseScanResult, DeviceApi, GetDevicesResult } from '@renderer/api/devices'
+import type { ConflictResolution, RowFilter } from './database'
 
 // import type { WebUtils } from 'electron' // Not used in Tauri
 
@@ -35,11 +36,49 @@ declare global {
     // electron: Electron // Not used in Tauri
     api: DeviceApi & DatabaseApi & {
       // Device operations
-      getDevices: () => Promise<GetDevicesResult>
+      getDevices: (forceRefresh?: boolean) => Promise<GetDevicesResult>
       cancelIOSDeviceDatabaseScan: (scanKey: string) => Promise<CancelIOSDeviceDatabaseScanResult>
       refreshIOSDeviceDatabaseFile: (deviceId: string, packageName: string, remotePath: string) => Promise<any>
       uploadIOSDbFile: (deviceId: string, packageName: string, localFilePath: string, remoteLocation: string) => Promise<any>
-      pushDatabaseFile: (deviceId: string, localPath: string, packageName: string, remotePath: string, deviceType?: string) => Promise<any>
+      pushDatabaseFile: (deviceId: string, localPath: string, packageName: string, remotePath: string, deviceType?: string, userId?: number, verify?: boolean, stopApp?: boolean) => Promise<any>
+      adbPullDataDirectory: (deviceId: string, packageName: string, remotePath: string) => Promise<any>
+      getAppPreferenceFiles: (deviceId: string, packageName: string, deviceType: string) => Promise<any>
+      pullAppPreferences: (deviceId: string, packageName: string, deviceType: string, remotePath: string) => Promise<any>
+      getPulledFileMetadata: (localPath: string) => Promise<any>
+      checkPulledFileStaleness: (localPath: string, deviceType: string) => Promise<any>
+      getTempDirUsage: () => Promise<any>
+      setTempDirQuota: (maxBytes: number) => Promise<any>
+      checkEnvironment: () => Promise<any>
+      installWindowsDependencies: (dependencies?: Array<'platformTools' | 'appleMobileDeviceSupport'>) => Promise<any>
+      checkToolQuarantine: () => Promise<any>
+      clearToolQuarantine: () => Promise<any>
+      getPlatformCapabilities: () => Promise<any>
+      getDeviceCapabilities: (deviceId: string, refresh?: boolean) => Promise<any>
+      favoritesGet: () => Promise<any>
+      bookmarkDatabase: (deviceType: string, deviceId: string, packageName: string, remotePath: string, name?: string) => Promise<any>
+      bookmarksList: (deviceId?: string, packageName?: string) => Promise<any>
+      bookmarkDelete: (bookmarkId: string) => Promise<any>
+      repullBookmark: (bookmarkId: string) => Promise<any>
+      pullAndOpenDeviceDatabase: (deviceType: string, deviceId: string, packageName: string, remotePath: string) => Promise<any>
+      saveAndPush: (deviceType: string, deviceId: string, packageName: string, localPath: string, remotePath: string, userId?: number) => Promise<any>
+      mergeAndPush: (deviceType: string, deviceId: string, packageName: string, localPath: string, remotePath: string, userId?: number, resolutions?: ConflictResolution[]) => Promise<any>
+      favoritesPin: (kind: 'device' | 'package', id: string, nickname?: string) => Promise<any>
+      favoritesUnpin: (kind: 'device' | 'package', id: string) => Promise<any>
+      favoritesReorder: (kind: 'device' | 'package', ids: string[]) => Promise<any>
+      getLastPushHookResults: () => Promise<any>
+      schedulePullCreate: (deviceType: string, deviceId: string, packageName: string, remotePath: string, intervalMinutes: number, maxSnapshots?: number) => Promise<any>
+      schedulePullList: () => Promise<any>
+      schedulePullDelete: (scheduleId: string, deleteSnapshots?: boolean) => Promise<any>
+      inspectAppBundle: (bundlePath: string) => Promise<any>
+      installApk: (deviceId: string, apkPath: string, allowDowngrade?: boolean, grantPermissions?: boolean) => Promise<any>
+      installIpa: (deviceId: string, ipaPath: string, deviceType: string) => Promise<any>
+      iosLaunchApp: (deviceId: string, bundleId: string) => Promise<any>
+      iosTerminateApp: (deviceId: string, bundleId: string) => Promise<any>
+      deviceSessionStart: (deviceId: string, deviceType: string, intervalSeconds?: number) => Promise<any>
+      deviceSessionStop: (deviceId: string) => Promise<any>
+      deviceSessionList: () => Promise<any>
+      iosPairDevice: (deviceId: string) => Promise<any>
+      iosValidatePairing: (deviceId: string) => Promise<any>
 
       // Database methods
       updateTableRow: (
@@ -51,9 +90,10 @@ declare global {
         deviceName?: string,
         deviceType?: string,
         packageName?: string,
-        appName?: string
+        appName?: string,
+        dryRun?: boolean
       ) => Promise<any>
-      executeQuery: (query: string, dbPath: string) => Promise<any>
+      executeQuery: (query: string, dbPath: string, dryRun?: boolean, profile?: boolean) => Promise<any>
       insertTableRow: (
         table: string, 
         row: any, 
@@ -81,7 +121,8 @@ declare global {
         deviceName?: string,
         deviceType?: string,
         packageName?: string,
-        appName?: string
+        appName?: string,
+        dryRun?: boolean
       ) => Promise<any>
       clearTable: (
         tableName: string,
@@ -92,6 +133,67 @@ declare global {
         packageName?: string,
         appName?: string
       ) => Promise<any>
+      compareQuery: (
+        query: string,
+        leftDbPath: string,
+        rightDbPath: string,
+        keyColumns?: string[]
+      ) => Promise<any>
+      generateMigration: (sourceDbPath: string, targetDbPath: string) => Promise<any>
+      snapshotDiff: (a: string, b: string) => Promise<any>
+      threeWayMerge: (basePath: string, localPath: string, remotePath: string, mergedPath?: string, resolutions?: ConflictResolution[]) => Promise<any>
+      replayChangesOnto: (contextKey: string, targetPath: string, changeIds?: string[]) => Promise<any>
+      aggregateForChart: (query: string, spec: any, currentDbPath?: string) => Promise<any>
+      scanPii: (sampleSize?: number, currentDbPath?: string) => Promise<any>
+      analyzeDeleteImpact: (tableName: string, condition: string, currentDbPath?: string) => Promise<any>
+      listTriggers: (tableName?: string, currentDbPath?: string) => Promise<any>
+      disableTriggers: (tableName?: string, currentDbPath?: string) => Promise<any>
+      restoreTriggers: (tableName?: string, currentDbPath?: string) => Promise<any>
+      exportSchemaDiagram: (format: 'dot' | 'mermaid', currentDbPath?: string) => Promise<any>
+      lintQuery: (query: string, currentDbPath?: string) => Promise<any>
+      convertTimestamp: (value: number | string, fromFormat: string, toFormat: string, timezone?: string) => Promise<any>
+      listValueTransformers: (tableName?: string, currentDbPath?: string) => Promise<any>
+      setValueTransformer: (tableName: string, columnName: string, transformer: { kind: 'hex' | 'uuid' | 'protobuf' | 'flags', [key: string]: unknown } | null, currentDbPath?: string) => Promise<any>
+      decompressCell: (tableName: string, columnName: string, rowId: number, currentDbPath?: string) => Promise<any>
+      recompressCell: (tableName: string, columnName: string, rowId: number, content: string, format?: 'gzip' | 'zlib', currentDbPath?: string) => Promise<any>
+      extractArchiveDatabases: (archivePath: string) => Promise<any>
+      exportRows: (
+        tableName: string,
+        format: 'csv' | 'json' | 'xlsx',
+        selection: { keyColumn?: string, keyValues?: unknown[], filter?: RowFilter },
+        outputPath?: string,
+        currentDbPath?: string,
+      ) => Promise<any>
+      exportData: (
+        format: 'csv' | 'json' | 'xlsx',
+        source: { tableName?: string, query?: string },
+        outputPath?: string,
+        currentDbPath?: string,
+      ) => Promise<any>
+      exportTableParquet: (tableName: string, outputPath?: string, currentDbPath?: string) => Promise<any>
+      copyRowsAs: (
+        format: 'markdown' | 'json' | 'csv' | 'insert',
+        columns: string[],
+        rows: Record<string, unknown>[],
+        tableName?: string,
+        currentDbPath?: string,
+      ) => Promise<any>
+      generateInserts: (
+        tableName: string,
+        selection: { keyColumn?: string, keyValues?: unknown[], filter?: RowFilter },
+        includeCreateTable?: boolean,
+        currentDbPath?: string,
+      ) => Promise<any>
+      generateTestData: (tableName: string, rowCount: number, seed?: number, currentDbPath?: string) => Promise<any>
+      bulkUpdate: (
+        tableName: string,
+        assignments: Record<string, unknown>,
+        filter: RowFilter,
+        dryRun?: boolean,
+        currentDbPath?: string,
+        deviceContext?: { deviceId?: string, deviceName?: string, deviceType?: string, packageName?: string, appName?: string },
+      ) => Promise<any>
+      openCopy: (filePath: string) => Promise<any>
 
       // Change history methods
       getChangeHistory: (contextKey: string, tableName?: string) => Promise<any>
@@ -99,6 +201,8 @@ declare global {
       getChangeHistoryDiagnostics: () => Promise<any>
       clearContextChanges: (contextKey: string) => Promise<any>
       clearAllChangeHistory: () => Promise<any>
+      subscribeChangeHistory: (contextKey: string) => Promise<any>
+      unsubscribeChangeHistory: (contextKey: string) => Promise<any>
 
       // File dialog methods
       openFile: () => Promise<OpenFileResult>
@@ -112,6 +216,16 @@ declare global {
         }>
       }) => Promise<string | null>
       exportLogs: () => Promise<string | null>
+      listOperations: () => Promise<any>
+      cancelOperation: (id: string) => Promise<any>
+      scanDroppedDirectory: (dirPath: string) => Promise<any>
+
+      // Local file workspace methods
+      openLocalFile: (filePath: string, readOnly?: boolean) => Promise<any>
+      getRecentFiles: () => Promise<any>
+      removeRecentFile: (filePath?: string) => Promise<any>
+      watchLocalFile: (filePath: string) => Promise<any>
+      unwatchLocalFile: (filePath: string) => Promise<any>
       webUtils: any // Changed from WebUtils to any
 
       // Virtual device methods
@@ -147,6 +261,25 @@ declare global {
         error?: string
       }>
 
+      // Diagnostics methods
+      exportDiagnosticsBundle: (outputPath?: string, filter?: { operationId?: string, device?: string }) => Promise<any>
+      runHealthCheck: () => Promise<any>
+      listCrashReports: () => Promise<any>
+      clearCrashReports: () => Promise<any>
+      uploadCrashReports: () => Promise<any>
+      getTelemetryUsage: () => Promise<any>
+      purgeTelemetry: () => Promise<any>
+      uploadTelemetry: () => Promise<any>
+      getLogLevel: () => Promise<any>
+      setLogLevel: (filter: string) => Promise<any>
+      setFullLogging: (enabled: boolean) => Promise<any>
+      getSettings: () => Promise<any>
+      setSettings: (settings: Record<string, unknown>) => Promise<any>
+      getInterruptedOperations: () => Promise<any>
+      resolveInterruptedOperation: (id: string, discardLocalFile?: boolean) => Promise<any>
+      getSafeModeStatus: () => Promise<any>
+      confirmDangerousOperation: (operation: 'drop' | 'deleteAll' | 'clearTable' | 'pushDatabase') => Promise<any>
+
       // Auto-updater methods
       checkForUpdates: () => Promise<{
         success: boolean
@@ -157,7 +290,39 @@ declare global {
         error?: string
       }>
 
-      downloadAndInstallUpdate: () => Promise<{
+      downloadAndInstallUpdate: (installOnRestart?: boolean) => Promise<{
+        success: boolean
+        version?: string
+        error?: string
+      }>
+
+      getUpdateChangelog: () => Promise<{
+        success: boolean
+        data?: {
+          currentVersion: string
+          version: string
+          date?: string
+          notes?: string
+        }
+        error?: string
+      }>
+
+      selectUpdateBundle: () => Promise<{
+        canceled: boolean
+        filePath?: string
+      }>
+
+      installUpdateFromFile: (bundlePath: string, signaturePath?: string, allowDowngrade?: boolean) => Promise<{
+        success: boolean
+        error?: string
+      }>
+
+      cancelUpdateDownload: () => Promise<{
+        success: boolean
+        error?: string
+      }>
+
+      installStagedUpdate: () => Promise<{
         success: boolean
         error?: string
       }>